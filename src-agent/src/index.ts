@@ -7,20 +7,33 @@ import { createRpcExports } from "./rpc/router";
 // Import all modules to trigger side-effectful handler registration
 import "./modules/process";
 import "./modules/module";
+import "./modules/layout";
+import "./modules/variables";
+import "./modules/cleanup";
+import "./modules/operations";
+import "./modules/jobs";
 import "./modules/thread";
 import "./modules/memory";
+import "./modules/freeze";
 import "./modules/java";
 import "./modules/objc";
 import "./modules/native";
 import "./modules/swift";
 import "./modules/il2cpp";
+import "./modules/golang";
+import "./modules/wasm";
 import "./modules/stalker";
 import "./modules/network";
 import "./modules/filesystem";
 import "./modules/console";
 import "./modules/monitor";
+import "./modules/watch";
 import "./modules/resolver";
 import "./modules/antidetect";
+import "./modules/tamper";
+import "./modules/metrics";
+import "./modules/pointers";
+import "./modules/snapshots";
 
 // Expose all registered handlers via Frida's rpc.exports
 rpc.exports = createRpcExports();
@@ -0,0 +1,32 @@
+import { registerHandler } from "../rpc/router";
+
+// --- Session cleanup ---
+//
+// Modules that leave changes in the target (hooks, patches, frozen values)
+// register a step that undoes all of them and returns how many it undid.
+// `cleanupAgent` runs every step even when one throws, so a single failure
+// never leaves the rest of the target untouched.
+
+type CleanupStep = () => number | Promise<number>;
+
+const steps: Array<{ name: string; run: CleanupStep }> = [];
+
+export function registerCleanup(name: string, run: CleanupStep): void {
+  steps.push({ name, run });
+}
+
+registerHandler("cleanupAgent", async (_params: unknown) => {
+  const results: Array<{ name: string; removed: number; error: string | null }> = [];
+  for (const step of steps) {
+    try {
+      results.push({ name: step.name, removed: await step.run(), error: null });
+    } catch (e) {
+      results.push({
+        name: step.name,
+        removed: 0,
+        error: e instanceof Error ? e.message : String(e),
+      });
+    }
+  }
+  return results;
+});
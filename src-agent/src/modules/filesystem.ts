@@ -1,4 +1,5 @@
 import { registerHandler } from "../rpc/router";
+import { sendBinary, takeBinary } from "../rpc/binary";
 import { findExportByName } from "../runtime/frida-compat";
 
 function hexEncode(bytes: Uint8Array): string {
@@ -123,11 +124,13 @@ registerHandler("listDirectory", (params: unknown) => {
 });
 
 registerHandler("readFile", (params: unknown) => {
-  const { path, offset = 0, size, encoding = "hex" } = params as {
+  const { path, offset = 0, size, encoding = "hex", binaryId } = params as {
     path: string;
     offset?: number;
     size?: number;
-    encoding?: "hex" | "utf8";
+    // `binary` sends the chunk raw as the call's `binaryId`.
+    encoding?: "hex" | "utf8" | "binary";
+    binaryId?: string;
   };
 
   // Hard caps: 4 MiB for utf8 text and 4 MiB for binary chunks. Frida RPC has
@@ -160,15 +163,52 @@ registerHandler("readFile", (params: unknown) => {
     }
 
     const data = file.readBytes(chunkSize);
-    if (!data) return "";
-
-    const bytes = toUint8Array(data);
+    const bytes = data ? toUint8Array(data) : new Uint8Array(0);
+    if (encoding === "binary") {
+      if (!binaryId) throw new Error("binary reads need a binaryId");
+      sendBinary(binaryId, bytes.slice().buffer as ArrayBuffer);
+      return { size: bytes.length };
+    }
+    if (bytes.length === 0) return "";
     return hexEncode(bytes);
   } finally {
     file.close();
   }
 });
 
+registerHandler("fileSize", (params: unknown) => {
+  const { path } = params as { path: string };
+
+  const file = new File(path, "rb");
+  try {
+    file.seek(0, File.SEEK_END);
+    return file.tell();
+  } finally {
+    file.close();
+  }
+});
+
+// Counterpart of chunked `readFile` for host-to-device transfers: the first
+// chunk truncates `path`, later ones pass `append`. The chunk arrives raw as
+// the call's `binaryId`.
+registerHandler("writeFile", (params: unknown) => {
+  const { path, binaryId, append = false } = params as {
+    path: string;
+    binaryId: string;
+    append?: boolean;
+  };
+
+  const bytes = takeBinary(binaryId);
+  const file = new File(path, append ? "ab" : "wb");
+  try {
+    file.write(bytes.buffer as ArrayBuffer);
+    file.flush();
+    return { written: bytes.length };
+  } finally {
+    file.close();
+  }
+});
+
 registerHandler("sqliteQuery", (params: unknown) => {
   const { path, query } = params as { path: string; query: string };
 
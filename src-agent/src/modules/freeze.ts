@@ -0,0 +1,158 @@
+import { registerHandler } from "../rpc/router";
+import { readByteArray, writeByteArray } from "../runtime/frida-compat";
+import { encodeValue } from "./memory";
+import type { WriteValueType } from "./memory";
+import { registerCleanup } from "./cleanup";
+
+// --- Freeze engine ---
+//
+// Frozen values are re-asserted from inside the target on a tight timer,
+// so a write lands within about a millisecond of the game changing the
+// value and keeps landing while the host is busy or briefly unreachable.
+// Each tick compares before writing; unchanged values cost one read.
+
+const DEFAULT_INTERVAL_MS = 1;
+const MAX_INTERVAL_MS = 1000;
+
+interface FrozenEntry {
+  id: string;
+  address: NativePointer;
+  type: WriteValueType;
+  value: number | string;
+  bytes: Uint8Array;
+  /** Times the target changed the value and it was written back. */
+  corrections: number;
+  lastError: string | null;
+}
+
+const entries = new Map<string, FrozenEntry>();
+let intervalMs = DEFAULT_INTERVAL_MS;
+let timer: ReturnType<typeof setInterval> | null = null;
+let nextId = 0;
+
+function toFrozenInfo(entry: FrozenEntry) {
+  return {
+    id: entry.id,
+    address: entry.address.toString(),
+    type: entry.type,
+    value: entry.value,
+    corrections: entry.corrections,
+    lastError: entry.lastError,
+  };
+}
+
+function sameBytes(current: ArrayBuffer | null, expected: Uint8Array): boolean {
+  if (!current || current.byteLength !== expected.length) return false;
+  const view = new Uint8Array(current);
+  for (let i = 0; i < expected.length; i++) {
+    if (view[i] !== expected[i]) return false;
+  }
+  return true;
+}
+
+function tick(): void {
+  for (const entry of entries.values()) {
+    try {
+      if (sameBytes(readByteArray(entry.address, entry.bytes.length), entry.bytes)) continue;
+      writeByteArray(entry.address, entry.bytes);
+      entry.corrections++;
+      entry.lastError = null;
+    } catch (e) {
+      // Keep trying: the page may be reprotected or remapped only briefly.
+      entry.lastError = e instanceof Error ? e.message : String(e);
+    }
+  }
+}
+
+function restartTimer(): void {
+  if (timer !== null) {
+    clearInterval(timer);
+    timer = null;
+  }
+  if (entries.size > 0) {
+    timer = setInterval(tick, intervalMs);
+  }
+}
+
+function setIntervalMs(value: number | undefined): void {
+  if (value === undefined) return;
+  if (!Number.isFinite(value) || value < 1 || value > MAX_INTERVAL_MS) {
+    throw new Error(`Freeze interval must be between 1 and ${MAX_INTERVAL_MS} ms`);
+  }
+  intervalMs = Math.floor(value);
+}
+
+// --- RPC Handlers ---
+
+// Values are encoded up front so a bad entry freezes nothing, then written
+// once immediately rather than waiting for the first tick.
+registerHandler("freezeValues", (params: unknown) => {
+  const { values, intervalMs: requestedInterval } = params as {
+    values: Array<{ address: string; type: WriteValueType; value: number | string }>;
+    intervalMs?: number;
+  };
+  setIntervalMs(requestedInterval);
+
+  const encoded = values.map((value, index) => {
+    try {
+      return { ...value, address: ptr(value.address), bytes: encodeValue(value.type, value.value) };
+    } catch (e) {
+      throw new Error(`Value ${index} (${value.address}): ${e instanceof Error ? e.message : String(e)}`);
+    }
+  });
+
+  const frozen = encoded.map((value) => {
+    // Re-freezing an address replaces its previous value.
+    for (const existing of entries.values()) {
+      if (existing.address.equals(value.address)) entries.delete(existing.id);
+    }
+    const entry: FrozenEntry = {
+      id: `freeze_${++nextId}`,
+      address: value.address,
+      type: value.type,
+      value: value.value,
+      bytes: value.bytes,
+      corrections: 0,
+      lastError: null,
+    };
+    entries.set(entry.id, entry);
+    return entry;
+  });
+
+  tick();
+  restartTimer();
+  return frozen.map(toFrozenInfo);
+});
+
+registerHandler("unfreezeValues", (params: unknown) => {
+  const { ids } = (params as { ids?: string[] }) ?? {};
+  let removed = 0;
+  if (ids === undefined) {
+    removed = entries.size;
+    entries.clear();
+  } else {
+    for (const id of ids) {
+      if (entries.delete(id)) removed++;
+    }
+  }
+  restartTimer();
+  return { removed };
+});
+
+registerCleanup("frozenValues", () => {
+  const removed = entries.size;
+  entries.clear();
+  restartTimer();
+  return removed;
+});
+
+registerHandler("listFrozenValues", (_params: unknown) => {
+  return { intervalMs, values: Array.from(entries.values()).map(toFrozenInfo) };
+});
+
+registerHandler("setFreezeInterval", (params: unknown) => {
+  const { intervalMs: requestedInterval } = params as { intervalMs: number };
+  setIntervalMs(requestedInterval);
+  restartTimer();
+  return { intervalMs };
+});
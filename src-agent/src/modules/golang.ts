@@ -0,0 +1,421 @@
+import { registerHandler } from "../rpc/router";
+import { findLoadedModule } from "../runtime/frida-compat";
+import { onLayoutChanged } from "./layout";
+
+// ── pclntab ─────────────────────────────────────────────────────────────────
+
+// pcHeader magics by Go release.
+const PCLNTAB_MAGICS: Record<number, string> = {
+  0xfffffff1: "1.20+",
+  0xfffffff0: "1.18",
+  0xfffffffa: "1.16",
+};
+
+const MAX_FUNCTION_RESULTS = 5000;
+const MAX_STACK_DEPTH = 32;
+const MAX_GO_STRING = 4096;
+
+interface GoFunction {
+  name: string;
+  entry: NativePointer;
+}
+
+interface Pclntab {
+  module: Module;
+  header: NativePointer;
+  version: string;
+  ptrSize: number;
+  textStart: NativePointer;
+  funcnametab: NativePointer;
+  functab: NativePointer;
+  nfunc: number;
+  // 1.18+ functab entries are u32 offsets from textStart; 1.16 uses
+  // pointer-sized absolute entries.
+  relative: boolean;
+}
+
+const pclntabCache = new Map<string, Pclntab | null>();
+
+onLayoutChanged((change) => {
+  for (const module of [...change.modulesRemoved, ...change.modulesMoved]) {
+    pclntabCache.delete(module.path);
+  }
+});
+
+function readUint(address: NativePointer, size: number): number {
+  return size === 8 ? address.readU64().toNumber() : address.readU32();
+}
+
+function findPclntabHeader(module: Module): NativePointer | null {
+  try {
+    const section = module
+      .enumerateSections()
+      .find((s) => s.name === ".gopclntab" || s.name === "__gopclntab");
+    if (section) return section.address;
+  } catch {
+    // Section enumeration is unavailable for some formats; fall back to scanning.
+  }
+
+  for (const magic of Object.keys(PCLNTAB_MAGICS)) {
+    const bytes = Number(magic).toString(16).match(/../g)!.reverse().join(" ");
+    for (const range of module.enumerateRanges("r--")) {
+      let matches: MemoryScanMatch[];
+      try {
+        matches = Memory.scanSync(range.base, range.size, `${bytes} 00 00 ?? 0?`);
+      } catch {
+        continue;
+      }
+      for (const match of matches) {
+        const minLC = match.address.add(6).readU8();
+        const ptrSize = match.address.add(7).readU8();
+        if ([1, 2, 4].includes(minLC) && (ptrSize === 4 || ptrSize === 8)) {
+          return match.address;
+        }
+      }
+    }
+  }
+  return null;
+}
+
+/** Link-time addresses in non-relocated headers are rebased onto the module. */
+function rebase(module: Module, value: number): NativePointer {
+  const address = ptr(value);
+  const end = module.base.add(module.size);
+  if (address.compare(module.base) >= 0 && address.compare(end) < 0) {
+    return address;
+  }
+  const darwinLinkBase = 0x100000000;
+  const offset =
+    Process.platform === "darwin" && value >= darwinLinkBase ? value - darwinLinkBase : value;
+  return module.base.add(offset);
+}
+
+function loadPclntab(module: Module): Pclntab | null {
+  if (pclntabCache.has(module.path)) {
+    return pclntabCache.get(module.path)!;
+  }
+
+  let table: Pclntab | null = null;
+  const header = findPclntabHeader(module);
+  if (header) {
+    const version = PCLNTAB_MAGICS[header.readU32()];
+    const ptrSize = header.add(7).readU8();
+    const field = (index: number) => readUint(header.add(8 + index * ptrSize), ptrSize);
+    const nfunc = field(0);
+
+    if (version === "1.16") {
+      table = {
+        module,
+        header,
+        version,
+        ptrSize,
+        textStart: module.base,
+        funcnametab: header.add(field(2)),
+        functab: header.add(field(6)),
+        nfunc,
+        relative: false,
+      };
+    } else if (version) {
+      table = {
+        module,
+        header,
+        version,
+        ptrSize,
+        textStart: rebase(module, field(2)),
+        funcnametab: header.add(field(3)),
+        functab: header.add(field(7)),
+        nfunc,
+        relative: true,
+      };
+    }
+  }
+
+  pclntabCache.set(module.path, table);
+  return table;
+}
+
+function functabEntry(table: Pclntab, index: number): { entry: NativePointer; funcoff: number } {
+  if (table.relative) {
+    const slot = table.functab.add(index * 8);
+    return { entry: table.textStart.add(slot.readU32()), funcoff: slot.add(4).readU32() };
+  }
+  const slot = table.functab.add(index * 2 * table.ptrSize);
+  return {
+    entry: rebase(table.module, readUint(slot, table.ptrSize)),
+    funcoff: readUint(slot.add(table.ptrSize), table.ptrSize),
+  };
+}
+
+function functionAt(table: Pclntab, index: number): GoFunction {
+  const { entry, funcoff } = functabEntry(table, index);
+  // _func starts with the entry (u32 offset or uintptr) followed by nameOff.
+  const nameOffField = table.relative ? 4 : table.ptrSize;
+  const nameOff = table.functab.add(funcoff).add(nameOffField).readS32();
+  return { name: table.funcnametab.add(nameOff).readCString() ?? "?", entry };
+}
+
+function resolveGoFunction(table: Pclntab, address: NativePointer): GoFunction | null {
+  let low = 0;
+  let high = table.nfunc - 1;
+  if (high < 0 || address.compare(functabEntry(table, 0).entry) < 0) {
+    return null;
+  }
+  // Entry nfunc is the end-of-text sentinel.
+  if (address.compare(functabEntry(table, table.nfunc).entry) >= 0) {
+    return null;
+  }
+
+  while (low < high) {
+    const mid = (low + high + 1) >>> 1;
+    if (functabEntry(table, mid).entry.compare(address) <= 0) {
+      low = mid;
+    } else {
+      high = mid - 1;
+    }
+  }
+  return functionAt(table, low);
+}
+
+function requireGoModule(moduleName?: string): Pclntab {
+  const module = moduleName ? findLoadedModule(moduleName) : Process.mainModule;
+  if (!module) throw new Error(`Module not found: ${moduleName ?? "<main>"}`);
+  const table = loadPclntab(module);
+  if (!table) throw new Error(`No Go pclntab found in ${module.name}`);
+  return table;
+}
+
+function describe(table: Pclntab, address: NativePointer) {
+  const fn = resolveGoFunction(table, address);
+  return {
+    address: address.toString(),
+    function: fn?.name ?? null,
+    offset: fn ? address.sub(fn.entry).toInt32() : null,
+  };
+}
+
+// ── Goroutines ──────────────────────────────────────────────────────────────
+
+// Best-effort runtime.g layout for 64-bit targets. `sched` (gobuf) sits at
+// 0x38 in every supported release; status/goid moved when Go 1.23 added
+// g.syscallbp. Callers can override offsets for unusual builds.
+interface GLayout {
+  schedSp: number;
+  schedPc: number;
+  schedLr: number;
+  schedBp: number;
+  status: number;
+  goid: number;
+}
+
+const G_LAYOUT_PRE_123: GLayout = {
+  schedSp: 0x38,
+  schedPc: 0x40,
+  schedLr: 0x60,
+  schedBp: 0x68,
+  status: 0x90,
+  goid: 0x98,
+};
+
+const G_LAYOUT_123: GLayout = { ...G_LAYOUT_PRE_123, status: 0x98, goid: 0xa0 };
+
+const G_STATUS = [
+  "idle",
+  "runnable",
+  "running",
+  "syscall",
+  "waiting",
+  "moribund",
+  "dead",
+  "enqueue",
+  "copystack",
+  "preempted",
+];
+const G_STATUS_DEAD = 6;
+const G_STATUS_SCAN = 0x1000;
+
+function goVersion(module: Module): string | null {
+  const symbol = module.findSymbolByName("runtime.buildVersion");
+  if (!symbol) return null;
+  return readGoString(symbol, MAX_GO_STRING);
+}
+
+function pickLayout(version: string | null): GLayout {
+  const match = version?.match(/^go1\.(\d+)/);
+  return match && Number(match[1]) >= 23 ? G_LAYOUT_123 : G_LAYOUT_PRE_123;
+}
+
+function unwindFramePointers(table: Pclntab, pc: NativePointer, bp: NativePointer) {
+  const frames = [describe(table, pc)];
+  let frame = bp;
+  for (let depth = 0; depth < MAX_STACK_DEPTH && !frame.isNull(); depth++) {
+    let next: NativePointer;
+    let ret: NativePointer;
+    try {
+      next = frame.readPointer();
+      ret = frame.add(Process.pointerSize).readPointer();
+    } catch {
+      break;
+    }
+    if (ret.isNull()) break;
+    frames.push(describe(table, ret));
+    // Frames grow towards higher addresses; anything else is a broken chain.
+    if (next.compare(frame) <= 0) break;
+    frame = next;
+  }
+  return frames;
+}
+
+// ── Go values ───────────────────────────────────────────────────────────────
+
+function readGoString(address: NativePointer, maxLength: number): string | null {
+  const data = address.readPointer();
+  const length = readUint(address.add(Process.pointerSize), Process.pointerSize);
+  if (data.isNull() || length === 0) return "";
+  return data.readUtf8String(Math.min(length, maxLength));
+}
+
+// ── RPC Handlers ────────────────────────────────────────────────────────────
+
+registerHandler("isGoAvailable", (params: unknown) => {
+  const { module } = (params as { module?: string }) ?? {};
+  const target = module ? findLoadedModule(module) : Process.mainModule;
+  return target !== null && loadPclntab(target) !== null;
+});
+
+registerHandler("getGoInfo", (params: unknown) => {
+  const { module } = (params as { module?: string }) ?? {};
+  const table = requireGoModule(module);
+  return {
+    module: table.module.name,
+    goVersion: goVersion(table.module),
+    pclntabVersion: table.version,
+    pclntab: table.header.toString(),
+    textStart: table.textStart.toString(),
+    functionCount: table.nfunc,
+    pointerSize: table.ptrSize,
+  };
+});
+
+registerHandler("enumerateGoFunctions", (params: unknown) => {
+  const { module, filter, limit = MAX_FUNCTION_RESULTS } =
+    (params as { module?: string; filter?: string; limit?: number }) ?? {};
+  const table = requireGoModule(module);
+  const needle = filter?.toLowerCase();
+  const max = Math.min(limit, MAX_FUNCTION_RESULTS);
+
+  const result: { name: string; address: string }[] = [];
+  for (let index = 0; index < table.nfunc && result.length < max; index++) {
+    const fn = functionAt(table, index);
+    if (needle && !fn.name.toLowerCase().includes(needle)) continue;
+    result.push({ name: fn.name, address: fn.entry.toString() });
+  }
+  return result;
+});
+
+registerHandler("resolveGoFunction", (params: unknown) => {
+  const { address, module } = params as { address: string; module?: string };
+  const table = requireGoModule(module);
+  const fn = resolveGoFunction(table, ptr(address));
+  if (!fn) return null;
+  return {
+    name: fn.name,
+    entry: fn.entry.toString(),
+    offset: ptr(address).sub(fn.entry).toInt32(),
+  };
+});
+
+registerHandler("enumerateGoroutines", (params: unknown) => {
+  const {
+    module,
+    includeDead = false,
+    layout: overrides = {},
+  } = (params as { module?: string; includeDead?: boolean; layout?: Partial<GLayout> }) ?? {};
+  const table = requireGoModule(module);
+  if (table.ptrSize !== 8) {
+    throw new Error("Goroutine enumeration is only supported on 64-bit targets");
+  }
+
+  const allgs = table.module.findSymbolByName("runtime.allgs");
+  if (!allgs) {
+    throw new Error("runtime.allgs not found; the binary is stripped of its symbol table");
+  }
+
+  const version = goVersion(table.module);
+  const layout = { ...pickLayout(version), ...overrides };
+  const base = allgs.readPointer();
+  const count = readUint(allgs.add(8), 8);
+  const isArm = Process.arch === "arm64";
+
+  const goroutines = [];
+  for (let index = 0; index < count; index++) {
+    const g = base.add(index * 8).readPointer();
+    if (g.isNull()) continue;
+
+    const rawStatus = g.add(layout.status).readU32();
+    const status = rawStatus & ~G_STATUS_SCAN;
+    if (status === G_STATUS_DEAD && !includeDead) continue;
+
+    const pc = g.add(layout.schedPc).readPointer();
+    const bp = g.add(layout.schedBp).readPointer();
+    goroutines.push({
+      goid: g.add(layout.goid).readU64().toNumber(),
+      g: g.toString(),
+      status: G_STATUS[status] ?? `unknown(${rawStatus})`,
+      sp: g.add(layout.schedSp).readPointer().toString(),
+      pc: pc.toString(),
+      lr: isArm ? g.add(layout.schedLr).readPointer().toString() : null,
+      // A running goroutine's saved context is stale until it next yields.
+      stack: pc.isNull() ? [] : unwindFramePointers(table, pc, bp),
+    });
+  }
+
+  return { goVersion: version, count: goroutines.length, goroutines };
+});
+
+registerHandler("readGoString", (params: unknown) => {
+  const { address, maxLength = MAX_GO_STRING } = params as {
+    address: string;
+    maxLength?: number;
+  };
+  const header = ptr(address);
+  return {
+    data: header.readPointer().toString(),
+    length: readUint(header.add(Process.pointerSize), Process.pointerSize),
+    value: readGoString(header, Math.min(maxLength, MAX_GO_STRING)),
+  };
+});
+
+registerHandler("readGoSlice", (params: unknown) => {
+  const { address, elementSize = 1, limit = 256 } = params as {
+    address: string;
+    elementSize?: number;
+    limit?: number;
+  };
+  const header = ptr(address);
+  const size = Process.pointerSize;
+  const data = header.readPointer();
+  const length = readUint(header.add(size), size);
+  const capacity = readUint(header.add(size * 2), size);
+
+  const count = Math.min(length, limit);
+  const elements: string[] = [];
+  if (!data.isNull() && elementSize > 0) {
+    for (let index = 0; index < count; index++) {
+      const bytes = data.add(index * elementSize).readByteArray(elementSize);
+      elements.push(
+        bytes
+          ? Array.from(new Uint8Array(bytes), (b) => b.toString(16).padStart(2, "0")).join("")
+          : "",
+      );
+    }
+  }
+
+  return {
+    data: data.toString(),
+    length,
+    capacity,
+    elementSize,
+    truncated: length > count,
+    elements,
+  };
+});
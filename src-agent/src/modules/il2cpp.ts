@@ -1,5 +1,6 @@
 import { registerHandler } from "../rpc/router";
 import { emitHookEvent } from "../rpc/protocol";
+import { registerCleanup } from "./cleanup";
 
 // ── IL2CPP Module Discovery ─────────────────────────────────────────────────
 
@@ -522,6 +523,13 @@ registerHandler("unhookIl2cppMethod", (params: unknown) => {
   return { hookId, removed: true };
 });
 
+registerCleanup("il2cppHooks", () => {
+  const removed = il2cppHooks.size;
+  for (const hook of il2cppHooks.values()) hook.listener.detach();
+  il2cppHooks.clear();
+  return removed;
+});
+
 registerHandler("dumpIl2cppMetadata", (params: unknown) => {
   const p = (params as { imagePtr?: string; maxClasses?: number }) ?? {};
   const maxClasses = p.maxClasses ?? 200;
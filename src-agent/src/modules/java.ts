@@ -1,6 +1,7 @@
 import { JavaRuntime as Java } from "../bridges";
 import { registerHandler } from "../rpc/router";
 import { emitHookEvent } from "../rpc/protocol";
+import { registerCleanup } from "./cleanup";
 
 interface JavaHookEntry {
   hookId: string;
@@ -350,6 +351,27 @@ registerHandler("listJavaHooks", (_params: unknown) => {
   return Array.from(javaHooks.values()).map(toHookInfo);
 });
 
+registerCleanup("javaHooks", () => {
+  if (javaHooks.size === 0) return 0;
+  return new Promise<number>((resolve, reject) => {
+    Java.performNow(() => {
+      try {
+        const removed = javaHooks.size;
+        for (const hook of javaHooks.values()) {
+          const methodGroup = Java.use(hook.className)[hook.methodName];
+          if (methodGroup?.overloads?.[hook.overloadIndex]) {
+            methodGroup.overloads[hook.overloadIndex].implementation = null;
+          }
+        }
+        javaHooks.clear();
+        resolve(removed);
+      } catch (e) {
+        reject(e);
+      }
+    });
+  });
+});
+
 registerHandler("setJavaHookActive", (params: unknown) => {
   const { hookId, active } = params as { hookId: string; active: boolean };
   const hook = javaHooks.get(hookId);
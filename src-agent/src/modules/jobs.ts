@@ -0,0 +1,72 @@
+import { getHandler, registerHandler } from "../rpc/router";
+import { emitEvent } from "../rpc/protocol";
+
+// --- Background jobs ---
+//
+// Long-running handlers (scans, heap searches, dumps) are started here and
+// answered through a `job/result` event instead of holding the RPC open, so
+// the host can keep serving attach/RPC/post requests meanwhile. Jobs run one
+// at a time per session, in the order they were started.
+
+// Methods that run arbitrary JavaScript. The host gates them behind
+// CARF_ALLOW_EVAL by the outer method name, so they are never run as jobs.
+const EVAL_METHODS = ["evaluate", "eval", "runScript", "loadScript"];
+
+let queue: Promise<void> = Promise.resolve();
+let pending = 0;
+// Jobs queued or running, and those of them the host gave up on.
+const outstanding = new Set<string>();
+const cancelled = new Set<string>();
+
+async function runJob(jobId: string, method: string, params: unknown): Promise<void> {
+  const handler = getHandler(method);
+  try {
+    if (cancelled.has(jobId)) return;
+    if (!handler) throw new Error(`Unknown RPC method: ${method}`);
+    const result = await handler(params);
+    if (cancelled.has(jobId)) return;
+    emitEvent("job/result", {
+      jobId,
+      success: true,
+      data: typeof result === "undefined" ? "null" : JSON.stringify(result),
+    });
+  } catch (e) {
+    if (cancelled.has(jobId)) return;
+    emitEvent("job/result", {
+      jobId,
+      success: false,
+      error: e instanceof Error ? e.message : String(e),
+    });
+  } finally {
+    pending--;
+    outstanding.delete(jobId);
+    cancelled.delete(jobId);
+  }
+}
+
+registerHandler("startJob", (params: unknown) => {
+  const { jobId, method, params: jobParams } = params as {
+    jobId: string;
+    method: string;
+    params: unknown;
+  };
+  if (method === "startJob" || EVAL_METHODS.includes(method)) {
+    throw new Error(`${method} cannot run as a job`);
+  }
+  if (!getHandler(method)) {
+    throw new Error(`Unknown RPC method: ${method}`);
+  }
+  pending++;
+  outstanding.add(jobId);
+  queue = queue.then(() => runJob(jobId, method, jobParams));
+  return { jobId, queued: pending };
+});
+
+// The host stopped waiting for the job. A queued job is skipped; a running
+// one finishes, but its result is dropped.
+registerHandler("cancelJob", (params: unknown) => {
+  const { jobId } = params as { jobId: string };
+  if (!outstanding.has(jobId)) return { cancelled: false };
+  cancelled.add(jobId);
+  return { cancelled: true };
+});
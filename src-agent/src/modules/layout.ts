@@ -0,0 +1,182 @@
+import { registerHandler } from "../rpc/router";
+import { emitEvent } from "../rpc/protocol";
+
+// --- Address space layout watcher ---
+//
+// Tracks module load/unload/rebase and large swings in mapped memory so the
+// host can invalidate anything keyed on addresses. Module changes are picked
+// up immediately through a module observer; raw mappings are polled.
+
+interface ModuleSummary {
+  name: string;
+  path: string;
+  base: string;
+  size: number;
+}
+
+interface LayoutChange {
+  reason: "modules" | "mappings";
+  modulesAdded: ModuleSummary[];
+  modulesRemoved: ModuleSummary[];
+  modulesMoved: { name: string; path: string; oldBase: string; newBase: string }[];
+  mappedBytesBefore: number;
+  mappedBytesAfter: number;
+}
+
+type LayoutListener = (change: LayoutChange) => void;
+
+const DEFAULT_INTERVAL_MS = 5000;
+const DEFAULT_MAPPING_THRESHOLD = 16 * 1024 * 1024;
+
+const listeners = new Set<LayoutListener>();
+let modules = new Map<string, ModuleSummary>();
+let mappedBytes = 0;
+let observer: { detach(): void } | null = null;
+let pollTimer: ReturnType<typeof setInterval> | null = null;
+let pendingCheck = false;
+let intervalMs = DEFAULT_INTERVAL_MS;
+let mappingThreshold = DEFAULT_MAPPING_THRESHOLD;
+let changeCount = 0;
+
+/** Registers a callback for layout changes; used to drop address caches. */
+export function onLayoutChanged(listener: LayoutListener): void {
+  listeners.add(listener);
+}
+
+function snapshotModules(): Map<string, ModuleSummary> {
+  const snapshot = new Map<string, ModuleSummary>();
+  for (const module of Process.enumerateModules()) {
+    snapshot.set(module.path, {
+      name: module.name,
+      path: module.path,
+      base: module.base.toString(),
+      size: module.size,
+    });
+  }
+  return snapshot;
+}
+
+function totalMappedBytes(): number {
+  let total = 0;
+  for (const range of Process.enumerateRanges("---")) {
+    total += range.size;
+  }
+  return total;
+}
+
+function checkLayout(): void {
+  pendingCheck = false;
+
+  const nextModules = snapshotModules();
+  const nextMapped = totalMappedBytes();
+
+  const added: ModuleSummary[] = [];
+  const removed: ModuleSummary[] = [];
+  const moved: LayoutChange["modulesMoved"] = [];
+
+  for (const [path, module] of nextModules) {
+    const previous = modules.get(path);
+    if (!previous) {
+      added.push(module);
+    } else if (previous.base !== module.base) {
+      moved.push({ name: module.name, path, oldBase: previous.base, newBase: module.base });
+    }
+  }
+  for (const [path, module] of modules) {
+    if (!nextModules.has(path)) removed.push(module);
+  }
+
+  const moduleChange = added.length + removed.length + moved.length > 0;
+  const mappingChange = Math.abs(nextMapped - mappedBytes) >= mappingThreshold;
+
+  if (moduleChange || mappingChange) {
+    const change: LayoutChange = {
+      reason: moduleChange ? "modules" : "mappings",
+      modulesAdded: added,
+      modulesRemoved: removed,
+      modulesMoved: moved,
+      mappedBytesBefore: mappedBytes,
+      mappedBytesAfter: nextMapped,
+    };
+    changeCount += 1;
+    for (const listener of listeners) {
+      try {
+        listener(change);
+      } catch {
+        // A faulty cache owner must not stop the others from invalidating.
+      }
+    }
+    emitEvent("memory/layout_changed", change);
+  }
+
+  modules = nextModules;
+  if (mappingChange || moduleChange) {
+    mappedBytes = nextMapped;
+  }
+}
+
+// Module observer callbacks can arrive in bursts (dlopen of a library with
+// many dependencies); fold them into one check.
+function scheduleCheck(): void {
+  if (pendingCheck) return;
+  pendingCheck = true;
+  setTimeout(checkLayout, 0);
+}
+
+function startWatcher(): void {
+  modules = snapshotModules();
+  mappedBytes = totalMappedBytes();
+
+  try {
+    observer = Process.attachModuleObserver({
+      onAdded: scheduleCheck,
+      onRemoved: scheduleCheck,
+    });
+  } catch {
+    // Older runtimes without module observers still get polled.
+    observer = null;
+  }
+  pollTimer = setInterval(checkLayout, intervalMs);
+}
+
+function stopWatcher(): void {
+  observer?.detach();
+  observer = null;
+  if (pollTimer !== null) {
+    clearInterval(pollTimer);
+    pollTimer = null;
+  }
+}
+
+function watcherStatus() {
+  return {
+    active: pollTimer !== null,
+    intervalMs,
+    mappingThreshold,
+    moduleCount: modules.size,
+    mappedBytes,
+    changeCount,
+  };
+}
+
+registerHandler("startLayoutWatcher", (params: unknown) => {
+  const p = (params as { intervalMs?: number; mappingThreshold?: number }) ?? {};
+  stopWatcher();
+  intervalMs = Math.max(p.intervalMs ?? DEFAULT_INTERVAL_MS, 250);
+  mappingThreshold = Math.max(p.mappingThreshold ?? DEFAULT_MAPPING_THRESHOLD, 0);
+  startWatcher();
+  return watcherStatus();
+});
+
+registerHandler("stopLayoutWatcher", (_params: unknown) => {
+  stopWatcher();
+  return watcherStatus();
+});
+
+registerHandler("getLayoutWatcherStatus", (_params: unknown) => {
+  return watcherStatus();
+});
+
+// Watch from the moment the agent loads so changes are never missed between
+// attach and the first UI request.
+startWatcher();
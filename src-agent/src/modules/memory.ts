@@ -1,6 +1,10 @@
 import { registerHandler } from "../rpc/router";
+import { sendBinary } from "../rpc/binary";
 import { emitEvent } from "../rpc/protocol";
+import { beginOperation } from "./operations";
+import type { Operation } from "./operations";
 import { readByteArray, writeByteArray } from "../runtime/frida-compat";
+import { registerCleanup } from "./cleanup";
 
 function hexEncode(buf: ArrayBuffer): string {
   const bytes = new Uint8Array(buf);
@@ -83,6 +87,50 @@ registerHandler("readMemory", (params: unknown) => {
   return hexEncode(buf);
 });
 
+// One chunk of a memory dump, sent raw as the call's `binaryId`. Unlike
+// readMemory it never fails on a bad page: pages that cannot be read (guard
+// pages, holes in a module) are zero-filled and counted, so a dump of a whole
+// module still lines up with its layout.
+registerHandler("readMemoryChunk", (params: unknown) => {
+  const { address, size, binaryId } = params as {
+    address: string;
+    size: number;
+    binaryId: string;
+  };
+  if (size <= 0 || size > READ_MEMORY_MAX) {
+    throw new Error(`Invalid size: ${size} (max ${READ_MEMORY_MAX} bytes)`);
+  }
+  const start = ptr(address);
+  try {
+    const buf = readByteArray(start, size);
+    if (buf) {
+      sendBinary(binaryId, buf);
+      return { unreadable: 0 };
+    }
+  } catch {
+    // Fall back to reading page by page below.
+  }
+
+  const bytes = new Uint8Array(size);
+  let unreadable = 0;
+  let offset = 0;
+  while (offset < size) {
+    const here = start.add(offset);
+    const pageOffset = here.and(Process.pageSize - 1).toUInt32();
+    const length = Math.min(size - offset, Process.pageSize - pageOffset);
+    try {
+      const page = readByteArray(here, length);
+      if (!page) throw new Error("unreadable");
+      bytes.set(new Uint8Array(page), offset);
+    } catch {
+      unreadable += length;
+    }
+    offset += length;
+  }
+  sendBinary(binaryId, bytes.buffer);
+  return { unreadable };
+});
+
 registerHandler("writeMemory", (params: unknown) => {
   const {
     address,
@@ -98,47 +146,262 @@ registerHandler("writeMemory", (params: unknown) => {
   return { written: decoded.length };
 });
 
-registerHandler("scanMemory", async (params: unknown) => {
-  const { address, base, size, pattern, protection, ranges } = params as {
-    address?: string;
-    base?: string;
-    size?: number;
-    pattern: string;
-    protection?: string;
-    ranges?: string;
-  };
-  const resolvedBase = address ?? base;
-  const resolvedProtection = ranges ?? protection ?? "r--";
+// --- Batch writes ---
 
-  const results: Array<ReturnType<typeof buildScanResult>> = [];
+export type WriteValueType =
+  | "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64"
+  | "f32" | "f64" | "pointer" | "bytes" | "utf8" | "utf16";
+
+interface ValueWrite {
+  address: string;
+  type: WriteValueType;
+  value: number | string;
+}
 
-  if (resolvedBase && typeof size === "number") {
-    results.push(...(await scanRange(ptr(resolvedBase), size, pattern)));
-    emitEvent("scan/progress", { progress: 100, scanned: 1, total: 1 });
-    emitEvent("scan/result", { results });
-    return results;
+// Strings are written without a terminator, matching in-place string edits.
+function encodeString(type: "utf8" | "utf16", text: string): Uint8Array {
+  if (type === "utf8") {
+    const binary = unescape(encodeURIComponent(text));
+    return Uint8Array.from(binary, (char) => char.charCodeAt(0));
   }
+  const bytes = new Uint8Array(text.length * 2);
+  for (let i = 0; i < text.length; i++) {
+    const unit = text.charCodeAt(i);
+    bytes[i * 2] = unit & 0xff;
+    bytes[i * 2 + 1] = unit >> 8;
+  }
+  return bytes;
+}
 
-  const rangesToScan = Process.enumerateRanges(
-    resolvedProtection as PageProtection,
-  );
+const NUMERIC_WRITERS: Record<string, (target: NativePointer, value: string) => void> = {
+  u8: (target, value) => target.writeU8(Number(value)),
+  i8: (target, value) => target.writeS8(Number(value)),
+  u16: (target, value) => target.writeU16(Number(value)),
+  i16: (target, value) => target.writeS16(Number(value)),
+  u32: (target, value) => target.writeU32(Number(value)),
+  i32: (target, value) => target.writeS32(Number(value)),
+  u64: (target, value) => target.writeU64(uint64(value)),
+  i64: (target, value) => target.writeS64(int64(value)),
+  f32: (target, value) => target.writeFloat(Number(value)),
+  f64: (target, value) => target.writeDouble(Number(value)),
+  pointer: (target, value) => target.writePointer(ptr(value)),
+};
+
+// Numbers go through a scratch buffer so the target gets exactly the bytes
+// Frida's typed writers would produce.
+export function encodeValue(type: WriteValueType, value: number | string): Uint8Array {
+  if (type === "bytes") return hexDecode(String(value).replace(/\s+/g, ""));
+  if (type === "utf8" || type === "utf16") return encodeString(type, String(value));
+
+  const writer = NUMERIC_WRITERS[type];
+  if (!writer) throw new Error(`Unsupported value type: ${type}`);
+  const size = type === "pointer" ? Process.pointerSize : Number(type.slice(1)) / 8;
+  const scratch = Memory.alloc(size);
+  writer(scratch, String(value));
+  return new Uint8Array(readByteArray(scratch, size)!);
+}
 
-  for (const [index, range] of rangesToScan.entries()) {
+// Every value is encoded before anything is written, so a bad entry never
+// leaves a half-applied set. With `atomic`, the original bytes are kept and
+// restored if any write fails; either way all writes happen in one JS turn
+// to land within as few target frames as possible.
+registerHandler("writeValuesBatch", (params: unknown) => {
+  const { writes, atomic } = params as { writes: ValueWrite[]; atomic?: boolean };
+
+  const encoded = writes.map((write, index) => {
     try {
-      results.push(...(await scanRange(range.base, range.size, pattern)));
-    } catch {
-      // Ignore unreadable ranges and continue scanning the rest.
+      return { index, address: ptr(write.address), bytes: encodeValue(write.type, write.value) };
+    } catch (e) {
+      throw new Error(`Write ${index} (${write.address}): ${e instanceof Error ? e.message : String(e)}`);
+    }
+  });
+
+  const originals = atomic
+    ? encoded.map(({ address, bytes }) => readByteArray(address, bytes.length))
+    : [];
+
+  const failed: Array<{ index: number; address: string; error: string }> = [];
+  let written = 0;
+  for (const { index, address, bytes } of encoded) {
+    try {
+      writeByteArray(address, bytes);
+      written++;
+    } catch (e) {
+      failed.push({
+        index,
+        address: address.toString(),
+        error: e instanceof Error ? e.message : String(e),
+      });
+      if (atomic) break;
+    }
+  }
+
+  let rolledBack = false;
+  if (atomic && failed.length > 0) {
+    for (let i = written - 1; i >= 0; i--) {
+      const original = originals[i];
+      if (original) writeByteArray(encoded[i].address, original);
+    }
+    rolledBack = true;
+    written = 0;
+  }
+
+  return { written, failed, rolledBack };
+});
+
+type ScanErrorPolicy = "skip" | "retry" | "abort";
+
+interface SkippedRange {
+  base: string;
+  size: number;
+  protection: string | null;
+  attempts: number;
+  reason: string;
+}
+
+const DEFAULT_SCAN_RETRIES = 2;
+
+// Pages can be unmapped or reprotected between enumerateRanges and the scan
+// reaching them; the policy decides whether that skips the range, retries it
+// or ends the scan. Matches from a failed attempt are discarded so a retry
+// never reports them twice.
+async function scanWithPolicy(
+  base: NativePointer,
+  size: number,
+  pattern: string,
+  policy: ScanErrorPolicy,
+  retries: number,
+): Promise<{ results: Array<ReturnType<typeof buildScanResult>>; attempts: number; error: string | null }> {
+  const maxAttempts = policy === "retry" ? retries + 1 : 1;
+  let error = "";
+  for (let attempt = 1; attempt <= maxAttempts; attempt++) {
+    try {
+      return { results: await scanRange(base, size, pattern), attempts: attempt, error: null };
+    } catch (e) {
+      error = e instanceof Error ? e.message : String(e);
+    }
+  }
+  return { results: [], attempts: maxAttempts, error };
+}
+
+interface ScanParams {
+  address?: string;
+  base?: string;
+  size?: number;
+  pattern: string;
+  protection?: string;
+  ranges?: string;
+  onReadError?: ScanErrorPolicy;
+  retries?: number;
+  /** Drop matches whose address is not a multiple of this. */
+  alignment?: number;
+  /** Skip file-backed mappings when enumerating ranges. */
+  privateOnly?: boolean;
+}
+
+async function runScan(params: ScanParams, operation?: Operation) {
+  const {
+    address,
+    base,
+    size,
+    pattern,
+    protection,
+    ranges,
+    onReadError,
+    retries,
+    alignment,
+    privateOnly,
+  } = params;
+  const resolvedBase = address ?? base;
+  const resolvedProtection = ranges ?? protection ?? "r--";
+  const policy: ScanErrorPolicy = onReadError ?? "skip";
+  const retryCount = Math.max(0, retries ?? DEFAULT_SCAN_RETRIES);
+
+  const rangesToScan: Array<{ base: NativePointer; size: number; protection: string | null }> =
+    resolvedBase && typeof size === "number"
+      ? [{ base: ptr(resolvedBase), size, protection: null }]
+      : Process.enumerateRanges(resolvedProtection as PageProtection).filter(
+          (range) => !privateOnly || range.file === undefined,
+        );
+  const alignmentMask = alignment && alignment > 1 ? alignment - 1 : 0;
+
+  const results: Array<ReturnType<typeof buildScanResult>> = [];
+  const skipped: SkippedRange[] = [];
+  let scanned = 0;
+  let aborted = false;
+  let cancelled = false;
+
+  for (const [index, range] of rangesToScan.entries()) {
+    // Checked per range: a single Memory.scan cannot be interrupted.
+    if (operation?.isCancelled()) {
+      cancelled = true;
+      break;
+    }
+
+    const outcome = await scanWithPolicy(range.base, range.size, pattern, policy, retryCount);
+    for (const result of outcome.results) {
+      if (alignmentMask === 0 || ptr(result.address).and(alignmentMask).isNull()) {
+        results.push(result);
+      }
+    }
+    scanned = index + 1;
+
+    if (outcome.error !== null) {
+      skipped.push({
+        base: range.base.toString(),
+        size: range.size,
+        protection: range.protection,
+        attempts: outcome.attempts,
+        reason: outcome.error,
+      });
+      aborted = policy === "abort";
     }
 
     emitEvent("scan/progress", {
       progress: Math.round(((index + 1) / rangesToScan.length) * 100),
       scanned: index + 1,
       total: rangesToScan.length,
+      operationId: operation?.id,
     });
+
+    if (aborted) break;
   }
 
-  emitEvent("scan/result", { results });
-  return results;
+  const metadata = {
+    policy,
+    rangesTotal: rangesToScan.length,
+    rangesScanned: scanned - skipped.length,
+    skippedRanges: skipped,
+    skippedBytes: skipped.reduce((total, range) => total + range.size, 0),
+    aborted,
+    cancelled,
+  };
+  return { results, metadata };
+}
+
+registerHandler("scanMemory", async (params: unknown) => {
+  const response = await runScan(params as ScanParams);
+  emitEvent("scan/result", response);
+  return response;
+});
+
+// Returns as soon as the scan is queued so the host stays free to cancel it;
+// results arrive through `scan/result` tagged with the operation id.
+registerHandler("startMemoryScan", (params: unknown) => {
+  const operation = beginOperation("scan");
+  runScan(params as ScanParams, operation)
+    .then((response) => {
+      emitEvent("scan/result", { ...response, operationId: operation.id });
+    })
+    .catch((e) => {
+      emitEvent("scan/result", {
+        results: [],
+        operationId: operation.id,
+        error: e instanceof Error ? e.message : String(e),
+      });
+    })
+    .finally(() => operation.finish());
+  return { operationId: operation.id };
 });
 
 registerHandler("protectMemory", (params: unknown) => {
@@ -153,6 +416,32 @@ registerHandler("protectMemory", (params: unknown) => {
 
 // --- Advanced Memory Features (Frida 17+) ---
 
+// Original bytes of every patched span, keyed by address, so patches can be
+// reverted. Re-patching an address keeps the bytes from before the first
+// patch.
+const patches = new Map<string, { address: NativePointer; original: Uint8Array }>();
+
+function writePatch(target: NativePointer, bytes: Uint8Array): void {
+  const size = bytes.length;
+  // Use Memory.patchCode for executable memory regions
+  const range = Process.findRangeByAddress(target);
+  if (range && range.protection.includes("x")) {
+    Memory.patchCode(target, size, (code) => {
+      code.writeByteArray(Array.from(bytes));
+    });
+  } else {
+    // For non-code regions, ensure writable, write, then restore
+    const origProtection = range?.protection ?? "rw-";
+    if (range && !range.protection.includes("w")) {
+      Memory.protect(target, size, "rw-" as PageProtection);
+    }
+    target.writeByteArray(Array.from(bytes));
+    if (range && !range.protection.includes("w")) {
+      Memory.protect(target, size, origProtection as PageProtection);
+    }
+  }
+}
+
 registerHandler("patchMemory", (params: unknown) => {
   const { address, bytes } = params as { address: string; bytes: string };
 
@@ -172,23 +461,18 @@ registerHandler("patchMemory", (params: unknown) => {
   }
 
   try {
-    // Use Memory.patchCode for executable memory regions
-    const range = Process.findRangeByAddress(target);
-    if (range && range.protection.includes("x")) {
-      Memory.patchCode(target, size, (code) => {
-        code.writeByteArray(Array.from(decoded));
-      });
-    } else {
-      // For non-code regions, ensure writable, write, then restore
-      const origProtection = range?.protection ?? "rw-";
-      if (range && !range.protection.includes("w")) {
-        Memory.protect(target, size, "rw-" as PageProtection);
-      }
-      target.writeByteArray(Array.from(decoded));
-      if (range && !range.protection.includes("w")) {
-        Memory.protect(target, size, origProtection as PageProtection);
+    const key = target.toString();
+    const previous = patches.get(key);
+    if (!previous || previous.original.length < size) {
+      const current = readByteArray(target, size);
+      if (current) {
+        const original = new Uint8Array(current);
+        // The first bytes are already patched; keep what was there before.
+        if (previous) original.set(previous.original);
+        patches.set(key, { address: target, original });
       }
     }
+    writePatch(target, decoded);
 
     return { patched: true, address: target.toString(), size };
   } catch (e) {
@@ -198,6 +482,17 @@ registerHandler("patchMemory", (params: unknown) => {
   }
 });
 
+// Newest first: a patch overlapping an earlier one saved that patch's bytes
+// as its original, so it has to be undone before the earlier one.
+registerCleanup("patches", () => {
+  const reverted = patches.size;
+  for (const patch of [...patches.values()].reverse()) {
+    writePatch(patch.address, patch.original);
+  }
+  patches.clear();
+  return reverted;
+});
+
 registerHandler("queryMemoryProtection", (params: unknown) => {
   const { address } = params as { address: string };
 
@@ -313,6 +608,69 @@ registerHandler("compareMemory", (params: unknown) => {
   }
 });
 
+// Reads the same span from a read-only file mapping and from its backing
+// file on disk, with file offsets aligned to memory offsets, so in-memory
+// patches made by the target itself stand out.
+registerHandler("readMappedFile", (params: unknown) => {
+  const { address, size } = params as { address: string; size: number };
+  const target = ptr(address);
+  const range = Process.findRangeByAddress(target);
+  if (!range || !range.file) {
+    throw new Error(`${address} is not in a file-backed mapping`);
+  }
+  if (range.protection.includes("w")) {
+    throw new Error(`${address} is in a writable mapping (${range.protection})`);
+  }
+
+  const memoryOffset = target.sub(range.base).toUInt32();
+  const length = Math.min(size, range.size - memoryOffset, READ_MEMORY_MAX);
+  if (length <= 0) {
+    throw new Error(`Invalid size: ${size}`);
+  }
+  const fileOffset = range.file.offset + memoryOffset;
+
+  const memoryBuf = readByteArray(target, length);
+  if (!memoryBuf) throw new Error("Failed to read memory");
+
+  const file = new File(range.file.path, "rb");
+  let diskBuf: ArrayBuffer | null;
+  try {
+    file.seek(fileOffset);
+    diskBuf = file.readBytes(length);
+  } finally {
+    file.close();
+  }
+
+  // Past the end of the file the mapping is zero-filled.
+  const memory = new Uint8Array(memoryBuf);
+  const disk = new Uint8Array(length);
+  if (diskBuf) disk.set(new Uint8Array(diskBuf).subarray(0, length));
+  const diskLength = diskBuf ? Math.min(diskBuf.byteLength, length) : 0;
+
+  const differences: Array<{ offset: number; length: number }> = [];
+  for (let i = 0; i < length && differences.length < 1000; i++) {
+    if (memory[i] === disk[i]) continue;
+    const last = differences[differences.length - 1];
+    if (last && last.offset + last.length === i) {
+      last.length += 1;
+    } else {
+      differences.push({ offset: i, length: 1 });
+    }
+  }
+
+  return {
+    path: range.file.path,
+    address: target.toString(),
+    rangeBase: range.base.toString(),
+    fileOffset,
+    size: length,
+    diskSize: diskLength,
+    memory: hexEncode(memoryBuf),
+    disk: hexEncode(disk.buffer),
+    differences,
+  };
+});
+
 registerHandler("enumerateMallocRanges", (_params: unknown) => {
   try {
     const ranges = Process.enumerateMallocRanges();
@@ -0,0 +1,251 @@
+import { registerHandler } from "../rpc/router";
+import { emitEvent } from "../rpc/protocol";
+import { findExportByName } from "../runtime/frida-compat";
+
+// --- Process resource metrics ---
+//
+// Samples the target's own CPU time, resident memory, thread count and open
+// handle/fd count from inside the process, so it works the same on every
+// device type. CPU% is CPU time over wall time since the previous sample and
+// can exceed 100 on multi-core targets. Fields the platform cannot report
+// are null.
+
+const DEFAULT_INTERVAL_MS = 1000;
+const MIN_INTERVAL_MS = 250;
+
+interface ProcessMetrics {
+  timestamp: number;
+  cpuPercent: number | null;
+  rssBytes: number | null;
+  threadCount: number;
+  handleCount: number | null;
+}
+
+interface Sampler {
+  cpuTimeMs(): number | null;
+  rssBytes(): number | null;
+  handleCount(): number | null;
+}
+
+let sampler: Sampler | null = null;
+let timer: ReturnType<typeof setInterval> | null = null;
+let intervalMs = DEFAULT_INTERVAL_MS;
+let lastCpuTimeMs: number | null = null;
+let lastSampleAt = 0;
+
+function nativeFunction(
+  name: string,
+  retType: NativeFunctionReturnType,
+  argTypes: NativeFunctionArgumentType[],
+  moduleName: string | null = null,
+): AnyNativeFunction | null {
+  const address = findExportByName(moduleName, name);
+  return address ? new NativeFunction(address, retType, argTypes) : null;
+}
+
+function posixCpuTimeMs(): () => number | null {
+  const getrusage = nativeFunction("getrusage", "int", ["int", "pointer"]);
+  // struct rusage starts with ru_utime and ru_stime, each a timeval of
+  // { long tv_sec; suseconds_t tv_usec } padded to two longs.
+  const timevalSize = Process.pointerSize * 2;
+  const usage = Memory.alloc(256);
+  return () => {
+    if (!getrusage || (getrusage(0 /* RUSAGE_SELF */, usage) as number) !== 0) return null;
+    const millis = (timeval: NativePointer) =>
+      Number(timeval.readLong()) * 1000 + timeval.add(Process.pointerSize).readS32() / 1000;
+    return millis(usage) + millis(usage.add(timevalSize));
+  };
+}
+
+function countEntries(directory: string): number | null {
+  const opendir = nativeFunction("opendir", "pointer", ["pointer"]);
+  const readdir = nativeFunction("readdir", "pointer", ["pointer"]);
+  const closedir = nativeFunction("closedir", "int", ["pointer"]);
+  if (!opendir || !readdir || !closedir) return null;
+
+  const dir = opendir(Memory.allocUtf8String(directory)) as NativePointer;
+  if (dir.isNull()) return null;
+  let count = 0;
+  while (!(readdir(dir) as NativePointer).isNull()) count += 1;
+  closedir(dir);
+  // ".", ".." and the descriptor opendir itself holds.
+  return Math.max(0, count - 3);
+}
+
+function linuxSampler(): Sampler {
+  const pageSize = Process.pageSize;
+  return {
+    cpuTimeMs: posixCpuTimeMs(),
+    rssBytes() {
+      try {
+        // statm: size resident shared text lib data dt, in pages.
+        const fields = File.readAllText("/proc/self/statm").trim().split(/\s+/);
+        return Number(fields[1]) * pageSize;
+      } catch {
+        return null;
+      }
+    },
+    handleCount: () => countEntries("/proc/self/fd"),
+  };
+}
+
+function darwinSampler(): Sampler {
+  const machTaskSelf = nativeFunction("mach_task_self", "uint", []);
+  const taskInfo = nativeFunction("task_info", "int", ["uint", "int", "pointer", "pointer"]);
+  const procPidinfo = nativeFunction("proc_pidinfo", "int", [
+    "int",
+    "int",
+    "uint64",
+    "pointer",
+    "int",
+  ]);
+  const MACH_TASK_BASIC_INFO = 20;
+  const MACH_TASK_BASIC_INFO_COUNT = 12; // sizeof(mach_task_basic_info) / sizeof(natural_t)
+  const PROC_PIDLISTFDS = 1;
+  const PROC_FDINFO_SIZE = 8;
+  const info = Memory.alloc(MACH_TASK_BASIC_INFO_COUNT * 4);
+  const count = Memory.alloc(4);
+
+  return {
+    cpuTimeMs: posixCpuTimeMs(),
+    rssBytes() {
+      if (!machTaskSelf || !taskInfo) return null;
+      count.writeU32(MACH_TASK_BASIC_INFO_COUNT);
+      const task = machTaskSelf() as number;
+      if ((taskInfo(task, MACH_TASK_BASIC_INFO, info, count) as number) !== 0) return null;
+      // { virtual_size, resident_size, ... }
+      return Number(info.add(8).readU64());
+    },
+    handleCount() {
+      if (!procPidinfo) return null;
+      const needed = procPidinfo(Process.id, PROC_PIDLISTFDS, 0, NULL, 0) as number;
+      if (needed <= 0) return null;
+      const buffer = Memory.alloc(needed);
+      const written = procPidinfo(Process.id, PROC_PIDLISTFDS, 0, buffer, needed) as number;
+      return written > 0 ? written / PROC_FDINFO_SIZE : null;
+    },
+  };
+}
+
+function windowsSampler(): Sampler {
+  const getCurrentProcess = nativeFunction("GetCurrentProcess", "pointer", [], "kernel32.dll");
+  const getProcessTimes = nativeFunction(
+    "GetProcessTimes",
+    "int",
+    ["pointer", "pointer", "pointer", "pointer", "pointer"],
+    "kernel32.dll",
+  );
+  const getMemoryInfo = nativeFunction(
+    "K32GetProcessMemoryInfo",
+    "int",
+    ["pointer", "pointer", "uint"],
+    "kernel32.dll",
+  );
+  const getHandleCount = nativeFunction(
+    "GetProcessHandleCount",
+    "int",
+    ["pointer", "pointer"],
+    "kernel32.dll",
+  );
+  const times = Memory.alloc(32);
+  // PROCESS_MEMORY_COUNTERS: DWORD cb, DWORD PageFaultCount, then eight SIZE_Ts.
+  const countersSize = 8 + 8 * Process.pointerSize;
+  const counters = Memory.alloc(countersSize);
+  const handles = Memory.alloc(4);
+  const self = () => (getCurrentProcess ? (getCurrentProcess() as NativePointer) : null);
+
+  return {
+    cpuTimeMs() {
+      const process = self();
+      if (!process || !getProcessTimes) return null;
+      const ok = getProcessTimes(process, times, times.add(8), times.add(16), times.add(24));
+      if ((ok as number) === 0) return null;
+      // Kernel and user FILETIMEs, in 100 ns units.
+      return (Number(times.add(16).readU64()) + Number(times.add(24).readU64())) / 10_000;
+    },
+    rssBytes() {
+      const process = self();
+      if (!process || !getMemoryInfo) return null;
+      counters.writeU32(countersSize);
+      if ((getMemoryInfo(process, counters, countersSize) as number) === 0) return null;
+      // WorkingSetSize follows PeakWorkingSetSize.
+      return Number(counters.add(8 + Process.pointerSize).readPointer());
+    },
+    handleCount() {
+      const process = self();
+      if (!process || !getHandleCount) return null;
+      if ((getHandleCount(process, handles) as number) === 0) return null;
+      return handles.readU32();
+    },
+  };
+}
+
+function getSampler(): Sampler {
+  if (sampler) return sampler;
+  switch (Process.platform) {
+    case "linux":
+      sampler = linuxSampler();
+      break;
+    case "darwin":
+      sampler = darwinSampler();
+      break;
+    case "windows":
+      sampler = windowsSampler();
+      break;
+    default:
+      sampler = { cpuTimeMs: () => null, rssBytes: () => null, handleCount: () => null };
+  }
+  return sampler;
+}
+
+function safely<T>(read: () => T | null): T | null {
+  try {
+    return read();
+  } catch {
+    return null;
+  }
+}
+
+function sample(): ProcessMetrics {
+  const current = getSampler();
+  const now = Date.now();
+  const cpuTimeMs = safely(() => current.cpuTimeMs());
+
+  let cpuPercent: number | null = null;
+  if (cpuTimeMs !== null && lastCpuTimeMs !== null && now > lastSampleAt) {
+    cpuPercent = Math.max(0, ((cpuTimeMs - lastCpuTimeMs) / (now - lastSampleAt)) * 100);
+  }
+  lastCpuTimeMs = cpuTimeMs;
+  lastSampleAt = now;
+
+  return {
+    timestamp: now,
+    cpuPercent,
+    rssBytes: safely(() => current.rssBytes()),
+    threadCount: Process.enumerateThreads().length,
+    handleCount: safely(() => current.handleCount()),
+  };
+}
+
+registerHandler("startProcessMetrics", (params: unknown) => {
+  const { intervalMs: requested } = (params ?? {}) as { intervalMs?: number };
+  if (timer !== null) clearInterval(timer);
+
+  intervalMs = Math.max(MIN_INTERVAL_MS, requested ?? DEFAULT_INTERVAL_MS);
+  // Primes the CPU baseline so the first event already has a percentage.
+  sample();
+  timer = setInterval(() => emitEvent("process/metrics", sample()), intervalMs);
+  return { started: true, intervalMs };
+});
+
+registerHandler("stopProcessMetrics", (_params: unknown) => {
+  if (timer === null) return { stopped: false };
+  clearInterval(timer);
+  timer = null;
+  lastCpuTimeMs = null;
+  return { stopped: true };
+});
+
+registerHandler("getProcessMetrics", (_params: unknown) => {
+  return { running: timer !== null, intervalMs, metrics: sample() };
+});
@@ -1,5 +1,6 @@
 import { registerHandler } from "../rpc/router";
 import { emitMemoryAccess, emitLog } from "../rpc/protocol";
+import { hasPageWatches } from "./watch";
 
 interface MonitorEvent {
   operation: "read" | "write" | "execute";
@@ -25,10 +26,17 @@ let emitWindowStart = 0;
 let emitsInWindow = 0;
 let droppedSinceLastWarn = 0;
 
+export function isMemoryMonitorActive(): boolean {
+  return monitorActive;
+}
+
 registerHandler("startMemoryMonitor", (params: unknown) => {
   if (monitorActive) {
     throw new Error("Memory monitor already active");
   }
+  if (hasPageWatches()) {
+    throw new Error("Memory watches are using MemoryAccessMonitor; stop them first");
+  }
 
   const { ranges } = params as {
     ranges: Array<{ base: string; size: number }>;
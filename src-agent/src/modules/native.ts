@@ -1,6 +1,7 @@
 import { registerHandler } from "../rpc/router";
 import { emitHookEvent } from "../rpc/protocol";
-import { findExportByName } from "../runtime/frida-compat";
+import { findExportByName, findLoadedModule } from "../runtime/frida-compat";
+import { registerCleanup } from "./cleanup";
 
 interface HookEntry {
   hookId: string;
@@ -27,7 +28,16 @@ function toHookInfo(hook: HookEntry) {
   };
 }
 
-function resolveTarget(target: string): NativePointer {
+/** Addresses of installed native hooks, for integrity checks. */
+export function listHookSites(): Array<{ hookId: string; target: string; address: string }> {
+  return Array.from(hooks.values()).map((hook) => ({
+    hookId: hook.hookId,
+    target: hook.target,
+    address: hook.address,
+  }));
+}
+
+export function resolveTarget(target: string): NativePointer {
   // Only treat as address when the 0x prefix is explicit. Without the prefix we
   // cannot distinguish a symbol like "deadbeef" (a valid C identifier) from a
   // hex address, and dereferencing it caused SIGSEGVs in the field.
@@ -35,11 +45,16 @@ function resolveTarget(target: string): NativePointer {
     return ptr(target);
   }
 
-  // module!symbol format
+  // module!symbol or module!0xoffset format
   const bangIndex = target.indexOf("!");
   if (bangIndex !== -1) {
     const moduleName = target.slice(0, bangIndex);
     const symbolName = target.slice(bangIndex + 1);
+    if (/^0x[0-9a-fA-F]+$/.test(symbolName)) {
+      const module = findLoadedModule(moduleName);
+      if (!module) throw new Error(`Module not found: ${moduleName}`);
+      return module.base.add(ptr(symbolName));
+    }
     const addr = findExportByName(moduleName, symbolName);
     if (!addr) throw new Error(`Export not found: ${target}`);
     return addr;
@@ -51,20 +66,22 @@ function resolveTarget(target: string): NativePointer {
   return addr;
 }
 
-registerHandler("hookFunction", (params: unknown) => {
-  const {
-    target,
-    captureArgs = false,
-    captureRetval = false,
-    captureBacktrace = false,
-  } = params as {
-    target: string;
-    captureArgs?: boolean;
-    captureRetval?: boolean;
-    captureBacktrace?: boolean;
-  };
+export interface NativeHookOptions {
+  captureArgs?: boolean;
+  captureRetval?: boolean;
+  captureBacktrace?: boolean;
+}
 
-  const addr = resolveTarget(target);
+/**
+ * Attaches an Interceptor hook at `addr`, labelled `target` in hook events
+ * and listings. Runtime-specific modules resolve their own addresses and
+ * hook through here so their hooks share this registry.
+ */
+export function installNativeHook(
+  target: string,
+  addr: NativePointer,
+  { captureArgs = false, captureRetval = false, captureBacktrace = false }: NativeHookOptions,
+) {
   const hookId = `native_hook_${Date.now()}_${Math.random().toString(36).slice(2, 8)}`;
 
   const listener = Interceptor.attach(addr, {
@@ -139,6 +156,11 @@ registerHandler("hookFunction", (params: unknown) => {
   });
 
   return toHookInfo(hooks.get(hookId)!);
+}
+
+registerHandler("hookFunction", (params: unknown) => {
+  const { target, ...options } = params as { target: string } & NativeHookOptions;
+  return installNativeHook(target, resolveTarget(target), options);
 });
 
 registerHandler("unhookFunction", (params: unknown) => {
@@ -175,3 +197,10 @@ registerHandler("setNativeHookActive", (params: unknown) => {
 registerHandler("listHooks", (_params: unknown) => {
   return Array.from(hooks.values()).map(toHookInfo);
 });
+
+registerCleanup("nativeHooks", () => {
+  const removed = hooks.size;
+  for (const hook of hooks.values()) hook.listener.detach();
+  hooks.clear();
+  return removed;
+});
@@ -1,6 +1,7 @@
 import { JavaRuntime as Java } from "../bridges";
 import { registerHandler } from "../rpc/router";
 import { emitNetworkRequest } from "../rpc/protocol";
+import { registerCleanup } from "./cleanup";
 import {
   findExportByName,
   readByteArray,
@@ -559,9 +560,11 @@ registerHandler("startNetworkCapture", (_params: unknown) => {
   };
 });
 
-registerHandler("stopNetworkCapture", (_params: unknown) => {
-  if (!capturing) throw new Error("Network capture is not running");
-
+// Removes every capture hook and returns how many there were.
+function stopCapture(): number {
+  let removed = activeHooks.javaCleanup.length;
+  if (activeHooks.sslRead) removed++;
+  if (activeHooks.sslWrite) removed++;
   activeHooks.sslRead?.detach();
   activeHooks.sslWrite?.detach();
   activeHooks.sslRead = undefined;
@@ -571,10 +574,18 @@ registerHandler("stopNetworkCapture", (_params: unknown) => {
   }
   javaConnections.clear();
   capturing = false;
+  return removed;
+}
 
+registerHandler("stopNetworkCapture", (_params: unknown) => {
+  if (!capturing) throw new Error("Network capture is not running");
+
+  stopCapture();
   return { stopped: true };
 });
 
+registerCleanup("networkCapture", () => (capturing ? stopCapture() : 0));
+
 registerHandler("isNetworkCaptureActive", (_params: unknown) => {
   return { active: capturing };
 });
@@ -1,6 +1,7 @@
 import { ObjCRuntime as ObjC } from "../bridges";
 import { registerHandler } from "../rpc/router";
 import { emitHookEvent } from "../rpc/protocol";
+import { registerCleanup } from "./cleanup";
 
 interface ObjcHookEntry {
   hookId: string;
@@ -198,6 +199,13 @@ registerHandler("listObjcHooks", (_params: unknown) => {
   return Array.from(objcHooks.values()).map(toHookInfo);
 });
 
+registerCleanup("objcHooks", () => {
+  const removed = objcHooks.size;
+  for (const hook of objcHooks.values()) hook.listener.detach();
+  objcHooks.clear();
+  return removed;
+});
+
 registerHandler("setObjcHookActive", (params: unknown) => {
   const { hookId, active } = params as { hookId: string; active: boolean };
   const hook = objcHooks.get(hookId);
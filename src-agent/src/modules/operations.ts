@@ -0,0 +1,37 @@
+import { registerHandler } from "../rpc/router";
+
+// --- Cancellable operations ---
+//
+// Long-running handlers register here so the host can stop them through
+// `cancelOperation`. Cancellation is cooperative: the operation checks
+// `isCancelled()` between units of work and wraps up with what it has.
+
+export interface Operation {
+  id: string;
+  isCancelled(): boolean;
+  finish(): void;
+}
+
+const cancelled = new Map<string, boolean>();
+let nextId = 1;
+
+export function beginOperation(prefix: string): Operation {
+  const id = `${prefix}-${Process.id}-${nextId++}`;
+  cancelled.set(id, false);
+  return {
+    id,
+    isCancelled: () => cancelled.get(id) === true,
+    finish: () => {
+      cancelled.delete(id);
+    },
+  };
+}
+
+registerHandler("cancelOperation", (params: unknown) => {
+  const { operationId } = params as { operationId: string };
+  if (!cancelled.has(operationId)) {
+    return { cancelled: false };
+  }
+  cancelled.set(operationId, true);
+  return { cancelled: true };
+});
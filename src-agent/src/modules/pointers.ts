@@ -0,0 +1,275 @@
+import { registerHandler } from "../rpc/router";
+import { emitEvent } from "../rpc/protocol";
+
+// --- Pointer chain scanning ---
+//
+// A pointer map records every pointer-aligned, pointer-sized value in
+// writable memory that points into a readable range. Chains are found by
+// walking it backwards from the target: every slot whose value is within
+// `maxOffset` below the current address is one level up, until a slot
+// inside a module image (a static address that survives restarts modulo
+// ASLR) is reached. Addresses are kept as doubles, which is exact below
+// 2^53 once pointer tags are stripped.
+
+const CHUNK_SIZE = 4 * 1024 * 1024;
+const MAX_ENTRIES = 8_000_000;
+const MAX_NODES_PER_LEVEL = 200_000;
+const TAG_MASK_HIGH = 0x00ffffff; // drops the top byte (TBI / MTE tags)
+
+interface PointerMap {
+  createdAt: number;
+  /** Pointed-to values, ascending. */
+  values: Float64Array;
+  /** Slot address holding `values[i]`. */
+  slots: Float64Array;
+  truncated: boolean;
+}
+
+interface Span {
+  base: number;
+  end: number;
+  name: string;
+}
+
+interface PointerChain {
+  module: string;
+  moduleOffset: number;
+  offsets: number[];
+}
+
+interface Node {
+  address: number;
+  /** Offset added to this node's pointer value to reach the next node. */
+  offset: number;
+  next: number;
+}
+
+let snapshot: PointerMap | null = null;
+
+function toNumber(pointer: NativePointer): number {
+  return Number(pointer.toString());
+}
+
+function findSpan(spans: Span[], address: number): Span | null {
+  let low = 0;
+  let high = spans.length - 1;
+  while (low <= high) {
+    const mid = (low + high) >> 1;
+    const span = spans[mid];
+    if (address < span.base) high = mid - 1;
+    else if (address >= span.end) low = mid + 1;
+    else return span;
+  }
+  return null;
+}
+
+function readableSpans(): Span[] {
+  return Process.enumerateRanges({ protection: "r--", coalesce: true })
+    .map((range) => {
+      const base = toNumber(range.base);
+      return { base, end: base + range.size, name: "" };
+    })
+    .sort((a, b) => a.base - b.base);
+}
+
+function moduleSpans(): Span[] {
+  return Process.enumerateModules()
+    .map((module) => {
+      const base = toNumber(module.base);
+      return { base, end: base + module.size, name: module.name };
+    })
+    .sort((a, b) => a.base - b.base);
+}
+
+function buildPointerMap(): PointerMap {
+  const readable = readableSpans();
+  const pointerSize = Process.pointerSize;
+  let values = new Float64Array(1 << 20);
+  let slots = new Float64Array(1 << 20);
+  let count = 0;
+  let truncated = false;
+
+  const push = (value: number, slot: number) => {
+    if (count === values.length) {
+      const grown = Math.min(values.length * 2, MAX_ENTRIES);
+      const nextValues = new Float64Array(grown);
+      const nextSlots = new Float64Array(grown);
+      nextValues.set(values);
+      nextSlots.set(slots);
+      values = nextValues;
+      slots = nextSlots;
+    }
+    values[count] = value;
+    slots[count] = slot;
+    count += 1;
+  };
+
+  const ranges = Process.enumerateRanges({ protection: "rw-", coalesce: true });
+  outer: for (const [index, range] of ranges.entries()) {
+    const rangeBase = toNumber(range.base);
+    for (let done = 0; done < range.size; done += CHUNK_SIZE) {
+      const length = Math.min(CHUNK_SIZE, range.size - done);
+      let bytes: ArrayBuffer | null;
+      try {
+        bytes = range.base.add(done).readByteArray(length);
+      } catch {
+        continue;
+      }
+      if (!bytes) continue;
+
+      const view = new DataView(bytes);
+      for (let at = 0; at + pointerSize <= length; at += pointerSize) {
+        const value =
+          pointerSize === 8
+            ? (view.getUint32(at + 4, true) & TAG_MASK_HIGH) * 0x1_0000_0000 +
+              view.getUint32(at, true)
+            : view.getUint32(at, true);
+        if (value === 0 || findSpan(readable, value) === null) continue;
+        if (count >= MAX_ENTRIES) {
+          truncated = true;
+          break outer;
+        }
+        push(value, rangeBase + done + at);
+      }
+    }
+
+    emitEvent("scan/progress", {
+      progress: Math.round(((index + 1) / ranges.length) * 100),
+      scanned: index + 1,
+      total: ranges.length,
+      phase: "pointerMap",
+    });
+  }
+
+  const order = new Uint32Array(count);
+  for (let i = 0; i < count; i++) order[i] = i;
+  order.sort((a, b) => values[a] - values[b]);
+  const sortedValues = new Float64Array(count);
+  const sortedSlots = new Float64Array(count);
+  for (let i = 0; i < count; i++) {
+    sortedValues[i] = values[order[i]];
+    sortedSlots[i] = slots[order[i]];
+  }
+
+  return { createdAt: Date.now(), values: sortedValues, slots: sortedSlots, truncated };
+}
+
+/** First index whose value is >= `value`. */
+function lowerBound(values: Float64Array, value: number): number {
+  let low = 0;
+  let high = values.length;
+  while (low < high) {
+    const mid = (low + high) >>> 1;
+    if (values[mid] < value) low = mid + 1;
+    else high = mid;
+  }
+  return low;
+}
+
+function chainFrom(nodes: Node[], start: number, module: Span): PointerChain {
+  const offsets: number[] = [];
+  for (let index = start; nodes[index].next !== -1; index = nodes[index].next) {
+    offsets.push(nodes[index].offset);
+  }
+  return {
+    module: module.name,
+    moduleOffset: nodes[start].address - module.base,
+    offsets,
+  };
+}
+
+function findChains(
+  map: PointerMap,
+  target: number,
+  maxDepth: number,
+  maxOffset: number,
+  maxResults: number,
+): { chains: PointerChain[]; truncated: boolean } {
+  const modules = moduleSpans();
+  const nodes: Node[] = [{ address: target, offset: 0, next: -1 }];
+  const visited = new Set<number>([target]);
+  const chains: PointerChain[] = [];
+  let frontier = [0];
+  let truncated = false;
+
+  for (let depth = 1; depth <= maxDepth && frontier.length > 0; depth++) {
+    const nextFrontier: number[] = [];
+    for (const parent of frontier) {
+      const wanted = nodes[parent].address;
+      for (
+        let i = lowerBound(map.values, wanted - maxOffset);
+        i < map.values.length && map.values[i] <= wanted;
+        i++
+      ) {
+        const slot = map.slots[i];
+        const node = nodes.length;
+        nodes.push({ address: slot, offset: wanted - map.values[i], next: parent });
+
+        const module = findSpan(modules, slot);
+        if (module) {
+          chains.push(chainFrom(nodes, node, module));
+          if (chains.length >= maxResults) return { chains, truncated: true };
+          continue;
+        }
+        if (visited.has(slot)) continue;
+        visited.add(slot);
+        if (nextFrontier.length >= MAX_NODES_PER_LEVEL) {
+          truncated = true;
+          continue;
+        }
+        nextFrontier.push(node);
+      }
+    }
+    frontier = nextFrontier;
+  }
+
+  return { chains, truncated };
+}
+
+function resolveChain(chain: PointerChain): { address: string | null; error: string | null } {
+  const module = Process.findModuleByName(chain.module);
+  if (!module) return { address: null, error: `module ${chain.module} is not loaded` };
+
+  let address = module.base.add(chain.moduleOffset);
+  try {
+    for (const offset of chain.offsets) {
+      const pointer = address.readPointer().strip();
+      if (pointer.isNull()) {
+        return { address: null, error: `null pointer at ${address}` };
+      }
+      address = pointer.add(offset);
+    }
+  } catch (e) {
+    return { address: null, error: e instanceof Error ? e.message : String(e) };
+  }
+  return { address: address.toString(), error: null };
+}
+
+function snapshotInfo(map: PointerMap) {
+  return { createdAt: map.createdAt, entries: map.values.length, truncated: map.truncated };
+}
+
+// Takes a fresh pointer map that later `pointerScan` calls reuse.
+registerHandler("snapshotPointerMap", (_params: unknown) => {
+  snapshot = buildPointerMap();
+  return snapshotInfo(snapshot);
+});
+
+registerHandler("pointerScan", (params: unknown) => {
+  const { target, maxDepth, maxOffset, maxResults, refresh } = params as {
+    target: string;
+    maxDepth: number;
+    maxOffset: number;
+    maxResults: number;
+    refresh?: boolean;
+  };
+  if (refresh || snapshot === null) snapshot = buildPointerMap();
+
+  const found = findChains(snapshot, toNumber(ptr(target)), maxDepth, maxOffset, maxResults);
+  return { ...found, snapshot: snapshotInfo(snapshot) };
+});
+
+registerHandler("resolvePointerChains", (params: unknown) => {
+  const { chains } = params as { chains: PointerChain[] };
+  return chains.map(resolveChain);
+});
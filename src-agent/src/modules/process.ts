@@ -4,6 +4,12 @@ registerHandler("ping", (_params: unknown) => {
   return "pong";
 });
 
+function nativeEndianness(): "little" | "big" {
+  const probe = Memory.alloc(2);
+  probe.writeU16(1);
+  return probe.readU8() === 1 ? "little" : "big";
+}
+
 registerHandler("getProcessInfo", (_params: unknown) => {
   return {
     pid: Process.id,
@@ -11,8 +17,10 @@ registerHandler("getProcessInfo", (_params: unknown) => {
     platform: Process.platform,
     pageSize: Process.pageSize,
     pointerSize: Process.pointerSize,
+    endianness: nativeEndianness(),
     codeSigningPolicy: Process.codeSigningPolicy,
     isDebuggerAttached: Process.isDebuggerAttached(),
+    fridaVersion: Frida.version,
     mainModule: Process.mainModule
       ? {
           name: Process.mainModule.name,
@@ -0,0 +1,128 @@
+import { registerHandler } from "../rpc/router";
+import { readByteArray } from "../runtime/frida-compat";
+
+// --- Value snapshots ---
+//
+// Named sets of addresses with the values they held when the snapshot was
+// taken, e.g. scan results before and after an in-app action. They live in
+// the agent so they go away with the process whose addresses they describe.
+// Values are kept as strings: 64-bit integers and pointers would lose
+// precision as numbers, and the host does the comparing.
+
+const MAX_ENTRIES = 1_000_000;
+
+type SnapshotValueType =
+  | "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64"
+  | "f32" | "f64" | "pointer" | "bytes";
+
+interface SnapshotEntry {
+  address: string;
+  type: SnapshotValueType;
+  /** Bytes to keep for `bytes`. */
+  size?: number;
+}
+
+interface SnapshotValue {
+  address: string;
+  type: SnapshotValueType;
+  /** `null` when the address could not be read. */
+  value: string | null;
+}
+
+interface Snapshot {
+  name: string;
+  createdAt: number;
+  values: SnapshotValue[];
+}
+
+const snapshots = new Map<string, Snapshot>();
+
+const READERS: Record<string, (target: NativePointer) => string> = {
+  u8: (target) => String(target.readU8()),
+  i8: (target) => String(target.readS8()),
+  u16: (target) => String(target.readU16()),
+  i16: (target) => String(target.readS16()),
+  u32: (target) => String(target.readU32()),
+  i32: (target) => String(target.readS32()),
+  u64: (target) => target.readU64().toString(),
+  i64: (target) => target.readS64().toString(),
+  f32: (target) => String(target.readFloat()),
+  f64: (target) => String(target.readDouble()),
+  pointer: (target) => target.readPointer().toString(),
+};
+
+function hexEncode(buf: ArrayBuffer): string {
+  const bytes = new Uint8Array(buf);
+  let hex = "";
+  for (let i = 0; i < bytes.length; i++) {
+    hex += bytes[i].toString(16).padStart(2, "0");
+  }
+  return hex;
+}
+
+function readEntry(entry: SnapshotEntry): string | null {
+  try {
+    const target = ptr(entry.address);
+    if (entry.type === "bytes") {
+      const bytes = readByteArray(target, entry.size ?? 0);
+      return bytes ? hexEncode(bytes) : null;
+    }
+    return READERS[entry.type](target);
+  } catch {
+    return null;
+  }
+}
+
+function snapshotInfo(snapshot: Snapshot) {
+  return {
+    name: snapshot.name,
+    createdAt: snapshot.createdAt,
+    count: snapshot.values.length,
+    unreadable: snapshot.values.filter((value) => value.value === null).length,
+  };
+}
+
+// Reads every entry now and stores the values under `name`, replacing a
+// snapshot with the same name.
+registerHandler("saveValueSnapshot", (params: unknown) => {
+  const { name, entries } = params as { name: string; entries: SnapshotEntry[] };
+  if (entries.length > MAX_ENTRIES) {
+    throw new Error(`A snapshot holds at most ${MAX_ENTRIES} entries, got ${entries.length}`);
+  }
+  for (const entry of entries) {
+    if (entry.type !== "bytes" && !(entry.type in READERS)) {
+      throw new Error(`Unsupported snapshot value type: ${entry.type}`);
+    }
+    if (entry.type === "bytes" && !(Number(entry.size) > 0)) {
+      throw new Error(`bytes entry at ${entry.address} needs a size`);
+    }
+  }
+
+  const snapshot: Snapshot = {
+    name,
+    createdAt: Date.now(),
+    values: entries.map((entry) => ({
+      address: entry.address,
+      type: entry.type,
+      value: readEntry(entry),
+    })),
+  };
+  snapshots.set(name, snapshot);
+  return snapshotInfo(snapshot);
+});
+
+registerHandler("listValueSnapshots", (_params: unknown) => {
+  return [...snapshots.values()].map(snapshotInfo);
+});
+
+registerHandler("getValueSnapshot", (params: unknown) => {
+  const { name } = params as { name: string };
+  const snapshot = snapshots.get(name);
+  if (!snapshot) throw new Error(`No snapshot named ${name}`);
+  return snapshot;
+});
+
+registerHandler("deleteValueSnapshot", (params: unknown) => {
+  const { name } = params as { name: string };
+  return { deleted: snapshots.delete(name) };
+});
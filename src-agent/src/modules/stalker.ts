@@ -1,6 +1,9 @@
 import { JavaRuntime as Java } from "../bridges";
 import { registerHandler } from "../rpc/router";
 import { emitStalkerEvent } from "../rpc/protocol";
+import { registerCleanup } from "./cleanup";
+import { onLayoutChanged } from "./layout";
+import { resolveTarget } from "./native";
 
 interface StructuredStalkerEvent {
   threadId: number;
@@ -23,12 +26,28 @@ interface StalkerSession {
   packageName: string | null;
 }
 
+interface StalkerEventOptions {
+  call?: boolean;
+  ret?: boolean;
+  exec?: boolean;
+  block?: boolean;
+  compile?: boolean;
+}
+
+interface FunctionTrace {
+  traceId: string;
+  target: string;
+  address: string;
+  listener: InvocationListener;
+}
+
 interface ModuleRange {
   base: NativePointer;
   size: number;
 }
 
 const sessions = new Map<number, StalkerSession>();
+const traces = new Map<string, FunctionTrace>();
 const moduleMap = new ModuleMap();
 const addressCache = new Map<string, {
   address: string;
@@ -40,6 +59,14 @@ const MAX_BUFFER_SIZE = 1000;
 const MAX_CACHE_SIZE = 8192;
 const excludedModuleRanges = new Set<string>();
 
+// Symbolication results are keyed by address, so they go stale as soon as a
+// module is loaded, unloaded or rebased.
+onLayoutChanged((change) => {
+  if (change.reason !== "modules") return;
+  moduleMap.update();
+  addressCache.clear();
+});
+
 // Periodic flush to avoid holding too many events in memory
 let flushTimer: ReturnType<typeof setInterval> | null = null;
 
@@ -372,21 +399,7 @@ function ensureFlushTimer(): void {
   }, FLUSH_INTERVAL_MS);
 }
 
-registerHandler("startStalker", (params: unknown) => {
-  const {
-    threadId,
-    events = {},
-  } = params as {
-    threadId: number;
-    events?: {
-      call?: boolean;
-      ret?: boolean;
-      exec?: boolean;
-      block?: boolean;
-      compile?: boolean;
-    };
-  };
-
+function followThread(threadId: number, events: StalkerEventOptions) {
   if (sessions.has(threadId)) {
     throw new Error(`Stalker already following thread: ${threadId}`);
   }
@@ -485,11 +498,9 @@ registerHandler("startStalker", (params: unknown) => {
     events: eventMask,
     mode: samplingMode ? "sampling" : "stalker",
   };
-});
-
-registerHandler("stopStalker", (params: unknown) => {
-  const { threadId } = params as { threadId: number };
+}
 
+function unfollowThread(threadId: number) {
   if (!sessions.has(threadId)) {
     throw new Error(`Stalker not following thread: ${threadId}`);
   }
@@ -514,6 +525,83 @@ registerHandler("stopStalker", (params: unknown) => {
   }
 
   return { threadId, stopped: true };
+}
+
+registerHandler("startStalker", (params: unknown) => {
+  const { threadId, events = {} } = params as {
+    threadId: number;
+    events?: StalkerEventOptions;
+  };
+
+  return followThread(threadId, events);
+});
+
+registerHandler("stopStalker", (params: unknown) => {
+  const { threadId } = params as { threadId: number };
+  return unfollowThread(threadId);
+});
+
+// Follows whichever thread enters `target` for the duration of the call, so a
+// trace covers exactly one invocation instead of everything the thread does.
+registerHandler("traceFunction", (params: unknown) => {
+  const { target, events = { call: true, ret: true } } = params as {
+    target: string;
+    events?: StalkerEventOptions;
+  };
+
+  const address = resolveTarget(target);
+  const traceId = `stalker_trace_${Date.now()}_${Math.random().toString(36).slice(2, 8)}`;
+
+  const listener = Interceptor.attach(address, {
+    onEnter() {
+      // Recursive calls and threads already followed by startStalker are
+      // left alone; only the outermost invocation owns the session.
+      if (sessions.has(this.threadId)) {
+        return;
+      }
+      try {
+        followThread(this.threadId, events);
+        this.traceOwner = true;
+      } catch {
+        this.traceOwner = false;
+      }
+    },
+    onLeave() {
+      if (this.traceOwner === true && sessions.has(this.threadId)) {
+        unfollowThread(this.threadId);
+      }
+    },
+  });
+
+  traces.set(traceId, { traceId, target, address: address.toString(), listener });
+  return { traceId, target, address: address.toString() };
+});
+
+registerHandler("untraceFunction", (params: unknown) => {
+  const { traceId } = params as { traceId: string };
+  const trace = traces.get(traceId);
+  if (!trace) {
+    throw new Error(`Trace not found: ${traceId}`);
+  }
+
+  trace.listener.detach();
+  traces.delete(traceId);
+  return { traceId, stopped: true };
+});
+
+// Traces first, so none of them starts following a thread again while the
+// sessions are being stopped.
+registerCleanup("functionTraces", () => {
+  const removed = traces.size;
+  for (const trace of traces.values()) trace.listener.detach();
+  traces.clear();
+  return removed;
+});
+
+registerCleanup("stalkerSessions", () => {
+  const threadIds = [...sessions.keys()];
+  for (const threadId of threadIds) unfollowThread(threadId);
+  return threadIds.length;
 });
 
 registerHandler("getStalkerEvents", (params: unknown) => {
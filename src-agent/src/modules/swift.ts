@@ -2,6 +2,7 @@ import { SwiftRuntime as Swift, ObjCRuntime as ObjC } from "../bridges";
 import type { SwiftTypeDescriptor } from "../bridges";
 import { registerHandler } from "../rpc/router";
 import { emitHookEvent } from "../rpc/protocol";
+import { registerCleanup } from "./cleanup";
 
 interface SwiftHookEntry {
   hookId: string;
@@ -399,6 +400,13 @@ registerHandler("listSwiftHooks", (_params: unknown) => {
   return Array.from(swiftHooks.values()).map(toHookInfo);
 });
 
+registerCleanup("swiftHooks", () => {
+  const removed = swiftHooks.size;
+  for (const hook of swiftHooks.values()) hook.listener.detach();
+  swiftHooks.clear();
+  return removed;
+});
+
 registerHandler("setSwiftHookActive", (params: unknown) => {
   const { hookId, active } = params as { hookId: string; active: boolean };
   const hook = swiftHooks.get(hookId);
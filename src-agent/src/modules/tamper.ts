@@ -0,0 +1,71 @@
+import { emitEvent } from "../rpc/protocol";
+import { listHookSites } from "./native";
+
+// --- Anti-tamper signals ---
+//
+// The host watches for targets that fight instrumentation. This module
+// heartbeats so the host notices when the agent's JS thread stops running,
+// and re-reads hooked prologues to catch targets that restore their original
+// code. Baselines are taken on the first check after a hook appears, once
+// Interceptor has flushed its patch.
+
+const CHECK_INTERVAL_MS = 2000;
+const PROLOGUE_SIZE = 16;
+
+const baselines = new Map<string, string>();
+const reverted = new Set<string>();
+
+function readPrologue(address: string): string | null {
+  try {
+    const bytes = ptr(address).readByteArray(PROLOGUE_SIZE);
+    if (!bytes) return null;
+    return Array.from(new Uint8Array(bytes))
+      .map((b) => b.toString(16).padStart(2, "0"))
+      .join("");
+  } catch {
+    return null;
+  }
+}
+
+function checkHooks(): void {
+  const sites = listHookSites();
+  const live = new Set(sites.map((site) => site.hookId));
+  for (const hookId of baselines.keys()) {
+    if (!live.has(hookId)) {
+      baselines.delete(hookId);
+      reverted.delete(hookId);
+    }
+  }
+
+  for (const site of sites) {
+    const current = readPrologue(site.address);
+    if (current === null) continue;
+
+    const expected = baselines.get(site.hookId);
+    if (expected === undefined) {
+      baselines.set(site.hookId, current);
+      continue;
+    }
+
+    if (current === expected) {
+      reverted.delete(site.hookId);
+    } else if (!reverted.has(site.hookId)) {
+      reverted.add(site.hookId);
+      emitEvent("tamper/evidence", {
+        kind: "hook_reverted",
+        detail: {
+          hookId: site.hookId,
+          target: site.target,
+          address: site.address,
+          expected,
+          actual: current,
+        },
+      });
+    }
+  }
+}
+
+setInterval(() => {
+  emitEvent("agent/heartbeat", {});
+  checkHooks();
+}, CHECK_INTERVAL_MS);
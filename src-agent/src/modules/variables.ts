@@ -0,0 +1,53 @@
+import { registerHandler } from "../rpc/router";
+import { emitEvent } from "../rpc/protocol";
+
+// --- Profile variables ---
+//
+// A key-value store shared by every session of the same target. The host
+// seeds it at attach time and persists every change it hears about through
+// `vars/set`, so offsets found in one session are there in the next.
+
+let profile: string | null = null;
+const variables = new Map<string, unknown>();
+
+export function getVariable<T = unknown>(key: string): T | undefined {
+  return variables.get(key) as T | undefined;
+}
+
+/** Stores a value for this target's profile; `null` deletes the key. */
+export function setVariable(key: string, value: unknown, persist = true): void {
+  if (value === null || value === undefined) {
+    variables.delete(key);
+  } else {
+    variables.set(key, value);
+  }
+  if (persist) {
+    emitEvent("vars/set", { key, value: value ?? null });
+  }
+}
+
+registerHandler("setProfileVariables", (params: unknown) => {
+  const p = params as { profile: string; variables?: Record<string, unknown> };
+  profile = p.profile;
+  variables.clear();
+  for (const [key, value] of Object.entries(p.variables ?? {})) {
+    variables.set(key, value);
+  }
+  return { profile, count: variables.size };
+});
+
+registerHandler("getVariable", (params: unknown) => {
+  const { key } = params as { key: string };
+  return getVariable(key) ?? null;
+});
+
+registerHandler("setVariable", (params: unknown) => {
+  const { key, value, persist } = params as { key: string; value: unknown; persist?: boolean };
+  if (!key) throw new Error("Variable key is required");
+  setVariable(key, value, persist ?? true);
+  return { key };
+});
+
+registerHandler("listVariables", (_params: unknown) => {
+  return { profile, variables: Object.fromEntries(variables) };
+});
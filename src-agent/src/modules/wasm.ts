@@ -0,0 +1,614 @@
+import { registerHandler } from "../rpc/router";
+import { onLayoutChanged } from "./layout";
+import { installNativeHook, type NativeHookOptions } from "./native";
+
+// WebAssembly support for targets embedding a wasm engine. Wire bytes are
+// found by scanning for the module header; compiled code is located per
+// runtime: wasmtime keeps each compiled module as an in-memory ELF image
+// whose symbols name every function, V8 routes calls through a per-module
+// jump table at the start of its code space.
+
+const WASM_MAGIC = "00 61 73 6d 01 00 00 00";
+const ELF_MAGIC = "7f 45 4c 46 02 01";
+const MAX_RESULTS = 5000;
+const MAX_NAME_LENGTH = 1024;
+/** Consecutive direct jumps needed before a region is taken for a V8 jump table. */
+const MIN_JUMP_SLOTS = 8;
+
+// Known section ids by their required position; custom sections (0) may
+// appear anywhere.
+const SECTION_ORDER: Record<number, number> = {
+  1: 1,
+  2: 2,
+  3: 3,
+  4: 4,
+  5: 5,
+  13: 6,
+  6: 7,
+  7: 8,
+  8: 9,
+  9: 10,
+  12: 11,
+  10: 12,
+  11: 13,
+};
+
+interface WireModule {
+  address: NativePointer;
+  size: number;
+  importedFunctions: number;
+  declaredFunctions: number;
+  /** Absolute function index → name from the name section, exports or imports. */
+  names: Map<number, string>;
+  copies: number;
+}
+
+interface JumpTableLayout {
+  slotSize: number;
+  /** Slots never straddle a line; the tail of each line is padding. */
+  lineSize: number;
+}
+
+interface WasmFunction {
+  moduleIndex: number;
+  index: number;
+  name: string | null;
+  address: NativePointer;
+  /** V8 only: the jump table slot calls go through. */
+  slot: NativePointer | null;
+}
+
+interface CodeRegion {
+  id: string;
+  runtime: "wasmtime" | "v8";
+  base: NativePointer;
+  size: number;
+  functions: WasmFunction[];
+  /** V8 only: the wire bytes the jump table was matched with. */
+  module: WireModule | null;
+}
+
+let wireCache: WireModule[] | null = null;
+let regionCache: CodeRegion[] | null = null;
+
+onLayoutChanged(() => {
+  wireCache = null;
+  regionCache = null;
+});
+
+function isModuleMemory(address: NativePointer): boolean {
+  return Process.findModuleByAddress(address) !== null;
+}
+
+function scanRanges(protection: string, pattern: string): MemoryScanMatch[] {
+  const matches: MemoryScanMatch[] = [];
+  for (const range of Process.enumerateRanges({ protection, coalesce: true })) {
+    if (range.file || isModuleMemory(range.base)) continue;
+    try {
+      matches.push(...Memory.scanSync(range.base, range.size, pattern));
+    } catch {
+      // Ranges can be unmapped or guarded while we scan.
+    }
+  }
+  return matches;
+}
+
+// ── Wire bytes ──────────────────────────────────────────────────────────────
+
+class WireReader {
+  constructor(
+    private readonly base: NativePointer,
+    public offset: number,
+    private readonly limit: number,
+  ) {}
+
+  u8(): number {
+    if (this.offset >= this.limit) throw new Error("unexpected end of module");
+    return this.base.add(this.offset++).readU8();
+  }
+
+  uleb(): number {
+    let result = 0;
+    for (let shift = 0; shift < 35; shift += 7) {
+      const byte = this.u8();
+      result += (byte & 0x7f) * 2 ** shift;
+      if ((byte & 0x80) === 0) return result;
+    }
+    throw new Error("LEB128 value too long");
+  }
+
+  name(): string {
+    const length = this.uleb();
+    if (length > MAX_NAME_LENGTH || this.offset + length > this.limit) {
+      throw new Error("invalid name");
+    }
+    const value = length === 0 ? "" : this.base.add(this.offset).readUtf8String(length);
+    this.offset += length;
+    return value ?? "";
+  }
+
+  limits(): void {
+    const flags = this.u8();
+    this.uleb();
+    if (flags & 1) this.uleb();
+  }
+}
+
+/**
+ * Parses the module header at `address`. Sections are read until one is out
+ * of order or runs past `limit`, which is where the wire bytes end.
+ */
+function parseWireModule(address: NativePointer, limit: number): WireModule | null {
+  const module: WireModule = {
+    address,
+    size: 8,
+    importedFunctions: 0,
+    declaredFunctions: 0,
+    names: new Map(),
+    copies: 1,
+  };
+  const exportNames = new Map<number, string>();
+  const importNames: string[] = [];
+  let lastOrder = 0;
+  let sections = 0;
+  const reader = new WireReader(address, 8, limit);
+
+  while (reader.offset < limit) {
+    let end = limit;
+    try {
+      const id = reader.u8();
+      const order = SECTION_ORDER[id];
+      if (id !== 0 && (order === undefined || order <= lastOrder)) break;
+      const size = reader.uleb();
+      end = reader.offset + size;
+      if (end > limit) break;
+
+      const section = new WireReader(address, reader.offset, end);
+      if (id === 2) {
+        for (let count = section.uleb(); count > 0; count--) {
+          const name = `${section.name()}.${section.name()}`;
+          const kind = section.u8();
+          if (kind === 0) {
+            section.uleb();
+            importNames.push(name);
+          } else if (kind === 1) {
+            section.u8();
+            section.limits();
+          } else if (kind === 2) {
+            section.limits();
+          } else if (kind === 3) {
+            section.u8();
+            section.u8();
+          } else if (kind === 4) {
+            section.u8();
+            section.uleb();
+          } else {
+            throw new Error(`unknown import kind ${kind}`);
+          }
+        }
+      } else if (id === 3) {
+        module.declaredFunctions = section.uleb();
+      } else if (id === 7) {
+        for (let count = section.uleb(); count > 0; count--) {
+          const name = section.name();
+          const kind = section.u8();
+          const index = section.uleb();
+          if (kind === 0 && !exportNames.has(index)) exportNames.set(index, name);
+        }
+      } else if (id === 0 && section.name() === "name") {
+        readFunctionNames(section, end, module.names);
+      }
+
+      if (id !== 0) lastOrder = order;
+    } catch {
+      break;
+    }
+    reader.offset = end;
+    module.size = end;
+    sections++;
+  }
+
+  module.importedFunctions = importNames.length;
+  if (sections === 0 || module.importedFunctions + module.declaredFunctions === 0) {
+    return null;
+  }
+  importNames.forEach((name, index) => {
+    if (!module.names.has(index)) module.names.set(index, name);
+  });
+  for (const [index, name] of exportNames) {
+    if (!module.names.has(index)) module.names.set(index, name);
+  }
+  return module;
+}
+
+function readFunctionNames(section: WireReader, end: number, names: Map<number, string>): void {
+  while (section.offset < end) {
+    const id = section.u8();
+    const size = section.uleb();
+    const next = section.offset + size;
+    if (id === 1) {
+      for (let count = section.uleb(); count > 0; count--) {
+        const index = section.uleb();
+        names.set(index, section.name());
+      }
+    }
+    section.offset = next;
+  }
+}
+
+function wireModules(refresh: boolean): WireModule[] {
+  if (wireCache && !refresh) return wireCache;
+
+  const modules: WireModule[] = [];
+  for (const match of scanRanges("r--", WASM_MAGIC)) {
+    const range = Process.findRangeByAddress(match.address);
+    if (!range) continue;
+    const limit = range.base.add(range.size).sub(match.address).toInt32();
+    const module = parseWireModule(match.address, limit);
+    if (!module) continue;
+
+    // Engines and the embedder often hold several copies of the same bytes.
+    const copy = modules.find(
+      (other) =>
+        other.size === module.size &&
+        other.importedFunctions === module.importedFunctions &&
+        other.declaredFunctions === module.declaredFunctions,
+    );
+    if (copy) {
+      copy.copies++;
+    } else {
+      modules.push(module);
+    }
+  }
+
+  wireCache = modules;
+  return modules;
+}
+
+// ── wasmtime ────────────────────────────────────────────────────────────────
+
+const WASMTIME_SYMBOL = /^wasm\[(\d+)\]::function\[(\d+)\](?:::(.+))?$/;
+const LEGACY_WASMTIME_SYMBOL = /^_?wasm_function_(\d+)$/;
+
+interface ElfSection {
+  name: string;
+  type: number;
+  offset: number;
+  size: number;
+  link: number;
+}
+
+/**
+ * Reads the symbols of a wasmtime code image. The image is the compiled ELF
+ * object copied into memory as-is, so a symbol lives at
+ * `image + .text file offset + st_value`.
+ */
+function parseWasmtimeImage(image: NativePointer): CodeRegion | null {
+  const shoff = image.add(0x28).readU64().toNumber();
+  const shentsize = image.add(0x3a).readU16();
+  const shnum = image.add(0x3c).readU16();
+  const shstrndx = image.add(0x3e).readU16();
+  if (shentsize !== 64 || shnum === 0 || shnum > 256 || shstrndx >= shnum) return null;
+
+  const headers: Omit<ElfSection, "name">[] = [];
+  const nameOffsets: number[] = [];
+  for (let index = 0; index < shnum; index++) {
+    const header = image.add(shoff + index * shentsize);
+    nameOffsets.push(header.readU32());
+    headers.push({
+      type: header.add(4).readU32(),
+      offset: header.add(0x18).readU64().toNumber(),
+      size: header.add(0x20).readU64().toNumber(),
+      link: header.add(0x28).readU32(),
+    });
+  }
+  const names = image.add(headers[shstrndx].offset);
+  const sections: ElfSection[] = headers.map((header, index) => ({
+    ...header,
+    name: names.add(nameOffsets[index]).readCString() ?? "",
+  }));
+
+  const text = sections.find((section) => section.name === ".text");
+  const symtab = sections.find((section) => section.type === 2);
+  if (!text || !symtab || symtab.link >= sections.length) return null;
+  const textBase = image.add(text.offset);
+  if (!Process.findRangeByAddress(textBase)?.protection.includes("x")) return null;
+
+  const strtab = image.add(sections[symtab.link].offset);
+  const functions: WasmFunction[] = [];
+  for (let offset = 0; offset + 24 <= symtab.size; offset += 24) {
+    const symbol = image.add(symtab.offset + offset);
+    const name = strtab.add(symbol.readU32()).readCString() ?? "";
+    const value = symbol.add(8).readU64().toNumber();
+
+    const match = WASMTIME_SYMBOL.exec(name);
+    const legacy = match ? null : LEGACY_WASMTIME_SYMBOL.exec(name);
+    if (!match && !legacy) continue;
+    functions.push({
+      moduleIndex: match ? Number(match[1]) : 0,
+      index: Number(match ? match[2] : legacy![1]),
+      name: match?.[3] ?? null,
+      address: textBase.add(value),
+      slot: null,
+    });
+  }
+  if (functions.length === 0) return null;
+
+  return {
+    id: image.toString(),
+    runtime: "wasmtime",
+    base: textBase,
+    size: text.size,
+    functions: functions.sort((a, b) => a.moduleIndex - b.moduleIndex || a.index - b.index),
+    module: null,
+  };
+}
+
+// ── V8 ──────────────────────────────────────────────────────────────────────
+
+function jumpTableLayouts(): JumpTableLayout[] {
+  switch (Process.arch) {
+    case "x64":
+      return [
+        { slotSize: 5, lineSize: 64 },
+        { slotSize: 8, lineSize: 8 },
+      ];
+    case "ia32":
+      return [{ slotSize: 5, lineSize: 5 }];
+    case "arm64":
+      return [
+        { slotSize: 4, lineSize: 4 },
+        { slotSize: 8, lineSize: 8 },
+      ];
+    default:
+      return [
+        { slotSize: 8, lineSize: 8 },
+        { slotSize: 16, lineSize: 16 },
+      ];
+  }
+}
+
+function slotAddress(base: NativePointer, layout: JumpTableLayout, slot: number): NativePointer {
+  const perLine = Math.floor(layout.lineSize / layout.slotSize);
+  const line = Math.floor(slot / perLine);
+  return base.add(line * layout.lineSize + (slot % perLine) * layout.slotSize);
+}
+
+/** Target of an unconditional direct jump at `address`, skipping a BTI landing pad. */
+function directJumpTarget(address: NativePointer, end: NativePointer): NativePointer | null {
+  let cursor = address;
+  for (let step = 0; step < 2 && cursor.compare(end) < 0; step++) {
+    let instruction: Instruction;
+    try {
+      instruction = Instruction.parse(cursor);
+    } catch {
+      return null;
+    }
+    if (instruction.mnemonic === "bti") {
+      cursor = instruction.next;
+      continue;
+    }
+    if (instruction.mnemonic !== "jmp" && instruction.mnemonic !== "b") return null;
+    const [operand] = (instruction as unknown as { operands: { type: string; value: unknown }[] })
+      .operands;
+    return operand?.type === "imm" ? ptr(String(operand.value)) : null;
+  }
+  return null;
+}
+
+function countJumpSlots(base: NativePointer, end: NativePointer, layout: JumpTableLayout) {
+  let count = 0;
+  for (;;) {
+    const slot = slotAddress(base, layout, count);
+    if (slot.add(layout.slotSize).compare(end) > 0) break;
+    if (!directJumpTarget(slot, slot.add(layout.slotSize))) break;
+    count++;
+  }
+  return count;
+}
+
+/**
+ * Best effort: V8's code space starts with a jump table holding one direct
+ * jump per declared function, in declaration order. Lazily compiled
+ * functions jump to a compile stub until first called, and tier-up patches
+ * the slot, so resolved targets are only valid until then.
+ */
+function findJumpTables(modules: WireModule[], layouts: JumpTableLayout[]): CodeRegion[] {
+  const regions: CodeRegion[] = [];
+  for (const range of Process.enumerateRanges({ protection: "r-x", coalesce: false })) {
+    if (range.file || isModuleMemory(range.base)) continue;
+    const end = range.base.add(range.size);
+
+    for (const layout of layouts) {
+      const count = countJumpSlots(range.base, end, layout);
+      if (count < MIN_JUMP_SLOTS) continue;
+
+      const module =
+        modules.find((candidate) => candidate.declaredFunctions === count) ??
+        (modules.length === 1 ? modules[0] : null);
+      const imported = module?.importedFunctions ?? 0;
+      const functions: WasmFunction[] = [];
+      for (let slot = 0; slot < count; slot++) {
+        const address = slotAddress(range.base, layout, slot);
+        const index = imported + slot;
+        functions.push({
+          moduleIndex: 0,
+          index,
+          name: module?.names.get(index) ?? null,
+          address: directJumpTarget(address, end)!,
+          slot: address,
+        });
+      }
+
+      regions.push({
+        id: range.base.toString(),
+        runtime: "v8",
+        base: range.base,
+        size: range.size,
+        functions,
+        module,
+      });
+      break;
+    }
+  }
+  return regions;
+}
+
+// ── Regions ─────────────────────────────────────────────────────────────────
+
+function detectRuntimes(): string[] {
+  const runtimes = new Set<string>();
+  if (
+    Module.findGlobalExportByName("wasmtime_module_new") ||
+    Process.enumerateModules().some((module) => /wasmtime/i.test(module.name))
+  ) {
+    runtimes.add("wasmtime");
+  }
+  if (
+    Process.enumerateModules().some((module) =>
+      /v8|node|chrome|electron|monochrome|webview/i.test(module.name),
+    )
+  ) {
+    runtimes.add("v8");
+  }
+  return [...runtimes];
+}
+
+function codeRegions(refresh: boolean, layout?: JumpTableLayout): CodeRegion[] {
+  if (regionCache && !refresh && !layout) return regionCache;
+
+  const regions: CodeRegion[] = [];
+  for (const match of scanRanges("r--", ELF_MAGIC)) {
+    if (!match.address.and(Process.pageSize - 1).isNull()) continue;
+    try {
+      const region = parseWasmtimeImage(match.address);
+      if (region) regions.push(region);
+    } catch {
+      // Not a complete ELF image.
+    }
+  }
+  regions.push(...findJumpTables(wireModules(refresh), layout ? [layout] : jumpTableLayouts()));
+
+  if (!layout) regionCache = regions;
+  return regions;
+}
+
+interface RegionParams {
+  region?: string;
+  refresh?: boolean;
+  slotSize?: number;
+  lineSize?: number;
+}
+
+function requireRegion({ region, refresh = false, slotSize, lineSize }: RegionParams): CodeRegion {
+  const layout = slotSize ? { slotSize, lineSize: lineSize ?? slotSize } : undefined;
+  const regions = codeRegions(refresh, layout);
+  if (region) {
+    const found = regions.find((candidate) => candidate.id === region);
+    if (!found) throw new Error(`Wasm code region not found: ${region}`);
+    return found;
+  }
+  if (regions.length === 1) return regions[0];
+  if (regions.length === 0) throw new Error("No compiled wasm code found");
+  throw new Error(
+    `Multiple wasm code regions found; pass one of: ${regions.map((r) => r.id).join(", ")}`,
+  );
+}
+
+function requireFunction(
+  params: RegionParams & { index: number; moduleIndex?: number },
+): WasmFunction {
+  const { index, moduleIndex = 0 } = params;
+  const region = requireRegion(params);
+  const fn = region.functions.find(
+    (candidate) => candidate.index === index && candidate.moduleIndex === moduleIndex,
+  );
+  if (!fn) {
+    const imported = region.module?.importedFunctions ?? 0;
+    throw new Error(
+      index < imported
+        ? `Wasm function ${index} is an import and has no compiled code`
+        : `Wasm function ${index} not found in ${region.id}`,
+    );
+  }
+  return fn;
+}
+
+function toFunctionInfo(fn: WasmFunction) {
+  return {
+    moduleIndex: fn.moduleIndex,
+    index: fn.index,
+    name: fn.name,
+    address: fn.address.toString(),
+    slot: fn.slot?.toString() ?? null,
+  };
+}
+
+// ── RPC Handlers ────────────────────────────────────────────────────────────
+
+registerHandler("getWasmInfo", (params: unknown) => {
+  const { refresh = false } = (params as { refresh?: boolean }) ?? {};
+  return {
+    runtimes: detectRuntimes(),
+    modules: wireModules(refresh).length,
+    codeRegions: codeRegions(refresh).length,
+  };
+});
+
+registerHandler("enumerateWasmModules", (params: unknown) => {
+  const { refresh = false } = (params as { refresh?: boolean }) ?? {};
+  return wireModules(refresh).map((module) => ({
+    address: module.address.toString(),
+    size: module.size,
+    importedFunctions: module.importedFunctions,
+    declaredFunctions: module.declaredFunctions,
+    namedFunctions: module.names.size,
+    copies: module.copies,
+  }));
+});
+
+registerHandler("enumerateWasmCodeRegions", (params: unknown) => {
+  const { refresh = false, slotSize, lineSize } = (params as RegionParams) ?? {};
+  const layout = slotSize ? { slotSize, lineSize: lineSize ?? slotSize } : undefined;
+  return codeRegions(refresh, layout).map((region) => ({
+    id: region.id,
+    runtime: region.runtime,
+    base: region.base.toString(),
+    size: region.size,
+    functionCount: region.functions.length,
+    module: region.module?.address.toString() ?? null,
+  }));
+});
+
+registerHandler("enumerateWasmFunctions", (params: unknown) => {
+  const {
+    filter,
+    limit = MAX_RESULTS,
+    ...regionParams
+  } = (params as RegionParams & { filter?: string; limit?: number }) ?? {};
+  const region = requireRegion(regionParams);
+  const needle = filter?.toLowerCase();
+  const max = Math.min(limit, MAX_RESULTS);
+
+  const result = [];
+  for (const fn of region.functions) {
+    if (result.length >= max) break;
+    if (needle && !(fn.name ?? `function[${fn.index}]`).toLowerCase().includes(needle)) continue;
+    result.push(toFunctionInfo(fn));
+  }
+  return result;
+});
+
+registerHandler("resolveWasmFunction", (params: unknown) => {
+  return toFunctionInfo(
+    requireFunction(params as RegionParams & { index: number; moduleIndex?: number }),
+  );
+});
+
+registerHandler("hookWasmFunction", (params: unknown) => {
+  const { captureArgs, captureRetval, captureBacktrace, ...target } = params as RegionParams &
+    NativeHookOptions & { index: number; moduleIndex?: number };
+  const fn = requireFunction(target);
+  const label = `wasm[${fn.moduleIndex}]::function[${fn.index}]${fn.name ? `::${fn.name}` : ""}`;
+  return installNativeHook(label, fn.address, { captureArgs, captureRetval, captureBacktrace });
+});
@@ -0,0 +1,441 @@
+import { registerHandler } from "../rpc/router";
+import { emitEvent } from "../rpc/protocol";
+import { registerCleanup } from "./cleanup";
+import { isMemoryMonitorActive } from "./monitor";
+
+// --- Data watchpoints ---
+//
+// A watch answers "who touches this value?". Small read/write watches and
+// execute watches use the CPU's debug registers, armed on every thread
+// (and on threads started later). A hit is reported and that thread's
+// watch is re-armed REARM_DELAY_MS later, which also caps how fast a tight
+// loop can report. Ranges the hardware cannot cover fall back to
+// MemoryAccessMonitor, which guards whole pages and is re-armed after every
+// access.
+
+type WatchKind = "read" | "write" | "execute";
+
+interface Watch {
+  id: string;
+  address: NativePointer;
+  size: number;
+  on: WatchKind;
+  mode: "hardware" | "page";
+  /** Debug register index, for hardware watches. */
+  slot: number;
+  hits: number;
+}
+
+const MAX_HARDWARE_SIZE = 8;
+const HARDWARE_SLOTS = 4;
+const REARM_DELAY_MS = 10;
+const BACKTRACE_DEPTH = 16;
+
+const watches = new Map<string, Watch>();
+let nextId = 0;
+let exceptionHandlerInstalled = false;
+let threadObserver: { detach(): void } | null = null;
+let pageMonitorEnabled = false;
+let rearmScheduled = false;
+
+function toWatchInfo(watch: Watch) {
+  return {
+    id: watch.id,
+    address: watch.address.toString(),
+    size: watch.size,
+    on: watch.on,
+    mode: watch.mode,
+    hits: watch.hits,
+  };
+}
+
+function frame(address: NativePointer) {
+  const sym = DebugSymbol.fromAddress(address);
+  return {
+    address: address.toString(),
+    moduleName: sym.moduleName,
+    symbolName: sym.name ?? null,
+    fileName: sym.fileName,
+    lineNumber: sym.lineNumber,
+  };
+}
+
+function describeInstruction(address: NativePointer): string | null {
+  try {
+    const insn = Instruction.parse(address);
+    return insn.opStr ? `${insn.mnemonic} ${insn.opStr}` : insn.mnemonic;
+  } catch {
+    return null;
+  }
+}
+
+function emitHit(
+  watch: Watch,
+  operation: WatchKind,
+  address: NativePointer,
+  from: NativePointer,
+  backtrace: NativePointer[],
+): void {
+  watch.hits++;
+  emitEvent("carf://memory/watch-hit", {
+    watchId: watch.id,
+    operation,
+    address: address.toString(),
+    from: from.toString(),
+    instruction: describeInstruction(from),
+    threadId: Process.getCurrentThreadId(),
+    backtrace: backtrace.map(frame),
+    timestamp: Date.now(),
+  });
+}
+
+// --- Hardware watches ---
+
+function hardwareWatches(): Watch[] {
+  return [...watches.values()].filter((watch) => watch.mode === "hardware");
+}
+
+// Read watches use "rw": x86 has no read-only watchpoints, so they report
+// writes too on every architecture.
+function arm(thread: ThreadDetails, watch: Watch): void {
+  if (watch.on === "execute") {
+    thread.setHardwareBreakpoint(watch.slot, watch.address);
+  } else {
+    const conditions = watch.on === "write" ? "w" : "rw";
+    thread.setHardwareWatchpoint(watch.slot, watch.address, watch.size, conditions);
+  }
+}
+
+function disarm(thread: ThreadDetails, watch: Watch): void {
+  if (watch.on === "execute") {
+    thread.unsetHardwareBreakpoint(watch.slot);
+  } else {
+    thread.unsetHardwareWatchpoint(watch.slot);
+  }
+}
+
+function findThread(threadId: number): ThreadDetails | undefined {
+  return Process.enumerateThreads().find((thread) => thread.id === threadId);
+}
+
+function freeSlot(on: WatchKind): number {
+  const breakpoint = on === "execute";
+  const used = new Set(
+    hardwareWatches()
+      .filter((watch) => (watch.on === "execute") === breakpoint)
+      .map((watch) => watch.slot),
+  );
+  for (let slot = 0; slot < HARDWARE_SLOTS; slot++) {
+    if (!used.has(slot)) return slot;
+  }
+  return -1;
+}
+
+// x86 data watchpoints trap once the access is done, with pc on the next
+// instruction, and every debug-register trap arrives as "single-step". On
+// ARM the trap comes before the access, with pc on the accessing
+// instruction, as a "breakpoint".
+const TRAPS_AFTER_ACCESS = Process.arch === "ia32" || Process.arch === "x64";
+const MAX_INSTRUCTION_LENGTH = 15;
+
+interface MemoryOperand {
+  type: string;
+  value: { base?: string; index?: string; scale?: number; disp?: number };
+}
+
+function memoryOperand(at: NativePointer): MemoryOperand | null {
+  try {
+    const insn = Instruction.parse(at) as unknown as { operands: MemoryOperand[] };
+    return insn.operands.find((op) => op.type === "mem") ?? null;
+  } catch {
+    return null;
+  }
+}
+
+// The instruction that made the access. On x86 that is the one ending at
+// `pc`: decode backwards and prefer a candidate that touches memory.
+function accessingInstruction(pc: NativePointer): NativePointer | null {
+  if (!TRAPS_AFTER_ACCESS) return pc;
+  let fallback: NativePointer | null = null;
+  for (let length = 1; length <= MAX_INSTRUCTION_LENGTH; length++) {
+    const start = pc.sub(length);
+    try {
+      if (!Instruction.parse(start).next.equals(pc)) continue;
+    } catch {
+      continue;
+    }
+    if (memoryOperand(start)) return start;
+    if (!fallback) fallback = start;
+  }
+  return fallback;
+}
+
+// Best effort: the address the instruction at `at` touches, from its memory
+// operand and the registers in `context`. On x86 the registers are the ones
+// after the access, so an instruction that updates its own base is missed.
+function accessedAddress(at: NativePointer, context: CpuContext): NativePointer | null {
+  const operand = memoryOperand(at);
+  if (!operand) return null;
+  try {
+    const regs = context as unknown as Record<string, NativePointer | undefined>;
+    const { base, index, scale, disp } = operand.value;
+    let address = base && regs[base] ? ptr(regs[base]!.toString()) : ptr(0);
+    if (index && regs[index]) {
+      for (let i = 0; i < (scale ?? 1); i++) address = address.add(regs[index]!);
+    }
+    return address.add(disp ?? 0);
+  } catch {
+    return null;
+  }
+}
+
+function isSoftwareBreakpoint(at: NativePointer): boolean {
+  try {
+    const mnemonic = Instruction.parse(at).mnemonic;
+    return mnemonic === "brk" || mnemonic === "bkpt" || mnemonic === "int3";
+  } catch {
+    return false;
+  }
+}
+
+interface Hit {
+  watch: Watch;
+  address: NativePointer;
+  /** The accessing instruction. */
+  from: NativePointer;
+}
+
+// Which armed watch fired, or null when the trap is not ours. The exception
+// only carries the pc, so execute watches match on it and data watches on
+// the accessing instruction's memory operand. A trap that can only come from
+// a debug register (any x86 single-step while data watches are armed) is
+// ours even when the operand cannot be worked out; otherwise an operand
+// outside every watch, or a software breakpoint, means some other trap.
+function hitWatch(details: ExceptionDetails): Hit | null {
+  const { pc } = details.context;
+  const candidates = hardwareWatches();
+  const execute = candidates.find((watch) => watch.on === "execute" && watch.address.equals(pc));
+  if (execute) return { watch: execute, address: pc, from: pc };
+
+  const data = candidates.filter((watch) => watch.on !== "execute");
+  if (data.length === 0) return null;
+  const from = accessingInstruction(pc);
+  const address = from ? accessedAddress(from, details.context) : null;
+  const watch = address
+    ? data.find(
+        (candidate) =>
+          address.compare(candidate.address) >= 0 &&
+          address.compare(candidate.address.add(candidate.size)) < 0,
+      )
+    : undefined;
+  if (watch && address) return { watch, address, from: from ?? pc };
+
+  const debugRegisterTrap = TRAPS_AFTER_ACCESS
+    ? details.type === "single-step"
+    : address === null && !isSoftwareBreakpoint(pc);
+  if (!debugRegisterTrap) return null;
+  return { watch: data[0], address: data[0].address, from: from ?? pc };
+}
+
+function onException(details: ExceptionDetails): boolean {
+  if (details.type !== "breakpoint" && details.type !== "single-step") return false;
+  const hit = hitWatch(details);
+  if (!hit) return false;
+
+  const { watch, address, from } = hit;
+  let backtrace: NativePointer[] = [];
+  try {
+    backtrace = Thread.backtrace(details.context, Backtracer.ACCURATE).slice(0, BACKTRACE_DEPTH);
+  } catch {
+    backtrace = [from];
+  }
+  emitHit(watch, watch.on, address, from, backtrace);
+
+  // On ARM the faulting instruction has to run before the watch can fire
+  // again; on x86 this keeps the same rate limit.
+  const threadId = Process.getCurrentThreadId();
+  const thread = findThread(threadId);
+  if (thread) {
+    disarm(thread, watch);
+    setTimeout(() => {
+      const current = findThread(threadId);
+      if (current && watches.get(watch.id) === watch) arm(current, watch);
+    }, REARM_DELAY_MS);
+  }
+  return true;
+}
+
+function startHardware(watch: Watch): void {
+  const threads = Process.enumerateThreads();
+  const armed: ThreadDetails[] = [];
+  try {
+    for (const thread of threads) {
+      arm(thread, watch);
+      armed.push(thread);
+    }
+  } catch (e) {
+    for (const thread of armed) {
+      try {
+        disarm(thread, watch);
+      } catch {
+        // The thread may have exited.
+      }
+    }
+    throw e;
+  }
+
+  if (!exceptionHandlerInstalled) {
+    Process.setExceptionHandler(onException);
+    exceptionHandlerInstalled = true;
+  }
+  if (!threadObserver && typeof Process.attachThreadObserver === "function") {
+    threadObserver = Process.attachThreadObserver({
+      onAdded(thread) {
+        for (const active of hardwareWatches()) {
+          try {
+            arm(thread, active);
+          } catch {
+            // Keep watching the threads that could be armed.
+          }
+        }
+      },
+    });
+  }
+}
+
+function stopHardware(watch: Watch): void {
+  for (const thread of Process.enumerateThreads()) {
+    try {
+      disarm(thread, watch);
+    } catch {
+      // The thread may have exited.
+    }
+  }
+  if (hardwareWatches().length === 0 && threadObserver) {
+    threadObserver.detach();
+    threadObserver = null;
+  }
+}
+
+// --- Page watches ---
+
+function pageWatches(): Watch[] {
+  return [...watches.values()].filter((watch) => watch.mode === "page");
+}
+
+export function hasPageWatches(): boolean {
+  return pageWatches().length > 0;
+}
+
+function enablePageMonitor(): void {
+  rearmScheduled = false;
+  const guarded = pageWatches();
+  const ranges = guarded.map((watch) => ({ base: watch.address, size: watch.size }));
+  if (pageMonitorEnabled) {
+    MemoryAccessMonitor.disable();
+    pageMonitorEnabled = false;
+  }
+  if (ranges.length === 0) return;
+
+  MemoryAccessMonitor.enable(ranges, {
+    onAccess(details) {
+      const watch = guarded[details.rangeIndex];
+      const inRange =
+        watch &&
+        details.address.compare(watch.address) >= 0 &&
+        details.address.compare(watch.address.add(watch.size)) < 0;
+      const wanted =
+        watch &&
+        (watch.on === details.operation || (watch.on === "read" && details.operation === "write"));
+      if (inRange && wanted) {
+        emitHit(watch, details.operation, details.address, details.from, [details.from]);
+      }
+      // Each page only reports its first access; guard them all again.
+      if (!rearmScheduled) {
+        rearmScheduled = true;
+        setTimeout(enablePageMonitor, 0);
+      }
+    },
+  });
+  pageMonitorEnabled = true;
+}
+
+// --- Handlers ---
+
+registerHandler("startMemoryWatch", (params: unknown) => {
+  const { address, size, on } = params as { address: string; size: number; on: WatchKind };
+  if (on !== "read" && on !== "write" && on !== "execute") {
+    throw new Error(`Invalid watch kind: ${on}`);
+  }
+  if (!Number.isInteger(size) || size <= 0) {
+    throw new Error(`Invalid watch size: ${size}`);
+  }
+
+  const watch: Watch = {
+    id: `watch_${nextId++}`,
+    address: ptr(address),
+    size,
+    on,
+    mode: "hardware",
+    slot: -1,
+    hits: 0,
+  };
+
+  const slot = size <= MAX_HARDWARE_SIZE ? freeSlot(on) : -1;
+  let hardwareError: unknown = null;
+  if (slot >= 0) {
+    watch.slot = slot;
+    try {
+      startHardware(watch);
+      watches.set(watch.id, watch);
+      return toWatchInfo(watch);
+    } catch (e) {
+      hardwareError = e;
+    }
+  }
+
+  if (isMemoryMonitorActive()) {
+    const reason = hardwareError instanceof Error ? `${hardwareError.message}; ` : "";
+    throw new Error(
+      `${reason}the memory monitor is using MemoryAccessMonitor, stop it to watch this range`,
+    );
+  }
+  watch.mode = "page";
+  watch.slot = -1;
+  watches.set(watch.id, watch);
+  try {
+    enablePageMonitor();
+  } catch (e) {
+    watches.delete(watch.id);
+    enablePageMonitor();
+    throw e;
+  }
+  return toWatchInfo(watch);
+});
+
+function removeWatch(watch: Watch): void {
+  watches.delete(watch.id);
+  if (watch.mode === "hardware") {
+    stopHardware(watch);
+  } else {
+    enablePageMonitor();
+  }
+}
+
+registerHandler("stopMemoryWatch", (params: unknown) => {
+  const { id } = params as { id: string };
+  const watch = watches.get(id);
+  if (!watch) throw new Error(`No memory watch ${id}`);
+  removeWatch(watch);
+  return toWatchInfo(watch);
+});
+
+registerHandler("listMemoryWatches", (_params: unknown) => {
+  return [...watches.values()].map(toWatchInfo);
+});
+
+registerCleanup("memoryWatches", () => {
+  const all = [...watches.values()];
+  for (const watch of all) removeWatch(watch);
+  return all.length;
+});
@@ -0,0 +1,29 @@
+// Raw bytes exchanged with the Backend beside an RPC, so chunked dumps and
+// transfers don't pay for hex inside JSON. The Backend tags such calls with a
+// `binaryId` and pairs it with a `carf:binary` message carrying the bytes.
+
+const received = new Map<string, ArrayBuffer>();
+
+function listen(): void {
+  recv("carf:binary", (message: { id: string }, data: ArrayBuffer | null) => {
+    if (data) received.set(message.id, data);
+    listen();
+  });
+}
+
+listen();
+
+// Attaches `data` to the reply of the RPC that passed `binaryId`.
+function sendBinary(binaryId: string, data: ArrayBuffer): void {
+  send({ type: "carf:binary", id: binaryId }, data);
+}
+
+// The bytes the Backend posted just ahead of the RPC that passed `binaryId`.
+function takeBinary(binaryId: string): Uint8Array {
+  const data = received.get(binaryId);
+  if (!data) throw new Error(`No data received for ${binaryId}`);
+  received.delete(binaryId);
+  return new Uint8Array(data);
+}
+
+export { sendBinary, takeBinary };
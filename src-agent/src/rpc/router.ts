@@ -10,6 +10,10 @@ function registerHandler(method: string, handler: RpcHandler): void {
 	handlers.set(method, handler);
 }
 
+function getHandler(method: string): RpcHandler | undefined {
+	return handlers.get(method);
+}
+
 function createRpcExports(): Record<string, (...args: unknown[]) => unknown> {
 	const exports: Record<string, (...args: unknown[]) => unknown> = {};
 
@@ -31,4 +35,4 @@ function createRpcExports(): Record<string, (...args: unknown[]) => unknown> {
 	return exports;
 }
 
-export { registerHandler, createRpcExports };
+export { registerHandler, getHandler, createRpcExports };
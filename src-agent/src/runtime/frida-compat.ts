@@ -1,4 +1,4 @@
-function findLoadedModule(name: string): Module | null {
+export function findLoadedModule(name: string): Module | null {
 	const directMatch = Process.findModuleByName(name);
 	if (directMatch) {
 		return directMatch;
@@ -0,0 +1,111 @@
+//! Hot-path benchmarks. Run with `cargo bench --bench hot_paths`; compare
+//! against a saved baseline with `-- --save-baseline main` /
+//! `-- --baseline main` to catch regressions.
+
+use std::time::Duration;
+
+use carf_lib::bench::{
+    batch_script_events, format_values, query_message_history, record_message_history,
+    record_trace_batches, seconds_format, synthetic_event_batches, synthetic_script_events,
+    synthetic_values, EventBatcher, MessageHistoryStore, TraceStore,
+};
+use criterion::{
+    black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput,
+};
+
+const EVENTS: usize = 50_000;
+
+fn trace_store(c: &mut Criterion) {
+    let mut group = c.benchmark_group("trace_store");
+    group.throughput(Throughput::Elements(EVENTS as u64));
+    for batch in [50, 500] {
+        let batches = synthetic_event_batches("bench", EVENTS, batch);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(batch),
+            &batches,
+            |b, batches| {
+                b.iter(|| {
+                    let mut store = TraceStore::default();
+                    record_trace_batches(&mut store, black_box(batches));
+                    store
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+fn event_batching(c: &mut Criterion) {
+    let events = synthetic_script_events("bench", EVENTS);
+
+    let mut group = c.benchmark_group("event_batching");
+    group.throughput(Throughput::Elements(EVENTS as u64));
+    // At the per-name limit, and ten times over it so the drop path runs.
+    for interval_us in [200, 20] {
+        group.bench_with_input(
+            BenchmarkId::new("interval_us", interval_us),
+            &interval_us,
+            |b, &interval_us| {
+                b.iter_batched(
+                    || events.clone(),
+                    |events| {
+                        let mut batcher = EventBatcher::default();
+                        batch_script_events(
+                            &mut batcher,
+                            events,
+                            Duration::from_micros(interval_us),
+                        )
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+    group.finish();
+}
+
+fn message_history(c: &mut Criterion) {
+    let events = synthetic_script_events("bench", EVENTS);
+
+    let mut group = c.benchmark_group("message_history");
+    group.throughput(Throughput::Elements(EVENTS as u64));
+    group.bench_function("record", |b| {
+        b.iter(|| {
+            let mut store = MessageHistoryStore::default();
+            record_message_history(&mut store, black_box(&events));
+            store
+        })
+    });
+    group.finish();
+
+    let mut store = MessageHistoryStore::default();
+    record_message_history(&mut store, &events);
+    let mut group = c.benchmark_group("message_query");
+    for text in ["hook-3", "no such text"] {
+        group.bench_with_input(BenchmarkId::from_parameter(text), &text, |b, text| {
+            b.iter(|| query_message_history(&store, black_box(text)))
+        });
+    }
+    group.finish();
+}
+
+fn value_format(c: &mut Criterion) {
+    let values = synthetic_values(EVENTS);
+    let format = seconds_format();
+
+    let mut group = c.benchmark_group("value_format");
+    group.throughput(Throughput::Elements(EVENTS as u64));
+    group.bench_function("seconds", |b| {
+        b.iter(|| format_values(black_box(&values), &format))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    trace_store,
+    event_batching,
+    message_history,
+    value_format
+);
+criterion_main!(benches);
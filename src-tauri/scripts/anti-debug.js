@@ -0,0 +1,77 @@
+// CARF bundled script: anti-debug bypass.
+//
+// Neutralizes the usual "am I being debugged?" checks so the target keeps
+// running normally while instrumented.
+
+function findExport(symbolName) {
+  return Module.findGlobalExportByName(symbolName);
+}
+
+// ptrace(PT_DENY_ATTACH) on Apple platforms, ptrace(PTRACE_TRACEME) on Linux.
+const ptrace = findExport("ptrace");
+if (ptrace !== null) {
+  const PT_DENY_ATTACH = 31;
+  const PTRACE_TRACEME = 0;
+  Interceptor.attach(ptrace, {
+    onEnter(args) {
+      const request = args[0].toInt32();
+      this.blocked =
+        (Process.platform === "darwin" && request === PT_DENY_ATTACH) ||
+        (Process.platform === "linux" && request === PTRACE_TRACEME);
+    },
+    onLeave(retval) {
+      if (this.blocked) {
+        retval.replace(ptr(0));
+      }
+    },
+  });
+  console.log("[anti-debug] ptrace patched");
+}
+
+// sysctl(KERN_PROC) on Apple platforms: clear P_TRACED from kp_proc.p_flag.
+const sysctl = Process.platform === "darwin" ? findExport("sysctl") : null;
+if (sysctl !== null) {
+  const CTL_KERN = 1;
+  const KERN_PROC = 14;
+  const P_TRACED = 0x800;
+  const P_FLAG_OFFSET = 32;
+  Interceptor.attach(sysctl, {
+    onEnter(args) {
+      const mib = args[0];
+      this.info =
+        mib.readInt() === CTL_KERN && mib.add(4).readInt() === KERN_PROC ? args[2] : null;
+    },
+    onLeave() {
+      if (this.info === null || this.info.isNull()) {
+        return;
+      }
+      const flags = this.info.add(P_FLAG_OFFSET);
+      flags.writeInt(flags.readInt() & ~P_TRACED);
+    },
+  });
+  console.log("[anti-debug] sysctl patched");
+}
+
+// Windows debugger checks.
+if (Process.platform === "windows") {
+  const isDebuggerPresent = findExport("IsDebuggerPresent");
+  if (isDebuggerPresent !== null) {
+    Interceptor.replace(isDebuggerPresent, new NativeCallback(() => 0, "int", []));
+    console.log("[anti-debug] IsDebuggerPresent patched");
+  }
+
+  const checkRemote = findExport("CheckRemoteDebuggerPresent");
+  if (checkRemote !== null) {
+    Interceptor.attach(checkRemote, {
+      onEnter(args) {
+        this.result = args[1];
+      },
+      onLeave() {
+        if (!this.result.isNull()) {
+          this.result.writeInt(0);
+        }
+      },
+    });
+    console.log("[anti-debug] CheckRemoteDebuggerPresent patched");
+  }
+}
@@ -0,0 +1,75 @@
+// CARF bundled script: SSL pinning bypass.
+//
+// Disables certificate validation in the common TLS stacks so traffic can be
+// intercepted with a proxy. Native hooks work everywhere; the Java hooks are
+// applied only when the Java bridge is available in this runtime.
+
+function findExport(moduleName, symbolName) {
+  if (moduleName !== null) {
+    const module = Process.findModuleByName(moduleName);
+    return module === null ? null : module.findExportByName(symbolName);
+  }
+  return Module.findGlobalExportByName(symbolName);
+}
+
+function replaceReturn(moduleName, symbolName, retType, argTypes, value) {
+  const address = findExport(moduleName, symbolName);
+  if (address === null) {
+    return false;
+  }
+  Interceptor.replace(
+    address,
+    new NativeCallback(() => value, retType, argTypes),
+  );
+  console.log(`[ssl-unpinning] ${symbolName} patched`);
+  return true;
+}
+
+// Apple: SecTrustEvaluateWithError(trust, error) -> bool
+replaceReturn(null, "SecTrustEvaluateWithError", "bool", ["pointer", "pointer"], 1);
+
+// BoringSSL (Android, Chrome, Flutter): force the custom verify callback to
+// report ssl_verify_ok (0).
+for (const symbol of ["SSL_set_custom_verify", "SSL_CTX_set_custom_verify"]) {
+  const address = findExport(null, symbol);
+  if (address === null) {
+    continue;
+  }
+  const verifyOk = new NativeCallback(() => 0, "int", ["pointer", "pointer"]);
+  Interceptor.attach(address, {
+    onEnter(args) {
+      args[2] = verifyOk;
+    },
+  });
+  console.log(`[ssl-unpinning] ${symbol} patched`);
+}
+
+// OpenSSL: SSL_get_verify_result(ssl) -> X509_V_OK
+replaceReturn(null, "SSL_get_verify_result", "long", ["pointer"], 0);
+
+if (typeof Java !== "undefined" && Java.available) {
+  Java.perform(() => {
+    const tryHook = (label, fn) => {
+      try {
+        fn();
+        console.log(`[ssl-unpinning] ${label} patched`);
+      } catch (_error) {
+        // Class not present in this app.
+      }
+    };
+
+    tryHook("TrustManagerImpl", () => {
+      const TrustManagerImpl = Java.use("com.android.org.conscrypt.TrustManagerImpl");
+      TrustManagerImpl.verifyChain.implementation = function (untrustedChain) {
+        return untrustedChain;
+      };
+    });
+
+    tryHook("OkHttp CertificatePinner", () => {
+      const CertificatePinner = Java.use("okhttp3.CertificatePinner");
+      CertificatePinner.check.overloads.forEach((overload) => {
+        overload.implementation = function () {};
+      });
+    });
+  });
+}
@@ -0,0 +1,39 @@
+// CARF bundled script: generic tracer.
+//
+// Logs file, network and process-creation calls made by the target. Edit
+// TRACED to add or remove functions.
+
+const TRACED = {
+  open: (args) => `path=${args[0].readUtf8String()}`,
+  openat: (args) => `path=${args[1].readUtf8String()}`,
+  connect: (args) => `fd=${args[0].toInt32()}`,
+  send: (args) => `fd=${args[0].toInt32()} len=${args[2].toInt32()}`,
+  recv: (args) => `fd=${args[0].toInt32()} len=${args[2].toInt32()}`,
+  execve: (args) => `path=${args[0].readUtf8String()}`,
+  dlopen: (args) => `path=${args[0].isNull() ? "<self>" : args[0].readUtf8String()}`,
+  CreateFileW: (args) => `path=${args[0].readUtf16String()}`,
+  CreateProcessW: (args) =>
+    `app=${args[0].isNull() ? "<null>" : args[0].readUtf16String()}`,
+};
+
+for (const [name, describe] of Object.entries(TRACED)) {
+  const address = Module.findGlobalExportByName(name);
+  if (address === null) {
+    continue;
+  }
+
+  Interceptor.attach(address, {
+    onEnter(args) {
+      try {
+        this.summary = describe(args);
+      } catch (_error) {
+        this.summary = "";
+      }
+    },
+    onLeave(retval) {
+      console.log(`[tracer] tid=${this.threadId} ${name}(${this.summary}) = ${retval}`);
+    },
+  });
+}
+
+console.log("[tracer] ready");
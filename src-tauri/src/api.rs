@@ -1,14 +1,148 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use regex::RegexBuilder;
+use serde::Serialize;
 use serde_json::Value;
 
 use crate::error::AppError;
+use crate::services::app_log::{self, LogEntry};
+use crate::services::audit::{self, AuditEntry, AuditFilter};
+use crate::services::backups::{self, BackupInfo};
+use crate::services::bookmarks::{self, Bookmark, ResolvedBookmark, ResolvedLocation};
+use crate::services::file_transfer::{self, FileTransfer, FileTransferProgress, Transfer};
 use crate::services::frida::{
-    AppInfo, AttachOptions, CollectionPage, DeviceInfo, OsPlatform, ProcessInfo, SpawnOptions,
+    AppInfo, AttachOptions, CollectionPage, DeviceInfo, DeviceSystemInfo, DeviceType, EvalResult,
+    FridaService, MessageFilter, MessageLogOptions, MessageLogStatus, MessagePage, OsPlatform,
+    PairingState, PendingSpawn, PortForward, ProcessInfo, ProcessListOptions, ProcessOutputPage,
+    ProcessSortKey, RemoteDeviceOptions, SessionCleanupReport, SessionDiagnostic, SessionMarker,
+    SpawnGatingOptions, SpawnOptions, TimeoutPolicy,
+};
+use crate::services::hooks::{self, HookInfo, HookOptions};
+use crate::services::hotkeys::{HotkeyBinding, HotkeyRpcAction};
+use crate::services::inspector::{self, ByteInspection, TypeGuess};
+use crate::services::launch::Workspace;
+use crate::services::memory::{
+    self, BatchWriteReport, FreezeState, FrozenValue, Hexdump, HexdumpOptions, MappedFileSlice,
+    MemoryWatch, ValueWrite,
+};
+use crate::services::memory_dump::{self, Dump, MemoryDump, MemoryDumpProgress};
+use crate::services::perf::{self, PerfReport, PerfSelftestOptions};
+use crate::services::plugins::{self, PluginInfo};
+use crate::services::pointer_scan::{
+    self, PointerMapInfo, PointerScanOptions, PointerScanResult, ResolvedPointerChain,
+    SavedPointerChain,
+};
+use crate::services::process_metrics::{self, ProcessMetricsStatus};
+use crate::services::remote_devices::{self, RemoteEndpoint};
+use crate::services::scanner::{self, ScanPreset};
+use crate::services::script_catalog::{self, BundledScriptInfo};
+use crate::services::script_compiler::{self, CompiledScript};
+use crate::services::session_manager::{AttachManyResult, SessionInfo};
+use crate::services::session_restore::{self, SessionRestoreReport};
+use crate::services::settings::{self, Settings};
+use crate::services::stalker::{self, FunctionTrace, StalkerEventOptions, TraceSummary};
+use crate::services::storage;
+use crate::services::target_profiles::{
+    self, ProfileLaunchReport, ProfileLaunchStep, ProfileTarget, TargetProfile,
 };
-use crate::services::session_manager::SessionInfo;
+use crate::services::typed_memory::{
+    self, Endianness, TypedReadOptions, TypedValue, TypedValueType,
+};
+use crate::services::value_format::{self, ValueFormat};
+use crate::services::value_snapshots::{
+    self, SnapshotComparison, SnapshotEntry, ValueSnapshotInfo,
+};
+use crate::services::variables;
 use crate::state::AppState;
 
 const DEFAULT_LIST_LIMIT: usize = 200;
+const DEFAULT_WAIT_TIMEOUT_MS: u64 = 30_000;
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(100);
 const MAX_LIST_LIMIT: usize = 500;
+const DEFAULT_PROCESS_WATCH_INTERVAL_MS: u64 = 2_000;
+const GUESS_TYPE_SAMPLES: usize = 6;
+const GUESS_TYPE_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+const DEFAULT_PAIRING_TIMEOUT_MS: u64 = 60_000;
+const PAIRING_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Data directory subfolder holding the host files the HTTP bridge reads
+/// and writes: transfers, dumps, exports, imports and scripts. Any local
+/// process can reach the bridge, so it gets no access to the rest of the
+/// file system.
+const BRIDGE_FILES_DIR: &str = "bridge-files";
+
+/// RPC methods that execute arbitrary JavaScript inside the Frida agent.
+/// These must never be callable through the HTTP bridge unless the operator
+/// has explicitly opted in via `CARF_ALLOW_EVAL=1`, since the bridge is
+/// reachable from any process on the local machine. Hotkeys are held to the
+/// same rule, since the bridge can bind and trigger them.
+const EVAL_METHODS: &[&str] = &["evaluate", "eval", "runScript", "loadScript"];
+
+/// Agent RPC methods only the host sends, on its own behalf. `startJob`
+/// runs any handler by name, so accepting it from a caller would skip the
+/// eval and audit checks on the method it wraps; the Frida runtime's job
+/// bookkeeping is the only sender of both.
+const HOST_INTERNAL_METHODS: &[&str] = &["startJob", "cancelJob"];
+
+pub fn is_eval_method(method: &str) -> bool {
+    EVAL_METHODS.contains(&method)
+}
+
+pub fn is_host_internal_method(method: &str) -> bool {
+    HOST_INTERNAL_METHODS.contains(&method)
+}
+
+fn ensure_host_internal_rejected(method: &str) -> Result<(), AppError> {
+    if is_host_internal_method(method) {
+        return Err(AppError::InvalidArgument(format!(
+            "rpc method '{method}' is internal to CARF and cannot be called directly"
+        )));
+    }
+    Ok(())
+}
+
+/// Whether the operator opted into `EVAL_METHODS` and `repl_eval`.
+pub fn eval_allowed() -> bool {
+    std::env::var("CARF_ALLOW_EVAL")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+/// Resolves a host path given over the HTTP bridge inside
+/// `BRIDGE_FILES_DIR`. Relative paths are taken from there; anything that
+/// would leave it is rejected.
+pub fn bridge_local_path(local_path: &str) -> Result<String, AppError> {
+    let dir = storage::data_file(BRIDGE_FILES_DIR)?;
+    std::fs::create_dir_all(&dir).map_err(|error| {
+        AppError::StorageError(format!("failed to create {}: {error}", dir.display()))
+    })?;
+    let path = Path::new(local_path);
+    let relative = path.strip_prefix(&dir).unwrap_or(path);
+    let contained = relative.components().count() > 0
+        && relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+    if !contained {
+        return Err(AppError::InvalidArgument(format!(
+            "{local_path} is outside {}; the HTTP bridge only uses files there",
+            dir.display()
+        )));
+    }
+    Ok(dir.join(relative).to_string_lossy().into_owned())
+}
+
+fn ensure_hotkey_rpc_allowed(method: &str) -> Result<(), AppError> {
+    ensure_host_internal_rejected(method)?;
+    if is_eval_method(method) && !eval_allowed() {
+        return Err(AppError::InvalidArgument(format!(
+            "rpc method '{method}' cannot be bound to a hotkey. Set CARF_ALLOW_EVAL=1 to enable."
+        )));
+    }
+    Ok(())
+}
 
 fn normalize_query(query: Option<String>) -> Option<String> {
     query.and_then(|value| {
@@ -27,6 +161,7 @@ fn normalize_limit(limit: Option<usize>) -> usize {
 
 fn build_collection_page<T, F>(
     items: &[T],
+    offset: usize,
     limit: usize,
     query: Option<String>,
     matches: F,
@@ -44,16 +179,17 @@ where
         }
 
         total += 1;
-        if page.len() < limit {
+        if total > offset && page.len() < limit {
             page.push(item.clone());
         }
     }
 
     CollectionPage {
+        truncated: total > offset + page.len(),
         items: page,
         total,
+        offset,
         limit,
-        truncated: total > limit,
         query,
     }
 }
@@ -126,11 +262,21 @@ fn load_processes(
         }
     }
 
-    let mut processes = state
+    let mut svc = state
         .frida_service
         .lock()
-        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?
-        .list_processes(device_id)?;
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    // A background watch already holds an up-to-date list.
+    let watched = if force_refresh {
+        None
+    } else {
+        svc.watched_processes(device_id)?
+    };
+    let mut processes = match watched {
+        Some(processes) => processes,
+        None => svc.list_processes(device_id)?,
+    };
+    drop(svc);
 
     processes.sort_by(|left, right| left.name.cmp(&right.name).then(left.pid.cmp(&right.pid)));
 
@@ -249,12 +395,20 @@ pub fn list_devices(state: &AppState) -> Result<Vec<DeviceInfo>, AppError> {
     svc.list_devices()
 }
 
-pub fn add_remote_device(state: &AppState, address: String) -> Result<DeviceInfo, AppError> {
+pub fn add_remote_device(
+    state: &AppState,
+    address: String,
+    options: Option<RemoteDeviceOptions>,
+) -> Result<DeviceInfo, AppError> {
     let mut svc = state
         .frida_service
         .lock()
         .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
-    let device = svc.add_remote_device(&address)?;
+    let options = options.unwrap_or_default();
+    let device = svc.add_remote_device(&address, options.clone())?;
+    if let Err(error) = remote_devices::remember(address.trim(), &options) {
+        log::warn!("Failed to save remote device {address}: {error}");
+    }
     state.events.emit(
         "carf://device/added",
         serde_json::to_value(&device).map_err(|error| AppError::Internal(error.to_string()))?,
@@ -262,6 +416,22 @@ pub fn add_remote_device(state: &AppState, address: String) -> Result<DeviceInfo
     Ok(device)
 }
 
+pub fn remote_endpoints_list() -> Result<Vec<RemoteEndpoint>, AppError> {
+    remote_devices::list()
+}
+
+pub fn remote_endpoint_update(
+    address: String,
+    nickname: Option<String>,
+    options: Option<RemoteDeviceOptions>,
+) -> Result<RemoteEndpoint, AppError> {
+    remote_devices::update(&address, nickname, options)
+}
+
+pub fn remote_endpoint_remove(address: String) -> Result<bool, AppError> {
+    remote_devices::remove(&address)
+}
+
 pub fn remove_remote_device(state: &AppState, address: String) -> Result<(), AppError> {
     let mut svc = state
         .frida_service
@@ -275,6 +445,31 @@ pub fn remove_remote_device(state: &AppState, address: String) -> Result<(), App
     Ok(())
 }
 
+pub fn add_portal(
+    state: &AppState,
+    address: String,
+    options: Option<RemoteDeviceOptions>,
+) -> Result<DeviceInfo, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let device = svc.add_portal(&address, options.unwrap_or_default())?;
+    state.events.emit(
+        "carf://device/added",
+        serde_json::to_value(&device).map_err(|error| AppError::Internal(error.to_string()))?,
+    );
+    Ok(device)
+}
+
+pub fn list_portal_nodes(state: &AppState, device_id: String) -> Result<Vec<ProcessInfo>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.list_portal_nodes(&device_id)
+}
+
 pub fn get_device_info(state: &AppState, device_id: String) -> Result<DeviceInfo, AppError> {
     let mut svc = state
         .frida_service
@@ -283,24 +478,251 @@ pub fn get_device_info(state: &AppState, device_id: String) -> Result<DeviceInfo
     svc.get_device_info(&device_id)
 }
 
+/// Starts lockdown pairing with an iOS device and waits for the user to
+/// answer the trust prompt, releasing the service between polls. Each
+/// pairing state change is emitted as `carf://device/pairing`. Returns the
+/// device once it is paired, denied, or the timeout passes.
+pub fn pair_device(
+    state: &AppState,
+    device_id: String,
+    timeout_ms: Option<u64>,
+) -> Result<DeviceInfo, AppError> {
+    let deadline =
+        Instant::now() + Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_PAIRING_TIMEOUT_MS));
+    let mut last_state = None;
+
+    loop {
+        let device = get_device_info(state, device_id.clone())?;
+        let Some(pairing) = &device.pairing else {
+            return Err(AppError::InvalidArgument(format!(
+                "{device_id} is not an iOS device connected over USB"
+            )));
+        };
+
+        if last_state != Some(pairing.state) {
+            last_state = Some(pairing.state);
+            state.events.emit(
+                "carf://device/pairing",
+                serde_json::to_value(&device)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            );
+        }
+
+        let waiting = matches!(
+            pairing.state,
+            PairingState::Unpaired | PairingState::AwaitingTrust | PairingState::Locked
+        );
+        if !waiting || Instant::now() >= deadline {
+            return Ok(device);
+        }
+        std::thread::sleep(PAIRING_POLL_INTERVAL);
+    }
+}
+
+pub fn unpair_device(state: &AppState, device_id: String) -> Result<DeviceInfo, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.unpair_device(&device_id)?;
+    svc.get_device_info(&device_id)
+}
+
+pub fn device_forward_port(
+    state: &AppState,
+    device_id: String,
+    remote_port: u16,
+) -> Result<PortForward, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.forward_port(&device_id, remote_port)
+}
+
+pub fn device_stop_port_forward(state: &AppState, forward_id: String) -> Result<(), AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.stop_port_forward(&forward_id)
+}
+
+pub fn device_list_port_forwards(state: &AppState) -> Result<Vec<PortForward>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.list_port_forwards()
+}
+
+pub fn query_device(state: &AppState, device_id: String) -> Result<DeviceSystemInfo, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.query_device(&device_id)
+}
+
 pub fn list_processes(
     state: &AppState,
     device_id: String,
     query: Option<String>,
     limit: Option<usize>,
     force_refresh: Option<bool>,
+    options: Option<ProcessListOptions>,
 ) -> Result<CollectionPage<ProcessInfo>, AppError> {
+    let options = options.unwrap_or_default();
     let query = normalize_query(query);
     let limit = normalize_limit(limit);
     let query_filter = query.clone();
-    let processes = load_processes(state, &device_id, force_refresh.unwrap_or(false))?;
+    let regex = options
+        .regex
+        .as_deref()
+        .map(str::trim)
+        .filter(|pattern| !pattern.is_empty())
+        .map(|pattern| {
+            RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|error| AppError::InvalidArgument(format!("regex: {error}")))
+        })
+        .transpose()?;
+    let force_refresh = force_refresh.unwrap_or(false);
+    let mut processes = load_processes(state, &device_id, force_refresh)?;
 
-    Ok(build_collection_page(&processes, limit, query, |process| {
-        query_filter
-            .as_deref()
-            .map(|value| process_matches(process, value))
-            .unwrap_or(true)
-    }))
+    if options.user_apps_only == Some(true) {
+        processes = keep_application_processes(
+            processes,
+            &load_applications(state, &device_id, force_refresh)?,
+        );
+    }
+    sort_processes(
+        &mut processes,
+        options.sort_by.unwrap_or_default(),
+        options.descending.unwrap_or(false),
+    );
+
+    let mut page = build_collection_page(
+        &processes,
+        options.offset.unwrap_or(0),
+        limit,
+        query,
+        |process| {
+            query_filter
+                .as_deref()
+                .map(|value| process_matches(process, value))
+                .unwrap_or(true)
+                && regex.as_ref().map_or(true, |regex| {
+                    regex.is_match(&process.name)
+                        || process
+                            .identifier
+                            .as_deref()
+                            .is_some_and(|identifier| regex.is_match(identifier))
+                })
+        },
+    );
+
+    if options.include_icons == Some(true) {
+        attach_process_icons(state, &device_id, &mut page.items)?;
+    }
+    Ok(page)
+}
+
+/// Icons are fetched per page rather than cached with the list, since a full
+/// scope query is far slower and larger than a plain enumeration.
+fn attach_process_icons(
+    state: &AppState,
+    device_id: &str,
+    processes: &mut [ProcessInfo],
+) -> Result<(), AppError> {
+    let pids = processes
+        .iter()
+        .filter(|process| process.icon.is_none())
+        .map(|process| process.pid)
+        .collect();
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let mut icons = match svc.process_icons(device_id, pids) {
+        Ok(icons) => icons,
+        Err(error) => {
+            log::debug!("Process icons unavailable for {device_id}: {error}");
+            return Ok(());
+        }
+    };
+    drop(svc);
+
+    for process in processes.iter_mut() {
+        if let Some(icon) = icons.remove(&process.pid) {
+            process.icon = Some(icon);
+        }
+    }
+    Ok(())
+}
+
+fn attach_application_icons(
+    state: &AppState,
+    device_id: &str,
+    apps: &mut [AppInfo],
+) -> Result<(), AppError> {
+    let identifiers = apps
+        .iter()
+        .filter(|app| app.icon.is_none())
+        .map(|app| app.identifier.clone())
+        .collect();
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let mut icons = match svc.application_icons(device_id, identifiers) {
+        Ok(icons) => icons,
+        Err(error) => {
+            log::debug!("Application icons unavailable for {device_id}: {error}");
+            return Ok(());
+        }
+    };
+    drop(svc);
+
+    for app in apps.iter_mut() {
+        if let Some(icon) = icons.remove(&app.identifier) {
+            app.icon = Some(icon);
+        }
+    }
+    Ok(())
+}
+
+/// Processes owned by a running application, tagged with its identifier.
+fn keep_application_processes(processes: Vec<ProcessInfo>, apps: &[AppInfo]) -> Vec<ProcessInfo> {
+    use std::collections::HashMap;
+
+    let identifier_by_pid = apps
+        .iter()
+        .filter_map(|app| app.pid.map(|pid| (pid, app.identifier.as_str())))
+        .collect::<HashMap<_, _>>();
+
+    processes
+        .into_iter()
+        .filter_map(|mut process| {
+            let identifier = identifier_by_pid.get(&process.pid)?;
+            process
+                .identifier
+                .get_or_insert_with(|| identifier.to_string());
+            Some(process)
+        })
+        .collect()
+}
+
+/// `load_processes` returns the list by name; only re-sort when asked for
+/// something else.
+fn sort_processes(processes: &mut [ProcessInfo], key: ProcessSortKey, descending: bool) {
+    if matches!(key, ProcessSortKey::Pid) {
+        processes.sort_by_key(|process| process.pid);
+    }
+    if descending {
+        processes.reverse();
+    }
 }
 
 pub fn list_applications(
@@ -309,18 +731,24 @@ pub fn list_applications(
     query: Option<String>,
     limit: Option<usize>,
     force_refresh: Option<bool>,
+    include_icons: Option<bool>,
 ) -> Result<CollectionPage<AppInfo>, AppError> {
     let query = normalize_query(query);
     let limit = normalize_limit(limit);
     let query_filter = query.clone();
     let apps = load_applications(state, &device_id, force_refresh.unwrap_or(false))?;
 
-    Ok(build_collection_page(&apps, limit, query, |app| {
+    let mut page = build_collection_page(&apps, 0, limit, query, |app| {
         query_filter
             .as_deref()
             .map(|value| app_matches(app, value))
             .unwrap_or(true)
-    }))
+    });
+
+    if include_icons == Some(true) {
+        attach_application_icons(state, &device_id, &mut page.items)?;
+    }
+    Ok(page)
 }
 
 fn processes_to_apps(processes: &[ProcessInfo]) -> Vec<AppInfo> {
@@ -380,6 +808,12 @@ pub fn kill_process(state: &AppState, device_id: String, pid: u32) -> Result<(),
         .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
     let result = svc.kill_process(&device_id, pid);
     drop(svc);
+    audit::record(
+        "kill",
+        None,
+        serde_json::json!({ "deviceId": device_id, "pid": pid }),
+        &result,
+    );
 
     if result.is_ok() {
         state
@@ -392,87 +826,1973 @@ pub fn kill_process(state: &AppState, device_id: String, pid: u32) -> Result<(),
     result
 }
 
-pub fn spawn_and_attach(
+pub fn enable_spawn_gating(
     state: &AppState,
     device_id: String,
-    options: SpawnOptions,
-) -> Result<SessionInfo, AppError> {
+    options: Option<SpawnGatingOptions>,
+) -> Result<Vec<PendingSpawn>, AppError> {
     let mut svc = state
         .frida_service
         .lock()
         .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
-    let session = svc.spawn_and_attach(&device_id, options)?;
-    drop(svc);
-    state
-        .list_cache
+    svc.enable_spawn_gating(&device_id, options.unwrap_or_default())
+}
+
+pub fn disable_spawn_gating(state: &AppState, device_id: String) -> Result<(), AppError> {
+    let mut svc = state
+        .frida_service
         .lock()
-        .map_err(|_| AppError::Internal("list_cache lock poisoned".to_string()))?
-        .invalidate_device(&device_id);
-    emit_console_message(
-        state,
-        "info",
-        "system",
-        format!("Attached to {}", session.process_name),
-        None,
-    );
-    Ok(session)
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.disable_spawn_gating(&device_id)
 }
 
-pub fn attach(
+pub fn list_pending_spawns(
     state: &AppState,
     device_id: String,
-    options: AttachOptions,
-) -> Result<SessionInfo, AppError> {
+) -> Result<Vec<PendingSpawn>, AppError> {
     let mut svc = state
         .frida_service
         .lock()
         .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
-    let session = svc.attach(&device_id, options)?;
-    emit_console_message(
-        state,
-        "info",
-        "system",
-        format!("Attached to {}", session.process_name),
+    svc.list_pending_spawns(&device_id)
+}
+
+pub fn resume_spawn(state: &AppState, device_id: String, pid: u32) -> Result<(), AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.resume_spawn(&device_id, pid)
+}
+
+pub fn deny_spawn(state: &AppState, device_id: String, pid: u32) -> Result<(), AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let result = svc.deny_spawn(&device_id, pid);
+    drop(svc);
+    audit::record(
+        "kill",
         None,
+        serde_json::json!({ "deviceId": device_id, "pid": pid }),
+        &result,
     );
-    Ok(session)
+    result
 }
 
-pub fn detach(state: &AppState, session_id: String) -> Result<(), AppError> {
+pub fn watch_processes(
+    state: &AppState,
+    device_id: String,
+    interval_ms: Option<u64>,
+) -> Result<Vec<ProcessInfo>, AppError> {
     let mut svc = state
         .frida_service
         .lock()
         .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
-    svc.detach(&session_id)
+    let interval = Duration::from_millis(interval_ms.unwrap_or(DEFAULT_PROCESS_WATCH_INTERVAL_MS));
+    let mut processes = svc.watch_processes(&device_id, interval)?;
+    processes.sort_by(|left, right| left.name.cmp(&right.name).then(left.pid.cmp(&right.pid)));
+    Ok(processes)
 }
 
-pub fn resume(state: &AppState, session_id: String) -> Result<(), AppError> {
+pub fn unwatch_processes(state: &AppState, device_id: String) -> Result<bool, AppError> {
     let mut svc = state
         .frida_service
         .lock()
         .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
-    svc.resume(&session_id)
+    svc.unwatch_processes(&device_id)
 }
 
-pub fn list_sessions(state: &AppState) -> Result<Vec<SessionInfo>, AppError> {
+pub fn get_frontmost_application(
+    state: &AppState,
+    device_id: String,
+) -> Result<Option<AppInfo>, AppError> {
     let mut svc = state
         .frida_service
         .lock()
         .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
-    svc.list_sessions()
+    svc.frontmost_application(&device_id)
 }
 
-pub fn rpc_call(
+pub fn process_output_read(
     state: &AppState,
     session_id: String,
-    method: String,
-    params: Value,
-) -> Result<Value, AppError> {
+    since_cursor: Option<u64>,
+) -> Result<ProcessOutputPage, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.process_output_read(&session_id, since_cursor.unwrap_or(0))
+}
+
+pub fn process_metrics_start(
+    state: &AppState,
+    session_id: String,
+    interval_ms: Option<u32>,
+) -> Result<u32, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    process_metrics::start(&mut svc, &session_id, interval_ms)
+}
+
+pub fn process_metrics_stop(state: &AppState, session_id: String) -> Result<bool, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    process_metrics::stop(&mut svc, &session_id)
+}
+
+pub fn process_metrics_get(
+    state: &AppState,
+    session_id: String,
+) -> Result<ProcessMetricsStatus, AppError> {
     let mut svc = state
         .frida_service
         .lock()
         .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
-    svc.rpc_call(&session_id, &method, params)
+    process_metrics::status(&mut svc, &session_id)
+}
+
+pub fn spawn_and_attach(
+    state: &AppState,
+    device_id: String,
+    options: SpawnOptions,
+) -> Result<SessionInfo, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let args = serde_json::json!({ "deviceId": device_id, "identifier": options.identifier });
+    let result = svc.spawn_and_attach(&device_id, options);
+    drop(svc);
+    audit::record(
+        "spawn",
+        result.as_ref().ok().map(|session| session.id.as_str()),
+        args,
+        &result,
+    );
+    let session = result?;
+    state
+        .list_cache
+        .lock()
+        .map_err(|_| AppError::Internal("list_cache lock poisoned".to_string()))?
+        .invalidate_device(&device_id);
+    emit_console_message(
+        state,
+        "info",
+        "system",
+        format!("Attached to {}", session.process_name),
+        None,
+    );
+    emit_resolved_bookmarks(state, &session);
+    Ok(session)
+}
+
+pub fn attach(
+    state: &AppState,
+    device_id: String,
+    options: AttachOptions,
+) -> Result<SessionInfo, AppError> {
+    let args = serde_json::json!({ "deviceId": device_id, "target": options.target });
+    let result = attach_unaudited(state, &device_id, options);
+    record_attach(state, args, result)
+}
+
+fn attach_unaudited(
+    state: &AppState,
+    device_id: &str,
+    options: AttachOptions,
+) -> Result<SessionInfo, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.attach(device_id, options)
+}
+
+/// Audits the outcome of an attach and announces the new session.
+fn record_attach(
+    state: &AppState,
+    args: Value,
+    result: Result<SessionInfo, AppError>,
+) -> Result<SessionInfo, AppError> {
+    audit::record(
+        "attach",
+        result.as_ref().ok().map(|session| session.id.as_str()),
+        args,
+        &result,
+    );
+    let session = result?;
+    emit_console_message(
+        state,
+        "info",
+        "system",
+        format!("Attached to {}", session.process_name),
+        None,
+    );
+    emit_resolved_bookmarks(state, &session);
+    Ok(session)
+}
+
+/// Attaches to the process called `name`. With `wait`, polls until it shows
+/// up (or `timeout_ms` passes) and attaches the moment it does.
+pub fn attach_by_name(
+    state: &AppState,
+    device_id: String,
+    name: String,
+    wait: bool,
+    timeout_ms: Option<u64>,
+) -> Result<SessionInfo, AppError> {
+    let options = AttachOptions::for_target(Value::String(name));
+    if wait {
+        attach_when_running(state, device_id, options, timeout_ms)
+    } else {
+        attach(state, device_id, options)
+    }
+}
+
+/// Retries `attach` until the target exists, releasing the service between
+/// polls so other commands keep working while we wait.
+pub fn attach_when_running(
+    state: &AppState,
+    device_id: String,
+    options: AttachOptions,
+    timeout_ms: Option<u64>,
+) -> Result<SessionInfo, AppError> {
+    let timeout_ms = timeout_ms.unwrap_or(DEFAULT_WAIT_TIMEOUT_MS);
+    let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+    let args = serde_json::json!({ "deviceId": device_id, "target": options.target });
+
+    // Only the final outcome is audited, not every poll.
+    let result = loop {
+        match attach_unaudited(state, &device_id, options.clone()) {
+            Err(AppError::ProcessNotFound(_)) if Instant::now() < deadline => {
+                std::thread::sleep(WAIT_POLL_INTERVAL);
+            }
+            Err(AppError::ProcessNotFound(_)) => {
+                let target = match &options.target {
+                    Value::String(name) => name.clone(),
+                    other => other.to_string(),
+                };
+                break Err(AppError::ProcessNotFound(format!(
+                    "{target} did not start within {timeout_ms}ms"
+                )));
+            }
+            result => break result,
+        }
+    };
+    record_attach(state, args, result)
+}
+
+/// Attaches to each pid in turn, loading the same script into every session.
+/// One target failing does not stop the others.
+pub fn attach_many(
+    state: &AppState,
+    device_id: String,
+    pids: Vec<u32>,
+    script_path: Option<String>,
+    script_parameters: Option<Value>,
+) -> Vec<AttachManyResult> {
+    pids.into_iter()
+        .map(|pid| {
+            let options = AttachOptions {
+                script_path: script_path.clone(),
+                script_parameters: script_parameters.clone(),
+                ..AttachOptions::for_target(Value::from(pid))
+            };
+            match attach(state, device_id.clone(), options) {
+                Ok(session) => AttachManyResult {
+                    pid,
+                    session: Some(session),
+                    error: None,
+                },
+                Err(error) => AttachManyResult {
+                    pid,
+                    session: None,
+                    error: Some(error.to_string()),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Attaches every target in a launch workspace, in order. Failures are
+/// reported to the console and do not stop the remaining targets.
+pub fn auto_attach_workspace(state: &AppState, workspace: &Workspace) {
+    let device_id = match resolve_device_selector(state, workspace.device_id.as_deref()) {
+        Ok(device_id) => device_id,
+        Err(error) => {
+            emit_console_message(
+                state,
+                "error",
+                "system",
+                format!("Workspace auto-attach failed: {error}"),
+                None,
+            );
+            return;
+        }
+    };
+
+    for target in &workspace.targets {
+        let options = AttachOptions {
+            script_path: target.script_path.clone(),
+            script_parameters: target.script_parameters.clone(),
+            ..AttachOptions::for_target(target.target.clone())
+        };
+        let result = if target.wait {
+            attach_when_running(state, device_id.clone(), options, target.timeout_ms)
+        } else {
+            attach(state, device_id.clone(), options)
+        }
+        .and_then(|session| match &target.bundled_script {
+            Some(script_id) => load_bundled_script(state, session.id, script_id.clone()),
+            None => Ok(()),
+        });
+
+        if let Err(error) = result {
+            emit_console_message(
+                state,
+                "error",
+                "system",
+                format!("Workspace auto-attach to {} failed: {error}", target.target),
+                None,
+            );
+        }
+    }
+}
+
+/// Reconnects the saved remote endpoints, then the addresses listed in
+/// `Settings::auto_connect`. Failures go to the console; the rest still
+/// connect.
+pub fn auto_connect_devices(state: &AppState) {
+    let mut connections = remote_devices::connections().unwrap_or_else(|error| {
+        log::warn!("Skipping saved remote devices: {error}");
+        Vec::new()
+    });
+    match settings_get(state) {
+        Ok(settings) => {
+            for address in settings.auto_connect {
+                if !connections.iter().any(|(saved, _)| *saved == address) {
+                    connections.push((address, RemoteDeviceOptions::default()));
+                }
+            }
+        }
+        Err(error) => log::warn!("Skipping device auto-connect settings: {error}"),
+    }
+
+    for (address, options) in connections {
+        if let Err(error) = add_remote_device(state, address.clone(), Some(options)) {
+            emit_console_message(
+                state,
+                "error",
+                "system",
+                format!("Auto-connect to {address} failed: {error}"),
+                None,
+            );
+        }
+    }
+}
+
+/// Unset means the local device; `usb` (as links and profiles say it) the
+/// first USB device.
+fn resolve_device_selector(state: &AppState, selector: Option<&str>) -> Result<String, AppError> {
+    match selector
+        .map(str::trim)
+        .filter(|selector| !selector.is_empty())
+    {
+        None => Ok("local".to_string()),
+        Some("usb") => first_usb_device(state),
+        Some(device_id) => Ok(device_id.to_string()),
+    }
+}
+
+fn first_usb_device(state: &AppState) -> Result<String, AppError> {
+    list_devices(state)?
+        .into_iter()
+        .find(|device| matches!(device.device_type, DeviceType::Usb))
+        .map(|device| device.id)
+        .ok_or_else(|| AppError::DeviceNotFound("No USB device connected".to_string()))
+}
+
+pub fn profiles_list() -> Result<Vec<TargetProfile>, AppError> {
+    target_profiles::list()
+}
+
+pub fn profile_save(profile: TargetProfile) -> Result<TargetProfile, AppError> {
+    for binding in &profile.hotkeys {
+        if let Some(rpc) = &binding.rpc {
+            ensure_hotkey_rpc_allowed(&rpc.method)?;
+        }
+    }
+    target_profiles::save(profile)
+}
+
+pub fn profile_delete(profile_id: String) -> Result<bool, AppError> {
+    target_profiles::delete(&profile_id)
+}
+
+/// Attaches to or spawns the profile's target, loads its scripts, installs
+/// its hooks, binds its hotkeys and, for a spawn, resumes it. Only failing
+/// to get a session fails the launch; later steps report their errors.
+pub fn profile_launch(
+    state: &AppState,
+    profile_id: String,
+) -> Result<ProfileLaunchReport, AppError> {
+    let profile = target_profiles::get(&profile_id)?;
+    let device_id = resolve_device_selector(state, profile.device.as_deref())?;
+    let (session, resume_spawned) = match profile.target {
+        ProfileTarget::Attach {
+            target,
+            wait,
+            timeout_ms,
+        } => {
+            let options = AttachOptions::for_target(target);
+            let session = if wait {
+                attach_when_running(state, device_id, options, timeout_ms)?
+            } else {
+                attach(state, device_id, options)?
+            };
+            (session, false)
+        }
+        ProfileTarget::Spawn(mut options) => {
+            let resume_spawned = options.auto_resume != Some(false);
+            options.auto_resume = Some(false);
+            let session = spawn_and_attach(state, device_id, *options)?;
+            (session, resume_spawned)
+        }
+    };
+
+    let mut steps = Vec::new();
+    let mut step = |name: String, result: Result<(), AppError>| {
+        steps.push(ProfileLaunchStep {
+            name,
+            error: result.err().map(|error| error.to_string()),
+        });
+    };
+    if !profile.scripts.is_empty() {
+        let loaded = target_profiles::script_source(&profile.scripts).and_then(|code| {
+            rpc_call(
+                state,
+                session.id.clone(),
+                "loadScript".to_string(),
+                serde_json::json!({ "code": code }),
+            )
+            .map(|_| ())
+        });
+        step("scripts".to_string(), loaded);
+    }
+    for hook in profile.hooks {
+        let name = format!("hook {}", hook.symbol_or_offset);
+        let added = hook_add(
+            state,
+            session.id.clone(),
+            hook.module,
+            hook.symbol_or_offset,
+            Some(hook.options),
+        );
+        step(name, added.map(|_| ()));
+    }
+    for binding in profile.hotkeys {
+        let name = format!("hotkey {}", binding.action);
+        let rpc = binding.rpc.map(|rpc| HotkeyRpcAction {
+            session_id: rpc.session_id.or_else(|| Some(session.id.clone())),
+            ..rpc
+        });
+        let bound = hotkeys_set(state, binding.action.clone(), Some(binding.chord))
+            .and_then(|_| hotkeys_set_rpc(state, binding.action, rpc))
+            .map(|_| ());
+        step(name, bound);
+    }
+    if resume_spawned {
+        step("resume".to_string(), resume(state, session.id.clone()));
+    }
+
+    Ok(ProfileLaunchReport {
+        profile_id,
+        session,
+        steps,
+    })
+}
+
+pub fn detach(state: &AppState, session_id: String) -> Result<(), AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.detach(&session_id)
+}
+
+pub fn cleanup_session(
+    state: &AppState,
+    session_id: String,
+) -> Result<SessionCleanupReport, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let result = svc.cleanup_session(&session_id);
+    audit::record("cleanupSession", Some(&session_id), Value::Null, &result);
+    result
+}
+
+pub fn resume(state: &AppState, session_id: String) -> Result<(), AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.resume(&session_id)
+}
+
+pub fn list_sessions(state: &AppState) -> Result<Vec<SessionInfo>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.list_sessions()
+}
+
+pub fn get_session_diagnostic(
+    state: &AppState,
+    session_id: String,
+) -> Result<Option<SessionDiagnostic>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.session_diagnostic(&session_id)
+}
+
+pub fn add_session_marker(
+    state: &AppState,
+    session_id: String,
+    label: String,
+) -> Result<SessionMarker, AppError> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(AppError::InvalidArgument(
+            "Marker label must not be empty".to_string(),
+        ));
+    }
+
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.add_session_marker(&session_id, label)
+}
+
+pub fn list_session_markers(
+    state: &AppState,
+    session_id: String,
+) -> Result<Vec<SessionMarker>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.list_session_markers(&session_id)
+}
+
+pub fn restore_last_session(state: &AppState) -> Result<SessionRestoreReport, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let report = session_restore::restore_last_session(&mut svc)?;
+    drop(svc);
+    for session in &report.restored {
+        emit_console_message(
+            state,
+            "info",
+            "system",
+            format!("Restored session for {}", session.process_name),
+            None,
+        );
+    }
+    Ok(report)
+}
+
+pub fn rpc_call(
+    state: &AppState,
+    session_id: String,
+    method: String,
+    params: Value,
+) -> Result<Value, AppError> {
+    ensure_host_internal_rejected(&method)?;
+    if !audit::is_mutating_rpc(&method) {
+        return session_rpc(state, &session_id, &method, params);
+    }
+    let result = session_rpc(state, &session_id, &method, params.clone());
+    audit::record(&method, Some(&session_id), params, &result);
+    result
+}
+
+pub fn hook_add(
+    state: &AppState,
+    session_id: String,
+    module: Option<String>,
+    symbol_or_offset: String,
+    options: Option<HookOptions>,
+) -> Result<HookInfo, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let result = hooks::add_native_hook(
+        &mut svc,
+        &session_id,
+        module.as_deref(),
+        &symbol_or_offset,
+        &options.unwrap_or_default(),
+    );
+    audit::record(
+        "hookAdd",
+        Some(&session_id),
+        serde_json::json!({ "module": module, "symbolOrOffset": symbol_or_offset }),
+        &result,
+    );
+    result
+}
+
+pub fn hook_remove(state: &AppState, session_id: String, hook_id: String) -> Result<(), AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let result = hooks::remove_native_hook(&mut svc, &session_id, &hook_id);
+    audit::record(
+        "hookRemove",
+        Some(&session_id),
+        serde_json::json!({ "hookId": hook_id }),
+        &result,
+    );
+    result
+}
+
+pub fn hook_list(state: &AppState, session_id: String) -> Result<Vec<HookInfo>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    hooks::list_native_hooks(&mut svc, &session_id)
+}
+
+pub fn inspect_bytes(
+    state: &AppState,
+    session_id: String,
+    address: String,
+) -> Result<ByteInspection, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    inspector::inspect_bytes(&mut svc, &session_id, &address)
+}
+
+/// Samples the value at `address` a few times over ~250ms and ranks what it
+/// probably is. The service is released between samples.
+pub fn guess_type(
+    state: &AppState,
+    session_id: String,
+    address: String,
+) -> Result<TypeGuess, AppError> {
+    let mut samples = Vec::with_capacity(GUESS_TYPE_SAMPLES);
+    for index in 0..GUESS_TYPE_SAMPLES {
+        if index > 0 {
+            std::thread::sleep(GUESS_TYPE_SAMPLE_INTERVAL);
+        }
+        let mut svc = state
+            .frida_service
+            .lock()
+            .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+        samples.push(inspector::read_sample(&mut svc, &session_id, &address)?);
+    }
+
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    inspector::guess_types(&mut svc, &session_id, &address, &samples)
+}
+
+pub fn write_values_batch(
+    state: &AppState,
+    session_id: String,
+    writes: Vec<ValueWrite>,
+    atomic: bool,
+) -> Result<BatchWriteReport, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let args = serde_json::json!({ "writes": writes.len(), "atomic": atomic });
+    let result = memory::write_values_batch(&mut svc, &session_id, writes, atomic);
+    audit::record("writeValuesBatch", Some(&session_id), args, &result);
+    result
+}
+
+pub fn scan_snapshot_save(
+    state: &AppState,
+    session_id: String,
+    name: String,
+    entries: Vec<SnapshotEntry>,
+) -> Result<ValueSnapshotInfo, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    value_snapshots::save(&mut svc, &session_id, &name, entries)
+}
+
+pub fn scan_snapshots_list(
+    state: &AppState,
+    session_id: String,
+) -> Result<Vec<ValueSnapshotInfo>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    value_snapshots::list(&mut svc, &session_id)
+}
+
+pub fn scan_snapshot_delete(
+    state: &AppState,
+    session_id: String,
+    name: String,
+) -> Result<bool, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    value_snapshots::delete(&mut svc, &session_id, &name)
+}
+
+pub fn scan_snapshot_compare(
+    state: &AppState,
+    session_id: String,
+    before: String,
+    after: String,
+) -> Result<SnapshotComparison, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    value_snapshots::compare(&mut svc, &session_id, &before, &after)
+}
+
+pub fn memory_read_typed(
+    state: &AppState,
+    session_id: String,
+    address: String,
+    value_type: TypedValueType,
+    options: Option<TypedReadOptions>,
+) -> Result<TypedValue, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    typed_memory::read(
+        &mut svc,
+        &session_id,
+        &address,
+        value_type,
+        options.unwrap_or_default(),
+    )
+}
+
+pub fn memory_write_typed(
+    state: &AppState,
+    session_id: String,
+    address: String,
+    value_type: TypedValueType,
+    value: Value,
+    endianness: Option<Endianness>,
+) -> Result<TypedValue, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let args = serde_json::json!({ "address": address, "type": value_type, "value": value });
+    let result = typed_memory::write(
+        &mut svc,
+        &session_id,
+        &address,
+        value_type,
+        value,
+        endianness,
+    );
+    audit::record("memoryWriteTyped", Some(&session_id), args, &result);
+    result
+}
+
+pub fn freeze_values(
+    state: &AppState,
+    session_id: String,
+    values: Vec<ValueWrite>,
+    interval_ms: Option<u32>,
+) -> Result<Vec<FrozenValue>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let args = serde_json::json!({ "values": values, "intervalMs": interval_ms });
+    let result = memory::freeze_values(&mut svc, &session_id, values, interval_ms);
+    audit::record("freezeValues", Some(&session_id), args, &result);
+    result
+}
+
+pub fn unfreeze_values(
+    state: &AppState,
+    session_id: String,
+    ids: Option<Vec<String>>,
+) -> Result<usize, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let args = serde_json::json!({ "ids": ids });
+    let result = memory::unfreeze_values(&mut svc, &session_id, ids);
+    audit::record("unfreezeValues", Some(&session_id), args, &result);
+    result
+}
+
+pub fn list_frozen_values(state: &AppState, session_id: String) -> Result<FreezeState, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    memory::list_frozen_values(&mut svc, &session_id)
+}
+
+pub fn memory_watch(
+    state: &AppState,
+    session_id: String,
+    address: String,
+    size: usize,
+    on: String,
+) -> Result<MemoryWatch, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let result = memory::watch(&mut svc, &session_id, &address, size, &on);
+    audit::record(
+        "memoryWatch",
+        Some(&session_id),
+        serde_json::json!({ "address": address, "size": size, "on": on }),
+        &result,
+    );
+    result
+}
+
+pub fn memory_unwatch(
+    state: &AppState,
+    session_id: String,
+    watch_id: String,
+) -> Result<MemoryWatch, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let result = memory::unwatch(&mut svc, &session_id, &watch_id);
+    audit::record(
+        "memoryUnwatch",
+        Some(&session_id),
+        serde_json::json!({ "watchId": watch_id }),
+        &result,
+    );
+    result
+}
+
+pub fn memory_watches_list(
+    state: &AppState,
+    session_id: String,
+) -> Result<Vec<MemoryWatch>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    memory::list_watches(&mut svc, &session_id)
+}
+
+pub fn scan_presets(state: &AppState) -> Result<Vec<ScanPreset>, AppError> {
+    let settings = state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?;
+    Ok(scanner::list_presets(&settings.settings().scanner.presets))
+}
+
+pub fn scan_preset_save(state: &AppState, preset: ScanPreset) -> Result<(), AppError> {
+    state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?
+        .save_scan_preset(preset)
+}
+
+pub fn scan_preset_delete(state: &AppState, name: String) -> Result<bool, AppError> {
+    state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?
+        .delete_scan_preset(&name)
+}
+
+pub fn scan_with_preset(
+    state: &AppState,
+    session_id: String,
+    preset: String,
+    values: Vec<Value>,
+) -> Result<Value, AppError> {
+    let preset = {
+        let settings = state
+            .settings_service
+            .lock()
+            .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?;
+        scanner::find_preset(&settings.settings().scanner.presets, &preset)?
+    };
+    scanner::scan_with_preset(
+        |method, params| session_rpc(state, &session_id, method, params),
+        &preset,
+        &values,
+    )
+}
+
+pub fn pointer_scan(
+    state: &AppState,
+    session_id: String,
+    options: PointerScanOptions,
+) -> Result<PointerScanResult, AppError> {
+    pointer_scan::scan(
+        |method, params| session_rpc(state, &session_id, method, params),
+        options,
+    )
+}
+
+pub fn pointer_map_snapshot(
+    state: &AppState,
+    session_id: String,
+) -> Result<PointerMapInfo, AppError> {
+    pointer_scan::snapshot(|method, params| session_rpc(state, &session_id, method, params))
+}
+
+pub fn bookmarks_list(profile: String) -> Result<Vec<Bookmark>, AppError> {
+    bookmarks::list(&profile)
+}
+
+pub fn bookmark_save(profile: String, bookmark: Bookmark) -> Result<(), AppError> {
+    bookmarks::save(&profile, bookmark)
+}
+
+pub fn bookmark_delete(profile: String, name: String) -> Result<bool, AppError> {
+    bookmarks::delete(&profile, &name)
+}
+
+/// Resolves the bookmarks saved for the session's target in its process.
+pub fn bookmarks_resolve(
+    state: &AppState,
+    session_id: String,
+) -> Result<Vec<ResolvedBookmark>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let session = svc
+        .list_sessions()?
+        .into_iter()
+        .find(|session| session.id == session_id)
+        .ok_or_else(|| AppError::SessionNotFound(format!("Session not found: {session_id}")))?;
+    bookmarks::resolve_profile(&mut svc, &session_id, &variables::profile_for(&session))
+}
+
+pub fn address_resolve(
+    state: &AppState,
+    session_id: String,
+    locations: Vec<String>,
+) -> Result<Vec<ResolvedLocation>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    bookmarks::resolve_locations(&mut svc, &session_id, &locations)
+}
+
+pub fn address_to_location(
+    state: &AppState,
+    session_id: String,
+    addresses: Vec<String>,
+) -> Result<Vec<String>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    bookmarks::to_locations(&mut svc, &session_id, &addresses)
+}
+
+pub fn pointer_chains_list(profile: String) -> Result<Vec<SavedPointerChain>, AppError> {
+    pointer_scan::list_saved(&profile)
+}
+
+pub fn pointer_chain_save(profile: String, chain: SavedPointerChain) -> Result<(), AppError> {
+    pointer_scan::save(&profile, chain)
+}
+
+pub fn pointer_chain_delete(profile: String, name: String) -> Result<bool, AppError> {
+    pointer_scan::delete(&profile, &name)
+}
+
+pub fn pointer_chains_resolve(
+    state: &AppState,
+    session_id: String,
+    names: Option<Vec<String>>,
+) -> Result<Vec<ResolvedPointerChain>, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    pointer_scan::resolve_saved(&mut svc, &session_id, names)
+}
+
+pub fn read_mapped_file(
+    state: &AppState,
+    session_id: String,
+    address: String,
+    size: Option<usize>,
+) -> Result<MappedFileSlice, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    memory::read_mapped_file(&mut svc, &session_id, &address, size)
+}
+
+pub fn memory_hexdump(
+    state: &AppState,
+    session_id: String,
+    address: String,
+    size: Option<usize>,
+    options: Option<HexdumpOptions>,
+) -> Result<Hexdump, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    memory::hexdump(
+        &mut svc,
+        &session_id,
+        &address,
+        size,
+        options.unwrap_or_default(),
+    )
+}
+
+pub fn format_values(values: Vec<Value>, format: ValueFormat) -> Vec<String> {
+    value_format::format_values(&values, &format)
+}
+
+pub fn compile_script(
+    state: &AppState,
+    entrypoint_path: String,
+) -> Result<CompiledScript, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    script_compiler::compile_script(&mut svc, &entrypoint_path)
+}
+
+pub fn list_bundled_scripts() -> Vec<BundledScriptInfo> {
+    script_catalog::list_bundled_scripts()
+}
+
+pub fn load_bundled_script(
+    state: &AppState,
+    session_id: String,
+    script_id: String,
+) -> Result<(), AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let result = script_catalog::load_bundled_script(&mut svc, &session_id, &script_id);
+    audit::record(
+        "loadBundledScript",
+        Some(&session_id),
+        serde_json::json!({ "scriptId": script_id }),
+        &result,
+    );
+    result
+}
+
+/// Audited like mutating RPCs, since a snippet can change anything.
+pub fn repl_eval(
+    state: &AppState,
+    session_id: String,
+    code: String,
+) -> Result<EvalResult, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let result = svc.eval(&session_id, &code);
+    audit::record(
+        "replEval",
+        Some(&session_id),
+        serde_json::json!({ "code": code }),
+        &result,
+    );
+    result
+}
+
+/// Copies a host file into the target's file system through the agent.
+/// `transfer_id` (generated when omitted) can be passed to
+/// `cancel_operation`.
+pub fn file_push(
+    state: &AppState,
+    session_id: String,
+    local_path: String,
+    remote_path: String,
+    transfer_id: Option<String>,
+) -> Result<FileTransfer, AppError> {
+    let transfer_id = transfer_id.unwrap_or_else(|| format!("push-{}", uuid::Uuid::new_v4()));
+    let guard = state.operations.begin(&transfer_id)?;
+    let transfer = Transfer::push(&transfer_id, &local_path, &remote_path);
+    let result = file_transfer::push_via_agent(
+        &transfer,
+        |method, params, data| session_rpc_write(state, &session_id, method, params, data),
+        || guard.is_cancelled(),
+        |progress| emit_transfer_progress(state, &progress),
+    );
+    if let Ok(done) = &result {
+        emit_transfer_progress(state, &done.progress());
+    }
+    audit::record(
+        "filePush",
+        Some(&session_id),
+        serde_json::json!({ "localPath": local_path, "remotePath": remote_path }),
+        &result,
+    );
+    result
+}
+
+/// Copies a file from the target's file system to the host through the
+/// agent. `transfer_id` (generated when omitted) can be passed to
+/// `cancel_operation`.
+pub fn file_pull(
+    state: &AppState,
+    session_id: String,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+) -> Result<FileTransfer, AppError> {
+    let transfer_id = transfer_id.unwrap_or_else(|| format!("pull-{}", uuid::Uuid::new_v4()));
+    let guard = state.operations.begin(&transfer_id)?;
+    let transfer = Transfer::pull(&transfer_id, &remote_path, &local_path);
+    let result = file_transfer::pull_via_agent(
+        &transfer,
+        |method, params| session_rpc(state, &session_id, method, params),
+        |method, params| session_rpc_read(state, &session_id, method, params),
+        || guard.is_cancelled(),
+        |progress| emit_transfer_progress(state, &progress),
+    );
+    if let Ok(done) = &result {
+        emit_transfer_progress(state, &done.progress());
+    }
+    result
+}
+
+/// Locks the service only to issue the call. Agent jobs (scans and the
+/// like) are waited on with the lock released, and chunked transfers lock
+/// per chunk, so other commands run in between.
+fn session_rpc(
+    state: &AppState,
+    session_id: &str,
+    method: &str,
+    params: Value,
+) -> Result<Value, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    if !FridaService::is_job_method(method) {
+        return svc.rpc_call(session_id, method, params);
+    }
+    let job = svc.start_job(session_id, method, params)?;
+    drop(svc);
+    job.wait()
+}
+
+/// `session_rpc` for a chunk read whose bytes come back beside the reply.
+fn session_rpc_read(
+    state: &AppState,
+    session_id: &str,
+    method: &str,
+    params: Value,
+) -> Result<(Value, Vec<u8>), AppError> {
+    state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?
+        .rpc_read_bytes(session_id, method, params)
+}
+
+/// `session_rpc` for a chunk write whose bytes go out beside the call.
+fn session_rpc_write(
+    state: &AppState,
+    session_id: &str,
+    method: &str,
+    params: Value,
+    data: Vec<u8>,
+) -> Result<Value, AppError> {
+    state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?
+        .rpc_write_bytes(session_id, method, params, data)
+}
+
+/// Copies a host file to `remote_path` on an adb device. `transfer_id`
+/// (generated when omitted) can be passed to `cancel_operation`.
+pub fn adb_push_file(
+    state: &AppState,
+    serial: String,
+    local_path: String,
+    remote_path: String,
+    transfer_id: Option<String>,
+) -> Result<FileTransfer, AppError> {
+    let transfer_id = transfer_id.unwrap_or_else(|| format!("adb-push-{}", uuid::Uuid::new_v4()));
+    let guard = state.operations.begin(&transfer_id)?;
+    let adb = state
+        .adb_service
+        .lock()
+        .map_err(|_| AppError::Internal("adb_service lock poisoned".to_string()))?
+        .clone();
+    let transfer = Transfer::push(&transfer_id, &local_path, &remote_path);
+    let local = Path::new(&local_path);
+    adb.push_file(
+        &serial,
+        local,
+        &remote_path,
+        || guard.is_cancelled(),
+        |sent, total| emit_transfer_progress(state, &transfer.progress(sent, total)),
+    )?;
+    let done = transfer.finish(std::fs::metadata(local).map_or(0, |meta| meta.len()));
+    emit_transfer_progress(state, &done.progress());
+    Ok(done)
+}
+
+/// Copies `remote_path` from an adb device to a host file. `transfer_id`
+/// (generated when omitted) can be passed to `cancel_operation`.
+pub fn adb_pull_file(
+    state: &AppState,
+    serial: String,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+) -> Result<FileTransfer, AppError> {
+    let transfer_id = transfer_id.unwrap_or_else(|| format!("adb-pull-{}", uuid::Uuid::new_v4()));
+    let guard = state.operations.begin(&transfer_id)?;
+    let adb = state
+        .adb_service
+        .lock()
+        .map_err(|_| AppError::Internal("adb_service lock poisoned".to_string()))?
+        .clone();
+    let transfer = Transfer::pull(&transfer_id, &remote_path, &local_path);
+    let local = Path::new(&local_path);
+    adb.pull_file(
+        &serial,
+        &remote_path,
+        local,
+        || guard.is_cancelled(),
+        |received, total| emit_transfer_progress(state, &transfer.progress(received, total)),
+    )?;
+    let done = transfer.finish(std::fs::metadata(local).map_or(0, |meta| meta.len()));
+    emit_transfer_progress(state, &done.progress());
+    Ok(done)
+}
+
+fn emit_transfer_progress(state: &AppState, progress: &FileTransferProgress) {
+    emit_progress(state, "carf://file-transfer/progress", progress);
+}
+
+fn emit_progress(state: &AppState, event: &str, progress: &impl Serialize) {
+    if let Ok(payload) = serde_json::to_value(progress) {
+        state.events.emit(event, payload);
+    }
+}
+
+/// Streams `size` bytes at `address` to a host file. `dump_id` (generated
+/// when omitted) can be passed to `cancel_operation`.
+pub fn memory_dump_range(
+    state: &AppState,
+    session_id: String,
+    address: String,
+    size: u64,
+    path: String,
+    dump_id: Option<String>,
+) -> Result<MemoryDump, AppError> {
+    let base = memory_dump::parse_range(&address, size)?;
+    let dump_id = dump_id.unwrap_or_else(|| format!("dump-{}", uuid::Uuid::new_v4()));
+    let dump = Dump::new(&dump_id, base, size, &path, None);
+    run_memory_dump(state, &session_id, &dump_id, dump)
+}
+
+/// Streams a loaded module's whole image to a host file. `dump_id`
+/// (generated when omitted) can be passed to `cancel_operation`.
+pub fn memory_dump_module(
+    state: &AppState,
+    session_id: String,
+    module: String,
+    path: String,
+    dump_id: Option<String>,
+) -> Result<MemoryDump, AppError> {
+    let (base, size) = memory_dump::module_range(
+        |method, params| session_rpc(state, &session_id, method, params),
+        &module,
+    )?;
+    let dump_id = dump_id.unwrap_or_else(|| format!("dump-{}", uuid::Uuid::new_v4()));
+    let dump = Dump::new(&dump_id, base, size, &path, Some(module));
+    run_memory_dump(state, &session_id, &dump_id, dump)
+}
+
+fn run_memory_dump(
+    state: &AppState,
+    session_id: &str,
+    dump_id: &str,
+    dump: Dump,
+) -> Result<MemoryDump, AppError> {
+    let guard = state.operations.begin(dump_id)?;
+    let result = memory_dump::dump_via_agent(
+        &dump,
+        |method, params| session_rpc_read(state, session_id, method, params),
+        || guard.is_cancelled(),
+        |progress| emit_dump_progress(state, &progress),
+    );
+    if let Ok(done) = &result {
+        emit_dump_progress(state, &done.progress());
+    }
+    result
+}
+
+fn emit_dump_progress(state: &AppState, progress: &MemoryDumpProgress) {
+    emit_progress(state, "carf://memory-dump/progress", progress);
+}
+
+/// `data` is base64 so binary payloads survive the JSON bridge.
+pub fn script_post(
+    state: &AppState,
+    session_id: String,
+    script: Option<String>,
+    message: Value,
+    data: Option<String>,
+) -> Result<(), AppError> {
+    let data = data
+        .map(|data| {
+            STANDARD
+                .decode(data.trim())
+                .map_err(|error| AppError::InvalidArgument(format!("data is not base64: {error}")))
+        })
+        .transpose()?;
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let script = script.as_deref().unwrap_or("user");
+    let args = serde_json::json!({
+        "script": script,
+        "message": message,
+        "dataBytes": data.as_ref().map(Vec::len),
+    });
+    let result = svc.post_script_message(&session_id, script, message, data);
+    audit::record("scriptPost", Some(&session_id), args, &result);
+    result
+}
+
+pub fn message_log_start(
+    state: &AppState,
+    options: Option<MessageLogOptions>,
+) -> Result<MessageLogStatus, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.message_log_start(options.unwrap_or_default())
+}
+
+pub fn message_log_stop(state: &AppState) -> Result<MessageLogStatus, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.message_log_stop()
+}
+
+pub fn message_log_status(state: &AppState) -> Result<MessageLogStatus, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.message_log_status()
+}
+
+pub fn messages_query(
+    state: &AppState,
+    filter: Option<MessageFilter>,
+    since: Option<u64>,
+    limit: Option<usize>,
+) -> Result<MessagePage, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.messages_query(filter.unwrap_or_default(), since.unwrap_or(0), limit)
+}
+
+pub fn start_stalker_trace(
+    state: &AppState,
+    session_id: String,
+    thread_id: u64,
+    events: Option<StalkerEventOptions>,
+) -> Result<Value, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    stalker::start_thread_trace(
+        &mut svc,
+        &session_id,
+        thread_id,
+        &events.unwrap_or_default(),
+    )
+}
+
+/// Stops following the thread and reports what has been buffered so far.
+pub fn stop_stalker_trace(
+    state: &AppState,
+    session_id: String,
+    thread_id: u64,
+) -> Result<TraceSummary, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    stalker::stop_thread_trace(&mut svc, &session_id, thread_id)?;
+    svc.stalker_trace_summary(&session_id)
+}
+
+pub fn trace_function(
+    state: &AppState,
+    session_id: String,
+    module: Option<String>,
+    symbol_or_offset: String,
+    events: Option<StalkerEventOptions>,
+) -> Result<FunctionTrace, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let result = stalker::start_function_trace(
+        &mut svc,
+        &session_id,
+        module.as_deref(),
+        &symbol_or_offset,
+        &events.unwrap_or_default(),
+    );
+    audit::record(
+        "traceFunction",
+        Some(&session_id),
+        serde_json::json!({ "module": module, "symbolOrOffset": symbol_or_offset }),
+        &result,
+    );
+    result
+}
+
+pub fn untrace_function(
+    state: &AppState,
+    session_id: String,
+    trace_id: String,
+) -> Result<(), AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let result = stalker::stop_function_trace(&mut svc, &session_id, &trace_id);
+    audit::record(
+        "untraceFunction",
+        Some(&session_id),
+        serde_json::json!({ "traceId": trace_id }),
+        &result,
+    );
+    result
+}
+
+pub fn export_stalker_trace(
+    state: &AppState,
+    session_id: String,
+    path: String,
+) -> Result<TraceSummary, AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.export_stalker_trace(&session_id, &path)
+}
+
+pub fn clear_stalker_trace(state: &AppState, session_id: String) -> Result<(), AppError> {
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.clear_stalker_trace(&session_id)
+}
+
+pub fn settings_get(state: &AppState) -> Result<Settings, AppError> {
+    Ok(state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?
+        .settings()
+        .clone())
+}
+
+/// Replaces every setting, persists them and applies the ones the backend
+/// acts on.
+pub fn settings_set(state: &AppState, settings: Settings) -> Result<Settings, AppError> {
+    let settings = state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?
+        .replace(settings)?;
+    apply_settings(state, &settings)?;
+    Ok(settings)
+}
+
+pub fn settings_reset(state: &AppState) -> Result<Settings, AppError> {
+    let settings = state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?
+        .reset()?;
+    apply_settings(state, &settings)?;
+    Ok(settings)
+}
+
+/// Persists and applies the backend log level; `None` goes back to `info`.
+pub fn log_set_level(state: &AppState, level: Option<String>) -> Result<(), AppError> {
+    let settings = {
+        let mut settings_service = state
+            .settings_service
+            .lock()
+            .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?;
+        settings_service.set_log_level(level)?;
+        settings_service.settings().clone()
+    };
+    apply_settings(state, &settings)
+}
+
+pub fn log_get_recent(count: Option<usize>) -> Result<Vec<LogEntry>, AppError> {
+    Ok(app_log::recent(count.unwrap_or(200)))
+}
+
+pub fn hotkeys_list(state: &AppState) -> Result<Vec<HotkeyBinding>, AppError> {
+    Ok(state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?
+        .hotkeys())
+}
+
+/// Binds or unbinds one action and announces the changed settings.
+pub fn hotkeys_set(
+    state: &AppState,
+    action: String,
+    chord: Option<String>,
+) -> Result<Vec<HotkeyBinding>, AppError> {
+    let (bindings, settings) = {
+        let mut settings_service = state
+            .settings_service
+            .lock()
+            .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?;
+        let bindings = settings_service.set_hotkey(&action, chord.as_deref())?;
+        (bindings, settings_service.settings().clone())
+    };
+    apply_settings(state, &settings)?;
+    Ok(bindings)
+}
+
+/// Binds an agent RPC call to a hotkey action, or unbinds it when `rpc` is
+/// `None`.
+pub fn hotkeys_set_rpc(
+    state: &AppState,
+    action: String,
+    rpc: Option<HotkeyRpcAction>,
+) -> Result<Vec<HotkeyBinding>, AppError> {
+    if let Some(rpc) = &rpc {
+        ensure_hotkey_rpc_allowed(&rpc.method)?;
+    }
+    let (bindings, settings) = {
+        let mut settings_service = state
+            .settings_service
+            .lock()
+            .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?;
+        let bindings = settings_service.set_hotkey_rpc(&action, rpc)?;
+        (bindings, settings_service.settings().clone())
+    };
+    apply_settings(state, &settings)?;
+    Ok(bindings)
+}
+
+/// Runs the RPC call bound to `action`, emitting `carf://hotkey/rpc-result`
+/// with its outcome.
+pub fn hotkeys_trigger(state: &AppState, action: String) -> Result<Value, AppError> {
+    let rpc = settings_get(state)?
+        .hotkey_rpc
+        .remove(&action)
+        .ok_or_else(|| {
+            AppError::InvalidArgument(format!("Hotkey action {action} has no RPC call bound"))
+        })?;
+    // Bindings saved before the check existed, or edited on disk.
+    ensure_hotkey_rpc_allowed(&rpc.method)?;
+
+    let result = (|| {
+        let session_id = match rpc.session_id.clone() {
+            Some(session_id) => session_id,
+            None => match list_sessions(state)?.as_slice() {
+                [session] => session.id.clone(),
+                [] => return Err(AppError::SessionNotFound("No session attached".to_string())),
+                _ => {
+                    return Err(AppError::InvalidArgument(format!(
+                        "Hotkey action {action} names no session and several are attached"
+                    )))
+                }
+            },
+        };
+        // Job methods wait for their result without holding the service.
+        let value = session_rpc(state, &session_id, &rpc.method, rpc.params.clone())?;
+        Ok((session_id, value))
+    })();
+
+    if audit::is_mutating_rpc(&rpc.method) {
+        let session_id = match &result {
+            Ok((session_id, _)) => Some(session_id.as_str()),
+            Err(_) => rpc.session_id.as_deref(),
+        };
+        audit::record(&rpc.method, session_id, rpc.params.clone(), &result);
+    }
+    let payload = match &result {
+        Ok((session_id, value)) => serde_json::json!({
+            "action": action,
+            "sessionId": session_id,
+            "method": rpc.method,
+            "ok": true,
+            "result": value,
+        }),
+        Err(error) => serde_json::json!({
+            "action": action,
+            "sessionId": rpc.session_id,
+            "method": rpc.method,
+            "ok": false,
+            "error": error.to_string(),
+        }),
+    };
+    state.events.emit("carf://hotkey/rpc-result", payload);
+    result.map(|(_, value)| value)
+}
+
+pub fn settings_export(state: &AppState, path: String) -> Result<(), AppError> {
+    state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?
+        .export(&path)
+}
+
+pub fn settings_import(state: &AppState, path: String) -> Result<Settings, AppError> {
+    let settings = state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?
+        .import(&path)?;
+    apply_settings(state, &settings)?;
+    Ok(settings)
+}
+
+/// Re-reads `settings.json` after it was edited outside CARF.
+pub fn settings_reload(state: &AppState) -> Result<Settings, AppError> {
+    let settings = state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?
+        .reload()?;
+    apply_settings(state, &settings)?;
+    Ok(settings)
+}
+
+pub fn audit_query(filter: Option<AuditFilter>) -> Result<Vec<AuditEntry>, AppError> {
+    audit::query(&filter.unwrap_or_default())
+}
+
+pub fn plugins_list() -> Vec<PluginInfo> {
+    plugins::list_plugins()
+}
+
+pub fn plugin_invoke(
+    state: &AppState,
+    plugin: String,
+    command: String,
+    args: Value,
+) -> Result<Value, AppError> {
+    plugins::invoke(state, &plugin, &command, args)
+}
+
+pub fn list_backups() -> Result<Vec<BackupInfo>, AppError> {
+    backups::list_backups()
+}
+
+/// Restores a backup over its data file. Restored settings are reloaded
+/// right away; other files are read fresh on their next use.
+pub fn restore_backup(state: &AppState, name: String) -> Result<String, AppError> {
+    let source = backups::restore_backup(&name)?;
+    if source == settings::SETTINGS_FILE {
+        settings_reload(state)?;
+    }
+    Ok(source)
+}
+
+/// Opens the OS file manager at one of CARF's files: `data` (the data dir),
+/// `settings`, `variables`, `logs` (message logs), `app-logs` (the backend
+/// log) or `backups`. Returns the path shown.
+pub fn reveal_in_file_manager(state: &AppState, kind: String) -> Result<String, AppError> {
+    let path = match kind.as_str() {
+        "data" => storage::data_dir()?,
+        "settings" | "variables" => {
+            let name = if kind == "settings" {
+                settings::SETTINGS_FILE
+            } else {
+                variables::VARIABLES_FILE
+            };
+            let file = storage::data_file(name)?;
+            if file.exists() {
+                file
+            } else {
+                storage::data_dir()?
+            }
+        }
+        "logs" => {
+            let mut svc = state
+                .frida_service
+                .lock()
+                .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+            PathBuf::from(svc.message_log_status()?.directory)
+        }
+        "app-logs" => app_log::log_dir()?,
+        "backups" => backups::backup_dir()?,
+        other => {
+            return Err(AppError::InvalidArgument(format!(
+                "Unknown location {other}; expected data, settings, variables, logs, app-logs \
+                 or backups"
+            )))
+        }
+    };
+    storage::reveal_in_file_manager(&path)?;
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// One pass of the settings file watcher: emits
+/// `carf://settings/changed-externally` when `settings.json` was edited
+/// outside CARF, plus `carf://settings/changed` if it was reloaded.
+pub fn check_settings_file(state: &AppState) -> Result<(), AppError> {
+    let (change, settings) = {
+        let mut settings_service = state
+            .settings_service
+            .lock()
+            .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?;
+        let Some(change) = settings_service.poll_external_change() else {
+            return Ok(());
+        };
+        let settings = change.reloaded.then(|| settings_service.settings().clone());
+        (change, settings)
+    };
+
+    log::info!("{} changed outside CARF", change.path);
+    if let Some(settings) = settings {
+        apply_settings(state, &settings)?;
+    }
+    state.events.emit(
+        "carf://settings/changed-externally",
+        serde_json::to_value(&change).map_err(|error| AppError::Internal(error.to_string()))?,
+    );
+    Ok(())
+}
+
+/// Pushes new settings to the services that read them and tells the
+/// frontend. Hotkeys, aliases and rules are the frontend's to apply.
+fn apply_settings(state: &AppState, settings: &Settings) -> Result<(), AppError> {
+    settings::apply_log_level(settings.log_level.as_deref());
+    {
+        let mut svc = state
+            .frida_service
+            .lock()
+            .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+        svc.set_timeout_policy(settings.timeouts.clone());
+        svc.set_default_runtime(settings.default_runtime.clone());
+    }
+    state.events.emit(
+        "carf://settings/changed",
+        serde_json::to_value(settings).map_err(|error| AppError::Internal(error.to_string()))?,
+    );
+    Ok(())
+}
+
+pub fn get_timeout_policy(state: &AppState) -> Result<TimeoutPolicy, AppError> {
+    let svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    Ok(svc.timeout_policy())
+}
+
+pub fn set_timeout_policy(state: &AppState, policy: TimeoutPolicy) -> Result<(), AppError> {
+    state
+        .settings_service
+        .lock()
+        .map_err(|_| AppError::Internal("settings_service lock poisoned".to_string()))?
+        .set_timeouts(policy.clone())?;
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    svc.set_timeout_policy(policy);
+    Ok(())
+}
+
+pub fn perf_selftest(options: Option<PerfSelftestOptions>) -> Result<PerfReport, AppError> {
+    Ok(perf::perf_selftest(&options.unwrap_or_default()))
+}
+
+pub fn variable_profiles() -> Result<Vec<String>, AppError> {
+    variables::list_profiles()
+}
+
+pub fn variable_list(profile: String) -> Result<BTreeMap<String, Value>, AppError> {
+    variables::list_variables(&profile)
+}
+
+pub fn variable_get(profile: String, key: String) -> Result<Option<Value>, AppError> {
+    variables::get_variable(&profile, &key)
+}
+
+pub fn variable_snapshot(profile: String) -> Result<variables::ProfileSnapshot, AppError> {
+    variables::snapshot(&profile)
+}
+
+pub fn variable_set(
+    state: &AppState,
+    profile: String,
+    key: String,
+    value: Value,
+    expected_revision: Option<String>,
+) -> Result<String, AppError> {
+    let revision =
+        variables::set_variable(&profile, &key, value.clone(), expected_revision.as_deref())?;
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    variables::sync_to_sessions(&mut svc, &profile, &key, Some(&value))?;
+    Ok(revision)
+}
+
+pub fn variable_delete(
+    state: &AppState,
+    profile: String,
+    key: String,
+    expected_revision: Option<String>,
+) -> Result<String, AppError> {
+    let revision = variables::delete_variable(&profile, &key, expected_revision.as_deref())?;
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    variables::sync_to_sessions(&mut svc, &profile, &key, None)?;
+    Ok(revision)
+}
+
+pub fn variable_merge(
+    state: &AppState,
+    profile: String,
+    base: BTreeMap<String, Value>,
+    ours: BTreeMap<String, Value>,
+    strategy: variables::MergeStrategy,
+) -> Result<variables::MergeResult, AppError> {
+    let result = variables::merge_profile(&profile, &base, &ours, strategy)?;
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    for key in &result.changed {
+        variables::sync_to_sessions(&mut svc, &profile, key, result.variables.get(key))?;
+    }
+    Ok(result)
+}
+
+/// Cancels a blocking host operation such as an attach, or, when
+/// `session_id` is given, an agent-side operation such as a memory scan.
+/// Returns `false` when nothing with that id is running.
+pub fn cancel_operation(
+    state: &AppState,
+    operation_id: String,
+    session_id: Option<String>,
+) -> Result<bool, AppError> {
+    // Checked before taking `frida_service`: a blocked attach holds it.
+    if state.operations.cancel(&operation_id) {
+        return Ok(true);
+    }
+    let Some(session_id) = session_id else {
+        return Ok(false);
+    };
+
+    let mut svc = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))?;
+    let response = svc.rpc_call(
+        &session_id,
+        "cancelOperation",
+        serde_json::json!({ "operationId": operation_id }),
+    )?;
+    Ok(response
+        .get("cancelled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false))
+}
+
+/// Rebases the target's saved bookmarks in the new process and emits them
+/// as `carf://bookmarks/resolved`. Targets without bookmarks emit nothing.
+fn emit_resolved_bookmarks(state: &AppState, session: &SessionInfo) {
+    let profile = variables::profile_for(session);
+    let resolved = state
+        .frida_service
+        .lock()
+        .map_err(|_| AppError::Internal("frida_service lock poisoned".to_string()))
+        .and_then(|mut svc| bookmarks::resolve_profile(&mut svc, &session.id, &profile));
+    match resolved {
+        Ok(resolved) if resolved.is_empty() => {}
+        Ok(resolved) => state.events.emit(
+            "carf://bookmarks/resolved",
+            serde_json::json!({
+                "sessionId": session.id,
+                "profile": profile,
+                "bookmarks": resolved,
+            }),
+        ),
+        Err(error) => log::warn!("Failed to resolve bookmarks for {profile}: {error}"),
+    }
 }
 
 fn emit_console_message(
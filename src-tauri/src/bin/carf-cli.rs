@@ -0,0 +1,4 @@
+fn main() -> anyhow::Result<()> {
+    carf_lib::init_logging();
+    carf_lib::run_cli()
+}
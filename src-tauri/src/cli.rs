@@ -0,0 +1,205 @@
+//! `carf-cli`: attaches through the same backend as the app, loads a script
+//! and streams every event to stdout as NDJSON until the session ends. No
+//! webview is started, so it runs in CI and over SSH.
+
+use anyhow::{anyhow, bail, Context};
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::api;
+use crate::services::frida::{AttachOptions, SpawnOptions};
+use crate::state::{AppState, BridgeEvent};
+
+const USAGE: &str = "\
+Usage: carf-cli [OPTIONS] <TARGET>
+
+Targets (one of):
+  --pid <PID>              Attach to a running process
+  --name <NAME>            Attach to a running process by name
+  --spawn <IDENTIFIER>     Spawn a program or app and attach to it
+
+Options:
+  --device <ID>            Device to use [default: local]
+  --script <PATH>          Load a user script from a file
+  --bundled <ID>           Load a bundled script (see --list-bundled)
+  --param <JSON>           Parameters passed to --script at load time
+  --runtime <qjs|v8>       Script runtime
+  --wait                   With --name, wait for the process to start
+  --list-bundled           Print the bundled scripts and exit
+  -h, --help               Print this help
+
+Events are printed one JSON object per line: {\"event\": ..., \"payload\": ...}.
+The CLI exits when the session detaches.";
+
+/// Parsed `carf-cli` arguments.
+#[derive(Debug, Default)]
+struct CliArgs {
+    device: Option<String>,
+    pid: Option<u32>,
+    name: Option<String>,
+    spawn: Option<String>,
+    script: Option<String>,
+    bundled: Option<String>,
+    params: Option<Value>,
+    runtime: Option<String>,
+    wait: bool,
+    list_bundled: bool,
+}
+
+pub fn run() -> anyhow::Result<()> {
+    let Some(args) = parse_args(std::env::args().skip(1))? else {
+        println!("{USAGE}");
+        return Ok(());
+    };
+
+    if args.list_bundled {
+        for script in api::list_bundled_scripts() {
+            println!("{}", serde_json::to_string(&script)?);
+        }
+        return Ok(());
+    }
+
+    let state = AppState::new()?;
+    // Subscribed before attaching so nothing the script sends on load is
+    // missed.
+    let mut receiver = state.events.subscribe();
+    let device_id = args.device.clone().unwrap_or_else(|| "local".to_string());
+
+    let session = match &args.spawn {
+        Some(identifier) => api::spawn_and_attach(
+            &state,
+            device_id,
+            SpawnOptions {
+                identifier: identifier.clone(),
+                argv: None,
+                envp: None,
+                cwd: None,
+                stdio: None,
+                // Resumed below, once every script is in.
+                auto_resume: Some(false),
+                realm: None,
+                persist_timeout: None,
+                runtime: args.runtime.clone(),
+                enable_child_gating: None,
+                script_path: args.script.clone(),
+                script_parameters: args.params.clone(),
+                max_duration_ms: None,
+                detach_at: None,
+            },
+        ),
+        None => {
+            let target = match (args.pid, &args.name) {
+                (Some(pid), _) => Value::from(pid),
+                (None, Some(name)) => Value::String(name.clone()),
+                (None, None) => bail!("no target given\n\n{USAGE}"),
+            };
+            let options = AttachOptions {
+                runtime: args.runtime.clone(),
+                script_path: args.script.clone(),
+                script_parameters: args.params.clone(),
+                ..AttachOptions::for_target(target)
+            };
+            if args.wait {
+                api::attach_when_running(&state, device_id, options, None)
+            } else {
+                api::attach(&state, device_id, options)
+            }
+        }
+    }
+    .context("attach failed")?;
+
+    if let Some(bundled) = &args.bundled {
+        api::load_bundled_script(&state, session.id.clone(), bundled.clone())
+            .with_context(|| format!("failed to load bundled script {bundled}"))?;
+    }
+    if args.spawn.is_some() {
+        api::resume(&state, session.id.clone()).context("resume failed")?;
+    }
+    print_event("carf://cli/attached", serde_json::to_value(&session)?);
+
+    loop {
+        let event = match receiver.blocking_recv() {
+            Ok(event) => event,
+            Err(RecvError::Lagged(skipped)) => {
+                print_event("carf://cli/lagged", json!({ "skipped": skipped }));
+                continue;
+            }
+            Err(RecvError::Closed) => return Ok(()),
+        };
+        for event in unbatch(event) {
+            let detached = event.name == "carf://session/detached"
+                && event.payload.get("sessionId").and_then(Value::as_str)
+                    == Some(session.id.as_str());
+            print_event(&event.name, event.payload);
+            if detached {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// `None` when help was asked for.
+fn parse_args(mut args: impl Iterator<Item = String>) -> anyhow::Result<Option<CliArgs>> {
+    let mut parsed = CliArgs::default();
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || -> anyhow::Result<String> {
+            inline
+                .map(str::to_string)
+                .or_else(|| args.next())
+                .ok_or_else(|| anyhow!("{flag} needs a value"))
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--device" => parsed.device = Some(value()?),
+            "--pid" => {
+                let pid = value()?;
+                parsed.pid = Some(pid.parse().with_context(|| format!("bad pid {pid}"))?);
+            }
+            "--name" => parsed.name = Some(value()?),
+            "--spawn" => parsed.spawn = Some(value()?),
+            "--script" => parsed.script = Some(value()?),
+            "--bundled" => parsed.bundled = Some(value()?),
+            "--param" => {
+                let params = value()?;
+                parsed.params =
+                    Some(serde_json::from_str(&params).context("--param must be JSON")?);
+            }
+            "--runtime" => parsed.runtime = Some(value()?),
+            "--wait" => parsed.wait = true,
+            "--list-bundled" => parsed.list_bundled = true,
+            other => bail!("unknown argument {other}\n\n{USAGE}"),
+        }
+    }
+
+    let targets = [
+        parsed.pid.is_some(),
+        parsed.name.is_some(),
+        parsed.spawn.is_some(),
+    ];
+    if targets.iter().filter(|given| **given).count() > 1 {
+        bail!("give only one of --pid, --name and --spawn");
+    }
+    Ok(Some(parsed))
+}
+
+/// Script events reach the hub in `carf://events/batch` wrappers; the CLI
+/// prints them one by one like the frontend's `listen` sees them.
+fn unbatch(event: BridgeEvent) -> Vec<BridgeEvent> {
+    if event.name != "carf://events/batch" {
+        return vec![event];
+    }
+    event
+        .payload
+        .get("events")
+        .cloned()
+        .and_then(|events| serde_json::from_value(events).ok())
+        .unwrap_or_default()
+}
+
+fn print_event(name: &str, payload: Value) {
+    println!("{}", json!({ "event": name, "payload": payload }));
+}
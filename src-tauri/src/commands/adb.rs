@@ -1,8 +1,18 @@
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
+use crate::api;
 use crate::error::AppError;
-use crate::services::adb::{AdbDevice, DeviceProps};
-use crate::state::AppState;
+use crate::services::adb::{
+    AdbDevice, AdbService, DeviceProps, FridaServerProgress, FridaServerStatus,
+};
+use crate::services::file_transfer::FileTransfer;
+use crate::state::{AppState, EventHub};
+
+fn emit_frida_server_progress(events: &EventHub, progress: FridaServerProgress) {
+    if let Ok(payload) = serde_json::to_value(progress) {
+        events.emit("carf://adb/frida-server/progress", payload);
+    }
+}
 
 /// Lists all devices visible to the local `adb` daemon.
 #[tauri::command]
@@ -27,22 +37,122 @@ pub fn adb_device_props(
     svc.device_props(&serial)
 }
 
-/// Pushes a frida-server binary for the given `version`/`arch` to the device.
+/// Pushes the frida-server binary for `version`/`arch` (defaulting to the
+/// bundled Frida version and the device's ABI), preferring a binary downloaded
+/// with `server_binary_download` over the host temp directory. Emits
+/// `carf://adb/frida-server/progress` while it streams.
 #[tauri::command]
 pub fn adb_push_frida_server(
     state: State<'_, AppState>,
     serial: String,
-    version: String,
-    arch: String,
+    version: Option<String>,
+    arch: Option<String>,
 ) -> Result<(), AppError> {
     let svc = state
         .adb_service
         .lock()
         .map_err(|_| AppError::Internal("adb_service lock poisoned".to_string()))?;
-    svc.push_frida_server(&serial, &version, &arch)
+    let version = version.unwrap_or_else(|| frida::Frida::version().to_string());
+    let arch = match arch {
+        Some(arch) => arch,
+        None => svc.frida_server_arch(&serial)?,
+    };
+    let local_path = AdbService::local_frida_server_path(&version, &arch);
+    svc.push_frida_server(&serial, &local_path, |bytes_sent, total_bytes| {
+        emit_frida_server_progress(
+            &state.events,
+            FridaServerProgress {
+                serial: serial.clone(),
+                stage: "push".to_string(),
+                bytes_sent,
+                total_bytes,
+                message: format!("Pushing {}", local_path.display()),
+            },
+        );
+    })
+}
+
+/// Copies a host file to `remote_path` on the device, emitting
+/// `carf://file-transfer/progress` while it streams. `transfer_id`
+/// (generated when omitted) can be passed to `cancel_operation`.
+#[tauri::command]
+pub async fn adb_push_file(
+    app: AppHandle,
+    serial: String,
+    local_path: String,
+    remote_path: String,
+    transfer_id: Option<String>,
+) -> Result<FileTransfer, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::adb_push_file(
+            &app.state::<AppState>(),
+            serial,
+            local_path,
+            remote_path,
+            transfer_id,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Copies `remote_path` from the device to a host file, emitting
+/// `carf://file-transfer/progress` while it streams. `transfer_id`
+/// (generated when omitted) can be passed to `cancel_operation`.
+#[tauri::command]
+pub async fn adb_pull_file(
+    app: AppHandle,
+    serial: String,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+) -> Result<FileTransfer, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::adb_pull_file(
+            &app.state::<AppState>(),
+            serial,
+            remote_path,
+            local_path,
+            transfer_id,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Pushes a matching frida-server (or `binary_path`), restarts it as root and
+/// waits for its port, emitting `carf://adb/frida-server/progress` per step.
+#[tauri::command]
+pub fn adb_deploy_frida_server(
+    state: State<'_, AppState>,
+    serial: String,
+    binary_path: Option<String>,
+    version: Option<String>,
+) -> Result<FridaServerStatus, AppError> {
+    let svc = state
+        .adb_service
+        .lock()
+        .map_err(|_| AppError::Internal("adb_service lock poisoned".to_string()))?;
+    let version = version.unwrap_or_else(|| frida::Frida::version().to_string());
+    svc.deploy_frida_server(&serial, binary_path.as_deref(), &version, |progress| {
+        emit_frida_server_progress(&state.events, progress);
+    })
+}
+
+/// Reports whether frida-server is running and listening on its port.
+#[tauri::command]
+pub fn adb_frida_server_status(
+    state: State<'_, AppState>,
+    serial: String,
+) -> Result<FridaServerStatus, AppError> {
+    let svc = state
+        .adb_service
+        .lock()
+        .map_err(|_| AppError::Internal("adb_service lock poisoned".to_string()))?;
+    svc.frida_server_status(&serial)
 }
 
-/// Starts frida-server in the background on the device.
+/// Starts frida-server as root in the background on the device.
 #[tauri::command]
 pub fn adb_start_frida_server(state: State<'_, AppState>, serial: String) -> Result<(), AppError> {
     let svc = state
@@ -1,5 +1,5 @@
 use serde::Serialize;
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 use crate::api;
 use crate::error::AppError;
@@ -21,13 +21,17 @@ struct RpcChunkEvent {
 /// `params` is forwarded as-is to the agent's RPC handler.
 /// Returns the JSON value produced by the agent method.
 #[tauri::command]
-pub fn rpc_call(
-    state: State<'_, AppState>,
+pub async fn rpc_call(
+    app: AppHandle,
     session_id: String,
     method: String,
     params: serde_json::Value,
 ) -> Result<serde_json::Value, AppError> {
-    api::rpc_call(&state, session_id, method, params)
+    tokio::task::spawn_blocking(move || {
+        api::rpc_call(&app.state::<AppState>(), session_id, method, params)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
 }
 
 #[tauri::command]
@@ -0,0 +1,11 @@
+use crate::api;
+use crate::error::AppError;
+use crate::services::audit::{AuditEntry, AuditFilter};
+
+/// Returns audit log entries (attach, spawn, kill, script loads, memory
+/// writes, hooks) matching `filter`, oldest first. The log is append-only
+/// `audit.ndjson` in the data dir.
+#[tauri::command]
+pub fn audit_query(filter: Option<AuditFilter>) -> Result<Vec<AuditEntry>, AppError> {
+    api::audit_query(filter)
+}
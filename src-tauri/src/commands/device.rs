@@ -2,7 +2,10 @@ use tauri::State;
 
 use crate::api;
 use crate::error::AppError;
-use crate::services::frida::DeviceInfo;
+use crate::services::frida::{
+    DeviceInfo, DeviceSystemInfo, PortForward, ProcessInfo, RemoteDeviceOptions,
+};
+use crate::services::remote_devices::RemoteEndpoint;
 use crate::state::AppState;
 
 /// Lists all Frida-visible devices (local, USB, remote).
@@ -11,13 +14,15 @@ pub fn list_devices(state: State<'_, AppState>) -> Result<Vec<DeviceInfo>, AppEr
     api::list_devices(&state)
 }
 
-/// Connects to a remote Frida device at the given TCP address (host:port).
+/// Connects to a remote Frida device at the given TCP address (host:port),
+/// with the token and TLS certificate it was started with, if any.
 #[tauri::command]
 pub fn add_remote_device(
     state: State<'_, AppState>,
     address: String,
+    options: Option<RemoteDeviceOptions>,
 ) -> Result<DeviceInfo, AppError> {
-    api::add_remote_device(&state, address)
+    api::add_remote_device(&state, address, options)
 }
 
 /// Removes a previously added remote device.
@@ -29,6 +34,49 @@ pub fn remove_remote_device(
     api::remove_remote_device(&state, address)
 }
 
+/// Lists the remote devices reconnected at startup. Every successful
+/// `add_remote_device` is saved here.
+#[tauri::command]
+pub fn remote_endpoints_list() -> Result<Vec<RemoteEndpoint>, AppError> {
+    api::remote_endpoints_list()
+}
+
+/// Renames a saved remote device or replaces the credentials it reconnects
+/// with.
+#[tauri::command]
+pub fn remote_endpoint_update(
+    address: String,
+    nickname: Option<String>,
+    options: Option<RemoteDeviceOptions>,
+) -> Result<RemoteEndpoint, AppError> {
+    api::remote_endpoint_update(address, nickname, options)
+}
+
+/// Stops reconnecting a remote device at startup; does not disconnect it.
+#[tauri::command]
+pub fn remote_endpoint_remove(address: String) -> Result<bool, AppError> {
+    api::remote_endpoint_remove(address)
+}
+
+/// Connects to a frida-portal control endpoint (host:port) and registers it as a device.
+#[tauri::command]
+pub fn add_portal(
+    state: State<'_, AppState>,
+    address: String,
+    options: Option<RemoteDeviceOptions>,
+) -> Result<DeviceInfo, AppError> {
+    api::add_portal(&state, address, options)
+}
+
+/// Lists the nodes (joined processes) aggregated by a portal device.
+#[tauri::command]
+pub fn list_portal_nodes(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<Vec<ProcessInfo>, AppError> {
+    api::list_portal_nodes(&state, device_id)
+}
+
 /// Returns detailed info for a single device by its Frida device id.
 #[tauri::command]
 pub fn get_device_info(
@@ -37,3 +85,60 @@ pub fn get_device_info(
 ) -> Result<DeviceInfo, AppError> {
     api::get_device_info(&state, device_id)
 }
+
+/// Pairs with an iOS device over USB, waiting up to `timeout_ms` for the user
+/// to trust this computer. Returns the device with its final pairing state.
+#[tauri::command]
+pub fn pair_device(
+    state: State<'_, AppState>,
+    device_id: String,
+    timeout_ms: Option<u64>,
+) -> Result<DeviceInfo, AppError> {
+    api::pair_device(&state, device_id, timeout_ms)
+}
+
+/// Removes this computer's pairing record from an iOS device.
+#[tauri::command]
+pub fn unpair_device(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<DeviceInfo, AppError> {
+    api::unpair_device(&state, device_id)
+}
+
+/// Opens a listener on an ephemeral loopback port whose connections are
+/// tunneled to `remote_port` on the device. Returns the forward with the
+/// bound local port.
+#[tauri::command]
+pub fn device_forward_port(
+    state: State<'_, AppState>,
+    device_id: String,
+    remote_port: u16,
+) -> Result<PortForward, AppError> {
+    api::device_forward_port(&state, device_id, remote_port)
+}
+
+/// Closes a port forward and any connections it is serving.
+#[tauri::command]
+pub fn device_stop_port_forward(
+    state: State<'_, AppState>,
+    forward_id: String,
+) -> Result<(), AppError> {
+    api::device_stop_port_forward(&state, forward_id)
+}
+
+/// Lists the port forwards that are currently open.
+#[tauri::command]
+pub fn device_list_port_forwards(state: State<'_, AppState>) -> Result<Vec<PortForward>, AppError> {
+    api::device_list_port_forwards(&state)
+}
+
+/// Queries the device's OS name and version, architecture and pointer size,
+/// Android API level, access level and whether it is jailbroken or rooted.
+#[tauri::command]
+pub fn query_device(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<DeviceSystemInfo, AppError> {
+    api::query_device(&state, device_id)
+}
@@ -0,0 +1,52 @@
+use tauri::{AppHandle, Manager};
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::file_transfer::FileTransfer;
+use crate::state::AppState;
+
+/// Copies a host file into the target process's file system through the
+/// agent, emitting `carf://file-transfer/progress` per chunk.
+#[tauri::command]
+pub async fn file_push(
+    app: AppHandle,
+    session_id: String,
+    local_path: String,
+    remote_path: String,
+    transfer_id: Option<String>,
+) -> Result<FileTransfer, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::file_push(
+            &app.state::<AppState>(),
+            session_id,
+            local_path,
+            remote_path,
+            transfer_id,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Copies a file from the target process's file system to the host through
+/// the agent, emitting `carf://file-transfer/progress` per chunk.
+#[tauri::command]
+pub async fn file_pull(
+    app: AppHandle,
+    session_id: String,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+) -> Result<FileTransfer, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::file_pull(
+            &app.state::<AppState>(),
+            session_id,
+            remote_path,
+            local_path,
+            transfer_id,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
@@ -0,0 +1,69 @@
+use serde_json::Value;
+use tauri::State;
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::frida::DeviceInfo;
+use crate::services::gadget::{
+    self, ApkPatchOptions, GadgetConfigOptions, GadgetProgress, PatchedApk, GADGET_DEFAULT_PORT,
+};
+use crate::state::{AppState, EventHub};
+
+fn emit_gadget_progress(events: &EventHub, progress: GadgetProgress) {
+    if let Ok(payload) = serde_json::to_value(progress) {
+        events.emit("carf://gadget/progress", payload);
+    }
+}
+
+/// Builds a Frida Gadget config for listen or script mode, ready to be saved
+/// as `libfrida-gadget.config.so`.
+#[tauri::command]
+pub fn gadget_config(options: GadgetConfigOptions) -> Result<Value, AppError> {
+    gadget::gadget_config(&options)
+}
+
+/// Repackages an APK to load Frida Gadget at startup and signs it with a
+/// debug key, emitting `carf://gadget/progress` per step. Needs apktool and
+/// the Android SDK build-tools on `PATH`.
+#[tauri::command]
+pub fn gadget_patch_apk(
+    state: State<'_, AppState>,
+    options: ApkPatchOptions,
+    version: Option<String>,
+) -> Result<PatchedApk, AppError> {
+    let version = version.unwrap_or_else(|| frida::Frida::version().to_string());
+    gadget::patch_apk(&options, &version, |progress| {
+        emit_gadget_progress(&state.events, progress);
+    })
+}
+
+/// Adds a gadget in listen mode as a remote device. With `serial`, the
+/// gadget's port on that Android device is first forwarded through adb;
+/// otherwise `address` must be reachable directly.
+#[tauri::command]
+pub fn gadget_connect(
+    state: State<'_, AppState>,
+    serial: Option<String>,
+    address: Option<String>,
+    port: Option<u16>,
+) -> Result<DeviceInfo, AppError> {
+    let port = port.unwrap_or(GADGET_DEFAULT_PORT);
+    let address = match (serial, address) {
+        (Some(serial), _) => {
+            let local_port = state
+                .adb_service
+                .lock()
+                .map_err(|_| AppError::Internal("adb_service lock poisoned".to_string()))?
+                .forward(&serial, port)?;
+            format!("127.0.0.1:{local_port}")
+        }
+        (None, Some(address)) if address.contains(':') => address,
+        (None, Some(address)) => format!("{address}:{port}"),
+        (None, None) => {
+            return Err(AppError::InvalidArgument(
+                "gadget_connect needs a device serial or an address".to_string(),
+            ))
+        }
+    };
+    api::add_remote_device(&state, address, None)
+}
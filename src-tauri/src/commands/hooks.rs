@@ -0,0 +1,40 @@
+use tauri::State;
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::hooks::{HookInfo, HookOptions};
+use crate::state::AppState;
+
+/// Installs a native Interceptor hook on `module!symbol` or `module+offset`.
+///
+/// When `module` is omitted the symbol is resolved globally, and an offset is
+/// treated as an absolute address.
+#[tauri::command]
+pub fn hook_add(
+    state: State<'_, AppState>,
+    session_id: String,
+    module: Option<String>,
+    symbol_or_offset: String,
+    options: Option<HookOptions>,
+) -> Result<HookInfo, AppError> {
+    api::hook_add(&state, session_id, module, symbol_or_offset, options)
+}
+
+/// Removes a native hook previously installed with `hook_add`.
+#[tauri::command]
+pub fn hook_remove(
+    state: State<'_, AppState>,
+    session_id: String,
+    hook_id: String,
+) -> Result<(), AppError> {
+    api::hook_remove(&state, session_id, hook_id)
+}
+
+/// Lists the native hooks installed in the session, including hit counts.
+#[tauri::command]
+pub fn hook_list(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<HookInfo>, AppError> {
+    api::hook_list(&state, session_id)
+}
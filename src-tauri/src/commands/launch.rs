@@ -0,0 +1,26 @@
+use tauri::{AppHandle, Manager, State};
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::launch::{self, LaunchOptions};
+use crate::state::AppState;
+
+/// Returns the workspace and kiosk flag CARF was started with.
+#[tauri::command]
+pub fn get_launch_options(launch: State<'_, LaunchOptions>) -> LaunchOptions {
+    launch.inner().clone()
+}
+
+/// Attaches what a `carf://` link asks for, once the user has confirmed it.
+/// Runs in the background; progress arrives as the usual session and console
+/// events.
+#[tauri::command]
+pub fn run_deep_link(app: AppHandle, url: String) -> Result<(), AppError> {
+    let workspace = launch::parse_deep_link(&url)?;
+
+    std::thread::spawn(move || {
+        let state = app.state::<AppState>();
+        api::auto_attach_workspace(&state, &workspace);
+    });
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use tauri::State;
+
+use crate::error::AppError;
+use crate::services::local_server::{LocalServerOptions, LocalServerStatus};
+use crate::state::AppState;
+
+/// Starts a frida-server on this machine, optionally as root via the
+/// desktop's password prompt.
+#[tauri::command]
+pub fn local_server_start(
+    state: State<'_, AppState>,
+    options: Option<LocalServerOptions>,
+) -> Result<LocalServerStatus, AppError> {
+    let mut svc = state
+        .local_server
+        .lock()
+        .map_err(|_| AppError::Internal("local_server lock poisoned".to_string()))?;
+    svc.start(options.unwrap_or_default())
+}
+
+/// Stops the frida-server started with `local_server_start`.
+#[tauri::command]
+pub fn local_server_stop(state: State<'_, AppState>) -> Result<LocalServerStatus, AppError> {
+    let mut svc = state
+        .local_server
+        .lock()
+        .map_err(|_| AppError::Internal("local_server lock poisoned".to_string()))?;
+    svc.stop()
+}
+
+/// Reports whether the local frida-server is running and reachable.
+#[tauri::command]
+pub fn local_server_status(state: State<'_, AppState>) -> Result<LocalServerStatus, AppError> {
+    let mut svc = state
+        .local_server
+        .lock()
+        .map_err(|_| AppError::Internal("local_server lock poisoned".to_string()))?;
+    Ok(svc.status())
+}
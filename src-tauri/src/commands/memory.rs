@@ -0,0 +1,402 @@
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::bookmarks::{Bookmark, ResolvedBookmark, ResolvedLocation};
+use crate::services::inspector::{ByteInspection, TypeGuess};
+use crate::services::memory::{
+    BatchWriteReport, FreezeState, FrozenValue, Hexdump, HexdumpOptions, MappedFileSlice,
+    MemoryWatch, ValueWrite,
+};
+use crate::services::memory_dump::MemoryDump;
+use crate::services::pointer_scan::{
+    PointerMapInfo, PointerScanOptions, PointerScanResult, ResolvedPointerChain, SavedPointerChain,
+};
+use crate::services::scanner::ScanPreset;
+use crate::services::typed_memory::{Endianness, TypedReadOptions, TypedValue, TypedValueType};
+use crate::services::value_format::ValueFormat;
+use crate::services::value_snapshots::{SnapshotComparison, SnapshotEntry, ValueSnapshotInfo};
+use crate::state::AppState;
+
+/// Decodes the bytes at `address` as integers of every width and endianness,
+/// floats, a pointer, timestamps and strings for the data inspector panel.
+#[tauri::command]
+pub fn inspect_bytes(
+    state: State<'_, AppState>,
+    session_id: String,
+    address: String,
+) -> Result<ByteInspection, AppError> {
+    api::inspect_bytes(&state, session_id, address)
+}
+
+/// Samples the value at `address` briefly and returns ranked type guesses
+/// (integer, float, pointer, string) to speed up triage of scan results.
+#[tauri::command]
+pub async fn guess_type(
+    app: AppHandle,
+    session_id: String,
+    address: String,
+) -> Result<TypeGuess, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::guess_type(&app.state::<AppState>(), session_id, address)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Writes many typed values in one agent round-trip. With `atomic`, a failed
+/// write reverts the ones already applied.
+#[tauri::command]
+pub fn write_values_batch(
+    state: State<'_, AppState>,
+    session_id: String,
+    writes: Vec<ValueWrite>,
+    atomic: Option<bool>,
+) -> Result<BatchWriteReport, AppError> {
+    api::write_values_batch(&state, session_id, writes, atomic.unwrap_or(false))
+}
+
+/// Records the current values at `entries` (e.g. scan results) as a named
+/// snapshot in the target, replacing one with the same name.
+#[tauri::command]
+pub fn scan_snapshot_save(
+    state: State<'_, AppState>,
+    session_id: String,
+    name: String,
+    entries: Vec<SnapshotEntry>,
+) -> Result<ValueSnapshotInfo, AppError> {
+    api::scan_snapshot_save(&state, session_id, name, entries)
+}
+
+/// Lists the value snapshots taken in the session's process.
+#[tauri::command]
+pub fn scan_snapshots_list(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<ValueSnapshotInfo>, AppError> {
+    api::scan_snapshots_list(&state, session_id)
+}
+
+/// Deletes a value snapshot. Returns `false` when there was none by `name`.
+#[tauri::command]
+pub fn scan_snapshot_delete(
+    state: State<'_, AppState>,
+    session_id: String,
+    name: String,
+) -> Result<bool, AppError> {
+    api::scan_snapshot_delete(&state, session_id, name)
+}
+
+/// Compares two value snapshots into changed (with deltas), unchanged,
+/// added and removed addresses.
+#[tauri::command]
+pub fn scan_snapshot_compare(
+    state: State<'_, AppState>,
+    session_id: String,
+    before: String,
+    after: String,
+) -> Result<SnapshotComparison, AppError> {
+    api::scan_snapshot_compare(&state, session_id, before, after)
+}
+
+/// Reads a typed value at `address` using the target's pointer size and
+/// byte order. Strings and byte arrays take a `length` in the options.
+#[tauri::command]
+pub fn memory_read_typed(
+    state: State<'_, AppState>,
+    session_id: String,
+    address: String,
+    value_type: TypedValueType,
+    options: Option<TypedReadOptions>,
+) -> Result<TypedValue, AppError> {
+    api::memory_read_typed(&state, session_id, address, value_type, options)
+}
+
+/// Encodes `value` as `value_type` for the target and writes it at
+/// `address`, returning what was written.
+#[tauri::command]
+pub fn memory_write_typed(
+    state: State<'_, AppState>,
+    session_id: String,
+    address: String,
+    value_type: TypedValueType,
+    value: Value,
+    endianness: Option<Endianness>,
+) -> Result<TypedValue, AppError> {
+    api::memory_write_typed(&state, session_id, address, value_type, value, endianness)
+}
+
+/// Keeps typed values frozen from inside the agent, re-checked every
+/// `interval_ms` (default 1ms) and written back whenever the target changes
+/// them.
+#[tauri::command]
+pub fn freeze_values(
+    state: State<'_, AppState>,
+    session_id: String,
+    values: Vec<ValueWrite>,
+    interval_ms: Option<u32>,
+) -> Result<Vec<FrozenValue>, AppError> {
+    api::freeze_values(&state, session_id, values, interval_ms)
+}
+
+/// Unfreezes the given entries, or all of them when `ids` is omitted.
+#[tauri::command]
+pub fn unfreeze_values(
+    state: State<'_, AppState>,
+    session_id: String,
+    ids: Option<Vec<String>>,
+) -> Result<usize, AppError> {
+    api::unfreeze_values(&state, session_id, ids)
+}
+
+/// Lists frozen values with how often each was restored.
+#[tauri::command]
+pub fn list_frozen_values(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<FreezeState, AppError> {
+    api::list_frozen_values(&state, session_id)
+}
+
+/// Watches `size` bytes at `address` for `read`, `write` or `execute`
+/// accesses, emitting `carf://memory/watch-hit` with the accessing
+/// instruction and a backtrace for each hit.
+#[tauri::command]
+pub fn memory_watch(
+    state: State<'_, AppState>,
+    session_id: String,
+    address: String,
+    size: usize,
+    on: String,
+) -> Result<MemoryWatch, AppError> {
+    api::memory_watch(&state, session_id, address, size, on)
+}
+
+/// Removes a memory watch.
+#[tauri::command]
+pub fn memory_unwatch(
+    state: State<'_, AppState>,
+    session_id: String,
+    watch_id: String,
+) -> Result<MemoryWatch, AppError> {
+    api::memory_unwatch(&state, session_id, watch_id)
+}
+
+/// Lists active memory watches with their hit counts.
+#[tauri::command]
+pub fn memory_watches_list(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<MemoryWatch>, AppError> {
+    api::memory_watches_list(&state, session_id)
+}
+
+/// Lists the built-in scan presets together with the user's own.
+#[tauri::command]
+pub fn scan_presets(state: State<'_, AppState>) -> Result<Vec<ScanPreset>, AppError> {
+    api::scan_presets(&state)
+}
+
+/// Saves a user scan preset, replacing one with the same name.
+#[tauri::command]
+pub fn scan_preset_save(state: State<'_, AppState>, preset: ScanPreset) -> Result<(), AppError> {
+    api::scan_preset_save(&state, preset)
+}
+
+/// Deletes a user scan preset; returns false when none had that name.
+#[tauri::command]
+pub fn scan_preset_delete(state: State<'_, AppState>, name: String) -> Result<bool, AppError> {
+    api::scan_preset_delete(&state, name)
+}
+
+/// Scans for `values` using the named preset's value type, layout, alignment
+/// and memory filters. Returns the same response as the `scanMemory` RPC.
+#[tauri::command]
+pub fn scan_with_preset(
+    state: State<'_, AppState>,
+    session_id: String,
+    preset: String,
+    values: Vec<Value>,
+) -> Result<Value, AppError> {
+    api::scan_with_preset(&state, session_id, preset, values)
+}
+
+/// Finds pointer chains from static module addresses to `options.target`,
+/// reusing the session's last pointer map unless `options.refresh` is set.
+#[tauri::command]
+pub async fn pointer_scan(
+    app: AppHandle,
+    session_id: String,
+    options: PointerScanOptions,
+) -> Result<PointerScanResult, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::pointer_scan(&app.state::<AppState>(), session_id, options)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Takes a fresh pointer map of the session's writable memory.
+#[tauri::command]
+pub fn pointer_map_snapshot(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<PointerMapInfo, AppError> {
+    api::pointer_map_snapshot(&state, session_id)
+}
+
+/// Lists the bookmarks saved for `profile`.
+#[tauri::command]
+pub fn bookmarks_list(profile: String) -> Result<Vec<Bookmark>, AppError> {
+    api::bookmarks_list(profile)
+}
+
+/// Saves a bookmark for `profile`, replacing one with the same name. Its
+/// `location` must be `module!offset` or a `0x` address.
+#[tauri::command]
+pub fn bookmark_save(profile: String, bookmark: Bookmark) -> Result<(), AppError> {
+    api::bookmark_save(profile, bookmark)
+}
+
+/// Deletes a bookmark; returns false when none had that name.
+#[tauri::command]
+pub fn bookmark_delete(profile: String, name: String) -> Result<bool, AppError> {
+    api::bookmark_delete(profile, name)
+}
+
+/// Rebases the session target's bookmarks against its current module bases.
+#[tauri::command]
+pub fn bookmarks_resolve(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<ResolvedBookmark>, AppError> {
+    api::bookmarks_resolve(&state, session_id)
+}
+
+/// Resolves `module!offset` locations to addresses in the session.
+#[tauri::command]
+pub fn address_resolve(
+    state: State<'_, AppState>,
+    session_id: String,
+    locations: Vec<String>,
+) -> Result<Vec<ResolvedLocation>, AppError> {
+    api::address_resolve(&state, session_id, locations)
+}
+
+/// Converts addresses to `module!offset` locations; addresses outside
+/// every module are returned unchanged.
+#[tauri::command]
+pub fn address_to_location(
+    state: State<'_, AppState>,
+    session_id: String,
+    addresses: Vec<String>,
+) -> Result<Vec<String>, AppError> {
+    api::address_to_location(&state, session_id, addresses)
+}
+
+/// Lists the pointer chains saved for `profile`.
+#[tauri::command]
+pub fn pointer_chains_list(profile: String) -> Result<Vec<SavedPointerChain>, AppError> {
+    api::pointer_chains_list(profile)
+}
+
+/// Saves a pointer chain for `profile`, replacing one with the same name.
+#[tauri::command]
+pub fn pointer_chain_save(profile: String, chain: SavedPointerChain) -> Result<(), AppError> {
+    api::pointer_chain_save(profile, chain)
+}
+
+/// Deletes a saved pointer chain; returns false when none had that name.
+#[tauri::command]
+pub fn pointer_chain_delete(profile: String, name: String) -> Result<bool, AppError> {
+    api::pointer_chain_delete(profile, name)
+}
+
+/// Follows the session target's saved chains (or only `names`) in its
+/// current process.
+#[tauri::command]
+pub fn pointer_chains_resolve(
+    state: State<'_, AppState>,
+    session_id: String,
+    names: Option<Vec<String>>,
+) -> Result<Vec<ResolvedPointerChain>, AppError> {
+    api::pointer_chains_resolve(&state, session_id, names)
+}
+
+/// Reads `size` bytes (default 4096) at `address` from a read-only file
+/// mapping and from its backing file on disk, listing where they differ.
+#[tauri::command]
+pub fn read_mapped_file(
+    state: State<'_, AppState>,
+    session_id: String,
+    address: String,
+    size: Option<usize>,
+) -> Result<MappedFileSlice, AppError> {
+    api::read_mapped_file(&state, session_id, address, size)
+}
+
+/// Reads `size` bytes (default 256) at `address` as hexdump rows of
+/// offset, hex and ASCII, noting pointers into loaded modules.
+#[tauri::command]
+pub fn memory_hexdump(
+    state: State<'_, AppState>,
+    session_id: String,
+    address: String,
+    size: Option<usize>,
+    options: Option<HexdumpOptions>,
+) -> Result<Hexdump, AppError> {
+    api::memory_hexdump(&state, session_id, address, size, options)
+}
+
+/// Streams `size` bytes at `address` to `path` in chunks, emitting
+/// `carf://memory-dump/progress`. Unreadable pages are zero-filled.
+///
+/// Async with `spawn_blocking`, so the window keeps rendering progress and
+/// `cancel_operation` can run while the dump streams.
+#[tauri::command]
+pub async fn memory_dump_range(
+    app: AppHandle,
+    session_id: String,
+    address: String,
+    size: u64,
+    path: String,
+    dump_id: Option<String>,
+) -> Result<MemoryDump, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::memory_dump_range(
+            &app.state::<AppState>(),
+            session_id,
+            address,
+            size,
+            path,
+            dump_id,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Streams a loaded module's image to `path` in chunks, emitting
+/// `carf://memory-dump/progress`.
+#[tauri::command]
+pub async fn memory_dump_module(
+    app: AppHandle,
+    session_id: String,
+    module: String,
+    path: String,
+    dump_id: Option<String>,
+) -> Result<MemoryDump, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::memory_dump_module(&app.state::<AppState>(), session_id, module, path, dump_id)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Renders raw values with a saved display format (radix, scaling, decimals,
+/// unit suffix, enum names) so every view shows them the same way.
+#[tauri::command]
+pub fn format_values(values: Vec<Value>, format: ValueFormat) -> Vec<String> {
+    api::format_values(values, format)
+}
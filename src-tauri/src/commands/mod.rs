@@ -1,6 +1,21 @@
 pub mod adb;
 pub mod agent;
 pub mod ai;
+pub mod audit;
 pub mod device;
+pub mod files;
+pub mod gadget;
+pub mod hooks;
+pub mod launch;
+pub mod local_server;
+pub mod memory;
+pub mod operations;
+pub mod plugins;
 pub mod process;
+pub mod profiles;
+pub mod scripts;
+pub mod server_binaries;
 pub mod session;
+pub mod settings;
+pub mod stalker;
+pub mod variables;
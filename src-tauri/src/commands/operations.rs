@@ -0,0 +1,23 @@
+use tauri::{AppHandle, Manager};
+
+use crate::api;
+use crate::error::AppError;
+use crate::state::AppState;
+
+/// Cancels an in-flight operation by id. Pass `session_id` for operations
+/// running inside an agent, such as `startMemoryScan`.
+///
+/// Async, like the commands it cancels: a sync command would wait on the
+/// main thread behind the very call it is meant to stop.
+#[tauri::command]
+pub async fn cancel_operation(
+    app: AppHandle,
+    operation_id: String,
+    session_id: Option<String>,
+) -> Result<bool, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::cancel_operation(&app.state::<AppState>(), operation_id, session_id)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
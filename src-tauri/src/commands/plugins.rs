@@ -0,0 +1,25 @@
+use serde_json::Value;
+use tauri::State;
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::plugins::PluginInfo;
+use crate::state::AppState;
+
+/// Lists the backend plugins compiled into this build and their commands.
+#[tauri::command]
+pub fn plugins_list() -> Vec<PluginInfo> {
+    api::plugins_list()
+}
+
+/// Runs `command` of backend plugin `plugin`. The plugin's events arrive as
+/// `carf://plugin/<plugin>/<event>`.
+#[tauri::command]
+pub fn plugin_invoke(
+    state: State<'_, AppState>,
+    plugin: String,
+    command: String,
+    args: Option<Value>,
+) -> Result<Value, AppError> {
+    api::plugin_invoke(&state, plugin, command, args.unwrap_or(Value::Null))
+}
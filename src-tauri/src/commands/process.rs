@@ -2,10 +2,15 @@ use tauri::State;
 
 use crate::api;
 use crate::error::AppError;
-use crate::services::frida::{AppInfo, CollectionPage, ProcessInfo};
+use crate::services::frida::{
+    AppInfo, CollectionPage, PendingSpawn, ProcessInfo, ProcessListOptions, ProcessOutputPage,
+    SpawnGatingOptions,
+};
+use crate::services::process_metrics::ProcessMetricsStatus;
 use crate::state::AppState;
 
-/// Lists all running processes on the given device.
+/// Lists running processes on the given device, filtered, sorted and paged
+/// according to `options`.
 #[tauri::command]
 pub fn list_processes(
     state: State<'_, AppState>,
@@ -13,11 +18,13 @@ pub fn list_processes(
     query: Option<String>,
     limit: Option<usize>,
     force_refresh: Option<bool>,
+    options: Option<ProcessListOptions>,
 ) -> Result<CollectionPage<ProcessInfo>, AppError> {
-    api::list_processes(&state, device_id, query, limit, force_refresh)
+    api::list_processes(&state, device_id, query, limit, force_refresh, options)
 }
 
-/// Lists all installed applications on the given device.
+/// Lists all installed applications on the given device, with PNG icons
+/// when `include_icons` is set and the device provides them.
 #[tauri::command]
 pub fn list_applications(
     state: State<'_, AppState>,
@@ -25,8 +32,84 @@ pub fn list_applications(
     query: Option<String>,
     limit: Option<usize>,
     force_refresh: Option<bool>,
+    include_icons: Option<bool>,
 ) -> Result<CollectionPage<AppInfo>, AppError> {
-    api::list_applications(&state, device_id, query, limit, force_refresh)
+    api::list_applications(
+        &state,
+        device_id,
+        query,
+        limit,
+        force_refresh,
+        include_icons,
+    )
+}
+
+/// The application currently on screen, for one-click attach on mobile
+/// devices. `None` when the device reports no frontmost app.
+#[tauri::command]
+pub fn get_frontmost_application(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<Option<AppInfo>, AppError> {
+    api::get_frontmost_application(&state, device_id)
+}
+
+/// Refreshes the device's process list in the background and emits
+/// `carf://process/changed` with added/removed pids. Returns the current list.
+#[tauri::command]
+pub fn watch_processes(
+    state: State<'_, AppState>,
+    device_id: String,
+    interval_ms: Option<u64>,
+) -> Result<Vec<ProcessInfo>, AppError> {
+    api::watch_processes(&state, device_id, interval_ms)
+}
+
+/// Stops the background process refresher for the device.
+#[tauri::command]
+pub fn unwatch_processes(state: State<'_, AppState>, device_id: String) -> Result<bool, AppError> {
+    api::unwatch_processes(&state, device_id)
+}
+
+/// Reads stdout/stderr captured from the session's process (spawned with
+/// `stdio: "pipe"`) after `since_cursor`.
+#[tauri::command]
+pub fn process_output_read(
+    state: State<'_, AppState>,
+    session_id: String,
+    since_cursor: Option<u64>,
+) -> Result<ProcessOutputPage, AppError> {
+    api::process_output_read(&state, session_id, since_cursor)
+}
+
+/// Starts emitting `carf://process/metrics` (CPU%, RSS, threads, handles)
+/// for the session every `interval_ms` (default 1000). Returns the interval
+/// in effect.
+#[tauri::command]
+pub fn process_metrics_start(
+    state: State<'_, AppState>,
+    session_id: String,
+    interval_ms: Option<u32>,
+) -> Result<u32, AppError> {
+    api::process_metrics_start(&state, session_id, interval_ms)
+}
+
+/// Stops the session's metrics sampler. Returns `false` if it was not running.
+#[tauri::command]
+pub fn process_metrics_stop(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<bool, AppError> {
+    api::process_metrics_stop(&state, session_id)
+}
+
+/// Samples the session's process once and reports whether the sampler runs.
+#[tauri::command]
+pub fn process_metrics_get(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<ProcessMetricsStatus, AppError> {
+    api::process_metrics_get(&state, session_id)
 }
 
 /// Kills the process with the given PID on the given device.
@@ -38,3 +121,46 @@ pub fn kill_process(
 ) -> Result<(), AppError> {
     api::kill_process(&state, device_id, pid)
 }
+
+/// Suspends every process the device starts and emits `carf://spawn/added`
+/// for it; with `autoAttach`, matching spawns are attached to, given the
+/// chosen script and resumed. Returns the spawns already pending.
+#[tauri::command]
+pub fn enable_spawn_gating(
+    state: State<'_, AppState>,
+    device_id: String,
+    options: Option<SpawnGatingOptions>,
+) -> Result<Vec<PendingSpawn>, AppError> {
+    api::enable_spawn_gating(&state, device_id, options)
+}
+
+/// Stops gating new spawns. Spawns already pending stay suspended.
+#[tauri::command]
+pub fn disable_spawn_gating(state: State<'_, AppState>, device_id: String) -> Result<(), AppError> {
+    api::disable_spawn_gating(&state, device_id)
+}
+
+/// Lists processes suspended by spawn gating on the device.
+#[tauri::command]
+pub fn list_pending_spawns(
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<Vec<PendingSpawn>, AppError> {
+    api::list_pending_spawns(&state, device_id)
+}
+
+/// Lets a gated spawn run.
+#[tauri::command]
+pub fn resume_spawn(
+    state: State<'_, AppState>,
+    device_id: String,
+    pid: u32,
+) -> Result<(), AppError> {
+    api::resume_spawn(&state, device_id, pid)
+}
+
+/// Kills a gated spawn before it runs.
+#[tauri::command]
+pub fn deny_spawn(state: State<'_, AppState>, device_id: String, pid: u32) -> Result<(), AppError> {
+    api::deny_spawn(&state, device_id, pid)
+}
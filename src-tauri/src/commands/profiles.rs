@@ -0,0 +1,34 @@
+use tauri::State;
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::target_profiles::{ProfileLaunchReport, TargetProfile};
+use crate::state::AppState;
+
+/// Lists saved target profiles, most recently changed first.
+#[tauri::command]
+pub fn profiles_list() -> Result<Vec<TargetProfile>, AppError> {
+    api::profiles_list()
+}
+
+/// Creates a target profile, or replaces the one with the same id.
+#[tauri::command]
+pub fn profile_save(profile: TargetProfile) -> Result<TargetProfile, AppError> {
+    api::profile_save(profile)
+}
+
+/// Deletes a target profile. Returns whether it existed.
+#[tauri::command]
+pub fn profile_delete(profile_id: String) -> Result<bool, AppError> {
+    api::profile_delete(profile_id)
+}
+
+/// Attaches to or spawns a profile's target and applies its scripts, hooks
+/// and hotkeys in one go.
+#[tauri::command]
+pub fn profile_launch(
+    state: State<'_, AppState>,
+    profile_id: String,
+) -> Result<ProfileLaunchReport, AppError> {
+    api::profile_launch(&state, profile_id)
+}
@@ -0,0 +1,99 @@
+use serde_json::Value;
+use tauri::State;
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::frida::{
+    EvalResult, MessageFilter, MessageLogOptions, MessageLogStatus, MessagePage,
+};
+use crate::services::script_catalog::BundledScriptInfo;
+use crate::services::script_compiler::CompiledScript;
+use crate::state::AppState;
+
+/// Bundles a multi-file TypeScript/ESM agent into a single loadable script.
+///
+/// Bundles are cached under the app data dir and rebuilt only when a source
+/// file in the agent's project changes.
+#[tauri::command]
+pub fn compile_script(
+    state: State<'_, AppState>,
+    entrypoint_path: String,
+) -> Result<CompiledScript, AppError> {
+    api::compile_script(&state, entrypoint_path)
+}
+
+/// Lists the curated agents embedded in the app (SSL unpinning, anti-debug
+/// bypass, generic tracer).
+#[tauri::command]
+pub fn list_bundled_scripts() -> Vec<BundledScriptInfo> {
+    api::list_bundled_scripts()
+}
+
+/// Loads a bundled agent by id as the session's user script.
+#[tauri::command]
+pub fn load_bundled_script(
+    state: State<'_, AppState>,
+    session_id: String,
+    script_id: String,
+) -> Result<(), AppError> {
+    api::load_bundled_script(&state, session_id, script_id)
+}
+
+/// Evaluates a JavaScript snippet in the session's REPL script, which keeps
+/// its globals between calls, and returns the pretty-printed result.
+#[tauri::command]
+pub fn repl_eval(
+    state: State<'_, AppState>,
+    session_id: String,
+    code: String,
+) -> Result<EvalResult, AppError> {
+    api::repl_eval(&state, session_id, code)
+}
+
+/// Posts a message to the session's `user` (default) or `agent` script,
+/// received there with `recv()`. `data` is an optional base64 buffer handed
+/// to the handler as its second argument.
+#[tauri::command]
+pub fn script_post(
+    state: State<'_, AppState>,
+    session_id: String,
+    script: Option<String>,
+    message: Value,
+    data: Option<String>,
+) -> Result<(), AppError> {
+    api::script_post(&state, session_id, script, message, data)
+}
+
+/// Starts recording every script message to a rotating NDJSON file under the
+/// app data dir. Restarting applies new options and keeps appending.
+#[tauri::command]
+pub fn message_log_start(
+    state: State<'_, AppState>,
+    options: Option<MessageLogOptions>,
+) -> Result<MessageLogStatus, AppError> {
+    api::message_log_start(&state, options)
+}
+
+/// Stops recording and flushes the current log file.
+#[tauri::command]
+pub fn message_log_stop(state: State<'_, AppState>) -> Result<MessageLogStatus, AppError> {
+    api::message_log_stop(&state)
+}
+
+/// Reports whether messages are being recorded and where the log lives.
+#[tauri::command]
+pub fn message_log_status(state: State<'_, AppState>) -> Result<MessageLogStatus, AppError> {
+    api::message_log_status(&state)
+}
+
+/// Searches recent script messages, oldest first. `since` is the
+/// `nextCursor` of a previous page; omit it to start from the oldest kept.
+#[tauri::command]
+pub fn messages_query(
+    state: State<'_, AppState>,
+    filter: Option<MessageFilter>,
+    since: Option<u64>,
+    limit: Option<usize>,
+) -> Result<MessagePage, AppError> {
+    api::messages_query(&state, filter, since, limit)
+}
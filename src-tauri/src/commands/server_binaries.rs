@@ -0,0 +1,54 @@
+use tauri::State;
+
+use crate::error::AppError;
+use crate::services::server_binaries::{self, ServerBinary};
+use crate::state::AppState;
+
+/// Lists the frida-server binaries downloaded into the data dir.
+#[tauri::command]
+pub fn server_binaries_list() -> Result<Vec<ServerBinary>, AppError> {
+    server_binaries::list()
+}
+
+/// Downloads frida-server `version` (defaulting to CARF's Frida) for
+/// `os`/`arch`, verifies its checksum and installs it where the ADB deploy and
+/// local server flows look for it. A release without a published checksum
+/// needs `sha256` or `allow_unverified`. Emits `carf://frida-server/download`
+/// as it goes.
+///
+/// Async with `spawn_blocking`, since a download can take minutes.
+#[tauri::command]
+pub async fn server_binary_download(
+    state: State<'_, AppState>,
+    version: Option<String>,
+    os: String,
+    arch: String,
+    sha256: Option<String>,
+    allow_unverified: Option<bool>,
+) -> Result<ServerBinary, AppError> {
+    let events = state.events.clone();
+    let version = version.unwrap_or_else(|| frida::Frida::version().to_string());
+    let allow_unverified = allow_unverified.unwrap_or(false);
+    tokio::task::spawn_blocking(move || {
+        server_binaries::download(
+            &version,
+            &os,
+            &arch,
+            sha256.as_deref(),
+            allow_unverified,
+            |progress| {
+                if let Ok(payload) = serde_json::to_value(progress) {
+                    events.emit("carf://frida-server/download", payload);
+                }
+            },
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Deletes a downloaded frida-server. Returns whether one was installed.
+#[tauri::command]
+pub fn server_binary_delete(version: String, os: String, arch: String) -> Result<bool, AppError> {
+    server_binaries::delete(&version, &os, &arch)
+}
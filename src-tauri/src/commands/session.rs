@@ -1,29 +1,83 @@
-use tauri::State;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, State};
 
 use crate::api;
 use crate::error::AppError;
-use crate::services::frida::{AttachOptions, SpawnOptions};
-use crate::services::session_manager::SessionInfo;
+use crate::services::frida::{
+    AttachOptions, SessionCleanupReport, SessionDiagnostic, SessionMarker, SpawnOptions,
+};
+use crate::services::session_manager::{AttachManyResult, SessionInfo};
+use crate::services::session_restore::SessionRestoreReport;
 use crate::state::AppState;
 
 /// Spawns the application identified by `options.identifier` and attaches Frida.
 #[tauri::command]
-pub fn spawn_and_attach(
-    state: State<'_, AppState>,
+pub async fn spawn_and_attach(
+    app: AppHandle,
     device_id: String,
     options: SpawnOptions,
 ) -> Result<SessionInfo, AppError> {
-    api::spawn_and_attach(&state, device_id, options)
+    tokio::task::spawn_blocking(move || {
+        api::spawn_and_attach(&app.state::<AppState>(), device_id, options)
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
 }
 
 /// Attaches Frida to an already-running process (pid or name).
 #[tauri::command]
-pub fn attach(
-    state: State<'_, AppState>,
+pub async fn attach(
+    app: AppHandle,
     device_id: String,
     options: AttachOptions,
 ) -> Result<SessionInfo, AppError> {
-    api::attach(&state, device_id, options)
+    tokio::task::spawn_blocking(move || api::attach(&app.state::<AppState>(), device_id, options))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Attaches to a process by name, optionally waiting for it to launch.
+#[tauri::command]
+pub async fn attach_by_name(
+    app: AppHandle,
+    device_id: String,
+    name: String,
+    wait: Option<bool>,
+    timeout_ms: Option<u64>,
+) -> Result<SessionInfo, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::attach_by_name(
+            &app.state::<AppState>(),
+            device_id,
+            name,
+            wait.unwrap_or(false),
+            timeout_ms,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?
+}
+
+/// Attaches to several processes at once, loading the same script into each.
+#[tauri::command]
+pub async fn attach_many(
+    app: AppHandle,
+    device_id: String,
+    pids: Vec<u32>,
+    script_path: Option<String>,
+    script_parameters: Option<Value>,
+) -> Result<Vec<AttachManyResult>, AppError> {
+    tokio::task::spawn_blocking(move || {
+        api::attach_many(
+            &app.state::<AppState>(),
+            device_id,
+            pids,
+            script_path,
+            script_parameters,
+        )
+    })
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))
 }
 
 /// Detaches from the session and cleans up Frida resources.
@@ -32,14 +86,60 @@ pub fn detach(state: State<'_, AppState>, session_id: String) -> Result<(), AppE
     api::detach(&state, session_id)
 }
 
+/// Removes hooks, reverts patches, unloads the user script and detaches in
+/// one step, reporting what each part undid.
+#[tauri::command]
+pub fn cleanup_session(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<SessionCleanupReport, AppError> {
+    api::cleanup_session(&state, session_id)
+}
+
 /// Resumes a suspended spawned process.
 #[tauri::command]
 pub fn resume(state: State<'_, AppState>, session_id: String) -> Result<(), AppError> {
     api::resume(&state, session_id)
 }
 
-/// Returns the list of all currently active sessions.
+/// Returns every active session, oldest first, with its loaded script count.
 #[tauri::command]
 pub fn list_sessions(state: State<'_, AppState>) -> Result<Vec<SessionInfo>, AppError> {
     api::list_sessions(&state)
 }
+
+/// Returns the `TARGET_ANTI_TAMPER_SUSPECTED` diagnostic for the session, if
+/// any evidence of the target blocking Frida has been seen.
+#[tauri::command]
+pub fn get_session_diagnostic(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Option<SessionDiagnostic>, AppError> {
+    api::get_session_diagnostic(&state, session_id)
+}
+
+/// Drops a timestamped marker ("died here", "opened shop") into the session's
+/// event log. Timelines and trace exports use markers as alignment points.
+#[tauri::command]
+pub fn add_session_marker(
+    state: State<'_, AppState>,
+    session_id: String,
+    label: String,
+) -> Result<SessionMarker, AppError> {
+    api::add_session_marker(&state, session_id, label)
+}
+
+/// Returns the session's markers, oldest first.
+#[tauri::command]
+pub fn list_session_markers(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<SessionMarker>, AppError> {
+    api::list_session_markers(&state, session_id)
+}
+
+/// Reattaches to the targets from the previous run and reloads their scripts.
+#[tauri::command]
+pub fn restore_last_session(state: State<'_, AppState>) -> Result<SessionRestoreReport, AppError> {
+    api::restore_last_session(&state)
+}
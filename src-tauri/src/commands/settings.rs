@@ -0,0 +1,147 @@
+use serde_json::Value;
+use tauri::State;
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::app_log::LogEntry;
+use crate::services::backups::BackupInfo;
+use crate::services::frida::TimeoutPolicy;
+use crate::services::hotkeys::{HotkeyBinding, HotkeyRpcAction};
+use crate::services::perf::{PerfReport, PerfSelftestOptions};
+use crate::services::settings::Settings;
+use crate::state::AppState;
+
+/// Returns the persisted application settings.
+#[tauri::command]
+pub fn settings_get(state: State<'_, AppState>) -> Result<Settings, AppError> {
+    api::settings_get(&state)
+}
+
+/// Replaces every application setting, persists them to `settings.json` and
+/// emits `carf://settings/changed`.
+#[tauri::command]
+pub fn settings_set(state: State<'_, AppState>, settings: Settings) -> Result<Settings, AppError> {
+    api::settings_set(&state, settings)
+}
+
+/// Restores every application setting to its default.
+#[tauri::command]
+pub fn settings_reset(state: State<'_, AppState>) -> Result<Settings, AppError> {
+    api::settings_reset(&state)
+}
+
+/// Sets the backend log level (`error`, `warn`, `info`, `debug` or
+/// `trace`) and saves it to settings; omitted goes back to `info`.
+#[tauri::command]
+pub fn log_set_level(state: State<'_, AppState>, level: Option<String>) -> Result<(), AppError> {
+    api::log_set_level(&state, level)
+}
+
+/// Returns the last `count` backend log entries (200 by default), oldest
+/// first. The full log is in `logs/carf.log` under the data dir.
+#[tauri::command]
+pub fn log_get_recent(count: Option<usize>) -> Result<Vec<LogEntry>, AppError> {
+    api::log_get_recent(count)
+}
+
+/// Lists hotkey bindings, ordered by action.
+#[tauri::command]
+pub fn hotkeys_list(state: State<'_, AppState>) -> Result<Vec<HotkeyBinding>, AppError> {
+    api::hotkeys_list(&state)
+}
+
+/// Binds `action` to `chord` (e.g. `Ctrl+Shift+F`), or unbinds it when
+/// `chord` is empty. Fails when another action already uses the chord.
+#[tauri::command]
+pub fn hotkeys_set(
+    state: State<'_, AppState>,
+    action: String,
+    chord: Option<String>,
+) -> Result<Vec<HotkeyBinding>, AppError> {
+    api::hotkeys_set(&state, action, chord)
+}
+
+/// Makes hotkey `action` call an agent RPC method when it fires, or removes
+/// that call when `rpc` is omitted.
+#[tauri::command]
+pub fn hotkeys_set_rpc(
+    state: State<'_, AppState>,
+    action: String,
+    rpc: Option<HotkeyRpcAction>,
+) -> Result<Vec<HotkeyBinding>, AppError> {
+    api::hotkeys_set_rpc(&state, action, rpc)
+}
+
+/// Runs the RPC call bound to `action` and returns its result. Called by the
+/// frontend when the action's chord is pressed; the outcome is also emitted
+/// as `carf://hotkey/rpc-result`.
+#[tauri::command]
+pub fn hotkeys_trigger(state: State<'_, AppState>, action: String) -> Result<Value, AppError> {
+    api::hotkeys_trigger(&state, action)
+}
+
+/// Writes the current application settings to `path` as a portable JSON export.
+#[tauri::command]
+pub fn settings_export(state: State<'_, AppState>, path: String) -> Result<(), AppError> {
+    api::settings_export(&state, path)
+}
+
+/// Replaces the application settings with a previously exported file.
+#[tauri::command]
+pub fn settings_import(state: State<'_, AppState>, path: String) -> Result<Settings, AppError> {
+    api::settings_import(&state, path)
+}
+
+/// Re-reads `settings.json`, picking up edits made outside CARF. Saves are
+/// refused after such an edit until this is called, unless
+/// `reloadExternalEdits` is on.
+#[tauri::command]
+pub fn settings_reload(state: State<'_, AppState>) -> Result<Settings, AppError> {
+    api::settings_reload(&state)
+}
+
+/// Lists timestamped backups of settings and variables, newest first.
+#[tauri::command]
+pub fn list_backups() -> Result<Vec<BackupInfo>, AppError> {
+    api::list_backups()
+}
+
+/// Restores a backup from `list_backups` over the file it was taken of and
+/// returns that file's name. The replaced contents are backed up first.
+#[tauri::command]
+pub fn restore_backup(state: State<'_, AppState>, name: String) -> Result<String, AppError> {
+    api::restore_backup(&state, name)
+}
+
+/// Opens the OS file manager at `data`, `settings`, `variables`, `logs`,
+/// `app-logs` or `backups` and returns the path shown.
+#[tauri::command]
+pub fn reveal_in_file_manager(
+    state: State<'_, AppState>,
+    kind: String,
+) -> Result<String, AppError> {
+    api::reveal_in_file_manager(&state, kind)
+}
+
+/// Returns how long attach, script load, RPC and scan calls may take.
+#[tauri::command]
+pub fn get_timeout_policy(state: State<'_, AppState>) -> Result<TimeoutPolicy, AppError> {
+    api::get_timeout_policy(&state)
+}
+
+/// Applies and persists new operation timeouts; `0` disables a timeout.
+#[tauri::command]
+pub fn set_timeout_policy(
+    state: State<'_, AppState>,
+    policy: TimeoutPolicy,
+) -> Result<(), AppError> {
+    api::set_timeout_policy(&state, policy)
+}
+
+/// Benchmarks the backend hot paths (stalker trace buffer, event batching,
+/// message history and value formatting) on synthetic data and reports their
+/// throughput on this machine.
+#[tauri::command]
+pub fn perf_selftest(options: Option<PerfSelftestOptions>) -> Result<PerfReport, AppError> {
+    api::perf_selftest(options)
+}
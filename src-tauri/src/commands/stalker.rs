@@ -0,0 +1,67 @@
+use serde_json::Value;
+use tauri::State;
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::stalker::{FunctionTrace, StalkerEventOptions, TraceSummary};
+use crate::state::AppState;
+
+/// Starts Stalker tracing on a thread. Events are buffered host-side and also
+/// streamed as `carf://stalker/event`.
+#[tauri::command]
+pub fn start_stalker_trace(
+    state: State<'_, AppState>,
+    session_id: String,
+    thread_id: u64,
+    events: Option<StalkerEventOptions>,
+) -> Result<Value, AppError> {
+    api::start_stalker_trace(&state, session_id, thread_id, events)
+}
+
+/// Stops tracing a thread and returns the size of the buffered trace.
+#[tauri::command]
+pub fn stop_stalker_trace(
+    state: State<'_, AppState>,
+    session_id: String,
+    thread_id: u64,
+) -> Result<TraceSummary, AppError> {
+    api::stop_stalker_trace(&state, session_id, thread_id)
+}
+
+/// Traces each call to `module!symbol` or `module+offset` from entry to return.
+#[tauri::command]
+pub fn trace_function(
+    state: State<'_, AppState>,
+    session_id: String,
+    module: Option<String>,
+    symbol_or_offset: String,
+    events: Option<StalkerEventOptions>,
+) -> Result<FunctionTrace, AppError> {
+    api::trace_function(&state, session_id, module, symbol_or_offset, events)
+}
+
+/// Removes a function trace installed with `trace_function`.
+#[tauri::command]
+pub fn untrace_function(
+    state: State<'_, AppState>,
+    session_id: String,
+    trace_id: String,
+) -> Result<(), AppError> {
+    api::untrace_function(&state, session_id, trace_id)
+}
+
+/// Writes the session's buffered trace to a JSON file.
+#[tauri::command]
+pub fn export_stalker_trace(
+    state: State<'_, AppState>,
+    session_id: String,
+    path: String,
+) -> Result<TraceSummary, AppError> {
+    api::export_stalker_trace(&state, session_id, path)
+}
+
+/// Discards the session's buffered trace.
+#[tauri::command]
+pub fn clear_stalker_trace(state: State<'_, AppState>, session_id: String) -> Result<(), AppError> {
+    api::clear_stalker_trace(&state, session_id)
+}
@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use tauri::State;
+
+use crate::api;
+use crate::error::AppError;
+use crate::services::variables::{MergeResult, MergeStrategy, ProfileSnapshot};
+use crate::state::AppState;
+
+/// Lists every target profile that has stored variables.
+#[tauri::command]
+pub fn variable_profiles() -> Result<Vec<String>, AppError> {
+    api::variable_profiles()
+}
+
+/// Returns all variables stored for `profile`.
+#[tauri::command]
+pub fn variable_list(profile: String) -> Result<BTreeMap<String, Value>, AppError> {
+    api::variable_list(profile)
+}
+
+/// Returns one variable, or `null` when it is not set.
+#[tauri::command]
+pub fn variable_get(profile: String, key: String) -> Result<Option<Value>, AppError> {
+    api::variable_get(profile, key)
+}
+
+/// Returns `profile`'s variables with the revision to pass back on writes.
+#[tauri::command]
+pub fn variable_snapshot(profile: String) -> Result<ProfileSnapshot, AppError> {
+    api::variable_snapshot(profile)
+}
+
+/// Stores a variable and pushes it to live sessions of the same profile.
+/// Returns the profile's new revision; with `expected_revision`, fails with
+/// `STORAGE_CONFLICT` when the profile changed since that revision.
+#[tauri::command]
+pub fn variable_set(
+    state: State<'_, AppState>,
+    profile: String,
+    key: String,
+    value: Value,
+    expected_revision: Option<String>,
+) -> Result<String, AppError> {
+    api::variable_set(&state, profile, key, value, expected_revision)
+}
+
+/// Removes a variable and clears it from live sessions of the same profile.
+/// Returns the profile's new revision; see `variable_set`.
+#[tauri::command]
+pub fn variable_delete(
+    state: State<'_, AppState>,
+    profile: String,
+    key: String,
+    expected_revision: Option<String>,
+) -> Result<String, AppError> {
+    api::variable_delete(&state, profile, key, expected_revision)
+}
+
+/// Three-way merges an edited copy of `profile` with what is stored now,
+/// resolving keys changed on both sides with `strategy`.
+#[tauri::command]
+pub fn variable_merge(
+    state: State<'_, AppState>,
+    profile: String,
+    base: BTreeMap<String, Value>,
+    ours: BTreeMap<String, Value>,
+    strategy: MergeStrategy,
+) -> Result<MergeResult, AppError> {
+    api::variable_merge(&state, profile, base, ours, strategy)
+}
@@ -1,4 +1,8 @@
+use std::backtrace::Backtrace;
+use std::error::Error as StdError;
+
 use serde::{Serialize, Serializer};
+use serde_json::{json, Value};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -31,6 +35,15 @@ pub enum AppError {
     #[error("Script load failed: {0}")]
     ScriptLoadFailed(String),
 
+    #[error("Operation cancelled: {0}")]
+    OperationCancelled(String),
+
+    #[error("Operation timed out: {0}")]
+    Timeout(String),
+
+    #[error("Frida version mismatch: {0}")]
+    VersionMismatch(String),
+
     // Agent errors
     #[error("Agent RPC error: {0}")]
     AgentRpcError(String),
@@ -62,27 +75,86 @@ pub enum AppError {
     #[error("AI provider error: {0}")]
     AiProviderError(String),
 
+    // Local frida-server errors
+    #[error("Local frida-server error: {0}")]
+    LocalServerError(String),
+
+    // Frida Gadget deployment errors
+    #[error("Gadget error: {0}")]
+    GadgetError(String),
+
+    // Local storage errors (settings, data files)
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
+    #[error("Storage conflict: {0}")]
+    StorageConflict(String),
+
+    // Request validation errors
+    #[error("Invalid argument: {0}")]
+    InvalidArgument(String),
+
     // Catch-all
     #[error("Internal error: {0}")]
     Internal(String),
+
+    // An error annotated with the lower-level error behind it
+    #[error("{error}")]
+    Caused {
+        error: Box<AppError>,
+        #[source]
+        source: Box<dyn StdError + Send + Sync>,
+        /// Where the error was raised; only captured in debug builds.
+        trace: Option<String>,
+    },
 }
 
 /// Tauri requires commands to return serializable errors.
-/// We serialize AppError as a JSON object with `code` and `message` fields.
+/// We serialize AppError as a JSON object with `code` and `message` fields,
+/// plus `details` when the error has a cause chain or backtrace.
 impl Serialize for AppError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
         use serde::ser::SerializeMap;
-        let mut map = serializer.serialize_map(Some(2))?;
+        let details = self.details();
+        let mut map = serializer.serialize_map(Some(2 + usize::from(details.is_some())))?;
         map.serialize_entry("code", &self.error_code())?;
         map.serialize_entry("message", &self.to_string())?;
+        if let Some(details) = details {
+            map.serialize_entry("details", &details)?;
+        }
         map.end()
     }
 }
 
 impl AppError {
+    /// Keeps `source` (e.g. the Frida error behind an attach failure) as the
+    /// cause of this error so it reaches the frontend in `details`.
+    pub fn caused_by(self, source: impl Into<Box<dyn StdError + Send + Sync>>) -> Self {
+        AppError::Caused {
+            error: Box::new(self),
+            source: source.into(),
+            trace: cfg!(debug_assertions).then(|| Backtrace::force_capture().to_string()),
+        }
+    }
+
+    /// `{ causes, backtrace }`, where `causes` lists each underlying error
+    /// from the outermost in.
+    fn details(&self) -> Option<Value> {
+        let AppError::Caused { trace, .. } = self else {
+            return None;
+        };
+        let mut causes = Vec::new();
+        let mut next = self.source();
+        while let Some(cause) = next {
+            causes.push(cause.to_string());
+            next = cause.source();
+        }
+        Some(json!({ "causes": causes, "backtrace": trace }))
+    }
+
     fn error_code(&self) -> &'static str {
         match self {
             AppError::DeviceNotFound(_) => "DEVICE_NOT_FOUND",
@@ -93,6 +165,9 @@ impl AppError {
             AppError::SpawnFailed(_, _) => "SPAWN_FAILED",
             AppError::AttachFailed(_, _) => "ATTACH_FAILED",
             AppError::ScriptLoadFailed(_) => "SCRIPT_LOAD_FAILED",
+            AppError::OperationCancelled(_) => "OPERATION_CANCELLED",
+            AppError::Timeout(_) => "TIMEOUT",
+            AppError::VersionMismatch(_) => "VERSION_MISMATCH",
             AppError::AgentRpcError(_) => "AGENT_RPC_ERROR",
             AppError::AgentMethodNotFound(_) => "AGENT_METHOD_NOT_FOUND",
             AppError::AdbNotFound => "ADB_NOT_FOUND",
@@ -102,7 +177,13 @@ impl AppError {
             AppError::ConnectionFailed(_, _) => "CONNECTION_FAILED",
             AppError::InvalidAddress(_) => "INVALID_ADDRESS",
             AppError::AiProviderError(_) => "AI_PROVIDER_ERROR",
+            AppError::LocalServerError(_) => "LOCAL_SERVER_ERROR",
+            AppError::GadgetError(_) => "GADGET_ERROR",
+            AppError::StorageError(_) => "STORAGE_ERROR",
+            AppError::StorageConflict(_) => "STORAGE_CONFLICT",
+            AppError::InvalidArgument(_) => "INVALID_ARGUMENT",
             AppError::Internal(_) => "INTERNAL_ERROR",
+            AppError::Caused { error, .. } => error.error_code(),
         }
     }
 }
@@ -112,3 +193,39 @@ impl From<anyhow::Error> for AppError {
         AppError::Internal(e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_errors_serialize_without_details() {
+        let value = serde_json::to_value(AppError::Internal("boom".to_string())).unwrap();
+        assert_eq!(
+            value,
+            json!({ "code": "INTERNAL_ERROR", "message": "Internal error: boom" })
+        );
+    }
+
+    #[test]
+    fn caused_errors_serialize_their_cause_chain() {
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        let error = AppError::ScriptLoadFailed("failed to read agent.js".to_string())
+            .caused_by(AppError::StorageError("agent.js".to_string()).caused_by(io));
+
+        let value = serde_json::to_value(&error).unwrap();
+        assert_eq!(value["code"], "SCRIPT_LOAD_FAILED");
+        assert_eq!(
+            value["message"],
+            "Script load failed: failed to read agent.js"
+        );
+        assert_eq!(
+            value["details"]["causes"],
+            json!(["Storage error: agent.js", "no such file"])
+        );
+        assert_eq!(
+            value["details"]["backtrace"].is_string(),
+            cfg!(debug_assertions)
+        );
+    }
+}
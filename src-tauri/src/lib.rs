@@ -1,26 +1,110 @@
 mod api;
+mod cli;
 mod commands;
 mod error;
 mod services;
 mod state;
 mod web_bridge;
 
+/// Hot paths and their workloads re-exported for the `benches/` suite; not a
+/// stable API.
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::services::frida::{EventBatcher, MessageHistoryStore};
+    pub use crate::services::perf::{
+        batch_script_events, query_message_history, record_message_history,
+        record_trace_batches, seconds_format, synthetic_event_batches, synthetic_script_events,
+        synthetic_values,
+    };
+    pub use crate::services::stalker::TraceStore;
+    pub use crate::services::value_format::format_values;
+}
+
+/// Extension point for custom CARF builds: pass `BackendPlugin`s to
+/// `run_with_plugins` or `run_web_bridge_with_plugins`.
+pub mod plugin {
+    pub use crate::error::AppError;
+    pub use crate::services::plugins::{BackendPlugin, PluginContext};
+}
+
 use commands::{
     adb::{
-        adb_connect, adb_device_props, adb_devices, adb_install_apk, adb_is_frida_running,
-        adb_pair, adb_push_frida_server, adb_shell, adb_start_frida_server, adb_stop_frida_server,
+        adb_connect, adb_deploy_frida_server, adb_device_props, adb_devices,
+        adb_frida_server_status, adb_install_apk, adb_is_frida_running, adb_pair, adb_pull_file,
+        adb_push_file, adb_push_frida_server, adb_shell, adb_start_frida_server,
+        adb_stop_frida_server,
     },
     agent::{rpc_call, rpc_call_chunked},
     ai::ai_chat,
-    device::{add_remote_device, get_device_info, list_devices, remove_remote_device},
-    process::{kill_process, list_applications, list_processes},
-    session::{attach, detach, list_sessions, resume, spawn_and_attach},
+    audit::audit_query,
+    device::{
+        add_portal, add_remote_device, device_forward_port, device_list_port_forwards,
+        device_stop_port_forward, get_device_info, list_devices, list_portal_nodes, pair_device,
+        query_device, remote_endpoint_remove, remote_endpoint_update, remote_endpoints_list,
+        remove_remote_device, unpair_device,
+    },
+    files::{file_pull, file_push},
+    gadget::{gadget_config, gadget_connect, gadget_patch_apk},
+    hooks::{hook_add, hook_list, hook_remove},
+    launch::{get_launch_options, run_deep_link},
+    local_server::{local_server_start, local_server_status, local_server_stop},
+    memory::{
+        address_resolve, address_to_location, bookmark_delete, bookmark_save, bookmarks_list,
+        bookmarks_resolve, format_values, freeze_values, guess_type, inspect_bytes,
+        list_frozen_values, memory_dump_module, memory_dump_range, memory_hexdump,
+        memory_read_typed, memory_unwatch, memory_watch, memory_watches_list, memory_write_typed,
+        pointer_chain_delete, pointer_chain_save, pointer_chains_list, pointer_chains_resolve,
+        pointer_map_snapshot, pointer_scan, read_mapped_file, scan_preset_delete, scan_preset_save,
+        scan_presets, scan_snapshot_compare, scan_snapshot_delete, scan_snapshot_save,
+        scan_snapshots_list, scan_with_preset, unfreeze_values, write_values_batch,
+    },
+    operations::cancel_operation,
+    plugins::{plugin_invoke, plugins_list},
+    process::{
+        deny_spawn, disable_spawn_gating, enable_spawn_gating, get_frontmost_application,
+        kill_process, list_applications, list_pending_spawns, list_processes, process_metrics_get,
+        process_metrics_start, process_metrics_stop, process_output_read, resume_spawn,
+        unwatch_processes, watch_processes,
+    },
+    profiles::{profile_delete, profile_launch, profile_save, profiles_list},
+    scripts::{
+        compile_script, list_bundled_scripts, load_bundled_script, message_log_start,
+        message_log_status, message_log_stop, messages_query, repl_eval, script_post,
+    },
+    server_binaries::{server_binaries_list, server_binary_delete, server_binary_download},
+    session::{
+        add_session_marker, attach, attach_by_name, attach_many, cleanup_session, detach,
+        get_session_diagnostic, list_session_markers, list_sessions, restore_last_session, resume,
+        spawn_and_attach,
+    },
+    settings::{
+        get_timeout_policy, hotkeys_list, hotkeys_set, hotkeys_set_rpc, hotkeys_trigger,
+        list_backups, log_get_recent, log_set_level, perf_selftest, restore_backup,
+        reveal_in_file_manager, set_timeout_policy, settings_export, settings_get, settings_import,
+        settings_reload, settings_reset, settings_set,
+    },
+    stalker::{
+        clear_stalker_trace, export_stalker_trace, start_stalker_trace, stop_stalker_trace,
+        trace_function, untrace_function,
+    },
+    variables::{
+        variable_delete, variable_get, variable_list, variable_merge, variable_profiles,
+        variable_set, variable_snapshot,
+    },
 };
+use std::sync::Arc;
+
 use state::AppState;
 use tauri::{Emitter, Manager};
 
 pub fn run() {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    run_with_plugins(Vec::new());
+}
+
+/// `run` for custom builds; `plugins` are registered during setup, which
+/// fails if two share a name.
+pub fn run_with_plugins(plugins: Vec<Arc<dyn plugin::BackendPlugin>>) {
+    services::app_log::init();
 
     let app_state = match AppState::new() {
         Ok(state) => state,
@@ -38,12 +122,28 @@ pub fn run() {
     };
 
     tauri::Builder::default()
+        // Registered first so a second launch hands its `carf://` link to the
+        // running instance instead of opening another window.
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.set_focus();
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .manage(app_state)
-        .setup(|app| {
+        .setup(move |app| {
+            for plugin in plugins {
+                services::plugins::register(plugin)?;
+            }
+            setup_launch_options(app);
+            setup_deep_link_handler(app);
             setup_event_forwarder(app);
             setup_device_change_listener(app);
+            setup_settings_file_watcher(app);
+            setup_workspace_auto_attach(app);
+            setup_device_auto_connect(app);
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -51,42 +151,335 @@ pub fn run() {
             list_devices,
             add_remote_device,
             remove_remote_device,
+            remote_endpoints_list,
+            remote_endpoint_update,
+            remote_endpoint_remove,
             get_device_info,
+            query_device,
+            pair_device,
+            unpair_device,
+            device_forward_port,
+            device_stop_port_forward,
+            device_list_port_forwards,
+            add_portal,
+            list_portal_nodes,
             // Process commands
             list_processes,
             list_applications,
+            get_frontmost_application,
             kill_process,
+            enable_spawn_gating,
+            disable_spawn_gating,
+            list_pending_spawns,
+            resume_spawn,
+            deny_spawn,
+            watch_processes,
+            unwatch_processes,
+            process_output_read,
+            process_metrics_start,
+            process_metrics_stop,
+            process_metrics_get,
             // Session commands
             spawn_and_attach,
             attach,
+            attach_by_name,
+            attach_many,
             detach,
+            cleanup_session,
             resume,
             list_sessions,
+            restore_last_session,
+            get_session_diagnostic,
+            add_session_marker,
+            list_session_markers,
             // Agent commands
             rpc_call,
             rpc_call_chunked,
+            // Memory commands
+            inspect_bytes,
+            guess_type,
+            format_values,
+            write_values_batch,
+            memory_read_typed,
+            memory_write_typed,
+            scan_snapshot_save,
+            scan_snapshots_list,
+            scan_snapshot_delete,
+            scan_snapshot_compare,
+            freeze_values,
+            unfreeze_values,
+            list_frozen_values,
+            memory_watch,
+            memory_unwatch,
+            memory_watches_list,
+            scan_presets,
+            scan_preset_save,
+            scan_preset_delete,
+            scan_with_preset,
+            pointer_scan,
+            pointer_map_snapshot,
+            bookmarks_list,
+            bookmark_save,
+            bookmark_delete,
+            bookmarks_resolve,
+            address_resolve,
+            address_to_location,
+            pointer_chains_list,
+            pointer_chain_save,
+            pointer_chain_delete,
+            pointer_chains_resolve,
+            read_mapped_file,
+            memory_hexdump,
+            memory_dump_range,
+            memory_dump_module,
+            // Script commands
+            compile_script,
+            list_bundled_scripts,
+            load_bundled_script,
+            script_post,
+            repl_eval,
+            message_log_start,
+            message_log_stop,
+            message_log_status,
+            messages_query,
+            // File transfer commands
+            file_push,
+            file_pull,
+            // Hook commands
+            hook_add,
+            hook_remove,
+            hook_list,
+            // Stalker commands
+            start_stalker_trace,
+            stop_stalker_trace,
+            trace_function,
+            untrace_function,
+            export_stalker_trace,
+            clear_stalker_trace,
+            // Local frida-server commands
+            local_server_start,
+            local_server_stop,
+            local_server_status,
+            // frida-server binary commands
+            server_binaries_list,
+            server_binary_download,
+            server_binary_delete,
             // AI commands
             ai_chat,
             // ADB commands
             adb_devices,
             adb_device_props,
             adb_push_frida_server,
+            adb_push_file,
+            adb_pull_file,
             adb_start_frida_server,
             adb_stop_frida_server,
             adb_is_frida_running,
+            adb_deploy_frida_server,
+            adb_frida_server_status,
             adb_shell,
             adb_install_apk,
             adb_pair,
             adb_connect,
+            // Gadget commands
+            gadget_config,
+            gadget_patch_apk,
+            gadget_connect,
+            // Launch commands
+            get_launch_options,
+            run_deep_link,
+            // Target profile commands
+            profiles_list,
+            profile_save,
+            profile_delete,
+            profile_launch,
+            // Settings commands
+            settings_export,
+            settings_get,
+            settings_set,
+            settings_reset,
+            log_set_level,
+            log_get_recent,
+            hotkeys_list,
+            hotkeys_set,
+            hotkeys_set_rpc,
+            hotkeys_trigger,
+            settings_import,
+            settings_reload,
+            list_backups,
+            restore_backup,
+            reveal_in_file_manager,
+            get_timeout_policy,
+            set_timeout_policy,
+            perf_selftest,
+            // Audit commands
+            audit_query,
+            // Plugin commands
+            plugins_list,
+            plugin_invoke,
+            // Variable commands
+            variable_profiles,
+            variable_list,
+            variable_get,
+            variable_snapshot,
+            variable_set,
+            variable_delete,
+            variable_merge,
+            // Operation commands
+            cancel_operation,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running CARF application");
+        .build(tauri::generate_context!())
+        .expect("error while building CARF application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                detach_all_sessions(app);
+            }
+        });
+}
+
+/// Installs the logger shared by the app and the web bridge: stderr plus a
+/// rotating `logs/carf.log` in the data dir.
+pub fn init_logging() {
+    services::app_log::init();
+}
+
+/// Entry point of the headless `carf-cli` binary.
+pub fn run_cli() -> anyhow::Result<()> {
+    cli::run()
 }
 
 pub async fn run_web_bridge() -> anyhow::Result<()> {
+    run_web_bridge_with_plugins(Vec::new()).await
+}
+
+/// `run_web_bridge` for custom builds; see `run_with_plugins`.
+pub async fn run_web_bridge_with_plugins(
+    plugins: Vec<Arc<dyn plugin::BackendPlugin>>,
+) -> anyhow::Result<()> {
+    for plugin in plugins {
+        services::plugins::register(plugin)?;
+    }
     web_bridge::run().await
 }
 
+/// Detaches every session before the process exits. Leaving agents loaded
+/// keeps their hooks pointing into a script runtime that is about to vanish,
+/// which crashes the target.
+fn detach_all_sessions(app: &tauri::AppHandle) {
+    use std::sync::TryLockError;
+    use std::time::{Duration, Instant};
+
+    const EXIT_DETACH_TIMEOUT: Duration = Duration::from_secs(3);
+
+    let state = app.state::<AppState>();
+    let deadline = Instant::now() + EXIT_DETACH_TIMEOUT;
+
+    // A long-running command may still hold the service; wait for it, but
+    // only up to the same deadline.
+    let mut svc = loop {
+        match state.frida_service.try_lock() {
+            Ok(guard) => break guard,
+            Err(TryLockError::Poisoned(poisoned)) => break poisoned.into_inner(),
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            Err(TryLockError::WouldBlock) => {
+                log::warn!("frida_service is busy; exiting without detaching sessions");
+                return;
+            }
+        }
+    };
+
+    match svc.detach_all(deadline.saturating_duration_since(Instant::now())) {
+        Ok(0) => {}
+        Ok(count) => log::info!("Detached {count} session(s) before exit"),
+        Err(error) => log::warn!("Failed to detach sessions before exit: {error}"),
+    }
+}
+
+/// Polls `settings.json` for edits made outside CARF, e.g. by a sync tool,
+/// so they are not silently overwritten by the next save.
+fn setup_settings_file_watcher(app: &tauri::App) {
+    let app_handle = app.handle().clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(std::time::Duration::from_secs(2));
+        let state = app_handle.state::<AppState>();
+        if let Err(error) = api::check_settings_file(&state) {
+            log::debug!("settings watcher check failed: {error}");
+        }
+    });
+}
+
+/// Connects the remote devices listed in settings without holding up the
+/// window.
+fn setup_device_auto_connect(app: &tauri::App) {
+    let app_handle = app.handle().clone();
+    std::thread::spawn(move || {
+        let state = app_handle.state::<AppState>();
+        api::auto_connect_devices(&state);
+    });
+}
+
+/// Reads how CARF was started. On macOS a `carf://` link is not in argv but
+/// delivered through the deep-link plugin.
+fn setup_launch_options(app: &tauri::App) {
+    use tauri_plugin_deep_link::DeepLinkExt;
+
+    let mut launch = services::launch::from_env();
+    if launch.deep_link.is_none() {
+        if let Ok(Some(urls)) = app.deep_link().get_current() {
+            if let Some(url) = urls.first() {
+                launch.open_deep_link(url.as_str());
+            }
+        }
+    }
+    app.manage(launch);
+}
+
+/// Forwards `carf://` links opened while CARF runs, including those handed
+/// over by a second launch, to the frontend as `carf://launch/deep-link`.
+/// Nothing is attached until the user confirms through `run_deep_link`.
+fn setup_deep_link_handler(app: &tauri::App) {
+    use tauri_plugin_deep_link::DeepLinkExt;
+
+    // Bundles register the scheme on install; on Linux and in Windows dev
+    // builds it has to be registered at runtime.
+    #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+    if let Err(error) = app.deep_link().register_all() {
+        log::warn!("Could not register the carf:// scheme: {error}");
+    }
+
+    let app_handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            let request = services::launch::deep_link_request(url.as_str());
+            let _ = app_handle.emit("carf://launch/deep-link", request);
+        }
+    });
+}
+
+/// Attaches the launch workspace's targets in the background so a slow or
+/// not-yet-running target does not hold up the window.
+fn setup_workspace_auto_attach(app: &tauri::App) {
+    let launch = app.state::<services::launch::LaunchOptions>();
+    // A link's targets wait for the user to confirm through `run_deep_link`.
+    if launch.deep_link.is_some() {
+        return;
+    }
+    let Some(workspace) = launch.workspace.clone() else {
+        return;
+    };
+    if workspace.targets.is_empty() {
+        return;
+    }
+
+    let app_handle = app.handle().clone();
+    std::thread::spawn(move || {
+        let state = app_handle.state::<AppState>();
+        api::auto_attach_workspace(&state, &workspace);
+    });
+}
+
 fn setup_event_forwarder(app: &tauri::App) {
     let app_handle = app.handle().clone();
     let state = app.state::<AppState>();
@@ -1,10 +1,26 @@
 use std::collections::HashMap;
-use std::process::Command;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 
 use crate::error::AppError;
 use crate::services::frida::{AppInfo, ProcessInfo};
+use crate::services::server_binaries;
+
+/// Where frida-server is installed on the device.
+const FRIDA_SERVER_PATH: &str = "/data/local/tmp/frida-server";
+/// frida-server's default listening port.
+const FRIDA_SERVER_PORT: u16 = 27042;
+/// Bytes streamed to the device between progress reports.
+const PUSH_CHUNK_SIZE: usize = 256 * 1024;
+/// How long to wait for a freshly started frida-server to start listening.
+const START_TIMEOUT: Duration = Duration::from_secs(5);
+const START_POLL_INTERVAL: Duration = Duration::from_millis(250);
 
 // ─── ADB types ────────────────────────────────────────────────────────────────
 
@@ -34,8 +50,38 @@ pub struct DeviceProps {
     pub selinux_status: String,
 }
 
+/// Mirrors frontend `FridaServerStatus`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FridaServerStatus {
+    pub serial: String,
+    pub running: bool,
+    pub pids: Vec<u32>,
+    /// Whether anything on the device listens on `port`.
+    pub listening: bool,
+    pub port: u16,
+}
+
+/// One step of a frida-server deployment, emitted as
+/// `carf://adb/frida-server/progress`.
+///
+/// Mirrors frontend `FridaServerProgress`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FridaServerProgress {
+    pub serial: String,
+    /// `resolve`, `push`, `start` or `verify`.
+    pub stage: String,
+    pub bytes_sent: u64,
+    pub total_bytes: u64,
+    pub message: String,
+}
+
 // ─── AdbService ───────────────────────────────────────────────────────────────
 
+/// Holds no state, so long transfers work on a copy instead of holding the
+/// `adb_service` lock.
+#[derive(Clone)]
 pub struct AdbService;
 
 impl AdbService {
@@ -47,13 +93,10 @@ impl AdbService {
 
     /// Runs an adb command and returns stdout as a String.
     fn run(&self, args: &[&str]) -> Result<String, AppError> {
-        let output = Command::new("adb").args(args).output().map_err(|e| {
-            if e.kind() == std::io::ErrorKind::NotFound {
-                AppError::AdbNotFound
-            } else {
-                AppError::AdbError(e.to_string())
-            }
-        })?;
+        let output = Command::new("adb")
+            .args(args)
+            .output()
+            .map_err(spawn_error)?;
 
         if output.status.success() {
             Ok(String::from_utf8_lossy(&output.stdout).into_owned())
@@ -76,6 +119,43 @@ impl AdbService {
         Ok(out.trim().to_string())
     }
 
+    /// Runs `command` on the device as root: directly when adbd already runs
+    /// as root, otherwise through `su`. Only called with fixed commands.
+    fn run_as_root(&self, serial: &str, command: &str) -> Result<String, AppError> {
+        let uid = self
+            .run_on(serial, &["shell", "id", "-u"])
+            .unwrap_or_default();
+        if uid.trim() == "0" {
+            return self.run_on(serial, &["shell", command]);
+        }
+
+        // adb joins the shell arguments back together, so the command has
+        // to be quoted for the device shell to reach `su -c` in one piece.
+        let quoted = format!("'{command}'");
+        self.run_on(serial, &["shell", "su", "-c", &quoted])
+            .or_else(|_| self.run_on(serial, &["shell", "su", "0", "sh", "-c", &quoted]))
+            .map_err(|error| {
+                log::debug!("su failed on {serial}: {error}");
+                AppError::AdbRootRequired
+            })
+    }
+
+    /// Whether a socket on the device listens on `port`, from
+    /// `/proc/net/tcp{,6}` (state `0A`).
+    fn is_port_listening(&self, serial: &str, port: u16) -> bool {
+        let command = "cat /proc/net/tcp /proc/net/tcp6";
+        let table = self
+            .run_on(serial, &["shell", command])
+            .or_else(|_| self.run_as_root(serial, command))
+            .unwrap_or_default();
+        let port = format!(":{port:04X}");
+
+        table.lines().any(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            fields.len() > 3 && fields[1].ends_with(&port) && fields[3] == "0A"
+        })
+    }
+
     fn infer_process_identifier(process: &ProcessInfo) -> Option<&str> {
         process.identifier.as_deref().or_else(|| {
             if process.name.contains('.') {
@@ -228,39 +308,186 @@ impl AdbService {
         Ok(applications)
     }
 
-    /// Pushes a frida-server binary to the device at `/data/local/tmp/frida-server`.
+    /// The frida-server release architecture matching the device's primary ABI.
+    pub fn frida_server_arch(&self, serial: &str) -> Result<String, AppError> {
+        let abi = self.getprop(serial, "ro.product.cpu.abi")?;
+        let arch = match abi.as_str() {
+            "arm64-v8a" => "arm64",
+            "armeabi-v7a" | "armeabi" => "arm",
+            "x86_64" => "x86_64",
+            "x86" => "x86",
+            other => {
+                return Err(AppError::AdbError(format!(
+                    "No frida-server build for ABI {other:?}"
+                )))
+            }
+        };
+        Ok(arch.to_string())
+    }
+
+    /// Where a downloaded `frida-server-{version}-android-{arch}` binary is
+    /// expected on the host: the one installed by `server_binaries`, else
+    /// a copy in the host temp directory.
+    pub fn local_frida_server_path(version: &str, arch: &str) -> PathBuf {
+        server_binaries::installed_path(version, "android", arch).unwrap_or_else(|| {
+            std::env::temp_dir().join(format!("frida-server-{version}-android-{arch}"))
+        })
+    }
+
+    /// Streams `local_path` to `/data/local/tmp/frida-server` and marks it
+    /// executable, calling `progress(bytes_sent, total_bytes)` as it goes.
     pub fn push_frida_server(
         &self,
         serial: &str,
-        version: &str,
-        arch: &str,
+        local_path: &Path,
+        progress: impl FnMut(u64, u64),
     ) -> Result<(), AppError> {
-        let local_path = format!("/tmp/frida-server-{version}-android-{arch}");
-        self.run_on(
-            serial,
-            &["push", &local_path, "/data/local/tmp/frida-server"],
-        )?;
-        // Make executable
-        self.run_on(
-            serial,
-            &["shell", "chmod", "755", "/data/local/tmp/frida-server"],
-        )?;
+        self.push_file(serial, local_path, FRIDA_SERVER_PATH, || false, progress)?;
+        self.run_on(serial, &["shell", "chmod", "755", FRIDA_SERVER_PATH])?;
+        Ok(())
+    }
+
+    /// Streams `local_path` to `remote_path` on the device, calling
+    /// `progress(bytes_sent, total_bytes)` as it goes.
+    pub fn push_file(
+        &self,
+        serial: &str,
+        local_path: &Path,
+        remote_path: &str,
+        is_cancelled: impl Fn() -> bool,
+        mut progress: impl FnMut(u64, u64),
+    ) -> Result<(), AppError> {
+        let mut file = File::open(local_path).map_err(|error| {
+            AppError::AdbError(format!("Cannot read {}: {error}", local_path.display()))
+        })?;
+        let total = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+        // `exec-in` gives us the raw stdin of a device command, which lets us
+        // report progress; `adb push` only prints it to a terminal.
+        let target = format!("cat > {}", shell_quote(remote_path));
+        let mut child = Command::new("adb")
+            .args(["-s", serial, "exec-in", &target])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(spawn_error)?;
+
+        let mut sent = 0;
+        progress(sent, total);
+        if let Some(mut stdin) = child.stdin.take() {
+            let mut buffer = vec![0; PUSH_CHUNK_SIZE];
+            loop {
+                if is_cancelled() {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(cancelled("push", &local_path.display().to_string(), sent));
+                }
+                let read = file
+                    .read(&mut buffer)
+                    .map_err(|error| AppError::AdbError(error.to_string()))?;
+                if read == 0 {
+                    break;
+                }
+                stdin
+                    .write_all(&buffer[..read])
+                    .map_err(|error| AppError::AdbError(error.to_string()))?;
+                sent += read as u64;
+                progress(sent, total);
+            }
+        }
+
+        let output = child
+            .wait_with_output()
+            .map_err(|error| AppError::AdbError(error.to_string()))?;
+        if !output.status.success() {
+            return Err(AppError::AdbError(
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            ));
+        }
         Ok(())
     }
 
-    /// Starts frida-server on the device in the background.
+    /// Streams `remote_path` from the device into `local_path`, calling
+    /// `progress(bytes_received, total_bytes)` as it goes. `local_path` is
+    /// only replaced once the whole file arrived.
+    pub fn pull_file(
+        &self,
+        serial: &str,
+        remote_path: &str,
+        local_path: &Path,
+        is_cancelled: impl Fn() -> bool,
+        mut progress: impl FnMut(u64, u64),
+    ) -> Result<(), AppError> {
+        let quoted = shell_quote(remote_path);
+        let total = self
+            .run_on(serial, &["shell", "stat", "-c", "%s", &quoted])
+            .ok()
+            .and_then(|size| size.trim().parse().ok())
+            .unwrap_or(0);
+
+        let partial = PathBuf::from(format!("{}.part", local_path.display()));
+        let mut file = File::create(&partial).map_err(|error| {
+            AppError::AdbError(format!("Cannot write {}: {error}", partial.display()))
+        })?;
+        let mut child = Command::new("adb")
+            .args(["-s", serial, "exec-out", &format!("cat {quoted}")])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(spawn_error)?;
+
+        let mut received = 0;
+        progress(received, total);
+        let copied = child.stdout.take().map_or(Ok(()), |mut stdout| {
+            let mut buffer = vec![0; PUSH_CHUNK_SIZE];
+            loop {
+                if is_cancelled() {
+                    return Err(cancelled("pull", remote_path, received));
+                }
+                let read = stdout
+                    .read(&mut buffer)
+                    .map_err(|error| AppError::AdbError(error.to_string()))?;
+                if read == 0 {
+                    return Ok(());
+                }
+                file.write_all(&buffer[..read])
+                    .map_err(|error| AppError::AdbError(error.to_string()))?;
+                received += read as u64;
+                progress(received, total);
+            }
+        });
+        if copied.is_err() {
+            let _ = child.kill();
+        }
+        let output = child.wait_with_output();
+        let result = match (copied, output) {
+            (Err(error), _) => Err(error),
+            (_, Err(error)) => Err(AppError::AdbError(error.to_string())),
+            (Ok(()), Ok(output)) if !output.status.success() => Err(AppError::AdbError(
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            )),
+            (Ok(()), Ok(_)) => std::fs::rename(&partial, local_path).map_err(|error| {
+                AppError::AdbError(format!("Cannot write {}: {error}", local_path.display()))
+            }),
+        };
+        if result.is_err() {
+            let _ = std::fs::remove_file(&partial);
+        }
+        result
+    }
+
+    /// Starts frida-server as root; `-D` daemonizes it so adb returns.
     pub fn start_frida_server(&self, serial: &str) -> Result<(), AppError> {
-        self.run_on(
-            serial,
-            &["shell", "nohup", "/data/local/tmp/frida-server", "&"],
-        )?;
+        self.run_as_root(serial, &format!("{FRIDA_SERVER_PATH} -D"))?;
         Ok(())
     }
 
     /// Stops any running frida-server process.
     pub fn stop_frida_server(&self, serial: &str) -> Result<(), AppError> {
         // pkill returns non-zero if no process found; treat both as success
-        let _ = self.run_on(serial, &["shell", "pkill", "-f", "frida-server"]);
+        let _ = self.run_as_root(serial, "pkill -f frida-server");
         Ok(())
     }
 
@@ -272,6 +499,86 @@ impl AdbService {
         Ok(!output.trim().is_empty())
     }
 
+    /// Whether frida-server runs on the device and listens on its port.
+    pub fn frida_server_status(&self, serial: &str) -> Result<FridaServerStatus, AppError> {
+        let pids = self
+            .run_on(serial, &["shell", "pgrep", "-f", "frida-server"])
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|pid| pid.parse().ok())
+            .collect::<Vec<u32>>();
+
+        Ok(FridaServerStatus {
+            serial: serial.to_string(),
+            running: !pids.is_empty(),
+            pids,
+            listening: self.is_port_listening(serial, FRIDA_SERVER_PORT),
+            port: FRIDA_SERVER_PORT,
+        })
+    }
+
+    /// Pushes a frida-server matching the device (or `binary_path`), restarts
+    /// it as root and waits until it listens on its port.
+    pub fn deploy_frida_server(
+        &self,
+        serial: &str,
+        binary_path: Option<&str>,
+        version: &str,
+        mut progress: impl FnMut(FridaServerProgress),
+    ) -> Result<FridaServerStatus, AppError> {
+        let mut report = |stage: &str, bytes_sent: u64, total_bytes: u64, message: String| {
+            progress(FridaServerProgress {
+                serial: serial.to_string(),
+                stage: stage.to_string(),
+                bytes_sent,
+                total_bytes,
+                message,
+            })
+        };
+
+        let local_path = match binary_path {
+            Some(path) => PathBuf::from(path),
+            None => {
+                let arch = self.frida_server_arch(serial)?;
+                Self::local_frida_server_path(version, &arch)
+            }
+        };
+        report("resolve", 0, 0, format!("Using {}", local_path.display()));
+
+        self.stop_frida_server(serial)?;
+        self.push_frida_server(serial, &local_path, |sent, total| {
+            report(
+                "push",
+                sent,
+                total,
+                format!("Pushing to {FRIDA_SERVER_PATH}"),
+            );
+        })?;
+
+        report("start", 0, 0, "Starting frida-server as root".to_string());
+        self.start_frida_server(serial)?;
+
+        report(
+            "verify",
+            0,
+            0,
+            format!("Waiting for port {FRIDA_SERVER_PORT}"),
+        );
+        let deadline = Instant::now() + START_TIMEOUT;
+        loop {
+            let status = self.frida_server_status(serial)?;
+            if status.listening {
+                return Ok(status);
+            }
+            if Instant::now() >= deadline {
+                return Err(AppError::AdbError(format!(
+                    "frida-server did not start listening on port {FRIDA_SERVER_PORT}"
+                )));
+            }
+            thread::sleep(START_POLL_INTERVAL);
+        }
+    }
+
     /// Executes a shell command on the device and returns stdout.
     ///
     /// Even though adb accepts program + args as separate tokens, the device side
@@ -305,6 +612,16 @@ impl AdbService {
         Ok(())
     }
 
+    /// Forwards a free host port to `remote_port` on the device and returns
+    /// the host port adb picked.
+    pub fn forward(&self, serial: &str, remote_port: u16) -> Result<u16, AppError> {
+        let remote = format!("tcp:{remote_port}");
+        let output = self.run_on(serial, &["forward", "tcp:0", &remote])?;
+        output.trim().parse().map_err(|_| {
+            AppError::AdbError(format!("unexpected adb forward output: {}", output.trim()))
+        })
+    }
+
     /// Connects to a device over TCP/IP.
     pub fn connect(&self, address: &str) -> Result<(), AppError> {
         if !address.contains(':') {
@@ -347,3 +664,23 @@ fn validate_shell_token(token: &str) -> Result<(), AppError> {
     }
     Ok(())
 }
+
+fn cancelled(direction: &str, path: &str, bytes: u64) -> AppError {
+    AppError::OperationCancelled(format!(
+        "{direction} of {path} cancelled after {bytes} bytes"
+    ))
+}
+
+/// Single-quotes `path` for the device shell, which re-parses whatever adb
+/// forwards.
+fn shell_quote(path: &str) -> String {
+    format!("'{}'", path.replace('\'', r"'\''"))
+}
+
+fn spawn_error(error: std::io::Error) -> AppError {
+    if error.kind() == std::io::ErrorKind::NotFound {
+        AppError::AdbNotFound
+    } else {
+        AppError::AdbError(error.to_string())
+    }
+}
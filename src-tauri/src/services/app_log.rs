@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::services::storage;
+
+const LOG_DIR: &str = "logs";
+const LOG_FILE: &str = "carf.log";
+const MAX_FILE_BYTES: u64 = 10 * 1024 * 1024;
+/// Rotated files kept besides the current one.
+const MAX_FILES: usize = 5;
+/// Entries kept in memory for `log_get_recent`.
+const MAX_RECENT: usize = 2_000;
+
+static LOGGER: OnceLock<AppLogger> = OnceLock::new();
+
+/// Mirrors frontend `LogEntry`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub timestamp: u64,
+    pub level: String,
+    /// Module the entry came from, e.g. `carf_lib::services::frida::runtime`.
+    pub target: String,
+    pub message: String,
+}
+
+struct LogFile {
+    directory: PathBuf,
+    writer: BufWriter<File>,
+    bytes: u64,
+}
+
+impl LogFile {
+    fn open(directory: PathBuf) -> std::io::Result<Self> {
+        fs::create_dir_all(&directory)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(directory.join(LOG_FILE))?;
+        let bytes = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        Ok(Self {
+            directory,
+            writer: BufWriter::new(file),
+            bytes,
+        })
+    }
+
+    fn append(&mut self, line: &str) -> std::io::Result<()> {
+        if self.bytes > 0 && self.bytes + line.len() as u64 + 1 > MAX_FILE_BYTES {
+            self.rotate()?;
+        }
+        self.writer.write_all(line.as_bytes())?;
+        self.writer.write_all(b"\n")?;
+        // Flushed per line so the file is complete if CARF crashes.
+        self.writer.flush()?;
+        self.bytes += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// Shifts `carf.N.log` up by one, dropping the oldest, and starts a
+    /// fresh `carf.log`.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.writer.flush()?;
+        let rotated = |index: usize| self.directory.join(format!("carf.{index}.log"));
+
+        let _ = fs::remove_file(rotated(MAX_FILES));
+        for index in (1..MAX_FILES).rev() {
+            let from = rotated(index);
+            if from.exists() {
+                fs::rename(&from, rotated(index + 1))?;
+            }
+        }
+        fs::rename(self.directory.join(LOG_FILE), rotated(1))?;
+
+        self.writer = BufWriter::new(File::create(self.directory.join(LOG_FILE))?);
+        self.bytes = 0;
+        Ok(())
+    }
+}
+
+/// Writes to stderr through `env_logger`, and also to a rotating
+/// `logs/carf.log` under the data dir and an in-memory buffer of recent
+/// entries.
+struct AppLogger {
+    console: env_logger::Logger,
+    file: Mutex<Option<LogFile>>,
+    recent: Mutex<VecDeque<LogEntry>>,
+}
+
+impl log::Log for AppLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.console.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.console.matches(record) {
+            return;
+        }
+        self.console.log(record);
+
+        let entry = LogEntry {
+            timestamp: storage::now_millis(),
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+
+        {
+            let mut file = self
+                .file
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if let Some(log_file) = file.as_mut() {
+                let line = serde_json::to_string(&entry).unwrap_or_default();
+                if let Err(error) = log_file.append(&line) {
+                    // Logging the failure here would recurse; stderr only.
+                    eprintln!("Stopping the log file after a write failure: {error}");
+                    *file = None;
+                }
+            }
+        }
+
+        let mut recent = self
+            .recent
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if recent.len() >= MAX_RECENT {
+            recent.pop_front();
+        }
+        recent.push_back(entry);
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        if let Some(log_file) = self
+            .file
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .as_mut()
+        {
+            let _ = log_file.writer.flush();
+        }
+    }
+}
+
+/// Installs the app logger. Without `RUST_LOG` every level passes the
+/// console filter and the effective level is `Settings::log_level`, applied
+/// through the max level once settings load.
+pub fn init() {
+    let console =
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("trace"))
+            .build();
+    let max_level = console.filter();
+
+    let file = match storage::data_file(LOG_DIR).and_then(|directory| {
+        LogFile::open(directory).map_err(|error| AppError::StorageError(error.to_string()))
+    }) {
+        Ok(file) => Some(file),
+        Err(error) => {
+            eprintln!("Logs will not be written to disk: {error}");
+            None
+        }
+    };
+
+    let logger = LOGGER.get_or_init(|| AppLogger {
+        console,
+        file: Mutex::new(file),
+        recent: Mutex::new(VecDeque::new()),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// The last `count` log entries, oldest first.
+pub fn recent(count: usize) -> Vec<LogEntry> {
+    let Some(logger) = LOGGER.get() else {
+        return Vec::new();
+    };
+    let recent = logger
+        .recent
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    recent
+        .iter()
+        .skip(recent.len().saturating_sub(count))
+        .cloned()
+        .collect()
+}
+
+/// `logs/` in the data dir, where `carf.log` and its rotations live.
+pub fn log_dir() -> Result<PathBuf, AppError> {
+    storage::data_file(LOG_DIR)
+}
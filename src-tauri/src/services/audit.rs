@@ -0,0 +1,200 @@
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::error::AppError;
+use crate::services::storage;
+
+const AUDIT_FILE: &str = "audit.ndjson";
+const DEFAULT_QUERY_LIMIT: usize = 200;
+/// Strings longer than this are summarized by length, so script sources and
+/// byte arrays do not bloat the log.
+const MAX_STRING_CHARS: usize = 256;
+const MAX_ARRAY_ITEMS: usize = 32;
+/// Bytes read per step when scanning the log from its end.
+const READ_BLOCK: u64 = 64 * 1024;
+
+/// Agent RPC methods that change the target. Prefixes match whole families
+/// (`hookJavaMethod`, `hookObjcMethod`, ...).
+const MUTATING_RPC_PREFIXES: &[&str] = &[
+    "write",
+    "patch",
+    "protect",
+    "allocate",
+    "call",
+    "hook",
+    "unhook",
+    "freeze",
+    "loadScript",
+    "unloadScript",
+];
+
+/// Serializes appends from concurrent commands.
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// One state-changing action CARF performed.
+///
+/// Mirrors frontend `AuditEntry`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub timestamp: u64,
+    /// `attach`, `spawn`, `kill`, or the agent RPC method called.
+    pub action: String,
+    pub session_id: Option<String>,
+    /// Arguments, with long strings and arrays replaced by their size.
+    pub args: Value,
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+/// Mirrors frontend `AuditFilter`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AuditFilter {
+    pub action: Option<String>,
+    pub session_id: Option<String>,
+    /// Only entries at or after this time, in ms.
+    pub since: Option<u64>,
+    /// Newest entries kept. Defaults to 200.
+    pub limit: Option<usize>,
+}
+
+/// Whether agent RPC `method` changes the target and belongs in the audit
+/// log.
+pub fn is_mutating_rpc(method: &str) -> bool {
+    MUTATING_RPC_PREFIXES
+        .iter()
+        .any(|prefix| method.starts_with(prefix))
+}
+
+/// Appends an entry for `action` with `result`'s outcome. Failing to write
+/// is logged; it never fails the action itself.
+pub fn record<T>(
+    action: &str,
+    session_id: Option<&str>,
+    args: Value,
+    result: &Result<T, AppError>,
+) {
+    let entry = AuditEntry {
+        timestamp: storage::now_millis(),
+        action: action.to_string(),
+        session_id: session_id.map(str::to_string),
+        args: summarize(args),
+        ok: result.is_ok(),
+        error: result.as_ref().err().map(ToString::to_string),
+    };
+    if let Err(error) = append(&entry) {
+        log::warn!("Failed to write audit entry for {action}: {error}");
+    }
+}
+
+/// The newest entries matching `filter`, oldest first. The log is read
+/// backwards from its end, so only as much of it as `limit` needs.
+pub fn query(filter: &AuditFilter) -> Result<Vec<AuditEntry>, AppError> {
+    let path = storage::data_file(AUDIT_FILE)?;
+    let read_error = |error: std::io::Error| {
+        AppError::StorageError(format!("failed to read {}: {error}", path.display()))
+    };
+    let _guard = FILE_LOCK
+        .lock()
+        .map_err(|_| AppError::Internal("audit file lock poisoned".to_string()))?;
+    let mut file = match File::open(&path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(read_error(error)),
+    };
+
+    let limit = filter.limit.unwrap_or(DEFAULT_QUERY_LIMIT);
+    let mut matches = Vec::new();
+    let mut end = file.seek(SeekFrom::End(0)).map_err(read_error)?;
+    // Bytes after the last newline seen, i.e. the start of a line whose
+    // beginning is in an earlier block.
+    let mut tail = Vec::new();
+    while end > 0 && matches.len() < limit {
+        let start = end.saturating_sub(READ_BLOCK);
+        let mut block = vec![0; (end - start) as usize];
+        file.seek(SeekFrom::Start(start)).map_err(read_error)?;
+        file.read_exact(&mut block).map_err(read_error)?;
+        block.extend_from_slice(&tail);
+        end = start;
+
+        let mut lines = block.split(|&byte| byte == b'\n');
+        // Unless this is the start of the file, the first piece is cut off.
+        tail = if start > 0 {
+            lines.next().unwrap_or_default().to_vec()
+        } else {
+            Vec::new()
+        };
+        let complete: Vec<&[u8]> = lines.collect();
+        for line in complete.into_iter().rev() {
+            if matches.len() == limit {
+                break;
+            }
+            let entry = serde_json::from_slice::<AuditEntry>(line).ok();
+            if let Some(entry) = entry.filter(|entry| matches_filter(entry, filter)) {
+                matches.push(entry);
+            }
+        }
+    }
+    matches.reverse();
+    Ok(matches)
+}
+
+fn matches_filter(entry: &AuditEntry, filter: &AuditFilter) -> bool {
+    if let Some(action) = &filter.action {
+        if entry.action != *action {
+            return false;
+        }
+    }
+    if let Some(session_id) = &filter.session_id {
+        if entry.session_id.as_deref() != Some(session_id.as_str()) {
+            return false;
+        }
+    }
+    match filter.since {
+        Some(since) => entry.timestamp >= since,
+        None => true,
+    }
+}
+
+fn append(entry: &AuditEntry) -> Result<(), AppError> {
+    let path = storage::data_file(AUDIT_FILE)?;
+    let line =
+        serde_json::to_string(entry).map_err(|error| AppError::Internal(error.to_string()))?;
+    let _guard = FILE_LOCK
+        .lock()
+        .map_err(|_| AppError::Internal("audit file lock poisoned".to_string()))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|error| {
+            AppError::StorageError(format!("failed to open {}: {error}", path.display()))
+        })?;
+    writeln!(file, "{line}").map_err(|error| {
+        AppError::StorageError(format!("failed to write {}: {error}", path.display()))
+    })
+}
+
+fn summarize(value: Value) -> Value {
+    match value {
+        Value::String(text) if text.chars().count() > MAX_STRING_CHARS => {
+            Value::String(format!("<{} chars>", text.chars().count()))
+        }
+        Value::Array(items) if items.len() > MAX_ARRAY_ITEMS => {
+            Value::String(format!("<{} items>", items.len()))
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(summarize).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key, summarize(value)))
+                .collect::<Map<String, Value>>(),
+        ),
+        other => other,
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::services::storage;
+
+const BACKUP_DIR: &str = "backups";
+const DEFAULT_MAX_COUNT: usize = 20;
+const DEFAULT_MAX_AGE_DAYS: u32 = 30;
+/// Saves of the same file closer together than this share one backup, so a
+/// file rewritten in bursts (e.g. agent variables) cannot push every useful
+/// backup out of retention.
+const MIN_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+static RETENTION: Mutex<BackupRetention> = Mutex::new(BackupRetention {
+    max_count: DEFAULT_MAX_COUNT,
+    max_age_days: DEFAULT_MAX_AGE_DAYS,
+});
+
+/// When each file was last backed up by `snapshot`, in ms.
+static LAST_SNAPSHOT: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+
+/// How many timestamped backups of each file are kept.
+///
+/// Mirrors frontend `BackupRetention`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BackupRetention {
+    /// Backups kept per file; the oldest go first. At least one is kept.
+    pub max_count: usize,
+    /// Backups older than this are removed; 0 keeps them regardless of age.
+    pub max_age_days: u32,
+}
+
+impl Default for BackupRetention {
+    fn default() -> Self {
+        Self {
+            max_count: DEFAULT_MAX_COUNT,
+            max_age_days: DEFAULT_MAX_AGE_DAYS,
+        }
+    }
+}
+
+/// Mirrors frontend `BackupInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    /// Pass to `restore_backup`.
+    pub name: String,
+    /// Data file the backup is of, e.g. `settings.json`.
+    pub source: String,
+    pub created_at: u64,
+    pub size: u64,
+}
+
+/// Applies the retention used for backups taken from now on.
+pub fn set_retention(retention: BackupRetention) {
+    *RETENTION
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = retention;
+}
+
+/// Copies a just-saved data file to `backups/<file>.<millis>` and prunes that
+/// file's older backups, at most once per `MIN_SNAPSHOT_INTERVAL` per file.
+/// Failures are logged; they never fail the save.
+pub fn snapshot(path: &Path) {
+    let Some(source) = path.file_name().and_then(|name| name.to_str()) else {
+        return;
+    };
+    let now = storage::now_millis();
+    {
+        let mut last = LAST_SNAPSHOT
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let interval = MIN_SNAPSHOT_INTERVAL.as_millis() as u64;
+        if last
+            .get(source)
+            .is_some_and(|taken| now.saturating_sub(*taken) < interval)
+        {
+            return;
+        }
+        last.insert(source.to_string(), now);
+    }
+    copy_to_backups(path, source);
+}
+
+fn copy_to_backups(path: &Path, source: &str) {
+    let result = backup_dir().and_then(|directory| {
+        let target = directory.join(format!("{source}.{}", storage::now_millis()));
+        fs::copy(path, &target).map_err(|error| {
+            AppError::StorageError(format!("failed to back up {}: {error}", path.display()))
+        })?;
+        prune(&directory, source);
+        Ok(())
+    });
+    if let Err(error) = result {
+        log::warn!("{error}");
+    }
+}
+
+/// Every backup, newest first.
+pub fn list_backups() -> Result<Vec<BackupInfo>, AppError> {
+    let directory = backup_dir()?;
+    let entries = fs::read_dir(&directory).map_err(|error| {
+        AppError::StorageError(format!("failed to list {}: {error}", directory.display()))
+    })?;
+
+    let mut backups: Vec<BackupInfo> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let (source, created_at) = parse_name(&name)?;
+            Some(BackupInfo {
+                source: source.to_string(),
+                created_at,
+                size: entry.metadata().map_or(0, |metadata| metadata.len()),
+                name,
+            })
+        })
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    Ok(backups)
+}
+
+/// Copies backup `name` over the data file it was taken of, after backing up
+/// that file's current contents so the restore can itself be undone.
+/// Returns the restored file's name.
+pub fn restore_backup(name: &str) -> Result<String, AppError> {
+    let (source, _) = parse_name(name)
+        .filter(|_| !name.contains(['/', '\\']))
+        .ok_or_else(|| AppError::InvalidArgument(format!("Not a backup name: {name}")))?;
+    let backup = backup_dir()?.join(name);
+    if !backup.is_file() {
+        return Err(AppError::InvalidArgument(format!("No backup named {name}")));
+    }
+
+    let target = storage::data_file(source)?;
+    // Always kept, whenever the last backup was taken.
+    if target.exists() {
+        copy_to_backups(&target, source);
+    }
+    fs::copy(&backup, &target).map_err(|error| {
+        AppError::StorageError(format!("failed to restore {}: {error}", target.display()))
+    })?;
+    Ok(source.to_string())
+}
+
+/// `backups/` in the data dir, created if needed.
+pub fn backup_dir() -> Result<PathBuf, AppError> {
+    let directory = storage::data_file(BACKUP_DIR)?;
+    fs::create_dir_all(&directory).map_err(|error| {
+        AppError::StorageError(format!("failed to create {}: {error}", directory.display()))
+    })?;
+    Ok(directory)
+}
+
+/// Splits `settings.json.1700000000000` into the source file name and the
+/// backup's timestamp.
+fn parse_name(name: &str) -> Option<(&str, u64)> {
+    let (source, millis) = name.rsplit_once('.')?;
+    let created_at = millis.parse().ok()?;
+    (!source.is_empty()).then_some((source, created_at))
+}
+
+fn prune(directory: &Path, source: &str) {
+    let retention = RETENTION
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+    let Ok(backups) = list_backups() else {
+        return;
+    };
+
+    for backup in stale_backups(&backups, source, &retention, storage::now_millis()) {
+        if let Err(error) = fs::remove_file(directory.join(&backup.name)) {
+            log::warn!("Failed to remove old backup {}: {error}", backup.name);
+        }
+    }
+}
+
+/// Backups of `source` that `retention` drops as of `now`; `backups` is
+/// newest first, as `list_backups` returns it.
+fn stale_backups<'a>(
+    backups: &'a [BackupInfo],
+    source: &str,
+    retention: &BackupRetention,
+    now: u64,
+) -> Vec<&'a BackupInfo> {
+    let cutoff = (retention.max_age_days > 0).then(|| {
+        let max_age = Duration::from_secs(u64::from(retention.max_age_days) * 24 * 60 * 60);
+        now.saturating_sub(max_age.as_millis() as u64)
+    });
+    backups
+        .iter()
+        .filter(|backup| backup.source == source)
+        .enumerate()
+        // The newest backup stays whatever the limits say.
+        .filter(|(index, backup)| {
+            *index > 0
+                && (*index >= retention.max_count
+                    || cutoff.is_some_and(|cutoff| backup.created_at < cutoff))
+        })
+        .map(|(_, backup)| backup)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60 * 1000;
+    const NOW: u64 = 100 * DAY;
+
+    fn backup(source: &str, created_at: u64) -> BackupInfo {
+        BackupInfo {
+            name: format!("{source}.{created_at}"),
+            source: source.to_string(),
+            created_at,
+            size: 0,
+        }
+    }
+
+    fn retention(max_count: usize, max_age_days: u32) -> BackupRetention {
+        BackupRetention {
+            max_count,
+            max_age_days,
+        }
+    }
+
+    fn stale_names<'a>(backups: &'a [BackupInfo], retention: &BackupRetention) -> Vec<&'a str> {
+        stale_backups(backups, "settings.json", retention, NOW)
+            .into_iter()
+            .map(|backup| backup.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn parse_name_splits_at_the_last_dot() {
+        assert_eq!(
+            parse_name("settings.json.1700000000000"),
+            Some(("settings.json", 1_700_000_000_000))
+        );
+        assert_eq!(
+            parse_name("scripts.index.v2.json.42"),
+            Some(("scripts.index.v2.json", 42))
+        );
+    }
+
+    #[test]
+    fn parse_name_rejects_other_names() {
+        assert_eq!(parse_name("settings.json"), None);
+        assert_eq!(parse_name("settings.json.bak"), None);
+        assert_eq!(parse_name("settings.json.-1"), None);
+        assert_eq!(parse_name("settings.json."), None);
+        assert_eq!(parse_name(".1700000000000"), None);
+        assert_eq!(parse_name("1700000000000"), None);
+    }
+
+    #[test]
+    fn max_count_keeps_exactly_that_many() {
+        let backups: Vec<_> = (0..4)
+            .map(|age| backup("settings.json", NOW - age * 1000))
+            .collect();
+        let names = |count| stale_names(&backups, &retention(count, 0));
+
+        assert!(names(4).is_empty());
+        assert_eq!(names(3), [backups[3].name.as_str()]);
+        assert_eq!(
+            names(2),
+            [backups[2].name.as_str(), backups[3].name.as_str()]
+        );
+    }
+
+    #[test]
+    fn max_age_days_drops_only_older_backups() {
+        let backups = [
+            backup("settings.json", NOW),
+            backup("settings.json", NOW - 30 * DAY),
+            backup("settings.json", NOW - 30 * DAY - 1),
+        ];
+
+        assert_eq!(
+            stale_names(&backups, &retention(10, 30)),
+            [backups[2].name.as_str()]
+        );
+        assert!(stale_names(&backups, &retention(10, 0)).is_empty());
+    }
+
+    #[test]
+    fn the_newest_backup_is_always_kept() {
+        let backups = [
+            backup("settings.json", NOW - 90 * DAY),
+            backup("settings.json", NOW - 91 * DAY),
+        ];
+
+        assert_eq!(
+            stale_names(&backups, &retention(0, 1)),
+            [backups[1].name.as_str()]
+        );
+    }
+
+    #[test]
+    fn other_sources_are_left_alone() {
+        let backups = [
+            backup("settings.json", NOW),
+            backup("variables.json", NOW - 1),
+            backup("settings.json", NOW - 2),
+            backup("variables.json", NOW - 3),
+        ];
+
+        assert_eq!(
+            stale_names(&backups, &retention(1, 0)),
+            [backups[2].name.as_str()]
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::services::frida::FridaService;
+use crate::services::storage;
+
+pub const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// Profile → bookmarks, persisted as one JSON document.
+type BookmarkFile = BTreeMap<String, Vec<Bookmark>>;
+
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A saved address. `location` is `module!offset` (offset in hex) so it
+/// can be rebased each run; a bare `0x...` address is kept as is.
+///
+/// Mirrors frontend `Bookmark`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bookmark {
+    pub name: String,
+    pub location: String,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// A location resolved against the session's current module bases.
+///
+/// Mirrors frontend `ResolvedLocation`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedLocation {
+    pub location: String,
+    pub address: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Mirrors frontend `ResolvedBookmark`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedBookmark {
+    pub name: String,
+    pub location: String,
+    pub address: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A parsed `location`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Location {
+    Module { module: String, offset: u64 },
+    Absolute(u64),
+}
+
+#[derive(Deserialize)]
+struct ModuleSpan {
+    name: String,
+    base: String,
+    size: u64,
+}
+
+struct Module {
+    name: String,
+    base: u64,
+    size: u64,
+}
+
+/// The session's loaded modules, for converting between addresses and
+/// `module!offset` locations.
+pub struct ModuleMap {
+    modules: Vec<Module>,
+}
+
+impl ModuleMap {
+    pub fn load(frida: &mut FridaService, session_id: &str) -> Result<Self, AppError> {
+        let response = frida.rpc_call(session_id, "enumerateModules", json!({}))?;
+        let spans: Vec<ModuleSpan> = serde_json::from_value(response).map_err(|error| {
+            AppError::AgentRpcError(format!("unexpected enumerateModules response: {error}"))
+        })?;
+        let modules = spans
+            .into_iter()
+            .filter_map(|span| {
+                Some(Module {
+                    base: parse_hex(&span.base)?,
+                    name: span.name,
+                    size: span.size,
+                })
+            })
+            .collect();
+        Ok(Self { modules })
+    }
+
+    /// `module!0x...` for an address inside a module.
+    pub fn locate(&self, address: u64) -> Option<String> {
+        self.modules
+            .iter()
+            .find(|module| address >= module.base && address - module.base < module.size)
+            .map(|module| format!("{}!{:#x}", module.name, address - module.base))
+    }
+
+    fn resolve(&self, location: &str) -> ResolvedLocation {
+        let resolved = parse_location(location).and_then(|parsed| match parsed {
+            Location::Absolute(address) => Ok(address),
+            Location::Module { module, offset } => {
+                let found = self
+                    .modules
+                    .iter()
+                    .find(|candidate| candidate.name.eq_ignore_ascii_case(&module))
+                    .ok_or_else(|| {
+                        AppError::InvalidArgument(format!("module {module} is not loaded"))
+                    })?;
+                if offset >= found.size {
+                    return Err(AppError::InvalidArgument(format!(
+                        "offset {offset:#x} is past the end of {module} ({:#x} bytes)",
+                        found.size
+                    )));
+                }
+                Ok(found.base + offset)
+            }
+        });
+        match resolved {
+            Ok(address) => ResolvedLocation {
+                location: location.to_string(),
+                address: Some(format!("{address:#x}")),
+                error: None,
+            },
+            Err(error) => ResolvedLocation {
+                location: location.to_string(),
+                address: None,
+                error: Some(error.to_string()),
+            },
+        }
+    }
+}
+
+pub fn parse_hex(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    u64::from_str_radix(digits, 16).ok()
+}
+
+fn parse_location(location: &str) -> Result<Location, AppError> {
+    let invalid =
+        || AppError::InvalidArgument(format!("{location:?} is not a module!offset or 0x address"));
+    match location.rsplit_once('!') {
+        Some((module, offset)) if !module.trim().is_empty() => Ok(Location::Module {
+            module: module.trim().to_string(),
+            offset: parse_hex(offset).ok_or_else(invalid)?,
+        }),
+        Some(_) => Err(invalid()),
+        None => parse_hex(location)
+            .map(Location::Absolute)
+            .ok_or_else(invalid),
+    }
+}
+
+/// Rebases `module!offset` locations against the session's current module
+/// bases. Absolute addresses pass through unchanged.
+pub fn resolve_locations(
+    frida: &mut FridaService,
+    session_id: &str,
+    locations: &[String],
+) -> Result<Vec<ResolvedLocation>, AppError> {
+    let modules = ModuleMap::load(frida, session_id)?;
+    Ok(locations
+        .iter()
+        .map(|location| modules.resolve(location))
+        .collect())
+}
+
+/// Turns absolute addresses into `module!offset` locations for storing.
+/// Addresses outside every module (heap, stack) come back unchanged.
+pub fn to_locations(
+    frida: &mut FridaService,
+    session_id: &str,
+    addresses: &[String],
+) -> Result<Vec<String>, AppError> {
+    let modules = ModuleMap::load(frida, session_id)?;
+    addresses
+        .iter()
+        .map(|address| {
+            let value = parse_hex(address).ok_or_else(|| {
+                AppError::InvalidArgument(format!("{address:?} is not a 0x address"))
+            })?;
+            Ok(modules
+                .locate(value)
+                .unwrap_or_else(|| format!("{value:#x}")))
+        })
+        .collect()
+}
+
+pub fn list(profile: &str) -> Result<Vec<Bookmark>, AppError> {
+    Ok(read_file()?.remove(profile).unwrap_or_default())
+}
+
+/// Saves a bookmark under `profile`, replacing one with the same name.
+pub fn save(profile: &str, bookmark: Bookmark) -> Result<(), AppError> {
+    if bookmark.name.trim().is_empty() {
+        return Err(AppError::InvalidArgument(
+            "bookmark name must not be empty".to_string(),
+        ));
+    }
+    parse_location(&bookmark.location)?;
+    update_file(|file| {
+        let bookmarks = file.entry(profile.to_string()).or_default();
+        match bookmarks
+            .iter_mut()
+            .find(|saved| saved.name == bookmark.name)
+        {
+            Some(existing) => *existing = bookmark,
+            None => bookmarks.push(bookmark),
+        }
+    })
+}
+
+/// Returns whether a bookmark was removed.
+pub fn delete(profile: &str, name: &str) -> Result<bool, AppError> {
+    update_file(|file| {
+        let Some(bookmarks) = file.get_mut(profile) else {
+            return false;
+        };
+        let before = bookmarks.len();
+        bookmarks.retain(|saved| saved.name != name);
+        let removed = bookmarks.len() != before;
+        if bookmarks.is_empty() {
+            file.remove(profile);
+        }
+        removed
+    })
+}
+
+/// Resolves every bookmark saved for `profile` in the session's process.
+pub fn resolve_profile(
+    frida: &mut FridaService,
+    session_id: &str,
+    profile: &str,
+) -> Result<Vec<ResolvedBookmark>, AppError> {
+    let bookmarks = list(profile)?;
+    if bookmarks.is_empty() {
+        return Ok(Vec::new());
+    }
+    let modules = ModuleMap::load(frida, session_id)?;
+    Ok(bookmarks
+        .into_iter()
+        .map(|bookmark| {
+            let resolved = modules.resolve(&bookmark.location);
+            ResolvedBookmark {
+                name: bookmark.name,
+                location: resolved.location,
+                address: resolved.address,
+                error: resolved.error,
+            }
+        })
+        .collect())
+}
+
+fn lock_file() -> Result<std::sync::MutexGuard<'static, ()>, AppError> {
+    FILE_LOCK
+        .lock()
+        .map_err(|_| AppError::Internal("bookmarks lock poisoned".to_string()))
+}
+
+fn read_file() -> Result<BookmarkFile, AppError> {
+    let _guard = lock_file()?;
+    Ok(storage::read_json_with_backup(&storage::data_file(BOOKMARKS_FILE)?)?.unwrap_or_default())
+}
+
+fn update_file<T>(update: impl FnOnce(&mut BookmarkFile) -> T) -> Result<T, AppError> {
+    let _guard = lock_file()?;
+    let path = storage::data_file(BOOKMARKS_FILE)?;
+    let mut file: BookmarkFile = storage::read_json_with_backup(&path)?.unwrap_or_default();
+    let result = update(&mut file);
+    storage::write_json_with_backup(&path, &file)?;
+    Ok(result)
+}
@@ -0,0 +1,277 @@
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+
+/// Bytes per agent round trip, well under the agent's 4 MiB `readFile` cap.
+/// They travel as a raw `carf:binary` message beside the RPC.
+const CHUNK_SIZE: u64 = 1024 * 1024;
+
+// ─── File transfer types ──────────────────────────────────────────────────────
+
+/// `carf://file-transfer/progress`, emitted per chunk and once more, with
+/// `done` set, when a transfer completes.
+///
+/// Mirrors frontend `FileTransferProgress`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTransferProgress {
+    /// Pass it to `cancel_operation` to stop the transfer.
+    pub transfer_id: String,
+    /// `push` (host to device) or `pull` (device to host).
+    pub direction: String,
+    pub source: String,
+    pub destination: String,
+    pub bytes_transferred: u64,
+    pub total_bytes: u64,
+    pub done: bool,
+}
+
+/// A finished transfer.
+///
+/// Mirrors frontend `FileTransfer`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileTransfer {
+    pub transfer_id: String,
+    pub direction: String,
+    pub source: String,
+    pub destination: String,
+    pub bytes: u64,
+    pub duration_ms: u64,
+}
+
+/// Tracks one transfer and builds its progress events and result.
+pub struct Transfer {
+    id: String,
+    direction: &'static str,
+    source: String,
+    destination: String,
+    started: Instant,
+}
+
+impl Transfer {
+    pub fn push(id: &str, local_path: &str, remote_path: &str) -> Self {
+        Self::new(id, "push", local_path, remote_path)
+    }
+
+    pub fn pull(id: &str, remote_path: &str, local_path: &str) -> Self {
+        Self::new(id, "pull", remote_path, local_path)
+    }
+
+    fn new(id: &str, direction: &'static str, source: &str, destination: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            direction,
+            source: source.to_string(),
+            destination: destination.to_string(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn progress(&self, bytes_transferred: u64, total_bytes: u64) -> FileTransferProgress {
+        FileTransferProgress {
+            transfer_id: self.id.clone(),
+            direction: self.direction.to_string(),
+            source: self.source.clone(),
+            destination: self.destination.clone(),
+            bytes_transferred,
+            total_bytes,
+            done: false,
+        }
+    }
+
+    fn describe(&self) -> String {
+        format!("{} of {}", self.direction, self.source)
+    }
+
+    pub fn finish(&self, bytes: u64) -> FileTransfer {
+        FileTransfer {
+            transfer_id: self.id.clone(),
+            direction: self.direction.to_string(),
+            source: self.source.clone(),
+            destination: self.destination.clone(),
+            bytes,
+            duration_ms: self.started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+impl FileTransfer {
+    /// The `done` progress event that closes a transfer.
+    pub fn progress(&self) -> FileTransferProgress {
+        FileTransferProgress {
+            transfer_id: self.transfer_id.clone(),
+            direction: self.direction.clone(),
+            source: self.source.clone(),
+            destination: self.destination.clone(),
+            bytes_transferred: self.bytes,
+            total_bytes: self.bytes,
+            done: true,
+        }
+    }
+}
+
+// ─── Agent transfers ──────────────────────────────────────────────────────────
+
+/// Copies `transfer`'s local source to the target's file system in chunks
+/// written by the agent (`writeFile`), so it works in any attached process
+/// that may write there. `rpc_write` sends each chunk's bytes with the call.
+pub fn push_via_agent(
+    transfer: &Transfer,
+    mut rpc_write: impl FnMut(&str, Value, Vec<u8>) -> Result<Value, AppError>,
+    is_cancelled: impl Fn() -> bool,
+    mut progress: impl FnMut(FileTransferProgress),
+) -> Result<FileTransfer, AppError> {
+    let local_path = Path::new(&transfer.source);
+    let mut file = File::open(local_path).map_err(|error| io_error("read", local_path, error))?;
+    let total = file.metadata().map(|meta| meta.len()).unwrap_or(0);
+
+    let mut buffer = vec![0; CHUNK_SIZE as usize];
+    let mut sent = 0;
+    progress(transfer.progress(sent, total));
+    loop {
+        if is_cancelled() {
+            return Err(cancelled(&transfer.describe(), sent));
+        }
+        let read = file
+            .read(&mut buffer)
+            .map_err(|error| io_error("read", local_path, error))?;
+        // An empty file still needs its one (truncating) write.
+        if read == 0 && sent > 0 {
+            break;
+        }
+        rpc_write(
+            "writeFile",
+            json!({
+                "path": transfer.destination,
+                "append": sent > 0,
+            }),
+            buffer[..read].to_vec(),
+        )?;
+        sent += read as u64;
+        progress(transfer.progress(sent, total));
+        if read == 0 {
+            break;
+        }
+    }
+    Ok(transfer.finish(sent))
+}
+
+/// Copies `transfer`'s remote source from the target's file system to the
+/// host in chunks read by the agent (`readFile`). `rpc_read` returns each
+/// chunk's bytes beside the reply.
+pub fn pull_via_agent(
+    transfer: &Transfer,
+    mut rpc: impl FnMut(&str, Value) -> Result<Value, AppError>,
+    mut rpc_read: impl FnMut(&str, Value) -> Result<(Value, Vec<u8>), AppError>,
+    is_cancelled: impl Fn() -> bool,
+    mut progress: impl FnMut(FileTransferProgress),
+) -> Result<FileTransfer, AppError> {
+    let total = rpc("fileSize", json!({ "path": transfer.source }))?
+        .as_u64()
+        .ok_or_else(|| AppError::AgentRpcError("fileSize returned no size".to_string()))?;
+
+    let received = receive_to_file(
+        Path::new(&transfer.destination),
+        total,
+        &transfer.describe(),
+        |offset, size| {
+            let (_, bytes) = rpc_read(
+                "readFile",
+                json!({
+                    "path": transfer.source,
+                    "offset": offset,
+                    "size": size,
+                    "encoding": "binary",
+                }),
+            )?;
+            Ok(bytes)
+        },
+        is_cancelled,
+        |received| progress(transfer.progress(received, total)),
+    )?;
+    Ok(transfer.finish(received))
+}
+
+// ─── Streaming to a host file ─────────────────────────────────────────────────
+
+/// Streams up to `total` bytes into `path`, fetching them with
+/// `read_chunk(offset, size)` at most `CHUNK_SIZE` at a time so only one
+/// chunk is ever held in memory. An empty chunk means the source ended
+/// early. The bytes land in a `.part` sibling that only replaces `path` once
+/// everything arrived. `what` names the operation in the cancellation error.
+/// Returns the number of bytes written.
+pub fn receive_to_file(
+    path: &Path,
+    total: u64,
+    what: &str,
+    read_chunk: impl FnMut(u64, u64) -> Result<Vec<u8>, AppError>,
+    is_cancelled: impl Fn() -> bool,
+    progress: impl FnMut(u64),
+) -> Result<u64, AppError> {
+    let partial = PathBuf::from(format!("{}.part", path.display()));
+    let file = File::create(&partial).map_err(|error| io_error("create", &partial, error))?;
+    let written = write_chunks(
+        file,
+        &partial,
+        total,
+        what,
+        read_chunk,
+        is_cancelled,
+        progress,
+    );
+    let result = written.and_then(|written| {
+        fs::rename(&partial, path).map_err(|error| io_error("write", path, error))?;
+        Ok(written)
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&partial);
+    }
+    result
+}
+
+fn write_chunks(
+    file: File,
+    partial: &Path,
+    total: u64,
+    what: &str,
+    mut read_chunk: impl FnMut(u64, u64) -> Result<Vec<u8>, AppError>,
+    is_cancelled: impl Fn() -> bool,
+    mut progress: impl FnMut(u64),
+) -> Result<u64, AppError> {
+    let mut writer = BufWriter::new(file);
+    let mut written = 0;
+    progress(written);
+    while written < total {
+        if is_cancelled() {
+            return Err(cancelled(what, written));
+        }
+        let bytes = read_chunk(written, CHUNK_SIZE.min(total - written))?;
+        if bytes.is_empty() {
+            break;
+        }
+        writer
+            .write_all(&bytes)
+            .map_err(|error| io_error("write", partial, error))?;
+        written += bytes.len() as u64;
+        progress(written);
+    }
+    writer
+        .flush()
+        .map_err(|error| io_error("write", partial, error))?;
+    Ok(written)
+}
+
+fn cancelled(what: &str, bytes: u64) -> AppError {
+    AppError::OperationCancelled(format!("{what} cancelled after {bytes} bytes"))
+}
+
+fn io_error(action: &str, path: &Path, error: std::io::Error) -> AppError {
+    AppError::StorageError(format!("failed to {action} {}: {error}", path.display()))
+}
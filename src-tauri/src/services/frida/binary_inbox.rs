@@ -0,0 +1,44 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Payloads kept for RPCs that have not collected theirs yet. Each call takes
+/// its own right after the reply, so more than this only builds up when a
+/// call failed after the agent sent its bytes.
+const MAX_PENDING: usize = 4;
+
+/// A `binaryId` and the bytes sent with it.
+type Payload = (String, Vec<u8>);
+
+/// Raw bytes the core agent attached to `carf:binary` messages, keyed by the
+/// `binaryId` the RPC that asked for them passed. Chunked dumps and
+/// transfers use it so their data travels beside the JSON reply instead of
+/// inside it as hex. Shared with the agent script's handler, which fills it
+/// from Frida's thread before the reply that follows the message arrives.
+#[derive(Clone, Default)]
+pub(super) struct BinaryInbox {
+    pending: Arc<Mutex<VecDeque<Payload>>>,
+}
+
+impl BinaryInbox {
+    pub(super) fn put(&self, id: String, data: Vec<u8>) {
+        let mut pending = self.lock();
+        if pending.len() >= MAX_PENDING {
+            pending.pop_front();
+        }
+        pending.push_back((id, data));
+    }
+
+    pub(super) fn take(&self, id: &str) -> Option<Vec<u8>> {
+        let mut pending = self.lock();
+        let index = pending
+            .iter()
+            .position(|(pending_id, _)| pending_id == id)?;
+        pending.remove(index).map(|(_, data)| data)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<Payload>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,146 @@
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::os::raw::{c_uint, c_void};
+use std::path::PathBuf;
+use std::sync::mpsc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::services::storage::{self, now_millis};
+
+/// Directory under the data dir that crash reports are written to.
+const CRASHES_DIR: &str = "crashes";
+
+/// A target crash as reported by Frida when the session detached.
+///
+/// Mirrors frontend `CrashReport`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub session_id: String,
+    pub device_id: String,
+    pub pid: u32,
+    pub process_name: String,
+    pub timestamp: u64,
+    pub summary: String,
+    /// The OS crash log, when the device's crash reporter produced one.
+    pub report: String,
+    /// Frida's crash parameters, with non-string values printed as GVariant
+    /// text.
+    pub parameters: BTreeMap<String, String>,
+}
+
+/// Identifies the session a `detached` signal belongs to.
+struct CrashTap {
+    session_id: String,
+    device_id: String,
+    sender: mpsc::Sender<CrashReport>,
+}
+
+/// Connects the session's `detached` signal so a crash that ends it is
+/// sent to `sender`. Detaches without crash information send nothing.
+pub(super) fn connect_detached_signal(
+    session: *mut frida_sys::FridaSession,
+    session_id: &str,
+    device_id: &str,
+    sender: mpsc::Sender<CrashReport>,
+) {
+    let tap = CrashTap {
+        session_id: session_id.to_string(),
+        device_id: device_id.to_string(),
+        sender,
+    };
+    let user_data = Box::into_raw(Box::new(tap)) as *mut c_void;
+
+    unsafe {
+        let callback = Some(std::mem::transmute::<*mut c_void, unsafe extern "C" fn()>(
+            on_detached as *mut c_void,
+        ));
+
+        frida_sys::g_signal_connect_data(
+            session as _,
+            c"detached".as_ptr(),
+            callback,
+            user_data,
+            Some(free_crash_tap),
+            0,
+        );
+    }
+}
+
+/// Writes `report` to `crashes/` and returns the file's path.
+pub(super) fn save_crash_report(report: &CrashReport) -> Result<PathBuf, AppError> {
+    let path = storage::data_dir()?
+        .join(CRASHES_DIR)
+        .join(format!("crash-{}-{}.json", report.pid, report.timestamp));
+    storage::write_json(&path, report)?;
+    Ok(path)
+}
+
+unsafe extern "C" fn on_detached(
+    _session: *mut frida_sys::FridaSession,
+    _reason: c_uint,
+    crash: *mut frida_sys::FridaCrash,
+    user_data: *mut c_void,
+) {
+    if crash.is_null() || user_data.is_null() {
+        return;
+    }
+
+    let tap = &*(user_data as *const CrashTap);
+    let _ = tap.sender.send(CrashReport {
+        session_id: tap.session_id.clone(),
+        device_id: tap.device_id.clone(),
+        pid: frida_sys::frida_crash_get_pid(crash),
+        process_name: read_string(frida_sys::frida_crash_get_process_name(crash)),
+        timestamp: now_millis(),
+        summary: read_string(frida_sys::frida_crash_get_summary(crash)),
+        report: read_string(frida_sys::frida_crash_get_report(crash)),
+        parameters: read_parameters(frida_sys::frida_crash_get_parameters(crash)),
+    });
+}
+
+unsafe extern "C" fn free_crash_tap(user_data: *mut c_void, _closure: *mut frida_sys::GClosure) {
+    drop(Box::from_raw(user_data as *mut CrashTap));
+}
+
+unsafe fn read_string(value: *const frida_sys::gchar) -> String {
+    if value.is_null() {
+        return String::new();
+    }
+    CStr::from_ptr(value).to_string_lossy().into_owned()
+}
+
+/// Flattens a `GHashTable<string, GVariant>` into printable strings.
+unsafe fn read_parameters(parameters: *mut frida_sys::GHashTable) -> BTreeMap<String, String> {
+    let mut values = BTreeMap::new();
+    if parameters.is_null() {
+        return values;
+    }
+
+    let mut iter: frida_sys::GHashTableIter = std::mem::zeroed();
+    frida_sys::g_hash_table_iter_init(&mut iter, parameters);
+    let mut key = std::ptr::null_mut();
+    let mut value = std::ptr::null_mut();
+    while frida_sys::g_hash_table_iter_next(&mut iter, &mut key, &mut value) != 0 {
+        if key.is_null() || value.is_null() {
+            continue;
+        }
+        let variant = value as *mut frida_sys::GVariant;
+        let type_string = CStr::from_ptr(frida_sys::g_variant_get_type_string(variant));
+        let text = if type_string.to_bytes() == b"s" {
+            read_string(frida_sys::g_variant_get_string(
+                variant,
+                std::ptr::null_mut(),
+            ))
+        } else {
+            let printed = frida_sys::g_variant_print(variant, 0);
+            let text = read_string(printed);
+            frida_sys::g_free(printed as _);
+            text
+        };
+        values.insert(read_string(key as *const frida_sys::gchar), text);
+    }
+    values
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::storage::now_millis;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const FIRST_RETRY: Duration = Duration::from_secs(1);
+const MAX_RETRY: Duration = Duration::from_secs(60);
+const MAX_RECONNECT_ATTEMPTS: u32 = 8;
+/// How long a probe (and the reconnect after it) may run before the target
+/// counts as down. The worker is not stopped, its late result is dropped.
+pub(super) const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `carf://device/unhealthy`: a device with sessions (or a remote endpoint)
+/// stopped answering. Emitted when the outage starts and again when
+/// reconnecting a remote device is given up.
+///
+/// Mirrors frontend `DeviceUnhealthyEvent`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceUnhealthy {
+    pub device_id: String,
+    /// Set for remote devices, which are reconnected automatically.
+    pub address: Option<String>,
+    pub reason: String,
+    pub attempts: u32,
+    /// When the next reconnect is tried; `None` when none will be.
+    pub next_retry_ms: Option<u64>,
+    pub timestamp: u64,
+}
+
+/// `carf://device/reconnected`: an unhealthy device answers again.
+///
+/// Mirrors frontend `DeviceReconnectedEvent`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceReconnected {
+    pub device_id: String,
+    pub address: Option<String>,
+    /// Reconnects it took; `0` when the device came back on its own.
+    pub attempts: u32,
+    pub down_ms: u64,
+    pub timestamp: u64,
+}
+
+/// What the health check pings. Remote devices are tracked by the address
+/// they were added with, so they can be re-added after the manager drops
+/// them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum HealthTarget {
+    Remote(String),
+    Device(String),
+}
+
+impl HealthTarget {
+    pub(super) fn key(&self) -> &str {
+        match self {
+            HealthTarget::Remote(address) => address,
+            HealthTarget::Device(device_id) => device_id,
+        }
+    }
+
+    fn address(&self) -> Option<String> {
+        match self {
+            HealthTarget::Remote(address) => Some(address.clone()),
+            HealthTarget::Device(_) => None,
+        }
+    }
+}
+
+struct Outage {
+    target: HealthTarget,
+    /// Id the device had when it was last seen healthy.
+    device_id: String,
+    since: u64,
+    attempts: u32,
+    next_retry: Option<Instant>,
+}
+
+/// Devices currently failing their health check, and when to look again.
+#[derive(Default)]
+pub(super) struct DeviceHealthStore {
+    next_check: Option<Instant>,
+    /// Last id seen for each healthy target, for events about it later.
+    device_ids: HashMap<String, String>,
+    outages: HashMap<String, Outage>,
+    /// Probes running on worker threads, and whether each also reconnects.
+    in_flight: HashMap<String, Probe>,
+}
+
+struct Probe {
+    target: HealthTarget,
+    reconnect: bool,
+    started: Instant,
+}
+
+impl DeviceHealthStore {
+    /// Whether the periodic check or a pending reconnect is due.
+    pub(super) fn is_due(&self, now: Instant) -> bool {
+        self.next_check.map_or(true, |next| next <= now)
+            || self
+                .outages
+                .values()
+                .any(|outage| outage.next_retry.is_some_and(|retry| retry <= now))
+    }
+
+    pub(super) fn schedule(&mut self, now: Instant) {
+        if self.next_check.map_or(true, |next| next <= now) {
+            self.next_check = Some(now + CHECK_INTERVAL);
+        }
+    }
+
+    /// Marks a probe of `target` as started; `false` when one still runs.
+    pub(super) fn begin(&mut self, target: &HealthTarget, reconnect: bool, now: Instant) -> bool {
+        if self.in_flight.contains_key(target.key()) {
+            return false;
+        }
+        let probe = Probe {
+            target: target.clone(),
+            reconnect,
+            started: now,
+        };
+        self.in_flight.insert(target.key().to_string(), probe);
+        true
+    }
+
+    /// Marks a probe as done; `false` when it already timed out, so its
+    /// result is stale.
+    pub(super) fn finish(&mut self, target: &HealthTarget) -> bool {
+        self.in_flight.remove(target.key()).is_some()
+    }
+
+    /// Probes that ran past `PROBE_TIMEOUT`, with whether they reconnect.
+    pub(super) fn timed_out(&mut self, now: Instant) -> Vec<(HealthTarget, bool)> {
+        let expired: Vec<String> = self
+            .in_flight
+            .iter()
+            .filter(|(_, probe)| now.saturating_duration_since(probe.started) >= PROBE_TIMEOUT)
+            .map(|(key, _)| key.clone())
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|key| self.in_flight.remove(&key))
+            .map(|probe| (probe.target, probe.reconnect))
+            .collect()
+    }
+
+    /// Targets that are down but no longer otherwise monitored, e.g. a USB
+    /// device whose sessions were reaped when it dropped off.
+    pub(super) fn failing_targets(&self) -> Vec<HealthTarget> {
+        self.outages
+            .values()
+            .map(|outage| outage.target.clone())
+            .collect()
+    }
+
+    /// Records a passed check; returns the reconnect event when `target` was
+    /// down.
+    pub(super) fn healthy(
+        &mut self,
+        target: &HealthTarget,
+        device_id: &str,
+    ) -> Option<DeviceReconnected> {
+        self.device_ids
+            .insert(target.key().to_string(), device_id.to_string());
+        let outage = self.outages.remove(target.key())?;
+        let timestamp = now_millis();
+        Some(DeviceReconnected {
+            device_id: device_id.to_string(),
+            address: target.address(),
+            attempts: outage.attempts,
+            down_ms: timestamp.saturating_sub(outage.since),
+            timestamp,
+        })
+    }
+
+    /// Records a failed check; returns the event to emit when this starts a
+    /// new outage.
+    pub(super) fn unhealthy(
+        &mut self,
+        target: &HealthTarget,
+        reason: &str,
+        now: Instant,
+    ) -> Option<DeviceUnhealthy> {
+        if self.outages.contains_key(target.key()) {
+            return None;
+        }
+        let device_id = self
+            .device_ids
+            .get(target.key())
+            .cloned()
+            .unwrap_or_else(|| target.key().to_string());
+        let next_retry = matches!(target, HealthTarget::Remote(_)).then_some(now + FIRST_RETRY);
+        let outage = Outage {
+            target: target.clone(),
+            device_id,
+            since: now_millis(),
+            attempts: 0,
+            next_retry,
+        };
+        let event = self.event(&outage, reason, now);
+        self.outages.insert(target.key().to_string(), outage);
+        Some(event)
+    }
+
+    /// Whether a reconnect of `target` should be tried now.
+    pub(super) fn should_reconnect(&self, target: &HealthTarget, now: Instant) -> bool {
+        self.outages
+            .get(target.key())
+            .and_then(|outage| outage.next_retry)
+            .is_some_and(|retry| retry <= now)
+    }
+
+    /// Records a failed reconnect and backs off exponentially. Returns the
+    /// event to emit when that was the last attempt.
+    pub(super) fn reconnect_failed(
+        &mut self,
+        target: &HealthTarget,
+        reason: &str,
+        now: Instant,
+    ) -> Option<DeviceUnhealthy> {
+        let outage = self.outages.get_mut(target.key())?;
+        outage.attempts += 1;
+        if outage.attempts < MAX_RECONNECT_ATTEMPTS {
+            let delay = FIRST_RETRY
+                .saturating_mul(1u32 << outage.attempts.min(16))
+                .min(MAX_RETRY);
+            outage.next_retry = Some(now + delay);
+            return None;
+        }
+        outage.next_retry = None;
+        Some(self.event(&self.outages[target.key()], reason, now))
+    }
+
+    /// Counts a reconnect that succeeded, so `DeviceReconnected` reports it.
+    pub(super) fn reconnected(&mut self, target: &HealthTarget) {
+        if let Some(outage) = self.outages.get_mut(target.key()) {
+            outage.attempts += 1;
+        }
+    }
+
+    /// Stops tracking `key`, e.g. when its remote device is removed.
+    pub(super) fn forget(&mut self, key: &str) {
+        self.outages.remove(key);
+        self.device_ids.remove(key);
+        self.in_flight.remove(key);
+    }
+
+    fn event(&self, outage: &Outage, reason: &str, now: Instant) -> DeviceUnhealthy {
+        DeviceUnhealthy {
+            device_id: outage.device_id.clone(),
+            address: outage.target.address(),
+            reason: reason.to_string(),
+            attempts: outage.attempts,
+            next_retry_ms: outage
+                .next_retry
+                .map(|retry| retry.saturating_duration_since(now).as_millis() as u64),
+            timestamp: now_millis(),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use serde_json::json;
+
+use crate::state::BridgeEvent;
+
+/// Carries several script events in one emit. The frontend's `listen`
+/// unpacks it, so listeners see the same events either way.
+const BATCH_EVENT: &str = "carf://events/batch";
+
+/// Minimum spacing between emits while a script is chatty. The actor polls
+/// this often while events keep coming; the first event after a quiet second
+/// waits for its regular poll instead.
+const FLUSH_INTERVAL: Duration = Duration::from_millis(16);
+/// A batch is emitted as soon as it holds this many events.
+const MAX_BATCH: usize = 500;
+/// Events of one name accepted per second; the rest are dropped and counted.
+const MAX_EVENTS_PER_SECOND: u32 = 5_000;
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+/// Events that answer a request or report a failure. They are never dropped,
+/// however chatty the script is.
+const UNLIMITED_EVENTS: &[&str] = &["carf://scan/result", "carf://script/error"];
+
+/// Coalesces script events toward the frontend so a tracing agent calling
+/// `send()` thousands of times a second costs a few emits per frame instead
+/// of one each.
+pub struct EventBatcher {
+    pending: Vec<BridgeEvent>,
+    last_flush: Instant,
+    last_push: Option<Instant>,
+    window_start: Instant,
+    window_counts: HashMap<String, u32>,
+    /// Drops since the last flush, by event name.
+    dropped: HashMap<String, u64>,
+    dropped_total: u64,
+}
+
+impl Default for EventBatcher {
+    fn default() -> Self {
+        let now = Instant::now();
+        Self {
+            pending: Vec::new(),
+            last_flush: now,
+            last_push: None,
+            window_start: now,
+            window_counts: HashMap::new(),
+            dropped: HashMap::new(),
+            dropped_total: 0,
+        }
+    }
+}
+
+impl EventBatcher {
+    pub fn push(&mut self, event: BridgeEvent, now: Instant) {
+        self.last_push = Some(now);
+        if UNLIMITED_EVENTS.contains(&event.name.as_str()) {
+            self.pending.push(event);
+            return;
+        }
+        if now.duration_since(self.window_start) >= RATE_WINDOW {
+            self.window_start = now;
+            self.window_counts.clear();
+        }
+
+        let count = self.window_counts.entry(event.name.clone()).or_default();
+        if *count >= MAX_EVENTS_PER_SECOND {
+            *self.dropped.entry(event.name).or_default() += 1;
+            self.dropped_total += 1;
+            return;
+        }
+        *count += 1;
+        self.pending.push(event);
+    }
+
+    /// Whether a flush should happen now: the batch is full, or something is
+    /// waiting and the last flush was at least a frame ago.
+    pub fn is_due(&self, now: Instant) -> bool {
+        if self.pending.len() >= MAX_BATCH {
+            return true;
+        }
+        let waiting = !self.pending.is_empty() || !self.dropped.is_empty();
+        waiting && now.duration_since(self.last_flush) >= FLUSH_INTERVAL
+    }
+
+    /// How soon the actor should look again: when the waiting batch is due,
+    /// or a frame from now while a script sent something in the last second.
+    /// `None` when the batcher is idle.
+    pub fn poll_within(&self, now: Instant) -> Option<Duration> {
+        let waiting = !self.pending.is_empty() || !self.dropped.is_empty();
+        if waiting {
+            return Some(FLUSH_INTERVAL.saturating_sub(now.duration_since(self.last_flush)));
+        }
+        self.last_push
+            .filter(|pushed| now.duration_since(*pushed) < RATE_WINDOW)
+            .map(|_| FLUSH_INTERVAL)
+    }
+
+    /// Events to emit for everything pending. A lone event with nothing
+    /// dropped goes out as itself; anything else is wrapped in one or more
+    /// `BATCH_EVENT`s, the first carrying the drop counters.
+    pub fn flush(&mut self, now: Instant) -> Vec<BridgeEvent> {
+        self.last_flush = now;
+        if self.pending.len() == 1 && self.dropped.is_empty() {
+            return self.pending.drain(..).collect();
+        }
+
+        let mut dropped = std::mem::take(&mut self.dropped);
+        let mut batches = Vec::new();
+        let mut pending = std::mem::take(&mut self.pending).into_iter().peekable();
+        while pending.peek().is_some() || !dropped.is_empty() {
+            let events: Vec<BridgeEvent> = pending.by_ref().take(MAX_BATCH).collect();
+            batches.push(BridgeEvent {
+                name: BATCH_EVENT.to_string(),
+                payload: json!({
+                    "events": events,
+                    "dropped": std::mem::take(&mut dropped),
+                    "droppedTotal": self.dropped_total,
+                }),
+            });
+        }
+        batches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACE: &str = "carf://trace/event";
+    const SCAN_RESULT: &str = "carf://scan/result";
+    const SCRIPT_ERROR: &str = "carf://script/error";
+
+    fn event(name: &str) -> BridgeEvent {
+        BridgeEvent {
+            name: name.to_string(),
+            payload: json!({}),
+        }
+    }
+
+    /// Names of every event a flush delivers, unpacking batches.
+    fn delivered(events: &[BridgeEvent]) -> Vec<String> {
+        events
+            .iter()
+            .flat_map(|event| match event.name.as_str() {
+                BATCH_EVENT => event.payload["events"]
+                    .as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|inner| inner["name"].as_str().unwrap().to_string())
+                    .collect(),
+                name => vec![name.to_string()],
+            })
+            .collect()
+    }
+
+    fn count(names: &[String], name: &str) -> usize {
+        names.iter().filter(|candidate| *candidate == name).count()
+    }
+
+    #[test]
+    fn pending_events_wait_for_the_flush_interval() {
+        let mut batcher = EventBatcher::default();
+        let start = Instant::now();
+        assert!(batcher.flush(start).is_empty());
+        assert!(!batcher.is_due(start));
+
+        batcher.push(event(TRACE), start);
+        assert!(!batcher.is_due(start));
+        assert!(batcher.poll_within(start).unwrap() <= FLUSH_INTERVAL);
+        let due = start + FLUSH_INTERVAL;
+        assert!(batcher.is_due(due));
+
+        // A lone event goes out as itself.
+        let flushed = batcher.flush(due);
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].name, TRACE);
+
+        batcher.push(event(TRACE), due + Duration::from_millis(1));
+        assert!(!batcher.is_due(due + Duration::from_millis(15)));
+        assert_eq!(
+            batcher.poll_within(due + Duration::from_millis(6)),
+            Some(Duration::from_millis(10))
+        );
+        assert!(batcher.is_due(due + FLUSH_INTERVAL));
+    }
+
+    #[test]
+    fn a_full_batch_is_due_at_once() {
+        let mut batcher = EventBatcher::default();
+        let start = Instant::now();
+        for _ in 0..MAX_BATCH {
+            batcher.push(event(TRACE), start);
+        }
+        assert!(batcher.is_due(start));
+
+        let flushed = batcher.flush(start);
+        assert_eq!(flushed.len(), 1);
+        assert_eq!(flushed[0].name, BATCH_EVENT);
+        assert_eq!(delivered(&flushed).len(), MAX_BATCH);
+    }
+
+    #[test]
+    fn polling_stops_a_second_after_the_last_push() {
+        let mut batcher = EventBatcher::default();
+        let start = Instant::now();
+        assert_eq!(batcher.poll_within(start), None);
+
+        batcher.push(event(TRACE), start);
+        batcher.flush(start);
+        assert_eq!(
+            batcher.poll_within(start + Duration::from_millis(500)),
+            Some(FLUSH_INTERVAL)
+        );
+        assert_eq!(batcher.poll_within(start + RATE_WINDOW), None);
+    }
+
+    #[test]
+    fn events_over_the_rate_limit_are_dropped_and_counted() {
+        let mut batcher = EventBatcher::default();
+        let start = Instant::now();
+        for _ in 0..MAX_EVENTS_PER_SECOND + 10 {
+            batcher.push(event(TRACE), start);
+        }
+
+        let flushed = batcher.flush(start + FLUSH_INTERVAL);
+        assert_eq!(delivered(&flushed).len(), MAX_EVENTS_PER_SECOND as usize);
+        assert_eq!(flushed[0].payload["dropped"], json!({ TRACE: 10 }));
+        assert_eq!(flushed[0].payload["droppedTotal"], 10);
+        assert_eq!(flushed[1].payload["dropped"], json!({}));
+
+        // The next window accepts events again.
+        let next = start + RATE_WINDOW;
+        batcher.push(event(TRACE), next);
+        assert_eq!(delivered(&batcher.flush(next)), [TRACE]);
+    }
+
+    #[test]
+    fn scan_results_and_script_errors_bypass_the_rate_limit() {
+        let mut batcher = EventBatcher::default();
+        let start = Instant::now();
+        for _ in 0..MAX_EVENTS_PER_SECOND + 1 {
+            batcher.push(event(TRACE), start);
+        }
+        for _ in 0..3 {
+            batcher.push(event(SCAN_RESULT), start);
+            batcher.push(event(SCRIPT_ERROR), start);
+        }
+
+        let flushed = batcher.flush(start + FLUSH_INTERVAL);
+        let names = delivered(&flushed);
+        assert_eq!(count(&names, SCAN_RESULT), 3);
+        assert_eq!(count(&names, SCRIPT_ERROR), 3);
+        assert_eq!(count(&names, TRACE), MAX_EVENTS_PER_SECOND as usize);
+        assert_eq!(flushed[0].payload["dropped"], json!({ TRACE: 1 }));
+    }
+}
@@ -0,0 +1,515 @@
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+use super::owned::DeviceRef;
+use super::util::take_gerror_message;
+
+const PUMP_BUFFER_SIZE: usize = 64 * 1024;
+
+/// A loopback listener whose connections are tunneled to a TCP port on a
+/// device through Frida's channel API.
+///
+/// Mirrors frontend `PortForward`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortForward {
+    pub id: String,
+    pub device_id: String,
+    pub remote_port: u16,
+    pub local_port: u16,
+    pub created_at: u64,
+}
+
+impl DeviceRef {
+    fn open_channel(&self, address: &CString) -> Result<Channel, String> {
+        let cancellable = unsafe { frida_sys::g_cancellable_new() };
+        let mut error = std::ptr::null_mut();
+        let stream = unsafe {
+            frida_sys::frida_device_open_channel_sync(
+                self.as_ptr(),
+                address.as_ptr(),
+                cancellable,
+                &mut error,
+            )
+        };
+
+        if !error.is_null() || stream.is_null() {
+            unsafe { frida_sys::frida_unref(cancellable.cast()) };
+            return Err(take_gerror_message(error));
+        }
+        Ok(Channel {
+            stream,
+            cancellable,
+        })
+    }
+}
+
+/// One tunneled connection. Each direction is pumped by its own thread, but
+/// the stream belongs to Frida's main context: every read, write and close
+/// is started there as an async GIO call, which the actor's
+/// `MainContextPump` completes while the thread waits for its outcome.
+/// Cancelling ends whichever read is still pending.
+struct Channel {
+    stream: *mut frida_sys::GIOStream,
+    cancellable: *mut frida_sys::GCancellable,
+}
+
+unsafe impl Send for Channel {}
+unsafe impl Sync for Channel {}
+
+enum StreamOp {
+    Read,
+    Write,
+    CloseOutput,
+}
+
+/// One stream call in flight. It owns the buffer the call reads into or
+/// writes from, so the buffer outlives the call even if nobody waits.
+struct PendingOp {
+    channel: Arc<Channel>,
+    buffer: Vec<u8>,
+    done: mpsc::SyncSender<(Vec<u8>, Result<usize, String>)>,
+}
+
+impl Channel {
+    /// Copies device output to `socket` until the device closes the stream.
+    fn pump_to_socket(self: &Arc<Self>, socket: &mut TcpStream) -> Result<(), String> {
+        let mut buffer = vec![0u8; PUMP_BUFFER_SIZE];
+        loop {
+            buffer.resize(PUMP_BUFFER_SIZE, 0);
+            let (returned, read) = self.run(StreamOp::Read, buffer);
+            buffer = returned;
+            let read = read?;
+            if read == 0 {
+                return Ok(());
+            }
+            socket
+                .write_all(&buffer[..read])
+                .map_err(|error| error.to_string())?;
+        }
+    }
+
+    /// Copies `socket` input to the device. A clean EOF half-closes the
+    /// channel so the device side can still answer.
+    fn pump_from_socket(self: &Arc<Self>, socket: &mut TcpStream) -> Result<(), String> {
+        let mut buffer = vec![0u8; PUMP_BUFFER_SIZE];
+        loop {
+            buffer.resize(PUMP_BUFFER_SIZE, 0);
+            let read = socket
+                .read(&mut buffer)
+                .map_err(|error| error.to_string())?;
+            if read == 0 {
+                return self.run(StreamOp::CloseOutput, buffer).1.map(drop);
+            }
+            buffer.truncate(read);
+            let (returned, written) = self.run(StreamOp::Write, buffer);
+            buffer = returned;
+            written?;
+        }
+    }
+
+    /// Starts `op` on Frida's main context and waits for it. Returns the
+    /// buffer with the bytes read, or as it was passed, and the byte count.
+    fn run(self: &Arc<Self>, op: StreamOp, buffer: Vec<u8>) -> (Vec<u8>, Result<usize, String>) {
+        let (done, outcome) = mpsc::sync_channel(1);
+        let pending = Box::new(PendingOp {
+            channel: self.clone(),
+            buffer,
+            done,
+        });
+        invoke_on_main_context(Box::new(move || unsafe { start_op(op, pending) }));
+        outcome.recv().unwrap_or_else(|_| {
+            (
+                Vec::new(),
+                Err("Frida's main context dropped the call".to_string()),
+            )
+        })
+    }
+
+    fn cancel(&self) {
+        unsafe { frida_sys::g_cancellable_cancel(self.cancellable) };
+    }
+}
+
+impl Drop for Channel {
+    fn drop(&mut self) {
+        let handles = ChannelHandles {
+            stream: self.stream,
+            cancellable: self.cancellable,
+        };
+        invoke_on_main_context(Box::new(move || handles.close()));
+    }
+}
+
+/// What a dropped `Channel` leaves for the main context to close.
+struct ChannelHandles {
+    stream: *mut frida_sys::GIOStream,
+    cancellable: *mut frida_sys::GCancellable,
+}
+
+unsafe impl Send for ChannelHandles {}
+
+impl ChannelHandles {
+    fn close(self) {
+        unsafe {
+            // The close keeps its own reference until it completes.
+            frida_sys::g_io_stream_close_async(
+                self.stream,
+                frida_sys::G_PRIORITY_DEFAULT as i32,
+                std::ptr::null_mut(),
+                None,
+                std::ptr::null_mut(),
+            );
+            frida_sys::frida_unref(self.stream.cast());
+            frida_sys::frida_unref(self.cancellable.cast());
+        }
+    }
+}
+
+type MainContextTask = Box<dyn FnOnce() + Send>;
+
+/// Queues `task` on Frida's main context, made the thread default while it
+/// runs so the async calls it starts complete on that context too.
+fn invoke_on_main_context(task: MainContextTask) {
+    unsafe extern "C" fn run(data: frida_sys::gpointer) -> frida_sys::gboolean {
+        let task = Box::from_raw(data.cast::<MainContextTask>());
+        let context = frida_sys::frida_get_main_context();
+        frida_sys::g_main_context_push_thread_default(context);
+        task();
+        frida_sys::g_main_context_pop_thread_default(context);
+        frida_sys::FALSE as i32
+    }
+
+    let data = Box::into_raw(Box::new(task));
+    unsafe {
+        frida_sys::g_main_context_invoke(
+            frida_sys::frida_get_main_context(),
+            Some(run),
+            data.cast(),
+        );
+    }
+}
+
+/// # Safety
+/// Must run on Frida's main context, as `invoke_on_main_context` does.
+unsafe fn start_op(op: StreamOp, mut pending: Box<PendingOp>) {
+    let stream = pending.channel.stream;
+    let cancellable = pending.channel.cancellable;
+    let priority = frida_sys::G_PRIORITY_DEFAULT as i32;
+    // Moving the box into the callback's data leaves the buffer in place.
+    let buffer = pending.buffer.as_mut_ptr();
+    let len = pending.buffer.len();
+    let data = Box::into_raw(pending).cast();
+    match op {
+        StreamOp::Read => frida_sys::g_input_stream_read_async(
+            frida_sys::g_io_stream_get_input_stream(stream),
+            buffer.cast(),
+            len,
+            priority,
+            cancellable,
+            Some(read_ready),
+            data,
+        ),
+        StreamOp::Write => frida_sys::g_output_stream_write_all_async(
+            frida_sys::g_io_stream_get_output_stream(stream),
+            buffer.cast_const().cast(),
+            len,
+            priority,
+            cancellable,
+            Some(write_ready),
+            data,
+        ),
+        StreamOp::CloseOutput => frida_sys::g_output_stream_close_async(
+            frida_sys::g_io_stream_get_output_stream(stream),
+            priority,
+            cancellable,
+            Some(close_ready),
+            data,
+        ),
+    }
+}
+
+unsafe extern "C" fn read_ready(
+    source: *mut frida_sys::GObject,
+    result: *mut frida_sys::GAsyncResult,
+    data: frida_sys::gpointer,
+) {
+    let mut error = std::ptr::null_mut();
+    let read = frida_sys::g_input_stream_read_finish(source.cast(), result, &mut error);
+    let outcome = if read < 0 {
+        Err(take_gerror_message(error))
+    } else {
+        Ok(read as usize)
+    };
+    finish_op(data, outcome);
+}
+
+unsafe extern "C" fn write_ready(
+    source: *mut frida_sys::GObject,
+    result: *mut frida_sys::GAsyncResult,
+    data: frida_sys::gpointer,
+) {
+    let mut error = std::ptr::null_mut();
+    let mut written = 0;
+    let ok = frida_sys::g_output_stream_write_all_finish(
+        source.cast(),
+        result,
+        &mut written,
+        &mut error,
+    );
+    let outcome = if ok == 0 {
+        Err(take_gerror_message(error))
+    } else {
+        Ok(written)
+    };
+    finish_op(data, outcome);
+}
+
+unsafe extern "C" fn close_ready(
+    source: *mut frida_sys::GObject,
+    result: *mut frida_sys::GAsyncResult,
+    data: frida_sys::gpointer,
+) {
+    let mut error = std::ptr::null_mut();
+    let ok = frida_sys::g_output_stream_close_finish(source.cast(), result, &mut error);
+    let outcome = if ok == 0 {
+        Err(take_gerror_message(error))
+    } else {
+        Ok(0)
+    };
+    finish_op(data, outcome);
+}
+
+unsafe fn finish_op(data: frida_sys::gpointer, outcome: Result<usize, String>) {
+    let pending = Box::from_raw(data.cast::<PendingOp>());
+    let PendingOp { buffer, done, .. } = *pending;
+    let _ = done.send((buffer, outcome));
+}
+
+/// State shared between a forward's accept thread and whoever stops it.
+struct Listener {
+    local_addr: SocketAddr,
+    stopped: AtomicBool,
+    next_connection: AtomicU64,
+    /// Client sockets still being served, so stopping can cut them off.
+    connections: Mutex<HashMap<u64, TcpStream>>,
+}
+
+impl Listener {
+    fn stop(&self) {
+        if self.stopped.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // Wake the accept loop so it notices the flag.
+        let _ = TcpStream::connect(self.local_addr);
+        if let Ok(mut connections) = self.connections.lock() {
+            for (_, socket) in connections.drain() {
+                let _ = socket.shutdown(Shutdown::Both);
+            }
+        }
+    }
+
+    fn track(&self, socket: &TcpStream) -> Option<u64> {
+        let id = self.next_connection.fetch_add(1, Ordering::Relaxed);
+        let clone = socket.try_clone().ok()?;
+        self.connections.lock().ok()?.insert(id, clone);
+        Some(id)
+    }
+
+    fn untrack(&self, id: u64) {
+        if let Ok(mut connections) = self.connections.lock() {
+            connections.remove(&id);
+        }
+    }
+}
+
+struct ActiveForward {
+    info: PortForward,
+    listener: Arc<Listener>,
+}
+
+/// Port forwards opened with `device_forward_port`, keyed by forward id.
+/// Dropping the store closes every listener.
+#[derive(Default)]
+pub(super) struct ForwardStore {
+    forwards: HashMap<String, ActiveForward>,
+    next_id: u64,
+}
+
+impl ForwardStore {
+    /// Binds an ephemeral loopback port and starts accepting connections,
+    /// each of which opens a fresh `tcp:<remote_port>` channel on the device.
+    ///
+    /// # Safety
+    /// `device` must point to a live `FridaDevice`; a reference is taken for
+    /// the lifetime of the forward.
+    pub(super) unsafe fn start(
+        &mut self,
+        device_id: &str,
+        device: *mut frida_sys::FridaDevice,
+        remote_port: u16,
+        now: u64,
+    ) -> Result<PortForward, AppError> {
+        if remote_port == 0 {
+            return Err(AppError::InvalidArgument(
+                "remote port must be between 1 and 65535".to_string(),
+            ));
+        }
+
+        let socket = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .map_err(|error| AppError::Internal(format!("Failed to bind a local port: {error}")))?;
+        let local_addr = socket
+            .local_addr()
+            .map_err(|error| AppError::Internal(error.to_string()))?;
+
+        self.next_id += 1;
+        let info = PortForward {
+            id: format!("forward-{}", self.next_id),
+            device_id: device_id.to_string(),
+            remote_port,
+            local_port: local_addr.port(),
+            created_at: now,
+        };
+
+        let listener = Arc::new(Listener {
+            local_addr,
+            stopped: AtomicBool::new(false),
+            next_connection: AtomicU64::new(0),
+            connections: Mutex::new(HashMap::new()),
+        });
+        let device = Arc::new(DeviceRef::new(device));
+        let address = CString::new(format!("tcp:{remote_port}"))
+            .map_err(|error| AppError::Internal(error.to_string()))?;
+
+        let accept_listener = listener.clone();
+        let forward_id = info.id.clone();
+        thread::Builder::new()
+            .name(format!("carf-{forward_id}"))
+            .spawn(move || accept_loop(socket, accept_listener, device, address))
+            .map_err(|error| AppError::Internal(error.to_string()))?;
+
+        self.forwards.insert(
+            info.id.clone(),
+            ActiveForward {
+                info: info.clone(),
+                listener,
+            },
+        );
+        Ok(info)
+    }
+
+    /// Closes the listener and every connection it is serving.
+    pub(super) fn stop(&mut self, forward_id: &str) -> Result<(), AppError> {
+        let forward = self.forwards.remove(forward_id).ok_or_else(|| {
+            AppError::InvalidArgument(format!("Unknown port forward: {forward_id}"))
+        })?;
+        forward.listener.stop();
+        Ok(())
+    }
+
+    /// Active forwards, oldest first.
+    pub(super) fn list(&self) -> Vec<PortForward> {
+        let mut forwards = self
+            .forwards
+            .values()
+            .map(|forward| forward.info.clone())
+            .collect::<Vec<_>>();
+        forwards.sort_by_key(|forward| forward.created_at);
+        forwards
+    }
+}
+
+impl Drop for ForwardStore {
+    fn drop(&mut self) {
+        for forward in self.forwards.values() {
+            forward.listener.stop();
+        }
+    }
+}
+
+fn accept_loop(
+    socket: TcpListener,
+    listener: Arc<Listener>,
+    device: Arc<DeviceRef>,
+    address: CString,
+) {
+    for incoming in socket.incoming() {
+        if listener.stopped.load(Ordering::SeqCst) {
+            break;
+        }
+        let Ok(client) = incoming else {
+            continue;
+        };
+
+        let listener = listener.clone();
+        let device = device.clone();
+        let address = address.clone();
+        let spawned = thread::Builder::new()
+            .name("carf-forward-conn".to_string())
+            .spawn(move || {
+                let Some(connection_id) = listener.track(&client) else {
+                    return;
+                };
+                if let Err(error) = serve(&device, &address, client) {
+                    log::warn!(
+                        "Port forward to {} failed: {error}",
+                        address.to_string_lossy()
+                    );
+                }
+                listener.untrack(connection_id);
+            });
+        if let Err(error) = spawned {
+            log::warn!("Failed to start port forward connection: {error}");
+        }
+    }
+}
+
+fn serve(device: &DeviceRef, address: &CString, client: TcpStream) -> Result<(), String> {
+    let channel = match device.open_channel(address) {
+        Ok(channel) => Arc::new(channel),
+        Err(error) => {
+            let _ = client.shutdown(Shutdown::Both);
+            return Err(error);
+        }
+    };
+
+    let mut upstream_socket = client.try_clone().map_err(|error| error.to_string())?;
+    let upstream_channel = channel.clone();
+    let upstream = thread::spawn(move || {
+        let result = upstream_channel.pump_from_socket(&mut upstream_socket);
+        if result.is_err() {
+            upstream_channel.cancel();
+        }
+        result
+    });
+
+    let mut downstream_socket = client;
+    let downstream = channel.pump_to_socket(&mut downstream_socket);
+    channel.cancel();
+    let _ = downstream_socket.shutdown(Shutdown::Both);
+
+    let upstream = upstream
+        .join()
+        .unwrap_or_else(|_| Err("upstream pump panicked".to_string()));
+
+    // Cancellation is how each side stops the other; only report the first
+    // real failure.
+    [downstream, upstream]
+        .into_iter()
+        .filter_map(Result::err)
+        .find(|error| !is_cancellation(error))
+        .map_or(Ok(()), Err)
+}
+
+fn is_cancellation(message: &str) -> bool {
+    message.contains("cancelled") || message.contains("canceled")
+}
@@ -0,0 +1,95 @@
+use std::ffi::{CStr, CString};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+
+/// Icons at least this wide are preferred; the smallest such PNG is picked
+/// to keep list payloads small.
+const PREFERRED_ICON_WIDTH: i64 = 32;
+
+struct PngIcon {
+    width: i64,
+    bytes: Vec<u8>,
+}
+
+/// Picks a PNG from the `icons` entry of a process or application
+/// `parameters` table (only present for `FRIDA_SCOPE_FULL` queries) and
+/// returns it as a `data:` URL.
+pub(super) unsafe fn icon_from_parameters(
+    parameters: *mut frida_sys::GHashTable,
+) -> Option<String> {
+    if parameters.is_null() {
+        return None;
+    }
+
+    let key = CString::new("icons").ok()?;
+    let icons =
+        frida_sys::g_hash_table_lookup(parameters, key.as_ptr().cast()) as *mut frida_sys::GVariant;
+    if icons.is_null() {
+        return None;
+    }
+
+    let mut best: Option<PngIcon> = None;
+    for index in 0..frida_sys::g_variant_n_children(icons) {
+        let entry = frida_sys::g_variant_get_child_value(icons, index);
+        if let Some(icon) = read_png_icon(entry) {
+            let better = match &best {
+                None => true,
+                Some(current) if current.width < PREFERRED_ICON_WIDTH => icon.width > current.width,
+                Some(current) => icon.width >= PREFERRED_ICON_WIDTH && icon.width < current.width,
+            };
+            if better {
+                best = Some(icon);
+            }
+        }
+        frida_sys::g_variant_unref(entry);
+    }
+
+    best.map(|icon| format!("data:image/png;base64,{}", STANDARD.encode(icon.bytes)))
+}
+
+/// Reads one `a{sv}` icon entry, skipping non-PNG (raw RGBA) images.
+unsafe fn read_png_icon(entry: *mut frida_sys::GVariant) -> Option<PngIcon> {
+    let format = lookup(entry, "format")?;
+    let name = frida_sys::g_variant_get_string(format, std::ptr::null_mut());
+    let is_png = CStr::from_ptr(name).to_bytes() == b"png";
+    frida_sys::g_variant_unref(format);
+    if !is_png {
+        return None;
+    }
+
+    let width = lookup(entry, "width").map_or(0, |width| {
+        let value = frida_sys::g_variant_get_int64(width);
+        frida_sys::g_variant_unref(width);
+        value
+    });
+
+    let image = lookup(entry, "image")?;
+    let mut len = 0;
+    let data = frida_sys::g_variant_get_fixed_array(image, &mut len, 1) as *const u8;
+    let bytes = if data.is_null() {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(data, len).to_vec()
+    };
+    frida_sys::g_variant_unref(image);
+
+    if bytes.is_empty() {
+        None
+    } else {
+        Some(PngIcon { width, bytes })
+    }
+}
+
+unsafe fn lookup(
+    dictionary: *mut frida_sys::GVariant,
+    key: &str,
+) -> Option<*mut frida_sys::GVariant> {
+    let key = CString::new(key).ok()?;
+    let value = frida_sys::g_variant_lookup_value(dictionary, key.as_ptr(), std::ptr::null());
+    if value.is_null() {
+        None
+    } else {
+        Some(value)
+    }
+}
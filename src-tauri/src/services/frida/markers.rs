@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Markers kept per session; the oldest are dropped past this.
+const MAX_MARKERS_PER_SESSION: usize = 1_000;
+
+/// A user-placed point in a session's event log ("died here", "opened
+/// shop") that timelines, trace exports and reports align on.
+///
+/// Mirrors frontend `SessionMarker`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMarker {
+    pub id: String,
+    pub session_id: String,
+    pub label: String,
+    pub timestamp: u64,
+    /// Stalker trace entries recorded before the marker, counting dropped
+    /// ones; subtract an export's `dropped` to index into its `trace`.
+    pub trace_position: Option<u64>,
+}
+
+#[derive(Default)]
+pub(super) struct MarkerStore {
+    sessions: HashMap<String, Vec<SessionMarker>>,
+    next_id: u64,
+}
+
+impl MarkerStore {
+    pub(super) fn add(
+        &mut self,
+        session_id: &str,
+        label: String,
+        trace_position: Option<u64>,
+        now: u64,
+    ) -> SessionMarker {
+        self.next_id += 1;
+        let marker = SessionMarker {
+            id: format!("marker-{}", self.next_id),
+            session_id: session_id.to_string(),
+            label,
+            timestamp: now,
+            trace_position,
+        };
+
+        let markers = self.sessions.entry(session_id.to_string()).or_default();
+        if markers.len() >= MAX_MARKERS_PER_SESSION {
+            markers.remove(0);
+        }
+        markers.push(marker.clone());
+        marker
+    }
+
+    /// The session's markers, oldest first.
+    pub(super) fn list(&self, session_id: &str) -> &[SessionMarker] {
+        self.sessions
+            .get(session_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub(super) fn retain_sessions(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.sessions.retain(|session_id, _| keep(session_id));
+    }
+}
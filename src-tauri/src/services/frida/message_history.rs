@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::services::storage::now_millis;
+
+/// Messages kept across all sessions; the oldest are dropped past this.
+const MAX_MESSAGES: usize = 10_000;
+const DEFAULT_QUERY_LIMIT: usize = 200;
+const MAX_QUERY_LIMIT: usize = 1_000;
+
+/// One event a script emitted toward the frontend.
+///
+/// Mirrors frontend `HistoryMessage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryMessage {
+    pub cursor: u64,
+    pub timestamp: u64,
+    pub session_id: Option<String>,
+    /// Event name, e.g. `carf://console/message`.
+    pub event: String,
+    pub payload: Value,
+}
+
+/// Mirrors frontend `MessageFilter`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MessageFilter {
+    pub session_id: Option<String>,
+    /// Event names to keep; a trailing `*` matches by prefix
+    /// (`carf://hook/*`).
+    pub events: Option<Vec<String>>,
+    /// Case-insensitive substring searched in the payload's JSON.
+    pub text: Option<String>,
+}
+
+/// Mirrors frontend `MessagePage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessagePage {
+    pub messages: Vec<HistoryMessage>,
+    /// Pass back as `since` to continue after this page.
+    pub next_cursor: u64,
+    /// Messages after `since` were dropped to stay within the buffer.
+    pub truncated: bool,
+    /// More matches follow `next_cursor`.
+    pub has_more: bool,
+}
+
+/// Ring buffer of recent script events, so a frontend that reconnects or
+/// opens a view late can search and page through what it missed.
+#[derive(Default)]
+pub struct MessageHistoryStore {
+    messages: VecDeque<HistoryMessage>,
+    next_cursor: u64,
+    /// Highest cursor dropped from the buffer.
+    dropped_through: u64,
+}
+
+impl MessageHistoryStore {
+    pub fn record(&mut self, event: &str, payload: &Value) {
+        self.next_cursor += 1;
+        if self.messages.len() >= MAX_MESSAGES {
+            if let Some(dropped) = self.messages.pop_front() {
+                self.dropped_through = dropped.cursor;
+            }
+        }
+        self.messages.push_back(HistoryMessage {
+            cursor: self.next_cursor,
+            timestamp: now_millis(),
+            session_id: payload
+                .get("sessionId")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            event: event.to_string(),
+            payload: payload.clone(),
+        });
+    }
+
+    /// Matches recorded after `since`, oldest first.
+    pub fn query(&self, filter: &MessageFilter, since: u64, limit: Option<usize>) -> MessagePage {
+        let limit = limit
+            .unwrap_or(DEFAULT_QUERY_LIMIT)
+            .clamp(1, MAX_QUERY_LIMIT);
+        let text = filter
+            .text
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(str::to_lowercase);
+
+        let mut matches = self
+            .messages
+            .iter()
+            .filter(|message| message.cursor > since)
+            .filter(|message| matches_filter(message, filter, text.as_deref()));
+        let messages: Vec<HistoryMessage> = matches.by_ref().take(limit).cloned().collect();
+        let has_more = matches.next().is_some();
+
+        // Without more matches, skip past everything scanned so polling with
+        // the same filter does not rescan it.
+        let next_cursor = match messages.last() {
+            Some(last) if has_more => last.cursor,
+            _ => self.next_cursor.max(since),
+        };
+        MessagePage {
+            messages,
+            next_cursor,
+            truncated: since < self.dropped_through,
+            has_more,
+        }
+    }
+}
+
+fn matches_filter(message: &HistoryMessage, filter: &MessageFilter, text: Option<&str>) -> bool {
+    if let Some(session_id) = &filter.session_id {
+        if message.session_id.as_deref() != Some(session_id.as_str()) {
+            return false;
+        }
+    }
+    if let Some(events) = filter.events.as_ref().filter(|events| !events.is_empty()) {
+        let wanted = events
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => message.event.starts_with(prefix),
+                None => message.event == *pattern,
+            });
+        if !wanted {
+            return false;
+        }
+    }
+    match text {
+        Some(text) => message.payload.to_string().to_lowercase().contains(text),
+        None => true,
+    }
+}
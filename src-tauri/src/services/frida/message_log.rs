@@ -0,0 +1,202 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::storage::{self, now_millis};
+
+const LOG_DIR: &str = "message-logs";
+const LOG_FILE: &str = "messages.ndjson";
+const DEFAULT_MAX_FILE_MB: u64 = 64;
+const DEFAULT_MAX_FILES: usize = 5;
+
+/// Mirrors frontend `MessageLogOptions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MessageLogOptions {
+    /// The current file is rotated once it grows past this. Defaults to 64.
+    pub max_file_mb: Option<u64>,
+    /// Rotated files kept besides the current one. Defaults to 5.
+    pub max_files: Option<usize>,
+}
+
+/// Mirrors frontend `MessageLogStatus`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageLogStatus {
+    pub recording: bool,
+    /// Where logs are written, whether or not recording is on.
+    pub directory: String,
+    /// File currently being appended to.
+    pub path: Option<String>,
+    pub started_at: Option<u64>,
+    /// Messages written since recording started.
+    pub messages: u64,
+    /// Bytes in the current file.
+    pub bytes: u64,
+}
+
+struct ActiveLog {
+    directory: PathBuf,
+    writer: BufWriter<File>,
+    bytes: u64,
+    messages: u64,
+    max_file_bytes: u64,
+    max_files: usize,
+    started_at: u64,
+}
+
+impl ActiveLog {
+    fn path(&self) -> PathBuf {
+        self.directory.join(LOG_FILE)
+    }
+
+    /// Shifts `messages.N.ndjson` up by one, dropping the oldest, and starts
+    /// a fresh current file.
+    fn rotate(&mut self) -> std::io::Result<()> {
+        self.writer.flush()?;
+        let rotated = |index: usize| self.directory.join(format!("messages.{index}.ndjson"));
+
+        let _ = fs::remove_file(rotated(self.max_files));
+        for index in (1..self.max_files).rev() {
+            let from = rotated(index);
+            if from.exists() {
+                fs::rename(&from, rotated(index + 1))?;
+            }
+        }
+        if self.max_files > 0 {
+            fs::rename(self.path(), rotated(1))?;
+        }
+
+        self.writer = BufWriter::new(File::create(self.path())?);
+        self.bytes = 0;
+        Ok(())
+    }
+}
+
+/// Opt-in recorder appending every script message to a rotating NDJSON file
+/// under the data dir. Shared with each script's message handler, which
+/// records from Frida's thread; the actor flushes it on every pump.
+#[derive(Clone, Default)]
+pub(super) struct MessageRecorder {
+    active: Arc<Mutex<Option<ActiveLog>>>,
+}
+
+impl MessageRecorder {
+    pub(super) fn start(&self, options: &MessageLogOptions) -> Result<MessageLogStatus, AppError> {
+        let directory = log_directory()?;
+        let path = directory.join(LOG_FILE);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|error| {
+                AppError::StorageError(format!("failed to open {}: {error}", path.display()))
+            })?;
+        let bytes = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+
+        let mut active = self.lock();
+        if let Some(previous) = active.as_mut() {
+            let _ = previous.writer.flush();
+        }
+        *active = Some(ActiveLog {
+            directory,
+            writer: BufWriter::new(file),
+            bytes,
+            messages: 0,
+            max_file_bytes: options.max_file_mb.unwrap_or(DEFAULT_MAX_FILE_MB).max(1) * 1024 * 1024,
+            max_files: options.max_files.unwrap_or(DEFAULT_MAX_FILES),
+            started_at: now_millis(),
+        });
+        drop(active);
+        self.status()
+    }
+
+    pub(super) fn stop(&self) -> Result<MessageLogStatus, AppError> {
+        if let Some(mut log) = self.lock().take() {
+            log.writer.flush().map_err(|error| {
+                AppError::StorageError(format!("failed to flush message log: {error}"))
+            })?;
+        }
+        self.status()
+    }
+
+    pub(super) fn status(&self) -> Result<MessageLogStatus, AppError> {
+        let active = self.lock();
+        let directory = match active.as_ref() {
+            Some(log) => log.directory.clone(),
+            None => log_directory()?,
+        };
+        Ok(MessageLogStatus {
+            recording: active.is_some(),
+            directory: directory.to_string_lossy().into_owned(),
+            path: active
+                .as_ref()
+                .map(|log| log.path().to_string_lossy().into_owned()),
+            started_at: active.as_ref().map(|log| log.started_at),
+            messages: active.as_ref().map_or(0, |log| log.messages),
+            bytes: active.as_ref().map_or(0, |log| log.bytes),
+        })
+    }
+
+    /// Appends one line; `message` is only built while recording. Write
+    /// failures stop the recording rather than failing message delivery.
+    pub(super) fn record(&self, session_id: &str, script: &str, message: impl FnOnce() -> Value) {
+        let mut active = self.lock();
+        let Some(log) = active.as_mut() else {
+            return;
+        };
+
+        let line = json!({
+            "timestamp": now_millis(),
+            "sessionId": session_id,
+            "script": script,
+            "message": message(),
+        })
+        .to_string();
+
+        let written = (|| {
+            if log.bytes > 0 && log.bytes + line.len() as u64 + 1 > log.max_file_bytes {
+                log.rotate()?;
+            }
+            log.writer.write_all(line.as_bytes())?;
+            log.writer.write_all(b"\n")
+        })();
+
+        match written {
+            Ok(()) => {
+                log.bytes += line.len() as u64 + 1;
+                log.messages += 1;
+            }
+            Err(error) => {
+                log::warn!("Stopping message log after a write failure: {error}");
+                *active = None;
+            }
+        }
+    }
+
+    pub(super) fn flush(&self) {
+        if let Some(log) = self.lock().as_mut() {
+            let _ = log.writer.flush();
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<ActiveLog>> {
+        // A panic mid-write leaves at worst a truncated line; keep recording.
+        self.active
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+fn log_directory() -> Result<PathBuf, AppError> {
+    let directory = storage::data_file(LOG_DIR)?;
+    fs::create_dir_all(&directory).map_err(|error| {
+        AppError::StorageError(format!("failed to create {}: {error}", directory.display()))
+    })?;
+    Ok(directory)
+}
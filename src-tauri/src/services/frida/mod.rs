@@ -1,12 +1,57 @@
+mod binary_inbox;
+mod crash;
+mod device_health;
+mod event_batch;
+mod forwarding;
+mod icons;
+mod markers;
+mod message_history;
+mod message_log;
+mod operations;
 mod owned;
+mod process_output;
+mod process_watch;
+mod repl;
 mod runtime;
 mod script;
+mod spawn_gating;
+mod tamper;
 mod types;
 mod util;
+mod version;
 
+#[allow(unused_imports)]
+pub use crash::CrashReport;
+#[allow(unused_imports)]
+pub use device_health::{DeviceReconnected, DeviceUnhealthy};
+#[allow(unused_imports)]
+pub use event_batch::EventBatcher;
+#[allow(unused_imports)]
+pub use forwarding::PortForward;
+#[allow(unused_imports)]
+pub use markers::SessionMarker;
+#[allow(unused_imports)]
+pub use message_history::{HistoryMessage, MessageFilter, MessageHistoryStore, MessagePage};
+#[allow(unused_imports)]
+pub use message_log::{MessageLogOptions, MessageLogStatus};
+pub use operations::OperationRegistry;
+#[allow(unused_imports)]
+pub use process_output::{ProcessOutputChunk, ProcessOutputPage};
+#[allow(unused_imports)]
+pub use process_watch::ProcessDiff;
+#[allow(unused_imports)]
+pub use repl::EvalResult;
 pub use runtime::FridaService;
 #[allow(unused_imports)]
+pub use spawn_gating::{PendingSpawn, SpawnGatingOptions};
+#[allow(unused_imports)]
+pub use tamper::{SessionDiagnostic, TamperEvidence};
+#[allow(unused_imports)]
 pub use types::{
-    AppInfo, AttachOptions, CollectionPage, DeviceInfo, DeviceStatus, DeviceType, OsInfo,
-    OsPlatform, ProcessInfo, SpawnOptions,
+    AppInfo, AttachOptions, CleanupStep, CollectionPage, DeviceInfo, DevicePairing, DeviceStatus,
+    DeviceSystemInfo, DeviceType, OsInfo, OsPlatform, PairingState, ProcessInfo,
+    ProcessListOptions, ProcessSortKey, RemoteDeviceOptions, SessionCleanupReport, SpawnOptions,
+    TimeoutPolicy,
 };
+#[allow(unused_imports)]
+pub use version::FridaVersionMismatch;
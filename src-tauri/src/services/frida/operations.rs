@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::error::AppError;
+
+/// Blocking Frida calls that can be aborted from another thread while the
+/// actor is stuck inside them. Lives outside `FridaService` so cancelling
+/// does not need the service lock the blocked command is holding.
+#[derive(Clone, Default)]
+pub struct OperationRegistry {
+    cancellables: Arc<Mutex<HashMap<String, CancellablePtr>>>,
+}
+
+/// GCancellable is thread-safe; only the pointer needs to cross threads.
+struct CancellablePtr(*mut frida_sys::GCancellable);
+
+unsafe impl Send for CancellablePtr {}
+
+impl OperationRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `operation_id` for the lifetime of the returned guard.
+    pub fn begin(&self, operation_id: &str) -> Result<OperationGuard, AppError> {
+        let mut cancellables = self
+            .cancellables
+            .lock()
+            .map_err(|_| AppError::Internal("operations lock poisoned".to_string()))?;
+        if cancellables.contains_key(operation_id) {
+            return Err(AppError::Internal(format!(
+                "Operation {operation_id} is already running"
+            )));
+        }
+
+        let cancellable = unsafe { frida_sys::g_cancellable_new() };
+        cancellables.insert(operation_id.to_string(), CancellablePtr(cancellable));
+        Ok(OperationGuard {
+            registry: self.clone(),
+            operation_id: operation_id.to_string(),
+            cancellable,
+        })
+    }
+
+    /// Cancels a running operation. Returns `false` when no operation with
+    /// that id is in flight.
+    pub fn cancel(&self, operation_id: &str) -> bool {
+        let Ok(cancellables) = self.cancellables.lock() else {
+            return false;
+        };
+        match cancellables.get(operation_id) {
+            Some(cancellable) => {
+                unsafe { frida_sys::g_cancellable_cancel(cancellable.0) };
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+pub struct OperationGuard {
+    registry: OperationRegistry,
+    operation_id: String,
+    cancellable: *mut frida_sys::GCancellable,
+}
+
+impl OperationGuard {
+    pub(super) fn as_ptr(&self) -> *mut frida_sys::GCancellable {
+        self.cancellable
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        unsafe { frida_sys::g_cancellable_is_cancelled(self.cancellable) != 0 }
+    }
+
+    pub(super) fn operation_id(&self) -> &str {
+        &self.operation_id
+    }
+}
+
+impl Drop for OperationGuard {
+    fn drop(&mut self) {
+        // Unregister before releasing so `cancel` never sees a freed pointer.
+        if let Ok(mut cancellables) = self.registry.cancellables.lock() {
+            cancellables.remove(&self.operation_id);
+        }
+        unsafe { frida_sys::frida_unref(self.cancellable.cast()) };
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+use std::ffi::CString;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
@@ -9,6 +11,11 @@ use frida::{Device as FridaDevice, DeviceManager, Frida, Session};
 
 use crate::error::AppError;
 
+use super::runtime::frida_device_manager_ptr;
+use super::types::{ProcessInfo, RemoteDeviceOptions};
+use super::util::{take_gerror, take_gerror_message};
+use super::version;
+
 pub(super) struct OwnedSession {
     ptr: *mut Session<'static>,
 }
@@ -21,6 +28,21 @@ pub(super) struct OwnedDevice {
     ptr: *mut FridaDevice<'static>,
 }
 
+/// A device reference that may be used off the actor thread. Frida's sync
+/// API marshals onto its own main context, so blocking calls made through
+/// it from worker threads are safe and leave the actor free.
+pub(super) struct DeviceRef(*mut frida_sys::FridaDevice);
+
+unsafe impl Send for DeviceRef {}
+unsafe impl Sync for DeviceRef {}
+
+/// The device manager counterpart of `DeviceRef`, for reconnecting remote
+/// devices off the actor thread.
+pub(super) struct ManagerRef(*mut frida_sys::FridaDeviceManager);
+
+unsafe impl Send for ManagerRef {}
+unsafe impl Sync for ManagerRef {}
+
 pub(super) struct MainContextPump {
     stop: Arc<AtomicBool>,
     worker: Option<JoinHandle<()>>,
@@ -52,13 +74,16 @@ impl OwnedDeviceManager {
     pub(super) fn new(
         frida: &'static Frida,
         remote_addresses: &[String],
+        remote_options: &HashMap<String, RemoteDeviceOptions>,
     ) -> Result<Self, AppError> {
         let manager = DeviceManager::obtain(frida);
 
         for address in remote_addresses {
-            manager.get_remote_device(address).map_err(|error| {
-                AppError::ConnectionFailed("frida".to_string(), error.to_string())
-            })?;
+            add_remote_device(
+                frida_device_manager_ptr(&manager),
+                address,
+                remote_options.get(address),
+            )?;
         }
 
         Ok(Self {
@@ -72,6 +97,64 @@ impl OwnedDeviceManager {
     }
 }
 
+/// Connects to the frida-server at `address`, with the token and TLS
+/// certificate it was started with when given; the vendored binding only
+/// connects anonymously.
+fn add_remote_device(
+    manager: *mut frida_sys::FridaDeviceManager,
+    address: &str,
+    options: Option<&RemoteDeviceOptions>,
+) -> Result<DeviceRef, AppError> {
+    let failed = |message: String| AppError::ConnectionFailed(address.to_string(), message);
+    let to_cstring = |value: &str| CString::new(value).map_err(|error| failed(error.to_string()));
+    let host = to_cstring(address)?;
+    let token = options
+        .and_then(|options| options.token.as_deref())
+        .map(to_cstring)
+        .transpose()?;
+    let certificate = options
+        .and_then(|options| options.certificate.as_deref())
+        .map(to_cstring)
+        .transpose()?;
+
+    let mut error = std::ptr::null_mut();
+    unsafe {
+        let raw_options = frida_sys::frida_remote_device_options_new();
+        if let Some(path) = &certificate {
+            let certificate = frida_sys::g_tls_certificate_new_from_file(path.as_ptr(), &mut error);
+            if !error.is_null() {
+                frida_sys::frida_unref(raw_options.cast());
+                return Err(failed(format!(
+                    "failed to load certificate {}: {}",
+                    path.to_string_lossy(),
+                    take_gerror_message(error)
+                )));
+            }
+            frida_sys::frida_remote_device_options_set_certificate(raw_options, certificate);
+            frida_sys::frida_unref(certificate.cast());
+        }
+        if let Some(token) = &token {
+            frida_sys::frida_remote_device_options_set_token(raw_options, token.as_ptr());
+        }
+
+        let device = frida_sys::frida_device_manager_add_remote_device_sync(
+            manager,
+            host.as_ptr(),
+            raw_options,
+            std::ptr::null_mut(),
+            &mut error,
+        );
+        frida_sys::frida_unref(raw_options.cast());
+        if !error.is_null() {
+            let error = take_gerror(error);
+            let failure = version::mismatch_error(&error.message)
+                .unwrap_or_else(|| failed(error.message.clone()));
+            return Err(failure.caused_by(error));
+        }
+        Ok(DeviceRef(device))
+    }
+}
+
 impl Drop for OwnedDeviceManager {
     fn drop(&mut self) {
         unsafe {
@@ -106,6 +189,117 @@ impl Drop for OwnedDevice {
     }
 }
 
+impl DeviceRef {
+    pub(super) unsafe fn new(device: *mut frida_sys::FridaDevice) -> Self {
+        Self(frida_sys::g_object_ref(device.cast()).cast())
+    }
+
+    pub(super) fn as_ptr(&self) -> *mut frida_sys::FridaDevice {
+        self.0
+    }
+
+    /// The device's id, when it is not lost and answers a system parameters
+    /// query.
+    pub(super) fn probe(&self) -> Result<String, String> {
+        unsafe {
+            if frida_sys::frida_device_is_lost(self.0) != frida_sys::FALSE as i32 {
+                return Err("connection to the device was lost".to_string());
+            }
+            let mut error = std::ptr::null_mut();
+            let parameters = frida_sys::frida_device_query_system_parameters_sync(
+                self.0,
+                std::ptr::null_mut(),
+                &mut error,
+            );
+            if !error.is_null() {
+                return Err(format!(
+                    "device did not answer: {}",
+                    take_gerror_message(error)
+                ));
+            }
+            frida_sys::g_hash_table_unref(parameters);
+            let id = std::ffi::CStr::from_ptr(frida_sys::frida_device_get_id(self.0));
+            Ok(id.to_string_lossy().into_owned())
+        }
+    }
+
+    /// Minimal process list (pid and name), as `list_processes` returns it.
+    pub(super) fn enumerate_processes(&self) -> Result<Vec<ProcessInfo>, String> {
+        let mut error = std::ptr::null_mut();
+        let list = unsafe {
+            frida_sys::frida_device_enumerate_processes_sync(
+                self.0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                &mut error,
+            )
+        };
+        if !error.is_null() || list.is_null() {
+            return Err(take_gerror_message(error));
+        }
+
+        let mut processes = Vec::new();
+        unsafe {
+            for index in 0..frida_sys::frida_process_list_size(list) {
+                let process = frida_sys::frida_process_list_get(list, index);
+                let name = std::ffi::CStr::from_ptr(frida_sys::frida_process_get_name(process));
+                processes.push(ProcessInfo {
+                    pid: frida_sys::frida_process_get_pid(process),
+                    name: name.to_string_lossy().into_owned(),
+                    identifier: None,
+                    icon: None,
+                });
+                frida_sys::frida_unref(process.cast());
+            }
+            frida_sys::frida_unref(list.cast());
+        }
+        Ok(processes)
+    }
+}
+
+impl Drop for DeviceRef {
+    fn drop(&mut self) {
+        unsafe { frida_sys::frida_unref(self.0.cast()) };
+    }
+}
+
+impl ManagerRef {
+    pub(super) fn new(manager: &DeviceManager<'static>) -> Self {
+        let ptr = frida_device_manager_ptr(manager);
+        Self(unsafe { frida_sys::g_object_ref(ptr.cast()).cast() })
+    }
+
+    /// Drops the manager's connection to `address` and connects again, for a
+    /// remote device whose link went stale.
+    pub(super) fn reconnect_remote_device(
+        &self,
+        address: &str,
+        options: Option<&RemoteDeviceOptions>,
+    ) -> Result<DeviceRef, AppError> {
+        let host = CString::new(address)
+            .map_err(|error| AppError::ConnectionFailed(address.to_string(), error.to_string()))?;
+        let mut error = std::ptr::null_mut();
+        unsafe {
+            frida_sys::frida_device_manager_remove_remote_device_sync(
+                self.0,
+                host.as_ptr(),
+                std::ptr::null_mut(),
+                &mut error,
+            );
+        }
+        // Not being connected any more is the usual reason to get here.
+        let _ = take_gerror(error);
+
+        add_remote_device(self.0, address, options)
+    }
+}
+
+impl Drop for ManagerRef {
+    fn drop(&mut self) {
+        unsafe { frida_sys::frida_unref(self.0.cast()) };
+    }
+}
+
 impl MainContextPump {
     pub(super) fn start() -> Self {
         let stop = Arc::new(AtomicBool::new(false));
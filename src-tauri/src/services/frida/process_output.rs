@@ -0,0 +1,196 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::os::raw::{c_int, c_uint, c_void};
+use std::sync::mpsc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::storage::now_millis;
+
+/// Bytes of output kept per process before the oldest chunks are dropped.
+const MAX_BYTES_PER_PROCESS: usize = 256 * 1024;
+/// Processes with buffered output; the least recently written one is
+/// evicted when a new process starts writing past this.
+const MAX_PROCESSES: usize = 32;
+
+/// One read from a spawned process's stdout or stderr.
+///
+/// Mirrors frontend `ProcessOutputChunk`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessOutputChunk {
+    pub cursor: u64,
+    pub pid: u32,
+    pub fd: i32,
+    pub data: String,
+    pub timestamp: u64,
+}
+
+/// Mirrors frontend `ProcessOutputPage`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessOutputPage {
+    pub pid: u32,
+    pub chunks: Vec<ProcessOutputChunk>,
+    /// Pass back as `since_cursor` to continue after the last chunk.
+    pub next_cursor: u64,
+    /// Output after `since_cursor` was dropped to stay within the buffer.
+    pub truncated: bool,
+}
+
+/// Raw output as delivered by the device's `output` signal.
+pub(super) struct RawOutput {
+    pid: u32,
+    fd: i32,
+    data: Vec<u8>,
+}
+
+#[derive(Default)]
+struct OutputBuffer {
+    chunks: VecDeque<ProcessOutputChunk>,
+    bytes: usize,
+    /// Highest cursor dropped from this buffer.
+    dropped_through: u64,
+    last_write: u64,
+}
+
+/// Per-process ring buffers of stdio captured from targets spawned with
+/// `stdio: "pipe"`, so output written while the UI was not listening can
+/// still be read back by cursor.
+#[derive(Default)]
+pub(super) struct ProcessOutputStore {
+    buffers: HashMap<u32, OutputBuffer>,
+    /// Devices whose `output` signal is already connected.
+    tapped_devices: HashSet<String>,
+    next_cursor: u64,
+}
+
+impl ProcessOutputStore {
+    pub(super) fn is_tapped(&self, device_id: &str) -> bool {
+        self.tapped_devices.contains(device_id)
+    }
+
+    pub(super) fn mark_tapped(&mut self, device_id: &str) {
+        self.tapped_devices.insert(device_id.to_string());
+    }
+
+    /// Forgets every tap, for when the device objects they were connected
+    /// to are replaced. Buffered output is kept.
+    pub(super) fn clear_taps(&mut self) {
+        self.tapped_devices.clear();
+    }
+
+    pub(super) fn record(&mut self, output: RawOutput) -> ProcessOutputChunk {
+        if !self.buffers.contains_key(&output.pid) && self.buffers.len() >= MAX_PROCESSES {
+            if let Some(stale_pid) = self
+                .buffers
+                .iter()
+                .min_by_key(|(_, buffer)| buffer.last_write)
+                .map(|(pid, _)| *pid)
+            {
+                self.buffers.remove(&stale_pid);
+            }
+        }
+
+        self.next_cursor += 1;
+        let chunk = ProcessOutputChunk {
+            cursor: self.next_cursor,
+            pid: output.pid,
+            fd: output.fd,
+            data: String::from_utf8_lossy(&output.data).into_owned(),
+            timestamp: now_millis(),
+        };
+
+        let buffer = self.buffers.entry(output.pid).or_default();
+        buffer.bytes += chunk.data.len();
+        buffer.last_write = chunk.cursor;
+        buffer.chunks.push_back(chunk.clone());
+        while buffer.bytes > MAX_BYTES_PER_PROCESS && buffer.chunks.len() > 1 {
+            if let Some(dropped) = buffer.chunks.pop_front() {
+                buffer.bytes -= dropped.data.len();
+                buffer.dropped_through = dropped.cursor;
+            }
+        }
+
+        chunk
+    }
+
+    /// Chunks written by `pid` after `since_cursor`, oldest first.
+    pub(super) fn read(&self, pid: u32, since_cursor: u64) -> ProcessOutputPage {
+        let Some(buffer) = self.buffers.get(&pid) else {
+            return ProcessOutputPage {
+                pid,
+                chunks: Vec::new(),
+                next_cursor: since_cursor,
+                truncated: false,
+            };
+        };
+
+        let chunks: Vec<ProcessOutputChunk> = buffer
+            .chunks
+            .iter()
+            .filter(|chunk| chunk.cursor > since_cursor)
+            .cloned()
+            .collect();
+        ProcessOutputPage {
+            pid,
+            next_cursor: chunks.last().map_or(since_cursor, |chunk| chunk.cursor),
+            truncated: since_cursor < buffer.dropped_through,
+            chunks,
+        }
+    }
+}
+
+/// Connects the device's `output` signal to `sender`. The signal fires on
+/// the main-context pump thread; the sender is freed by GLib when the device
+/// is finalized.
+pub(super) fn connect_output_signal(
+    device: *mut frida_sys::FridaDevice,
+    sender: mpsc::Sender<RawOutput>,
+) {
+    let user_data = Box::into_raw(Box::new(sender)) as *mut c_void;
+
+    unsafe {
+        let callback = Some(std::mem::transmute::<*mut c_void, unsafe extern "C" fn()>(
+            on_output as *mut c_void,
+        ));
+
+        frida_sys::g_signal_connect_data(
+            device as _,
+            c"output".as_ptr(),
+            callback,
+            user_data,
+            Some(free_output_sender),
+            0,
+        );
+    }
+}
+
+unsafe extern "C" fn on_output(
+    _device: *mut frida_sys::FridaDevice,
+    pid: c_uint,
+    fd: c_int,
+    data: *mut frida_sys::GBytes,
+    user_data: *mut c_void,
+) {
+    if data.is_null() || user_data.is_null() {
+        return;
+    }
+
+    let mut size = 0;
+    let bytes = frida_sys::g_bytes_get_data(data, &mut size) as *const u8;
+    let data = if bytes.is_null() || size == 0 {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(bytes, size).to_vec()
+    };
+
+    let sender = &*(user_data as *const mpsc::Sender<RawOutput>);
+    let _ = sender.send(RawOutput { pid, fd, data });
+}
+
+unsafe extern "C" fn free_output_sender(
+    user_data: *mut c_void,
+    _closure: *mut frida_sys::GClosure,
+) {
+    drop(Box::from_raw(user_data as *mut mpsc::Sender<RawOutput>));
+}
@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::types::ProcessInfo;
+
+const MIN_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Added and removed processes since the previous refresh of a watched
+/// device, emitted as `carf://process/changed`.
+///
+/// Mirrors frontend `ProcessChangedEvent`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessDiff {
+    pub device_id: String,
+    pub added: Vec<ProcessInfo>,
+    pub removed: Vec<u32>,
+    pub total: usize,
+}
+
+struct ProcessWatch {
+    interval: Duration,
+    next_refresh: Instant,
+    /// An enumeration is running on a worker thread.
+    refreshing: bool,
+    processes: BTreeMap<u32, ProcessInfo>,
+}
+
+/// Per-device process lists kept current by periodic refreshes on the actor,
+/// so the frontend can follow changes without re-fetching the full list.
+#[derive(Default)]
+pub(super) struct ProcessWatchStore {
+    watches: HashMap<String, ProcessWatch>,
+}
+
+impl ProcessWatchStore {
+    /// Starts (or re-times) a watch seeded with `processes`.
+    pub(super) fn start(
+        &mut self,
+        device_id: &str,
+        interval: Duration,
+        processes: Vec<ProcessInfo>,
+    ) {
+        let interval = interval.max(MIN_INTERVAL);
+        self.watches.insert(
+            device_id.to_string(),
+            ProcessWatch {
+                interval,
+                next_refresh: Instant::now() + interval,
+                refreshing: false,
+                processes: processes
+                    .into_iter()
+                    .map(|process| (process.pid, process))
+                    .collect(),
+            },
+        );
+    }
+
+    pub(super) fn stop(&mut self, device_id: &str) -> bool {
+        self.watches.remove(device_id).is_some()
+    }
+
+    pub(super) fn cached(&self, device_id: &str) -> Option<Vec<ProcessInfo>> {
+        self.watches
+            .get(device_id)
+            .map(|watch| watch.processes.values().cloned().collect())
+    }
+
+    /// Devices whose refresh interval has elapsed and that are not already
+    /// being refreshed.
+    pub(super) fn due(&self, now: Instant) -> Vec<String> {
+        self.watches
+            .iter()
+            .filter(|(_, watch)| !watch.refreshing && watch.next_refresh <= now)
+            .map(|(device_id, _)| device_id.clone())
+            .collect()
+    }
+
+    /// Marks a refresh as started; `apply` or `defer` ends it.
+    pub(super) fn begin(&mut self, device_id: &str) {
+        if let Some(watch) = self.watches.get_mut(device_id) {
+            watch.refreshing = true;
+        }
+    }
+
+    /// Replaces the cached list and returns what changed, if anything.
+    pub(super) fn apply(
+        &mut self,
+        device_id: &str,
+        processes: Vec<ProcessInfo>,
+    ) -> Option<ProcessDiff> {
+        let watch = self.watches.get_mut(device_id)?;
+        watch.next_refresh = Instant::now() + watch.interval;
+        watch.refreshing = false;
+
+        let next: BTreeMap<u32, ProcessInfo> = processes
+            .into_iter()
+            .map(|process| (process.pid, process))
+            .collect();
+        // A reused pid under a different name counts as removed and added.
+        let added: Vec<ProcessInfo> = next
+            .values()
+            .filter(|process| {
+                watch
+                    .processes
+                    .get(&process.pid)
+                    .map_or(true, |previous| previous.name != process.name)
+            })
+            .cloned()
+            .collect();
+        let removed: Vec<u32> = watch
+            .processes
+            .values()
+            .filter(|previous| {
+                next.get(&previous.pid)
+                    .map_or(true, |process| process.name != previous.name)
+            })
+            .map(|previous| previous.pid)
+            .collect();
+
+        watch.processes = next;
+        if added.is_empty() && removed.is_empty() {
+            return None;
+        }
+        Some(ProcessDiff {
+            device_id: device_id.to_string(),
+            added,
+            removed,
+            total: watch.processes.len(),
+        })
+    }
+
+    /// Pushes the next refresh back after a failed enumeration.
+    pub(super) fn defer(&mut self, device_id: &str) {
+        if let Some(watch) = self.watches.get_mut(device_id) {
+            watch.next_refresh = Instant::now() + watch.interval;
+            watch.refreshing = false;
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+/// Loaded into a session the first time it evaluates something and kept
+/// until it detaches, so `var`s and functions defined by one snippet are
+/// there for the next. The last value is kept in `$_`.
+pub(super) const REPL_SOURCE: &str = r#"
+"use strict";
+
+const MAX_DEPTH = 4;
+const MAX_ITEMS = 100;
+const MAX_BYTES = 256;
+
+function typeName(value) {
+  if (value === null) return "null";
+  if (value instanceof NativePointer) return "NativePointer";
+  if (value instanceof Int64) return "Int64";
+  if (value instanceof UInt64) return "UInt64";
+  if (value instanceof ArrayBuffer) return "ArrayBuffer";
+  if (Array.isArray(value)) return "Array";
+  if (value instanceof Error) return value.name;
+  if (typeof value === "object" && value.constructor && value.constructor.name) {
+    return value.constructor.name;
+  }
+  return typeof value;
+}
+
+function format(value, depth, seen, indent) {
+  switch (typeof value) {
+    case "undefined": return "undefined";
+    case "string": return depth === 0 ? value : JSON.stringify(value);
+    case "bigint": return value.toString() + "n";
+    case "symbol": return value.toString();
+    case "function": return "[Function " + (value.name || "anonymous") + "]";
+    case "number":
+    case "boolean": return String(value);
+  }
+  if (value === null) return "null";
+  if (value instanceof NativePointer || value instanceof Int64 || value instanceof UInt64) {
+    return value.toString();
+  }
+  if (value instanceof Error) return value.stack || value.name + ": " + value.message;
+  if (value instanceof ArrayBuffer) {
+    const dump = hexdump(value, { length: Math.min(value.byteLength, MAX_BYTES), header: false });
+    const more = value.byteLength > MAX_BYTES ? "\n... " + value.byteLength + " bytes" : "";
+    return dump + more;
+  }
+  if (seen.has(value)) return "[Circular]";
+  if (depth >= MAX_DEPTH) return Array.isArray(value) ? "[Array]" : "[Object]";
+
+  seen.add(value);
+  const pad = indent + "  ";
+  const isArray = Array.isArray(value);
+  const keys = isArray ? [] : Object.keys(value);
+  const count = isArray ? value.length : keys.length;
+  const lines = [];
+  for (let i = 0; i < Math.min(count, MAX_ITEMS); i++) {
+    const item = isArray ? value[i] : value[keys[i]];
+    const text = format(item, depth + 1, seen, pad);
+    lines.push(pad + (isArray ? text : keys[i] + ": " + text));
+  }
+  if (count > MAX_ITEMS) lines.push(pad + "... " + (count - MAX_ITEMS) + " more");
+  seen.delete(value);
+
+  const [open, close] = isArray ? ["[", "]"] : ["{", "}"];
+  return lines.length === 0 ? open + close : open + "\n" + lines.join(",\n") + "\n" + indent + close;
+}
+
+function settle(value, started) {
+  globalThis.$_ = value;
+  return {
+    ok: true,
+    value: format(value, 0, new Set(), ""),
+    typeName: typeName(value),
+    durationMs: Date.now() - started,
+  };
+}
+
+function fail(error, started) {
+  const isError = error instanceof Error;
+  return {
+    ok: false,
+    value: isError ? error.name + ": " + error.message : format(error, 0, new Set(), ""),
+    typeName: typeName(error),
+    stack: isError && error.stack ? error.stack : null,
+    durationMs: Date.now() - started,
+  };
+}
+
+rpc.exports = {
+  eval(code) {
+    const started = Date.now();
+    try {
+      const value = (0, eval)(code);
+      if (value !== null && typeof value === "object" && typeof value.then === "function") {
+        return value.then((settled) => settle(settled, started), (error) => fail(error, started));
+      }
+      return settle(value, started);
+    } catch (error) {
+      return fail(error, started);
+    }
+  },
+};
+"#;
+
+/// Outcome of a REPL snippet. A snippet that throws is still a result, with
+/// `ok` unset and the error in `value` and `stack`.
+///
+/// Mirrors frontend `EvalResult`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EvalResult {
+    pub ok: bool,
+    /// The value pretty-printed: objects indented, pointers as hex, buffers
+    /// as a hexdump.
+    pub value: String,
+    /// `typeof`, or the class name for objects (`NativePointer`, `Array`, ...).
+    pub type_name: String,
+    #[serde(default)]
+    pub stack: Option<String>,
+    pub duration_ms: u64,
+}
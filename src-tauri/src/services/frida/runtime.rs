@@ -1,26 +1,72 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::ffi::{CStr, CString};
-use std::sync::mpsc::{self, RecvTimeoutError};
-use std::thread::{self, JoinHandle};
-use std::time::Duration;
+use std::sync::mpsc::{self, RecvTimeoutError, TryRecvError};
+use std::thread::{self, JoinHandle, ThreadId};
+use std::time::{Duration, Instant};
 
-use frida::{Frida, Script, ScriptOption, Session, SpawnOptions as FridaSpawnOptions};
+use frida::{
+    DeviceManager, Frida, Script, ScriptOption, Session, SpawnOptions as FridaSpawnOptions,
+    SpawnStdio,
+};
 use serde_json::{json, Value};
 
 use crate::error::AppError;
 use crate::services::session_manager::{SessionInfo, SessionMode, SessionStatus};
+use crate::services::session_restore::{self, SavedScript, SavedSession};
+use crate::services::stalker::{TraceStore, TraceSummary};
+use crate::services::storage::now_millis;
+use crate::services::variables;
 use crate::state::{BridgeEvent, EventHub};
 
-use super::owned::{MainContextPump, OwnedDevice, OwnedDeviceManager, OwnedSession};
+use super::binary_inbox::BinaryInbox;
+use super::crash::{connect_detached_signal, save_crash_report, CrashReport};
+use super::device_health::{DeviceHealthStore, HealthTarget, PROBE_TIMEOUT};
+use super::event_batch::EventBatcher;
+use super::forwarding::{ForwardStore, PortForward};
+use super::icons::icon_from_parameters;
+use super::markers::{MarkerStore, SessionMarker};
+use super::message_history::{MessageFilter, MessageHistoryStore, MessagePage};
+use super::message_log::{MessageLogOptions, MessageLogStatus, MessageRecorder};
+use super::operations::{OperationGuard, OperationRegistry};
+use super::owned::{
+    DeviceRef, MainContextPump, ManagerRef, OwnedDevice, OwnedDeviceManager, OwnedSession,
+};
+use super::process_output::{
+    connect_output_signal, ProcessOutputPage, ProcessOutputStore, RawOutput,
+};
+use super::process_watch::ProcessWatchStore;
+use super::repl::{EvalResult, REPL_SOURCE};
 use super::script::HostScriptHandler;
-use super::types::{AppInfo, AttachOptions, DeviceInfo, ProcessInfo, SpawnOptions};
+use super::spawn_gating::{
+    connect_spawn_added_signal, take_spawn_list, PendingSpawn, SpawnGatingOptions, SpawnGatingStore,
+};
+use super::tamper::{SessionDiagnostic, TamperMonitor};
+use super::types::{
+    AppInfo, AttachOptions, CleanupStep, DeviceInfo, DeviceSystemInfo, DeviceType, ProcessInfo,
+    RemoteDeviceOptions, SessionCleanupReport, SpawnOptions, TimeoutPolicy,
+};
 use super::util::{
-    get_device_arch, new_session_id, now_millis, parse_script_runtime, parse_spawn_stdio,
-    pause_process_for_device, project_root, resolve_attach_target, resume_process_for_device,
-    serialize_device, unwrap_rpc_result,
+    gerror_into, get_device_arch, new_session_id, parse_script_runtime, parse_spawn_stdio,
+    pause_process_for_device, project_root, query_device_system, resolve_attach_target,
+    resume_process_for_device, serialize_device, session_deadline, take_gerror, unwrap_rpc_result,
+    with_binary_id,
 };
+use super::version;
+
+/// Agent RPC methods that run as background jobs (see `FridaActor::start_job`)
+/// under `TimeoutPolicy::scan_ms` instead of holding the actor for `rpc_ms`.
+const JOB_RPC_METHODS: &[&str] = &[
+    "scanMemory",
+    "searchJavaHeap",
+    "dumpMemoryRange",
+    "compareMemory",
+    "pointerScan",
+    "snapshotPointerMap",
+];
 
 const FRIDA_ACTOR_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long before a time-boxed session expires the frontend gets warned.
+const SESSION_EXPIRY_WARNING_MS: u64 = 60_000;
 const COMPILED_AGENT_PATH: &str = "src-agent/dist/_agent.js";
 
 /// Agent JS bundle baked in at compile time. Using `include_str!` guarantees the
@@ -31,18 +77,37 @@ const EMBEDDED_AGENT_JS: &str = include_str!("../../../../src-agent/dist/_agent.
 
 type ActorTask = Box<dyn FnOnce(&mut FridaActor) + Send + 'static>;
 
+/// Which queue a task waits in. Interactive tasks (attach, RPC, post) always
+/// run before queued bulk tasks (enumerations, compiles, exports).
+#[derive(Clone, Copy)]
+enum Lane {
+    Interactive,
+    Bulk,
+}
+
+/// Owns the thread that owns every Frida object. Sessions, scripts and trace
+/// buffers live only on that thread and are touched only by tasks run there
+/// one at a time, so there are no locks inside the actor. Tasks run in
+/// submission order within their lane. A detach noticed by `pump` and an
+/// unload queued by a command therefore cannot interleave: whichever runs
+/// second sees the first's result (usually `SessionNotFound`).
+///
+/// Agent work that can take seconds (scans, dumps) never runs as an actor
+/// task at all: the actor only starts it as an agent job and the caller
+/// waits for the result off-thread.
 struct ActorHandle {
-    sender: Option<mpsc::Sender<ActorTask>>,
+    sender: Option<mpsc::Sender<(Lane, ActorTask)>>,
     worker: Option<JoinHandle<()>>,
+    worker_thread: ThreadId,
 }
 
 impl ActorHandle {
-    fn new(events: EventHub) -> Result<Self, AppError> {
-        let (sender, receiver) = mpsc::channel::<ActorTask>();
+    fn new(events: EventHub, operations: OperationRegistry) -> Result<Self, AppError> {
+        let (sender, receiver) = mpsc::channel::<(Lane, ActorTask)>();
         let (init_tx, init_rx) = mpsc::sync_channel::<Result<(), AppError>>(1);
 
         let worker = thread::spawn(move || {
-            let mut actor = match FridaActor::new(events) {
+            let mut actor = match FridaActor::new(events, operations) {
                 Ok(actor) => {
                     let _ = init_tx.send(Ok(()));
                     actor
@@ -53,11 +118,36 @@ impl ActorHandle {
                 }
             };
 
+            let mut interactive = VecDeque::new();
+            let mut bulk = VecDeque::new();
+            let mut disconnected = false;
             loop {
                 actor.pump();
 
-                match receiver.recv_timeout(FRIDA_ACTOR_POLL_INTERVAL) {
-                    Ok(task) => task(&mut actor),
+                // Take everything already queued so an interactive task never
+                // waits behind bulk work that happened to arrive first.
+                loop {
+                    match receiver.try_recv() {
+                        Ok((Lane::Interactive, task)) => interactive.push_back(task),
+                        Ok((Lane::Bulk, task)) => bulk.push_back(task),
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => {
+                            disconnected = true;
+                            break;
+                        }
+                    }
+                }
+                if let Some(task) = interactive.pop_front().or_else(|| bulk.pop_front()) {
+                    task(&mut actor);
+                    continue;
+                }
+                if disconnected {
+                    break;
+                }
+
+                match receiver.recv_timeout(actor.poll_interval()) {
+                    Ok((Lane::Interactive, task)) => interactive.push_back(task),
+                    Ok((Lane::Bulk, task)) => bulk.push_back(task),
                     Err(RecvTimeoutError::Timeout) => {}
                     Err(RecvTimeoutError::Disconnected) => break,
                 }
@@ -77,6 +167,7 @@ impl ActorHandle {
 
         Ok(Self {
             sender: Some(sender),
+            worker_thread: worker.thread().id(),
             worker: Some(worker),
         })
     }
@@ -86,6 +177,60 @@ impl ActorHandle {
         T: Send + 'static,
         F: FnOnce(&mut FridaActor) -> Result<T, AppError> + Send + 'static,
     {
+        self.send(Lane::Interactive, operation)?
+            .recv()
+            .map_err(|_| AppError::Internal("Frida actor stopped unexpectedly".to_string()))?
+    }
+
+    /// Like `request`, but yields to any interactive task queued meanwhile.
+    fn request_bulk<T, F>(&self, operation: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut FridaActor) -> Result<T, AppError> + Send + 'static,
+    {
+        self.send(Lane::Bulk, operation)?
+            .recv()
+            .map_err(|_| AppError::Internal("Frida actor stopped unexpectedly".to_string()))?
+    }
+
+    /// Like `request`, but stops waiting after `timeout`. The operation keeps
+    /// running on the actor thread; only the caller gives up.
+    fn request_with_timeout<T, F>(&self, timeout: Duration, operation: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut FridaActor) -> Result<T, AppError> + Send + 'static,
+    {
+        match self
+            .send(Lane::Interactive, operation)?
+            .recv_timeout(timeout)
+        {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(AppError::Timeout(format!(
+                "Frida actor did not respond within {}ms",
+                timeout.as_millis()
+            ))),
+            Err(RecvTimeoutError::Disconnected) => Err(AppError::Internal(
+                "Frida actor stopped unexpectedly".to_string(),
+            )),
+        }
+    }
+
+    fn send<T, F>(
+        &self,
+        lane: Lane,
+        operation: F,
+    ) -> Result<mpsc::Receiver<Result<T, AppError>>, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut FridaActor) -> Result<T, AppError> + Send + 'static,
+    {
+        // A task that waits on another task would wait on itself forever.
+        if thread::current().id() == self.worker_thread {
+            return Err(AppError::Internal(
+                "Frida actor request issued from the actor thread".to_string(),
+            ));
+        }
+
         let sender = self
             .sender
             .as_ref()
@@ -93,16 +238,17 @@ impl ActorHandle {
         let (result_tx, result_rx) = mpsc::sync_channel(1);
 
         sender
-            .send(Box::new(move |actor| {
-                let result = operation(actor);
-                actor.pump();
-                let _ = result_tx.send(result);
-            }))
+            .send((
+                lane,
+                Box::new(move |actor| {
+                    let result = operation(actor);
+                    actor.pump();
+                    let _ = result_tx.send(result);
+                }),
+            ))
             .map_err(|_| AppError::Internal("Failed to send Frida actor request".to_string()))?;
 
-        result_rx
-            .recv()
-            .map_err(|_| AppError::Internal("Frida actor stopped unexpectedly".to_string()))?
+        Ok(result_rx)
     }
 }
 
@@ -118,23 +264,103 @@ impl Drop for ActorHandle {
 
 pub struct FridaService {
     actor: ActorHandle,
+    operations: OperationRegistry,
+    timeouts: TimeoutPolicy,
+    default_runtime: Option<String>,
+}
+
+/// An agent job started by `FridaService::start_job`.
+pub struct JobHandle {
+    session_id: String,
+    method: String,
+    timeout_ms: u64,
+    result_rx: mpsc::Receiver<Result<Value, AppError>>,
+}
+
+impl JobHandle {
+    /// Blocks until the job's result arrives or `TimeoutPolicy::scan_ms`
+    /// passes.
+    pub fn wait(self) -> Result<Value, AppError> {
+        let Self {
+            session_id,
+            method,
+            timeout_ms,
+            result_rx,
+        } = self;
+        let ended = || {
+            AppError::SessionNotFound(format!(
+                "Session {session_id} ended before {method} finished"
+            ))
+        };
+        if timeout_ms == 0 {
+            return result_rx.recv().map_err(|_| ended())?;
+        }
+        match result_rx.recv_timeout(Duration::from_millis(timeout_ms)) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(AppError::Timeout(format!(
+                "RPC {method} did not finish within {timeout_ms}ms"
+            ))),
+            Err(RecvTimeoutError::Disconnected) => Err(ended()),
+        }
+    }
 }
 
 impl FridaService {
-    pub fn new(events: EventHub) -> Result<Self, AppError> {
+    pub fn new(events: EventHub, operations: OperationRegistry) -> Result<Self, AppError> {
         Ok(Self {
-            actor: ActorHandle::new(events)?,
+            actor: ActorHandle::new(events, operations.clone())?,
+            operations,
+            timeouts: TimeoutPolicy::default(),
+            default_runtime: None,
         })
     }
 
+    pub fn timeout_policy(&self) -> TimeoutPolicy {
+        self.timeouts.clone()
+    }
+
+    pub fn set_timeout_policy(&mut self, timeouts: TimeoutPolicy) {
+        self.timeouts = timeouts;
+    }
+
+    /// Script runtime used by attaches and spawns that do not name one.
+    pub fn set_default_runtime(&mut self, runtime: Option<String>) {
+        self.default_runtime = runtime;
+    }
+
+    /// Runs `operation` under a timeout of `timeout_ms`, or without one when
+    /// it is `0`. A timed-out operation keeps the actor busy until it
+    /// finishes, so requests queued behind it may time out as well.
+    fn request_within<T, F>(&self, timeout_ms: u64, what: &str, operation: F) -> Result<T, AppError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut FridaActor) -> Result<T, AppError> + Send + 'static,
+    {
+        if timeout_ms == 0 {
+            return self.actor.request(operation);
+        }
+        self.actor
+            .request_with_timeout(Duration::from_millis(timeout_ms), operation)
+            .map_err(|error| match error {
+                AppError::Timeout(_) => {
+                    AppError::Timeout(format!("{what} did not finish within {timeout_ms}ms"))
+                }
+                other => other,
+            })
+    }
+
     pub fn list_devices(&mut self) -> Result<Vec<DeviceInfo>, AppError> {
         self.actor.request(|actor| actor.list_devices())
     }
 
-    pub fn add_remote_device(&mut self, address: &str) -> Result<DeviceInfo, AppError> {
+    pub fn add_remote_device(
+        &mut self,
+        address: &str,
+        options: RemoteDeviceOptions,
+    ) -> Result<DeviceInfo, AppError> {
         let address = address.to_string();
         self.actor
-            .request(move |actor| actor.add_remote_device(&address))
+            .request(move |actor| actor.add_remote_device(&address, options))
     }
 
     pub fn remove_remote_device(&mut self, address: &str) -> Result<(), AppError> {
@@ -143,22 +369,184 @@ impl FridaService {
             .request(move |actor| actor.remove_remote_device(&address))
     }
 
+    pub fn add_portal(
+        &mut self,
+        address: &str,
+        options: RemoteDeviceOptions,
+    ) -> Result<DeviceInfo, AppError> {
+        let address = address.to_string();
+        self.actor
+            .request(move |actor| actor.add_portal(&address, options))
+    }
+
+    pub fn list_portal_nodes(&mut self, device_id: &str) -> Result<Vec<ProcessInfo>, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request(move |actor| actor.list_portal_nodes(&device_id))
+    }
+
     pub fn get_device_info(&mut self, device_id: &str) -> Result<DeviceInfo, AppError> {
         let device_id = device_id.to_string();
         self.actor
             .request(move |actor| actor.get_device_info(&device_id))
     }
 
+    /// Forgets the device's pairing record for this host (iOS over USB).
+    pub fn unpair_device(&mut self, device_id: &str) -> Result<(), AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request(move |actor| actor.unpair_device(&device_id))
+    }
+
+    /// Opens a loopback listener tunneled to `remote_port` on the device and
+    /// returns it with the bound local port.
+    pub fn forward_port(
+        &mut self,
+        device_id: &str,
+        remote_port: u16,
+    ) -> Result<PortForward, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request(move |actor| actor.forward_port(&device_id, remote_port))
+    }
+
+    pub fn stop_port_forward(&mut self, forward_id: &str) -> Result<(), AppError> {
+        let forward_id = forward_id.to_string();
+        self.actor
+            .request(move |actor| actor.forwards.stop(&forward_id))
+    }
+
+    pub fn list_port_forwards(&mut self) -> Result<Vec<PortForward>, AppError> {
+        self.actor.request(|actor| Ok(actor.forwards.list()))
+    }
+
+    /// Starts appending every script message to the NDJSON message log.
+    pub fn message_log_start(
+        &mut self,
+        options: MessageLogOptions,
+    ) -> Result<MessageLogStatus, AppError> {
+        self.actor
+            .request(move |actor| actor.message_log.start(&options))
+    }
+
+    pub fn message_log_stop(&mut self) -> Result<MessageLogStatus, AppError> {
+        self.actor.request(|actor| actor.message_log.stop())
+    }
+
+    pub fn message_log_status(&mut self) -> Result<MessageLogStatus, AppError> {
+        self.actor.request(|actor| actor.message_log.status())
+    }
+
+    /// Searches recent script events recorded after the `since` cursor.
+    pub fn messages_query(
+        &mut self,
+        filter: MessageFilter,
+        since: u64,
+        limit: Option<usize>,
+    ) -> Result<MessagePage, AppError> {
+        self.actor
+            .request(move |actor| Ok(actor.history.query(&filter, since, limit)))
+    }
+
+    /// OS, architecture, API level and access level reported by the device.
+    pub fn query_device(&mut self, device_id: &str) -> Result<DeviceSystemInfo, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request(move |actor| actor.query_device(&device_id))
+    }
+
     pub fn list_processes(&mut self, device_id: &str) -> Result<Vec<ProcessInfo>, AppError> {
         let device_id = device_id.to_string();
         self.actor
-            .request(move |actor| actor.list_processes(&device_id))
+            .request_bulk(move |actor| actor.list_processes(&device_id))
     }
 
     pub fn list_applications(&mut self, device_id: &str) -> Result<Vec<AppInfo>, AppError> {
         let device_id = device_id.to_string();
         self.actor
-            .request(move |actor| actor.list_applications(&device_id))
+            .request_bulk(move |actor| actor.list_applications(&device_id))
+    }
+
+    /// The application currently in the foreground, with its icon when the
+    /// device provides one. `None` when nothing is on screen or the device
+    /// has no notion of a frontmost app.
+    pub fn frontmost_application(&mut self, device_id: &str) -> Result<Option<AppInfo>, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request_bulk(move |actor| actor.frontmost_application(&device_id))
+    }
+
+    /// PNG icons (as `data:` URLs) for the given pids, where the device
+    /// provides them. Pids without an icon are left out.
+    pub fn process_icons(
+        &mut self,
+        device_id: &str,
+        pids: Vec<u32>,
+    ) -> Result<HashMap<u32, String>, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request_bulk(move |actor| actor.process_icons(&device_id, &pids))
+    }
+
+    /// PNG icons (as `data:` URLs) for the given application identifiers.
+    pub fn application_icons(
+        &mut self,
+        device_id: &str,
+        identifiers: Vec<String>,
+    ) -> Result<HashMap<String, String>, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request_bulk(move |actor| actor.application_icons(&device_id, &identifiers))
+    }
+
+    /// Starts refreshing `device_id`'s process list every `interval` in the
+    /// background, emitting `carf://process/changed` diffs. Returns the
+    /// current list as the baseline the diffs apply to.
+    pub fn watch_processes(
+        &mut self,
+        device_id: &str,
+        interval: Duration,
+    ) -> Result<Vec<ProcessInfo>, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request_bulk(move |actor| actor.watch_processes(&device_id, interval))
+    }
+
+    pub fn unwatch_processes(&mut self, device_id: &str) -> Result<bool, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request(move |actor| Ok(actor.process_watches.stop(&device_id)))
+    }
+
+    /// The last list seen by an active watch, if the device is watched.
+    pub fn watched_processes(
+        &mut self,
+        device_id: &str,
+    ) -> Result<Option<Vec<ProcessInfo>>, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request(move |actor| Ok(actor.process_watches.cached(&device_id)))
+    }
+
+    /// Anti-tamper evidence gathered for the session so far, if any.
+    pub fn session_diagnostic(
+        &mut self,
+        session_id: &str,
+    ) -> Result<Option<SessionDiagnostic>, AppError> {
+        let session_id = session_id.to_string();
+        self.actor
+            .request(move |actor| Ok(actor.tamper.diagnostic(&session_id)))
+    }
+
+    /// Captured stdio of the session's process written after `since_cursor`.
+    pub fn process_output_read(
+        &mut self,
+        session_id: &str,
+        since_cursor: u64,
+    ) -> Result<ProcessOutputPage, AppError> {
+        let session_id = session_id.to_string();
+        self.actor
+            .request(move |actor| actor.process_output_read(&session_id, since_cursor))
     }
 
     pub fn kill_process(&mut self, device_id: &str, pid: u32) -> Result<(), AppError> {
@@ -167,24 +555,78 @@ impl FridaService {
             .request(move |actor| actor.kill_process(&device_id, pid))
     }
 
+    /// Suspends every new process on the device at birth and emits
+    /// `carf://spawn/added` for it. Returns spawns already pending.
+    pub fn enable_spawn_gating(
+        &mut self,
+        device_id: &str,
+        options: SpawnGatingOptions,
+    ) -> Result<Vec<PendingSpawn>, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request(move |actor| actor.enable_spawn_gating(&device_id, options))
+    }
+
+    pub fn disable_spawn_gating(&mut self, device_id: &str) -> Result<(), AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request(move |actor| actor.disable_spawn_gating(&device_id))
+    }
+
+    pub fn list_pending_spawns(&mut self, device_id: &str) -> Result<Vec<PendingSpawn>, AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request(move |actor| actor.list_pending_spawns(&device_id))
+    }
+
+    pub fn resume_spawn(&mut self, device_id: &str, pid: u32) -> Result<(), AppError> {
+        let device_id = device_id.to_string();
+        self.actor
+            .request(move |actor| actor.resume_spawn(&device_id, pid))
+    }
+
+    /// Kills a pending spawn before it runs any of its own code.
+    pub fn deny_spawn(&mut self, device_id: &str, pid: u32) -> Result<(), AppError> {
+        self.kill_process(device_id, pid)
+    }
+
     pub fn spawn_and_attach(
         &mut self,
         device_id: &str,
-        options: SpawnOptions,
+        mut options: SpawnOptions,
     ) -> Result<SessionInfo, AppError> {
         let device_id = device_id.to_string();
-        self.actor
-            .request(move |actor| actor.spawn_and_attach(&device_id, options))
+        if options.runtime.is_none() {
+            options.runtime = self.default_runtime.clone();
+        }
+        self.request_within(self.timeouts.attach_ms, "spawn", move |actor| {
+            actor.spawn_and_attach(&device_id, options)
+        })
     }
 
+    /// Attaches under `TimeoutPolicy::attach_ms`. On timeout the attach is
+    /// cancelled through its operation id, so the actor is freed as soon as
+    /// Frida notices rather than whenever the device answers.
     pub fn attach(
         &mut self,
         device_id: &str,
-        options: AttachOptions,
+        mut options: AttachOptions,
     ) -> Result<SessionInfo, AppError> {
         let device_id = device_id.to_string();
-        self.actor
-            .request(move |actor| actor.attach(&device_id, options))
+        if options.runtime.is_none() {
+            options.runtime = self.default_runtime.clone();
+        }
+        let operation_id = options
+            .operation_id
+            .get_or_insert_with(|| format!("attach-{}", uuid::Uuid::new_v4()))
+            .clone();
+        let result = self.request_within(self.timeouts.attach_ms, "attach", move |actor| {
+            actor.attach(&device_id, options)
+        });
+        if matches!(result, Err(AppError::Timeout(_))) {
+            self.operations.cancel(&operation_id);
+        }
+        result
     }
 
     pub fn detach(&mut self, session_id: &str) -> Result<(), AppError> {
@@ -197,64 +639,311 @@ impl FridaService {
         self.actor.request(move |actor| actor.resume(&session_id))
     }
 
+    /// Removes hooks, reverts patches and frozen values, unloads the user
+    /// script and detaches, all in one actor request so nothing else runs
+    /// against the session in between. Steps that fail are reported rather
+    /// than stopping the rest.
+    pub fn cleanup_session(&mut self, session_id: &str) -> Result<SessionCleanupReport, AppError> {
+        let session_id = session_id.to_string();
+        self.request_within(self.timeouts.rpc_ms, "session cleanup", move |actor| {
+            actor.cleanup_session(&session_id)
+        })
+    }
+
     pub fn list_sessions(&mut self) -> Result<Vec<SessionInfo>, AppError> {
         self.actor.request(|actor| actor.list_sessions())
     }
 
+    /// Unloads every script and detaches every session, giving up after
+    /// `timeout` so a wedged target cannot hold up app exit. Returns how many
+    /// sessions were detached.
+    pub fn detach_all(&mut self, timeout: Duration) -> Result<usize, AppError> {
+        self.actor
+            .request_with_timeout(timeout, |actor| Ok(actor.detach_all()))
+    }
+
     pub fn rpc_call(
         &mut self,
         session_id: &str,
         method: &str,
         params: Value,
     ) -> Result<Value, AppError> {
+        if Self::is_job_method(method) {
+            return self.run_job(session_id, method, params);
+        }
+
+        let timeout_ms = match method {
+            "loadScript" => self.timeouts.script_load_ms,
+            _ => self.timeouts.rpc_ms,
+        };
+        let what = format!("RPC {method}");
         let session_id = session_id.to_string();
         let method = method.to_string();
-        self.actor
-            .request(move |actor| actor.rpc_call(&session_id, &method, params))
+        self.request_within(timeout_ms, &what, move |actor| {
+            actor.rpc_call(&session_id, &method, params)
+        })
     }
-}
-
-struct FridaActor {
-    frida: &'static Frida,
-    device_manager: OwnedDeviceManager,
-    remote_addresses: Vec<String>,
-    events: EventHub,
-    script_events_tx: mpsc::Sender<BridgeEvent>,
-    script_events_rx: mpsc::Receiver<BridgeEvent>,
-    _main_context_pump: MainContextPump,
-    sessions: HashMap<String, SessionBundle>,
-    agent_source: Option<String>,
-}
 
-struct SessionBundle {
-    info: SessionInfo,
-    session: OwnedSession,
-    core_script: Script<'static>,
-    user_script: Option<Script<'static>>,
-    spawned_pid: Option<u32>,
-    pause_mode: Option<PauseMode>,
-}
+    /// `rpc_call` for agent methods that answer with raw bytes (chunked
+    /// `readMemoryChunk` / `readFile`). The bytes arrive beside the JSON
+    /// reply as a `carf:binary` message instead of hex inside it.
+    pub fn rpc_read_bytes(
+        &mut self,
+        session_id: &str,
+        method: &str,
+        params: Value,
+    ) -> Result<(Value, Vec<u8>), AppError> {
+        let what = format!("RPC {method}");
+        let session_id = session_id.to_string();
+        let method = method.to_string();
+        self.request_within(self.timeouts.rpc_ms, &what, move |actor| {
+            actor.rpc_read_bytes(&session_id, &method, params)
+        })
+    }
 
-#[derive(Clone, Copy)]
-enum PauseMode {
-    FridaSpawn,
-    SignalStop,
-}
+    /// `rpc_call` for agent methods that take raw bytes (chunked
+    /// `writeFile`). `data` is posted as a `carf:binary` message just ahead
+    /// of the call.
+    pub fn rpc_write_bytes(
+        &mut self,
+        session_id: &str,
+        method: &str,
+        params: Value,
+        data: Vec<u8>,
+    ) -> Result<Value, AppError> {
+        let what = format!("RPC {method}");
+        let session_id = session_id.to_string();
+        let method = method.to_string();
+        self.request_within(self.timeouts.rpc_ms, &what, move |actor| {
+            actor.rpc_write_bytes(&session_id, &method, params, &data)
+        })
+    }
 
-struct SessionOptionsHandle {
-    ptr: *mut frida_sys::FridaSessionOptions,
-}
+    /// Whether `method` runs as an agent job (see `start_job`).
+    pub fn is_job_method(method: &str) -> bool {
+        JOB_RPC_METHODS.contains(&method)
+    }
 
-impl SessionOptionsHandle {
-    fn build(realm: Option<&str>, persist_timeout: Option<u32>) -> Self {
-        let ptr = unsafe { frida_sys::frida_session_options_new() };
-        let handle = Self { ptr };
+    /// Starts `method` as an agent job and waits for its result here rather
+    /// than on the actor, which stays free for other requests meanwhile.
+    fn run_job(
+        &mut self,
+        session_id: &str,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, AppError> {
+        self.start_job(session_id, method, params)?.wait()
+    }
 
-        if let Some(realm) = realm {
-            let value = match realm.to_ascii_lowercase().as_str() {
-                "emulated" => frida_sys::FridaRealm_FRIDA_REALM_EMULATED,
-                _ => frida_sys::FridaRealm_FRIDA_REALM_NATIVE,
-            };
+    /// Starts `method` as an agent job. Callers sharing the service should
+    /// release it before `JobHandle::wait`, which can take up to
+    /// `TimeoutPolicy::scan_ms`.
+    pub fn start_job(
+        &mut self,
+        session_id: &str,
+        method: &str,
+        params: Value,
+    ) -> Result<JobHandle, AppError> {
+        let job_session_id = session_id.to_string();
+        let job_method = method.to_string();
+        let timeout_ms = self.timeouts.scan_ms;
+        let result_rx = self.actor.request(move |actor| {
+            actor.start_job(&job_session_id, &job_method, params, timeout_ms)
+        })?;
+        Ok(JobHandle {
+            session_id: session_id.to_string(),
+            method: method.to_string(),
+            timeout_ms,
+            result_rx,
+        })
+    }
+
+    /// Evaluates `code` in the session's REPL script and pretty-prints the
+    /// result. A snippet that throws comes back as an `EvalResult` too.
+    pub fn eval(&mut self, session_id: &str, code: &str) -> Result<EvalResult, AppError> {
+        let session_id = session_id.to_string();
+        let code = code.to_string();
+        self.request_within(self.timeouts.rpc_ms, "evaluation", move |actor| {
+            actor.eval(&session_id, &code)
+        })
+    }
+
+    /// Posts `message` to one of the session's scripts, where `recv()` picks
+    /// it up; `data` arrives as the handler's second argument.
+    pub fn post_script_message(
+        &mut self,
+        session_id: &str,
+        script: &str,
+        message: Value,
+        data: Option<Vec<u8>>,
+    ) -> Result<(), AppError> {
+        let session_id = session_id.to_string();
+        let script = script.to_string();
+        self.actor.request(move |actor| {
+            actor.post_script_message(&session_id, &script, &message, data.as_deref())
+        })
+    }
+
+    /// Bundles a multi-file TypeScript/ESM agent into a single script with
+    /// Frida's built-in compiler.
+    pub fn compile_script(
+        &mut self,
+        entrypoint: &str,
+        project_root: &str,
+    ) -> Result<String, AppError> {
+        let entrypoint = entrypoint.to_string();
+        let project_root = project_root.to_string();
+        self.actor
+            .request_bulk(move |actor| actor.compile_script(&entrypoint, &project_root))
+    }
+
+    pub fn stalker_trace_summary(&mut self, session_id: &str) -> Result<TraceSummary, AppError> {
+        let session_id = session_id.to_string();
+        self.actor
+            .request(move |actor| actor.stalker_trace_summary(&session_id))
+    }
+
+    pub fn export_stalker_trace(
+        &mut self,
+        session_id: &str,
+        path: &str,
+    ) -> Result<TraceSummary, AppError> {
+        let session_id = session_id.to_string();
+        let path = path.to_string();
+        self.actor
+            .request_bulk(move |actor| actor.export_stalker_trace(&session_id, &path))
+    }
+
+    /// Drops a timestamped marker into the session's event log and emits it
+    /// as `carf://session/marker`.
+    pub fn add_session_marker(
+        &mut self,
+        session_id: &str,
+        label: &str,
+    ) -> Result<SessionMarker, AppError> {
+        let session_id = session_id.to_string();
+        let label = label.to_string();
+        self.actor
+            .request(move |actor| actor.add_session_marker(&session_id, label))
+    }
+
+    pub fn list_session_markers(
+        &mut self,
+        session_id: &str,
+    ) -> Result<Vec<SessionMarker>, AppError> {
+        let session_id = session_id.to_string();
+        self.actor.request(move |actor| {
+            actor.ensure_session(&session_id)?;
+            Ok(actor.markers.list(&session_id).to_vec())
+        })
+    }
+
+    pub fn clear_stalker_trace(&mut self, session_id: &str) -> Result<(), AppError> {
+        let session_id = session_id.to_string();
+        self.actor.request(move |actor| {
+            actor.traces.clear(&session_id);
+            Ok(())
+        })
+    }
+}
+
+struct FridaActor {
+    frida: &'static Frida,
+    device_manager: OwnedDeviceManager,
+    remote_addresses: Vec<String>,
+    /// Credentials for the remote addresses that need them. Kept in memory
+    /// only so the manager can reconnect when it is rebuilt.
+    remote_options: HashMap<String, RemoteDeviceOptions>,
+    portal_addresses: Vec<String>,
+    events: EventHub,
+    operations: OperationRegistry,
+    script_events_tx: mpsc::Sender<BridgeEvent>,
+    script_events_rx: mpsc::Receiver<BridgeEvent>,
+    process_output_tx: mpsc::Sender<RawOutput>,
+    process_output_rx: mpsc::Receiver<RawOutput>,
+    crash_tx: mpsc::Sender<CrashReport>,
+    crash_rx: mpsc::Receiver<CrashReport>,
+    spawn_tx: mpsc::Sender<PendingSpawn>,
+    spawn_rx: mpsc::Receiver<PendingSpawn>,
+    process_list_tx: mpsc::Sender<ProcessListResult>,
+    process_list_rx: mpsc::Receiver<ProcessListResult>,
+    health_tx: mpsc::Sender<HealthResult>,
+    health_rx: mpsc::Receiver<HealthResult>,
+    _main_context_pump: MainContextPump,
+    sessions: HashMap<String, SessionBundle>,
+    /// Agent jobs started by `start_job`, keyed by job id.
+    jobs: HashMap<String, PendingJob>,
+    process_watches: ProcessWatchStore,
+    process_output: ProcessOutputStore,
+    spawn_gating: SpawnGatingStore,
+    device_health: DeviceHealthStore,
+    /// Devices whose Frida version was compared with ours since they last
+    /// connected.
+    version_checked: HashSet<String>,
+    tamper: TamperMonitor,
+    traces: TraceStore,
+    markers: MarkerStore,
+    forwards: ForwardStore,
+    message_log: MessageRecorder,
+    binary: BinaryInbox,
+    history: MessageHistoryStore,
+    batcher: EventBatcher,
+    agent_source: Option<String>,
+}
+
+/// A watched device's process list, enumerated off the actor.
+type ProcessListResult = (String, Result<Vec<ProcessInfo>, String>);
+
+/// A health probe run off the actor: the device id or why the probe failed,
+/// and the same for the reconnect tried after a failed probe.
+struct HealthResult {
+    target: HealthTarget,
+    probe: Result<String, String>,
+    reconnect: Option<Result<String, String>>,
+}
+
+struct PendingJob {
+    session_id: String,
+    /// When the caller's `JobHandle::wait` gives up; the job is cancelled
+    /// then so it stops hiding the session from the stall check.
+    deadline: Option<Instant>,
+    result_tx: mpsc::SyncSender<Result<Value, AppError>>,
+}
+
+struct SessionBundle {
+    info: SessionInfo,
+    session: OwnedSession,
+    core_script: Script<'static>,
+    user_script: Option<Script<'static>>,
+    /// Where `user_script` came from, for restoring it after a restart.
+    user_script_origin: Option<SavedScript>,
+    /// Created by the first `eval`; keeps the REPL's globals between calls.
+    repl_script: Option<Script<'static>>,
+    spawned_pid: Option<u32>,
+    pause_mode: Option<PauseMode>,
+    expiry_warned: bool,
+}
+
+#[derive(Clone, Copy)]
+enum PauseMode {
+    FridaSpawn,
+    SignalStop,
+}
+
+struct SessionOptionsHandle {
+    ptr: *mut frida_sys::FridaSessionOptions,
+}
+
+impl SessionOptionsHandle {
+    fn build(realm: Option<&str>, persist_timeout: Option<u32>) -> Self {
+        let ptr = unsafe { frida_sys::frida_session_options_new() };
+        let handle = Self { ptr };
+
+        if let Some(realm) = realm {
+            let value = match realm.to_ascii_lowercase().as_str() {
+                "emulated" => frida_sys::FridaRealm_FRIDA_REALM_EMULATED,
+                _ => frida_sys::FridaRealm_FRIDA_REALM_NATIVE,
+            };
             unsafe {
                 frida_sys::frida_session_options_set_realm(handle.ptr, value);
             }
@@ -304,6 +993,16 @@ fn frida_device_ptr(device: &frida::Device<'static>) -> *mut frida_sys::FridaDev
     unsafe { std::mem::transmute_copy(device) }
 }
 
+pub(super) fn frida_device_manager_ptr(
+    manager: &DeviceManager<'static>,
+) -> *mut frida_sys::FridaDeviceManager {
+    debug_assert_eq!(
+        std::mem::size_of::<DeviceManager<'static>>(),
+        std::mem::size_of::<*mut frida_sys::FridaDeviceManager>(),
+    );
+    unsafe { std::mem::transmute_copy(manager) }
+}
+
 fn frida_session_ptr(session: &Session<'static>) -> *mut frida_sys::FridaSession {
     debug_assert_eq!(
         std::mem::size_of::<Session<'static>>(),
@@ -320,54 +1019,667 @@ fn frida_session_from_raw(session: *mut frida_sys::FridaSession) -> Session<'sta
     unsafe { std::mem::transmute(session) }
 }
 
-fn take_gerror_message(error: *mut frida_sys::GError) -> String {
-    if error.is_null() {
-        return "unknown Frida error".to_string();
+/// Hands load-time parameters to a freshly loaded user script.
+///
+/// Scripts can pick them up with `recv("init", ...)`, or export
+/// `init(stage, parameters)` like scripts written for the frida CLI; the
+/// export is awaited so `loadScript` only resolves once it has run.
+fn deliver_script_parameters(
+    script: &mut Script<'static>,
+    parameters: &Value,
+) -> Result<(), AppError> {
+    let message = json!({ "type": "init", "payload": parameters });
+    script
+        .post(message.to_string(), None)
+        .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
+
+    let exports = script
+        .list_exports()
+        .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
+    if exports.iter().any(|name| name == "init") {
+        script
+            .exports
+            .call("init", Some(json!(["late", parameters])))
+            .map_err(|error| {
+                AppError::ScriptLoadFailed(format!("init() failed: {error}")).caused_by(error)
+            })?;
     }
+    Ok(())
+}
 
-    let message = unsafe { CStr::from_ptr((*error).message) }
-        .to_string_lossy()
-        .into_owned();
-
-    unsafe {
-        frida_sys::g_error_free(error);
+fn failed_step(name: &str, error: String) -> CleanupStep {
+    CleanupStep {
+        name: name.to_string(),
+        removed: 0,
+        error: Some(error),
     }
-
-    message
 }
 
 impl FridaActor {
-    fn new(events: EventHub) -> Result<Self, AppError> {
+    fn new(events: EventHub, operations: OperationRegistry) -> Result<Self, AppError> {
         let frida = Box::leak(Box::new(unsafe { Frida::obtain() }));
-        let device_manager = OwnedDeviceManager::new(frida, &[]).map_err(|e| {
+        let device_manager = OwnedDeviceManager::new(frida, &[], &HashMap::new()).map_err(|e| {
             AppError::Internal(format!("Failed to initialize Frida device manager: {e}"))
+                .caused_by(e)
         })?;
         let (script_events_tx, script_events_rx) = mpsc::channel();
+        let (process_output_tx, process_output_rx) = mpsc::channel();
+        let (crash_tx, crash_rx) = mpsc::channel();
+        let (spawn_tx, spawn_rx) = mpsc::channel();
+        let (process_list_tx, process_list_rx) = mpsc::channel();
+        let (health_tx, health_rx) = mpsc::channel();
         let main_context_pump = MainContextPump::start();
 
         Ok(Self {
             frida,
             device_manager,
             remote_addresses: Vec::new(),
+            remote_options: HashMap::new(),
+            portal_addresses: Vec::new(),
             events,
+            operations,
             script_events_tx,
             script_events_rx,
+            process_output_tx,
+            process_output_rx,
+            crash_tx,
+            crash_rx,
+            spawn_tx,
+            spawn_rx,
+            process_list_tx,
+            process_list_rx,
+            health_tx,
+            health_rx,
             _main_context_pump: main_context_pump,
             sessions: HashMap::new(),
+            jobs: HashMap::new(),
+            process_watches: ProcessWatchStore::default(),
+            process_output: ProcessOutputStore::default(),
+            spawn_gating: SpawnGatingStore::default(),
+            device_health: DeviceHealthStore::default(),
+            version_checked: HashSet::new(),
+            tamper: TamperMonitor::default(),
+            traces: TraceStore::default(),
+            markers: MarkerStore::default(),
+            forwards: ForwardStore::default(),
+            message_log: MessageRecorder::default(),
+            binary: BinaryInbox::default(),
+            history: MessageHistoryStore::default(),
+            batcher: EventBatcher::default(),
             agent_source: None,
         })
     }
 
     fn pump(&mut self) {
+        self.drain_script_events();
+        self.drain_process_output();
+        self.drain_crash_reports();
+        self.drain_gated_spawns();
+        self.reap_detached_sessions();
+        self.expire_sessions();
+        self.expire_jobs();
+        self.check_agent_heartbeats();
+        self.refresh_process_watches();
+        self.check_device_health();
+
+        let sessions = &self.sessions;
+        self.traces
+            .retain_sessions(|session_id| sessions.contains_key(session_id));
+        self.markers
+            .retain_sessions(|session_id| sessions.contains_key(session_id));
+        self.message_log.flush();
+    }
+
+    /// The regular poll, shortened while script events are being batched so
+    /// batches go out at the batcher's pace rather than the poll's.
+    fn poll_interval(&self) -> Duration {
+        self.batcher
+            .poll_within(Instant::now())
+            .map_or(FRIDA_ACTOR_POLL_INTERVAL, |due| {
+                due.min(FRIDA_ACTOR_POLL_INTERVAL)
+            })
+    }
+
+    fn drain_script_events(&mut self) {
         while let Ok(event) = self.script_events_rx.try_recv() {
-            self.events.emit(event.name, event.payload);
+            if event.name == "carf://stalker/event" {
+                self.traces.record(&event.payload);
+            }
+            if event.name == "carf://vars/set" {
+                self.persist_agent_variable(&event.payload);
+            }
+            if event.name == "carf://job/result" {
+                self.finish_job(event.payload);
+                continue;
+            }
+            if event.name == "carf://agent/heartbeat" {
+                if let Some(session_id) = event.payload.get("sessionId").and_then(Value::as_str) {
+                    self.tamper.heartbeat(session_id, now_millis());
+                }
+                continue;
+            }
+            if event.name == "carf://tamper/evidence" {
+                self.record_tamper_evidence(&event.payload);
+                continue;
+            }
+            // Stalker events already have their own buffer and would
+            // crowd everything else out of the history.
+            if event.name != "carf://stalker/event" {
+                self.history.record(&event.name, &event.payload);
+            }
+            self.batcher.push(event, Instant::now());
         }
 
-        self.reap_detached_sessions();
+        let now = Instant::now();
+        if self.batcher.is_due(now) {
+            for event in self.batcher.flush(now) {
+                self.events.emit(event.name, event.payload);
+            }
+        }
+    }
+
+    fn record_tamper_evidence(&mut self, payload: &Value) {
+        let Some(session_id) = payload.get("sessionId").and_then(Value::as_str) else {
+            return;
+        };
+        let kind = payload
+            .get("kind")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        let detail = payload.get("detail").cloned().unwrap_or(Value::Null);
+        if let Some(diagnostic) = self.tamper.record(session_id, kind, detail, now_millis()) {
+            self.emit_diagnostic(&diagnostic);
+        }
+    }
+
+    /// Flags sessions whose agent stopped heartbeating. Sessions with a job
+    /// in flight are skipped: a long synchronous scan also blocks the timer.
+    fn check_agent_heartbeats(&mut self) {
+        let jobs = &self.jobs;
+        let diagnostics = self.tamper.check_stalls(now_millis(), |session_id| {
+            jobs.values().any(|job| job.session_id == session_id)
+        });
+        for diagnostic in diagnostics {
+            self.emit_diagnostic(&diagnostic);
+        }
+    }
+
+    fn emit_diagnostic(&self, diagnostic: &SessionDiagnostic) {
+        if let Ok(payload) = serde_json::to_value(diagnostic) {
+            self.events.emit("carf://session/diagnostic", payload);
+        }
+    }
+
+    fn drain_process_output(&mut self) {
+        while let Ok(output) = self.process_output_rx.try_recv() {
+            let chunk = self.process_output.record(output);
+            if let Ok(payload) = serde_json::to_value(&chunk) {
+                self.events.emit("carf://process/output", payload);
+            }
+        }
+    }
+
+    /// Saves crash reports delivered by sessions' `detached` signal and
+    /// emits `carf://target/crashed` with the saved file.
+    fn drain_crash_reports(&mut self) {
+        while let Ok(report) = self.crash_rx.try_recv() {
+            let path = match save_crash_report(&report) {
+                Ok(path) => Some(path.to_string_lossy().into_owned()),
+                Err(error) => {
+                    log::warn!(
+                        "Failed to save crash report for {} ({}): {error}",
+                        report.process_name,
+                        report.pid
+                    );
+                    None
+                }
+            };
+            self.events.emit(
+                "carf://target/crashed",
+                json!({
+                    "sessionId": report.session_id,
+                    "pid": report.pid,
+                    "processName": report.process_name,
+                    "summary": report.summary,
+                    "path": path,
+                }),
+            );
+        }
+    }
+
+    /// Emits `carf://spawn/added` for each gated spawn, attaching to and
+    /// resuming the ones the device's gating options auto-attach.
+    fn drain_gated_spawns(&mut self) {
+        while let Ok(spawn) = self.spawn_rx.try_recv() {
+            let Some(options) = self.spawn_gating.options(&spawn.device_id) else {
+                continue;
+            };
+            let auto_attach = options.wants(&spawn).then(|| options.clone());
+            if let Ok(payload) = serde_json::to_value(&spawn) {
+                self.events.emit("carf://spawn/added", payload);
+            }
+            if let Some(options) = auto_attach {
+                self.auto_attach_spawn(&spawn, options);
+            }
+        }
+    }
+
+    fn auto_attach_spawn(&mut self, spawn: &PendingSpawn, options: SpawnGatingOptions) {
+        let attach_options = AttachOptions {
+            runtime: options.runtime,
+            script_path: options.script_path,
+            script_parameters: options.script_parameters,
+            ..AttachOptions::for_target(json!(spawn.pid))
+        };
+        let attached = self.attach(&spawn.device_id, attach_options);
+        // Resume even when the attach failed so the process is not left
+        // suspended with nobody watching it.
+        let resumed = self.resume_spawn(&spawn.device_id, spawn.pid);
+        let error = match (&attached, &resumed) {
+            (Err(error), _) | (Ok(_), Err(error)) => Some(error.to_string()),
+            (Ok(_), Ok(())) => None,
+        };
+        self.events.emit(
+            "carf://spawn/attached",
+            json!({
+                "deviceId": spawn.device_id,
+                "pid": spawn.pid,
+                "identifier": spawn.identifier,
+                "session": attached.ok(),
+                "error": error,
+            }),
+        );
+    }
+
+    fn enable_spawn_gating(
+        &mut self,
+        device_id: &str,
+        options: SpawnGatingOptions,
+    ) -> Result<Vec<PendingSpawn>, AppError> {
+        let device = self.get_device(device_id)?;
+        if !self.spawn_gating.is_tapped(device_id) {
+            connect_spawn_added_signal(
+                frida_device_ptr(device.as_ref()),
+                device_id,
+                self.spawn_tx.clone(),
+            );
+            self.spawn_gating.mark_tapped(device_id);
+        }
+
+        let mut error = std::ptr::null_mut();
+        unsafe {
+            frida_sys::frida_device_enable_spawn_gating_sync(
+                frida_device_ptr(device.as_ref()),
+                std::ptr::null_mut(),
+                &mut error,
+            );
+        }
+        if !error.is_null() {
+            return Err(gerror_into(error, AppError::Internal));
+        }
+        self.spawn_gating.enable(device_id, options);
+        self.list_pending_spawns(device_id)
+    }
+
+    fn disable_spawn_gating(&mut self, device_id: &str) -> Result<(), AppError> {
+        let device = self.get_device(device_id)?;
+        let mut error = std::ptr::null_mut();
+        unsafe {
+            frida_sys::frida_device_disable_spawn_gating_sync(
+                frida_device_ptr(device.as_ref()),
+                std::ptr::null_mut(),
+                &mut error,
+            );
+        }
+        if !error.is_null() {
+            return Err(gerror_into(error, AppError::Internal));
+        }
+        self.spawn_gating.disable(device_id);
+        Ok(())
+    }
+
+    fn list_pending_spawns(&mut self, device_id: &str) -> Result<Vec<PendingSpawn>, AppError> {
+        let device = self.get_device(device_id)?;
+        let mut error = std::ptr::null_mut();
+        let list = unsafe {
+            frida_sys::frida_device_enumerate_pending_spawn_sync(
+                frida_device_ptr(device.as_ref()),
+                std::ptr::null_mut(),
+                &mut error,
+            )
+        };
+        if !error.is_null() {
+            return Err(gerror_into(error, AppError::Internal));
+        }
+        Ok(unsafe { take_spawn_list(list, device_id) })
+    }
+
+    fn resume_spawn(&mut self, device_id: &str, pid: u32) -> Result<(), AppError> {
+        let device = self.get_device(device_id)?;
+        device.as_ref().resume(pid).map_err(|error| {
+            AppError::ProcessNotFound(format!("Cannot resume {pid}: {error}")).caused_by(error)
+        })
+    }
+
+    fn process_output_read(
+        &self,
+        session_id: &str,
+        since_cursor: u64,
+    ) -> Result<ProcessOutputPage, AppError> {
+        let bundle = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(format!("Session not found: {session_id}")))?;
+        Ok(self.process_output.read(bundle.info.pid, since_cursor))
+    }
+
+    /// Starts capturing piped stdio from processes spawned on `device`.
+    /// Connected once per device, before the first piped spawn.
+    fn tap_process_output(&mut self, device_id: &str, device: &OwnedDevice) {
+        if self.process_output.is_tapped(device_id) {
+            return;
+        }
+        connect_output_signal(
+            frida_device_ptr(device.as_ref()),
+            self.process_output_tx.clone(),
+        );
+        self.process_output.mark_tapped(device_id);
+    }
+
+    /// Applies finished process lists and starts enumerations for watches
+    /// that are due. Enumerating can take seconds on a slow device, so it
+    /// runs on a worker thread rather than holding up the actor.
+    fn refresh_process_watches(&mut self) {
+        while let Ok((device_id, result)) = self.process_list_rx.try_recv() {
+            match result {
+                Ok(processes) => {
+                    if let Some(diff) = self.process_watches.apply(&device_id, processes) {
+                        if let Ok(payload) = serde_json::to_value(&diff) {
+                            self.events.emit("carf://process/changed", payload);
+                        }
+                    }
+                }
+                Err(error) => {
+                    log::warn!("Process watch refresh failed for {device_id}: {error}");
+                    self.process_watches.defer(&device_id);
+                }
+            }
+        }
+
+        for device_id in self.process_watches.due(Instant::now()) {
+            let device = match self.get_device(&device_id) {
+                Ok(device) => unsafe { DeviceRef::new(frida_device_ptr(device.as_ref())) },
+                Err(error) => {
+                    log::warn!("Process watch refresh failed for {device_id}: {error}");
+                    self.process_watches.defer(&device_id);
+                    continue;
+                }
+            };
+            self.process_watches.begin(&device_id);
+            let results = self.process_list_tx.clone();
+            let worker_device_id = device_id.clone();
+            let spawned = thread::Builder::new()
+                .name("carf-process-watch".to_string())
+                .spawn(move || {
+                    let _ = results.send((worker_device_id, device.enumerate_processes()));
+                });
+            if let Err(error) = spawned {
+                log::warn!("Could not start process watch refresh: {error}");
+                self.process_watches.defer(&device_id);
+            }
+        }
+    }
+
+    /// Pings remote endpoints and the devices sessions run on, emitting
+    /// `carf://device/unhealthy` and `carf://device/reconnected` as they go
+    /// down and come back. Lost remote devices are re-added with backoff.
+    /// Probes and reconnects block on the network, so they run on worker
+    /// threads and report back here; one that hangs past `PROBE_TIMEOUT`
+    /// counts as failed.
+    fn check_device_health(&mut self) {
+        let now = Instant::now();
+        while let Ok(result) = self.health_rx.try_recv() {
+            if self.device_health.finish(&result.target) {
+                self.apply_health(&result.target, result.probe, result.reconnect, now);
+            }
+        }
+        for (target, reconnect) in self.device_health.timed_out(now) {
+            let reason = format!("device did not answer within {}s", PROBE_TIMEOUT.as_secs());
+            let reconnect = reconnect.then(|| Err(reason.clone()));
+            self.apply_health(&target, Err(reason), reconnect, now);
+        }
+
+        if !self.device_health.is_due(now) {
+            return;
+        }
+        self.device_health.schedule(now);
+
+        let mut targets: Vec<HealthTarget> = self
+            .remote_addresses
+            .iter()
+            .cloned()
+            .map(HealthTarget::Remote)
+            .collect();
+        let session_devices = self
+            .sessions
+            .values()
+            .map(|bundle| HealthTarget::Device(bundle.info.device_id.clone()));
+        for target in session_devices.chain(self.device_health.failing_targets()) {
+            let is_remote = self
+                .remote_addresses
+                .iter()
+                .any(|address| target.key().contains(address.as_str()));
+            if target.key() != "local" && !is_remote && !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+
+        for target in targets {
+            let reconnect = match &target {
+                HealthTarget::Remote(address)
+                    if self.device_health.should_reconnect(&target, now) =>
+                {
+                    Some((
+                        ManagerRef::new(self.device_manager.as_ref()),
+                        address.clone(),
+                        self.remote_options.get(address).cloned(),
+                    ))
+                }
+                _ => None,
+            };
+            if !self.device_health.begin(&target, reconnect.is_some(), now) {
+                continue;
+            }
+            let device = self.find_health_target(&target);
+            let results = self.health_tx.clone();
+            let worker_target = target.clone();
+            let spawned = thread::Builder::new()
+                .name("carf-device-health".to_string())
+                .spawn(move || {
+                    let probe = device
+                        .ok_or_else(|| "device is no longer connected".to_string())
+                        .and_then(|device| device.probe());
+                    let reconnect = match (&probe, reconnect) {
+                        (Err(_), Some((manager, address, options))) => Some(
+                            manager
+                                .reconnect_remote_device(&address, options.as_ref())
+                                .map_err(|error| error.to_string())
+                                .and_then(|device| device.probe()),
+                        ),
+                        _ => None,
+                    };
+                    let _ = results.send(HealthResult {
+                        target: worker_target,
+                        probe,
+                        reconnect,
+                    });
+                });
+            if let Err(error) = spawned {
+                log::warn!("Could not start device health probe: {error}");
+                self.device_health.finish(&target);
+            }
+        }
+    }
+
+    /// Records one probe's outcome and emits the events it leads to.
+    fn apply_health(
+        &mut self,
+        target: &HealthTarget,
+        probe: Result<String, String>,
+        reconnect: Option<Result<String, String>>,
+        now: Instant,
+    ) {
+        let reason = match probe {
+            Ok(device_id) => {
+                if let Some(event) = self.device_health.healthy(target, &device_id) {
+                    log::info!("Device {device_id} is reachable again");
+                    self.emit_device_health("carf://device/reconnected", &event);
+                }
+                return;
+            }
+            Err(reason) => reason,
+        };
+        if let Some(event) = self.device_health.unhealthy(target, &reason, now) {
+            log::warn!("Device {} is unhealthy: {reason}", event.device_id);
+            self.emit_device_health("carf://device/unhealthy", &event);
+        }
+
+        let (HealthTarget::Remote(address), Some(result)) = (target, reconnect) else {
+            return;
+        };
+        match result {
+            Ok(device_id) => {
+                self.device_health.reconnected(target);
+                self.version_checked.remove(&device_id);
+                if let Some(event) = self.device_health.healthy(target, &device_id) {
+                    log::info!("Reconnected to remote device {address}");
+                    self.emit_device_health("carf://device/reconnected", &event);
+                }
+            }
+            Err(reason) => {
+                if let Some(event) = self.device_health.reconnect_failed(target, &reason, now) {
+                    log::warn!("Giving up reconnecting to {address}: {reason}");
+                    self.emit_device_health("carf://device/unhealthy", &event);
+                }
+            }
+        }
+    }
+
+    /// The device a health target refers to, if the manager still lists it.
+    fn find_health_target(&self, target: &HealthTarget) -> Option<DeviceRef> {
+        let device = match target {
+            HealthTarget::Device(device_id) => self.get_device(device_id).ok()?,
+            HealthTarget::Remote(address) => self
+                .device_manager
+                .as_ref()
+                .enumerate_all_devices()
+                .into_iter()
+                .find(|device| {
+                    device.get_id().contains(address.as_str())
+                        || device.get_name().contains(address.as_str())
+                })
+                .map(OwnedDevice::new)?,
+        };
+        Some(unsafe { DeviceRef::new(frida_device_ptr(device.as_ref())) })
+    }
+
+    fn emit_device_health(&self, name: &str, event: &impl serde::Serialize) {
+        if let Ok(payload) = serde_json::to_value(event) {
+            self.events.emit(name, payload);
+        }
+    }
+
+    fn watch_processes(
+        &mut self,
+        device_id: &str,
+        interval: Duration,
+    ) -> Result<Vec<ProcessInfo>, AppError> {
+        let processes = self.list_processes(device_id)?;
+        self.process_watches
+            .start(device_id, interval, processes.clone());
+        Ok(processes)
+    }
+
+    /// Runs `method` in the agent's job queue. The returned receiver yields
+    /// the result once the agent reports it through `job/result`.
+    fn start_job(
+        &mut self,
+        session_id: &str,
+        method: &str,
+        params: Value,
+        timeout_ms: u64,
+    ) -> Result<mpsc::Receiver<Result<Value, AppError>>, AppError> {
+        let job_id = uuid::Uuid::new_v4().to_string();
+        let (result_tx, result_rx) = mpsc::sync_channel(1);
+        self.jobs.insert(
+            job_id.clone(),
+            PendingJob {
+                session_id: session_id.to_string(),
+                deadline: (timeout_ms > 0)
+                    .then(|| Instant::now() + Duration::from_millis(timeout_ms)),
+                result_tx,
+            },
+        );
+
+        let started = self.rpc_call(
+            session_id,
+            "startJob",
+            json!({ "jobId": job_id, "method": method, "params": params }),
+        );
+        if let Err(error) = started {
+            self.jobs.remove(&job_id);
+            return Err(error);
+        }
+        Ok(result_rx)
+    }
+
+    fn finish_job(&mut self, payload: Value) {
+        let Some(job_id) = payload.get("jobId").and_then(Value::as_str) else {
+            return;
+        };
+        if let Some(job) = self.jobs.remove(job_id) {
+            self.tamper.heartbeat(&job.session_id, now_millis());
+            // The caller may have timed out and gone away.
+            let _ = job.result_tx.send(unwrap_rpc_result(payload));
+        }
+    }
+
+    /// Drops jobs whose caller has stopped waiting and tells the agent to
+    /// skip or discard them.
+    fn expire_jobs(&mut self) {
+        let now = Instant::now();
+        let expired = self
+            .jobs
+            .iter()
+            .filter(|(_, job)| job.deadline.is_some_and(|deadline| now >= deadline))
+            .map(|(job_id, _)| job_id.clone())
+            .collect::<Vec<_>>();
+        for job_id in expired {
+            let Some(job) = self.jobs.remove(&job_id) else {
+                continue;
+            };
+            if let Err(error) =
+                self.rpc_call(&job.session_id, "cancelJob", json!({ "jobId": job_id }))
+            {
+                log::debug!("Could not cancel timed-out job {job_id}: {error}");
+            }
+        }
     }
 
     fn rebuild_device_manager(&mut self) -> Result<(), AppError> {
-        self.device_manager = OwnedDeviceManager::new(self.frida, &self.remote_addresses)?;
+        self.device_manager =
+            OwnedDeviceManager::new(self.frida, &self.remote_addresses, &self.remote_options)?;
+        // Devices come back as new connections, possibly to an upgraded
+        // frida-server, so their versions are compared again.
+        self.version_checked.clear();
+        // Signals were connected to the old device objects; the next piped
+        // spawn on each device connects them again.
+        self.process_output.clear_taps();
+        for (device_id, options) in self.spawn_gating.reset() {
+            if let Err(error) = self.enable_spawn_gating(&device_id, options) {
+                log::warn!("Spawn gating on {device_id} was lost with its connection: {error}");
+            }
+        }
         Ok(())
     }
 
@@ -376,23 +1688,80 @@ impl FridaActor {
             .as_ref()
             .enumerate_all_devices()
             .into_iter()
-            .map(|device| serialize_device(&device))
+            .map(|device| self.serialize_device(&device))
             .collect::<Result<Vec<_>, _>>()
     }
 
-    fn add_remote_device(&mut self, address: &str) -> Result<DeviceInfo, AppError> {
+    /// Serializes a device, tagging the ones that were added as portals.
+    fn serialize_device(&self, device: &frida::Device<'static>) -> Result<DeviceInfo, AppError> {
+        let mut info = serialize_device(device)?;
+        if self.is_portal_device(&info.id, &info.name) {
+            info.device_type = DeviceType::Portal;
+        }
+        Ok(info)
+    }
+
+    fn is_portal_device(&self, device_id: &str, device_name: &str) -> bool {
+        self.portal_addresses
+            .iter()
+            .any(|address| device_id.contains(address.as_str()) || device_name.contains(address.as_str()))
+    }
+
+    fn add_remote_device(
+        &mut self,
+        address: &str,
+        options: RemoteDeviceOptions,
+    ) -> Result<DeviceInfo, AppError> {
         let address = address.trim();
         if !address.contains(':') {
             return Err(AppError::InvalidAddress(format!(
                 "Invalid address: {address}"
             )));
         }
+        if let Some(certificate) = &options.certificate {
+            if !std::path::Path::new(certificate).is_file() {
+                return Err(AppError::InvalidArgument(format!(
+                    "certificate not found: {certificate}"
+                )));
+            }
+        }
 
-        if !self.remote_addresses.iter().any(|entry| entry == address) {
+        let previous_options = self.remote_options.remove(address);
+        if !options.is_empty() {
+            self.remote_options.insert(address.to_string(), options);
+        }
+        let added = !self.remote_addresses.iter().any(|entry| entry == address);
+        if added {
             self.remote_addresses.push(address.to_string());
         }
-        self.rebuild_device_manager()?;
+        let connected = self
+            .rebuild_device_manager()
+            .and_then(|()| self.check_remote_device(address));
+        let device = match connected {
+            Ok(device) => device,
+            Err(error) => {
+                // Leaving a failing endpoint behind, e.g. one with a wrong
+                // token or an incompatible frida-server, would break every
+                // later rebuild.
+                if added {
+                    self.remote_addresses.retain(|entry| entry != address);
+                }
+                self.remote_options.remove(address);
+                if let Some(previous) = previous_options {
+                    self.remote_options.insert(address.to_string(), previous);
+                }
+                self.rebuild_device_manager()?;
+                return Err(error);
+            }
+        };
+
+        self.serialize_device(device.as_ref())
+    }
 
+    /// Finds the device just added for `address` and makes it handshake.
+    /// frida-server only does so on first use, so without this an
+    /// incompatible server is only reported by the first attach.
+    fn check_remote_device(&self, address: &str) -> Result<OwnedDevice, AppError> {
         let device = self.get_device(address).or_else(|_| {
             self.device_manager
                 .as_ref()
@@ -404,18 +1773,87 @@ impl FridaActor {
                 .map(OwnedDevice::new)
                 .ok_or_else(|| AppError::DeviceNotFound(format!("Device not found: {address}")))
         })?;
+        if let Err(error) = device.as_ref().query_system_parameters() {
+            if let Some(mismatch) = version::mismatch_error(&error.to_string()) {
+                return Err(mismatch.caused_by(error));
+            }
+        }
+        Ok(device)
+    }
+
+    fn remove_remote_device(&mut self, address: &str) -> Result<(), AppError> {
+        self.device_health.forget(address);
+        self.remote_addresses.retain(|entry| entry != address);
+        self.remote_options.remove(address);
+        self.portal_addresses.retain(|entry| entry != address);
+        self.rebuild_device_manager()
+    }
+
+    /// Connects to a frida-portal control endpoint. The portal shows up as a
+    /// single device whose processes are the nodes that joined it, so attach
+    /// and session traffic route through it via the regular device id.
+    fn add_portal(
+        &mut self,
+        address: &str,
+        options: RemoteDeviceOptions,
+    ) -> Result<DeviceInfo, AppError> {
+        let address = address.trim();
+        if !self.portal_addresses.iter().any(|entry| entry == address) {
+            self.portal_addresses.push(address.to_string());
+        }
+
+        self.add_remote_device(address, options).inspect_err(|_| {
+            self.portal_addresses.retain(|entry| entry != address);
+        })
+    }
+
+    fn list_portal_nodes(&mut self, device_id: &str) -> Result<Vec<ProcessInfo>, AppError> {
+        let info = self.get_device_info(device_id)?;
+        if !matches!(info.device_type, DeviceType::Portal) {
+            return Err(AppError::DeviceNotFound(format!(
+                "Device is not a portal: {device_id}"
+            )));
+        }
+        self.list_processes(device_id)
+    }
+
+    fn get_device_info(&mut self, device_id: &str) -> Result<DeviceInfo, AppError> {
+        let device = self.get_device(device_id)?;
+        self.serialize_device(device.as_ref())
+    }
+
+    fn unpair_device(&mut self, device_id: &str) -> Result<(), AppError> {
+        let device = self.get_device(device_id)?;
+        let mut error = std::ptr::null_mut();
+        unsafe {
+            frida_sys::frida_device_unpair_sync(
+                frida_device_ptr(device.as_ref()),
+                std::ptr::null_mut(),
+                &mut error,
+            );
+        }
 
-        serialize_device(device.as_ref())
+        if !error.is_null() {
+            return Err(gerror_into(error, AppError::Internal));
+        }
+        Ok(())
     }
 
-    fn remove_remote_device(&mut self, address: &str) -> Result<(), AppError> {
-        self.remote_addresses.retain(|entry| entry != address);
-        self.rebuild_device_manager()
+    fn forward_port(&mut self, device_id: &str, remote_port: u16) -> Result<PortForward, AppError> {
+        let device = self.get_device(device_id)?;
+        unsafe {
+            self.forwards.start(
+                device_id,
+                frida_device_ptr(device.as_ref()),
+                remote_port,
+                now_millis(),
+            )
+        }
     }
 
-    fn get_device_info(&mut self, device_id: &str) -> Result<DeviceInfo, AppError> {
+    fn query_device(&mut self, device_id: &str) -> Result<DeviceSystemInfo, AppError> {
         let device = self.get_device(device_id)?;
-        serialize_device(device.as_ref())
+        query_device_system(device.as_ref())
     }
 
     fn list_processes(&mut self, device_id: &str) -> Result<Vec<ProcessInfo>, AppError> {
@@ -447,7 +1885,7 @@ impl FridaActor {
         };
 
         if !error.is_null() {
-            return Err(AppError::Internal(take_gerror_message(error)));
+            return Err(gerror_into(error, AppError::Internal));
         }
 
         let count = unsafe { frida_sys::frida_application_list_size(applications) };
@@ -481,12 +1919,174 @@ impl FridaActor {
         Ok(results)
     }
 
+    fn frontmost_application(&mut self, device_id: &str) -> Result<Option<AppInfo>, AppError> {
+        let device = self.get_device(device_id)?;
+        let mut error = std::ptr::null_mut();
+        let application = unsafe {
+            let options = frida_sys::frida_frontmost_query_options_new();
+            frida_sys::frida_frontmost_query_options_set_scope(
+                options,
+                frida_sys::FridaScope_FRIDA_SCOPE_FULL,
+            );
+            let application = frida_sys::frida_device_get_frontmost_application_sync(
+                frida_device_ptr(device.as_ref()),
+                options,
+                std::ptr::null_mut(),
+                &mut error,
+            );
+            frida_sys::frida_unref(options.cast());
+            application
+        };
+
+        if !error.is_null() {
+            return Err(gerror_into(error, AppError::Internal));
+        }
+        if application.is_null() {
+            return Ok(None);
+        }
+
+        let info = unsafe {
+            let pid = frida_sys::frida_application_get_pid(application);
+            AppInfo {
+                identifier: CStr::from_ptr(frida_sys::frida_application_get_identifier(
+                    application,
+                ))
+                .to_string_lossy()
+                .into_owned(),
+                name: CStr::from_ptr(frida_sys::frida_application_get_name(application))
+                    .to_string_lossy()
+                    .into_owned(),
+                pid: if pid == 0 { None } else { Some(pid) },
+                icon: icon_from_parameters(frida_sys::frida_application_get_parameters(
+                    application,
+                )),
+            }
+        };
+        unsafe {
+            frida_sys::frida_unref(application.cast());
+        }
+
+        Ok(Some(info))
+    }
+
+    fn process_icons(
+        &mut self,
+        device_id: &str,
+        pids: &[u32],
+    ) -> Result<HashMap<u32, String>, AppError> {
+        // An options object with nothing selected matches every process.
+        if pids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let device = self.get_device(device_id)?;
+        let mut error = std::ptr::null_mut();
+        let processes = unsafe {
+            let options = frida_sys::frida_process_query_options_new();
+            for pid in pids {
+                frida_sys::frida_process_query_options_select_pid(options, *pid);
+            }
+            frida_sys::frida_process_query_options_set_scope(
+                options,
+                frida_sys::FridaScope_FRIDA_SCOPE_FULL,
+            );
+            let processes = frida_sys::frida_device_enumerate_processes_sync(
+                frida_device_ptr(device.as_ref()),
+                options,
+                std::ptr::null_mut(),
+                &mut error,
+            );
+            frida_sys::frida_unref(options.cast());
+            processes
+        };
+
+        if !error.is_null() {
+            return Err(gerror_into(error, AppError::Internal));
+        }
+
+        let mut icons = HashMap::new();
+        unsafe {
+            for index in 0..frida_sys::frida_process_list_size(processes) {
+                let process = frida_sys::frida_process_list_get(processes, index);
+                if let Some(icon) =
+                    icon_from_parameters(frida_sys::frida_process_get_parameters(process))
+                {
+                    icons.insert(frida_sys::frida_process_get_pid(process), icon);
+                }
+                frida_sys::frida_unref(process.cast());
+            }
+            frida_sys::frida_unref(processes.cast());
+        }
+
+        Ok(icons)
+    }
+
+    fn application_icons(
+        &mut self,
+        device_id: &str,
+        identifiers: &[String],
+    ) -> Result<HashMap<String, String>, AppError> {
+        if identifiers.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let device = self.get_device(device_id)?;
+        let mut error = std::ptr::null_mut();
+        let applications = unsafe {
+            let options = frida_sys::frida_application_query_options_new();
+            for identifier in identifiers {
+                if let Ok(identifier) = CString::new(identifier.as_str()) {
+                    frida_sys::frida_application_query_options_select_identifier(
+                        options,
+                        identifier.as_ptr(),
+                    );
+                }
+            }
+            frida_sys::frida_application_query_options_set_scope(
+                options,
+                frida_sys::FridaScope_FRIDA_SCOPE_FULL,
+            );
+            let applications = frida_sys::frida_device_enumerate_applications_sync(
+                frida_device_ptr(device.as_ref()),
+                options,
+                std::ptr::null_mut(),
+                &mut error,
+            );
+            frida_sys::frida_unref(options.cast());
+            applications
+        };
+
+        if !error.is_null() {
+            return Err(gerror_into(error, AppError::Internal));
+        }
+
+        let mut icons = HashMap::new();
+        unsafe {
+            for index in 0..frida_sys::frida_application_list_size(applications) {
+                let application = frida_sys::frida_application_list_get(applications, index);
+                if let Some(icon) =
+                    icon_from_parameters(frida_sys::frida_application_get_parameters(application))
+                {
+                    let identifier =
+                        CStr::from_ptr(frida_sys::frida_application_get_identifier(application))
+                            .to_string_lossy()
+                            .into_owned();
+                    icons.insert(identifier, icon);
+                }
+                frida_sys::frida_unref(application.cast());
+            }
+            frida_sys::frida_unref(applications.cast());
+        }
+
+        Ok(icons)
+    }
+
     fn kill_process(&mut self, device_id: &str, pid: u32) -> Result<(), AppError> {
         let mut device = self.get_device(device_id)?;
         device
             .as_mut()
             .kill(pid)
-            .map_err(|error| AppError::Internal(error.to_string()))
+            .map_err(|error| AppError::Internal(error.to_string()).caused_by(error))
     }
 
     fn spawn_and_attach(
@@ -518,7 +2118,11 @@ impl FridaActor {
         }
 
         if let Some(stdio) = options.stdio.as_deref() {
-            spawn_options = spawn_options.stdio(parse_spawn_stdio(stdio));
+            let stdio = parse_spawn_stdio(stdio);
+            if matches!(stdio, SpawnStdio::Pipe) {
+                self.tap_process_output(device_id, &device);
+            }
+            spawn_options = spawn_options.stdio(stdio);
         }
 
         let pid = device
@@ -526,6 +2130,7 @@ impl FridaActor {
             .spawn(&options.identifier, &spawn_options)
             .map_err(|error| {
                 AppError::SpawnFailed(options.identifier.clone(), error.to_string())
+                    .caused_by(error)
             })?;
         let session_options = SessionOptionsHandle::from_spawn_options(&options)?;
         let mut error = std::ptr::null_mut();
@@ -542,19 +2147,20 @@ impl FridaActor {
         if !error.is_null() {
             // `spawn` started the target in suspended state; if `attach` fails we
             // must kill the orphaned process or it stays suspended forever.
-            let message = take_gerror_message(error);
+            let error = take_gerror(error);
             if let Err(kill_err) = device.as_mut().kill(pid) {
                 log::warn!(
                     "Failed to kill orphaned spawn (pid {pid}) after attach failure: {kill_err}"
                 );
             }
-            return Err(AppError::AttachFailed(
-                options.identifier.clone(),
-                message,
-            ));
+            let failure = version::mismatch_error(&error.message).unwrap_or_else(|| {
+                AppError::AttachFailed(options.identifier.clone(), error.message.clone())
+            });
+            return Err(failure.caused_by(error));
         }
 
         let session = frida_session_from_raw(raw_session);
+        let created_at = now_millis();
 
         let info = SessionInfo {
             id: new_session_id(),
@@ -569,7 +2175,9 @@ impl FridaActor {
             },
             mode: SessionMode::Spawn,
             arch: get_device_arch(device.as_ref())?,
-            created_at: now_millis(),
+            created_at,
+            expires_at: session_deadline(created_at, options.max_duration_ms, options.detach_at),
+            script_count: 0,
         };
 
         let mut bundle =
@@ -581,19 +2189,24 @@ impl FridaActor {
             device
                 .as_ref()
                 .resume(pid)
-                .map_err(|error| AppError::Internal(error.to_string()))?;
+                .map_err(|error| AppError::Internal(error.to_string()).caused_by(error))?;
         }
 
         self.sessions.insert(info.id.clone(), bundle);
+        self.tamper.track(&info.id, created_at);
+        self.seed_profile_variables(&info.id);
+        self.check_frida_version(&info.id);
         if let Err(error) = self.configure_session(
             &info.id,
             options.runtime.as_deref(),
             options.script_path.as_deref(),
+            options.script_parameters.as_ref(),
             options.enable_child_gating == Some(true),
         ) {
             self.discard_session(&info.id);
             return Err(error);
         }
+        self.persist_sessions();
         Ok(info)
     }
 
@@ -607,25 +2220,41 @@ impl FridaActor {
                 &options.target,
             )?;
         let session_options = SessionOptionsHandle::from_attach_options(&options)?;
+        let operation = options
+            .operation_id
+            .as_deref()
+            .map(|operation_id| self.operations.begin(operation_id))
+            .transpose()?;
         let mut error = std::ptr::null_mut();
         let raw_session = unsafe {
             frida_sys::frida_device_attach_sync(
                 frida_device_ptr(device.as_ref()),
                 pid,
                 session_options.as_mut_ptr(),
-                std::ptr::null_mut(),
+                operation
+                    .as_ref()
+                    .map_or(std::ptr::null_mut(), OperationGuard::as_ptr),
                 &mut error,
             )
         };
 
         if !error.is_null() {
-            return Err(AppError::AttachFailed(
-                process_name.clone(),
-                take_gerror_message(error),
-            ));
+            if let Some(operation) = operation.as_ref().filter(|op| op.is_cancelled()) {
+                let _ = take_gerror(error);
+                return Err(AppError::OperationCancelled(format!(
+                    "attach to {process_name} ({})",
+                    operation.operation_id()
+                )));
+            }
+            let error = take_gerror(error);
+            let failure = version::mismatch_error(&error.message).unwrap_or_else(|| {
+                AppError::AttachFailed(process_name.clone(), error.message.clone())
+            });
+            return Err(failure.caused_by(error));
         }
 
         let session = frida_session_from_raw(raw_session);
+        let created_at = now_millis();
 
         let info = SessionInfo {
             id: new_session_id(),
@@ -636,21 +2265,28 @@ impl FridaActor {
             status: SessionStatus::Active,
             mode: SessionMode::Attach,
             arch: get_device_arch(device.as_ref())?,
-            created_at: now_millis(),
+            created_at,
+            expires_at: session_deadline(created_at, options.max_duration_ms, options.detach_at),
+            script_count: 0,
         };
 
         let bundle =
             self.build_session_bundle(info.clone(), session, options.runtime.as_deref())?;
         self.sessions.insert(info.id.clone(), bundle);
+        self.tamper.track(&info.id, created_at);
+        self.seed_profile_variables(&info.id);
+        self.check_frida_version(&info.id);
         if let Err(error) = self.configure_session(
             &info.id,
             options.runtime.as_deref(),
             options.script_path.as_deref(),
+            options.script_parameters.as_ref(),
             options.enable_child_gating == Some(true),
         ) {
             self.discard_session(&info.id);
             return Err(error);
         }
+        self.persist_sessions();
         Ok(info)
     }
 
@@ -666,12 +2302,62 @@ impl FridaActor {
         match detach_result {
             Ok(()) => {
                 self.emit_detached(session_id, "application_requested");
+                self.persist_sessions();
                 Ok(())
             }
             Err(error) => Err(AppError::SessionExpired(error.to_string())),
         }
     }
 
+    fn cleanup_session(&mut self, session_id: &str) -> Result<SessionCleanupReport, AppError> {
+        let has_user_script = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(format!("Session not found: {session_id}")))?
+            .user_script
+            .is_some();
+
+        let mut steps = match self.rpc_call(session_id, "cleanupAgent", json!({})) {
+            Ok(response) => serde_json::from_value(response).unwrap_or_else(|error| {
+                vec![failed_step(
+                    "agent",
+                    format!("unexpected cleanupAgent response: {error}"),
+                )]
+            }),
+            Err(error) => vec![failed_step("agent", error.to_string())],
+        };
+
+        steps.push(match self.unload_user_script(session_id) {
+            Ok(()) => CleanupStep {
+                name: "userScript".to_string(),
+                removed: usize::from(has_user_script),
+                error: None,
+            },
+            Err(error) => failed_step("userScript", error.to_string()),
+        });
+
+        let detached = match self.detach(session_id) {
+            Ok(()) => {
+                steps.push(CleanupStep {
+                    name: "detach".to_string(),
+                    removed: 1,
+                    error: None,
+                });
+                true
+            }
+            Err(error) => {
+                steps.push(failed_step("detach", error.to_string()));
+                false
+            }
+        };
+
+        Ok(SessionCleanupReport {
+            session_id: session_id.to_string(),
+            steps,
+            detached,
+        })
+    }
+
     fn resume(&mut self, session_id: &str) -> Result<(), AppError> {
         let pause_mode = self
             .sessions
@@ -692,7 +2378,7 @@ impl FridaActor {
                 device
                     .as_ref()
                     .resume(pid)
-                    .map_err(|error| AppError::Internal(error.to_string()))?;
+                    .map_err(|error| AppError::Internal(error.to_string()).caused_by(error))?;
             }
             Some(PauseMode::SignalStop) => {
                 let (device_id, pid) = {
@@ -714,12 +2400,35 @@ impl FridaActor {
         Ok(())
     }
 
+    fn detach_all(&mut self) -> usize {
+        let session_ids = self.sessions.keys().cloned().collect::<Vec<_>>();
+
+        for session_id in &session_ids {
+            if let Some(mut bundle) = self.sessions.remove(session_id) {
+                // Unload first so hooks are reverted while the session can
+                // still reach the agent.
+                bundle.cleanup();
+                if let Err(error) = bundle.session.as_ref().detach() {
+                    log::warn!("Failed to detach session {session_id}: {error}");
+                }
+                self.emit_detached(session_id, "application_exit");
+            }
+        }
+
+        session_ids.len()
+    }
+
     fn list_sessions(&mut self) -> Result<Vec<SessionInfo>, AppError> {
-        Ok(self
+        let mut sessions = self
             .sessions
             .values()
-            .map(|session| session.info.clone())
-            .collect())
+            .map(|session| SessionInfo {
+                script_count: usize::from(session.user_script.is_some()),
+                ..session.info.clone()
+            })
+            .collect::<Vec<_>>();
+        sessions.sort_by_key(|session| session.created_at);
+        Ok(sessions)
     }
 
     fn rpc_call(
@@ -731,10 +2440,12 @@ impl FridaActor {
         match method {
             "loadScript" => {
                 self.load_user_script(session_id, params)?;
+                self.persist_sessions();
                 return Ok(json!({}));
             }
             "unloadScript" => {
                 self.unload_user_script(session_id)?;
+                self.persist_sessions();
                 return Ok(json!({}));
             }
             "pause" => {
@@ -753,11 +2464,141 @@ impl FridaActor {
             .core_script
             .exports
             .call(method, Some(Value::Array(vec![params])))
-            .map_err(|error| AppError::AgentRpcError(error.to_string()))?;
+            .map_err(|error| AppError::AgentRpcError(error.to_string()).caused_by(error))?;
+        // A reply proves the agent is alive even if its timer was starved
+        // while the call ran.
+        self.tamper.heartbeat(session_id, now_millis());
 
         unwrap_rpc_result(response.unwrap_or(Value::Null))
     }
 
+    fn rpc_read_bytes(
+        &mut self,
+        session_id: &str,
+        method: &str,
+        params: Value,
+    ) -> Result<(Value, Vec<u8>), AppError> {
+        let (params, binary_id) = with_binary_id(params)?;
+        let reply = self.rpc_call(session_id, method, params)?;
+        let data = self
+            .binary
+            .take(&binary_id)
+            .ok_or_else(|| AppError::AgentRpcError(format!("{method} sent no data")))?;
+        Ok((reply, data))
+    }
+
+    fn rpc_write_bytes(
+        &mut self,
+        session_id: &str,
+        method: &str,
+        params: Value,
+        data: &[u8],
+    ) -> Result<Value, AppError> {
+        let (params, binary_id) = with_binary_id(params)?;
+        self.post_script_message(
+            session_id,
+            "agent",
+            &json!({ "type": "carf:binary", "id": binary_id }),
+            Some(data),
+        )?;
+        self.rpc_call(session_id, method, params)
+    }
+
+    fn compile_script(&mut self, entrypoint: &str, project_root: &str) -> Result<String, AppError> {
+        let entrypoint_c = CString::new(entrypoint)
+            .map_err(|_| AppError::ScriptLoadFailed("entrypoint path contains NUL".to_string()))?;
+        let project_root_c = CString::new(project_root)
+            .map_err(|_| AppError::ScriptLoadFailed("project root contains NUL".to_string()))?;
+
+        let mut error = std::ptr::null_mut();
+        let bundle = unsafe {
+            let compiler = frida_sys::frida_compiler_new(frida_device_manager_ptr(
+                self.device_manager.as_ref(),
+            ));
+            let options = frida_sys::frida_build_options_new();
+            frida_sys::frida_compiler_options_set_project_root(
+                options.cast(),
+                project_root_c.as_ptr(),
+            );
+
+            let bundle = frida_sys::frida_compiler_build_sync(
+                compiler,
+                entrypoint_c.as_ptr(),
+                options,
+                std::ptr::null_mut(),
+                &mut error,
+            );
+
+            frida_sys::frida_unref(options.cast());
+            frida_sys::frida_unref(compiler.cast());
+            bundle
+        };
+
+        if !error.is_null() || bundle.is_null() {
+            let error = take_gerror(error);
+            return Err(AppError::ScriptLoadFailed(format!(
+                "failed to compile {entrypoint}: {}",
+                error.message
+            ))
+            .caused_by(error));
+        }
+
+        let source = unsafe { CStr::from_ptr(bundle) }
+            .to_string_lossy()
+            .into_owned();
+        unsafe {
+            frida_sys::g_free(bundle.cast());
+        }
+        Ok(source)
+    }
+
+    fn stalker_trace_summary(&mut self, session_id: &str) -> Result<TraceSummary, AppError> {
+        self.ensure_session(session_id)?;
+        self.drain_script_events();
+        Ok(self.traces.summary(session_id))
+    }
+
+    fn export_stalker_trace(
+        &mut self,
+        session_id: &str,
+        path: &str,
+    ) -> Result<TraceSummary, AppError> {
+        self.ensure_session(session_id)?;
+        // Pick up batches the agent flushed while stopping so the export is
+        // complete even when it immediately follows a stop.
+        self.drain_script_events();
+        self.traces
+            .export(session_id, path, self.markers.list(session_id))
+    }
+
+    fn add_session_marker(
+        &mut self,
+        session_id: &str,
+        label: String,
+    ) -> Result<SessionMarker, AppError> {
+        self.ensure_session(session_id)?;
+        // Events the agent already sent belong before the marker.
+        self.drain_script_events();
+        let trace_position = self.traces.position(session_id);
+        let marker = self
+            .markers
+            .add(session_id, label, trace_position, now_millis());
+        if let Ok(payload) = serde_json::to_value(&marker) {
+            self.events.emit("carf://session/marker", payload);
+        }
+        Ok(marker)
+    }
+
+    fn ensure_session(&self, session_id: &str) -> Result<(), AppError> {
+        if self.sessions.contains_key(session_id) {
+            Ok(())
+        } else {
+            Err(AppError::SessionNotFound(format!(
+                "Session not found: {session_id}"
+            )))
+        }
+    }
+
     fn pause_session(&mut self, session_id: &str) -> Result<(), AppError> {
         let bundle = self
             .sessions
@@ -776,6 +2617,12 @@ impl FridaActor {
         session: Session<'static>,
         runtime: Option<&str>,
     ) -> Result<SessionBundle, AppError> {
+        connect_detached_signal(
+            frida_session_ptr(&session),
+            &info.id,
+            &info.device_id,
+            self.crash_tx.clone(),
+        );
         let session = OwnedSession::new(session);
         let core_script = self.load_core_script(&session, &info.id, runtime)?;
 
@@ -784,8 +2631,11 @@ impl FridaActor {
             session,
             core_script,
             user_script: None,
+            user_script_origin: None,
+            repl_script: None,
             spawned_pid: None,
             pause_mode: None,
+            expiry_warned: false,
         })
     }
 
@@ -802,17 +2652,20 @@ impl FridaActor {
         let mut script = session
             .as_ref()
             .create_script(source, &mut options)
-            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()))?;
+            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
 
         script
             .handle_message(HostScriptHandler::new(
                 session_id.to_string(),
+                "agent",
                 self.script_events_tx.clone(),
+                self.message_log.clone(),
+                Some(self.binary.clone()),
             ))
-            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()))?;
+            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
         script
             .load()
-            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()))?;
+            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
         Ok(script)
     }
 
@@ -829,6 +2682,8 @@ impl FridaActor {
             ));
         }
         let runtime = params.get("runtime").and_then(Value::as_str);
+        let parameters = params.get("parameters").filter(|value| !value.is_null());
+        let origin = SavedScript::from_load_params(&params);
 
         self.unload_user_script(session_id)?;
 
@@ -844,18 +2699,41 @@ impl FridaActor {
             .session
             .as_ref()
             .create_script(&code, &mut options)
-            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()))?;
+            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
         script
             .handle_message(HostScriptHandler::new(
                 session_id.to_string(),
+                "user",
                 self.script_events_tx.clone(),
+                self.message_log.clone(),
+                None,
             ))
-            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()))?;
+            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
         script
             .load()
-            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()))?;
+            .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
+
+        let profile = variables::profile_for(&bundle.info);
+        match variables::list_variables(&profile) {
+            Ok(values) => {
+                let message = json!({
+                    "type": "vars",
+                    "payload": { "profile": profile, "variables": values },
+                });
+                let _ = script.post(message.to_string(), None);
+            }
+            Err(error) => log::warn!("Failed to read variables for {profile}: {error}"),
+        }
+
+        if let Some(parameters) = parameters {
+            if let Err(error) = deliver_script_parameters(&mut script, parameters) {
+                let _ = script.unload();
+                return Err(error);
+            }
+        }
 
         bundle.user_script = Some(script);
+        bundle.user_script_origin = Some(origin);
         Ok(())
     }
 
@@ -864,6 +2742,7 @@ impl FridaActor {
         session_id: &str,
         runtime: Option<&str>,
         script_path: Option<&str>,
+        script_parameters: Option<&Value>,
         enable_child_gating: bool,
     ) -> Result<(), AppError> {
         if enable_child_gating {
@@ -872,7 +2751,7 @@ impl FridaActor {
 
         if let Some(path) = script_path {
             if !path.trim().is_empty() {
-                self.load_user_script_from_path(session_id, path, runtime)?;
+                self.load_user_script_from_path(session_id, path, runtime, script_parameters)?;
             }
         }
 
@@ -884,16 +2763,19 @@ impl FridaActor {
         session_id: &str,
         path: &str,
         runtime: Option<&str>,
+        parameters: Option<&Value>,
     ) -> Result<(), AppError> {
         let code = std::fs::read_to_string(path).map_err(|error| {
-            AppError::ScriptLoadFailed(format!("failed to read {path}: {error}"))
+            AppError::ScriptLoadFailed(format!("failed to read {path}: {error}")).caused_by(error)
         })?;
 
         self.load_user_script(
             session_id,
             json!({
                 "code": code,
+                "path": path,
                 "runtime": runtime,
+                "parameters": parameters,
             }),
         )
     }
@@ -915,20 +2797,92 @@ impl FridaActor {
         if error.is_null() {
             Ok(())
         } else {
-            Err(AppError::AttachFailed(
-                bundle.info.process_name.clone(),
-                take_gerror_message(error),
-            ))
+            let error = take_gerror(error);
+            Err(
+                AppError::AttachFailed(bundle.info.process_name.clone(), error.message.clone())
+                    .caused_by(error),
+            )
         }
     }
 
     fn discard_session(&mut self, session_id: &str) {
+        self.tamper.forget(session_id);
         if let Some(mut bundle) = self.sessions.remove(session_id) {
             let _ = bundle.session.as_ref().detach();
             bundle.cleanup();
         }
     }
 
+    fn post_script_message(
+        &self,
+        session_id: &str,
+        script: &str,
+        message: &Value,
+        data: Option<&[u8]>,
+    ) -> Result<(), AppError> {
+        let bundle = self
+            .sessions
+            .get(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(format!("Session not found: {session_id}")))?;
+        let target = match script {
+            "agent" => &bundle.core_script,
+            "user" => bundle.user_script.as_ref().ok_or_else(|| {
+                AppError::InvalidArgument(format!("No user script loaded in {session_id}"))
+            })?,
+            other => {
+                return Err(AppError::InvalidArgument(format!(
+                    "Unknown script {other}; expected agent or user"
+                )))
+            }
+        };
+        target
+            .post(message.to_string(), data)
+            .map_err(|error| AppError::AgentRpcError(error.to_string()).caused_by(error))
+    }
+
+    fn eval(&mut self, session_id: &str, code: &str) -> Result<EvalResult, AppError> {
+        if code.trim().is_empty() {
+            return Err(AppError::InvalidArgument("Nothing to evaluate".to_string()));
+        }
+        let bundle = self
+            .sessions
+            .get_mut(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(format!("Session not found: {session_id}")))?;
+
+        if bundle.repl_script.is_none() {
+            let mut options = ScriptOption::new().set_name("CARF REPL");
+            let mut script = bundle
+                .session
+                .as_ref()
+                .create_script(REPL_SOURCE, &mut options)
+                .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
+            script
+                .handle_message(HostScriptHandler::new(
+                    session_id.to_string(),
+                    "repl",
+                    self.script_events_tx.clone(),
+                    self.message_log.clone(),
+                    None,
+                ))
+                .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
+            script
+                .load()
+                .map_err(|error| AppError::ScriptLoadFailed(error.to_string()).caused_by(error))?;
+            bundle.repl_script = Some(script);
+        }
+        let Some(script) = bundle.repl_script.as_mut() else {
+            return Err(AppError::Internal("REPL script missing".to_string()));
+        };
+
+        let response = script
+            .exports
+            .call("eval", Some(json!([code])))
+            .map_err(|error| AppError::AgentRpcError(error.to_string()).caused_by(error))?;
+        serde_json::from_value(response.unwrap_or(Value::Null)).map_err(|error| {
+            AppError::AgentRpcError(format!("Malformed REPL result: {error}")).caused_by(error)
+        })
+    }
+
     fn unload_user_script(&mut self, session_id: &str) -> Result<(), AppError> {
         let bundle = self
             .sessions
@@ -938,10 +2892,104 @@ impl FridaActor {
         if let Some(script) = bundle.user_script.take() {
             let _ = script.unload();
         }
+        bundle.user_script_origin = None;
 
         Ok(())
     }
 
+    /// Compares the Frida in the session's target with ours, once per
+    /// device connection, and warns through `carf://device/version_mismatch` when the
+    /// major versions differ.
+    fn check_frida_version(&mut self, session_id: &str) {
+        let Some(device_id) = self
+            .sessions
+            .get(session_id)
+            .map(|bundle| bundle.info.device_id.clone())
+        else {
+            return;
+        };
+        if device_id == "local" || self.version_checked.contains(&device_id) {
+            return;
+        }
+        let remote_version = match self.rpc_call(session_id, "getProcessInfo", Value::Null) {
+            Ok(info) => info
+                .get("fridaVersion")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            Err(error) => {
+                log::debug!("Could not read the Frida version of {device_id}: {error}");
+                return;
+            }
+        };
+        self.version_checked.insert(device_id.clone());
+        let Some(mismatch) = remote_version.and_then(|remote| version::check(&device_id, &remote))
+        else {
+            return;
+        };
+        log::warn!("{}", mismatch.message);
+        if let Ok(payload) = serde_json::to_value(&mismatch) {
+            self.events.emit("carf://device/version_mismatch", payload);
+        }
+    }
+
+    /// Hands the session's profile variables to the built-in agent so
+    /// `getVariable` works without a round trip to the host.
+    fn seed_profile_variables(&mut self, session_id: &str) {
+        let Some(bundle) = self.sessions.get_mut(session_id) else {
+            return;
+        };
+        let profile = variables::profile_for(&bundle.info);
+        let result = variables::list_variables(&profile).and_then(|values| {
+            bundle
+                .core_script
+                .exports
+                .call(
+                    "setProfileVariables",
+                    Some(json!([{ "profile": profile, "variables": values }])),
+                )
+                .map_err(|error| AppError::AgentRpcError(error.to_string()).caused_by(error))
+        });
+        if let Err(error) = result {
+            log::warn!("Failed to seed variables for session {session_id}: {error}");
+        }
+    }
+
+    fn persist_agent_variable(&self, payload: &Value) {
+        let Some(bundle) = payload
+            .get("sessionId")
+            .and_then(Value::as_str)
+            .and_then(|session_id| self.sessions.get(session_id))
+        else {
+            return;
+        };
+        let profile = variables::profile_for(&bundle.info);
+        if let Err(error) = variables::apply_agent_event(&profile, payload) {
+            log::warn!("Failed to persist variable for {profile}: {error}");
+        }
+    }
+
+    /// Records the user's attached targets for `restore_last_session`. Only
+    /// called on user-initiated changes, so sessions torn down by the app
+    /// exiting or the target dying stay in the snapshot.
+    fn persist_sessions(&self) {
+        let sessions = self
+            .sessions
+            .values()
+            .map(|bundle| SavedSession {
+                device_id: bundle.info.device_id.clone(),
+                pid: bundle.info.pid,
+                process_name: bundle.info.process_name.clone(),
+                identifier: bundle.info.identifier.clone(),
+                mode: bundle.info.mode.clone(),
+                script: bundle.user_script_origin.clone(),
+            })
+            .collect();
+
+        if let Err(error) = session_restore::save_snapshot(sessions) {
+            log::warn!("Failed to save session snapshot: {error}");
+        }
+    }
+
     fn get_core_agent_source(&mut self) -> Result<&str, AppError> {
         // In debug builds, prefer the on-disk copy so `bun run compile:agent`
         // changes take effect without a Rust rebuild. In release builds we use
@@ -1004,7 +3052,56 @@ impl FridaActor {
         }
     }
 
-    fn emit_detached(&self, session_id: &str, reason: &str) {
+    /// Detaches time-boxed sessions whose deadline has passed, warning the
+    /// frontend once when a session enters its final minute.
+    fn expire_sessions(&mut self) {
+        let now = now_millis();
+        let mut expired_ids = Vec::new();
+
+        for (session_id, bundle) in self.sessions.iter_mut() {
+            let Some(expires_at) = bundle.info.expires_at else {
+                continue;
+            };
+
+            if now >= expires_at {
+                expired_ids.push(session_id.clone());
+            } else if !bundle.expiry_warned && expires_at - now <= SESSION_EXPIRY_WARNING_MS {
+                bundle.expiry_warned = true;
+                self.events.emit(
+                    "carf://session/expiring",
+                    json!({
+                        "sessionId": session_id,
+                        "expiresAt": expires_at,
+                        "remainingMs": expires_at - now,
+                    }),
+                );
+            }
+        }
+
+        for session_id in expired_ids {
+            if let Some(mut bundle) = self.sessions.remove(&session_id) {
+                log::info!(
+                    "Session {session_id} reached its time limit; detaching from {}",
+                    bundle.info.process_name
+                );
+                let _ = bundle.session.as_ref().detach();
+                bundle.cleanup();
+                self.emit_detached(&session_id, "session_expired");
+            }
+        }
+    }
+
+    /// Reports a session as gone. Its pending jobs can no longer finish, so
+    /// dropping them here wakes their callers with an error.
+    fn emit_detached(&mut self, session_id: &str, reason: &str) {
+        self.jobs.retain(|_, job| job.session_id != session_id);
+        if reason == "process_terminated" {
+            if let Some(diagnostic) = self.tamper.detached(session_id, now_millis()) {
+                self.emit_diagnostic(&diagnostic);
+            }
+        } else {
+            self.tamper.forget(session_id);
+        }
         self.events.emit(
             "carf://session/detached",
             json!({
@@ -1020,6 +3117,9 @@ impl SessionBundle {
         if let Some(script) = self.user_script.take() {
             let _ = script.unload();
         }
+        if let Some(script) = self.repl_script.take() {
+            let _ = script.unload();
+        }
 
         let _ = self.core_script.unload();
     }
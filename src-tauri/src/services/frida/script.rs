@@ -1,33 +1,73 @@
 use std::sync::mpsc;
 
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use frida::{Message, MessageLogLevel, ScriptHandler};
 use serde_json::{json, Value};
 
+use crate::services::storage::now_millis;
 use crate::state::BridgeEvent;
 
-use super::util::{now_millis, queue_event, stringify_value};
+use super::binary_inbox::BinaryInbox;
+use super::message_log::MessageRecorder;
+use super::util::{queue_event, stringify_value};
 
 pub(super) struct HostScriptHandler {
     session_id: String,
+    /// Which of the session's scripts this handler serves (`agent`, `user` or `repl`).
+    script: &'static str,
     event_sender: mpsc::Sender<BridgeEvent>,
+    recorder: MessageRecorder,
+    /// Set for the core agent only, so other scripts cannot fill it.
+    binary: Option<BinaryInbox>,
 }
 
 impl HostScriptHandler {
-    pub(super) fn new(session_id: String, event_sender: mpsc::Sender<BridgeEvent>) -> Self {
+    pub(super) fn new(
+        session_id: String,
+        script: &'static str,
+        event_sender: mpsc::Sender<BridgeEvent>,
+        recorder: MessageRecorder,
+        binary: Option<BinaryInbox>,
+    ) -> Self {
         Self {
             session_id,
+            script,
             event_sender,
+            recorder,
+            binary,
         }
     }
 
-    fn handle_raw_message(&self, message: Value) {
+    fn handle_raw_message(&self, message: Value, data: Option<&[u8]>) {
         let Some(kind) = message.get("type").and_then(Value::as_str) else {
             return;
         };
 
         match kind {
             "send" => {
-                self.handle_send_payload(message.get("payload").cloned().unwrap_or(Value::Null))
+                let payload = message.get("payload").cloned().unwrap_or(Value::Null);
+                if let Some(inbox) = &self.binary {
+                    if payload.get("type").and_then(Value::as_str) == Some("carf:binary") {
+                        // Frida delivers an empty buffer as no data at all.
+                        if let Some(id) = payload.get("id").and_then(Value::as_str) {
+                            inbox.put(id.to_string(), data.map(<[u8]>::to_vec).unwrap_or_default());
+                        }
+                        return;
+                    }
+                }
+                if let Some(data) = data {
+                    self.queue_session_event(
+                        "carf://script/binary",
+                        json!({
+                            "script": self.script,
+                            "payload": payload,
+                            "data": STANDARD.encode(data),
+                            "size": data.len(),
+                        }),
+                    );
+                }
+                self.handle_send_payload(payload, data.is_some())
             }
             "log" => {
                 let content = message
@@ -48,6 +88,17 @@ impl HostScriptHandler {
                     .and_then(Value::as_str)
                     .unwrap_or("Script error")
                     .to_string();
+                self.queue_session_event(
+                    "carf://script/error",
+                    json!({
+                        "script": self.script,
+                        "message": description,
+                        "stack": message.get("stack").cloned().unwrap_or(Value::Null),
+                        "fileName": message.get("fileName").cloned().unwrap_or(Value::Null),
+                        "lineNumber": message.get("lineNumber").cloned().unwrap_or(Value::Null),
+                        "columnNumber": message.get("columnNumber").cloned().unwrap_or(Value::Null),
+                    }),
+                );
                 self.queue_session_console(
                     "error",
                     "agent",
@@ -59,7 +110,9 @@ impl HostScriptHandler {
         }
     }
 
-    fn handle_send_payload(&self, payload: Value) {
+    /// Routes a `send()` payload to its event. With `has_binary`, the
+    /// payload was already forwarded whole as `carf://script/binary`.
+    fn handle_send_payload(&self, payload: Value, has_binary: bool) {
         let Value::Object(payload) = payload else {
             self.queue_session_console(
                 "info",
@@ -114,6 +167,29 @@ impl HostScriptHandler {
             "stalker/event" => {
                 self.queue_session_event("carf://stalker/event", data);
             }
+            "vars/set" => {
+                if matches!(data, Value::Object(_)) {
+                    self.queue_session_event("carf://vars/set", data);
+                }
+            }
+            "job/result" => {
+                if matches!(data, Value::Object(_)) {
+                    self.queue_session_event("carf://job/result", data);
+                }
+            }
+            "agent/heartbeat" => {
+                self.queue_session_event("carf://agent/heartbeat", data);
+            }
+            "tamper/evidence" => {
+                if matches!(data, Value::Object(_)) {
+                    self.queue_session_event("carf://tamper/evidence", data);
+                }
+            }
+            "memory/layout_changed" => {
+                if matches!(data, Value::Object(_)) {
+                    self.queue_session_event("carf://memory/layout_changed", data);
+                }
+            }
             "memory/access" => {
                 if matches!(data, Value::Object(_)) {
                     self.queue_session_event("carf://memory/access", data);
@@ -124,6 +200,11 @@ impl HostScriptHandler {
                     self.queue_session_event("carf://scan/progress", data);
                 }
             }
+            "process/metrics" => {
+                if matches!(data, Value::Object(_)) {
+                    self.queue_session_event("carf://process/metrics", data);
+                }
+            }
             "scan/result" => {
                 self.queue_session_event("carf://scan/result", data);
             }
@@ -152,6 +233,7 @@ impl HostScriptHandler {
                     }),
                 );
             }
+            _ if has_binary => {}
             _ => {
                 self.queue_session_console(
                     "info",
@@ -198,7 +280,30 @@ impl HostScriptHandler {
 }
 
 impl ScriptHandler for HostScriptHandler {
-    fn on_message(&mut self, message: Message, _data: Option<Vec<u8>>) {
+    fn on_message(&mut self, message: Message, data: Option<Vec<u8>>) {
+        self.recorder
+            .record(&self.session_id, self.script, || match &message {
+                Message::Log(log) => json!({
+                    "type": "log",
+                    "level": format!("{:?}", log.level).to_lowercase(),
+                    "payload": log.payload,
+                }),
+                Message::Error(error) => json!({
+                    "type": "error",
+                    "description": error.description,
+                    "stack": error.stack,
+                    "fileName": error.file_name,
+                    "lineNumber": error.line_number,
+                    "columnNumber": error.column_number,
+                }),
+                Message::Other(value) => value
+                    .get("data")
+                    .and_then(Value::as_str)
+                    .and_then(|data| serde_json::from_str::<Value>(data).ok())
+                    .unwrap_or_else(|| value.clone()),
+                Message::Send(_) => json!({ "type": "send" }),
+            });
+
         match message {
             Message::Log(log) => {
                 let level = match log.level {
@@ -209,20 +314,19 @@ impl ScriptHandler for HostScriptHandler {
                 };
                 self.queue_session_console(level, "agent", log.payload, None);
             }
-            Message::Error(error) => {
-                self.queue_session_console(
-                    "error",
-                    "agent",
-                    error.description.clone(),
-                    Some(json!({
-                        "description": error.description,
-                        "stack": error.stack,
-                        "fileName": error.file_name,
-                        "lineNumber": error.line_number,
-                        "columnNumber": error.column_number,
-                    })),
-                );
-            }
+            // Same shape as a raw `error` message, so both emit
+            // `carf://script/error` as well as the console line.
+            Message::Error(error) => self.handle_raw_message(
+                json!({
+                    "type": "error",
+                    "description": error.description,
+                    "stack": error.stack,
+                    "fileName": error.file_name,
+                    "lineNumber": error.line_number,
+                    "columnNumber": error.column_number,
+                }),
+                None,
+            ),
             Message::Other(value) => {
                 let raw = value
                     .get("data")
@@ -230,7 +334,7 @@ impl ScriptHandler for HostScriptHandler {
                     .and_then(|data| serde_json::from_str::<Value>(data).ok());
 
                 if let Some(raw) = raw {
-                    self.handle_raw_message(raw);
+                    self.handle_raw_message(raw, data.as_deref());
                 } else {
                     self.queue_session_console(
                         "warn",
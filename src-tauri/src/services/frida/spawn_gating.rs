@@ -0,0 +1,181 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::CStr;
+use std::os::raw::c_void;
+use std::sync::mpsc;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::services::storage::now_millis;
+
+/// How a device's gated spawns are handled.
+///
+/// Mirrors frontend `SpawnGatingOptions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SpawnGatingOptions {
+    /// Attach to every gated spawn, load `script_path` and resume it
+    /// instead of leaving it pending.
+    pub auto_attach: bool,
+    /// Only auto-attach spawns whose identifier (or path) is listed; empty
+    /// means all.
+    pub identifiers: Vec<String>,
+    pub script_path: Option<String>,
+    pub script_parameters: Option<Value>,
+    pub runtime: Option<String>,
+}
+
+impl SpawnGatingOptions {
+    pub(super) fn wants(&self, spawn: &PendingSpawn) -> bool {
+        self.auto_attach
+            && (self.identifiers.is_empty()
+                || spawn
+                    .identifier
+                    .as_ref()
+                    .is_some_and(|identifier| self.identifiers.contains(identifier)))
+    }
+}
+
+/// A process suspended by spawn gating, waiting for `resume_spawn` or
+/// `deny_spawn`.
+///
+/// Mirrors frontend `PendingSpawn`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingSpawn {
+    pub device_id: String,
+    pub pid: u32,
+    pub identifier: Option<String>,
+    pub timestamp: u64,
+}
+
+/// Devices with spawn gating enabled and the options they were enabled
+/// with.
+#[derive(Default)]
+pub(super) struct SpawnGatingStore {
+    gated: HashMap<String, SpawnGatingOptions>,
+    /// Devices whose `spawn-added` signal is already connected.
+    tapped_devices: HashSet<String>,
+}
+
+impl SpawnGatingStore {
+    pub(super) fn is_tapped(&self, device_id: &str) -> bool {
+        self.tapped_devices.contains(device_id)
+    }
+
+    pub(super) fn mark_tapped(&mut self, device_id: &str) {
+        self.tapped_devices.insert(device_id.to_string());
+    }
+
+    pub(super) fn enable(&mut self, device_id: &str, options: SpawnGatingOptions) {
+        self.gated.insert(device_id.to_string(), options);
+    }
+
+    pub(super) fn disable(&mut self, device_id: &str) -> bool {
+        self.gated.remove(device_id).is_some()
+    }
+
+    /// Forgets every tap and gated device, returning the gated devices and
+    /// their options. Used when the device objects are replaced, since the
+    /// new ones have neither the signal nor gating.
+    pub(super) fn reset(&mut self) -> HashMap<String, SpawnGatingOptions> {
+        self.tapped_devices.clear();
+        std::mem::take(&mut self.gated)
+    }
+
+    /// `None` when gating is not enabled on the device (any more).
+    pub(super) fn options(&self, device_id: &str) -> Option<&SpawnGatingOptions> {
+        self.gated.get(device_id)
+    }
+}
+
+/// Identifies the device a `spawn-added` signal belongs to.
+struct SpawnTap {
+    device_id: String,
+    sender: mpsc::Sender<PendingSpawn>,
+}
+
+/// Connects the device's `spawn-added` signal to `sender`. Like the
+/// `output` signal, it fires on the main-context pump thread.
+pub(super) fn connect_spawn_added_signal(
+    device: *mut frida_sys::FridaDevice,
+    device_id: &str,
+    sender: mpsc::Sender<PendingSpawn>,
+) {
+    let tap = SpawnTap {
+        device_id: device_id.to_string(),
+        sender,
+    };
+    let user_data = Box::into_raw(Box::new(tap)) as *mut c_void;
+
+    unsafe {
+        let callback = Some(std::mem::transmute::<*mut c_void, unsafe extern "C" fn()>(
+            on_spawn_added as *mut c_void,
+        ));
+
+        frida_sys::g_signal_connect_data(
+            device as _,
+            c"spawn-added".as_ptr(),
+            callback,
+            user_data,
+            Some(free_spawn_tap),
+            0,
+        );
+    }
+}
+
+/// Reads a `FridaSpawnList` returned by `enumerate_pending_spawn` and
+/// releases it.
+pub(super) unsafe fn take_spawn_list(
+    list: *mut frida_sys::FridaSpawnList,
+    device_id: &str,
+) -> Vec<PendingSpawn> {
+    if list.is_null() {
+        return Vec::new();
+    }
+    let timestamp = now_millis();
+    let spawns = (0..frida_sys::frida_spawn_list_size(list))
+        .map(|index| {
+            let spawn = frida_sys::frida_spawn_list_get(list, index);
+            let pending = read_spawn(spawn, device_id, timestamp);
+            frida_sys::frida_unref(spawn.cast());
+            pending
+        })
+        .collect();
+    frida_sys::frida_unref(list.cast());
+    spawns
+}
+
+unsafe fn read_spawn(
+    spawn: *mut frida_sys::FridaSpawn,
+    device_id: &str,
+    timestamp: u64,
+) -> PendingSpawn {
+    let identifier = frida_sys::frida_spawn_get_identifier(spawn);
+    PendingSpawn {
+        device_id: device_id.to_string(),
+        pid: frida_sys::frida_spawn_get_pid(spawn),
+        identifier: (!identifier.is_null())
+            .then(|| CStr::from_ptr(identifier).to_string_lossy().into_owned()),
+        timestamp,
+    }
+}
+
+unsafe extern "C" fn on_spawn_added(
+    _device: *mut frida_sys::FridaDevice,
+    spawn: *mut frida_sys::FridaSpawn,
+    user_data: *mut c_void,
+) {
+    if spawn.is_null() || user_data.is_null() {
+        return;
+    }
+
+    let tap = &*(user_data as *const SpawnTap);
+    let _ = tap
+        .sender
+        .send(read_spawn(spawn, &tap.device_id, now_millis()));
+}
+
+unsafe extern "C" fn free_spawn_tap(user_data: *mut c_void, _closure: *mut frida_sys::GClosure) {
+    drop(Box::from_raw(user_data as *mut SpawnTap));
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+const ANTI_TAMPER_SUSPECTED: &str = "TARGET_ANTI_TAMPER_SUSPECTED";
+
+/// A session that detaches this soon after attaching was likely killed or
+/// unloaded by the target rather than by the user.
+const EARLY_DETACH_MS: u64 = 5_000;
+/// The agent heartbeats every 2s; this many missed beats means its thread
+/// stopped running.
+const HEARTBEAT_STALL_MS: u64 = 10_000;
+
+/// One observation behind an anti-tamper diagnostic.
+///
+/// Mirrors frontend `TamperEvidence`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TamperEvidence {
+    /// `hook_reverted`, `agent_unresponsive` or `early_detach`.
+    pub kind: String,
+    pub detail: Value,
+    pub timestamp: u64,
+}
+
+/// Emitted as `carf://session/diagnostic` each time new evidence arrives.
+///
+/// Mirrors frontend `SessionDiagnostic`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionDiagnostic {
+    pub session_id: String,
+    pub code: String,
+    pub message: String,
+    pub evidence: Vec<TamperEvidence>,
+}
+
+struct SessionWatch {
+    attached_at: u64,
+    last_heartbeat: Option<u64>,
+    stall_reported: bool,
+    evidence: Vec<TamperEvidence>,
+}
+
+/// Collects signs that the target is fighting the agent, per session.
+#[derive(Default)]
+pub(super) struct TamperMonitor {
+    sessions: HashMap<String, SessionWatch>,
+}
+
+impl TamperMonitor {
+    pub(super) fn track(&mut self, session_id: &str, attached_at: u64) {
+        self.sessions.insert(
+            session_id.to_string(),
+            SessionWatch {
+                attached_at,
+                last_heartbeat: None,
+                stall_reported: false,
+                evidence: Vec::new(),
+            },
+        );
+    }
+
+    pub(super) fn forget(&mut self, session_id: &str) {
+        self.sessions.remove(session_id);
+    }
+
+    pub(super) fn heartbeat(&mut self, session_id: &str, now: u64) {
+        if let Some(watch) = self.sessions.get_mut(session_id) {
+            watch.last_heartbeat = Some(now);
+            watch.stall_reported = false;
+        }
+    }
+
+    /// Evidence reported by the agent itself, e.g. a reverted hook.
+    pub(super) fn record(
+        &mut self,
+        session_id: &str,
+        kind: &str,
+        detail: Value,
+        now: u64,
+    ) -> Option<SessionDiagnostic> {
+        let watch = self.sessions.get_mut(session_id)?;
+        watch.evidence.push(TamperEvidence {
+            kind: kind.to_string(),
+            detail,
+            timestamp: now,
+        });
+        Some(diagnostic(session_id, &watch.evidence))
+    }
+
+    /// Sessions whose agent stopped heartbeating. Agents that never sent a
+    /// heartbeat are skipped so older agents don't trip this, as are
+    /// sessions for which `busy` returns true.
+    pub(super) fn check_stalls(
+        &mut self,
+        now: u64,
+        busy: impl Fn(&str) -> bool,
+    ) -> Vec<SessionDiagnostic> {
+        let mut diagnostics = Vec::new();
+        for (session_id, watch) in self.sessions.iter_mut() {
+            let Some(last_heartbeat) = watch.last_heartbeat else {
+                continue;
+            };
+            if busy(session_id) {
+                watch.last_heartbeat = Some(now);
+                continue;
+            }
+            if watch.stall_reported || now.saturating_sub(last_heartbeat) < HEARTBEAT_STALL_MS {
+                continue;
+            }
+
+            watch.stall_reported = true;
+            watch.evidence.push(TamperEvidence {
+                kind: "agent_unresponsive".to_string(),
+                detail: json!({
+                    "lastHeartbeat": last_heartbeat,
+                    "silentMs": now - last_heartbeat,
+                    "sinceAttachMs": last_heartbeat.saturating_sub(watch.attached_at),
+                }),
+                timestamp: now,
+            });
+            diagnostics.push(diagnostic(session_id, &watch.evidence));
+        }
+        diagnostics
+    }
+
+    /// Called when a session detached on its own. Returns a diagnostic when
+    /// that happened suspiciously soon after attaching; the session is
+    /// forgotten either way.
+    pub(super) fn detached(&mut self, session_id: &str, now: u64) -> Option<SessionDiagnostic> {
+        let mut watch = self.sessions.remove(session_id)?;
+        let lifetime_ms = now.saturating_sub(watch.attached_at);
+        if lifetime_ms >= EARLY_DETACH_MS {
+            return None;
+        }
+
+        watch.evidence.push(TamperEvidence {
+            kind: "early_detach".to_string(),
+            detail: json!({ "lifetimeMs": lifetime_ms }),
+            timestamp: now,
+        });
+        Some(diagnostic(session_id, &watch.evidence))
+    }
+
+    pub(super) fn diagnostic(&self, session_id: &str) -> Option<SessionDiagnostic> {
+        self.sessions
+            .get(session_id)
+            .filter(|watch| !watch.evidence.is_empty())
+            .map(|watch| diagnostic(session_id, &watch.evidence))
+    }
+}
+
+fn diagnostic(session_id: &str, evidence: &[TamperEvidence]) -> SessionDiagnostic {
+    let latest = evidence.last().map(|item| item.kind.as_str());
+    let message = match latest {
+        Some("hook_reverted") => {
+            "A hook was overwritten in the target; it may be restoring patched code"
+        }
+        Some("agent_unresponsive") => {
+            "The agent stopped responding; the target may have killed its thread"
+        }
+        Some("early_detach") => {
+            "The session ended right after attaching; the target may be detecting Frida"
+        }
+        _ => "The target may be interfering with instrumentation",
+    };
+
+    SessionDiagnostic {
+        session_id: session_id.to_string(),
+        code: ANTI_TAMPER_SUSPECTED.to_string(),
+        message: message.to_string(),
+        evidence: evidence.to_vec(),
+    }
+}
@@ -14,6 +14,8 @@ pub struct DeviceInfo {
     pub os: Option<OsInfo>,
     pub arch: Option<String>,
     pub status: DeviceStatus,
+    /// Lockdown pairing with this host; only set for iOS devices over USB.
+    pub pairing: Option<DevicePairing>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +27,9 @@ pub enum DeviceType {
     Usb,
     #[serde(rename = "remote")]
     Remote,
+    /// A frida-portal control endpoint; its processes are the joined nodes.
+    #[serde(rename = "portal")]
+    Portal,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +43,56 @@ pub enum DeviceStatus {
     Pairing,
 }
 
+/// Credentials for a frida-server or frida-portal started with `--token`
+/// and/or `--certificate`.
+///
+/// Mirrors frontend `RemoteDeviceOptions`
+#[derive(Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RemoteDeviceOptions {
+    pub token: Option<String>,
+    /// PEM file with the server's certificate; connects over TLS when set.
+    pub certificate: Option<String>,
+}
+
+impl RemoteDeviceOptions {
+    pub fn is_empty(&self) -> bool {
+        self.token.is_none() && self.certificate.is_none()
+    }
+}
+
+impl std::fmt::Debug for RemoteDeviceOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RemoteDeviceOptions")
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .field("certificate", &self.certificate)
+            .finish()
+    }
+}
+
+/// Mirrors frontend `DevicePairing`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevicePairing {
+    pub state: PairingState,
+    /// The lockdown error behind a non-paired state.
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PairingState {
+    Paired,
+    /// No pairing record for this host yet.
+    Unpaired,
+    /// The "Trust This Computer?" prompt is showing on the device.
+    AwaitingTrust,
+    /// The user tapped "Don't Trust".
+    Denied,
+    /// The device must be unlocked before it can pair.
+    Locked,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OsInfo {
@@ -60,6 +115,28 @@ pub enum OsPlatform {
     Windows,
 }
 
+/// What frida-server reports about the device it runs on, for picking default
+/// scripts and pointer sizes.
+///
+/// Mirrors frontend `DeviceSystemInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceSystemInfo {
+    pub device_id: String,
+    pub platform: Option<OsPlatform>,
+    pub os_name: Option<String>,
+    pub os_version: Option<String>,
+    pub arch: Option<String>,
+    /// Pointer width in bytes implied by `arch`.
+    pub pointer_size: Option<u32>,
+    /// Android API level.
+    pub api_level: Option<i64>,
+    /// `full` or `jailed`.
+    pub access: Option<String>,
+    /// Full access on iOS or Android, i.e. jailbroken or rooted.
+    pub jailbroken: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProcessInfo {
@@ -83,11 +160,36 @@ pub struct AppInfo {
 pub struct CollectionPage<T> {
     pub items: Vec<T>,
     pub total: usize,
+    pub offset: usize,
     pub limit: usize,
     pub truncated: bool,
     pub query: Option<String>,
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessSortKey {
+    #[default]
+    Name,
+    Pid,
+}
+
+/// Filtering, ordering and paging for `list_processes`, applied before the
+/// list crosses IPC.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessListOptions {
+    /// Case-insensitive regex matched against the name and identifier.
+    pub regex: Option<String>,
+    /// Keep only processes that belong to installed applications.
+    pub user_apps_only: Option<bool>,
+    pub sort_by: Option<ProcessSortKey>,
+    pub descending: Option<bool>,
+    pub offset: Option<usize>,
+    /// Attach PNG icons to the returned page, where the device has them.
+    pub include_icons: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SpawnOptions {
@@ -102,6 +204,11 @@ pub struct SpawnOptions {
     pub runtime: Option<String>,
     pub enable_child_gating: Option<bool>,
     pub script_path: Option<String>,
+    /// Delivered to the `script_path` script at load time; see
+    /// `loadScript`'s `parameters`.
+    pub script_parameters: Option<Value>,
+    pub max_duration_ms: Option<u64>,
+    pub detach_at: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,4 +220,78 @@ pub struct AttachOptions {
     pub runtime: Option<String>,
     pub enable_child_gating: Option<bool>,
     pub script_path: Option<String>,
+    /// Delivered to the `script_path` script at load time; see
+    /// `loadScript`'s `parameters`.
+    pub script_parameters: Option<Value>,
+    pub max_duration_ms: Option<u64>,
+    pub detach_at: Option<u64>,
+    /// Caller-chosen id that `cancel_operation` can use to abort the attach
+    /// while it is still in progress.
+    pub operation_id: Option<String>,
+}
+
+impl AttachOptions {
+    /// Options for attaching to `target` with every setting left at its
+    /// default.
+    pub fn for_target(target: Value) -> Self {
+        Self {
+            target,
+            realm: None,
+            persist_timeout: None,
+            runtime: None,
+            enable_child_gating: None,
+            script_path: None,
+            script_parameters: None,
+            max_duration_ms: None,
+            detach_at: None,
+            operation_id: None,
+        }
+    }
+}
+
+/// How long a caller waits on each class of Frida operation before giving up
+/// with `AppError::Timeout`. `0` waits indefinitely.
+///
+/// Mirrors frontend `TimeoutPolicy`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TimeoutPolicy {
+    pub attach_ms: u64,
+    pub script_load_ms: u64,
+    pub rpc_ms: u64,
+    /// Scans, heap searches and dumps, which run as agent jobs and
+    /// legitimately take far longer than other RPC calls.
+    pub scan_ms: u64,
+}
+
+impl Default for TimeoutPolicy {
+    fn default() -> Self {
+        Self {
+            attach_ms: 30_000,
+            script_load_ms: 30_000,
+            rpc_ms: 60_000,
+            scan_ms: 600_000,
+        }
+    }
+}
+
+/// One thing `cleanup_session` undid, with how many it removed.
+///
+/// Mirrors frontend `CleanupStep`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CleanupStep {
+    /// `nativeHooks`, `patches`, `userScript`, `detach`, ...
+    pub name: String,
+    pub removed: usize,
+    pub error: Option<String>,
+}
+
+/// Mirrors frontend `SessionCleanupReport`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionCleanupReport {
+    pub session_id: String,
+    pub steps: Vec<CleanupStep>,
+    pub detached: bool,
 }
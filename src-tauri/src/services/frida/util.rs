@@ -3,7 +3,6 @@ use std::ffi::CStr;
 use std::path::PathBuf;
 use std::process::Command;
 use std::sync::mpsc;
-use std::time::{SystemTime, UNIX_EPOCH};
 
 use frida::{
     Device as FridaDevice, DeviceType as FridaDeviceType, ScriptRuntime, SpawnStdio, Variant,
@@ -13,28 +12,75 @@ use serde_json::Value;
 use crate::error::AppError;
 use crate::state::BridgeEvent;
 
-use super::types::{DeviceInfo, DeviceStatus, DeviceType, OsInfo, OsPlatform};
+use super::types::{
+    DeviceInfo, DevicePairing, DeviceStatus, DeviceSystemInfo, DeviceType, OsInfo, OsPlatform,
+    PairingState,
+};
 
 pub(super) fn serialize_device(device: &FridaDevice<'static>) -> Result<DeviceInfo, AppError> {
-    let details = device
-        .query_system_parameters()
-        .unwrap_or_else(|_| HashMap::new());
+    let device_type = map_device_type(device.get_type());
+    let is_usb = matches!(device_type, DeviceType::Usb);
+    let (details, pairing) = match device.query_system_parameters() {
+        Ok(details) => (details, None),
+        Err(error) if is_usb => (HashMap::new(), pairing_from_error(&error.to_string())),
+        Err(_) => (HashMap::new(), None),
+    };
+    let os = build_os_info(&details);
+    let pairing = pairing.or_else(|| {
+        let is_ios = matches!(os.as_ref().map(|os| &os.platform), Some(OsPlatform::Ios));
+        (is_usb && is_ios).then_some(DevicePairing {
+            state: PairingState::Paired,
+            message: None,
+        })
+    });
 
     Ok(DeviceInfo {
         id: device.get_id().to_string(),
         name: device.get_name().to_string(),
-        device_type: map_device_type(device.get_type()),
+        device_type,
         icon: None,
-        os: build_os_info(&details),
+        os,
         arch: details
             .get("arch")
             .and_then(Variant::get_string)
             .map(ToOwned::to_owned),
         status: if device.is_lost() {
             DeviceStatus::Disconnected
+        } else if pairing
+            .as_ref()
+            .is_some_and(|pairing| pairing.state != PairingState::Paired)
+        {
+            DeviceStatus::Pairing
         } else {
             DeviceStatus::Connected
         },
+        pairing,
+    })
+}
+
+/// Recognizes the lockdown errors an iOS device answers with until it is
+/// paired with this host. Querying system parameters starts pairing, so the
+/// first query on a new device shows the trust prompt.
+fn pairing_from_error(message: &str) -> Option<DevicePairing> {
+    let normalized = message.to_ascii_lowercase().replace([' ', '_'], "");
+    let state = if normalized.contains("pairingdialogresponsepending") {
+        PairingState::AwaitingTrust
+    } else if normalized.contains("userdeniedpairing") {
+        PairingState::Denied
+    } else if normalized.contains("passwordprotected") || normalized.contains("locked") {
+        PairingState::Locked
+    } else if normalized.contains("invalidhostid")
+        || normalized.contains("notpaired")
+        || normalized.contains("pairing")
+    {
+        PairingState::Unpaired
+    } else {
+        return None;
+    };
+
+    Some(DevicePairing {
+        state,
+        message: Some(message.to_string()),
     })
 }
 
@@ -49,6 +95,53 @@ pub(super) fn get_device_arch(device: &FridaDevice<'static>) -> Result<Option<St
         .map(ToOwned::to_owned))
 }
 
+pub(super) fn query_device_system(
+    device: &FridaDevice<'static>,
+) -> Result<DeviceSystemInfo, AppError> {
+    let details = device
+        .query_system_parameters()
+        .map_err(|error| AppError::Internal(error.to_string()))?;
+    let os_details = details.get("os").and_then(Variant::get_map);
+    let os_field = |key: &str| {
+        os_details
+            .and_then(|os| os.get(key))
+            .and_then(Variant::get_string)
+            .map(ToOwned::to_owned)
+    };
+    let string_field = |key: &str| {
+        details
+            .get(key)
+            .and_then(Variant::get_string)
+            .map(ToOwned::to_owned)
+    };
+
+    let platform = build_os_info(&details).map(|os| os.platform);
+    let arch = string_field("arch");
+    let access = string_field("access");
+    let jailbroken = matches!(platform, Some(OsPlatform::Ios | OsPlatform::Android))
+        && access.as_deref() == Some("full");
+
+    Ok(DeviceSystemInfo {
+        device_id: device.get_id().to_string(),
+        platform,
+        os_name: os_field("name"),
+        os_version: os_field("version"),
+        pointer_size: arch.as_deref().map(pointer_size_for_arch),
+        arch,
+        api_level: details.get("api-level").and_then(Variant::get_int),
+        access,
+        jailbroken,
+    })
+}
+
+/// Frida reports `ia32`, `x64`, `arm` or `arm64`.
+fn pointer_size_for_arch(arch: &str) -> u32 {
+    match arch {
+        "ia32" | "arm" | "mips" => 4,
+        _ => 8,
+    }
+}
+
 pub(super) fn resolve_attach_target(
     device_id: &str,
     device: &FridaDevice<'static>,
@@ -115,7 +208,7 @@ fn resolve_running_application_target(
     };
 
     if !error.is_null() {
-        return Err(AppError::Internal(take_gerror_message(error)));
+        return Err(gerror_into(error, AppError::Internal));
     }
 
     let count = unsafe { frida_sys::frida_application_list_size(applications) };
@@ -202,20 +295,80 @@ fn adb_pidof(device_id: &str, target: &str) -> Option<u32> {
         .find_map(|value| value.parse::<u32>().ok())
 }
 
-fn take_gerror_message(error: *mut frida_sys::GError) -> String {
-    if error.is_null() {
-        return "unknown Frida error".to_string();
+pub(super) fn take_gerror_message(error: *mut frida_sys::GError) -> String {
+    take_gerror(error).message
+}
+
+/// Frees `error` into the `AppError` `kind` builds from its message, keeping
+/// the `GError` as its cause.
+pub(super) fn gerror_into(error: *mut frida_sys::GError, kind: fn(String) -> AppError) -> AppError {
+    let error = take_gerror(error);
+    kind(error.message.clone()).caused_by(error)
+}
+
+/// A `GError` raised by frida-core, kept so its `FridaError` code travels
+/// with the `AppError` it causes.
+#[derive(Debug)]
+pub(super) struct FridaCoreError {
+    /// `FRIDA_ERROR_*` name, when the error is in Frida's domain.
+    pub code: Option<&'static str>,
+    pub message: String,
+}
+
+impl std::fmt::Display for FridaCoreError {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.code {
+            Some(code) => write!(formatter, "{code}: {}", self.message),
+            None => formatter.write_str(&self.message),
+        }
     }
+}
 
-    let message = unsafe { CStr::from_ptr((*error).message) }
-        .to_string_lossy()
-        .into_owned();
+impl std::error::Error for FridaCoreError {}
 
+/// Reads and frees `error`.
+pub(super) fn take_gerror(error: *mut frida_sys::GError) -> FridaCoreError {
+    if error.is_null() {
+        return FridaCoreError {
+            code: None,
+            message: "unknown Frida error".to_string(),
+        };
+    }
+
+    let (domain, code, message) = unsafe {
+        let message = CStr::from_ptr((*error).message)
+            .to_string_lossy()
+            .into_owned();
+        ((*error).domain, (*error).code, message)
+    };
     unsafe {
         frida_sys::g_error_free(error);
     }
 
-    message
+    let is_frida_error = domain == unsafe { frida_sys::frida_error_quark() };
+    FridaCoreError {
+        code: is_frida_error.then(|| frida_error_name(code)).flatten(),
+        message,
+    }
+}
+
+fn frida_error_name(code: i32) -> Option<&'static str> {
+    Some(match code {
+        0 => "FRIDA_ERROR_SERVER_NOT_RUNNING",
+        1 => "FRIDA_ERROR_EXECUTABLE_NOT_FOUND",
+        2 => "FRIDA_ERROR_EXECUTABLE_NOT_SUPPORTED",
+        3 => "FRIDA_ERROR_PROCESS_NOT_FOUND",
+        4 => "FRIDA_ERROR_PROCESS_NOT_RESPONDING",
+        5 => "FRIDA_ERROR_INVALID_ARGUMENT",
+        6 => "FRIDA_ERROR_INVALID_OPERATION",
+        7 => "FRIDA_ERROR_PERMISSION_DENIED",
+        8 => "FRIDA_ERROR_ADDRESS_IN_USE",
+        9 => "FRIDA_ERROR_TIMED_OUT",
+        10 => "FRIDA_ERROR_NOT_SUPPORTED",
+        11 => "FRIDA_ERROR_PROTOCOL",
+        12 => "FRIDA_ERROR_TRANSPORT",
+        _ => return None,
+    })
 }
 
 pub(super) fn parse_script_runtime(runtime: Option<&str>) -> ScriptRuntime {
@@ -261,6 +414,19 @@ pub(super) fn unwrap_rpc_result(value: Value) -> Result<Value, AppError> {
     }
 }
 
+/// Tags RPC `params` with a fresh `binaryId`, pairing the call with its
+/// `carf:binary` message.
+pub(super) fn with_binary_id(mut params: Value) -> Result<(Value, String), AppError> {
+    let binary_id = uuid::Uuid::new_v4().to_string();
+    let Value::Object(object) = &mut params else {
+        return Err(AppError::InvalidArgument(
+            "binary RPC params must be an object".to_string(),
+        ));
+    };
+    object.insert("binaryId".to_string(), Value::String(binary_id.clone()));
+    Ok((params, binary_id))
+}
+
 pub(super) fn stringify_value(value: &Value) -> String {
     match value {
         Value::String(string) => string.clone(),
@@ -275,11 +441,18 @@ pub(super) fn project_root() -> PathBuf {
         .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")))
 }
 
-pub(super) fn now_millis() -> u64 {
-    SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64
+/// Resolves the auto-detach deadline for a session from its relative and
+/// absolute limits, picking whichever comes first.
+pub(super) fn session_deadline(
+    created_at: u64,
+    max_duration_ms: Option<u64>,
+    detach_at: Option<u64>,
+) -> Option<u64> {
+    let relative = max_duration_ms.map(|duration| created_at.saturating_add(duration));
+    match (relative, detach_at) {
+        (Some(relative), Some(absolute)) => Some(relative.min(absolute)),
+        (relative, absolute) => relative.or(absolute),
+    }
 }
 
 pub(super) fn new_session_id() -> String {
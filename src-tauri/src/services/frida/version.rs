@@ -0,0 +1,45 @@
+use frida::Frida;
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+
+/// `carf://device/version_mismatch`: the Frida in a device's targets (its
+/// frida-server or gadget) has a different major version than CARF's, so
+/// some features will fail in confusing ways.
+///
+/// Mirrors frontend `FridaVersionMismatch`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FridaVersionMismatch {
+    pub device_id: String,
+    pub local_version: String,
+    pub remote_version: String,
+    pub message: String,
+}
+
+/// Frida keeps its protocol across minor and patch releases, not majors.
+/// Versions that do not parse are given the benefit of the doubt.
+pub(super) fn check(device_id: &str, remote_version: &str) -> Option<FridaVersionMismatch> {
+    let local_version = Frida::version();
+    let major = |version: &str| version.split('.').next()?.parse::<u32>().ok();
+    let (local, remote) = (major(local_version)?, major(remote_version)?);
+    if local == remote {
+        return None;
+    }
+    Some(FridaVersionMismatch {
+        device_id: device_id.to_string(),
+        local_version: local_version.to_string(),
+        remote_version: remote_version.to_string(),
+        message: format!(
+            "{device_id} runs Frida {remote_version} but CARF uses {local_version}; \
+             install frida-server {local}.x on the device"
+        ),
+    })
+}
+
+/// Frida only says a client/server pair is incompatible in the error text.
+pub(super) fn mismatch_error(message: &str) -> Option<AppError> {
+    message.contains("major versions match").then(|| {
+        AppError::VersionMismatch(format!("{message} (CARF uses Frida {})", Frida::version()))
+    })
+}
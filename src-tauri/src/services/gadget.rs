@@ -0,0 +1,512 @@
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::storage;
+
+/// Port a gadget in listen mode binds unless told otherwise; the same as
+/// frida-server's.
+pub const GADGET_DEFAULT_PORT: u16 = 27042;
+const GADGET_LIBRARY: &str = "libfrida-gadget.so";
+/// Gadget looks for its config next to itself under this name. The `.so`
+/// suffix makes the package manager extract it along with the library.
+const GADGET_CONFIG: &str = "libfrida-gadget.config.so";
+const DEBUG_KEYSTORE: &str = "gadget-debug.keystore";
+const DEBUG_KEY_ALIAS: &str = "androiddebugkey";
+const DEBUG_KEY_PASSWORD: &str = "android";
+
+// ─── Gadget types ─────────────────────────────────────────────────────────────
+
+/// How the gadget behaves once loaded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GadgetMode {
+    /// Expose a frida-server compatible endpoint that CARF connects to.
+    #[default]
+    Listen,
+    /// Run a script from the device's filesystem without a host.
+    Script,
+}
+
+/// Mirrors frontend `GadgetConfigOptions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GadgetConfigOptions {
+    pub mode: GadgetMode,
+    /// Listen mode: interface to bind, `127.0.0.1` by default. Use `0.0.0.0`
+    /// to reach the gadget over Wi-Fi instead of `adb forward`.
+    pub address: Option<String>,
+    pub port: Option<u16>,
+    /// Listen mode: let the app run right away instead of holding it at
+    /// launch until a client attaches and resumes it.
+    pub resume_on_load: bool,
+    /// Script mode: path of the script on the device.
+    pub script_path: Option<String>,
+    /// Script mode: reload the script whenever the file changes.
+    pub reload_on_change: bool,
+}
+
+/// Mirrors frontend `ApkPatchOptions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApkPatchOptions {
+    pub apk_path: String,
+    /// Native library directory to add the gadget to, e.g. `arm64-v8a`.
+    pub abi: String,
+    /// frida-gadget library for `abi`. Defaults to
+    /// `frida-gadget-{version}-android-{arch}.so` in the temp dir.
+    #[serde(default)]
+    pub gadget_path: Option<String>,
+    /// Defaults to `<name>-gadget.apk` next to the input.
+    #[serde(default)]
+    pub output_path: Option<String>,
+    #[serde(default)]
+    pub config: GadgetConfigOptions,
+}
+
+/// Mirrors frontend `PatchedApk`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchedApk {
+    pub output_path: String,
+    pub package: String,
+    /// Class whose static initializer now loads the gadget.
+    pub injected_class: String,
+    pub abi: String,
+    pub config: Value,
+}
+
+/// One step of an APK patch, emitted as `carf://gadget/progress`.
+///
+/// Mirrors frontend `GadgetProgress`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GadgetProgress {
+    /// `decode`, `inject`, `build`, `align` or `sign`.
+    pub stage: String,
+    pub message: String,
+}
+
+// ─── Config ───────────────────────────────────────────────────────────────────
+
+/// Builds the gadget's JSON config (`libfrida-gadget.config.so`).
+pub fn gadget_config(options: &GadgetConfigOptions) -> Result<Value, AppError> {
+    let interaction = match options.mode {
+        GadgetMode::Listen => json!({
+            "type": "listen",
+            "address": options.address.as_deref().unwrap_or("127.0.0.1"),
+            "port": options.port.unwrap_or(GADGET_DEFAULT_PORT),
+            "on_port_conflict": "fail",
+            "on_load": if options.resume_on_load { "resume" } else { "wait" },
+        }),
+        GadgetMode::Script => {
+            let path = options
+                .script_path
+                .as_deref()
+                .filter(|path| !path.trim().is_empty())
+                .ok_or_else(|| {
+                    AppError::InvalidArgument("script mode needs a script path".to_string())
+                })?;
+            let mut interaction = json!({ "type": "script", "path": path });
+            if options.reload_on_change {
+                interaction["on_change"] = json!("reload");
+            }
+            interaction
+        }
+    };
+    Ok(json!({ "interaction": interaction }))
+}
+
+/// Where a downloaded `frida-gadget-{version}-android-{arch}.so` is expected
+/// on the host.
+pub fn local_gadget_path(version: &str, abi: &str) -> Result<PathBuf, AppError> {
+    let arch = match abi {
+        "arm64-v8a" => "arm64",
+        "armeabi-v7a" | "armeabi" => "arm",
+        "x86" => "x86",
+        "x86_64" => "x86_64",
+        other => {
+            return Err(AppError::InvalidArgument(format!(
+                "No frida-gadget build for ABI {other:?}"
+            )))
+        }
+    };
+    Ok(std::env::temp_dir().join(format!("frida-gadget-{version}-android-{arch}.so")))
+}
+
+// ─── APK patching ─────────────────────────────────────────────────────────────
+
+/// Removes the apktool work directory however patching ends.
+struct WorkDir(PathBuf);
+
+impl Drop for WorkDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Repackages an APK so it loads Frida Gadget on startup, for devices
+/// without root. The gadget is added as a native library and a
+/// `System.loadLibrary` call is injected into the static initializer of the
+/// app's `Application` subclass (or its launcher activity). The result is
+/// signed with a CARF debug key, so the original app must be uninstalled
+/// before installing it.
+///
+/// Needs `apktool`, `zipalign`, `apksigner` and `keytool` on `PATH`.
+pub fn patch_apk(
+    options: &ApkPatchOptions,
+    version: &str,
+    mut progress: impl FnMut(GadgetProgress),
+) -> Result<PatchedApk, AppError> {
+    let mut report = |stage: &str, message: String| {
+        progress(GadgetProgress {
+            stage: stage.to_string(),
+            message,
+        })
+    };
+
+    let apk = Path::new(&options.apk_path);
+    if !apk.is_file() {
+        return Err(AppError::InvalidArgument(format!(
+            "{} does not exist",
+            options.apk_path
+        )));
+    }
+    let gadget = match &options.gadget_path {
+        Some(path) => PathBuf::from(path),
+        None => local_gadget_path(version, &options.abi)?,
+    };
+    if !gadget.is_file() {
+        return Err(AppError::GadgetError(format!(
+            "frida-gadget library not found at {}",
+            gadget.display()
+        )));
+    }
+    let config = gadget_config(&options.config)?;
+    let output = match &options.output_path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let stem = apk
+                .file_stem()
+                .unwrap_or(OsStr::new("app"))
+                .to_string_lossy();
+            apk.with_file_name(format!("{stem}-gadget.apk"))
+        }
+    };
+
+    let work = WorkDir(std::env::temp_dir().join(format!("carf-gadget-{}", uuid::Uuid::new_v4())));
+    let decoded = work.0.join("decoded");
+    report("decode", format!("Decoding {}", apk.display()));
+    run_tool(
+        "apktool",
+        &[
+            OsStr::new("d"),
+            OsStr::new("-f"),
+            OsStr::new("-o"),
+            decoded.as_os_str(),
+            apk.as_os_str(),
+        ],
+    )?;
+
+    let manifest_path = decoded.join("AndroidManifest.xml");
+    let manifest = read_text(&manifest_path)?;
+    let package = manifest_package(&manifest)?;
+    let entry_class = entry_class(&manifest, &package)?;
+    report("inject", format!("Loading the gadget from {entry_class}"));
+
+    let smali_path = find_smali(&decoded, &entry_class)?;
+    let smali = read_text(&smali_path)?;
+    write_text(&smali_path, &inject_load_library(&smali)?)?;
+    write_text(&manifest_path, &patch_manifest(&manifest))?;
+
+    let lib_dir = decoded.join("lib").join(&options.abi);
+    fs::create_dir_all(&lib_dir).map_err(|error| {
+        AppError::GadgetError(format!("failed to create {}: {error}", lib_dir.display()))
+    })?;
+    fs::copy(&gadget, lib_dir.join(GADGET_LIBRARY)).map_err(|error| {
+        AppError::GadgetError(format!("failed to copy {}: {error}", gadget.display()))
+    })?;
+    let config_text = serde_json::to_string_pretty(&config)
+        .map_err(|error| AppError::Internal(error.to_string()))?;
+    write_text(&lib_dir.join(GADGET_CONFIG), &config_text)?;
+
+    let unsigned = work.0.join("unsigned.apk");
+    let aligned = work.0.join("aligned.apk");
+    report("build", "Rebuilding the APK".to_string());
+    run_tool(
+        "apktool",
+        &[
+            OsStr::new("b"),
+            decoded.as_os_str(),
+            OsStr::new("-o"),
+            unsigned.as_os_str(),
+        ],
+    )?;
+
+    report("align", "Aligning".to_string());
+    run_tool(
+        "zipalign",
+        &[
+            OsStr::new("-p"),
+            OsStr::new("-f"),
+            OsStr::new("4"),
+            unsigned.as_os_str(),
+            aligned.as_os_str(),
+        ],
+    )?;
+
+    report("sign", format!("Signing {}", output.display()));
+    let keystore = debug_keystore()?;
+    let store_pass = format!("pass:{DEBUG_KEY_PASSWORD}");
+    run_tool(
+        "apksigner",
+        &[
+            OsStr::new("sign"),
+            OsStr::new("--ks"),
+            keystore.as_os_str(),
+            OsStr::new("--ks-pass"),
+            OsStr::new(&store_pass),
+            OsStr::new("--ks-key-alias"),
+            OsStr::new(DEBUG_KEY_ALIAS),
+            OsStr::new("--key-pass"),
+            OsStr::new(&store_pass),
+            OsStr::new("--out"),
+            output.as_os_str(),
+            aligned.as_os_str(),
+        ],
+    )?;
+
+    Ok(PatchedApk {
+        output_path: output.to_string_lossy().into_owned(),
+        package,
+        injected_class: entry_class,
+        abi: options.abi.clone(),
+        config,
+    })
+}
+
+fn run_tool(tool: &str, args: &[&OsStr]) -> Result<(), AppError> {
+    let output = Command::new(tool).args(args).output().map_err(|error| {
+        if error.kind() == std::io::ErrorKind::NotFound {
+            AppError::GadgetError(format!(
+                "{tool} not found on PATH; install apktool and the Android SDK build-tools"
+            ))
+        } else {
+            AppError::GadgetError(format!("failed to run {tool}: {error}"))
+        }
+    })?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let detail = if stderr.trim().is_empty() {
+            stdout.trim()
+        } else {
+            stderr.trim()
+        };
+        Err(AppError::GadgetError(format!("{tool} failed: {detail}")))
+    }
+}
+
+/// A signing key kept in the data dir so every patched build of an app is
+/// signed the same way and can be updated in place.
+fn debug_keystore() -> Result<PathBuf, AppError> {
+    let path = storage::data_file(DEBUG_KEYSTORE)?;
+    if path.is_file() {
+        return Ok(path);
+    }
+
+    run_tool(
+        "keytool",
+        &[
+            OsStr::new("-genkeypair"),
+            OsStr::new("-keystore"),
+            path.as_os_str(),
+            OsStr::new("-storepass"),
+            OsStr::new(DEBUG_KEY_PASSWORD),
+            OsStr::new("-alias"),
+            OsStr::new(DEBUG_KEY_ALIAS),
+            OsStr::new("-keypass"),
+            OsStr::new(DEBUG_KEY_PASSWORD),
+            OsStr::new("-keyalg"),
+            OsStr::new("RSA"),
+            OsStr::new("-keysize"),
+            OsStr::new("2048"),
+            OsStr::new("-validity"),
+            OsStr::new("10000"),
+            OsStr::new("-dname"),
+            OsStr::new("CN=CARF Debug,O=CARF,C=US"),
+        ],
+    )?;
+    Ok(path)
+}
+
+fn read_text(path: &Path) -> Result<String, AppError> {
+    fs::read_to_string(path).map_err(|error| {
+        AppError::GadgetError(format!("failed to read {}: {error}", path.display()))
+    })
+}
+
+fn write_text(path: &Path, text: &str) -> Result<(), AppError> {
+    fs::write(path, text).map_err(|error| {
+        AppError::GadgetError(format!("failed to write {}: {error}", path.display()))
+    })
+}
+
+fn manifest_package(manifest: &str) -> Result<String, AppError> {
+    let pattern = Regex::new(r#"<manifest\b[^>]*?\bpackage="([^"]+)""#)
+        .map_err(|error| AppError::Internal(error.to_string()))?;
+    pattern
+        .captures(manifest)
+        .map(|captures| captures[1].to_string())
+        .ok_or_else(|| AppError::GadgetError("AndroidManifest.xml has no package".to_string()))
+}
+
+/// The class whose static initializer runs first: the custom `Application`
+/// if there is one, otherwise the launcher activity.
+fn entry_class(manifest: &str, package: &str) -> Result<String, AppError> {
+    let application = Regex::new(r#"<application\b[^>]*?\bandroid:name="([^"]+)""#)
+        .map_err(|error| AppError::Internal(error.to_string()))?;
+    if let Some(captures) = application.captures(manifest) {
+        return Ok(qualify_class(&captures[1], package));
+    }
+
+    let activity = Regex::new(r#"(?s)<activity(-alias)?\b([^>]*)>(.*?)</activity(?:-alias)?>"#)
+        .map_err(|error| AppError::Internal(error.to_string()))?;
+    let attribute = |attributes: &str, name: &str| {
+        Regex::new(&format!(r#"\bandroid:{name}="([^"]+)""#))
+            .ok()?
+            .captures(attributes)
+            .map(|captures| captures[1].to_string())
+    };
+
+    for captures in activity.captures_iter(manifest) {
+        let body = &captures[3];
+        if !body.contains("android.intent.action.MAIN")
+            || !body.contains("android.intent.category.LAUNCHER")
+        {
+            continue;
+        }
+        let attributes = &captures[2];
+        let name = if captures.get(1).is_some() {
+            attribute(attributes, "targetActivity")
+        } else {
+            attribute(attributes, "name")
+        };
+        if let Some(name) = name {
+            return Ok(qualify_class(&name, package));
+        }
+    }
+
+    Err(AppError::GadgetError(
+        "found neither an Application class nor a launcher activity to load the gadget from"
+            .to_string(),
+    ))
+}
+
+fn qualify_class(name: &str, package: &str) -> String {
+    if name.starts_with('.') {
+        format!("{package}{name}")
+    } else if !name.contains('.') {
+        format!("{package}.{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+/// Looks for the class in every `smali*` directory apktool produced (one per
+/// dex file).
+fn find_smali(decoded: &Path, class: &str) -> Result<PathBuf, AppError> {
+    let relative = format!("{}.smali", class.replace('.', "/"));
+    let entries = fs::read_dir(decoded).map_err(|error| {
+        AppError::GadgetError(format!("failed to read {}: {error}", decoded.display()))
+    })?;
+
+    let mut dirs = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .and_then(OsStr::to_str)
+                    .is_some_and(|name| name.starts_with("smali"))
+        })
+        .collect::<Vec<_>>();
+    dirs.sort();
+
+    dirs.into_iter()
+        .map(|dir| dir.join(&relative))
+        .find(|path| path.is_file())
+        .ok_or_else(|| AppError::GadgetError(format!("no smali found for {class}")))
+}
+
+/// Adds `System.loadLibrary("frida-gadget")` to the start of `<clinit>`,
+/// creating the method when the class has none.
+fn inject_load_library(smali: &str) -> Result<String, AppError> {
+    const LOAD: &str = "    const-string v0, \"frida-gadget\"\n\n    invoke-static {v0}, Ljava/lang/System;->loadLibrary(Ljava/lang/String;)V\n";
+
+    if smali.contains("\"frida-gadget\"") {
+        return Ok(smali.to_string());
+    }
+
+    let Some(start) = smali.find(".method static constructor <clinit>()V") else {
+        let mut patched = smali.trim_end().to_string();
+        patched.push_str("\n\n.method static constructor <clinit>()V\n    .locals 1\n\n");
+        patched.push_str(LOAD);
+        patched.push_str("\n    return-void\n.end method\n");
+        return Ok(patched);
+    };
+
+    let end = smali[start..]
+        .find(".end method")
+        .map_or(smali.len(), |offset| start + offset);
+    let method = &smali[start..end];
+    let Some(locals_offset) = method.find(".locals ") else {
+        return Err(AppError::GadgetError(
+            "<clinit> declares .registers instead of .locals; patch it by hand".to_string(),
+        ));
+    };
+
+    let locals_start = start + locals_offset;
+    let line_end = smali[locals_start..]
+        .find('\n')
+        .map_or(smali.len(), |offset| locals_start + offset);
+    let count = smali[locals_start + ".locals ".len()..line_end]
+        .trim()
+        .parse::<u32>()
+        .unwrap_or(0);
+
+    let mut patched = String::with_capacity(smali.len() + LOAD.len() + 16);
+    patched.push_str(&smali[..locals_start]);
+    patched.push_str(&format!(".locals {}\n\n", count.max(1)));
+    patched.push_str(LOAD);
+    patched.push_str(&smali[line_end..]);
+    Ok(patched)
+}
+
+/// Makes the package manager extract native libraries so the gadget finds
+/// its config file, and grants network access for listen mode.
+fn patch_manifest(manifest: &str) -> String {
+    let mut patched = manifest.replace(
+        "android:extractNativeLibs=\"false\"",
+        "android:extractNativeLibs=\"true\"",
+    );
+    if !patched.contains("android.permission.INTERNET") {
+        if let Some(position) = patched.find("<application") {
+            patched.insert_str(
+                position,
+                "<uses-permission android:name=\"android.permission.INTERNET\"/>\n    ",
+            );
+        }
+    }
+    patched
+}
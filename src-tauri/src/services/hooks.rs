@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::frida::FridaService;
+
+// ─── Hook types ───────────────────────────────────────────────────────────────
+
+/// What each hit of a native hook should capture. Mirrors the agent's
+/// `hookFunction` parameters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HookOptions {
+    pub capture_args: bool,
+    pub capture_retval: bool,
+    pub capture_backtrace: bool,
+}
+
+/// Mirrors the agent's `toHookInfo` shape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HookInfo {
+    pub id: String,
+    pub target: String,
+    pub address: String,
+    #[serde(rename = "type")]
+    pub hook_type: String,
+    pub active: bool,
+    pub hits: u64,
+}
+
+// ─── Native hooks ─────────────────────────────────────────────────────────────
+
+/// Builds the agent-side target string for a module export or module offset.
+///
+/// Offsets are accepted as `0x1a2b`, `+0x1a2b` or plain decimal and are always
+/// normalized to hex so the agent can tell them apart from symbol names.
+pub fn native_hook_target(
+    module: Option<&str>,
+    symbol_or_offset: &str,
+) -> Result<String, AppError> {
+    let symbol_or_offset = symbol_or_offset.trim();
+    if symbol_or_offset.is_empty() {
+        return Err(AppError::InvalidAddress(
+            "hook target symbol or offset is empty".to_string(),
+        ));
+    }
+
+    let target = match parse_offset(symbol_or_offset) {
+        Some(offset) => format!("{offset:#x}"),
+        None => symbol_or_offset.to_string(),
+    };
+
+    Ok(
+        match module.map(str::trim).filter(|module| !module.is_empty()) {
+            Some(module) => format!("{module}!{target}"),
+            None => target,
+        },
+    )
+}
+
+fn parse_offset(value: &str) -> Option<u64> {
+    let value = value.strip_prefix('+').unwrap_or(value);
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse::<u64>().ok(),
+    }
+}
+
+/// Installs an Interceptor hook through the core agent. Hits stream to the
+/// frontend as `carf://hook/event`.
+pub fn add_native_hook(
+    frida: &mut FridaService,
+    session_id: &str,
+    module: Option<&str>,
+    symbol_or_offset: &str,
+    options: &HookOptions,
+) -> Result<HookInfo, AppError> {
+    let target = native_hook_target(module, symbol_or_offset)?;
+    let response = frida.rpc_call(
+        session_id,
+        "hookFunction",
+        json!({
+            "target": target,
+            "captureArgs": options.capture_args,
+            "captureRetval": options.capture_retval,
+            "captureBacktrace": options.capture_backtrace,
+        }),
+    )?;
+    decode(response)
+}
+
+pub fn remove_native_hook(
+    frida: &mut FridaService,
+    session_id: &str,
+    hook_id: &str,
+) -> Result<(), AppError> {
+    frida.rpc_call(session_id, "unhookFunction", json!({ "hookId": hook_id }))?;
+    Ok(())
+}
+
+pub fn list_native_hooks(
+    frida: &mut FridaService,
+    session_id: &str,
+) -> Result<Vec<HookInfo>, AppError> {
+    let response = frida.rpc_call(session_id, "listHooks", Value::Null)?;
+    decode(response)
+}
+
+fn decode<T>(value: Value) -> Result<T, AppError>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    serde_json::from_value(value)
+        .map_err(|error| AppError::AgentRpcError(format!("unexpected agent response: {error}")))
+}
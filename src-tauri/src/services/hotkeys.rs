@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::AppError;
+
+/// Modifiers in the order a normalized chord lists them.
+const MODIFIERS: [&str; 4] = ["Ctrl", "Alt", "Shift", "Meta"];
+
+const NAMED_KEYS: &[&str] = &[
+    "Enter",
+    "Escape",
+    "Space",
+    "Tab",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Home",
+    "End",
+    "PageUp",
+    "PageDown",
+    "ArrowUp",
+    "ArrowDown",
+    "ArrowLeft",
+    "ArrowRight",
+];
+
+/// One action's key chord.
+///
+/// Mirrors frontend `HotkeyBinding`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyBinding {
+    pub action: String,
+    /// Normalized chord, e.g. `Ctrl+Shift+F`.
+    pub chord: String,
+    /// Set when the action is an agent RPC call rather than a frontend one.
+    pub rpc: Option<HotkeyRpcAction>,
+}
+
+/// Agent RPC call run by `hotkeys_trigger` when its action's chord fires.
+///
+/// Mirrors frontend `HotkeyRpcAction`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyRpcAction {
+    /// Session whose agent receives the call; unset means the only attached
+    /// session.
+    pub session_id: Option<String>,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// Every binding in `hotkeys`, ordered by action, with the RPC call bound to
+/// each.
+pub fn list_bindings(
+    hotkeys: &BTreeMap<String, String>,
+    rpc_actions: &BTreeMap<String, HotkeyRpcAction>,
+) -> Vec<HotkeyBinding> {
+    hotkeys
+        .iter()
+        .map(|(action, chord)| HotkeyBinding {
+            action: action.clone(),
+            chord: chord.clone(),
+            rpc: rpc_actions.get(action).cloned(),
+        })
+        .collect()
+}
+
+/// Rejects an RPC action that could never be called.
+pub fn validate_rpc_action(action: &str, rpc: &HotkeyRpcAction) -> Result<(), AppError> {
+    if rpc.method.trim().is_empty() {
+        return Err(AppError::InvalidArgument(format!(
+            "RPC method for hotkey action {action} must not be empty"
+        )));
+    }
+    Ok(())
+}
+
+/// Binds `action` to `chord`, or unbinds it when `chord` is `None` or empty.
+/// Fails without changing anything when another action already uses the
+/// chord.
+pub fn bind(
+    hotkeys: &mut BTreeMap<String, String>,
+    action: &str,
+    chord: Option<&str>,
+) -> Result<(), AppError> {
+    let action = action.trim();
+    if action.is_empty() {
+        return Err(AppError::InvalidArgument(
+            "hotkey action must not be empty".to_string(),
+        ));
+    }
+    let Some(chord) = chord.map(str::trim).filter(|chord| !chord.is_empty()) else {
+        hotkeys.remove(action);
+        return Ok(());
+    };
+
+    let chord = normalize_chord(chord)?;
+    if let Some(other) = find_conflict(hotkeys, action, &chord) {
+        return Err(AppError::InvalidArgument(format!(
+            "{chord} is already bound to {other}"
+        )));
+    }
+    hotkeys.insert(action.to_string(), chord);
+    Ok(())
+}
+
+/// Normalizes every chord in `hotkeys` and rejects bad or shared ones, as
+/// `settings_set` requires.
+pub fn validate_bindings(hotkeys: &mut BTreeMap<String, String>) -> Result<(), AppError> {
+    let mut normalized = BTreeMap::new();
+    for (action, chord) in hotkeys.iter() {
+        bind(&mut normalized, action, Some(chord))?;
+    }
+    *hotkeys = normalized;
+    Ok(())
+}
+
+/// Rewrites a chord like `shift+ctrl+f` as `Ctrl+Shift+F`: known modifiers
+/// in a fixed order, then exactly one key.
+pub fn normalize_chord(chord: &str) -> Result<String, AppError> {
+    let mut modifiers = [false; MODIFIERS.len()];
+    let mut key = None;
+
+    for part in chord.split('+').map(str::trim) {
+        if part.is_empty() {
+            return Err(AppError::InvalidArgument(format!(
+                "Malformed hotkey: {chord}"
+            )));
+        }
+        if let Some(index) = modifier_index(part) {
+            modifiers[index] = true;
+            continue;
+        }
+        if key.is_some() {
+            return Err(AppError::InvalidArgument(format!(
+                "Hotkey {chord} has more than one key"
+            )));
+        }
+        key = Some(normalize_key(part).ok_or_else(|| {
+            AppError::InvalidArgument(format!("Unknown key {part} in hotkey {chord}"))
+        })?);
+    }
+
+    let key = key.ok_or_else(|| AppError::InvalidArgument(format!("Hotkey {chord} has no key")))?;
+    let mut parts: Vec<String> = MODIFIERS
+        .iter()
+        .zip(modifiers)
+        .filter(|(_, held)| *held)
+        .map(|(modifier, _)| modifier.to_string())
+        .collect();
+    parts.push(key);
+    Ok(parts.join("+"))
+}
+
+fn find_conflict<'a>(
+    hotkeys: &'a BTreeMap<String, String>,
+    action: &str,
+    chord: &str,
+) -> Option<&'a str> {
+    hotkeys
+        .iter()
+        .find(|(other, bound)| other.as_str() != action && bound.as_str() == chord)
+        .map(|(other, _)| other.as_str())
+}
+
+fn modifier_index(part: &str) -> Option<usize> {
+    match part.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Some(0),
+        "alt" | "option" => Some(1),
+        "shift" => Some(2),
+        "meta" | "cmd" | "command" | "super" | "win" => Some(3),
+        _ => None,
+    }
+}
+
+fn normalize_key(part: &str) -> Option<String> {
+    let mut chars = part.chars();
+    if let (Some(ch), None) = (chars.next(), chars.next()) {
+        return ch
+            .is_ascii_graphic()
+            .then(|| ch.to_ascii_uppercase().to_string());
+    }
+
+    let lower = part.to_ascii_lowercase();
+    if let Some(number) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&number).then(|| format!("F{number}"));
+    }
+    let lower = match lower.as_str() {
+        "esc" => "escape",
+        "return" => "enter",
+        "del" => "delete",
+        "up" => "arrowup",
+        "down" => "arrowdown",
+        "left" => "arrowleft",
+        "right" => "arrowright",
+        other => other,
+    };
+    NAMED_KEYS
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(lower))
+        .map(|name| name.to_string())
+}
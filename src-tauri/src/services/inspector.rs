@@ -0,0 +1,550 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::frida::FridaService;
+
+/// Bytes read for the string views; numeric views only need the first eight.
+const INSPECT_WINDOW: usize = 64;
+/// Timestamps outside 1980–2100 are almost certainly not timestamps.
+const PLAUSIBLE_UNIX_SECONDS: std::ops::Range<i64> = 315_532_800..4_102_444_800;
+/// 100ns ticks between 1601-01-01 and 1970-01-01.
+const FILETIME_UNIX_EPOCH: u64 = 116_444_736_000_000_000;
+/// Shortest run of printable characters reported as a string.
+const MIN_STRING_LEN: usize = 2;
+/// Type guessing wants more evidence than the inspector before calling
+/// bytes a string.
+const GUESS_STRING_LEN: usize = 4;
+/// Values below this are never treated as pointers; the first pages are
+/// unmapped on every platform we target.
+const MIN_POINTER: u64 = 0x10000;
+/// Integers at or below this magnitude read as counters, ids or enum values.
+const SMALL_INT_LIMIT: i64 = 1_000_000;
+/// Floats outside this magnitude are usually the bits of an integer or pointer.
+const PLAUSIBLE_FLOAT: std::ops::RangeInclusive<f64> = 1e-4..=1e7;
+
+// ─── Inspector types ──────────────────────────────────────────────────────────
+
+/// Every common interpretation of the bytes at an address, for the hex
+/// editor's data inspector panel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ByteInspection {
+    pub address: String,
+    /// Hex of the bytes the views were decoded from.
+    pub bytes: String,
+    pub little_endian: NumericViews,
+    pub big_endian: NumericViews,
+    pub pointer: Option<PointerView>,
+    pub timestamps: TimestampViews,
+    pub strings: StringViews,
+}
+
+/// 64-bit integers are strings so the frontend does not lose precision.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NumericViews {
+    pub u8: Option<u8>,
+    pub i8: Option<i8>,
+    pub u16: Option<u16>,
+    pub i16: Option<i16>,
+    pub u32: Option<u32>,
+    pub i32: Option<i32>,
+    pub u64: Option<String>,
+    pub i64: Option<String>,
+    pub f32: Option<f32>,
+    pub f64: Option<f64>,
+}
+
+/// The bytes read as a pointer of the target's width and byte order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerView {
+    pub value: String,
+    pub size: usize,
+    /// Whether the target address is mapped in the process.
+    pub valid: bool,
+    pub protection: Option<String>,
+    pub module: Option<String>,
+}
+
+/// ISO-8601 UTC renderings, present only when the value is plausible.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimestampViews {
+    pub unix_seconds32: Option<String>,
+    pub unix_seconds64: Option<String>,
+    pub unix_millis: Option<String>,
+    pub filetime: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StringViews {
+    pub utf8: Option<String>,
+    pub utf16le: Option<String>,
+}
+
+// ─── Type guess types ─────────────────────────────────────────────────────────
+
+/// Ranked guesses at what the value at `address` is, from samples taken a
+/// short time apart.
+///
+/// Mirrors frontend `TypeGuess`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeGuess {
+    pub address: String,
+    pub samples: usize,
+    /// Whether the first eight bytes changed between samples.
+    pub volatile: bool,
+    /// Most likely first.
+    pub candidates: Vec<TypeCandidate>,
+}
+
+/// `value_type` uses the same names as `write_values_batch`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypeCandidate {
+    pub value_type: String,
+    /// 0–1; only meaningful relative to the other candidates.
+    pub confidence: f64,
+    /// The latest sample decoded as this type.
+    pub value: String,
+    pub reason: String,
+}
+
+// ─── Inspection ───────────────────────────────────────────────────────────────
+
+pub fn inspect_bytes(
+    frida: &mut FridaService,
+    session_id: &str,
+    address: &str,
+) -> Result<ByteInspection, AppError> {
+    let bytes = read_window(frida, session_id, address)?;
+    let layout = pointer_layout(frida, session_id)?;
+    let pointer = read_pointer(&bytes, layout)
+        .map(|value| describe_pointer(frida, session_id, value, layout.size));
+
+    Ok(ByteInspection {
+        address: address.to_string(),
+        bytes: bytes.iter().map(|byte| format!("{byte:02x}")).collect(),
+        little_endian: numeric_views(&bytes, false),
+        big_endian: numeric_views(&bytes, true),
+        pointer,
+        timestamps: timestamp_views(&bytes, layout.big_endian),
+        strings: StringViews {
+            utf8: utf8_string(&bytes),
+            utf16le: utf16le_string(&bytes),
+        },
+    })
+}
+
+// ─── Type guessing ────────────────────────────────────────────────────────────
+
+/// Reads one sample for `guess_types`.
+pub fn read_sample(
+    frida: &mut FridaService,
+    session_id: &str,
+    address: &str,
+) -> Result<Vec<u8>, AppError> {
+    read_window(frida, session_id, address)
+}
+
+/// Ranks likely types for the value at `address` from `samples` of it,
+/// oldest first: a small integer that holds still, a float in a plausible
+/// range, a pointer into a module or mapped memory, or the head of a string.
+pub fn guess_types(
+    frida: &mut FridaService,
+    session_id: &str,
+    address: &str,
+    samples: &[Vec<u8>],
+) -> Result<TypeGuess, AppError> {
+    let latest = samples
+        .last()
+        .ok_or_else(|| AppError::InvalidArgument("no samples to guess from".to_string()))?;
+    let volatile = samples
+        .windows(2)
+        .any(|pair| pair[0].get(..8) != pair[1].get(..8));
+    let mut candidates = Vec::new();
+
+    if latest.iter().take(8).all(|byte| *byte == 0) {
+        candidates.push(candidate(
+            "i32",
+            0.3,
+            "0".to_string(),
+            "all zero; sample again once the value changes",
+        ));
+    } else {
+        let layout = pointer_layout(frida, session_id)?;
+        if let Some(value) = read_pointer(latest, layout).filter(|value| *value >= MIN_POINTER) {
+            let view = describe_pointer(frida, session_id, value, layout.size);
+            if view.valid {
+                let (confidence, reason) = match &view.module {
+                    Some(module) => (0.9, format!("points into {module}")),
+                    None => (
+                        0.8,
+                        format!(
+                            "points to mapped {} memory",
+                            view.protection.as_deref().unwrap_or("---")
+                        ),
+                    ),
+                };
+                candidates.push(candidate("pointer", confidence, view.value, &reason));
+            }
+        }
+
+        guess_strings(latest, &mut candidates);
+        guess_integers(samples, latest, volatile, layout, &mut candidates);
+        guess_floats(latest, volatile, layout, &mut candidates);
+    }
+
+    candidates.sort_by(|left, right| right.confidence.total_cmp(&left.confidence));
+    Ok(TypeGuess {
+        address: address.to_string(),
+        samples: samples.len(),
+        volatile,
+        candidates,
+    })
+}
+
+fn guess_strings(bytes: &[u8], candidates: &mut Vec<TypeCandidate>) {
+    if let Some(text) = utf8_string(bytes).filter(|text| text.chars().count() >= GUESS_STRING_LEN) {
+        candidates.push(candidate("utf8", 0.75, text, "printable UTF-8 text"));
+    }
+    if let Some(text) =
+        utf16le_string(bytes).filter(|text| text.chars().count() >= GUESS_STRING_LEN)
+    {
+        candidates.push(candidate("utf16", 0.7, text, "printable UTF-16 text"));
+    }
+}
+
+/// Decodes in the target's byte order, like the pointer candidate.
+fn guess_integers(
+    samples: &[Vec<u8>],
+    latest: &[u8],
+    volatile: bool,
+    layout: PointerLayout,
+    candidates: &mut Vec<TypeCandidate>,
+) {
+    let views = numeric_views(latest, layout.big_endian);
+    if let Some(value) = views.i32 {
+        if (value as i64).abs() <= SMALL_INT_LIMIT {
+            let series: Vec<i64> = samples
+                .iter()
+                .filter_map(|sample| numeric_views(sample, layout.big_endian).i32)
+                .map(i64::from)
+                .collect();
+            let largest_step = series
+                .windows(2)
+                .map(|pair| (pair[1] - pair[0]).abs())
+                .max()
+                .unwrap_or(0);
+            let (confidence, reason) = if !volatile {
+                (0.7, "small integer that holds still")
+            } else if largest_step <= 1_000 {
+                (0.65, "small integer that changes in small steps")
+            } else {
+                (0.5, "small integer")
+            };
+            candidates.push(candidate("i32", confidence, value.to_string(), reason));
+        }
+    }
+
+    // A lone 0 or 1 byte followed by zeroes reads the same in either order.
+    if let (false, Some(flag @ (0 | 1)), Some([0, 0, 0])) =
+        (volatile, latest.first().copied(), latest.get(1..4))
+    {
+        candidates.push(candidate(
+            "u8",
+            0.55,
+            flag.to_string(),
+            "0 or 1, likely a flag",
+        ));
+    }
+
+    let timestamps = timestamp_views(latest, layout.big_endian);
+    if let (Some(value), Some(time)) = (views.i64, timestamps.unix_millis) {
+        candidates.push(candidate(
+            "i64",
+            0.6,
+            value,
+            &format!("plausible Unix time in milliseconds ({time})"),
+        ));
+    }
+    if let (Some(value), Some(time)) = (views.u32, timestamps.unix_seconds32) {
+        candidates.push(candidate(
+            "u32",
+            0.55,
+            value.to_string(),
+            &format!("plausible Unix time in seconds ({time})"),
+        ));
+    }
+}
+
+fn guess_floats(
+    latest: &[u8],
+    volatile: bool,
+    layout: PointerLayout,
+    candidates: &mut Vec<TypeCandidate>,
+) {
+    let views = numeric_views(latest, layout.big_endian);
+    if let Some(value) = views.f32.filter(|value| plausible_float(*value as f64)) {
+        let (confidence, reason) = if volatile {
+            (0.7, "float in a plausible range that changes over time")
+        } else {
+            (0.6, "float in a plausible range")
+        };
+        candidates.push(candidate("f32", confidence, value.to_string(), reason));
+    }
+    if let Some(value) = views.f64.filter(|value| plausible_float(*value)) {
+        let (confidence, reason) = if volatile {
+            (0.65, "double in a plausible range that changes over time")
+        } else {
+            (0.55, "double in a plausible range")
+        };
+        candidates.push(candidate("f64", confidence, value.to_string(), reason));
+    }
+}
+
+fn plausible_float(value: f64) -> bool {
+    value.is_finite() && PLAUSIBLE_FLOAT.contains(&value.abs())
+}
+
+fn candidate(value_type: &str, confidence: f64, value: String, reason: &str) -> TypeCandidate {
+    TypeCandidate {
+        value_type: value_type.to_string(),
+        confidence,
+        value,
+        reason: reason.to_string(),
+    }
+}
+
+/// How the target process stores pointers.
+#[derive(Debug, Clone, Copy)]
+pub struct PointerLayout {
+    pub size: usize,
+    pub big_endian: bool,
+}
+
+pub fn pointer_layout(
+    frida: &mut FridaService,
+    session_id: &str,
+) -> Result<PointerLayout, AppError> {
+    let info = frida.rpc_call(session_id, "getProcessInfo", Value::Null)?;
+    Ok(PointerLayout {
+        size: info.get("pointerSize").and_then(Value::as_u64).unwrap_or(8) as usize,
+        big_endian: info.get("endianness").and_then(Value::as_str) == Some("big"),
+    })
+}
+
+/// The bytes read as a pointer of the target's width and byte order.
+pub fn read_pointer(bytes: &[u8], layout: PointerLayout) -> Option<u64> {
+    match (layout.size, layout.big_endian) {
+        (4, false) => fixed::<4>(bytes).map(|raw| u32::from_le_bytes(raw) as u64),
+        (4, true) => fixed::<4>(bytes).map(|raw| u32::from_be_bytes(raw) as u64),
+        (_, false) => fixed::<8>(bytes).map(u64::from_le_bytes),
+        (_, true) => fixed::<8>(bytes).map(u64::from_be_bytes),
+    }
+}
+
+/// Reads up to `INSPECT_WINDOW` bytes, shrinking the read when the window
+/// runs off the end of a mapping.
+fn read_window(
+    frida: &mut FridaService,
+    session_id: &str,
+    address: &str,
+) -> Result<Vec<u8>, AppError> {
+    let mut size = INSPECT_WINDOW;
+    loop {
+        match frida.rpc_call(
+            session_id,
+            "readMemory",
+            json!({ "address": address, "size": size }),
+        ) {
+            Ok(value) => {
+                let hex = value.as_str().unwrap_or_default();
+                return decode_hex(hex).ok_or_else(|| {
+                    AppError::AgentRpcError("readMemory returned invalid hex".to_string())
+                });
+            }
+            Err(_) if size > 1 => size /= 2,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+fn describe_pointer(
+    frida: &mut FridaService,
+    session_id: &str,
+    value: u64,
+    size: usize,
+) -> PointerView {
+    let target = format!("{value:#x}");
+    let protection = frida
+        .rpc_call(
+            session_id,
+            "queryMemoryProtection",
+            json!({ "address": target }),
+        )
+        .ok()
+        .and_then(|response| {
+            response
+                .get("protection")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        });
+    let module = protection.as_ref().and_then(|_| {
+        frida
+            .rpc_call(
+                session_id,
+                "findModuleByAddress",
+                json!({ "address": target }),
+            )
+            .ok()?
+            .get("name")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    });
+
+    PointerView {
+        value: target,
+        size,
+        valid: protection.is_some(),
+        protection,
+        module,
+    }
+}
+
+fn numeric_views(bytes: &[u8], big_endian: bool) -> NumericViews {
+    macro_rules! read {
+        ($ty:ty, $n:literal) => {
+            fixed::<$n>(bytes).map(|raw| {
+                if big_endian {
+                    <$ty>::from_be_bytes(raw)
+                } else {
+                    <$ty>::from_le_bytes(raw)
+                }
+            })
+        };
+    }
+
+    NumericViews {
+        u8: bytes.first().copied(),
+        i8: bytes.first().map(|byte| *byte as i8),
+        u16: read!(u16, 2),
+        i16: read!(i16, 2),
+        u32: read!(u32, 4),
+        i32: read!(i32, 4),
+        u64: read!(u64, 8).map(|value| value.to_string()),
+        i64: read!(i64, 8).map(|value| value.to_string()),
+        f32: read!(f32, 4).filter(|value| value.is_finite()),
+        f64: read!(f64, 8).filter(|value| value.is_finite()),
+    }
+}
+
+fn timestamp_views(bytes: &[u8], big_endian: bool) -> TimestampViews {
+    let seconds32 = fixed::<4>(bytes).map(|raw| {
+        let value = if big_endian {
+            u32::from_be_bytes(raw)
+        } else {
+            u32::from_le_bytes(raw)
+        };
+        value as i64
+    });
+    let value64 = fixed::<8>(bytes).map(|raw| {
+        if big_endian {
+            u64::from_be_bytes(raw)
+        } else {
+            u64::from_le_bytes(raw)
+        }
+    });
+
+    TimestampViews {
+        unix_seconds32: seconds32.and_then(|seconds| format_unix_millis(seconds, 0)),
+        unix_seconds64: value64.and_then(|seconds| format_unix_millis(seconds as i64, 0)),
+        unix_millis: value64.and_then(|millis| {
+            let millis = millis as i64;
+            format_unix_millis(millis.div_euclid(1000), millis.rem_euclid(1000))
+        }),
+        filetime: value64
+            .and_then(|ticks| ticks.checked_sub(FILETIME_UNIX_EPOCH))
+            .and_then(|ticks| {
+                let millis = (ticks / 10_000) as i64;
+                format_unix_millis(millis.div_euclid(1000), millis.rem_euclid(1000))
+            }),
+    }
+}
+
+fn utf8_string(bytes: &[u8]) -> Option<String> {
+    let end = bytes
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+    let text = std::str::from_utf8(&bytes[..end]).ok()?;
+    printable(text)
+}
+
+fn utf16le_string(bytes: &[u8]) -> Option<String> {
+    let units = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .collect::<Vec<_>>();
+    let text = String::from_utf16(&units).ok()?;
+    printable(&text)
+}
+
+fn printable(text: &str) -> Option<String> {
+    let is_printable = text
+        .chars()
+        .all(|ch| !ch.is_control() || matches!(ch, '\t' | '\n' | '\r'));
+    (is_printable && text.chars().count() >= MIN_STRING_LEN).then(|| text.to_string())
+}
+
+fn fixed<const N: usize>(bytes: &[u8]) -> Option<[u8; N]> {
+    bytes.get(..N)?.try_into().ok()
+}
+
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+/// Formats a UTC instant as `YYYY-MM-DDTHH:MM:SS.mmmZ`, or `None` when it
+/// falls outside the plausible range.
+fn format_unix_millis(seconds: i64, millis: i64) -> Option<String> {
+    if !PLAUSIBLE_UNIX_SECONDS.contains(&seconds) {
+        return None;
+    }
+
+    let days = seconds.div_euclid(86_400);
+    let time = seconds.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    Some(format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{millis:03}Z",
+        time / 3600,
+        time % 3600 / 60,
+        time % 60,
+    ))
+}
+
+/// Converts days since 1970-01-01 to a proleptic Gregorian date (Howard
+/// Hinnant's `civil_from_days`).
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,261 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::services::storage;
+
+const DEEP_LINK_SCHEME: &str = "carf://";
+
+// ─── Launch types ─────────────────────────────────────────────────────────────
+
+/// How CARF was started: `--workspace <file>` / `CARF_WORKSPACE`,
+/// `--kiosk` / `CARF_KIOSK=1`, or a `carf://` link.
+///
+/// Mirrors frontend `LaunchOptions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchOptions {
+    pub workspace_path: Option<String>,
+    pub workspace: Option<Workspace>,
+    /// Hide advanced UI so a prepared workspace can be handed to someone who
+    /// only needs its toggles.
+    pub kiosk: bool,
+    /// Why the workspace could not be loaded, shown once the UI is up.
+    pub error: Option<String>,
+    /// `carf://` URL CARF was opened with; `workspace` holds what it asks
+    /// for. Unlike a workspace file, a link's targets are only attached once
+    /// the user confirms through `run_deep_link`, since any web page can
+    /// open one.
+    pub deep_link: Option<String>,
+}
+
+/// `carf://launch/deep-link`: a link opened while CARF is running, or on
+/// macOS the one it was launched with. Attached only after the user confirms
+/// through `run_deep_link`.
+///
+/// Mirrors frontend `DeepLinkRequest`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkRequest {
+    pub url: String,
+    pub workspace: Option<Workspace>,
+    /// Why the link could not be parsed.
+    pub error: Option<String>,
+}
+
+/// A prepared setup opened at launch.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Workspace {
+    pub name: Option<String>,
+    /// Defaults to the local device.
+    pub device_id: Option<String>,
+    /// Targets attached automatically, in order.
+    pub targets: Vec<WorkspaceTarget>,
+    /// Automation rule definitions, applied by the frontend.
+    pub rules: Vec<Value>,
+    /// Kiosk mode from the workspace itself; the CLI flag can only turn it on.
+    pub kiosk: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceTarget {
+    /// Process name, app identifier or pid.
+    pub target: Value,
+    /// Keep polling until the target starts instead of failing right away.
+    #[serde(default)]
+    pub wait: bool,
+    pub timeout_ms: Option<u64>,
+    /// Relative paths resolve against the workspace file.
+    pub script_path: Option<String>,
+    pub script_parameters: Option<Value>,
+    /// Id of a bundled script loaded after attaching.
+    pub bundled_script: Option<String>,
+}
+
+// ─── Parsing ──────────────────────────────────────────────────────────────────
+
+/// Reads launch options from the command line, falling back to environment
+/// variables. A broken workspace file is reported in `error` rather than
+/// stopping the app from starting.
+pub fn from_env() -> LaunchOptions {
+    let mut workspace_path = std::env::var("CARF_WORKSPACE")
+        .ok()
+        .filter(|value| !value.trim().is_empty());
+    let mut kiosk = std::env::var("CARF_KIOSK")
+        .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+
+    let mut deep_link = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg.starts_with(DEEP_LINK_SCHEME) {
+            deep_link = Some(arg);
+        } else if arg == "--kiosk" {
+            kiosk = true;
+        } else if arg == "--workspace" {
+            workspace_path = args.next();
+        } else if let Some(path) = arg.strip_prefix("--workspace=") {
+            workspace_path = Some(path.to_string());
+        }
+    }
+
+    let mut options = LaunchOptions {
+        kiosk,
+        ..LaunchOptions::default()
+    };
+    if let Some(path) = workspace_path {
+        match load_workspace(&path) {
+            Ok(workspace) => {
+                options.kiosk |= workspace.kiosk;
+                options.workspace = Some(workspace);
+            }
+            Err(error) => {
+                log::warn!("Failed to load workspace {path}: {error}");
+                options.error = Some(error.to_string());
+            }
+        }
+        options.workspace_path = Some(path);
+    } else if let Some(url) = deep_link {
+        options.open_deep_link(&url);
+    }
+    options
+}
+
+impl LaunchOptions {
+    /// Records the link CARF was started with, unless a workspace file was
+    /// given, which takes precedence.
+    pub fn open_deep_link(&mut self, url: &str) {
+        if self.workspace_path.is_some() {
+            return;
+        }
+        let request = deep_link_request(url);
+        self.workspace = request.workspace;
+        self.error = request.error;
+        self.deep_link = Some(request.url);
+    }
+}
+
+/// Parses a link for the frontend to confirm, keeping the error instead of
+/// failing so the user learns why nothing was attached.
+pub fn deep_link_request(url: &str) -> DeepLinkRequest {
+    match parse_deep_link(url) {
+        Ok(workspace) => DeepLinkRequest {
+            url: url.to_string(),
+            workspace: Some(workspace),
+            error: None,
+        },
+        Err(error) => {
+            log::warn!("Ignoring deep link {url}: {error}");
+            DeepLinkRequest {
+                url: url.to_string(),
+                workspace: None,
+                error: Some(error.to_string()),
+            }
+        }
+    }
+}
+
+/// Turns `carf://attach?device=usb&name=com.example.app&script=ssl-unpinning`
+/// into a one-target workspace. `script` is a bundled script id; links
+/// cannot name script files. `pid` may replace `name`, and `wait` keeps
+/// polling until the target starts.
+pub fn parse_deep_link(url: &str) -> Result<Workspace, AppError> {
+    let rest = url
+        .strip_prefix(DEEP_LINK_SCHEME)
+        .ok_or_else(|| AppError::InvalidArgument(format!("Not a CARF link: {url}")))?;
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+    if action.trim_end_matches('/') != "attach" {
+        return Err(AppError::InvalidArgument(format!(
+            "Unsupported link action {action}; expected attach"
+        )));
+    }
+
+    let mut device_id = None;
+    let mut target = None;
+    let mut bundled_script = None;
+    let mut wait = false;
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value)?;
+        match key {
+            "device" => device_id = Some(value),
+            "name" => target = Some(Value::String(value)),
+            "pid" => {
+                let pid = value.parse::<u32>().map_err(|_| {
+                    AppError::InvalidArgument(format!("Link pid is not a number: {value}"))
+                })?;
+                target = Some(Value::from(pid));
+            }
+            "script" => bundled_script = Some(value),
+            "wait" => wait = value.is_empty() || value == "1" || value == "true",
+            other => log::debug!("Ignoring unknown link parameter {other}"),
+        }
+    }
+
+    let target =
+        target.ok_or_else(|| AppError::InvalidArgument("Link names no name or pid".to_string()))?;
+    Ok(Workspace {
+        device_id,
+        targets: vec![WorkspaceTarget {
+            target,
+            wait,
+            timeout_ms: None,
+            script_path: None,
+            script_parameters: None,
+            bundled_script,
+        }],
+        ..Workspace::default()
+    })
+}
+
+/// Decodes `%XX` escapes and `+` as a space.
+fn percent_decode(value: &str) -> Result<String, AppError> {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' => {
+                let byte = value
+                    .get(index + 1..index + 3)
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| {
+                        AppError::InvalidArgument(format!("Bad escape in link value {value}"))
+                    })?;
+                decoded.push(byte);
+                index += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded)
+        .map_err(|_| AppError::InvalidArgument(format!("Link value is not UTF-8: {value}")))
+}
+
+fn load_workspace(path: &str) -> Result<Workspace, AppError> {
+    let path = PathBuf::from(path);
+    let mut workspace: Workspace = storage::read_json(&path)?
+        .ok_or_else(|| AppError::StorageError(format!("{} does not exist", path.display())))?;
+
+    if let Some(base) = path.parent() {
+        for target in workspace.targets.iter_mut() {
+            if let Some(script) = target.script_path.as_mut() {
+                if PathBuf::from(&*script).is_relative() {
+                    *script = base.join(&*script).to_string_lossy().into_owned();
+                }
+            }
+        }
+    }
+    Ok(workspace)
+}
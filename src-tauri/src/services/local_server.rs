@@ -0,0 +1,334 @@
+use std::fs::File;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::services::{server_binaries, storage};
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:27042";
+const LOG_FILE: &str = "frida-server.log";
+const PROBE_TIMEOUT: Duration = Duration::from_millis(300);
+
+#[cfg(target_os = "windows")]
+const SERVER_BINARY: &str = "frida-server.exe";
+#[cfg(not(target_os = "windows"))]
+const SERVER_BINARY: &str = "frida-server";
+
+// ─── Local server types ───────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LocalServerOptions {
+    /// frida-server binary to run. Defaults to the bundled copy in the data
+    /// dir, then a downloaded one matching CARF's Frida, then `frida-server`
+    /// on `PATH`.
+    pub binary_path: Option<String>,
+    /// `host:port` to listen on. Defaults to `127.0.0.1:27042`.
+    pub listen: Option<String>,
+    /// Run as root through the platform's graphical password prompt
+    /// (`pkexec` on Linux, an administrator dialog on macOS).
+    pub elevated: bool,
+}
+
+/// Mirrors frontend `LocalServerStatus`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalServerStatus {
+    pub running: bool,
+    /// Whether something accepts connections on `listen`.
+    pub reachable: bool,
+    pub pid: Option<u32>,
+    pub binary_path: Option<String>,
+    pub listen: String,
+    pub elevated: bool,
+    pub started_at: Option<u64>,
+    /// Exit status of the last server CARF started, once it has stopped.
+    pub last_exit: Option<String>,
+    pub log_path: Option<String>,
+}
+
+// ─── LocalServerService ───────────────────────────────────────────────────────
+
+/// Manages a frida-server on this machine for platforms where injection into
+/// other processes needs a privileged helper.
+pub struct LocalServerService {
+    /// Only set for unprivileged servers; elevated ones daemonize and are
+    /// tracked by binary path instead.
+    child: Option<Child>,
+    binary_path: Option<PathBuf>,
+    listen: String,
+    elevated: bool,
+    started_at: Option<u64>,
+    last_exit: Option<String>,
+}
+
+impl LocalServerService {
+    pub fn new() -> Self {
+        Self {
+            child: None,
+            binary_path: None,
+            listen: DEFAULT_LISTEN.to_string(),
+            elevated: false,
+            started_at: None,
+            last_exit: None,
+        }
+    }
+
+    pub fn start(&mut self, options: LocalServerOptions) -> Result<LocalServerStatus, AppError> {
+        if self.status().running {
+            return Err(AppError::LocalServerError(
+                "frida-server is already running".to_string(),
+            ));
+        }
+
+        let binary = resolve_binary(options.binary_path.as_deref())?;
+        let listen = options
+            .listen
+            .map(|listen| listen.trim().to_string())
+            .filter(|listen| !listen.is_empty())
+            .unwrap_or_else(|| DEFAULT_LISTEN.to_string());
+        validate_listen(&listen)?;
+
+        if options.elevated {
+            run_elevated(&binary, &["-D", "-l", &listen])?;
+        } else {
+            let log_path = storage::data_file(LOG_FILE)?;
+            let log = File::create(&log_path).map_err(|error| {
+                AppError::StorageError(format!("failed to create {}: {error}", log_path.display()))
+            })?;
+            let stderr = log.try_clone().map_err(|error| {
+                AppError::StorageError(format!("failed to open {}: {error}", log_path.display()))
+            })?;
+
+            let child = Command::new(&binary)
+                .args(["-l", &listen])
+                .stdin(Stdio::null())
+                .stdout(log)
+                .stderr(stderr)
+                .spawn()
+                .map_err(|error| {
+                    AppError::LocalServerError(format!(
+                        "failed to start {}: {error}",
+                        binary.display()
+                    ))
+                })?;
+            self.child = Some(child);
+        }
+
+        self.binary_path = Some(binary);
+        self.listen = listen;
+        self.elevated = options.elevated;
+        self.started_at = Some(storage::now_millis());
+        self.last_exit = None;
+        Ok(self.status())
+    }
+
+    pub fn stop(&mut self) -> Result<LocalServerStatus, AppError> {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let status = child.wait().map_err(|error| {
+                AppError::LocalServerError(format!("failed to stop frida-server: {error}"))
+            })?;
+            self.last_exit = Some(status.to_string());
+        } else if self.elevated {
+            if let Some(binary) = self.binary_path.clone() {
+                if find_pid(&binary).is_some() {
+                    let pattern = binary.to_string_lossy().into_owned();
+                    run_elevated(Path::new("pkill"), &["-f", &pattern])?;
+                    self.last_exit = Some("stopped".to_string());
+                }
+            }
+        }
+
+        self.started_at = None;
+        Ok(self.status())
+    }
+
+    /// Reports whether the server CARF started is still alive. Reaps an
+    /// unprivileged server that exited on its own so the exit status shows
+    /// up in `last_exit`.
+    pub fn status(&mut self) -> LocalServerStatus {
+        let mut pid = None;
+
+        if let Some(child) = self.child.as_mut() {
+            match child.try_wait() {
+                Ok(None) => pid = Some(child.id()),
+                Ok(Some(status)) => {
+                    self.last_exit = Some(status.to_string());
+                    self.child = None;
+                    self.started_at = None;
+                }
+                Err(error) => {
+                    self.last_exit = Some(error.to_string());
+                    self.child = None;
+                    self.started_at = None;
+                }
+            }
+        } else if self.elevated {
+            pid = self.binary_path.as_deref().and_then(find_pid);
+        }
+
+        LocalServerStatus {
+            running: pid.is_some(),
+            reachable: is_reachable(&self.listen),
+            pid,
+            binary_path: self
+                .binary_path
+                .as_ref()
+                .map(|path| path.to_string_lossy().into_owned()),
+            listen: self.listen.clone(),
+            elevated: self.elevated,
+            started_at: self.started_at,
+            last_exit: self.last_exit.clone(),
+            log_path: storage::data_file(LOG_FILE)
+                .ok()
+                .map(|path| path.to_string_lossy().into_owned()),
+        }
+    }
+}
+
+impl Default for LocalServerService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for LocalServerService {
+    fn drop(&mut self) {
+        // Unprivileged servers are tied to the app's lifetime; elevated ones
+        // are daemonized and deliberately left running.
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+// ─── Helpers ──────────────────────────────────────────────────────────────────
+
+fn resolve_binary(explicit: Option<&str>) -> Result<PathBuf, AppError> {
+    if let Some(path) = explicit.map(str::trim).filter(|path| !path.is_empty()) {
+        let path = PathBuf::from(path);
+        return if path.is_file() {
+            Ok(path)
+        } else {
+            Err(AppError::LocalServerError(format!(
+                "{} does not exist",
+                path.display()
+            )))
+        };
+    }
+
+    let bundled = storage::data_dir()?.join("bin").join(SERVER_BINARY);
+    if bundled.is_file() {
+        return Ok(bundled);
+    }
+    let (os, arch) = server_binaries::host_target();
+    if let Some(downloaded) = server_binaries::installed_path(frida::Frida::version(), os, arch) {
+        return Ok(downloaded);
+    }
+
+    std::env::var_os("PATH")
+        .and_then(|paths| {
+            std::env::split_paths(&paths)
+                .map(|dir| dir.join(SERVER_BINARY))
+                .find(|candidate| candidate.is_file())
+        })
+        .ok_or_else(|| {
+            AppError::LocalServerError(format!(
+                "{SERVER_BINARY} not found; place it at {} or pass binaryPath",
+                bundled.display()
+            ))
+        })
+}
+
+fn validate_listen(listen: &str) -> Result<(), AppError> {
+    // Reject anything that is not a plain host:port so the value can be
+    // handed to an elevated shell without quoting concerns.
+    let valid = listen
+        .chars()
+        .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | ':' | '-' | '[' | ']'));
+    if valid && listen.contains(':') {
+        Ok(())
+    } else {
+        Err(AppError::InvalidAddress(listen.to_string()))
+    }
+}
+
+fn is_reachable(listen: &str) -> bool {
+    listen
+        .to_socket_addrs()
+        .ok()
+        .and_then(|mut addrs| addrs.next())
+        .map(|addr: SocketAddr| TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok())
+        .unwrap_or(false)
+}
+
+fn find_pid(binary: &Path) -> Option<u32> {
+    let output = Command::new("pgrep").arg("-f").arg(binary).output().ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.trim().parse().ok())
+}
+
+/// Runs `program args…` as root. A GUI app has no terminal for `sudo` to
+/// prompt on, so this goes through the desktop's own password dialog and only
+/// falls back to `sudo -n` (cached credentials) when none is available.
+fn run_elevated(program: &Path, args: &[&str]) -> Result<(), AppError> {
+    let output = if cfg!(target_os = "macos") {
+        let command = std::iter::once(program.to_string_lossy().into_owned())
+            .chain(args.iter().map(|arg| arg.to_string()))
+            .map(|arg| shell_quote(&arg))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let script = format!(
+            "do shell script \"{}\" with administrator privileges",
+            command.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        Command::new("osascript").args(["-e", &script]).output()
+    } else if cfg!(target_os = "linux") {
+        match Command::new("pkexec").arg(program).args(args).output() {
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Command::new("sudo")
+                .arg("-n")
+                .arg(program)
+                .args(args)
+                .output(),
+            other => other,
+        }
+    } else {
+        return Err(AppError::LocalServerError(
+            "elevated frida-server is only supported on Linux and macOS".to_string(),
+        ));
+    };
+
+    let output = output.map_err(|error| {
+        AppError::LocalServerError(format!("failed to request elevation: {error}"))
+    })?;
+    check_elevated_output(&output)
+}
+
+fn check_elevated_output(output: &Output) -> Result<(), AppError> {
+    if output.status.success() {
+        return Ok(());
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    let message = if stderr.contains("password is required") {
+        "sudo needs a password; run `sudo -v` in a terminal first or install pkexec".to_string()
+    } else if stderr.contains("User canceled") || output.status.code() == Some(126) {
+        "elevation was cancelled".to_string()
+    } else if stderr.is_empty() {
+        format!("elevated command failed ({})", output.status)
+    } else {
+        stderr
+    };
+    Err(AppError::LocalServerError(message))
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
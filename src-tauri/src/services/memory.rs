@@ -0,0 +1,450 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::bookmarks::{self, ModuleMap};
+use crate::services::frida::FridaService;
+use crate::services::inspector::{self, PointerLayout};
+
+/// Bytes compared when the caller does not ask for a size: one hex viewer page.
+const DEFAULT_MAPPED_FILE_WINDOW: usize = 4096;
+const DEFAULT_HEXDUMP_SIZE: usize = 256;
+const MAX_HEXDUMP_SIZE: usize = 64 * 1024;
+const DEFAULT_HEXDUMP_WIDTH: usize = 16;
+const MAX_HEXDUMP_WIDTH: usize = 64;
+
+// ─── Batch write types ────────────────────────────────────────────────────────
+
+/// One typed write. `value_type` is one of `u8`…`u64`, `i8`…`i64`, `f32`,
+/// `f64`, `pointer`, `bytes` (hex), `utf8` or `utf16`; 64-bit values and
+/// pointers may be passed as strings to keep their precision.
+///
+/// Mirrors frontend `ValueWrite`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueWrite {
+    pub address: String,
+    #[serde(rename = "type")]
+    pub value_type: String,
+    pub value: Value,
+}
+
+/// Mirrors frontend `BatchWriteReport`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchWriteReport {
+    pub written: usize,
+    pub failed: Vec<FailedWrite>,
+    /// An atomic batch hit a failure and every earlier write was reverted.
+    pub rolled_back: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedWrite {
+    pub index: usize,
+    pub address: String,
+    pub error: String,
+}
+
+// ─── Freeze types ─────────────────────────────────────────────────────────────
+
+/// A value the agent keeps writing back whenever the target changes it.
+///
+/// Mirrors frontend `FrozenValue`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrozenValue {
+    pub id: String,
+    pub address: String,
+    #[serde(rename = "type")]
+    pub value_type: String,
+    pub value: Value,
+    /// Times the target changed the value and it was restored.
+    pub corrections: u64,
+    /// Last failed write, cleared by the next successful one.
+    pub last_error: Option<String>,
+}
+
+/// Mirrors frontend `FreezeState`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FreezeState {
+    pub interval_ms: u32,
+    pub values: Vec<FrozenValue>,
+}
+
+#[derive(Deserialize)]
+struct UnfreezeResponse {
+    removed: usize,
+}
+
+// ─── Watch types ──────────────────────────────────────────────────────────────
+
+const WATCH_KINDS: &[&str] = &["read", "write", "execute"];
+
+/// A data watchpoint. Each hit is emitted as `carf://memory/watch-hit` with
+/// the accessing instruction and a backtrace.
+///
+/// Mirrors frontend `MemoryWatch`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryWatch {
+    pub id: String,
+    pub address: String,
+    pub size: usize,
+    /// `read` (which also catches writes), `write` or `execute`.
+    pub on: String,
+    /// `hardware` for debug registers, `page` for the MemoryAccessMonitor
+    /// fallback used for ranges the hardware cannot cover.
+    pub mode: String,
+    pub hits: u64,
+}
+
+// ─── Mapped file types ────────────────────────────────────────────────────────
+
+/// The same span of a read-only file mapping read from memory and from the
+/// backing file on disk. `file_offset` is where `address` lives in the file.
+///
+/// Mirrors frontend `MappedFileSlice`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MappedFileSlice {
+    pub path: String,
+    pub address: String,
+    pub range_base: String,
+    pub file_offset: u64,
+    pub size: usize,
+    /// Bytes actually present in the file; the rest of `disk` is zero fill.
+    pub disk_size: usize,
+    /// Hex of the bytes in memory.
+    pub memory: String,
+    /// Hex of the bytes on disk.
+    pub disk: String,
+    pub differences: Vec<ByteSpan>,
+}
+
+/// A run of differing bytes, as an offset from `address`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ByteSpan {
+    pub offset: usize,
+    pub length: usize,
+}
+
+// ─── Hexdump types ────────────────────────────────────────────────────────────
+
+/// Mirrors frontend `HexdumpOptions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HexdumpOptions {
+    /// Bytes per row, 16 by default and at most 64.
+    pub width: usize,
+    /// Look for aligned pointers into loaded modules.
+    pub annotate_pointers: bool,
+}
+
+impl Default for HexdumpOptions {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_HEXDUMP_WIDTH,
+            annotate_pointers: true,
+        }
+    }
+}
+
+/// Mirrors frontend `Hexdump`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hexdump {
+    pub address: String,
+    pub size: usize,
+    pub pointer_size: usize,
+    pub rows: Vec<HexdumpRow>,
+}
+
+/// Mirrors frontend `HexdumpRow`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HexdumpRow {
+    /// Offset of the row's first byte from the dump's address.
+    pub offset: usize,
+    pub address: String,
+    /// Space-separated byte pairs.
+    pub hex: String,
+    /// Printable ASCII, `.` for everything else.
+    pub ascii: String,
+    pub pointers: Vec<PointerAnnotation>,
+}
+
+/// A pointer-aligned value in a row that points into a loaded module.
+///
+/// Mirrors frontend `PointerAnnotation`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerAnnotation {
+    /// Offset from the dump's address, not from the row.
+    pub offset: usize,
+    pub value: String,
+    /// `module!0x...`
+    pub location: String,
+}
+
+// ─── Batch writes ─────────────────────────────────────────────────────────────
+
+/// Applies every write in one agent round-trip. All values are encoded before
+/// the first byte is written; with `atomic`, a failed write also reverts the
+/// ones before it.
+pub fn write_values_batch(
+    frida: &mut FridaService,
+    session_id: &str,
+    writes: Vec<ValueWrite>,
+    atomic: bool,
+) -> Result<BatchWriteReport, AppError> {
+    if writes.is_empty() {
+        return Ok(BatchWriteReport {
+            written: 0,
+            failed: Vec::new(),
+            rolled_back: false,
+        });
+    }
+
+    let response = frida.rpc_call(
+        session_id,
+        "writeValuesBatch",
+        json!({ "writes": writes, "atomic": atomic }),
+    )?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected writeValuesBatch response: {error}"))
+    })
+}
+
+// ─── Freezing ─────────────────────────────────────────────────────────────────
+
+/// Freezes values inside the agent, which re-checks them every
+/// `interval_ms` (default 1ms) and writes them back when they changed. No
+/// host round trip is involved per write, so freezes hold while the host is
+/// stalled. Freezing an already frozen address replaces its value.
+pub fn freeze_values(
+    frida: &mut FridaService,
+    session_id: &str,
+    values: Vec<ValueWrite>,
+    interval_ms: Option<u32>,
+) -> Result<Vec<FrozenValue>, AppError> {
+    let response = frida.rpc_call(
+        session_id,
+        "freezeValues",
+        json!({ "values": values, "intervalMs": interval_ms }),
+    )?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected freezeValues response: {error}"))
+    })
+}
+
+/// Stops freezing the given entries, or every entry when `ids` is `None`.
+/// Returns how many were removed.
+pub fn unfreeze_values(
+    frida: &mut FridaService,
+    session_id: &str,
+    ids: Option<Vec<String>>,
+) -> Result<usize, AppError> {
+    let response = frida.rpc_call(session_id, "unfreezeValues", json!({ "ids": ids }))?;
+    serde_json::from_value::<UnfreezeResponse>(response)
+        .map(|response| response.removed)
+        .map_err(|error| {
+            AppError::AgentRpcError(format!("unexpected unfreezeValues response: {error}"))
+        })
+}
+
+pub fn list_frozen_values(
+    frida: &mut FridaService,
+    session_id: &str,
+) -> Result<FreezeState, AppError> {
+    let response = frida.rpc_call(session_id, "listFrozenValues", json!({}))?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected listFrozenValues response: {error}"))
+    })
+}
+
+// ─── Watches ──────────────────────────────────────────────────────────────────
+
+/// Watches `size` bytes at `address` for `on` accesses. Up to eight bytes
+/// use a hardware watchpoint on every thread; larger ranges, or when the
+/// debug registers are taken, fall back to page guards.
+pub fn watch(
+    frida: &mut FridaService,
+    session_id: &str,
+    address: &str,
+    size: usize,
+    on: &str,
+) -> Result<MemoryWatch, AppError> {
+    bookmarks::parse_hex(address)
+        .ok_or_else(|| AppError::InvalidArgument(format!("{address:?} is not a 0x address")))?;
+    if size == 0 {
+        return Err(AppError::InvalidArgument(
+            "watch size must not be 0".to_string(),
+        ));
+    }
+    if !WATCH_KINDS.contains(&on) {
+        return Err(AppError::InvalidArgument(format!(
+            "watch kind must be one of {}, got {on:?}",
+            WATCH_KINDS.join(", ")
+        )));
+    }
+
+    let response = frida.rpc_call(
+        session_id,
+        "startMemoryWatch",
+        json!({ "address": address, "size": size, "on": on }),
+    )?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected startMemoryWatch response: {error}"))
+    })
+}
+
+/// Removes a watch and returns it with its final hit count.
+pub fn unwatch(
+    frida: &mut FridaService,
+    session_id: &str,
+    watch_id: &str,
+) -> Result<MemoryWatch, AppError> {
+    let response = frida.rpc_call(session_id, "stopMemoryWatch", json!({ "id": watch_id }))?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected stopMemoryWatch response: {error}"))
+    })
+}
+
+pub fn list_watches(
+    frida: &mut FridaService,
+    session_id: &str,
+) -> Result<Vec<MemoryWatch>, AppError> {
+    let response = frida.rpc_call(session_id, "listMemoryWatches", json!({}))?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected listMemoryWatches response: {error}"))
+    })
+}
+
+// ─── Mapped files ─────────────────────────────────────────────────────────────
+
+/// Opens the file behind a read-only mapping and reads the span at `address`
+/// from both disk and memory, so patches the target made in memory show up
+/// as differences.
+pub fn read_mapped_file(
+    frida: &mut FridaService,
+    session_id: &str,
+    address: &str,
+    size: Option<usize>,
+) -> Result<MappedFileSlice, AppError> {
+    let response = frida.rpc_call(
+        session_id,
+        "readMappedFile",
+        json!({
+            "address": address,
+            "size": size.unwrap_or(DEFAULT_MAPPED_FILE_WINDOW),
+        }),
+    )?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected readMappedFile response: {error}"))
+    })
+}
+
+// ─── Hexdump ──────────────────────────────────────────────────────────────────
+
+/// Reads `size` bytes (default 256, at most 64 KiB) at `address` and lays
+/// them out as hexdump rows. Pointer-aligned values that land inside a
+/// loaded module are annotated with their `module!offset`.
+pub fn hexdump(
+    frida: &mut FridaService,
+    session_id: &str,
+    address: &str,
+    size: Option<usize>,
+    options: HexdumpOptions,
+) -> Result<Hexdump, AppError> {
+    let base = bookmarks::parse_hex(address)
+        .ok_or_else(|| AppError::InvalidArgument(format!("{address:?} is not a 0x address")))?;
+    let size = size.unwrap_or(DEFAULT_HEXDUMP_SIZE);
+    if size == 0 || size > MAX_HEXDUMP_SIZE {
+        return Err(AppError::InvalidArgument(format!(
+            "size must be between 1 and {MAX_HEXDUMP_SIZE}, got {size}"
+        )));
+    }
+    if options.width == 0 || options.width > MAX_HEXDUMP_WIDTH {
+        return Err(AppError::InvalidArgument(format!(
+            "width must be between 1 and {MAX_HEXDUMP_WIDTH}, got {}",
+            options.width
+        )));
+    }
+
+    let response = frida.rpc_call(
+        session_id,
+        "readMemory",
+        json!({ "address": address, "size": size }),
+    )?;
+    let bytes = inspector::decode_hex(response.as_str().unwrap_or_default())
+        .ok_or_else(|| AppError::AgentRpcError("readMemory returned invalid hex".to_string()))?;
+    let layout = inspector::pointer_layout(frida, session_id)?;
+    let pointers = if options.annotate_pointers {
+        find_module_pointers(&ModuleMap::load(frida, session_id)?, base, &bytes, layout)
+    } else {
+        Vec::new()
+    };
+
+    let rows = bytes
+        .chunks(options.width)
+        .enumerate()
+        .map(|(index, row)| {
+            let offset = index * options.width;
+            HexdumpRow {
+                offset,
+                address: format!("{:#x}", base.wrapping_add(offset as u64)),
+                hex: row
+                    .iter()
+                    .map(|byte| format!("{byte:02x}"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+                ascii: row
+                    .iter()
+                    .map(|&byte| match byte {
+                        0x20..=0x7e => byte as char,
+                        _ => '.',
+                    })
+                    .collect(),
+                pointers: pointers
+                    .iter()
+                    .filter(|pointer| (offset..offset + row.len()).contains(&pointer.offset))
+                    .cloned()
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Ok(Hexdump {
+        address: format!("{base:#x}"),
+        size: bytes.len(),
+        pointer_size: layout.size,
+        rows,
+    })
+}
+
+/// Pointer-aligned (by absolute address) values that fall inside a module.
+fn find_module_pointers(
+    modules: &ModuleMap,
+    base: u64,
+    bytes: &[u8],
+    layout: PointerLayout,
+) -> Vec<PointerAnnotation> {
+    let misalignment = (base % layout.size as u64) as usize;
+    let first = (layout.size - misalignment) % layout.size;
+    (first..bytes.len())
+        .step_by(layout.size)
+        .filter_map(|offset| {
+            let value = inspector::read_pointer(&bytes[offset..], layout)?;
+            let location = modules.locate(value)?;
+            Some(PointerAnnotation {
+                offset,
+                value: format!("{value:#x}"),
+                location,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,186 @@
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::{bookmarks, file_transfer};
+
+// ─── Memory dump types ────────────────────────────────────────────────────────
+
+/// `carf://memory-dump/progress`, emitted per chunk and once more, with
+/// `done` set, when a dump completes.
+///
+/// Mirrors frontend `MemoryDumpProgress`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryDumpProgress {
+    /// Pass it to `cancel_operation` to stop the dump.
+    pub dump_id: String,
+    pub address: String,
+    pub path: String,
+    pub bytes_written: u64,
+    pub total_bytes: u64,
+    pub done: bool,
+}
+
+/// A finished dump.
+///
+/// Mirrors frontend `MemoryDump`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryDump {
+    pub dump_id: String,
+    pub address: String,
+    pub size: u64,
+    pub path: String,
+    /// Set for `memory_dump_module`.
+    pub module: Option<String>,
+    /// Bytes on pages that could not be read, written as zeros.
+    pub unreadable_bytes: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Deserialize)]
+struct Chunk {
+    unreadable: u64,
+}
+
+#[derive(Deserialize)]
+struct ModuleRange {
+    base: String,
+    size: u64,
+}
+
+/// Tracks one dump and builds its progress events and result.
+pub struct Dump {
+    id: String,
+    address: u64,
+    size: u64,
+    path: String,
+    module: Option<String>,
+    started: Instant,
+}
+
+impl Dump {
+    pub fn new(id: &str, address: u64, size: u64, path: &str, module: Option<String>) -> Self {
+        Self {
+            id: id.to_string(),
+            address,
+            size,
+            path: path.to_string(),
+            module,
+            started: Instant::now(),
+        }
+    }
+
+    pub fn progress(&self, bytes_written: u64) -> MemoryDumpProgress {
+        MemoryDumpProgress {
+            dump_id: self.id.clone(),
+            address: format!("{:#x}", self.address),
+            path: self.path.clone(),
+            bytes_written,
+            total_bytes: self.size,
+            done: false,
+        }
+    }
+
+    fn finish(&self, unreadable_bytes: u64) -> MemoryDump {
+        MemoryDump {
+            dump_id: self.id.clone(),
+            address: format!("{:#x}", self.address),
+            size: self.size,
+            path: self.path.clone(),
+            module: self.module.clone(),
+            unreadable_bytes,
+            duration_ms: self.started.elapsed().as_millis() as u64,
+        }
+    }
+}
+
+impl MemoryDump {
+    /// The `done` progress event that closes a dump.
+    pub fn progress(&self) -> MemoryDumpProgress {
+        MemoryDumpProgress {
+            dump_id: self.dump_id.clone(),
+            address: self.address.clone(),
+            path: self.path.clone(),
+            bytes_written: self.size,
+            total_bytes: self.size,
+            done: true,
+        }
+    }
+}
+
+// ─── Dumping ──────────────────────────────────────────────────────────────────
+
+/// Parses a `0x` address and rejects empty or wrapping ranges.
+pub fn parse_range(address: &str, size: u64) -> Result<u64, AppError> {
+    let base = bookmarks::parse_hex(address)
+        .ok_or_else(|| AppError::InvalidArgument(format!("{address:?} is not a 0x address")))?;
+    if size == 0 || base.checked_add(size).is_none() {
+        return Err(AppError::InvalidArgument(format!(
+            "invalid dump range {address} + {size:#x}"
+        )));
+    }
+    Ok(base)
+}
+
+/// Base and size of a loaded module, looked up by name.
+pub fn module_range(
+    mut rpc: impl FnMut(&str, Value) -> Result<Value, AppError>,
+    module: &str,
+) -> Result<(u64, u64), AppError> {
+    let response = rpc("findModuleByName", json!({ "name": module }))?;
+    if response.is_null() {
+        return Err(AppError::InvalidArgument(format!(
+            "Module {module:?} is not loaded"
+        )));
+    }
+    let range: ModuleRange = serde_json::from_value(response)
+        .map_err(|error| AppError::AgentRpcError(error.to_string()))?;
+    let base = bookmarks::parse_hex(&range.base).ok_or_else(|| {
+        AppError::AgentRpcError(format!("findModuleByName returned base {:?}", range.base))
+    })?;
+    Ok((base, range.size))
+}
+
+/// Streams `dump`'s range to its path one `readMemoryChunk` at a time,
+/// through `file_transfer::receive_to_file`. `rpc_read` returns each chunk's
+/// bytes beside the reply.
+pub fn dump_via_agent(
+    dump: &Dump,
+    mut rpc_read: impl FnMut(&str, Value) -> Result<(Value, Vec<u8>), AppError>,
+    is_cancelled: impl Fn() -> bool,
+    mut progress: impl FnMut(MemoryDumpProgress),
+) -> Result<MemoryDump, AppError> {
+    let mut unreadable = 0;
+    file_transfer::receive_to_file(
+        Path::new(&dump.path),
+        dump.size,
+        &format!("dump of {:#x}", dump.address),
+        |offset, size| {
+            let (reply, bytes) = rpc_read(
+                "readMemoryChunk",
+                json!({
+                    "address": format!("{:#x}", dump.address + offset),
+                    "size": size,
+                }),
+            )?;
+            let chunk: Chunk = serde_json::from_value(reply)
+                .map_err(|error| AppError::AgentRpcError(error.to_string()))?;
+            if bytes.len() as u64 != size {
+                return Err(AppError::AgentRpcError(format!(
+                    "readMemoryChunk returned {} of {size} bytes",
+                    bytes.len()
+                )));
+            }
+            unreadable += chunk.unreadable;
+            Ok(bytes)
+        },
+        is_cancelled,
+        |written| progress(dump.progress(written)),
+    )?;
+    Ok(dump.finish(unreadable))
+}
@@ -1,4 +1,35 @@
 pub mod adb;
 pub mod ai;
+pub mod app_log;
+pub mod audit;
+pub mod backups;
+pub mod bookmarks;
+pub mod file_transfer;
 pub mod frida;
+pub mod gadget;
+pub mod hooks;
+pub mod hotkeys;
+pub mod inspector;
+pub mod launch;
+pub mod local_server;
+pub mod memory;
+pub mod memory_dump;
+pub mod perf;
+pub mod plugins;
+pub mod pointer_scan;
+pub mod process_metrics;
+pub mod remote_devices;
+pub mod scanner;
+pub mod server_binaries;
+pub mod script_catalog;
+pub mod script_compiler;
 pub mod session_manager;
+pub mod session_restore;
+pub mod settings;
+pub mod stalker;
+pub mod storage;
+pub mod target_profiles;
+pub mod typed_memory;
+pub mod value_format;
+pub mod value_snapshots;
+pub mod variables;
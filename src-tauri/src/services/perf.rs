@@ -0,0 +1,256 @@
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::services::frida::{EventBatcher, MessageFilter, MessageHistoryStore};
+use crate::services::stalker::TraceStore;
+use crate::services::value_format::{format_values, Radix, ValueFormat};
+use crate::state::BridgeEvent;
+
+const DEFAULT_SELFTEST_EVENTS: usize = 100_000;
+/// Caps the synthetic workload so a self-test stays within a few seconds.
+const MAX_SELFTEST_EVENTS: usize = 1_000_000;
+const MIN_SELFTEST_EVENTS: usize = 1_000;
+const SELFTEST_BATCH: usize = 500;
+const SELFTEST_QUERIES: usize = 200;
+/// Spacing between synthetic script events: one event name at exactly the
+/// batcher's per-second limit, so batching is measured rather than dropping.
+const SCRIPT_EVENT_INTERVAL: Duration = Duration::from_micros(200);
+
+// ─── Self-test types ──────────────────────────────────────────────────────────
+
+/// Mirrors frontend `PerfSelftestOptions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PerfSelftestOptions {
+    /// Synthetic events fed through each store. Defaults to 100 000.
+    pub events: Option<usize>,
+}
+
+/// Mirrors frontend `PerfMeasurement`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerfMeasurement {
+    /// `trace_store`, `event_batching`, `message_history`, `message_query`
+    /// or `value_format`.
+    pub name: String,
+    /// `events`, `queries` or `values`.
+    pub unit: String,
+    pub items: u64,
+    pub elapsed_ms: f64,
+    pub per_second: f64,
+}
+
+/// Mirrors frontend `PerfReport`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerfReport {
+    pub measurements: Vec<PerfMeasurement>,
+}
+
+// ─── Workloads ────────────────────────────────────────────────────────────────
+
+/// `carf://stalker/event` payloads as the agent sends them, with runs of
+/// repeated sites so coalescing is exercised too.
+pub fn synthetic_event_batches(session_id: &str, events: usize, batch: usize) -> Vec<Value> {
+    let batch = batch.max(1);
+    (0..events)
+        .collect::<Vec<_>>()
+        .chunks(batch)
+        .map(|indices| {
+            let events = indices
+                .iter()
+                .map(|index| {
+                    let site = index / 3;
+                    json!({
+                        "threadId": 1,
+                        "type": if site % 2 == 0 { "call" } else { "ret" },
+                        "from": format!("0x{:x}", 0x1000 + site * 4),
+                        "to": format!("0x{:x}", 0x8000 + site * 16),
+                        "fromModule": "libapp.so",
+                        "toModule": "libapp.so",
+                        "fromSymbol": null,
+                        "toSymbol": null,
+                        "depth": (site % 16) as i64,
+                        "count": 1,
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({ "sessionId": session_id, "events": events })
+        })
+        .collect()
+}
+
+/// Feeds stalker event batches through the buffer the Frida actor uses.
+pub fn record_trace_batches(store: &mut TraceStore, batches: &[Value]) {
+    for batch in batches {
+        store.record(batch);
+    }
+}
+
+/// Hook hits and console messages as a chatty tracing agent emits them.
+pub fn synthetic_script_events(session_id: &str, events: usize) -> Vec<BridgeEvent> {
+    (0..events)
+        .map(|index| {
+            if index % 4 == 0 {
+                BridgeEvent {
+                    name: "carf://console/message".to_string(),
+                    payload: json!({
+                        "sessionId": session_id,
+                        "level": "log",
+                        "text": format!("tick {index}"),
+                    }),
+                }
+            } else {
+                BridgeEvent {
+                    name: "carf://hook/event".to_string(),
+                    payload: json!({
+                        "sessionId": session_id,
+                        "hookId": format!("hook-{}", index % 8),
+                        "target": format!("0x{:x}", 0x7f00_0000_1000u64 + (index % 8) as u64 * 16),
+                        "args": [format!("0x{index:x}"), index, "libapp.so"],
+                        "retval": null,
+                        "threadId": 1,
+                    }),
+                }
+            }
+        })
+        .collect()
+}
+
+/// Runs `events` through `batcher` on a clock that advances `interval` per
+/// event, flushing whenever the actor would. Returns how many events were
+/// emitted.
+pub fn batch_script_events(
+    batcher: &mut EventBatcher,
+    events: Vec<BridgeEvent>,
+    interval: Duration,
+) -> usize {
+    let mut now = Instant::now();
+    let mut emitted = 0;
+    for event in events {
+        now += interval;
+        batcher.push(event, now);
+        if batcher.is_due(now) {
+            emitted += batcher.flush(now).len();
+        }
+    }
+    emitted + batcher.flush(now).len()
+}
+
+/// Records `events` the way the actor does for every emitted script event.
+pub fn record_message_history(store: &mut MessageHistoryStore, events: &[BridgeEvent]) {
+    for event in events {
+        store.record(&event.name, &event.payload);
+    }
+}
+
+/// Pages through the whole history with a text filter, as the message
+/// search view does. Returns the number of matches.
+pub fn query_message_history(store: &MessageHistoryStore, text: &str) -> usize {
+    let filter = MessageFilter {
+        text: Some(text.to_string()),
+        ..MessageFilter::default()
+    };
+    let mut since = 0;
+    let mut matches = 0;
+    loop {
+        let page = store.query(&filter, since, None);
+        matches += page.messages.len();
+        if !page.has_more {
+            return matches;
+        }
+        since = page.next_cursor;
+    }
+}
+
+/// Raw values as table pages resolve them: integers, `0x` strings and
+/// floats.
+pub fn synthetic_values(count: usize) -> Vec<Value> {
+    (0..count)
+        .map(|index| match index % 3 {
+            0 => json!(index * 125),
+            1 => json!(format!("0x{:x}", 0x7f00_0000_0000u64 + index as u64)),
+            _ => json!(index as f64 / 7.0),
+        })
+        .collect()
+}
+
+/// A millisecond counter shown in seconds, the common library format.
+pub fn seconds_format() -> ValueFormat {
+    ValueFormat {
+        radix: Radix::Dec,
+        scale: Some(1000.0),
+        decimals: Some(2),
+        unit: Some("s".to_string()),
+        ..ValueFormat::default()
+    }
+}
+
+// ─── Self-test ────────────────────────────────────────────────────────────────
+
+/// Runs each backend hot path once on synthetic data and reports its
+/// throughput on this machine.
+pub fn perf_selftest(options: &PerfSelftestOptions) -> PerfReport {
+    let events = options
+        .events
+        .unwrap_or(DEFAULT_SELFTEST_EVENTS)
+        .clamp(MIN_SELFTEST_EVENTS, MAX_SELFTEST_EVENTS);
+    let mut measurements = Vec::new();
+
+    let batches = synthetic_event_batches("selftest", events, SELFTEST_BATCH);
+    let mut trace_store = TraceStore::default();
+    let started = Instant::now();
+    record_trace_batches(&mut trace_store, &batches);
+    measurements.push(measurement("trace_store", "events", events, started));
+
+    let script_events = synthetic_script_events("selftest", events);
+    let mut batcher = EventBatcher::default();
+    let started = Instant::now();
+    std::hint::black_box(batch_script_events(
+        &mut batcher,
+        script_events.clone(),
+        SCRIPT_EVENT_INTERVAL,
+    ));
+    measurements.push(measurement("event_batching", "events", events, started));
+
+    let mut history = MessageHistoryStore::default();
+    let started = Instant::now();
+    record_message_history(&mut history, &script_events);
+    measurements.push(measurement("message_history", "events", events, started));
+
+    let started = Instant::now();
+    for _ in 0..SELFTEST_QUERIES {
+        std::hint::black_box(query_message_history(&history, "hook-3"));
+    }
+    measurements.push(measurement(
+        "message_query",
+        "queries",
+        SELFTEST_QUERIES,
+        started,
+    ));
+
+    let values = synthetic_values(events);
+    let format = seconds_format();
+    let started = Instant::now();
+    std::hint::black_box(format_values(&values, &format));
+    measurements.push(measurement("value_format", "values", events, started));
+
+    PerfReport { measurements }
+}
+
+fn measurement(name: &str, unit: &str, items: usize, started: Instant) -> PerfMeasurement {
+    let elapsed = started.elapsed().as_secs_f64();
+    PerfMeasurement {
+        name: name.to_string(),
+        unit: unit.to_string(),
+        items: items as u64,
+        elapsed_ms: elapsed * 1000.0,
+        per_second: if elapsed > 0.0 {
+            items as f64 / elapsed
+        } else {
+            0.0
+        },
+    }
+}
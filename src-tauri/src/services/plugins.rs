@@ -0,0 +1,131 @@
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::api;
+use crate::error::AppError;
+use crate::state::AppState;
+
+static PLUGINS: Mutex<Vec<Arc<dyn BackendPlugin>>> = Mutex::new(Vec::new());
+
+/// A backend extension compiled into a CARF build, for target-specific
+/// helpers that do not belong in core. Its commands are reached through
+/// `plugin_invoke` and its events are emitted under
+/// `carf://plugin/<name>/`.
+pub trait BackendPlugin: Send + Sync {
+    /// Namespace for the plugin's commands and events, e.g. `unity`.
+    fn name(&self) -> &'static str;
+    /// Commands `invoke` accepts, listed by `plugins_list`.
+    fn commands(&self) -> &'static [&'static str];
+    fn invoke(
+        &self,
+        context: &PluginContext,
+        command: &str,
+        args: Value,
+    ) -> Result<Value, AppError>;
+}
+
+/// What a plugin may do with the backend while handling a command.
+pub struct PluginContext<'a> {
+    state: &'a AppState,
+    name: &'static str,
+}
+
+impl PluginContext<'_> {
+    /// Emits `carf://plugin/<name>/<event>`.
+    pub fn emit(&self, event: &str, payload: Value) {
+        self.state
+            .events
+            .emit(format!("carf://plugin/{}/{event}", self.name), payload);
+    }
+
+    /// Calls an RPC method on a session's agent, as `rpc_call` does.
+    pub fn rpc_call(
+        &self,
+        session_id: &str,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, AppError> {
+        api::rpc_call(
+            self.state,
+            session_id.to_string(),
+            method.to_string(),
+            params,
+        )
+    }
+}
+
+/// Mirrors frontend `PluginInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginInfo {
+    pub name: String,
+    pub commands: Vec<String>,
+}
+
+/// Adds `plugin` to the registry. Names must be unique.
+pub fn register(plugin: Arc<dyn BackendPlugin>) -> Result<(), AppError> {
+    let name = plugin.name();
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+    {
+        return Err(AppError::InvalidArgument(format!(
+            "Plugin name {name:?} must be non-empty ASCII letters, digits or '-'"
+        )));
+    }
+    let mut plugins = lock();
+    if plugins.iter().any(|existing| existing.name() == name) {
+        return Err(AppError::InvalidArgument(format!(
+            "A plugin named {name} is already registered"
+        )));
+    }
+    plugins.push(plugin);
+    Ok(())
+}
+
+pub fn list_plugins() -> Vec<PluginInfo> {
+    lock()
+        .iter()
+        .map(|plugin| PluginInfo {
+            name: plugin.name().to_string(),
+            commands: plugin
+                .commands()
+                .iter()
+                .map(|command| command.to_string())
+                .collect(),
+        })
+        .collect()
+}
+
+pub fn invoke(
+    state: &AppState,
+    plugin: &str,
+    command: &str,
+    args: Value,
+) -> Result<Value, AppError> {
+    // Cloned out so a slow command does not hold the registry.
+    let found = lock()
+        .iter()
+        .find(|registered| registered.name() == plugin)
+        .cloned()
+        .ok_or_else(|| AppError::InvalidArgument(format!("Unknown plugin {plugin}")))?;
+    if !found.commands().contains(&command) {
+        return Err(AppError::InvalidArgument(format!(
+            "Plugin {plugin} has no command {command}"
+        )));
+    }
+    let context = PluginContext {
+        state,
+        name: found.name(),
+    };
+    found.invoke(&context, command, args)
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Vec<Arc<dyn BackendPlugin>>> {
+    PLUGINS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
@@ -0,0 +1,259 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::frida::FridaService;
+use crate::services::storage;
+use crate::services::variables;
+
+pub const POINTER_CHAINS_FILE: &str = "pointer_chains.json";
+
+const DEFAULT_MAX_DEPTH: u32 = 4;
+const MAX_DEPTH_LIMIT: u32 = 8;
+const DEFAULT_MAX_OFFSET: u64 = 0x1000;
+const MAX_OFFSET_LIMIT: u64 = 0x10000;
+const DEFAULT_MAX_RESULTS: usize = 500;
+
+/// Profile → saved chains, persisted as one JSON document.
+type ChainFile = BTreeMap<String, Vec<SavedPointerChain>>;
+
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Mirrors frontend `PointerScanOptions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerScanOptions {
+    /// Address the chains must end at.
+    pub target: String,
+    /// Pointer dereferences per chain. Defaults to 4, at most 8.
+    pub max_depth: Option<u32>,
+    /// Largest offset added after a dereference. Defaults to 0x1000.
+    pub max_offset: Option<u64>,
+    /// Defaults to 500.
+    pub max_results: Option<usize>,
+    /// Takes a new pointer map instead of reusing the session's last one.
+    #[serde(default)]
+    pub refresh: bool,
+}
+
+/// `[[module + module_offset] + offsets[0]] + ...`: read a pointer at the
+/// module-relative base, add the first offset, read again, and so on. The
+/// last offset is added without a read.
+///
+/// Mirrors frontend `PointerChain`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerChain {
+    pub module: String,
+    pub module_offset: u64,
+    pub offsets: Vec<u64>,
+}
+
+/// Mirrors frontend `PointerMapInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerMapInfo {
+    pub created_at: u64,
+    /// Pointer slots recorded.
+    pub entries: u64,
+    /// The map hit its size cap and misses some pointers.
+    pub truncated: bool,
+}
+
+/// Mirrors frontend `PointerScanResult`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointerScanResult {
+    /// Shortest chains first.
+    pub chains: Vec<PointerChain>,
+    /// `max_results` or the per-level node cap was hit.
+    pub truncated: bool,
+    pub snapshot: PointerMapInfo,
+}
+
+/// Mirrors frontend `SavedPointerChain`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedPointerChain {
+    pub name: String,
+    #[serde(flatten)]
+    pub chain: PointerChain,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// A saved chain followed in the current process.
+///
+/// Mirrors frontend `ResolvedPointerChain`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedPointerChain {
+    pub name: String,
+    pub address: Option<String>,
+    /// Why the chain could not be followed, e.g. a null pointer on the way.
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AgentResolution {
+    address: Option<String>,
+    error: Option<String>,
+}
+
+/// Finds chains from static module addresses to `options.target` in the
+/// session's pointer map, taking the map first when there is none yet.
+pub fn scan(
+    mut rpc: impl FnMut(&str, Value) -> Result<Value, AppError>,
+    options: PointerScanOptions,
+) -> Result<PointerScanResult, AppError> {
+    let max_depth = options.max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+    if max_depth == 0 || max_depth > MAX_DEPTH_LIMIT {
+        return Err(AppError::InvalidArgument(format!(
+            "maxDepth must be between 1 and {MAX_DEPTH_LIMIT}, got {max_depth}"
+        )));
+    }
+    let max_offset = options.max_offset.unwrap_or(DEFAULT_MAX_OFFSET);
+    if max_offset > MAX_OFFSET_LIMIT {
+        return Err(AppError::InvalidArgument(format!(
+            "maxOffset must be at most {MAX_OFFSET_LIMIT:#x}, got {max_offset:#x}"
+        )));
+    }
+
+    let response = rpc(
+        "pointerScan",
+        json!({
+            "target": options.target,
+            "maxDepth": max_depth,
+            "maxOffset": max_offset,
+            "maxResults": options.max_results.unwrap_or(DEFAULT_MAX_RESULTS).max(1),
+            "refresh": options.refresh,
+        }),
+    )?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected pointerScan response: {error}"))
+    })
+}
+
+/// Takes a fresh pointer map for later scans of the session.
+pub fn snapshot(
+    mut rpc: impl FnMut(&str, Value) -> Result<Value, AppError>,
+) -> Result<PointerMapInfo, AppError> {
+    let response = rpc("snapshotPointerMap", json!({}))?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected snapshotPointerMap response: {error}"))
+    })
+}
+
+pub fn list_saved(profile: &str) -> Result<Vec<SavedPointerChain>, AppError> {
+    Ok(read_file()?.remove(profile).unwrap_or_default())
+}
+
+/// Saves a chain under `profile`, replacing one with the same name.
+pub fn save(profile: &str, chain: SavedPointerChain) -> Result<(), AppError> {
+    if chain.name.trim().is_empty() {
+        return Err(AppError::InvalidArgument(
+            "pointer chain name must not be empty".to_string(),
+        ));
+    }
+    if chain.chain.module.is_empty() {
+        return Err(AppError::InvalidArgument(
+            "pointer chain module must not be empty".to_string(),
+        ));
+    }
+    update_file(|file| {
+        let chains = file.entry(profile.to_string()).or_default();
+        match chains.iter_mut().find(|saved| saved.name == chain.name) {
+            Some(existing) => *existing = chain,
+            None => chains.push(chain),
+        }
+    })
+}
+
+/// Returns whether a chain was removed.
+pub fn delete(profile: &str, name: &str) -> Result<bool, AppError> {
+    update_file(|file| {
+        let Some(chains) = file.get_mut(profile) else {
+            return false;
+        };
+        let before = chains.len();
+        chains.retain(|saved| saved.name != name);
+        let removed = chains.len() != before;
+        if chains.is_empty() {
+            file.remove(profile);
+        }
+        removed
+    })
+}
+
+/// Follows the chains saved for the session's target (see
+/// [`variables::profile_for`]) in its current process, so addresses found
+/// in an earlier run are found again after ASLR moved everything. `names`
+/// limits which chains are resolved.
+pub fn resolve_saved(
+    frida: &mut FridaService,
+    session_id: &str,
+    names: Option<Vec<String>>,
+) -> Result<Vec<ResolvedPointerChain>, AppError> {
+    let session = frida
+        .list_sessions()?
+        .into_iter()
+        .find(|session| session.id == session_id)
+        .ok_or_else(|| AppError::SessionNotFound(format!("Session not found: {session_id}")))?;
+    let saved: Vec<SavedPointerChain> = list_saved(&variables::profile_for(&session))?
+        .into_iter()
+        .filter(|saved| {
+            names
+                .as_ref()
+                .map_or(true, |names| names.contains(&saved.name))
+        })
+        .collect();
+    if saved.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let chains: Vec<&PointerChain> = saved.iter().map(|saved| &saved.chain).collect();
+    let response = frida.rpc_call(
+        session_id,
+        "resolvePointerChains",
+        json!({ "chains": chains }),
+    )?;
+    let resolutions: Vec<AgentResolution> = serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected resolvePointerChains response: {error}"))
+    })?;
+
+    Ok(saved
+        .into_iter()
+        .zip(resolutions)
+        .map(|(saved, resolution)| ResolvedPointerChain {
+            name: saved.name,
+            address: resolution.address,
+            error: resolution.error,
+        })
+        .collect())
+}
+
+fn lock_file() -> Result<std::sync::MutexGuard<'static, ()>, AppError> {
+    FILE_LOCK
+        .lock()
+        .map_err(|_| AppError::Internal("pointer chains lock poisoned".to_string()))
+}
+
+fn read_file() -> Result<ChainFile, AppError> {
+    let _guard = lock_file()?;
+    Ok(
+        storage::read_json_with_backup(&storage::data_file(POINTER_CHAINS_FILE)?)?
+            .unwrap_or_default(),
+    )
+}
+
+fn update_file<T>(update: impl FnOnce(&mut ChainFile) -> T) -> Result<T, AppError> {
+    let _guard = lock_file()?;
+    let path = storage::data_file(POINTER_CHAINS_FILE)?;
+    let mut file: ChainFile = storage::read_json_with_backup(&path)?.unwrap_or_default();
+    let result = update(&mut file);
+    storage::write_json_with_backup(&path, &file)?;
+    Ok(result)
+}
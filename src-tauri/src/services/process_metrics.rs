@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::services::frida::FridaService;
+
+/// One resource sample of the target, taken by the agent from inside the
+/// process. Fields the platform cannot report are `None`.
+///
+/// Mirrors frontend `ProcessMetrics`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessMetrics {
+    pub timestamp: u64,
+    /// CPU time over wall time since the previous sample; above 100 when
+    /// several cores are busy.
+    pub cpu_percent: Option<f64>,
+    pub rss_bytes: Option<u64>,
+    pub thread_count: u32,
+    /// Open handles on Windows, open file descriptors elsewhere.
+    pub handle_count: Option<u32>,
+}
+
+/// Mirrors frontend `ProcessMetricsStatus`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessMetricsStatus {
+    /// Whether `carf://process/metrics` events are being emitted.
+    pub running: bool,
+    pub interval_ms: u32,
+    pub metrics: ProcessMetrics,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartResponse {
+    interval_ms: u32,
+}
+
+#[derive(Deserialize)]
+struct StopResponse {
+    stopped: bool,
+}
+
+/// Starts sampling the session's process every `interval_ms` (default
+/// 1000, at least 250), emitting `carf://process/metrics`. Restarting
+/// replaces the interval. Returns the interval in effect.
+pub fn start(
+    frida: &mut FridaService,
+    session_id: &str,
+    interval_ms: Option<u32>,
+) -> Result<u32, AppError> {
+    let response = frida.rpc_call(
+        session_id,
+        "startProcessMetrics",
+        json!({ "intervalMs": interval_ms }),
+    )?;
+    serde_json::from_value::<StartResponse>(response)
+        .map(|response| response.interval_ms)
+        .map_err(|error| {
+            AppError::AgentRpcError(format!("unexpected startProcessMetrics response: {error}"))
+        })
+}
+
+/// Stops the sampler. Returns `false` when it was not running.
+pub fn stop(frida: &mut FridaService, session_id: &str) -> Result<bool, AppError> {
+    let response = frida.rpc_call(session_id, "stopProcessMetrics", json!({}))?;
+    serde_json::from_value::<StopResponse>(response)
+        .map(|response| response.stopped)
+        .map_err(|error| {
+            AppError::AgentRpcError(format!("unexpected stopProcessMetrics response: {error}"))
+        })
+}
+
+/// Takes one sample now, whether or not the sampler is running.
+pub fn status(
+    frida: &mut FridaService,
+    session_id: &str,
+) -> Result<ProcessMetricsStatus, AppError> {
+    let response = frida.rpc_call(session_id, "getProcessMetrics", json!({}))?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected getProcessMetrics response: {error}"))
+    })
+}
@@ -0,0 +1,148 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::services::frida::RemoteDeviceOptions;
+use crate::services::storage;
+
+/// Written owner-only and without `.bak` and `backups/` copies, since it
+/// holds tokens.
+const REMOTE_DEVICES_FILE: &str = "remote-devices.json";
+
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// An endpoint as stored, token included so it can reconnect unattended.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SavedRemoteDevice {
+    address: String,
+    #[serde(default)]
+    nickname: Option<String>,
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    certificate: Option<String>,
+}
+
+impl SavedRemoteDevice {
+    fn endpoint(&self) -> RemoteEndpoint {
+        RemoteEndpoint {
+            address: self.address.clone(),
+            nickname: self.nickname.clone(),
+            has_token: self.token.is_some(),
+            certificate: self.certificate.clone(),
+        }
+    }
+}
+
+/// A remote device CARF reconnects at startup. The token stays in the
+/// backend; only whether there is one is reported.
+///
+/// Mirrors frontend `RemoteEndpoint`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteEndpoint {
+    /// `host:port`
+    pub address: String,
+    pub nickname: Option<String>,
+    pub has_token: bool,
+    pub certificate: Option<String>,
+}
+
+pub fn list() -> Result<Vec<RemoteEndpoint>, AppError> {
+    Ok(read_file()?
+        .iter()
+        .map(SavedRemoteDevice::endpoint)
+        .collect())
+}
+
+/// Addresses and credentials to reconnect, in the order they were saved.
+pub fn connections() -> Result<Vec<(String, RemoteDeviceOptions)>, AppError> {
+    Ok(read_file()?
+        .into_iter()
+        .map(|saved| {
+            let options = RemoteDeviceOptions {
+                token: saved.token,
+                certificate: saved.certificate,
+            };
+            (saved.address, options)
+        })
+        .collect())
+}
+
+/// Saves an endpoint that just connected with `options`, keeping the
+/// nickname it already had.
+pub fn remember(address: &str, options: &RemoteDeviceOptions) -> Result<(), AppError> {
+    update_file(|file| {
+        let existing = file.iter_mut().find(|saved| saved.address == address);
+        match existing {
+            Some(saved) => {
+                saved.token = options.token.clone();
+                saved.certificate = options.certificate.clone();
+            }
+            None => file.push(SavedRemoteDevice {
+                address: address.to_string(),
+                nickname: None,
+                token: options.token.clone(),
+                certificate: options.certificate.clone(),
+            }),
+        }
+    })
+}
+
+/// Renames a saved endpoint (an empty nickname clears it) and, when
+/// `options` is set, replaces its credentials for the next connection.
+pub fn update(
+    address: &str,
+    nickname: Option<String>,
+    options: Option<RemoteDeviceOptions>,
+) -> Result<RemoteEndpoint, AppError> {
+    update_file(|file| {
+        let saved = file
+            .iter_mut()
+            .find(|saved| saved.address == address)
+            .ok_or_else(|| {
+                AppError::DeviceNotFound(format!("No saved remote device: {address}"))
+            })?;
+        if let Some(nickname) = nickname {
+            let nickname = nickname.trim();
+            saved.nickname = (!nickname.is_empty()).then(|| nickname.to_string());
+        }
+        if let Some(options) = options {
+            saved.token = options.token;
+            saved.certificate = options.certificate;
+        }
+        Ok(saved.endpoint())
+    })?
+}
+
+/// Forgets a saved endpoint without disconnecting it. Returns whether one
+/// was removed.
+pub fn remove(address: &str) -> Result<bool, AppError> {
+    update_file(|file| {
+        let before = file.len();
+        file.retain(|saved| saved.address != address);
+        file.len() != before
+    })
+}
+
+fn lock_file() -> Result<std::sync::MutexGuard<'static, ()>, AppError> {
+    FILE_LOCK
+        .lock()
+        .map_err(|_| AppError::Internal("remote devices lock poisoned".to_string()))
+}
+
+fn read_file() -> Result<Vec<SavedRemoteDevice>, AppError> {
+    let _guard = lock_file()?;
+    Ok(storage::read_json(&storage::data_file(REMOTE_DEVICES_FILE)?)?.unwrap_or_default())
+}
+
+fn update_file<T>(update: impl FnOnce(&mut Vec<SavedRemoteDevice>) -> T) -> Result<T, AppError> {
+    let _guard = lock_file()?;
+    let path = storage::data_file(REMOTE_DEVICES_FILE)?;
+    let mut file: Vec<SavedRemoteDevice> = storage::read_json(&path)?.unwrap_or_default();
+    let result = update(&mut file);
+    storage::write_private_json(&path, &file)?;
+    Ok(result)
+}
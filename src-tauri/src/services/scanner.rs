@@ -0,0 +1,261 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::typed_memory::{self, Endianness};
+
+// ─── Preset types ─────────────────────────────────────────────────────────────
+
+/// Named scan parameters for a kind of value, e.g. "coordinates: three
+/// aligned floats in writable private memory". Built-in presets ship with
+/// CARF; user presets live in `settings.json` and override built-ins of the
+/// same name.
+///
+/// Mirrors frontend `ScanPreset`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanPreset {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// `u8`…`u64`, `i8`…`i64`, `f32` or `f64`.
+    pub value_type: String,
+    /// Consecutive values making up one match (3 for xyz coordinates).
+    #[serde(default = "default_components")]
+    pub components: usize,
+    /// Matches whose address is not a multiple of this are dropped.
+    #[serde(default)]
+    pub alignment: Option<u32>,
+    /// Page protection filter passed to `scanMemory`.
+    #[serde(default = "default_protection")]
+    pub protection: String,
+    /// Skip file-backed mappings (images, mapped assets), which rarely hold
+    /// live game state.
+    #[serde(default)]
+    pub private_only: bool,
+    /// Set on presets that ship with CARF; ignored when saving.
+    #[serde(default)]
+    pub builtin: bool,
+}
+
+fn default_components() -> usize {
+    1
+}
+
+fn default_protection() -> String {
+    "rw-".to_string()
+}
+
+fn builtin(
+    name: &str,
+    description: &str,
+    value_type: &str,
+    components: usize,
+    alignment: u32,
+) -> ScanPreset {
+    ScanPreset {
+        name: name.to_string(),
+        description: description.to_string(),
+        value_type: value_type.to_string(),
+        components,
+        alignment: Some(alignment),
+        protection: default_protection(),
+        private_only: true,
+        builtin: true,
+    }
+}
+
+/// Presets that encode common scanning practice for game-style targets.
+pub fn builtin_presets() -> Vec<ScanPreset> {
+    vec![
+        builtin(
+            "health-like",
+            "4-byte integer, aligned, in writable private memory",
+            "i32",
+            1,
+            4,
+        ),
+        builtin(
+            "currency",
+            "Unsigned 4-byte counter, aligned, in writable private memory",
+            "u32",
+            1,
+            4,
+        ),
+        builtin(
+            "currency-64",
+            "Unsigned 8-byte counter, aligned, in writable private memory",
+            "u64",
+            1,
+            8,
+        ),
+        builtin(
+            "float-stat",
+            "Single float such as speed or stamina, aligned",
+            "f32",
+            1,
+            4,
+        ),
+        builtin(
+            "coordinates",
+            "Float triplet (x, y, z), aligned",
+            "f32",
+            3,
+            4,
+        ),
+        builtin(
+            "coordinates-double",
+            "Double triplet (x, y, z), aligned",
+            "f64",
+            3,
+            8,
+        ),
+    ]
+}
+
+/// Built-in presets merged with the user's, sorted by name.
+pub fn list_presets(user: &[ScanPreset]) -> Vec<ScanPreset> {
+    let mut presets = builtin_presets();
+    presets.retain(|preset| !user.iter().any(|custom| custom.name == preset.name));
+    presets.extend(user.iter().cloned().map(|mut preset| {
+        preset.builtin = false;
+        preset
+    }));
+    presets.sort_by(|a, b| a.name.cmp(&b.name));
+    presets
+}
+
+pub fn find_preset(user: &[ScanPreset], name: &str) -> Result<ScanPreset, AppError> {
+    list_presets(user)
+        .into_iter()
+        .find(|preset| preset.name == name)
+        .ok_or_else(|| AppError::InvalidArgument(format!("Unknown scan preset: {name}")))
+}
+
+/// Rejects presets the scanner could not run, before they are saved.
+pub fn validate_preset(preset: &ScanPreset) -> Result<(), AppError> {
+    if preset.name.trim().is_empty() {
+        return Err(AppError::InvalidArgument(
+            "scan preset name must not be empty".to_string(),
+        ));
+    }
+    value_width(&preset.value_type)?;
+    if preset.components == 0 {
+        return Err(AppError::InvalidArgument(
+            "scan preset must match at least one value".to_string(),
+        ));
+    }
+    if let Some(alignment) = preset.alignment {
+        if !alignment.is_power_of_two() {
+            return Err(AppError::InvalidArgument(format!(
+                "scan alignment must be a power of two, got {alignment}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+// ─── Scanning ─────────────────────────────────────────────────────────────────
+
+/// Scans for `values` laid out as `preset` describes, encoded in the
+/// target's byte order. Returns the agent's `scanMemory` response unchanged.
+pub fn scan_with_preset(
+    mut rpc: impl FnMut(&str, Value) -> Result<Value, AppError>,
+    preset: &ScanPreset,
+    values: &[Value],
+) -> Result<Value, AppError> {
+    if values.len() != preset.components {
+        return Err(AppError::InvalidArgument(format!(
+            "preset {} expects {} value(s), got {}",
+            preset.name,
+            preset.components,
+            values.len()
+        )));
+    }
+
+    let info = rpc("getProcessInfo", Value::Null)?;
+    let endianness = typed_memory::reported_endianness(&info);
+    let pattern = encode_pattern(&preset.value_type, values, endianness)?;
+    rpc(
+        "scanMemory",
+        json!({
+            "pattern": pattern,
+            "protection": preset.protection,
+            "alignment": preset.alignment,
+            "privateOnly": preset.private_only,
+        }),
+    )
+}
+
+fn value_width(value_type: &str) -> Result<usize, AppError> {
+    match value_type {
+        "u8" | "i8" => Ok(1),
+        "u16" | "i16" => Ok(2),
+        "u32" | "i32" | "f32" => Ok(4),
+        "u64" | "i64" | "f64" => Ok(8),
+        other => Err(AppError::InvalidArgument(format!(
+            "unsupported scan value type: {other}"
+        ))),
+    }
+}
+
+/// `Memory.scan` pattern for consecutive values.
+fn encode_pattern(
+    value_type: &str,
+    values: &[Value],
+    endianness: Endianness,
+) -> Result<String, AppError> {
+    let width = value_width(value_type)?;
+    let mut bytes = Vec::with_capacity(width * values.len());
+    for value in values {
+        bytes.extend(encode_value(value_type, value, endianness)?);
+    }
+    Ok(bytes
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+fn encode_value(
+    value_type: &str,
+    value: &Value,
+    endianness: Endianness,
+) -> Result<Vec<u8>, AppError> {
+    let invalid = || AppError::InvalidArgument(format!("{value} is not a valid {value_type}"));
+    let text = match value {
+        Value::String(text) => text.trim().to_string(),
+        Value::Number(number) => number.to_string(),
+        _ => return Err(invalid()),
+    };
+
+    let bytes = match value_type {
+        "f32" => {
+            let number = text.parse::<f32>().map_err(|_| invalid())?;
+            match endianness {
+                Endianness::Little => number.to_le_bytes().to_vec(),
+                Endianness::Big => number.to_be_bytes().to_vec(),
+            }
+        }
+        "f64" => {
+            let number = text.parse::<f64>().map_err(|_| invalid())?;
+            match endianness {
+                Endianness::Little => number.to_le_bytes().to_vec(),
+                Endianness::Big => number.to_be_bytes().to_vec(),
+            }
+        }
+        _ => {
+            let integer = typed_memory::parse_integer_text(&text).ok_or_else(invalid)?;
+            let width = value_width(value_type)?;
+            let (min, max) = typed_memory::integer_range(width, value_type.starts_with('i'));
+            if integer < min || integer > max {
+                return Err(invalid());
+            }
+            match endianness {
+                Endianness::Little => integer.to_le_bytes()[..width].to_vec(),
+                Endianness::Big => integer.to_be_bytes()[16 - width..].to_vec(),
+            }
+        }
+    };
+    Ok(bytes)
+}
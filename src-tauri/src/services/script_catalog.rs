@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::services::frida::FridaService;
+
+/// A curated agent shipped inside the binary.
+struct BundledScript {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    platforms: &'static [&'static str],
+    source: &'static str,
+}
+
+const BUNDLED_SCRIPTS: &[BundledScript] = &[
+    BundledScript {
+        id: "ssl-unpinning",
+        name: "SSL pinning bypass",
+        description: "Disables certificate validation in BoringSSL, OpenSSL, Apple Security and common Android Java stacks.",
+        platforms: &["android", "ios", "macos", "linux"],
+        source: include_str!("../../scripts/ssl-unpinning.js"),
+    },
+    BundledScript {
+        id: "anti-debug",
+        name: "Anti-debug bypass",
+        description: "Neutralizes ptrace, sysctl P_TRACED and Windows debugger-presence checks.",
+        platforms: &["android", "ios", "macos", "linux", "windows"],
+        source: include_str!("../../scripts/anti-debug.js"),
+    },
+    BundledScript {
+        id: "tracer",
+        name: "Generic tracer",
+        description: "Logs file, network, library-loading and process-creation calls to the console.",
+        platforms: &["android", "ios", "macos", "linux", "windows"],
+        source: include_str!("../../scripts/tracer.js"),
+    },
+];
+
+/// Mirrors frontend `BundledScriptInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundledScriptInfo {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub platforms: Vec<String>,
+    pub size: usize,
+}
+
+pub fn list_bundled_scripts() -> Vec<BundledScriptInfo> {
+    BUNDLED_SCRIPTS
+        .iter()
+        .map(|script| BundledScriptInfo {
+            id: script.id.to_string(),
+            name: script.name.to_string(),
+            description: script.description.to_string(),
+            platforms: script
+                .platforms
+                .iter()
+                .map(|platform| platform.to_string())
+                .collect(),
+            size: script.source.len(),
+        })
+        .collect()
+}
+
+/// The source of a bundled script.
+pub fn bundled_source(script_id: &str) -> Result<&'static str, AppError> {
+    BUNDLED_SCRIPTS
+        .iter()
+        .find(|script| script.id == script_id)
+        .map(|script| script.source)
+        .ok_or_else(|| AppError::ScriptLoadFailed(format!("Unknown bundled script: {script_id}")))
+}
+
+/// Loads a bundled script as the session's user script, replacing any script
+/// loaded before it.
+pub fn load_bundled_script(
+    frida: &mut FridaService,
+    session_id: &str,
+    script_id: &str,
+) -> Result<(), AppError> {
+    let source = bundled_source(script_id)?;
+    frida.rpc_call(session_id, "loadScript", json!({ "code": source }))?;
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::services::frida::FridaService;
+use crate::services::storage;
+
+/// Subdirectory of the data dir holding compiled agent bundles.
+const CACHE_DIR: &str = "compiled-scripts";
+const SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "mjs", "cjs", "json"];
+const SKIPPED_DIRS: &[&str] = &["node_modules", "dist", "build", "target"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompiledScript {
+    pub entrypoint: String,
+    pub project_root: String,
+    /// Single-file bundle that can be passed as `scriptPath` or read and
+    /// loaded with `loadScript`.
+    pub bundle_path: String,
+    pub size: u64,
+    /// True when the bundle was reused because no source changed since the
+    /// last build.
+    pub cached: bool,
+}
+
+/// Bundles `entrypoint_path` and everything it imports into one script,
+/// reusing the cached bundle when no source file under the project root is
+/// newer than it.
+pub fn compile_script(
+    frida: &mut FridaService,
+    entrypoint_path: &str,
+) -> Result<CompiledScript, AppError> {
+    let entrypoint = fs::canonicalize(entrypoint_path).map_err(|error| {
+        AppError::ScriptLoadFailed(format!("failed to resolve {entrypoint_path}: {error}"))
+    })?;
+    let project_root = find_project_root(&entrypoint);
+    let bundle_path = storage::data_dir()?
+        .join(CACHE_DIR)
+        .join(format!("{:016x}.js", cache_key(&entrypoint)));
+
+    let cached = is_fresh(&bundle_path, &project_root);
+    if !cached {
+        let bundle = frida.compile_script(
+            &entrypoint.to_string_lossy(),
+            &project_root.to_string_lossy(),
+        )?;
+        if let Some(parent) = bundle_path.parent() {
+            fs::create_dir_all(parent).map_err(|error| {
+                AppError::StorageError(format!("failed to create {}: {error}", parent.display()))
+            })?;
+        }
+        fs::write(&bundle_path, bundle).map_err(|error| {
+            AppError::StorageError(format!(
+                "failed to write {}: {error}",
+                bundle_path.display()
+            ))
+        })?;
+    }
+
+    let size = fs::metadata(&bundle_path)
+        .map(|metadata| metadata.len())
+        .unwrap_or_default();
+
+    Ok(CompiledScript {
+        entrypoint: entrypoint.to_string_lossy().into_owned(),
+        project_root: project_root.to_string_lossy().into_owned(),
+        bundle_path: bundle_path.to_string_lossy().into_owned(),
+        size,
+        cached,
+    })
+}
+
+/// Nearest ancestor with a `package.json`, so the compiler can resolve
+/// `node_modules` imports; falls back to the entrypoint's directory.
+fn find_project_root(entrypoint: &Path) -> PathBuf {
+    let start = entrypoint.parent().unwrap_or(entrypoint);
+    start
+        .ancestors()
+        .find(|dir| dir.join("package.json").is_file())
+        .unwrap_or(start)
+        .to_path_buf()
+}
+
+fn cache_key(entrypoint: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    entrypoint.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn is_fresh(bundle_path: &Path, project_root: &Path) -> bool {
+    let Ok(built_at) = fs::metadata(bundle_path).and_then(|metadata| metadata.modified()) else {
+        return false;
+    };
+    newest_source_mtime(project_root).is_some_and(|newest| newest <= built_at)
+}
+
+fn newest_source_mtime(dir: &Path) -> Option<SystemTime> {
+    let mut newest = None;
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+
+            if file_type.is_dir() {
+                if !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_ref()) {
+                    pending.push(path);
+                }
+                continue;
+            }
+
+            let is_source = path
+                .extension()
+                .and_then(|extension| extension.to_str())
+                .is_some_and(|extension| SOURCE_EXTENSIONS.contains(&extension));
+            if !is_source {
+                continue;
+            }
+
+            if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) {
+                newest = newest.max(Some(modified));
+            }
+        }
+    }
+
+    newest
+}
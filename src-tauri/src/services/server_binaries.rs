@@ -0,0 +1,370 @@
+use std::fs::{self, File};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use xz2::read::XzDecoder;
+
+use crate::error::AppError;
+use crate::services::storage;
+
+/// Under the data dir, one folder per Frida version.
+const BINARIES_DIR: &str = "frida-servers";
+const RELEASES_API: &str = "https://api.github.com/repos/frida/frida/releases/tags";
+const DOWNLOAD_CHUNK_SIZE: usize = 256 * 1024;
+const OPERATING_SYSTEMS: &[&str] = &["android", "linux", "macos", "windows", "freebsd"];
+
+// ─── Server binary types ──────────────────────────────────────────────────────
+
+/// A frida-server release installed in the data dir.
+///
+/// Mirrors frontend `ServerBinary`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerBinary {
+    pub version: String,
+    /// Frida's OS name: `android`, `linux`, `macos`, ...
+    pub os: String,
+    /// Frida's arch name: `arm64`, `arm`, `x86_64`, `x86`, ...
+    pub arch: String,
+    pub path: String,
+    /// SHA-256 of the downloaded `.xz` archive.
+    pub sha256: String,
+    /// Whether `sha256` matched a published or caller-supplied checksum.
+    pub verified: bool,
+    pub size: u64,
+    pub downloaded_at: u64,
+}
+
+/// One step of a download, emitted as `carf://frida-server/download`.
+///
+/// Mirrors frontend `ServerDownloadProgress`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerDownloadProgress {
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    /// `resolve`, `download`, `verify` or `extract`.
+    pub stage: String,
+    pub bytes_received: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Deserialize)]
+struct Release {
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+    /// `sha256:<hex>`; only present on assets GitHub has hashed.
+    #[serde(default)]
+    digest: Option<String>,
+}
+
+// ─── Installed binaries ───────────────────────────────────────────────────────
+
+/// Where `version`'s frida-server for `os`/`arch` is (or would be) installed.
+pub fn binary_path(version: &str, os: &str, arch: &str) -> Result<PathBuf, AppError> {
+    validate(version, os, arch)?;
+    let suffix = if os == "windows" { ".exe" } else { "" };
+    Ok(storage::data_dir()?
+        .join(BINARIES_DIR)
+        .join(version)
+        .join(format!("frida-server-{version}-{os}-{arch}{suffix}")))
+}
+
+/// The installed binary for `os`/`arch`, if it was downloaded.
+pub fn installed_path(version: &str, os: &str, arch: &str) -> Option<PathBuf> {
+    binary_path(version, os, arch)
+        .ok()
+        .filter(|path| path.is_file())
+}
+
+/// The Frida OS and arch names of the machine CARF runs on.
+pub fn host_target() -> (&'static str, &'static str) {
+    let arch = match std::env::consts::ARCH {
+        "aarch64" => "arm64",
+        other => other,
+    };
+    (std::env::consts::OS, arch)
+}
+
+/// Every installed binary, newest version first.
+pub fn list() -> Result<Vec<ServerBinary>, AppError> {
+    let root = storage::data_dir()?.join(BINARIES_DIR);
+    let Ok(versions) = fs::read_dir(&root) else {
+        return Ok(Vec::new());
+    };
+
+    let mut binaries = Vec::new();
+    for entry in versions.flatten() {
+        let Ok(files) = fs::read_dir(entry.path()) else {
+            continue;
+        };
+        for file in files.flatten() {
+            let path = file.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            match storage::read_json::<ServerBinary>(&path) {
+                Ok(Some(binary)) if PathBuf::from(&binary.path).is_file() => binaries.push(binary),
+                Ok(_) => {}
+                Err(error) => log::warn!("Skipping frida-server metadata: {error}"),
+            }
+        }
+    }
+    binaries.sort_by(|left, right| {
+        version_key(&right.version)
+            .cmp(&version_key(&left.version))
+            .then_with(|| left.os.cmp(&right.os))
+            .then_with(|| left.arch.cmp(&right.arch))
+    });
+    Ok(binaries)
+}
+
+/// Returns whether a binary was removed.
+pub fn delete(version: &str, os: &str, arch: &str) -> Result<bool, AppError> {
+    let path = binary_path(version, os, arch)?;
+    let _ = fs::remove_file(metadata_path(&path));
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(AppError::StorageError(format!(
+            "failed to remove {}: {error}",
+            path.display()
+        ))),
+    }
+}
+
+// ─── Download ─────────────────────────────────────────────────────────────────
+
+/// Downloads `version`'s frida-server for `os`/`arch` from the Frida GitHub
+/// releases, checks it against `sha256` (or the digest GitHub publishes for
+/// the asset) and installs it, replacing an earlier copy. Without either
+/// checksum the download is refused unless `allow_unverified` is set.
+pub fn download(
+    version: &str,
+    os: &str,
+    arch: &str,
+    sha256: Option<&str>,
+    allow_unverified: bool,
+    mut progress: impl FnMut(ServerDownloadProgress),
+) -> Result<ServerBinary, AppError> {
+    let target = binary_path(version, os, arch)?;
+    let mut report = |stage: &str, bytes_received: u64, total_bytes: u64| {
+        progress(ServerDownloadProgress {
+            version: version.to_string(),
+            os: os.to_string(),
+            arch: arch.to_string(),
+            stage: stage.to_string(),
+            bytes_received,
+            total_bytes,
+        })
+    };
+
+    report("resolve", 0, 0);
+    let client = reqwest::blocking::Client::builder()
+        .user_agent(concat!("carf/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .map_err(|error| AppError::Internal(error.to_string()))?;
+    let release_url = format!("{RELEASES_API}/{version}");
+    let response = client
+        .get(&release_url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| AppError::ConnectionFailed(release_url.clone(), error.to_string()))?;
+    let release: Release = serde_json::from_reader(response)
+        .map_err(|error| AppError::ConnectionFailed(release_url.clone(), error.to_string()))?;
+    let asset_name = format!(
+        "{}.xz",
+        target.file_name().unwrap_or_default().to_string_lossy()
+    );
+    let asset = release
+        .assets
+        .into_iter()
+        .find(|asset| asset.name == asset_name)
+        .ok_or_else(|| {
+            AppError::InvalidArgument(format!("Frida {version} has no {asset_name} release"))
+        })?;
+    let expected = sha256
+        .map(|digest| digest.trim().to_ascii_lowercase())
+        .or_else(|| {
+            asset
+                .digest
+                .as_deref()
+                .and_then(|digest| digest.strip_prefix("sha256:"))
+                .map(str::to_ascii_lowercase)
+        });
+    if expected.is_none() && !allow_unverified {
+        return Err(AppError::InvalidArgument(format!(
+            "Frida {version} publishes no checksum for {asset_name}; pass its sha256, or \
+             allow unverified installs to download it anyway"
+        )));
+    }
+
+    let parent = target
+        .parent()
+        .ok_or_else(|| AppError::Internal(format!("{} has no parent", target.display())))?;
+    fs::create_dir_all(parent).map_err(|error| storage_error("create", parent, error))?;
+    let archive = parent.join(format!("{asset_name}.part"));
+    let result = fetch_archive(&client, &asset, &archive, &mut report).and_then(|actual| {
+        report("verify", asset.size, asset.size);
+        if let Some(expected) = &expected {
+            if *expected != actual {
+                return Err(AppError::ConnectionFailed(
+                    asset.browser_download_url.clone(),
+                    format!("checksum mismatch: expected sha256 {expected}, got {actual}"),
+                ));
+            }
+        } else {
+            log::warn!("Installing {asset_name} unverified, as requested");
+        }
+
+        report("extract", asset.size, asset.size);
+        extract(&archive, &target)?;
+        Ok(actual)
+    });
+    let _ = fs::remove_file(&archive);
+    let actual = result?;
+
+    let binary = ServerBinary {
+        version: version.to_string(),
+        os: os.to_string(),
+        arch: arch.to_string(),
+        path: target.to_string_lossy().into_owned(),
+        sha256: actual,
+        verified: expected.is_some(),
+        size: fs::metadata(&target).map(|meta| meta.len()).unwrap_or(0),
+        downloaded_at: storage::now_millis(),
+    };
+    storage::write_json(&metadata_path(&target), &binary)?;
+    Ok(binary)
+}
+
+/// Streams the asset to `archive` and returns its SHA-256 in hex.
+fn fetch_archive(
+    client: &reqwest::blocking::Client,
+    asset: &ReleaseAsset,
+    archive: &Path,
+    report: &mut impl FnMut(&str, u64, u64),
+) -> Result<String, AppError> {
+    let url = &asset.browser_download_url;
+    let mut response = client
+        .get(url)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|error| AppError::ConnectionFailed(url.clone(), error.to_string()))?;
+    let total = response.content_length().unwrap_or(asset.size);
+    let mut file =
+        File::create(archive).map_err(|error| storage_error("create", archive, error))?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; DOWNLOAD_CHUNK_SIZE];
+    let mut received = 0;
+    report("download", received, total);
+    loop {
+        let read = response
+            .read(&mut buffer)
+            .map_err(|error| AppError::ConnectionFailed(url.clone(), error.to_string()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        file.write_all(&buffer[..read])
+            .map_err(|error| storage_error("write", archive, error))?;
+        received += read as u64;
+        report("download", received, total);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect())
+}
+
+/// Decompresses `archive` next to `target`, then moves it into place so a
+/// failed extraction never leaves a truncated binary behind.
+fn extract(archive: &Path, target: &Path) -> Result<(), AppError> {
+    let partial = target.with_file_name(format!(
+        "{}.tmp",
+        target.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let result = File::open(archive)
+        .and_then(|compressed| {
+            let mut output = File::create(&partial)?;
+            io::copy(&mut XzDecoder::new(compressed), &mut output)?;
+            output.sync_all()
+        })
+        .and_then(|()| make_executable(&partial))
+        .and_then(|()| fs::rename(&partial, target));
+    if let Err(error) = result {
+        let _ = fs::remove_file(&partial);
+        return Err(storage_error("extract", target, error));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+// ─── Helpers ──────────────────────────────────────────────────────────────────
+
+/// Versions and arch names end up in paths and URLs, so only plain release
+/// names are accepted.
+fn validate(version: &str, os: &str, arch: &str) -> Result<(), AppError> {
+    let plain = |value: &str, extra: char| {
+        !value.is_empty()
+            && value
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == extra || c == '_')
+    };
+    if !plain(version, '.') || !version.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(AppError::InvalidArgument(format!(
+            "{version:?} is not a Frida version"
+        )));
+    }
+    if !OPERATING_SYSTEMS.contains(&os) {
+        return Err(AppError::InvalidArgument(format!(
+            "no frida-server builds for OS {os:?}; expected one of {}",
+            OPERATING_SYSTEMS.join(", ")
+        )));
+    }
+    if !plain(arch, '-') {
+        return Err(AppError::InvalidArgument(format!(
+            "{arch:?} is not an architecture name"
+        )));
+    }
+    Ok(())
+}
+
+fn metadata_path(binary: &Path) -> PathBuf {
+    let mut name = binary.as_os_str().to_owned();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+/// `17.2.1` sorts after `17.10.0` as text; compare numerically instead.
+fn version_key(version: &str) -> Vec<u64> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
+
+fn storage_error(action: &str, path: &Path, error: io::Error) -> AppError {
+    AppError::StorageError(format!("failed to {action} {}: {error}", path.display()))
+}
@@ -21,6 +21,21 @@ pub struct SessionInfo {
     pub mode: SessionMode,
     pub arch: Option<String>,
     pub created_at: u64,
+    pub expires_at: Option<u64>,
+    /// User scripts currently loaded; the built-in agent is not counted.
+    #[serde(default)]
+    pub script_count: usize,
+}
+
+/// Outcome for one pid of an `attach_many` call.
+///
+/// Mirrors frontend `AttachManyResult`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttachManyResult {
+    pub pid: u32,
+    pub session: Option<SessionInfo>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
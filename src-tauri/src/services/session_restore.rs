@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::frida::{AttachOptions, FridaService};
+use crate::services::session_manager::{SessionInfo, SessionMode};
+use crate::services::storage;
+
+const SNAPSHOT_FILE: &str = "last-session.json";
+const SNAPSHOT_VERSION: u32 = 1;
+
+// ─── Snapshot types ───────────────────────────────────────────────────────────
+
+/// Attached targets and their user scripts, rewritten whenever the user
+/// attaches, detaches or swaps scripts. Sessions that end because the app is
+/// closing are deliberately left in so the next launch can bring them back.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSnapshot {
+    pub version: u32,
+    pub saved_at: u64,
+    pub sessions: Vec<SavedSession>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedSession {
+    pub device_id: String,
+    pub pid: u32,
+    pub process_name: String,
+    pub identifier: Option<String>,
+    pub mode: SessionMode,
+    pub script: Option<SavedScript>,
+}
+
+/// How the session's user script was loaded. Scripts loaded from disk are
+/// stored by path and re-read on restore so edits made in between are
+/// picked up; anything else is stored inline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedScript {
+    pub path: Option<String>,
+    pub code: Option<String>,
+    pub runtime: Option<String>,
+    pub parameters: Option<Value>,
+}
+
+impl SavedScript {
+    /// Captures a `loadScript` request. `path` is set internally when the
+    /// code was read from a file.
+    pub fn from_load_params(params: &Value) -> Self {
+        let text = |key: &str| params.get(key).and_then(Value::as_str).map(str::to_string);
+        let path = text("path");
+        Self {
+            code: if path.is_some() { None } else { text("code") },
+            path,
+            runtime: text("runtime"),
+            parameters: params
+                .get("parameters")
+                .filter(|value| !value.is_null())
+                .cloned(),
+        }
+    }
+
+    fn to_load_params(&self) -> Result<Value, AppError> {
+        let code = match (&self.path, &self.code) {
+            (Some(path), _) => std::fs::read_to_string(path).map_err(|error| {
+                AppError::ScriptLoadFailed(format!("failed to read {path}: {error}"))
+            })?,
+            (None, Some(code)) => code.clone(),
+            (None, None) => {
+                return Err(AppError::ScriptLoadFailed(
+                    "Saved script has no source".to_string(),
+                ))
+            }
+        };
+
+        Ok(json!({
+            "code": code,
+            "path": self.path,
+            "runtime": self.runtime,
+            "parameters": self.parameters,
+        }))
+    }
+}
+
+/// Mirrors frontend `SessionRestoreReport`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRestoreReport {
+    pub restored: Vec<SessionInfo>,
+    pub skipped: Vec<SkippedSession>,
+    /// Restored sessions whose user script could not be reloaded.
+    pub script_errors: Vec<ScriptRestoreError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedSession {
+    pub device_id: String,
+    pub pid: u32,
+    pub process_name: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptRestoreError {
+    pub session_id: String,
+    pub process_name: String,
+    pub reason: String,
+}
+
+// ─── Persistence ──────────────────────────────────────────────────────────────
+
+pub fn save_snapshot(sessions: Vec<SavedSession>) -> Result<(), AppError> {
+    let snapshot = SessionSnapshot {
+        version: SNAPSHOT_VERSION,
+        saved_at: storage::now_millis(),
+        sessions,
+    };
+    storage::write_json(&storage::data_file(SNAPSHOT_FILE)?, &snapshot)
+}
+
+pub fn load_snapshot() -> Result<Option<SessionSnapshot>, AppError> {
+    let snapshot = storage::read_json::<SessionSnapshot>(&storage::data_file(SNAPSHOT_FILE)?)?;
+    Ok(snapshot.filter(|snapshot| snapshot.version == SNAPSHOT_VERSION))
+}
+
+// ─── Restore ──────────────────────────────────────────────────────────────────
+
+/// Reattaches every saved target that is still running under the same pid
+/// and name, then reloads its user script. Targets that are gone are
+/// reported as skipped rather than failing the whole restore, and scripts
+/// that fail to reload in `script_errors`.
+pub fn restore_last_session(frida: &mut FridaService) -> Result<SessionRestoreReport, AppError> {
+    let mut report = SessionRestoreReport::default();
+    let Some(snapshot) = load_snapshot()? else {
+        return Ok(report);
+    };
+
+    let active = frida.list_sessions()?;
+    for saved in snapshot.sessions {
+        let already_attached = active
+            .iter()
+            .any(|session| session.device_id == saved.device_id && session.pid == saved.pid);
+        if already_attached {
+            continue;
+        }
+
+        match restore_session(frida, &saved) {
+            Ok((session, script_error)) => {
+                if let Some(reason) = script_error {
+                    report.script_errors.push(ScriptRestoreError {
+                        session_id: session.id.clone(),
+                        process_name: saved.process_name,
+                        reason,
+                    });
+                }
+                report.restored.push(session);
+            }
+            Err(error) => report.skipped.push(SkippedSession {
+                device_id: saved.device_id,
+                pid: saved.pid,
+                process_name: saved.process_name,
+                reason: error.to_string(),
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Returns the new session and, when its script did not reload, why.
+fn restore_session(
+    frida: &mut FridaService,
+    saved: &SavedSession,
+) -> Result<(SessionInfo, Option<String>), AppError> {
+    // A pid alone may have been recycled by an unrelated process since the
+    // snapshot was written.
+    let still_running = frida
+        .list_processes(&saved.device_id)?
+        .iter()
+        .any(|process| {
+            process.pid == saved.pid
+                && (process.name == saved.process_name
+                    || (saved.identifier.is_some() && process.identifier == saved.identifier))
+        });
+    if !still_running {
+        return Err(AppError::ProcessNotFound(format!(
+            "{} (pid {}) is no longer running",
+            saved.process_name, saved.pid
+        )));
+    }
+
+    let session = frida.attach(
+        &saved.device_id,
+        AttachOptions::for_target(Value::from(saved.pid)),
+    )?;
+
+    let mut script_error = None;
+    if let Some(script) = saved.script.as_ref() {
+        let loaded = script
+            .to_load_params()
+            .and_then(|params| frida.rpc_call(&session.id, "loadScript", params));
+        if let Err(error) = loaded {
+            log::warn!(
+                "Reattached to {} but could not reload its script: {error}",
+                saved.process_name
+            );
+            script_error = Some(error.to_string());
+        }
+    }
+
+    Ok((session, script_error))
+}
@@ -0,0 +1,488 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::services::backups::{self, BackupRetention};
+use crate::services::frida::TimeoutPolicy;
+use crate::services::hotkeys::{self, HotkeyBinding, HotkeyRpcAction};
+use crate::services::scanner::{self, ScanPreset};
+use crate::services::storage;
+
+pub const SETTINGS_FILE: &str = "settings.json";
+/// Layout version of `settings.json`. Bump it and add a step to `migrate`
+/// whenever a change would not load through serde defaults alone.
+const SCHEMA_VERSION: u32 = 1;
+const EXPORT_FORMAT: &str = "carf-settings";
+const EXPORT_VERSION: u32 = 1;
+
+// ─── Settings types ───────────────────────────────────────────────────────────
+
+/// User-level configuration persisted to `settings.json` in the data dir.
+///
+/// Every field has a default so older files keep loading after new sections
+/// are added.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// Action name → key chord (e.g. `"toggleFreeze": "Ctrl+Shift+F"`).
+    pub hotkeys: BTreeMap<String, String>,
+    /// Action name → agent RPC call run when that action's hotkey fires.
+    pub hotkey_rpc: BTreeMap<String, HotkeyRpcAction>,
+    pub scanner: ScannerDefaults,
+    /// Device id → user-facing nickname.
+    pub device_aliases: BTreeMap<String, String>,
+    /// Automation rule definitions, stored verbatim for the frontend.
+    pub rules: Vec<Value>,
+    pub timeouts: TimeoutPolicy,
+    /// Reload `settings.json` when it is edited outside CARF instead of
+    /// refusing further saves until `settings_reload`.
+    pub reload_external_edits: bool,
+    pub backups: BackupRetention,
+    /// Script runtime (`qjs` or `v8`) for attaches that do not pick one.
+    /// Unset leaves the choice to Frida.
+    pub default_runtime: Option<String>,
+    /// Backend log verbosity (`error` through `trace`); unset means `info`.
+    /// Ignored while `RUST_LOG` is set.
+    pub log_level: Option<String>,
+    /// Remote device addresses (`host:port`) connected at startup.
+    pub auto_connect: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScannerDefaults {
+    /// Page protection filter passed to `scanMemory` when none is given.
+    pub protection: String,
+    /// User-defined scan presets; see `scanner::list_presets`.
+    pub presets: Vec<ScanPreset>,
+}
+
+impl Default for ScannerDefaults {
+    fn default() -> Self {
+        Self {
+            protection: "r--".to_string(),
+            presets: Vec::new(),
+        }
+    }
+}
+
+/// `settings.json` on disk: the settings plus the layout version they were
+/// written with.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsFile<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    settings: &'a Settings,
+}
+
+/// Envelope written by `settings_export` so imports can reject unrelated JSON.
+/// Imports read `settings` as raw JSON so they go through `upgrade` too.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsExport<S> {
+    format: String,
+    version: u32,
+    exported_at: u64,
+    settings: S,
+}
+
+// ─── SettingsService ──────────────────────────────────────────────────────────
+
+pub struct SettingsService {
+    path: Option<PathBuf>,
+    settings: Settings,
+    /// Set when `settings.json` was written by a newer CARF, whose file must
+    /// not be overwritten with this version's layout.
+    newer_file: Option<u32>,
+    /// Modification time of `settings.json` as last read or written here.
+    modified: Option<SystemTime>,
+    /// The file changed on disk and was not reloaded; saving would clobber
+    /// the external edit.
+    external_change: bool,
+}
+
+/// Mirrors frontend `SettingsFileChange`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsFileChange {
+    pub path: String,
+    /// False when the edit is waiting for `settings_reload`, or could not be
+    /// loaded.
+    pub reloaded: bool,
+}
+
+impl SettingsService {
+    /// Loads settings from the data dir, falling back to defaults when the file
+    /// is missing or unreadable so a bad settings file never blocks startup.
+    pub fn new() -> Self {
+        let path = match storage::data_file(SETTINGS_FILE) {
+            Ok(path) => Some(path),
+            Err(error) => {
+                log::warn!("Settings will not be persisted: {error}");
+                None
+            }
+        };
+
+        let raw = path.as_deref().and_then(|path| {
+            storage::read_json_with_backup::<Value>(path).unwrap_or_else(|error| {
+                log::warn!("Ignoring unreadable settings file: {error}");
+                None
+            })
+        });
+
+        let mut newer_file = None;
+        let settings = match raw {
+            Some(raw) => match upgrade(raw) {
+                Ok(settings) => settings,
+                Err(Upgrade::Newer(version)) => {
+                    log::warn!(
+                        "settings.json has schema version {version}, newer than {SCHEMA_VERSION}; \
+                         using defaults and leaving the file untouched"
+                    );
+                    newer_file = Some(version);
+                    Settings::default()
+                }
+                Err(Upgrade::Invalid(error)) => {
+                    log::warn!("Ignoring invalid settings file: {error}");
+                    Settings::default()
+                }
+            },
+            None => Settings::default(),
+        };
+
+        let modified = path.as_deref().and_then(modified_time);
+        backups::set_retention(settings.backups.clone());
+        Self {
+            path,
+            settings,
+            newer_file,
+            modified,
+            external_change: false,
+        }
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn set_timeouts(&mut self, timeouts: TimeoutPolicy) -> Result<(), AppError> {
+        self.settings.timeouts = timeouts;
+        self.save()
+    }
+
+    /// Replaces every setting at once.
+    pub fn replace(&mut self, mut settings: Settings) -> Result<Settings, AppError> {
+        validate(&settings)?;
+        hotkeys::validate_bindings(&mut settings.hotkeys)?;
+        backups::set_retention(settings.backups.clone());
+        self.settings = settings;
+        self.save()?;
+        Ok(self.settings.clone())
+    }
+
+    /// Puts every setting back to its default, user scan presets included.
+    pub fn reset(&mut self) -> Result<Settings, AppError> {
+        self.replace(Settings::default())
+    }
+
+    pub fn set_log_level(&mut self, level: Option<String>) -> Result<(), AppError> {
+        if let Some(level) = &level {
+            parse_log_level(level)?;
+        }
+        self.settings.log_level = level;
+        self.save()
+    }
+
+    pub fn hotkeys(&self) -> Vec<HotkeyBinding> {
+        hotkeys::list_bindings(&self.settings.hotkeys, &self.settings.hotkey_rpc)
+    }
+
+    /// Makes `action` call `rpc` when its hotkey fires, or a frontend action
+    /// again when `rpc` is `None`.
+    pub fn set_hotkey_rpc(
+        &mut self,
+        action: &str,
+        rpc: Option<HotkeyRpcAction>,
+    ) -> Result<Vec<HotkeyBinding>, AppError> {
+        match rpc {
+            Some(rpc) => {
+                hotkeys::validate_rpc_action(action, &rpc)?;
+                self.settings.hotkey_rpc.insert(action.to_string(), rpc);
+            }
+            None => {
+                self.settings.hotkey_rpc.remove(action);
+            }
+        }
+        self.save()?;
+        Ok(self.hotkeys())
+    }
+
+    /// Binds `action` to `chord`, or unbinds it when `chord` is empty.
+    /// Rejects a chord another action already uses.
+    pub fn set_hotkey(
+        &mut self,
+        action: &str,
+        chord: Option<&str>,
+    ) -> Result<Vec<HotkeyBinding>, AppError> {
+        let mut bindings = self.settings.hotkeys.clone();
+        hotkeys::bind(&mut bindings, action, chord)?;
+        self.settings.hotkeys = bindings;
+        self.save()?;
+        Ok(self.hotkeys())
+    }
+
+    /// Adds a user scan preset, replacing any with the same name. A preset
+    /// named like a built-in one shadows it.
+    pub fn save_scan_preset(&mut self, mut preset: ScanPreset) -> Result<(), AppError> {
+        scanner::validate_preset(&preset)?;
+        preset.builtin = false;
+        let presets = &mut self.settings.scanner.presets;
+        presets.retain(|existing| existing.name != preset.name);
+        presets.push(preset);
+        self.save()
+    }
+
+    /// Removes a user scan preset. Returns false when there was none by that
+    /// name; built-in presets cannot be removed.
+    pub fn delete_scan_preset(&mut self, name: &str) -> Result<bool, AppError> {
+        let presets = &mut self.settings.scanner.presets;
+        let before = presets.len();
+        presets.retain(|preset| preset.name != name);
+        if presets.len() == before {
+            return Ok(false);
+        }
+        self.save()?;
+        Ok(true)
+    }
+
+    /// Writes the current settings to `path` wrapped in an export envelope.
+    pub fn export(&self, path: &str) -> Result<(), AppError> {
+        let export = SettingsExport::<&Settings> {
+            format: EXPORT_FORMAT.to_string(),
+            version: EXPORT_VERSION,
+            exported_at: storage::now_millis(),
+            settings: &self.settings,
+        };
+        storage::write_json(Path::new(path), &export)
+    }
+
+    /// Replaces the current settings with the contents of an export file and
+    /// persists them.
+    pub fn import(&mut self, path: &str) -> Result<Settings, AppError> {
+        let export = storage::read_json::<SettingsExport<Value>>(Path::new(path))?
+            .ok_or_else(|| AppError::StorageError(format!("{path} does not exist")))?;
+
+        if export.format != EXPORT_FORMAT {
+            return Err(AppError::StorageError(format!(
+                "{path} is not a CARF settings export"
+            )));
+        }
+        if export.version > EXPORT_VERSION {
+            return Err(AppError::StorageError(format!(
+                "{path} was exported by a newer CARF (format version {})",
+                export.version
+            )));
+        }
+
+        self.settings = upgrade(export.settings).map_err(|error| upgrade_error(path, error))?;
+        backups::set_retention(self.settings.backups.clone());
+        self.newer_file = None;
+        self.external_change = false;
+        self.save()?;
+        Ok(self.settings.clone())
+    }
+
+    /// Re-reads `settings.json`, picking up edits made outside CARF.
+    pub fn reload(&mut self) -> Result<Settings, AppError> {
+        let path = self
+            .path
+            .clone()
+            .ok_or_else(|| AppError::StorageError("settings storage is unavailable".to_string()))?;
+        let modified = modified_time(&path);
+        let settings = match storage::read_json_with_backup::<Value>(&path)? {
+            Some(raw) => {
+                upgrade(raw).map_err(|error| upgrade_error(&path.to_string_lossy(), error))?
+            }
+            None => Settings::default(),
+        };
+
+        backups::set_retention(settings.backups.clone());
+        self.settings = settings;
+        self.newer_file = None;
+        self.modified = modified;
+        self.external_change = false;
+        Ok(self.settings.clone())
+    }
+
+    /// Checks whether `settings.json` changed on disk since CARF last read
+    /// or wrote it, reloading it when `reload_external_edits` is set.
+    pub fn poll_external_change(&mut self) -> Option<SettingsFileChange> {
+        let path = self.path.clone()?;
+        let modified = modified_time(&path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        let mut reloaded = false;
+        if self.settings.reload_external_edits {
+            match self.reload() {
+                Ok(_) => reloaded = true,
+                Err(error) => log::warn!("Failed to reload edited settings: {error}"),
+            }
+        }
+        self.external_change = !reloaded;
+        Some(SettingsFileChange {
+            path: path.to_string_lossy().into_owned(),
+            reloaded,
+        })
+    }
+
+    fn save(&mut self) -> Result<(), AppError> {
+        if self.external_change {
+            return Err(AppError::StorageConflict(
+                "settings.json was changed outside CARF; reload it before saving".to_string(),
+            ));
+        }
+        if let Some(version) = self.newer_file {
+            return Err(AppError::StorageError(format!(
+                "settings.json was written by a newer CARF (schema version {version}); \
+                 not overwriting it"
+            )));
+        }
+        let file = SettingsFile {
+            schema_version: SCHEMA_VERSION,
+            settings: &self.settings,
+        };
+        let path = self
+            .path
+            .as_deref()
+            .ok_or_else(|| AppError::StorageError("settings storage is unavailable".to_string()))?;
+        storage::write_json_with_backup(path, &file)?;
+        self.modified = modified_time(path);
+        Ok(())
+    }
+}
+
+impl Default for SettingsService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ─── Schema migrations ────────────────────────────────────────────────────────
+
+enum Upgrade {
+    /// Written by a newer CARF; its layout is unknown here.
+    Newer(u32),
+    Invalid(String),
+}
+
+/// Brings raw settings JSON of any known schema version to the current
+/// layout and checks it deserializes. Files without `schemaVersion` predate
+/// versioning and count as version 0.
+fn upgrade(mut raw: Value) -> Result<Settings, Upgrade> {
+    let Value::Object(object) = &mut raw else {
+        return Err(Upgrade::Invalid("expected a JSON object".to_string()));
+    };
+    let version = match object.remove("schemaVersion") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| Upgrade::Invalid(format!("bad schemaVersion {version}")))?,
+    };
+    if version > SCHEMA_VERSION {
+        return Err(Upgrade::Newer(version));
+    }
+
+    migrate(&mut raw, version);
+    let mut settings: Settings =
+        serde_json::from_value(raw).map_err(|error| Upgrade::Invalid(error.to_string()))?;
+
+    settings.scanner.presets.retain(|preset| {
+        let valid = scanner::validate_preset(preset);
+        if let Err(error) = &valid {
+            log::warn!("Dropping invalid scan preset {}: {error}", preset.name);
+        }
+        valid.is_ok()
+    });
+    Ok(settings)
+}
+
+fn validate(settings: &Settings) -> Result<(), AppError> {
+    if let Some(runtime) = &settings.default_runtime {
+        if !matches!(runtime.as_str(), "qjs" | "v8") {
+            return Err(AppError::InvalidArgument(format!(
+                "Unknown script runtime {runtime}; expected qjs or v8"
+            )));
+        }
+    }
+    if let Some(level) = &settings.log_level {
+        parse_log_level(level)?;
+    }
+    for (action, rpc) in &settings.hotkey_rpc {
+        hotkeys::validate_rpc_action(action, rpc)?;
+    }
+    for preset in &settings.scanner.presets {
+        scanner::validate_preset(preset)?;
+    }
+    Ok(())
+}
+
+fn parse_log_level(level: &str) -> Result<log::LevelFilter, AppError> {
+    level.parse().map_err(|_| {
+        AppError::InvalidArgument(format!(
+            "Unknown log level {level}; expected off, error, warn, info, debug or trace"
+        ))
+    })
+}
+
+/// Applies `Settings::log_level` to the backend logger. `RUST_LOG`, when
+/// set, wins.
+pub fn apply_log_level(level: Option<&str>) {
+    if std::env::var_os("RUST_LOG").is_some() {
+        return;
+    }
+    let filter = level
+        .and_then(|level| parse_log_level(level).ok())
+        .unwrap_or(log::LevelFilter::Info);
+    log::set_max_level(filter);
+}
+
+fn upgrade_error(source: &str, error: Upgrade) -> AppError {
+    match error {
+        Upgrade::Newer(version) => AppError::StorageError(format!(
+            "{source} holds settings schema version {version}, newer than this CARF supports"
+        )),
+        Upgrade::Invalid(error) => {
+            AppError::StorageError(format!("{source} holds invalid settings: {error}"))
+        }
+    }
+}
+
+/// Step `n` rewrites version `n` settings JSON into version `n + 1`.
+const MIGRATIONS: &[fn(&mut Value)] = &[
+    // 0 → 1: files from before versioning already have the version 1
+    // layout; they only gain the `schemaVersion` stamp on the next save.
+    |_raw| {},
+];
+const _: () = assert!(MIGRATIONS.len() == SCHEMA_VERSION as usize);
+
+fn migrate(raw: &mut Value, from: u32) {
+    for step in &MIGRATIONS[from as usize..] {
+        step(raw);
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
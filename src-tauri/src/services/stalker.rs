@@ -0,0 +1,281 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::frida::{FridaService, SessionMarker};
+use crate::services::hooks::native_hook_target;
+use crate::services::storage;
+
+/// Upper bound on buffered events per session. Once reached the oldest events
+/// are dropped so a runaway trace cannot exhaust host memory.
+const MAX_TRACE_EVENTS: usize = 200_000;
+const EXPORT_FORMAT: &str = "carf-stalker-trace";
+const EXPORT_VERSION: u32 = 1;
+
+// ─── Trace types ──────────────────────────────────────────────────────────────
+
+/// Which Stalker events to collect. Mirrors the agent's `startStalker` mask.
+///
+/// Fields left out of a request are off; omitting the whole mask traces calls
+/// and returns.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StalkerEventOptions {
+    #[serde(default)]
+    pub call: bool,
+    #[serde(default)]
+    pub ret: bool,
+    #[serde(default)]
+    pub exec: bool,
+    #[serde(default)]
+    pub block: bool,
+    #[serde(default)]
+    pub compile: bool,
+}
+
+impl Default for StalkerEventOptions {
+    fn default() -> Self {
+        Self {
+            call: true,
+            ret: true,
+            exec: false,
+            block: false,
+            compile: false,
+        }
+    }
+}
+
+/// Mirrors the agent's `StructuredStalkerEvent` shape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceEvent {
+    pub thread_id: i64,
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub from: String,
+    pub to: String,
+    pub from_module: Option<String>,
+    pub to_module: Option<String>,
+    pub from_symbol: Option<String>,
+    pub to_symbol: Option<String>,
+    pub depth: i64,
+    pub count: u64,
+}
+
+impl TraceEvent {
+    fn same_site(&self, other: &TraceEvent) -> bool {
+        self.thread_id == other.thread_id
+            && self.kind == other.kind
+            && self.from == other.from
+            && self.to == other.to
+            && self.depth == other.depth
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceSummary {
+    pub session_id: String,
+    /// Buffered entries after coalescing.
+    pub events: usize,
+    /// Raw events folded into an earlier entry's `count`.
+    pub coalesced: u64,
+    /// Entries discarded because the buffer hit its cap.
+    pub dropped: u64,
+}
+
+/// A function-scoped trace installed with `traceFunction`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FunctionTrace {
+    pub trace_id: String,
+    pub target: String,
+    pub address: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceExport<'a> {
+    format: &'static str,
+    version: u32,
+    exported_at: u64,
+    #[serde(flatten)]
+    summary: TraceSummary,
+    trace: &'a VecDeque<TraceEvent>,
+    markers: &'a [SessionMarker],
+}
+
+// ─── TraceStore ───────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct TraceBuffer {
+    events: VecDeque<TraceEvent>,
+    coalesced: u64,
+    dropped: u64,
+}
+
+impl TraceBuffer {
+    fn push(&mut self, event: TraceEvent) {
+        // Tight loops produce long runs of the same call/block; fold them into
+        // a single entry instead of storing every iteration.
+        if let Some(last) = self.events.back_mut() {
+            if last.same_site(&event) {
+                last.count = last.count.saturating_add(event.count);
+                self.coalesced += 1;
+                return;
+            }
+        }
+
+        if self.events.len() >= MAX_TRACE_EVENTS {
+            if self.dropped == 0 {
+                log::warn!("Stalker trace buffer is full; dropping the oldest events");
+            }
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(event);
+    }
+}
+
+/// Per-session buffer of `carf://stalker/event` batches, owned by the Frida
+/// actor so events are recorded in the order the agent sent them.
+#[derive(Default)]
+pub struct TraceStore {
+    buffers: HashMap<String, TraceBuffer>,
+}
+
+impl TraceStore {
+    /// Records a `carf://stalker/event` payload (`{ sessionId, events }`).
+    pub fn record(&mut self, payload: &Value) {
+        let Some(session_id) = payload.get("sessionId").and_then(Value::as_str) else {
+            return;
+        };
+        let Some(events) = payload.get("events").and_then(Value::as_array) else {
+            return;
+        };
+
+        let buffer = self.buffers.entry(session_id.to_string()).or_default();
+        for event in events {
+            match serde_json::from_value::<TraceEvent>(event.clone()) {
+                Ok(event) => buffer.push(event),
+                Err(error) => log::debug!("Skipping malformed stalker event: {error}"),
+            }
+        }
+    }
+
+    pub fn summary(&self, session_id: &str) -> TraceSummary {
+        let buffer = self.buffers.get(session_id);
+        TraceSummary {
+            session_id: session_id.to_string(),
+            events: buffer.map_or(0, |buffer| buffer.events.len()),
+            coalesced: buffer.map_or(0, |buffer| buffer.coalesced),
+            dropped: buffer.map_or(0, |buffer| buffer.dropped),
+        }
+    }
+
+    /// Entries recorded for the session so far, including dropped ones.
+    pub fn position(&self, session_id: &str) -> Option<u64> {
+        self.buffers
+            .get(session_id)
+            .map(|buffer| buffer.events.len() as u64 + buffer.dropped)
+    }
+
+    /// Writes the session's buffered trace to `path` as JSON, along with the
+    /// session's markers so traces can be aligned on them.
+    pub fn export(
+        &self,
+        session_id: &str,
+        path: &str,
+        markers: &[SessionMarker],
+    ) -> Result<TraceSummary, AppError> {
+        let buffer = self.buffers.get(session_id).ok_or_else(|| {
+            AppError::Internal(format!(
+                "No stalker trace recorded for session {session_id}"
+            ))
+        })?;
+
+        let summary = self.summary(session_id);
+        storage::write_json(
+            Path::new(path),
+            &TraceExport {
+                format: EXPORT_FORMAT,
+                version: EXPORT_VERSION,
+                exported_at: storage::now_millis(),
+                summary: summary.clone(),
+                trace: &buffer.events,
+                markers,
+            },
+        )?;
+        Ok(summary)
+    }
+
+    pub fn clear(&mut self, session_id: &str) {
+        self.buffers.remove(session_id);
+    }
+
+    /// Drops buffers for sessions that no longer exist.
+    pub fn retain_sessions(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.buffers.retain(|session_id, _| keep(session_id));
+    }
+}
+
+// ─── Agent calls ──────────────────────────────────────────────────────────────
+
+/// Starts following `thread_id`. Events stream as `carf://stalker/event` and
+/// are buffered host-side until exported or the session ends.
+pub fn start_thread_trace(
+    frida: &mut FridaService,
+    session_id: &str,
+    thread_id: u64,
+    events: &StalkerEventOptions,
+) -> Result<Value, AppError> {
+    frida.rpc_call(
+        session_id,
+        "startStalker",
+        json!({ "threadId": thread_id, "events": events }),
+    )
+}
+
+pub fn stop_thread_trace(
+    frida: &mut FridaService,
+    session_id: &str,
+    thread_id: u64,
+) -> Result<(), AppError> {
+    frida.rpc_call(session_id, "stopStalker", json!({ "threadId": thread_id }))?;
+    Ok(())
+}
+
+/// Traces every invocation of a function: the calling thread is followed from
+/// entry until the function returns.
+pub fn start_function_trace(
+    frida: &mut FridaService,
+    session_id: &str,
+    module: Option<&str>,
+    symbol_or_offset: &str,
+    events: &StalkerEventOptions,
+) -> Result<FunctionTrace, AppError> {
+    let target = native_hook_target(module, symbol_or_offset)?;
+    let response = frida.rpc_call(
+        session_id,
+        "traceFunction",
+        json!({ "target": target, "events": events }),
+    )?;
+    serde_json::from_value(response)
+        .map_err(|error| AppError::AgentRpcError(format!("unexpected agent response: {error}")))
+}
+
+pub fn stop_function_trace(
+    frida: &mut FridaService,
+    session_id: &str,
+    trace_id: &str,
+) -> Result<(), AppError> {
+    frida.rpc_call(
+        session_id,
+        "untraceFunction",
+        json!({ "traceId": trace_id }),
+    )?;
+    Ok(())
+}
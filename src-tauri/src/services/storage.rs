@@ -0,0 +1,212 @@
+use std::ffi::OsString;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::AppError;
+use crate::services::backups;
+
+/// Must match `identifier` in `tauri.conf.json` so the desktop app and the
+/// HTTP bridge read and write the same files.
+const APP_IDENTIFIER: &str = "com.carf.app";
+
+/// Returns the per-user CARF data directory, creating it if needed.
+///
+/// `CARF_DATA_DIR` overrides the platform default, which is useful for
+/// portable installs and for keeping test runs away from real user data.
+pub fn data_dir() -> Result<PathBuf, AppError> {
+    let dir = std::env::var_os("CARF_DATA_DIR")
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::data_dir().map(|base| base.join(APP_IDENTIFIER)))
+        .ok_or_else(|| {
+            AppError::StorageError("could not determine the user data directory".to_string())
+        })?;
+
+    fs::create_dir_all(&dir).map_err(|error| {
+        AppError::StorageError(format!("failed to create {}: {error}", dir.display()))
+    })?;
+    Ok(dir)
+}
+
+/// Resolves `name` inside the CARF data directory.
+pub fn data_file(name: &str) -> Result<PathBuf, AppError> {
+    Ok(data_dir()?.join(name))
+}
+
+/// Reads and deserializes a JSON file. Returns `Ok(None)` when the file does
+/// not exist yet so callers can fall back to defaults.
+pub fn read_json<T>(path: &Path) -> Result<Option<T>, AppError>
+where
+    T: DeserializeOwned,
+{
+    let raw = match fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => {
+            return Err(AppError::StorageError(format!(
+                "failed to read {}: {error}",
+                path.display()
+            )))
+        }
+    };
+
+    serde_json::from_str(&raw).map(Some).map_err(|error| {
+        AppError::StorageError(format!("{} is not valid: {error}", path.display()))
+    })
+}
+
+/// Serializes `value` as pretty JSON and writes it to `path`.
+///
+/// The JSON goes to a sibling temp file that is then renamed over `path`, so
+/// a crash mid-write leaves the old file intact rather than a truncated one.
+pub fn write_json<T>(path: &Path, value: &T) -> Result<(), AppError>
+where
+    T: Serialize,
+{
+    write_json_file(path, value, false)
+}
+
+/// `write_json` for files holding secrets: on Unix the file is created
+/// readable and writable by its owner only (0600). Elsewhere it relies on
+/// the per-user data directory's permissions.
+pub fn write_private_json<T>(path: &Path, value: &T) -> Result<(), AppError>
+where
+    T: Serialize,
+{
+    write_json_file(path, value, true)
+}
+
+fn write_json_file<T>(path: &Path, value: &T, private: bool) -> Result<(), AppError>
+where
+    T: Serialize,
+{
+    let raw = serde_json::to_string_pretty(value)
+        .map_err(|error| AppError::Internal(error.to_string()))?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|error| {
+            AppError::StorageError(format!("failed to create {}: {error}", parent.display()))
+        })?;
+    }
+
+    let temp = sibling(path, "tmp");
+    let written = create_file(&temp, private).and_then(|mut file| {
+        file.write_all(raw.as_bytes())?;
+        file.sync_all()
+    });
+    if let Err(error) = written.and_then(|()| fs::rename(&temp, path)) {
+        let _ = fs::remove_file(&temp);
+        return Err(AppError::StorageError(format!(
+            "failed to write {}: {error}",
+            path.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Creates (or truncates) `path`, with mode 0600 on Unix when `private`.
+fn create_file(path: &Path, private: bool) -> std::io::Result<fs::File> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+        // The mode only applies to new files, not to one left by a crash.
+        let _ = fs::remove_file(path);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    options.open(path)
+}
+
+/// `write_json` that first copies the current file to `<name>.bak`, for
+/// user data worth a second copy. Saves are also kept as timestamped copies
+/// under `backups/`, at most one per file every few minutes.
+pub fn write_json_with_backup<T>(path: &Path, value: &T) -> Result<(), AppError>
+where
+    T: Serialize,
+{
+    if path.exists() {
+        let backup = sibling(path, "bak");
+        fs::copy(path, &backup).map_err(|error| {
+            AppError::StorageError(format!("failed to back up {}: {error}", path.display()))
+        })?;
+    }
+    write_json(path, value)?;
+    backups::snapshot(path);
+    Ok(())
+}
+
+/// `read_json` that falls back to the `<name>.bak` left by
+/// `write_json_with_backup` when `path` is missing or unparseable.
+pub fn read_json_with_backup<T>(path: &Path) -> Result<Option<T>, AppError>
+where
+    T: DeserializeOwned,
+{
+    let primary = read_json(path);
+    if matches!(primary, Ok(Some(_))) {
+        return primary;
+    }
+
+    let backup = sibling(path, "bak");
+    match read_json(&backup) {
+        Ok(Some(value)) => {
+            match &primary {
+                Err(error) => log::warn!("Recovered {} from backup: {error}", path.display()),
+                _ => log::warn!("{} is missing; using its backup", path.display()),
+            }
+            Ok(Some(value))
+        }
+        _ => primary,
+    }
+}
+
+/// Shows `path` in the OS file manager, selecting it where the platform
+/// supports that and opening its folder otherwise.
+pub fn reveal_in_file_manager(path: &Path) -> Result<(), AppError> {
+    let spawned = if cfg!(target_os = "macos") {
+        Command::new("open").arg("-R").arg(path).spawn()
+    } else if cfg!(target_os = "windows") {
+        let mut select = OsString::from("/select,");
+        select.push(path);
+        Command::new("explorer").arg(select).spawn()
+    } else {
+        let folder = if path.is_dir() {
+            path
+        } else {
+            path.parent().unwrap_or(path)
+        };
+        Command::new("xdg-open").arg(folder).spawn()
+    };
+
+    let mut child = spawned.map_err(|error| {
+        AppError::StorageError(format!("failed to open the file manager: {error}"))
+    })?;
+    // Reap the launcher once it hands off to the file manager.
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Milliseconds since the Unix epoch, the timestamp format of every stored
+/// record and emitted event.
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// `path` with `.suffix` appended to its file name.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
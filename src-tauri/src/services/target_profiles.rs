@@ -0,0 +1,198 @@
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::AppError;
+use crate::services::frida::SpawnOptions;
+use crate::services::hooks::{self, HookOptions};
+use crate::services::hotkeys::{self, HotkeyBinding};
+use crate::services::script_catalog;
+use crate::services::session_manager::SessionInfo;
+use crate::services::storage;
+
+const PROFILES_FILE: &str = "target-profiles.json";
+
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+// ─── Profile types ────────────────────────────────────────────────────────────
+
+/// Everything needed to get from "CARF is open" to "instrumented target" in
+/// one `profile_launch`.
+///
+/// Mirrors frontend `TargetProfile`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TargetProfile {
+    /// Assigned on first save.
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    /// Device id, or `usb` for the first USB device. Unset means local.
+    #[serde(default)]
+    pub device: Option<String>,
+    pub target: ProfileTarget,
+    /// Bundled script ids, loaded together as the session's user script.
+    #[serde(default)]
+    pub scripts: Vec<String>,
+    /// Native hooks installed once the scripts are loaded.
+    #[serde(default)]
+    pub hooks: Vec<ProfileHook>,
+    /// Bound on launch. RPC actions without a session go to the launched one.
+    #[serde(default)]
+    pub hotkeys: Vec<HotkeyBinding>,
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+/// Mirrors frontend `ProfileTarget`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum ProfileTarget {
+    /// Attach to a running process by name, identifier or pid.
+    #[serde(rename_all = "camelCase")]
+    Attach {
+        target: Value,
+        /// Keep polling until the target starts instead of failing.
+        #[serde(default)]
+        wait: bool,
+        timeout_ms: Option<u64>,
+    },
+    /// Spawn the target. It is kept suspended until scripts and hooks are
+    /// in, then resumed unless `autoResume` is `false`.
+    Spawn(Box<SpawnOptions>),
+}
+
+/// Mirrors frontend `ProfileHook`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileHook {
+    #[serde(default)]
+    pub module: Option<String>,
+    pub symbol_or_offset: String,
+    #[serde(default)]
+    pub options: HookOptions,
+}
+
+/// What `profile_launch` did. Steps after the session exists are reported
+/// rather than aborting the launch.
+///
+/// Mirrors frontend `ProfileLaunchReport`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileLaunchReport {
+    pub profile_id: String,
+    pub session: SessionInfo,
+    pub steps: Vec<ProfileLaunchStep>,
+}
+
+/// Mirrors frontend `ProfileLaunchStep`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileLaunchStep {
+    /// `scripts`, `hook <target>`, `hotkey <action>` or `resume`.
+    pub name: String,
+    pub error: Option<String>,
+}
+
+// ─── Store ────────────────────────────────────────────────────────────────────
+
+/// Saved profiles, most recently changed first.
+pub fn list() -> Result<Vec<TargetProfile>, AppError> {
+    let mut profiles = read_file()?;
+    profiles.sort_by_key(|profile| std::cmp::Reverse(profile.updated_at));
+    Ok(profiles)
+}
+
+pub fn get(profile_id: &str) -> Result<TargetProfile, AppError> {
+    read_file()?
+        .into_iter()
+        .find(|profile| profile.id == profile_id)
+        .ok_or_else(|| AppError::InvalidArgument(format!("No target profile {profile_id}")))
+}
+
+/// Creates a profile (when `id` is empty) or replaces the one with its id.
+/// Hotkey chords are normalized; unknown scripts and bad hook targets are
+/// rejected here rather than at launch.
+pub fn save(mut profile: TargetProfile) -> Result<TargetProfile, AppError> {
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() {
+        return Err(AppError::InvalidArgument(
+            "Profile name must not be empty".to_string(),
+        ));
+    }
+    for script_id in &profile.scripts {
+        script_catalog::bundled_source(script_id)?;
+    }
+    for hook in &profile.hooks {
+        hooks::native_hook_target(hook.module.as_deref(), &hook.symbol_or_offset)?;
+    }
+    for binding in profile.hotkeys.iter_mut() {
+        binding.chord = hotkeys::normalize_chord(&binding.chord)?;
+        if let Some(rpc) = &binding.rpc {
+            hotkeys::validate_rpc_action(&binding.action, rpc)?;
+        }
+    }
+    if profile.id.trim().is_empty() {
+        profile.id = uuid::Uuid::new_v4().to_string();
+    }
+    profile.updated_at = storage::now_millis();
+
+    update_file(|file| {
+        match file.iter_mut().find(|saved| saved.id == profile.id) {
+            Some(saved) => *saved = profile.clone(),
+            None => file.push(profile.clone()),
+        }
+        profile
+    })
+}
+
+/// Returns whether a profile was removed.
+pub fn delete(profile_id: &str) -> Result<bool, AppError> {
+    update_file(|file| {
+        let before = file.len();
+        file.retain(|profile| profile.id != profile_id);
+        file.len() != before
+    })
+}
+
+/// The profile's scripts as one user script. Each runs in its own function
+/// scope, since bundled scripts declare the same top-level helpers.
+pub fn script_source(script_ids: &[String]) -> Result<Option<String>, AppError> {
+    match script_ids {
+        [] => Ok(None),
+        [script_id] => Ok(Some(script_catalog::bundled_source(script_id)?.to_string())),
+        _ => {
+            let wrapped = script_ids
+                .iter()
+                .map(|script_id| {
+                    let source = script_catalog::bundled_source(script_id)?;
+                    Ok(format!(
+                        "// {script_id}\n(function () {{\n{source}\n}})();\n"
+                    ))
+                })
+                .collect::<Result<Vec<_>, AppError>>()?;
+            Ok(Some(wrapped.concat()))
+        }
+    }
+}
+
+fn lock_file() -> Result<std::sync::MutexGuard<'static, ()>, AppError> {
+    FILE_LOCK
+        .lock()
+        .map_err(|_| AppError::Internal("target profiles lock poisoned".to_string()))
+}
+
+fn read_file() -> Result<Vec<TargetProfile>, AppError> {
+    let _guard = lock_file()?;
+    Ok(storage::read_json_with_backup(&storage::data_file(PROFILES_FILE)?)?.unwrap_or_default())
+}
+
+fn update_file<T>(update: impl FnOnce(&mut Vec<TargetProfile>) -> T) -> Result<T, AppError> {
+    let _guard = lock_file()?;
+    let path = storage::data_file(PROFILES_FILE)?;
+    let mut file: Vec<TargetProfile> = storage::read_json_with_backup(&path)?.unwrap_or_default();
+    let result = update(&mut file);
+    storage::write_json_with_backup(&path, &file)?;
+    Ok(result)
+}
@@ -0,0 +1,367 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::frida::FridaService;
+use crate::services::inspector;
+
+const DEFAULT_STRING_LENGTH: usize = 256;
+const MAX_READ_LENGTH: usize = 64 * 1024;
+
+/// Mirrors frontend `TypedValueType`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TypedValueType {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    F32,
+    F64,
+    Pointer,
+    Utf8,
+    Utf16,
+    Bytes,
+}
+
+/// Mirrors frontend `Endianness`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+/// Mirrors frontend `TypedReadOptions`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TypedReadOptions {
+    /// Defaults to the target's byte order.
+    pub endianness: Option<Endianness>,
+    /// Bytes to read for `bytes` (required) and the most to read for
+    /// strings, which stop at the first NUL. Strings default to 256.
+    pub length: Option<usize>,
+}
+
+/// A value as it sits in memory. 64-bit integers are decimal strings and
+/// pointers `0x` strings so they survive JSON numbers; `bytes` is hex.
+///
+/// Mirrors frontend `TypedValue`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedValue {
+    pub address: String,
+    #[serde(rename = "type")]
+    pub value_type: TypedValueType,
+    pub endianness: Endianness,
+    /// Bytes read or written.
+    pub size: usize,
+    pub value: Value,
+    /// Hex of the raw bytes.
+    pub raw: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TargetLayout {
+    pointer_size: usize,
+    #[serde(default)]
+    endianness: Option<Endianness>,
+}
+
+/// Reads a `value_type` at `address`, decoded with the target's pointer
+/// size and (unless overridden) byte order.
+pub fn read(
+    frida: &mut FridaService,
+    session_id: &str,
+    address: &str,
+    value_type: TypedValueType,
+    options: TypedReadOptions,
+) -> Result<TypedValue, AppError> {
+    let layout = target_layout(frida, session_id)?;
+    let endianness = options
+        .endianness
+        .or(layout.endianness)
+        .unwrap_or(Endianness::Little);
+    let size = match value_type {
+        TypedValueType::Bytes => options.length.ok_or_else(|| {
+            AppError::InvalidArgument("length is required to read bytes".to_string())
+        })?,
+        TypedValueType::Utf8 | TypedValueType::Utf16 => {
+            options.length.unwrap_or(DEFAULT_STRING_LENGTH)
+        }
+        _ => scalar_size(value_type, layout.pointer_size),
+    };
+    if size == 0 || size > MAX_READ_LENGTH {
+        return Err(AppError::InvalidArgument(format!(
+            "length must be between 1 and {MAX_READ_LENGTH}, got {size}"
+        )));
+    }
+
+    let response = frida.rpc_call(
+        session_id,
+        "readMemory",
+        json!({ "address": address, "size": size }),
+    )?;
+    let bytes = inspector::decode_hex(response.as_str().unwrap_or_default())
+        .ok_or_else(|| AppError::AgentRpcError("readMemory returned invalid hex".to_string()))?;
+    let (value, used) = decode(value_type, endianness, &bytes);
+    Ok(TypedValue {
+        address: address.to_string(),
+        value_type,
+        endianness,
+        size: used,
+        value,
+        raw: hex(&bytes[..used]),
+    })
+}
+
+/// Encodes `value` as a `value_type` for the target and writes it at
+/// `address`. Numbers may be JSON numbers or strings (decimal or `0x`);
+/// strings are written without a terminator, `bytes` takes hex.
+pub fn write(
+    frida: &mut FridaService,
+    session_id: &str,
+    address: &str,
+    value_type: TypedValueType,
+    value: Value,
+    endianness: Option<Endianness>,
+) -> Result<TypedValue, AppError> {
+    let layout = target_layout(frida, session_id)?;
+    let endianness = endianness
+        .or(layout.endianness)
+        .unwrap_or(Endianness::Little);
+    let bytes = encode(value_type, endianness, layout.pointer_size, &value)?;
+    if bytes.is_empty() {
+        return Err(AppError::InvalidArgument(
+            "value encodes to no bytes".to_string(),
+        ));
+    }
+
+    frida.rpc_call(
+        session_id,
+        "writeMemory",
+        json!({ "address": address, "bytes": hex(&bytes) }),
+    )?;
+    let (value, size) = decode(value_type, endianness, &bytes);
+    Ok(TypedValue {
+        address: address.to_string(),
+        value_type,
+        endianness,
+        size,
+        value,
+        raw: hex(&bytes),
+    })
+}
+
+fn target_layout(frida: &mut FridaService, session_id: &str) -> Result<TargetLayout, AppError> {
+    let response = frida.rpc_call(session_id, "getProcessInfo", Value::Null)?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected getProcessInfo response: {error}"))
+    })
+}
+
+fn scalar_size(value_type: TypedValueType, pointer_size: usize) -> usize {
+    match value_type {
+        TypedValueType::U8 | TypedValueType::I8 => 1,
+        TypedValueType::U16 | TypedValueType::I16 => 2,
+        TypedValueType::U32 | TypedValueType::I32 | TypedValueType::F32 => 4,
+        TypedValueType::U64 | TypedValueType::I64 | TypedValueType::F64 => 8,
+        TypedValueType::Pointer => pointer_size,
+        TypedValueType::Utf8 | TypedValueType::Utf16 | TypedValueType::Bytes => 0,
+    }
+}
+
+fn integer_bounds(value_type: TypedValueType, pointer_size: usize) -> Option<(i128, i128)> {
+    let width = scalar_size(value_type, pointer_size);
+    match value_type {
+        TypedValueType::I8 | TypedValueType::I16 | TypedValueType::I32 | TypedValueType::I64 => {
+            Some(integer_range(width, true))
+        }
+        TypedValueType::U8
+        | TypedValueType::U16
+        | TypedValueType::U32
+        | TypedValueType::U64
+        | TypedValueType::Pointer => Some(integer_range(width, false)),
+        _ => None,
+    }
+}
+
+/// Inclusive range of a `width`-byte integer. Shared with the scanner.
+pub(crate) fn integer_range(width: usize, signed: bool) -> (i128, i128) {
+    let bits = width as u32 * 8;
+    if signed {
+        (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1)
+    } else {
+        (0, (1i128 << bits) - 1)
+    }
+}
+
+/// Byte order from a `getProcessInfo` response; little-endian when the
+/// agent does not report one.
+pub(crate) fn reported_endianness(info: &Value) -> Endianness {
+    info.get("endianness")
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or(Endianness::Little)
+}
+
+fn encode(
+    value_type: TypedValueType,
+    endianness: Endianness,
+    pointer_size: usize,
+    value: &Value,
+) -> Result<Vec<u8>, AppError> {
+    if let Some((min, max)) = integer_bounds(value_type, pointer_size) {
+        let number = parse_integer(value)?;
+        if number < min || number > max {
+            return Err(AppError::InvalidArgument(format!(
+                "{number} does not fit in {value_type:?} ({min}..={max})"
+            )));
+        }
+        let size = scalar_size(value_type, pointer_size);
+        return Ok(match endianness {
+            Endianness::Little => (number as u64).to_le_bytes()[..size].to_vec(),
+            Endianness::Big => (number as u64).to_be_bytes()[8 - size..].to_vec(),
+        });
+    }
+
+    let text = || {
+        value.as_str().ok_or_else(|| {
+            AppError::InvalidArgument(format!("{value_type:?} value must be a string"))
+        })
+    };
+    Ok(match value_type {
+        TypedValueType::F32 => {
+            let number = parse_float(value)? as f32;
+            match endianness {
+                Endianness::Little => number.to_le_bytes().to_vec(),
+                Endianness::Big => number.to_be_bytes().to_vec(),
+            }
+        }
+        TypedValueType::F64 => {
+            let number = parse_float(value)?;
+            match endianness {
+                Endianness::Little => number.to_le_bytes().to_vec(),
+                Endianness::Big => number.to_be_bytes().to_vec(),
+            }
+        }
+        TypedValueType::Utf8 => text()?.as_bytes().to_vec(),
+        TypedValueType::Utf16 => text()?
+            .encode_utf16()
+            .flat_map(|unit| match endianness {
+                Endianness::Little => unit.to_le_bytes(),
+                Endianness::Big => unit.to_be_bytes(),
+            })
+            .collect(),
+        _ => {
+            let digits: String = text()?.split_whitespace().collect();
+            inspector::decode_hex(&digits).ok_or_else(|| {
+                AppError::InvalidArgument(format!("{digits:?} is not a hex byte string"))
+            })?
+        }
+    })
+}
+
+/// Decodes `bytes` and returns the value with the number of bytes it took;
+/// strings stop at their first NUL.
+fn decode(value_type: TypedValueType, endianness: Endianness, bytes: &[u8]) -> (Value, usize) {
+    let unsigned = || {
+        let mut raw = [0u8; 8];
+        match endianness {
+            Endianness::Little => {
+                raw[..bytes.len()].copy_from_slice(bytes);
+                u64::from_le_bytes(raw)
+            }
+            Endianness::Big => {
+                raw[8 - bytes.len()..].copy_from_slice(bytes);
+                u64::from_be_bytes(raw)
+            }
+        }
+    };
+    let signed = || {
+        let shift = 64 - bytes.len() as u32 * 8;
+        ((unsigned() << shift) as i64) >> shift
+    };
+    match value_type {
+        TypedValueType::U8 | TypedValueType::U16 | TypedValueType::U32 => {
+            (json!(unsigned()), bytes.len())
+        }
+        TypedValueType::I8 | TypedValueType::I16 | TypedValueType::I32 => {
+            (json!(signed()), bytes.len())
+        }
+        TypedValueType::U64 => (json!(unsigned().to_string()), bytes.len()),
+        TypedValueType::I64 => (json!(signed().to_string()), bytes.len()),
+        TypedValueType::Pointer => (json!(format!("{:#x}", unsigned())), bytes.len()),
+        TypedValueType::F32 => {
+            let bits = unsigned() as u32;
+            (json!(f32::from_bits(bits) as f64), bytes.len())
+        }
+        TypedValueType::F64 => (json!(f64::from_bits(unsigned())), bytes.len()),
+        TypedValueType::Utf8 => {
+            let end = bytes.iter().position(|&byte| byte == 0);
+            let text = String::from_utf8_lossy(&bytes[..end.unwrap_or(bytes.len())]);
+            (json!(text), end.map_or(bytes.len(), |end| end + 1))
+        }
+        TypedValueType::Utf16 => {
+            let units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| match endianness {
+                    Endianness::Little => u16::from_le_bytes([pair[0], pair[1]]),
+                    Endianness::Big => u16::from_be_bytes([pair[0], pair[1]]),
+                })
+                .take_while(|&unit| unit != 0)
+                .collect();
+            let terminated = units.len() < bytes.len() / 2;
+            let used = (units.len() + usize::from(terminated)) * 2;
+            (json!(String::from_utf16_lossy(&units)), used)
+        }
+        TypedValueType::Bytes => (json!(hex(bytes)), bytes.len()),
+    }
+}
+
+fn parse_integer(value: &Value) -> Result<i128, AppError> {
+    let invalid = || AppError::InvalidArgument(format!("{value} is not an integer"));
+    match value {
+        Value::Number(number) => number
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| number.as_u64().map(i128::from))
+            .ok_or_else(invalid),
+        Value::String(text) => parse_integer_text(text.trim()).ok_or_else(invalid),
+        _ => Err(invalid()),
+    }
+}
+
+/// Decimal or `0x` hex with an optional leading `-`. Shared with the
+/// scanner.
+pub(crate) fn parse_integer_text(text: &str) -> Option<i128> {
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text),
+    };
+    let magnitude = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => i128::from_str_radix(hex, 16).ok()?,
+        None => digits.parse::<i128>().ok()?,
+    };
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+fn parse_float(value: &Value) -> Result<f64, AppError> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+    .ok_or_else(|| AppError::InvalidArgument(format!("{value} is not a number")))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
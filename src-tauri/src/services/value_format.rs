@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// ─── Format types ─────────────────────────────────────────────────────────────
+
+/// Display rules attached to a saved value so every view and export renders
+/// it the same way, e.g. a millisecond counter shown as `12.5s`.
+///
+/// Mirrors frontend `ValueFormat`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ValueFormat {
+    pub radix: Radix,
+    /// The raw value is divided by this before display (`1000` for ms → s).
+    pub scale: Option<f64>,
+    /// Fixed number of decimal places after scaling.
+    pub decimals: Option<usize>,
+    /// Appended verbatim, so include a leading space if one is wanted.
+    pub unit: Option<String>,
+    /// Raw integer value → name. Checked before any other rule.
+    pub enum_names: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Radix {
+    #[default]
+    Dec,
+    /// Integers only; scale and decimals are ignored.
+    Hex,
+}
+
+enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+// ─── Formatting ───────────────────────────────────────────────────────────────
+
+/// Renders `value` according to `format`. 64-bit values arrive as decimal or
+/// `0x` strings; anything that is not a number is shown as-is.
+pub fn format_value(value: &Value, format: &ValueFormat) -> String {
+    let Some(number) = parse_number(value) else {
+        return match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+    };
+
+    if let Number::Int(raw) = number {
+        if let Some(name) = format.enum_names.get(&raw.to_string()) {
+            return name.clone();
+        }
+    }
+
+    let text = match (format.radix, number) {
+        (Radix::Hex, Number::Int(raw)) if raw < 0 => format!("-0x{:X}", raw.unsigned_abs()),
+        (Radix::Hex, Number::Int(raw)) => format!("0x{raw:X}"),
+        (_, number) => format_decimal(number, format),
+    };
+
+    match format.unit.as_deref() {
+        Some(unit) => format!("{text}{unit}"),
+        None => text,
+    }
+}
+
+pub fn format_values(values: &[Value], format: &ValueFormat) -> Vec<String> {
+    values
+        .iter()
+        .map(|value| format_value(value, format))
+        .collect()
+}
+
+fn format_decimal(number: Number, format: &ValueFormat) -> String {
+    let scale = format.scale.filter(|scale| *scale != 0.0 && *scale != 1.0);
+    match (number, scale, format.decimals) {
+        (Number::Int(raw), None, None) => raw.to_string(),
+        (number, scale, decimals) => {
+            let value = match number {
+                Number::Int(raw) => raw as f64,
+                Number::Float(value) => value,
+            } / scale.unwrap_or(1.0);
+            match decimals {
+                Some(decimals) => format!("{value:.decimals$}"),
+                None => value.to_string(),
+            }
+        }
+    }
+}
+
+fn parse_number(value: &Value) -> Option<Number> {
+    match value {
+        Value::Number(number) => number
+            .as_i64()
+            .map(|raw| Number::Int(raw.into()))
+            .or_else(|| number.as_u64().map(|raw| Number::Int(raw.into())))
+            .or_else(|| number.as_f64().map(Number::Float)),
+        Value::String(text) => {
+            let text = text.trim();
+            if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+                i128::from_str_radix(hex, 16).ok().map(Number::Int)
+            } else if let Ok(raw) = text.parse::<i128>() {
+                Some(Number::Int(raw))
+            } else {
+                text.parse::<f64>().ok().map(Number::Float)
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::error::AppError;
+use crate::services::bookmarks;
+use crate::services::frida::FridaService;
+
+/// An address to record, typically a scan result. `value_type` is one of
+/// `u8`…`u64`, `i8`…`i64`, `f32`, `f64`, `pointer` or `bytes`; `bytes`
+/// needs a `size`.
+///
+/// Mirrors frontend `SnapshotEntry`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotEntry {
+    pub address: String,
+    #[serde(rename = "type")]
+    pub value_type: String,
+    #[serde(default)]
+    pub size: Option<usize>,
+}
+
+/// Mirrors frontend `ValueSnapshotInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValueSnapshotInfo {
+    pub name: String,
+    pub created_at: u64,
+    pub count: usize,
+    /// Entries whose address could not be read when the snapshot was taken.
+    pub unreadable: usize,
+}
+
+/// One address in two snapshots. `delta` is `after - before` for numeric
+/// types when both values were read.
+///
+/// Mirrors frontend `SnapshotChange`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotChange {
+    pub address: String,
+    #[serde(rename = "type")]
+    pub value_type: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub delta: Option<f64>,
+}
+
+/// Mirrors frontend `SnapshotComparison`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotComparison {
+    pub before: String,
+    pub after: String,
+    pub changed: Vec<SnapshotChange>,
+    pub unchanged: Vec<SnapshotChange>,
+    /// Addresses recorded only in `before`.
+    pub removed: Vec<String>,
+    /// Addresses recorded only in `after`.
+    pub added: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AgentSnapshot {
+    values: Vec<AgentValue>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AgentValue {
+    address: String,
+    #[serde(rename = "type")]
+    value_type: String,
+    value: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DeleteResponse {
+    deleted: bool,
+}
+
+/// Reads the current value of every entry and keeps them in the agent under
+/// `name`, replacing a snapshot with the same name. Snapshots go away with
+/// the process.
+pub fn save(
+    frida: &mut FridaService,
+    session_id: &str,
+    name: &str,
+    entries: Vec<SnapshotEntry>,
+) -> Result<ValueSnapshotInfo, AppError> {
+    if name.trim().is_empty() {
+        return Err(AppError::InvalidArgument(
+            "snapshot name must not be empty".to_string(),
+        ));
+    }
+    let response = frida.rpc_call(
+        session_id,
+        "saveValueSnapshot",
+        json!({ "name": name, "entries": entries }),
+    )?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected saveValueSnapshot response: {error}"))
+    })
+}
+
+pub fn list(
+    frida: &mut FridaService,
+    session_id: &str,
+) -> Result<Vec<ValueSnapshotInfo>, AppError> {
+    let response = frida.rpc_call(session_id, "listValueSnapshots", json!({}))?;
+    serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected listValueSnapshots response: {error}"))
+    })
+}
+
+/// Returns whether a snapshot was removed.
+pub fn delete(frida: &mut FridaService, session_id: &str, name: &str) -> Result<bool, AppError> {
+    let response = frida.rpc_call(session_id, "deleteValueSnapshot", json!({ "name": name }))?;
+    serde_json::from_value::<DeleteResponse>(response)
+        .map(|response| response.deleted)
+        .map_err(|error| {
+            AppError::AgentRpcError(format!("unexpected deleteValueSnapshot response: {error}"))
+        })
+}
+
+/// Compares two snapshots address by address. An address recorded with
+/// different types in the two counts as changed.
+pub fn compare(
+    frida: &mut FridaService,
+    session_id: &str,
+    before: &str,
+    after: &str,
+) -> Result<SnapshotComparison, AppError> {
+    let mut old = fetch(frida, session_id, before)?;
+    let new = fetch(frida, session_id, after)?;
+
+    let mut comparison = SnapshotComparison {
+        before: before.to_string(),
+        after: after.to_string(),
+        changed: Vec::new(),
+        unchanged: Vec::new(),
+        removed: Vec::new(),
+        added: Vec::new(),
+    };
+    for (address, current) in new {
+        let Some(previous) = old.remove(&address) else {
+            comparison.added.push(current.address);
+            continue;
+        };
+        let same = previous.value_type == current.value_type
+            && previous.value.is_some()
+            && previous.value == current.value;
+        let change = SnapshotChange {
+            delta: delta(&current.value_type, &previous, &current),
+            address: current.address,
+            value_type: current.value_type,
+            before: previous.value,
+            after: current.value,
+        };
+        if same {
+            comparison.unchanged.push(change);
+        } else {
+            comparison.changed.push(change);
+        }
+    }
+    comparison.removed = old.into_values().map(|value| value.address).collect();
+    Ok(comparison)
+}
+
+/// Snapshot values keyed by normalized address.
+fn fetch(
+    frida: &mut FridaService,
+    session_id: &str,
+    name: &str,
+) -> Result<BTreeMap<u64, AgentValue>, AppError> {
+    let response = frida.rpc_call(session_id, "getValueSnapshot", json!({ "name": name }))?;
+    let snapshot: AgentSnapshot = serde_json::from_value(response).map_err(|error| {
+        AppError::AgentRpcError(format!("unexpected getValueSnapshot response: {error}"))
+    })?;
+    snapshot
+        .values
+        .into_iter()
+        .map(|value| {
+            let address = bookmarks::parse_hex(&value.address).ok_or_else(|| {
+                AppError::AgentRpcError(format!("snapshot address {} is not hex", value.address))
+            })?;
+            Ok((address, value))
+        })
+        .collect()
+}
+
+fn delta(value_type: &str, before: &AgentValue, after: &AgentValue) -> Option<f64> {
+    if before.value_type != after.value_type {
+        return None;
+    }
+    let (before, after) = (before.value.as_deref()?, after.value.as_deref()?);
+    match value_type {
+        "f32" | "f64" => Some(after.parse::<f64>().ok()? - before.parse::<f64>().ok()?),
+        "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" => {
+            Some((after.parse::<i128>().ok()? - before.parse::<i128>().ok()?) as f64)
+        }
+        _ => None,
+    }
+}
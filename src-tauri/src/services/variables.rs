@@ -0,0 +1,463 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::AppError;
+use crate::services::frida::FridaService;
+use crate::services::session_manager::SessionInfo;
+use crate::services::storage;
+
+pub const VARIABLES_FILE: &str = "variables.json";
+
+/// Profile → key → value, persisted as one JSON document.
+type VariableFile = BTreeMap<String, BTreeMap<String, Value>>;
+
+/// Serializes read-modify-write cycles; writes arrive both from commands and
+/// from agent events on the Frida actor thread.
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A profile's variables as last read, with the revision an editor passes
+/// back so a stale write is rejected instead of overwriting newer changes.
+///
+/// Mirrors frontend `VariableProfileSnapshot`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileSnapshot {
+    pub profile: String,
+    pub variables: BTreeMap<String, Value>,
+    /// Content hash of `variables`; changes whenever any entry does.
+    pub revision: String,
+    /// Modification time of the variables file in ms, when it exists.
+    pub modified_at: Option<u64>,
+}
+
+/// How keys changed on both sides since the common base are resolved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeStrategy {
+    /// Keep the caller's value (or deletion).
+    Ours,
+    /// Keep the value currently on disk (or deletion).
+    Theirs,
+    /// Keep both: arrays are concatenated without duplicates, objects are
+    /// merged key by key and a deletion loses to an edit. Scalars keep ours.
+    Union,
+}
+
+/// Mirrors frontend `VariableMergeResult`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeResult {
+    pub variables: BTreeMap<String, Value>,
+    pub revision: String,
+    /// Keys that changed differently on both sides and were resolved by the
+    /// strategy.
+    pub conflicts: Vec<String>,
+    /// Keys whose stored value changed, so live sessions can be updated.
+    #[serde(skip)]
+    pub changed: Vec<String>,
+}
+
+/// Variables are shared by every session of the same target: the app
+/// identifier when there is one, otherwise the process name.
+pub fn profile_for(session: &SessionInfo) -> String {
+    session
+        .identifier
+        .clone()
+        .unwrap_or_else(|| session.process_name.clone())
+}
+
+pub fn list_profiles() -> Result<Vec<String>, AppError> {
+    Ok(read_file()?.into_keys().collect())
+}
+
+pub fn list_variables(profile: &str) -> Result<BTreeMap<String, Value>, AppError> {
+    Ok(read_file()?.remove(profile).unwrap_or_default())
+}
+
+pub fn get_variable(profile: &str, key: &str) -> Result<Option<Value>, AppError> {
+    Ok(list_variables(profile)?.remove(key))
+}
+
+pub fn snapshot(profile: &str) -> Result<ProfileSnapshot, AppError> {
+    let _guard = lock_file()?;
+    let path = storage::data_file(VARIABLES_FILE)?;
+    let mut file: VariableFile = storage::read_json_with_backup(&path)?.unwrap_or_default();
+    let variables = file.remove(profile).unwrap_or_default();
+    let modified_at = fs::metadata(&path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_millis() as u64);
+
+    Ok(ProfileSnapshot {
+        profile: profile.to_string(),
+        revision: revision_of(&variables),
+        variables,
+        modified_at,
+    })
+}
+
+/// Stores a variable and returns the profile's new revision. With
+/// `expected_revision`, fails with `StorageConflict` if the profile was
+/// changed by someone else since that revision was read.
+pub fn set_variable(
+    profile: &str,
+    key: &str,
+    value: Value,
+    expected_revision: Option<&str>,
+) -> Result<String, AppError> {
+    if key.trim().is_empty() {
+        return Err(AppError::InvalidArgument(
+            "variable key must not be empty".to_string(),
+        ));
+    }
+    update_file(|file| {
+        check_revision(file, profile, expected_revision)?;
+        let variables = file.entry(profile.to_string()).or_default();
+        variables.insert(key.to_string(), value);
+        Ok(revision_of(variables))
+    })
+}
+
+/// Removes a variable and returns the profile's new revision; see
+/// [`set_variable`] for `expected_revision`.
+pub fn delete_variable(
+    profile: &str,
+    key: &str,
+    expected_revision: Option<&str>,
+) -> Result<String, AppError> {
+    update_file(|file| {
+        check_revision(file, profile, expected_revision)?;
+        let Some(variables) = file.get_mut(profile) else {
+            return Ok(revision_of(&BTreeMap::new()));
+        };
+        variables.remove(key);
+        let revision = revision_of(variables);
+        if variables.is_empty() {
+            file.remove(profile);
+        }
+        Ok(revision)
+    })
+}
+
+/// Three-way merges an editor's copy of a profile (`ours`, edited from
+/// `base`) with what is stored now, writes the result and returns it.
+///
+/// Keys changed on only one side take that side's change; keys changed on
+/// both sides to different values are conflicts resolved by `strategy`.
+pub fn merge_profile(
+    profile: &str,
+    base: &BTreeMap<String, Value>,
+    ours: &BTreeMap<String, Value>,
+    strategy: MergeStrategy,
+) -> Result<MergeResult, AppError> {
+    update_file(|file| {
+        let theirs = file.remove(profile).unwrap_or_default();
+        let (merged, conflicts) = merge_variables(base, ours, &theirs, strategy);
+
+        let changed = merged
+            .keys()
+            .chain(theirs.keys())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .filter(|key| merged.get(*key) != theirs.get(*key))
+            .cloned()
+            .collect();
+        if !merged.is_empty() {
+            file.insert(profile.to_string(), merged.clone());
+        }
+
+        Ok(MergeResult {
+            revision: revision_of(&merged),
+            variables: merged,
+            conflicts,
+            changed,
+        })
+    })
+}
+
+/// Persists a `vars/set` or `vars/delete` event sent by an agent.
+pub fn apply_agent_event(profile: &str, payload: &Value) -> Result<(), AppError> {
+    let Some(key) = payload.get("key").and_then(Value::as_str) else {
+        return Ok(());
+    };
+    match payload.get("value") {
+        Some(value) if !value.is_null() => {
+            set_variable(profile, key, value.clone(), None).map(|_| ())
+        }
+        _ => delete_variable(profile, key, None).map(|_| ()),
+    }
+}
+
+/// Pushes a changed variable into the agents of every live session for
+/// `profile` so running scripts see edits made from the UI.
+pub fn sync_to_sessions(
+    frida: &mut FridaService,
+    profile: &str,
+    key: &str,
+    value: Option<&Value>,
+) -> Result<(), AppError> {
+    for session in frida.list_sessions()? {
+        if profile_for(&session) != profile {
+            continue;
+        }
+        if let Err(error) = frida.rpc_call(
+            &session.id,
+            "setVariable",
+            json!({ "key": key, "value": value, "persist": false }),
+        ) {
+            log::warn!(
+                "Failed to sync variable {key} to session {}: {error}",
+                session.id
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The merge behind [`merge_profile`]; returns the merged variables and the
+/// conflicting keys.
+fn merge_variables(
+    base: &BTreeMap<String, Value>,
+    ours: &BTreeMap<String, Value>,
+    theirs: &BTreeMap<String, Value>,
+    strategy: MergeStrategy,
+) -> (BTreeMap<String, Value>, Vec<String>) {
+    let keys: BTreeSet<&String> = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .collect();
+
+    let mut merged = BTreeMap::new();
+    let mut conflicts = Vec::new();
+    for key in keys {
+        let (base_value, our_value, their_value) = (base.get(key), ours.get(key), theirs.get(key));
+        let value = if our_value == their_value || our_value == base_value {
+            their_value.cloned()
+        } else if their_value == base_value {
+            our_value.cloned()
+        } else {
+            conflicts.push(key.clone());
+            resolve_conflict(our_value, their_value, strategy)
+        };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value);
+        }
+    }
+    (merged, conflicts)
+}
+
+fn resolve_conflict(
+    ours: Option<&Value>,
+    theirs: Option<&Value>,
+    strategy: MergeStrategy,
+) -> Option<Value> {
+    match (strategy, ours, theirs) {
+        (MergeStrategy::Ours, ours, _) => ours.cloned(),
+        (MergeStrategy::Theirs, _, theirs) => theirs.cloned(),
+        (MergeStrategy::Union, Some(ours), Some(theirs)) => Some(union_values(ours, theirs)),
+        (MergeStrategy::Union, ours, theirs) => ours.or(theirs).cloned(),
+    }
+}
+
+fn union_values(ours: &Value, theirs: &Value) -> Value {
+    match (ours, theirs) {
+        (Value::Array(ours), Value::Array(theirs)) => {
+            let mut items = ours.clone();
+            for item in theirs {
+                if !items.contains(item) {
+                    items.push(item.clone());
+                }
+            }
+            Value::Array(items)
+        }
+        (Value::Object(ours), Value::Object(theirs)) => {
+            let mut fields = theirs.clone();
+            for (name, value) in ours {
+                let value = match theirs.get(name) {
+                    Some(their_value) => union_values(value, their_value),
+                    None => value.clone(),
+                };
+                fields.insert(name.clone(), value);
+            }
+            Value::Object(fields)
+        }
+        _ => ours.clone(),
+    }
+}
+
+fn check_revision(
+    file: &VariableFile,
+    profile: &str,
+    expected_revision: Option<&str>,
+) -> Result<(), AppError> {
+    let Some(expected) = expected_revision else {
+        return Ok(());
+    };
+    let current = file
+        .get(profile)
+        .map_or_else(|| revision_of(&BTreeMap::new()), revision_of);
+    if current != expected {
+        return Err(AppError::StorageConflict(format!(
+            "variables for {profile} changed since revision {expected} (now {current})"
+        )));
+    }
+    Ok(())
+}
+
+/// FNV-1a over the profile's canonical JSON. Keys are ordered (`BTreeMap`,
+/// and serde_json maps are sorted), so equal contents hash equally.
+fn revision_of(variables: &BTreeMap<String, Value>) -> String {
+    let raw = serde_json::to_vec(variables).unwrap_or_default();
+    let hash = raw.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+fn lock_file() -> Result<std::sync::MutexGuard<'static, ()>, AppError> {
+    FILE_LOCK
+        .lock()
+        .map_err(|_| AppError::Internal("variables lock poisoned".to_string()))
+}
+
+fn read_file() -> Result<VariableFile, AppError> {
+    let _guard = lock_file()?;
+    Ok(storage::read_json_with_backup(&storage::data_file(VARIABLES_FILE)?)?.unwrap_or_default())
+}
+
+/// Re-reads the file under the lock so edits made by another window or an
+/// external editor since the last read are kept; `update` sees them and
+/// can refuse with an error, in which case nothing is written.
+fn update_file<T>(
+    update: impl FnOnce(&mut VariableFile) -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let _guard = lock_file()?;
+    let path = storage::data_file(VARIABLES_FILE)?;
+    let mut file: VariableFile = storage::read_json_with_backup(&path)?.unwrap_or_default();
+    let result = update(&mut file)?;
+    storage::write_json_with_backup(&path, &file)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(value: Value) -> BTreeMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn merge(
+        base: Value,
+        ours: Value,
+        theirs: Value,
+        strategy: MergeStrategy,
+    ) -> (BTreeMap<String, Value>, Vec<String>) {
+        merge_variables(&vars(base), &vars(ours), &vars(theirs), strategy)
+    }
+
+    #[test]
+    fn one_sided_edits_are_kept_without_conflicts() {
+        let (merged, conflicts) = merge(
+            json!({ "a": 1, "b": 2 }),
+            json!({ "a": 10, "b": 2, "c": 3 }),
+            json!({ "a": 1, "b": 20, "d": 4 }),
+            MergeStrategy::Ours,
+        );
+        assert_eq!(merged, vars(json!({ "a": 10, "b": 20, "c": 3, "d": 4 })));
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn identical_edits_on_both_sides_are_not_conflicts() {
+        let (merged, conflicts) = merge(
+            json!({ "a": 1 }),
+            json!({ "a": 2 }),
+            json!({ "a": 2 }),
+            MergeStrategy::Union,
+        );
+        assert_eq!(merged, vars(json!({ "a": 2 })));
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn conflicts_follow_the_strategy() {
+        let base = json!({ "n": 1, "list": [1], "obj": { "x": 1 } });
+        let ours = json!({ "n": 2, "list": [1, 2], "obj": { "x": 2, "y": 1 } });
+        let theirs = json!({ "n": 3, "list": [1, 3], "obj": { "x": 3, "z": 1 } });
+
+        let (merged, conflicts) = merge(
+            base.clone(),
+            ours.clone(),
+            theirs.clone(),
+            MergeStrategy::Ours,
+        );
+        assert_eq!(merged, vars(ours.clone()));
+        assert_eq!(conflicts, ["list", "n", "obj"]);
+
+        let (merged, _) = merge(
+            base.clone(),
+            ours.clone(),
+            theirs.clone(),
+            MergeStrategy::Theirs,
+        );
+        assert_eq!(merged, vars(theirs.clone()));
+
+        let (merged, conflicts) = merge(base, ours, theirs, MergeStrategy::Union);
+        assert_eq!(
+            merged,
+            vars(json!({
+                "n": 2,
+                "list": [1, 2, 3],
+                "obj": { "x": 2, "y": 1, "z": 1 },
+            }))
+        );
+        assert_eq!(conflicts, ["list", "n", "obj"]);
+    }
+
+    #[test]
+    fn one_sided_deletions_are_applied() {
+        let (merged, conflicts) = merge(
+            json!({ "a": 1, "b": 2 }),
+            json!({ "b": 2 }),
+            json!({ "a": 1 }),
+            MergeStrategy::Union,
+        );
+        assert!(merged.is_empty());
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn deletion_against_an_edit_follows_the_strategy() {
+        let base = json!({ "a": 1 });
+        let ours = json!({});
+        let theirs = json!({ "a": 2 });
+
+        let (merged, conflicts) = merge(
+            base.clone(),
+            ours.clone(),
+            theirs.clone(),
+            MergeStrategy::Ours,
+        );
+        assert!(merged.is_empty());
+        assert_eq!(conflicts, ["a"]);
+
+        let (merged, _) = merge(
+            base.clone(),
+            ours.clone(),
+            theirs.clone(),
+            MergeStrategy::Theirs,
+        );
+        assert_eq!(merged, vars(json!({ "a": 2 })));
+
+        let (merged, _) = merge(base, ours, theirs, MergeStrategy::Union);
+        assert_eq!(merged, vars(json!({ "a": 2 })));
+    }
+}
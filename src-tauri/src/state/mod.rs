@@ -10,7 +10,9 @@ use tokio::sync::broadcast;
 
 use crate::services::{
     adb::AdbService,
-    frida::{AppInfo, FridaService, ProcessInfo},
+    frida::{AppInfo, FridaService, OperationRegistry, ProcessInfo},
+    local_server::LocalServerService,
+    settings::{apply_log_level, SettingsService},
 };
 
 const LIST_CACHE_TTL: Duration = Duration::from_secs(3);
@@ -114,17 +116,32 @@ pub struct AppState {
     pub frida_service: Mutex<FridaService>,
     pub adb_service: Mutex<AdbService>,
     pub list_cache: Mutex<ListCache>,
+    pub settings_service: Mutex<SettingsService>,
+    pub local_server: Mutex<LocalServerService>,
     pub events: EventHub,
+    /// Cancels in-flight Frida operations without waiting for `frida_service`,
+    /// which the operation being cancelled is usually holding.
+    pub operations: OperationRegistry,
 }
 
 impl AppState {
     pub fn new() -> Result<Self, crate::error::AppError> {
         let events = EventHub::new();
+        let operations = OperationRegistry::new();
+        let settings_service = SettingsService::new();
+        let mut frida_service = FridaService::new(events.clone(), operations.clone())?;
+        let settings = settings_service.settings();
+        apply_log_level(settings.log_level.as_deref());
+        frida_service.set_timeout_policy(settings.timeouts.clone());
+        frida_service.set_default_runtime(settings.default_runtime.clone());
         Ok(Self {
-            frida_service: Mutex::new(FridaService::new(events.clone())?),
+            frida_service: Mutex::new(frida_service),
             adb_service: Mutex::new(AdbService::new()),
             list_cache: Mutex::new(ListCache::default()),
+            settings_service: Mutex::new(settings_service),
+            local_server: Mutex::new(LocalServerService::new()),
             events,
+            operations,
         })
     }
 }
@@ -1,6 +1,8 @@
+use std::collections::BTreeMap;
 use std::convert::Infallible;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_stream::stream;
 use axum::extract::{Path, State};
@@ -16,15 +18,27 @@ use tower_http::cors::{AllowOrigin, Any, CorsLayer};
 use crate::api;
 use crate::error::AppError;
 use crate::services::ai::{self, AiChatRequest};
-use crate::services::frida::{AttachOptions, SpawnOptions};
+use crate::services::audit::AuditFilter;
+use crate::services::bookmarks::Bookmark;
+use crate::services::frida::{
+    AttachOptions, MessageFilter, MessageLogOptions, ProcessListOptions, RemoteDeviceOptions,
+    SpawnGatingOptions, SpawnOptions, TimeoutPolicy,
+};
+use crate::services::hooks::HookOptions;
+use crate::services::hotkeys::HotkeyRpcAction;
+use crate::services::memory::{HexdumpOptions, ValueWrite};
+use crate::services::perf::PerfSelftestOptions;
+use crate::services::pointer_scan::{PointerScanOptions, SavedPointerChain};
+use crate::services::scanner::ScanPreset;
+use crate::services::settings::Settings;
+use crate::services::stalker::StalkerEventOptions;
+use crate::services::target_profiles::TargetProfile;
+use crate::services::typed_memory::{Endianness, TypedReadOptions, TypedValueType};
+use crate::services::value_format::ValueFormat;
+use crate::services::value_snapshots::SnapshotEntry;
+use crate::services::variables::MergeStrategy;
 use crate::state::{AppState, BridgeEvent};
 
-/// RPC methods that execute arbitrary JavaScript inside the Frida agent.
-/// These must never be callable through the HTTP bridge unless the operator
-/// has explicitly opted in via `CARF_ALLOW_EVAL=1`, since the bridge is
-/// reachable from any process on the local machine.
-const EVAL_METHODS: &[&str] = &["evaluate", "eval", "runScript", "loadScript"];
-
 fn bridge_auth_token() -> Option<String> {
     std::env::var("CARF_BRIDGE_TOKEN").ok().and_then(|value| {
         let trimmed = value.trim().to_string();
@@ -57,6 +71,26 @@ struct DeviceIdArgs {
     device_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PairDeviceArgs {
+    device_id: String,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForwardPortArgs {
+    device_id: String,
+    remote_port: u16,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForwardIdArgs {
+    forward_id: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct ListQueryArgs {
@@ -64,6 +98,17 @@ struct ListQueryArgs {
     query: Option<String>,
     limit: Option<usize>,
     force_refresh: Option<bool>,
+    include_icons: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListProcessesArgs {
+    device_id: String,
+    query: Option<String>,
+    limit: Option<usize>,
+    force_refresh: Option<bool>,
+    options: Option<ProcessListOptions>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -73,18 +118,524 @@ struct KillProcessArgs {
     pid: u32,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnableSpawnGatingArgs {
+    device_id: String,
+    options: Option<SpawnGatingOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WatchProcessesArgs {
+    device_id: String,
+    interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessOutputReadArgs {
+    session_id: String,
+    since_cursor: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProcessMetricsStartArgs {
+    session_id: String,
+    interval_ms: Option<u32>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct SessionIdArgs {
     session_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddSessionMarkerArgs {
+    session_id: String,
+    label: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct AddressArgs {
     address: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteDeviceArgs {
+    address: String,
+    options: Option<RemoteDeviceOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RemoteEndpointUpdateArgs {
+    address: String,
+    nickname: Option<String>,
+    options: Option<RemoteDeviceOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HookAddArgs {
+    session_id: String,
+    module: Option<String>,
+    symbol_or_offset: String,
+    options: Option<HookOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HookRemoveArgs {
+    session_id: String,
+    hook_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InspectBytesArgs {
+    session_id: String,
+    address: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GuessTypeArgs {
+    session_id: String,
+    address: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WriteValuesBatchArgs {
+    session_id: String,
+    writes: Vec<ValueWrite>,
+    #[serde(default)]
+    atomic: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanSnapshotSaveArgs {
+    session_id: String,
+    name: String,
+    entries: Vec<SnapshotEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanSnapshotDeleteArgs {
+    session_id: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanSnapshotCompareArgs {
+    session_id: String,
+    before: String,
+    after: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryReadTypedArgs {
+    session_id: String,
+    address: String,
+    value_type: TypedValueType,
+    options: Option<TypedReadOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryWriteTypedArgs {
+    session_id: String,
+    address: String,
+    value_type: TypedValueType,
+    value: Value,
+    endianness: Option<Endianness>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FreezeValuesArgs {
+    session_id: String,
+    values: Vec<ValueWrite>,
+    interval_ms: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UnfreezeValuesArgs {
+    session_id: String,
+    ids: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryWatchArgs {
+    session_id: String,
+    address: String,
+    size: usize,
+    on: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryUnwatchArgs {
+    session_id: String,
+    watch_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanPresetSaveArgs {
+    preset: ScanPreset,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanPresetDeleteArgs {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScanWithPresetArgs {
+    session_id: String,
+    preset: String,
+    values: Vec<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PointerScanArgs {
+    session_id: String,
+    options: PointerScanOptions,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BookmarkSaveArgs {
+    profile: String,
+    bookmark: Bookmark,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddressResolveArgs {
+    session_id: String,
+    locations: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddressToLocationArgs {
+    session_id: String,
+    addresses: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PointerChainSaveArgs {
+    profile: String,
+    chain: SavedPointerChain,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PointerChainDeleteArgs {
+    profile: String,
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PointerChainsResolveArgs {
+    session_id: String,
+    names: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReadMappedFileArgs {
+    session_id: String,
+    address: String,
+    size: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryHexdumpArgs {
+    session_id: String,
+    address: String,
+    size: Option<usize>,
+    options: Option<HexdumpOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryDumpRangeArgs {
+    session_id: String,
+    address: String,
+    size: u64,
+    path: String,
+    dump_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MemoryDumpModuleArgs {
+    session_id: String,
+    module: String,
+    path: String,
+    dump_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FormatValuesArgs {
+    values: Vec<Value>,
+    format: ValueFormat,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CompileScriptArgs {
+    entrypoint_path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LoadBundledScriptArgs {
+    session_id: String,
+    script_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplEvalArgs {
+    session_id: String,
+    code: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FilePushArgs {
+    session_id: String,
+    local_path: String,
+    remote_path: String,
+    transfer_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FilePullArgs {
+    session_id: String,
+    remote_path: String,
+    local_path: String,
+    transfer_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileSaveArgs {
+    profile: TargetProfile,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileIdArgs {
+    profile_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ScriptPostArgs {
+    session_id: String,
+    script: Option<String>,
+    message: Value,
+    data: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessageLogStartArgs {
+    options: Option<MessageLogOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MessagesQueryArgs {
+    filter: Option<MessageFilter>,
+    since: Option<u64>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StalkerThreadArgs {
+    session_id: String,
+    thread_id: u64,
+    events: Option<StalkerEventOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceFunctionArgs {
+    session_id: String,
+    module: Option<String>,
+    symbol_or_offset: String,
+    events: Option<StalkerEventOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UntraceFunctionArgs {
+    session_id: String,
+    trace_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionPathArgs {
+    session_id: String,
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PathArgs {
+    path: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupNameArgs {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SettingsSetArgs {
+    settings: Settings,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PluginInvokeArgs {
+    plugin: String,
+    command: String,
+    args: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuditQueryArgs {
+    filter: Option<AuditFilter>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogLevelArgs {
+    level: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogRecentArgs {
+    count: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HotkeySetArgs {
+    action: String,
+    chord: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HotkeyRpcArgs {
+    action: String,
+    rpc: Option<HotkeyRpcAction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HotkeyActionArgs {
+    action: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RevealArgs {
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TimeoutPolicyArgs {
+    policy: TimeoutPolicy,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PerfSelftestArgs {
+    options: Option<PerfSelftestOptions>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VariableProfileArgs {
+    profile: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VariableKeyArgs {
+    profile: String,
+    key: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VariableSetArgs {
+    profile: String,
+    key: String,
+    value: Value,
+    expected_revision: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VariableDeleteArgs {
+    profile: String,
+    key: String,
+    expected_revision: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VariableMergeArgs {
+    profile: String,
+    base: BTreeMap<String, Value>,
+    ours: BTreeMap<String, Value>,
+    strategy: MergeStrategy,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CancelOperationArgs {
+    operation_id: String,
+    session_id: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct AttachArgs {
@@ -92,6 +643,24 @@ struct AttachArgs {
     options: AttachOptions,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AttachByNameArgs {
+    device_id: String,
+    name: String,
+    wait: Option<bool>,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AttachManyArgs {
+    device_id: String,
+    pids: Vec<u32>,
+    script_path: Option<String>,
+    script_parameters: Option<Value>,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct SpawnArgs {
@@ -109,6 +678,8 @@ struct RpcCallArgs {
 
 pub async fn run() -> anyhow::Result<()> {
     let state = Arc::new(AppState::new()?);
+    spawn_settings_file_watcher(state.clone());
+    spawn_device_auto_connect(state.clone());
 
     // Only allow requests from the local Vite dev server and loopback origins.
     // Opening this to `Any` would let any webpage the user happens to visit drive
@@ -136,6 +707,7 @@ pub async fn run() -> anyhow::Result<()> {
         .route("/api/health", get(health))
         .route("/api/events", get(events))
         .route("/api/invoke/{command}", post(invoke))
+        .route("/api/companion/status", get(companion_status))
         .layer(
             CorsLayer::new()
                 .allow_origin(AllowOrigin::list(cors_origins))
@@ -244,6 +816,7 @@ async fn index() -> Html<&'static str> {
         <li><a href="/api/health"><code>GET /api/health</code></a> for a quick health check</li>
         <li><code>GET /api/events</code> for the SSE event stream</li>
         <li><code>POST /api/invoke/&lt;command&gt;</code> for backend commands</li>
+        <li><code>GET /api/companion/status</code> for the phone companion (token required)</li>
       </ul>
     </main>
   </body>
@@ -277,7 +850,47 @@ async fn invoke(
         });
 
     match result {
-        Ok(data) => (StatusCode::OK, Json(json!({ "data": data }))).into_response(),
+        Ok(data) => (StatusCode::OK, Json(json!({ "data": data }))).into_response(),
+        Err(error) => (
+            status_code_for_error(&error),
+            Json(json!({ "error": error })),
+        )
+            .into_response(),
+    }
+}
+
+/// Target status for a phone companion on the same network. Unlike the rest
+/// of the bridge this never runs unauthenticated, even on loopback, because
+/// it is meant to be reached from another device.
+async fn companion_status(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if bridge_auth_token().is_none() {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({ "error": "companion access requires CARF_BRIDGE_TOKEN" })),
+        )
+            .into_response();
+    }
+    if let Err(status) = check_bridge_auth(&headers) {
+        return (status, Json(json!({ "error": "unauthorized" }))).into_response();
+    }
+
+    let result = tokio::task::spawn_blocking(move || api::list_sessions(&state))
+        .await
+        .unwrap_or_else(|join_error| {
+            Err(AppError::Internal(format!(
+                "companion status task panicked: {join_error}"
+            )))
+        });
+
+    match result {
+        Ok(sessions) => (
+            StatusCode::OK,
+            Json(json!({ "data": { "sessions": sessions } })),
+        )
+            .into_response(),
         Err(error) => (
             status_code_for_error(&error),
             Json(json!({ "error": error })),
@@ -300,6 +913,21 @@ async fn events(
     event_stream(state).into_response()
 }
 
+/// Same as the desktop app's settings watcher: picks up `settings.json`
+/// edits made outside CARF.
+fn spawn_settings_file_watcher(state: Arc<AppState>) {
+    std::thread::spawn(move || loop {
+        std::thread::sleep(Duration::from_secs(2));
+        if let Err(error) = api::check_settings_file(&state) {
+            log::debug!("settings watcher check failed: {error}");
+        }
+    });
+}
+
+fn spawn_device_auto_connect(state: Arc<AppState>) {
+    std::thread::spawn(move || api::auto_connect_devices(&state));
+}
+
 fn event_stream(
     state: Arc<AppState>,
 ) -> Sse<impl futures_core::Stream<Item = Result<Event, Infallible>>> {
@@ -331,9 +959,9 @@ fn dispatch(state: &AppState, command: &str, args: Value) -> Result<Value, AppEr
         "list_devices" => Ok(serde_json::to_value(api::list_devices(state)?)
             .map_err(|error| AppError::Internal(error.to_string()))?),
         "add_remote_device" => {
-            let args: AddressArgs = parse_args(args)?;
+            let args: RemoteDeviceArgs = parse_args(args)?;
             Ok(
-                serde_json::to_value(api::add_remote_device(state, args.address)?)
+                serde_json::to_value(api::add_remote_device(state, args.address, args.options)?)
                     .map_err(|error| AppError::Internal(error.to_string()))?,
             )
         }
@@ -342,6 +970,33 @@ fn dispatch(state: &AppState, command: &str, args: Value) -> Result<Value, AppEr
             api::remove_remote_device(state, args.address)?;
             Ok(Value::Null)
         }
+        "remote_endpoints_list" => Ok(serde_json::to_value(api::remote_endpoints_list()?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "remote_endpoint_update" => {
+            let args: RemoteEndpointUpdateArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::remote_endpoint_update(
+                args.address,
+                args.nickname,
+                args.options,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "remote_endpoint_remove" => {
+            let args: AddressArgs = parse_args(args)?;
+            Ok(Value::Bool(api::remote_endpoint_remove(args.address)?))
+        }
+        "add_portal" => {
+            let args: RemoteDeviceArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::add_portal(state, args.address, args.options)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "list_portal_nodes" => {
+            let args: DeviceIdArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::list_portal_nodes(state, args.device_id)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
         "get_device_info" => {
             let args: DeviceIdArgs = parse_args(args)?;
             Ok(
@@ -349,8 +1004,47 @@ fn dispatch(state: &AppState, command: &str, args: Value) -> Result<Value, AppEr
                     .map_err(|error| AppError::Internal(error.to_string()))?,
             )
         }
+        "query_device" => {
+            let args: DeviceIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::query_device(state, args.device_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "pair_device" => {
+            let args: PairDeviceArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::pair_device(state, args.device_id, args.timeout_ms)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "unpair_device" => {
+            let args: DeviceIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::unpair_device(state, args.device_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "device_forward_port" => {
+            let args: ForwardPortArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::device_forward_port(
+                state,
+                args.device_id,
+                args.remote_port,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "device_stop_port_forward" => {
+            let args: ForwardIdArgs = parse_args(args)?;
+            api::device_stop_port_forward(state, args.forward_id)?;
+            Ok(Value::Null)
+        }
+        "device_list_port_forwards" => {
+            Ok(serde_json::to_value(api::device_list_port_forwards(state)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
         "list_processes" => {
-            let args: ListQueryArgs = parse_args(args)?;
+            let args: ListProcessesArgs = parse_args(args)?;
             Ok(
                 serde_json::to_value(api::list_processes(
                     state,
@@ -358,6 +1052,7 @@ fn dispatch(state: &AppState, command: &str, args: Value) -> Result<Value, AppEr
                     args.query,
                     args.limit,
                     args.force_refresh,
+                    args.options,
                 )?)
                     .map_err(|error| AppError::Internal(error.to_string()))?,
             )
@@ -371,6 +1066,7 @@ fn dispatch(state: &AppState, command: &str, args: Value) -> Result<Value, AppEr
                     args.query,
                     args.limit,
                     args.force_refresh,
+                    args.include_icons,
                 )?)
                     .map_err(|error| AppError::Internal(error.to_string()))?,
             )
@@ -380,6 +1076,88 @@ fn dispatch(state: &AppState, command: &str, args: Value) -> Result<Value, AppEr
             api::kill_process(state, args.device_id, args.pid)?;
             Ok(Value::Null)
         }
+        "enable_spawn_gating" => {
+            let args: EnableSpawnGatingArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::enable_spawn_gating(
+                state,
+                args.device_id,
+                args.options,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "disable_spawn_gating" => {
+            let args: DeviceIdArgs = parse_args(args)?;
+            api::disable_spawn_gating(state, args.device_id)?;
+            Ok(Value::Null)
+        }
+        "list_pending_spawns" => {
+            let args: DeviceIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::list_pending_spawns(state, args.device_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "resume_spawn" => {
+            let args: KillProcessArgs = parse_args(args)?;
+            api::resume_spawn(state, args.device_id, args.pid)?;
+            Ok(Value::Null)
+        }
+        "deny_spawn" => {
+            let args: KillProcessArgs = parse_args(args)?;
+            api::deny_spawn(state, args.device_id, args.pid)?;
+            Ok(Value::Null)
+        }
+        "watch_processes" => {
+            let args: WatchProcessesArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::watch_processes(
+                state,
+                args.device_id,
+                args.interval_ms,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "unwatch_processes" => {
+            let args: DeviceIdArgs = parse_args(args)?;
+            Ok(Value::Bool(api::unwatch_processes(state, args.device_id)?))
+        }
+        "get_frontmost_application" => {
+            let args: DeviceIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::get_frontmost_application(state, args.device_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "process_output_read" => {
+            let args: ProcessOutputReadArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::process_output_read(
+                state,
+                args.session_id,
+                args.since_cursor,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "process_metrics_start" => {
+            let args: ProcessMetricsStartArgs = parse_args(args)?;
+            Ok(Value::from(api::process_metrics_start(
+                state,
+                args.session_id,
+                args.interval_ms,
+            )?))
+        }
+        "process_metrics_stop" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(Value::Bool(api::process_metrics_stop(
+                state,
+                args.session_id,
+            )?))
+        }
+        "process_metrics_get" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::process_metrics_get(state, args.session_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
         "attach" => {
             let args: AttachArgs = parse_args(args)?;
             Ok(
@@ -394,11 +1172,40 @@ fn dispatch(state: &AppState, command: &str, args: Value) -> Result<Value, AppEr
                     .map_err(|error| AppError::Internal(error.to_string()))?,
             )
         }
+        "attach_by_name" => {
+            let args: AttachByNameArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::attach_by_name(
+                state,
+                args.device_id,
+                args.name,
+                args.wait.unwrap_or(false),
+                args.timeout_ms,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "attach_many" => {
+            let args: AttachManyArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::attach_many(
+                state,
+                args.device_id,
+                args.pids,
+                args.script_path,
+                args.script_parameters,
+            ))
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
         "detach" => {
             let args: SessionIdArgs = parse_args(args)?;
             api::detach(state, args.session_id)?;
             Ok(Value::Null)
         }
+        "cleanup_session" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::cleanup_session(state, args.session_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
         "resume" => {
             let args: SessionIdArgs = parse_args(args)?;
             api::resume(state, args.session_id)?;
@@ -406,13 +1213,38 @@ fn dispatch(state: &AppState, command: &str, args: Value) -> Result<Value, AppEr
         }
         "list_sessions" => Ok(serde_json::to_value(api::list_sessions(state)?)
             .map_err(|error| AppError::Internal(error.to_string()))?),
+        "restore_last_session" => Ok(serde_json::to_value(api::restore_last_session(state)?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "get_session_diagnostic" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::get_session_diagnostic(state, args.session_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "add_session_marker" => {
+            let args: AddSessionMarkerArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::add_session_marker(state, args.session_id, args.label)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "list_session_markers" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::list_session_markers(state, args.session_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
         "rpc_call" => {
             let args: RpcCallArgs = parse_args(args)?;
-            if EVAL_METHODS.contains(&args.method.as_str())
-                && std::env::var("CARF_ALLOW_EVAL")
-                    .map(|v| v != "1" && !v.eq_ignore_ascii_case("true"))
-                    .unwrap_or(true)
-            {
+            if api::is_host_internal_method(&args.method) {
+                return Err(AppError::InvalidArgument(format!(
+                    "rpc method '{}' is internal to CARF and cannot be called over the HTTP bridge",
+                    args.method
+                )));
+            }
+            if api::is_eval_method(&args.method) && !api::eval_allowed() {
                 return Err(AppError::Internal(format!(
                     "rpc method '{}' is disabled on the HTTP bridge. Set CARF_ALLOW_EVAL=1 to enable.",
                     args.method
@@ -420,6 +1252,591 @@ fn dispatch(state: &AppState, command: &str, args: Value) -> Result<Value, AppEr
             }
             api::rpc_call(state, args.session_id, args.method, args.params)
         }
+        "inspect_bytes" => {
+            let args: InspectBytesArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::inspect_bytes(state, args.session_id, args.address)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "guess_type" => {
+            let args: GuessTypeArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::guess_type(state, args.session_id, args.address)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "write_values_batch" => {
+            let args: WriteValuesBatchArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::write_values_batch(
+                state,
+                args.session_id,
+                args.writes,
+                args.atomic,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "scan_snapshot_save" => {
+            let args: ScanSnapshotSaveArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::scan_snapshot_save(
+                state,
+                args.session_id,
+                args.name,
+                args.entries,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "scan_snapshots_list" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::scan_snapshots_list(state, args.session_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "scan_snapshot_delete" => {
+            let args: ScanSnapshotDeleteArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::scan_snapshot_delete(
+                state,
+                args.session_id,
+                args.name,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "scan_snapshot_compare" => {
+            let args: ScanSnapshotCompareArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::scan_snapshot_compare(
+                state,
+                args.session_id,
+                args.before,
+                args.after,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "memory_read_typed" => {
+            let args: MemoryReadTypedArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::memory_read_typed(
+                state,
+                args.session_id,
+                args.address,
+                args.value_type,
+                args.options,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "memory_write_typed" => {
+            let args: MemoryWriteTypedArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::memory_write_typed(
+                state,
+                args.session_id,
+                args.address,
+                args.value_type,
+                args.value,
+                args.endianness,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "freeze_values" => {
+            let args: FreezeValuesArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::freeze_values(
+                state,
+                args.session_id,
+                args.values,
+                args.interval_ms,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "unfreeze_values" => {
+            let args: UnfreezeValuesArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::unfreeze_values(state, args.session_id, args.ids)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "list_frozen_values" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::list_frozen_values(state, args.session_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "memory_watch" => {
+            let args: MemoryWatchArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::memory_watch(
+                state,
+                args.session_id,
+                args.address,
+                args.size,
+                args.on,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "memory_unwatch" => {
+            let args: MemoryUnwatchArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::memory_unwatch(state, args.session_id, args.watch_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "memory_watches_list" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::memory_watches_list(state, args.session_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "scan_presets" => Ok(serde_json::to_value(api::scan_presets(state)?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "scan_preset_save" => {
+            let args: ScanPresetSaveArgs = parse_args(args)?;
+            api::scan_preset_save(state, args.preset)?;
+            Ok(Value::Null)
+        }
+        "scan_preset_delete" => {
+            let args: ScanPresetDeleteArgs = parse_args(args)?;
+            Ok(Value::Bool(api::scan_preset_delete(state, args.name)?))
+        }
+        "scan_with_preset" => {
+            let args: ScanWithPresetArgs = parse_args(args)?;
+            api::scan_with_preset(state, args.session_id, args.preset, args.values)
+        }
+        "pointer_scan" => {
+            let args: PointerScanArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::pointer_scan(state, args.session_id, args.options)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "pointer_map_snapshot" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::pointer_map_snapshot(state, args.session_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "bookmarks_list" => {
+            let args: VariableProfileArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::bookmarks_list(args.profile)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "bookmark_save" => {
+            let args: BookmarkSaveArgs = parse_args(args)?;
+            api::bookmark_save(args.profile, args.bookmark)?;
+            Ok(Value::Null)
+        }
+        "bookmark_delete" => {
+            let args: PointerChainDeleteArgs = parse_args(args)?;
+            Ok(Value::Bool(api::bookmark_delete(args.profile, args.name)?))
+        }
+        "bookmarks_resolve" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::bookmarks_resolve(state, args.session_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "address_resolve" => {
+            let args: AddressResolveArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::address_resolve(
+                state,
+                args.session_id,
+                args.locations,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "address_to_location" => {
+            let args: AddressToLocationArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::address_to_location(
+                state,
+                args.session_id,
+                args.addresses,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "pointer_chains_list" => {
+            let args: VariableProfileArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::pointer_chains_list(args.profile)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "pointer_chain_save" => {
+            let args: PointerChainSaveArgs = parse_args(args)?;
+            api::pointer_chain_save(args.profile, args.chain)?;
+            Ok(Value::Null)
+        }
+        "pointer_chain_delete" => {
+            let args: PointerChainDeleteArgs = parse_args(args)?;
+            Ok(Value::Bool(api::pointer_chain_delete(
+                args.profile,
+                args.name,
+            )?))
+        }
+        "pointer_chains_resolve" => {
+            let args: PointerChainsResolveArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::pointer_chains_resolve(
+                state,
+                args.session_id,
+                args.names,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "read_mapped_file" => {
+            let args: ReadMappedFileArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::read_mapped_file(
+                state,
+                args.session_id,
+                args.address,
+                args.size,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "memory_hexdump" => {
+            let args: MemoryHexdumpArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::memory_hexdump(
+                state,
+                args.session_id,
+                args.address,
+                args.size,
+                args.options,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "memory_dump_range" => {
+            let args: MemoryDumpRangeArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::memory_dump_range(
+                state,
+                args.session_id,
+                args.address,
+                args.size,
+                api::bridge_local_path(&args.path)?,
+                args.dump_id,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "memory_dump_module" => {
+            let args: MemoryDumpModuleArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::memory_dump_module(
+                state,
+                args.session_id,
+                args.module,
+                api::bridge_local_path(&args.path)?,
+                args.dump_id,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "format_values" => {
+            let args: FormatValuesArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::format_values(args.values, args.format))
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "compile_script" => {
+            let args: CompileScriptArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::compile_script(
+                state,
+                api::bridge_local_path(&args.entrypoint_path)?,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "list_bundled_scripts" => Ok(serde_json::to_value(api::list_bundled_scripts())
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "load_bundled_script" => {
+            let args: LoadBundledScriptArgs = parse_args(args)?;
+            api::load_bundled_script(state, args.session_id, args.script_id)?;
+            Ok(Value::Null)
+        }
+        "repl_eval" => {
+            if !api::eval_allowed() {
+                return Err(AppError::Internal(
+                    "repl_eval is disabled on the HTTP bridge. Set CARF_ALLOW_EVAL=1 to enable."
+                        .to_string(),
+                ));
+            }
+            let args: ReplEvalArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::repl_eval(state, args.session_id, args.code)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "file_push" => {
+            let args: FilePushArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::file_push(
+                state,
+                args.session_id,
+                api::bridge_local_path(&args.local_path)?,
+                args.remote_path,
+                args.transfer_id,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "file_pull" => {
+            let args: FilePullArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::file_pull(
+                state,
+                args.session_id,
+                args.remote_path,
+                api::bridge_local_path(&args.local_path)?,
+                args.transfer_id,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "profiles_list" => Ok(serde_json::to_value(api::profiles_list()?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "profile_save" => {
+            let args: ProfileSaveArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::profile_save(args.profile)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "profile_delete" => {
+            let args: ProfileIdArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::profile_delete(args.profile_id)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "profile_launch" => {
+            let args: ProfileIdArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::profile_launch(state, args.profile_id)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "script_post" => {
+            let args: ScriptPostArgs = parse_args(args)?;
+            api::script_post(state, args.session_id, args.script, args.message, args.data)?;
+            Ok(Value::Null)
+        }
+        "message_log_start" => {
+            let args: MessageLogStartArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::message_log_start(state, args.options)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "message_log_stop" => Ok(serde_json::to_value(api::message_log_stop(state)?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "message_log_status" => Ok(serde_json::to_value(api::message_log_status(state)?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "messages_query" => {
+            let args: MessagesQueryArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::messages_query(
+                state,
+                args.filter,
+                args.since,
+                args.limit,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "hook_add" => {
+            let args: HookAddArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::hook_add(
+                state,
+                args.session_id,
+                args.module,
+                args.symbol_or_offset,
+                args.options,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "hook_remove" => {
+            let args: HookRemoveArgs = parse_args(args)?;
+            api::hook_remove(state, args.session_id, args.hook_id)?;
+            Ok(Value::Null)
+        }
+        "hook_list" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::hook_list(state, args.session_id)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "start_stalker_trace" => {
+            let args: StalkerThreadArgs = parse_args(args)?;
+            api::start_stalker_trace(state, args.session_id, args.thread_id, args.events)
+        }
+        "stop_stalker_trace" => {
+            let args: StalkerThreadArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::stop_stalker_trace(
+                state,
+                args.session_id,
+                args.thread_id,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "trace_function" => {
+            let args: TraceFunctionArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::trace_function(
+                state,
+                args.session_id,
+                args.module,
+                args.symbol_or_offset,
+                args.events,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "untrace_function" => {
+            let args: UntraceFunctionArgs = parse_args(args)?;
+            api::untrace_function(state, args.session_id, args.trace_id)?;
+            Ok(Value::Null)
+        }
+        "export_stalker_trace" => {
+            let args: SessionPathArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::export_stalker_trace(
+                state,
+                args.session_id,
+                api::bridge_local_path(&args.path)?,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "clear_stalker_trace" => {
+            let args: SessionIdArgs = parse_args(args)?;
+            api::clear_stalker_trace(state, args.session_id)?;
+            Ok(Value::Null)
+        }
+        "settings_export" => {
+            let args: PathArgs = parse_args(args)?;
+            api::settings_export(state, api::bridge_local_path(&args.path)?)?;
+            Ok(Value::Null)
+        }
+        "settings_get" => Ok(serde_json::to_value(api::settings_get(state)?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "settings_set" => {
+            let args: SettingsSetArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::settings_set(state, args.settings)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "settings_reset" => Ok(serde_json::to_value(api::settings_reset(state)?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "log_set_level" => {
+            let args: LogLevelArgs = parse_args(args)?;
+            api::log_set_level(state, args.level)?;
+            Ok(Value::Null)
+        }
+        "log_get_recent" => {
+            let args: LogRecentArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::log_get_recent(args.count)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "hotkeys_list" => Ok(serde_json::to_value(api::hotkeys_list(state)?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "hotkeys_set" => {
+            let args: HotkeySetArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::hotkeys_set(state, args.action, args.chord)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "hotkeys_set_rpc" => {
+            let args: HotkeyRpcArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::hotkeys_set_rpc(state, args.action, args.rpc)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "hotkeys_trigger" => {
+            let args: HotkeyActionArgs = parse_args(args)?;
+            api::hotkeys_trigger(state, args.action)
+        }
+        "settings_import" => {
+            let args: PathArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::settings_import(
+                state,
+                api::bridge_local_path(&args.path)?,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "settings_reload" => Ok(serde_json::to_value(api::settings_reload(state)?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "plugins_list" => Ok(serde_json::to_value(api::plugins_list())
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "plugin_invoke" => {
+            let args: PluginInvokeArgs = parse_args(args)?;
+            api::plugin_invoke(
+                state,
+                args.plugin,
+                args.command,
+                args.args.unwrap_or(Value::Null),
+            )
+        }
+        "audit_query" => {
+            let args: AuditQueryArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::audit_query(args.filter)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "list_backups" => Ok(serde_json::to_value(api::list_backups()?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "restore_backup" => {
+            let args: BackupNameArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::restore_backup(state, args.name)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "reveal_in_file_manager" => {
+            let args: RevealArgs = parse_args(args)?;
+            let path = api::reveal_in_file_manager(state, args.kind)?;
+            Ok(Value::String(path))
+        }
+        "get_timeout_policy" => Ok(serde_json::to_value(api::get_timeout_policy(state)?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "set_timeout_policy" => {
+            let args: TimeoutPolicyArgs = parse_args(args)?;
+            api::set_timeout_policy(state, args.policy)?;
+            Ok(Value::Null)
+        }
+        "perf_selftest" => {
+            let args: PerfSelftestArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::perf_selftest(args.options)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "variable_profiles" => Ok(serde_json::to_value(api::variable_profiles()?)
+            .map_err(|error| AppError::Internal(error.to_string()))?),
+        "variable_list" => {
+            let args: VariableProfileArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::variable_list(args.profile)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "variable_get" => {
+            let args: VariableKeyArgs = parse_args(args)?;
+            Ok(
+                serde_json::to_value(api::variable_get(args.profile, args.key)?)
+                    .map_err(|error| AppError::Internal(error.to_string()))?,
+            )
+        }
+        "variable_snapshot" => {
+            let args: VariableProfileArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::variable_snapshot(args.profile)?)
+                .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "variable_set" => {
+            let args: VariableSetArgs = parse_args(args)?;
+            Ok(Value::String(api::variable_set(
+                state,
+                args.profile,
+                args.key,
+                args.value,
+                args.expected_revision,
+            )?))
+        }
+        "variable_delete" => {
+            let args: VariableDeleteArgs = parse_args(args)?;
+            Ok(Value::String(api::variable_delete(
+                state,
+                args.profile,
+                args.key,
+                args.expected_revision,
+            )?))
+        }
+        "variable_merge" => {
+            let args: VariableMergeArgs = parse_args(args)?;
+            Ok(serde_json::to_value(api::variable_merge(
+                state,
+                args.profile,
+                args.base,
+                args.ours,
+                args.strategy,
+            )?)
+            .map_err(|error| AppError::Internal(error.to_string()))?)
+        }
+        "cancel_operation" => {
+            let args: CancelOperationArgs = parse_args(args)?;
+            Ok(Value::Bool(api::cancel_operation(
+                state,
+                args.operation_id,
+                args.session_id,
+            )?))
+        }
         "ai_chat" => {
             // ai_chat shells out to the local `claude`/`codex` CLI, which can
             // execute arbitrary commands on behalf of the bridge user. Only
@@ -457,7 +1874,8 @@ fn status_code_for_error(error: &AppError) -> StatusCode {
         | AppError::ProcessNotFound(_)
         | AppError::SessionNotFound(_)
         | AppError::AdbDeviceNotFound(_) => StatusCode::NOT_FOUND,
-        AppError::InvalidAddress(_) => StatusCode::BAD_REQUEST,
+        AppError::InvalidAddress(_) | AppError::InvalidArgument(_) => StatusCode::BAD_REQUEST,
+        AppError::StorageConflict(_) => StatusCode::CONFLICT,
         _ => StatusCode::INTERNAL_SERVER_ERROR,
     }
 }
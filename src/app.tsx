@@ -1,6 +1,7 @@
 import { Match, Suspense, Switch, lazy, onCleanup, onMount } from "solid-js";
 import "~/features/session/session-scope";
 import { appView } from "~/features/session/session.store";
+import { loadLaunchOptions } from "~/features/settings/launch.store";
 import { ToastHost } from "~/features/toast/ToastHost";
 import { scheduleIdle } from "~/lib/scheduling";
 
@@ -17,6 +18,7 @@ const SessionView = lazy(loadSessionView);
 
 export function App() {
 	onMount(() => {
+		void loadLaunchOptions();
 		const cancelProcessPreload = scheduleIdle(() => {
 			void loadProcessPanel();
 		}, 120);
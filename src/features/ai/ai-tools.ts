@@ -37,7 +37,7 @@ export const CARF_TOOLS: AiToolDef[] = [
 	// ── Memory (14) ──
 	{ name: "readMemory", category: "memory", description: "Read bytes from memory address", params: { address: "string (hex)", size: "number" } },
 	{ name: "writeMemory", category: "memory", description: "Write bytes to memory address", params: { address: "string (hex)", data: "string (hex bytes)" } },
-	{ name: "scanMemory", category: "memory", description: "Scan memory for pattern", params: { pattern: "string (IDA-style, e.g. '48 8B ?? 00')", protection: "string? (protection filter, e.g. 'r-x', defaults to 'r--')", base: "string? (hex; pair with size for single-range scan)", size: "number?" } },
+	{ name: "scanMemory", category: "memory", description: "Scan memory for pattern", params: { pattern: "string (IDA-style, e.g. '48 8B ?? 00')", protection: "string? (protection filter, e.g. 'r-x', defaults to 'r--')", base: "string? (hex; pair with size for single-range scan)", size: "number?", onReadError: "string? ('skip' | 'retry' | 'abort' for ranges that fail to read; defaults to 'skip')", retries: "number? (attempts per range for 'retry', default 2)" } },
 	{ name: "allocateMemory", category: "memory", description: "Allocate memory in target process", params: { size: "number" } },
 	{ name: "protectMemory", category: "memory", description: "Change memory protection", params: { address: "string", size: "number", protection: "string (e.g. 'rwx')" } },
 	{ name: "queryMemoryProtection", category: "memory", description: "Query current protection of memory address", params: { address: "string" } },
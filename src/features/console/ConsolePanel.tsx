@@ -209,7 +209,7 @@ export function ConsolePanel() {
 interface TimelineEntry {
   id: string;
   timestamp: number;
-  kind: "message" | "hook" | "system";
+  kind: "message" | "hook" | "system" | "marker";
   label: string;
   detail: string;
   color: string;
@@ -259,6 +259,17 @@ function TimelineView() {
       });
     }
 
+    for (const marker of consoleState.markers) {
+      result.push({
+        id: marker.id,
+        timestamp: marker.timestamp,
+        kind: "marker",
+        label: "MARKER",
+        detail: marker.label,
+        color: "bg-violet-500",
+      });
+    }
+
     result.sort((a, b) => a.timestamp - b.timestamp);
     return result;
   });
@@ -298,6 +309,7 @@ function TimelineView() {
             <option value="all">All</option>
             <option value="message">Messages</option>
             <option value="hook">Hook Events</option>
+            <option value="marker">Markers</option>
             <option value="system">System</option>
           </select>
           <span>{filteredEntries().length} events</span>
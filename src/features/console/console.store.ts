@@ -14,8 +14,11 @@ import type {
 	ConsoleMessage,
 	ConsolePanelTab,
 	ConsoleSource,
+	EvalResult,
 	HookEvent,
 	SessionDetachedEvent,
+	SessionDiagnostic,
+	SessionMarker,
 } from "~/lib/types";
 
 const MAX_MESSAGES = 10_000;
@@ -24,6 +27,7 @@ interface ConsoleState {
 	messages: ConsoleMessage[];
 	hookEvents: HookEvent[];
 	systemMessages: ConsoleMessage[];
+	markers: SessionMarker[];
 	replHistory: string[];
 }
 
@@ -31,6 +35,7 @@ const DEFAULT_STATE: ConsoleState = {
 	messages: [],
 	hookEvents: [],
 	systemMessages: [],
+	markers: [],
 	replHistory: [],
 };
 
@@ -83,6 +88,27 @@ function addHookEvent(event: HookEvent): void {
 	});
 }
 
+function addMarker(marker: SessionMarker): void {
+	if (state.markers.some((existing) => existing.id === marker.id)) {
+		return;
+	}
+	setState("markers", (prev) => [...prev, marker]);
+}
+
+/** Drops a marker into the session's event log; defaults to "Marker N". */
+async function placeMarker(sessionId: string, label?: string): Promise<void> {
+	try {
+		const marker = await invoke<SessionMarker>("add_session_marker", {
+			sessionId,
+			label: label?.trim() || `Marker ${state.markers.length + 1}`,
+		});
+		addMarker(marker);
+	} catch (err) {
+		const message = err instanceof Error ? err.message : String(err);
+		addMessage("error", "system", `Failed to place marker: ${message}`);
+	}
+}
+
 function addReplEntry(code: string): void {
 	setState("replHistory", (prev) => [...prev, code]);
 }
@@ -157,11 +183,8 @@ async function evaluateCode(sessionId: string, code: string): Promise<void> {
 	addReplEntry(code);
 	addMessage("info", "user", `> ${code}`);
 	try {
-		await invoke<unknown>("rpc_call", {
-			sessionId,
-			method: "evaluate",
-			params: { code },
-		});
+		const result = await invoke<EvalResult>("repl_eval", { sessionId, code });
+		addMessage(result.ok ? "info" : "error", "agent", result.value, result);
 	} catch (err) {
 		const message = err instanceof Error ? err.message : String(err);
 		addMessage("error", "agent", message);
@@ -200,10 +223,25 @@ function setupConsoleListeners(sessionId: string): () => void {
 		},
 	);
 
+	const unlistenDiagnostic = listen<SessionDiagnostic>(
+		"carf://session/diagnostic",
+		(payload) => {
+			if (payload.sessionId === sessionId) {
+				addMessage(
+					"warn",
+					"system",
+					`${payload.code}: ${payload.message}`,
+					payload.evidence,
+				);
+			}
+		},
+	);
+
 	return () => {
 		unlistenMessage();
 		unlistenHook();
 		unlistenDetached();
+		unlistenDiagnostic();
 	};
 }
 
@@ -212,6 +250,8 @@ export {
 	filteredMessages,
 	addMessage,
 	addHookEvent,
+	addMarker,
+	placeMarker,
 	addReplEntry,
 	clearMessages,
 	clearHookEvents,
@@ -5,6 +5,7 @@ import {
   selectedDevice,
   refreshDevices,
   selectDevice,
+  pairDevice,
   addRemoteDevice,
   setupDeviceListeners,
 } from "./device.store";
@@ -15,6 +16,8 @@ import type { DeviceInfo } from "~/lib/types";
 export default function DevicePanel() {
   const [showRemoteForm, setShowRemoteForm] = createSignal(false);
   const [remoteAddress, setRemoteAddress] = createSignal("");
+  const [remoteToken, setRemoteToken] = createSignal("");
+  const [remoteCertificate, setRemoteCertificate] = createSignal("");
   const [remoteError, setRemoteError] = createSignal<string | null>(null);
   const [connecting, setConnecting] = createSignal(false);
 
@@ -30,8 +33,13 @@ export default function DevicePanel() {
     setRemoteError(null);
     setConnecting(true);
     try {
-      await addRemoteDevice(addr);
+      await addRemoteDevice(addr, {
+        token: remoteToken().trim() || undefined,
+        certificate: remoteCertificate().trim() || undefined,
+      });
       setRemoteAddress("");
+      setRemoteToken("");
+      setRemoteCertificate("");
       setShowRemoteForm(false);
     } catch (err) {
       setRemoteError(err instanceof Error ? err.message : String(err));
@@ -116,6 +124,24 @@ export default function DevicePanel() {
                   {connecting() ? "Connecting..." : "Connect"}
                 </button>
               </div>
+              <div class="flex gap-2">
+                <input
+                  type="password"
+                  class="flex-1 rounded border bg-background px-2 py-1.5 text-sm outline-none placeholder:text-muted-foreground focus:border-primary"
+                  placeholder="Token (--token, optional)"
+                  value={remoteToken()}
+                  onInput={(e) => setRemoteToken(e.currentTarget.value)}
+                  onKeyDown={(e) => e.key === "Enter" && handleAddRemote()}
+                />
+                <input
+                  type="text"
+                  class="flex-1 rounded border bg-background px-2 py-1.5 text-sm outline-none placeholder:text-muted-foreground focus:border-primary"
+                  placeholder="Certificate path (--certificate, optional)"
+                  value={remoteCertificate()}
+                  onInput={(e) => setRemoteCertificate(e.currentTarget.value)}
+                  onKeyDown={(e) => e.key === "Enter" && handleAddRemote()}
+                />
+              </div>
               <Show when={remoteError()}>
                 <p class="text-xs text-destructive">{remoteError()}</p>
               </Show>
@@ -196,6 +222,8 @@ function DeviceCard(props: { device: DeviceInfo }) {
         return "USB";
       case "remote":
         return "Remote";
+      case "portal":
+        return "Portal";
     }
   };
 
@@ -206,10 +234,26 @@ function DeviceCard(props: { device: DeviceInfo }) {
       case "usb":
         return "bg-success/10 text-success";
       case "remote":
+      case "portal":
         return "bg-warning/10 text-warning";
     }
   };
 
+  const pairingLabel = () => {
+    switch (props.device.pairing?.state) {
+      case "unpaired":
+        return "Click to pair";
+      case "awaiting_trust":
+        return "Trust this computer on the device";
+      case "denied":
+        return "Pairing denied";
+      case "locked":
+        return "Unlock to pair";
+      default:
+        return null;
+    }
+  };
+
   const statusDotClass = () => {
     switch (props.device.status) {
       case "connected":
@@ -227,7 +271,10 @@ function DeviceCard(props: { device: DeviceInfo }) {
         "flex flex-col gap-3 rounded-lg border bg-surface p-4 text-left transition-all hover:border-primary/50 hover:bg-surface-hover",
         isSelected() && "border-primary bg-primary/5 ring-1 ring-primary",
       )}
-      onClick={() => selectDevice(props.device.id)}
+      onClick={() => {
+        selectDevice(props.device.id);
+        if (props.device.status === "pairing") pairDevice(props.device.id);
+      }}
     >
       {/* Top row: icon + status dot */}
       <div class="flex items-start justify-between">
@@ -272,6 +319,17 @@ function DeviceCard(props: { device: DeviceInfo }) {
               {props.device.arch}
             </span>
           </Show>
+
+          <Show when={pairingLabel()}>
+            {(label) => (
+              <span
+                class="rounded bg-warning/10 px-1.5 py-0.5 text-[10px] font-medium text-warning"
+                title={props.device.pairing?.message ?? undefined}
+              >
+                {label()}
+              </span>
+            )}
+          </Show>
         </div>
       </div>
     </button>
@@ -296,6 +354,7 @@ function DeviceIcon(props: { device: DeviceInfo }) {
     case "usb":
       return <Usb size={20} class="text-success" />;
     case "remote":
+    case "portal":
       return <Wifi size={20} class="text-warning" />;
     default:
       return <Monitor size={20} class="text-muted-foreground" />;
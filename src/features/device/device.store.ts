@@ -2,7 +2,7 @@ import { createMemo, createRoot } from "solid-js";
 import { createStore } from "solid-js/store";
 import { scheduleTransition } from "~/lib/scheduling";
 import { invoke, listen } from "~/lib/tauri";
-import type { DeviceInfo } from "~/lib/types";
+import type { DeviceInfo, RemoteDeviceOptions } from "~/lib/types";
 
 interface DeviceState {
 	devices: DeviceInfo[];
@@ -62,10 +62,38 @@ function extractRemovedDeviceId(payload: DeviceRemovedPayload): string | null {
 	return null;
 }
 
-async function addRemoteDevice(address: string): Promise<void> {
+/**
+ * Starts pairing with an iOS device; resolves once the user answered the
+ * trust prompt or the backend gave up waiting.
+ */
+async function pairDevice(deviceId: string): Promise<DeviceInfo | null> {
+	setState({ error: null });
+	try {
+		const device = await invoke<DeviceInfo>("pair_device", { deviceId });
+		upsertDevice(device);
+		return device;
+	} catch (err) {
+		setState({ error: err instanceof Error ? err.message : String(err) });
+		return null;
+	}
+}
+
+function upsertDevice(device: DeviceInfo): void {
+	setState("devices", (prev) => {
+		const exists = prev.some((d) => d.id === device.id);
+		return exists
+			? prev.map((d) => (d.id === device.id ? device : d))
+			: [...prev, device];
+	});
+}
+
+async function addRemoteDevice(
+	address: string,
+	options?: RemoteDeviceOptions,
+): Promise<void> {
 	setState({ loading: true, error: null });
 	try {
-		await invoke("add_remote_device", { address });
+		await invoke("add_remote_device", { address, options });
 		await refreshDevices();
 	} catch (err) {
 		setState({
@@ -89,14 +117,11 @@ async function removeRemoteDevice(address: string): Promise<void> {
 }
 
 function setupDeviceListeners(): () => void {
-	const unlistenAdded = listen<DeviceInfo>("carf://device/added", (device) => {
-		setState("devices", (prev) => {
-			const exists = prev.some((d) => d.id === device.id);
-			return exists
-				? prev.map((d) => (d.id === device.id ? device : d))
-				: [...prev, device];
-		});
-	});
+	const unlistenAdded = listen<DeviceInfo>("carf://device/added", upsertDevice);
+	const unlistenPairing = listen<DeviceInfo>(
+		"carf://device/pairing",
+		upsertDevice,
+	);
 
 	const unlistenRemoved = listen<DeviceRemovedPayload>(
 		"carf://device/removed",
@@ -115,6 +140,7 @@ function setupDeviceListeners(): () => void {
 
 	return () => {
 		unlistenAdded();
+		unlistenPairing();
 		unlistenRemoved();
 	};
 }
@@ -124,6 +150,7 @@ export {
 	selectedDevice,
 	refreshDevices,
 	selectDevice,
+	pairDevice,
 	addRemoteDevice,
 	removeRemoteDevice,
 	setupDeviceListeners,
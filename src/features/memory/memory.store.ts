@@ -1,7 +1,7 @@
 import { createStore } from "solid-js/store";
 import { createSignal } from "solid-js";
 import { extractEventSessionId } from "~/lib/event-normalizers";
-import type { MemoryRange, MemoryMonitorEvent, ScanResult } from "~/lib/types";
+import type { MemoryRange, MemoryMonitorEvent, ScanResponse, ScanResult } from "~/lib/types";
 import { restoreStore, snapshotStore } from "~/lib/store-snapshot";
 import { invoke, listen } from "~/lib/tauri";
 import { toastError } from "~/features/toast/toast.store";
@@ -304,12 +304,12 @@ async function searchMemory(
   );
 
   try {
-    const result = await invoke<ScanResult[]>("rpc_call", {
+    const result = await invoke<ScanResponse>("rpc_call", {
       sessionId,
       method: "scanMemory",
       params: { pattern, protection: "r--" },
     });
-    setSearchResults(result);
+    setSearchResults(result.results);
   } catch (e) {
     setState({ searching: false });
     toastError("Failed to search memory", e);
@@ -11,6 +11,7 @@ import {
   setShowMode,
   killProcess,
   attachToProcess,
+  attachToFrontmost,
   spawnAndAttach,
 } from "./process.store";
 import { selectedDevice } from "~/features/device/device.store";
@@ -113,6 +114,12 @@ export default function ProcessPanel() {
     setModalOpen(false);
   }
 
+  function handleAttachFrontmost() {
+    const d = device();
+    if (!d) return;
+    void attachToFrontmost(d.id);
+  }
+
   function handleKill() {
     const d = device();
     const t = processState.selectedTarget;
@@ -121,6 +128,10 @@ export default function ProcessPanel() {
   }
 
   const hasSelection = () => processState.selectedTarget !== null;
+  const isMobileDevice = () => {
+    const platform = device()?.os?.platform;
+    return platform === "ios" || platform === "android";
+  };
   const selectionHasPid = () => processState.selectedTarget?.pid != null;
   const selectionHasIdentifier = () =>
     processState.selectedTarget?.identifier != null;
@@ -172,6 +183,18 @@ export default function ProcessPanel() {
 
         <div class="flex-1" />
 
+        {/* Attach to whatever app is on screen */}
+        <Show when={isMobileDevice()}>
+          <button
+            class="rounded-md px-2 py-1 text-xs text-muted-foreground transition-colors hover:bg-surface-hover hover:text-foreground disabled:opacity-50"
+            onClick={handleAttachFrontmost}
+            disabled={processState.loading}
+            title="Attach to the application currently on screen"
+          >
+            Attach Frontmost
+          </button>
+        </Show>
+
         {/* Refresh */}
         <button
           class="rounded-md px-2 py-1 text-xs text-muted-foreground transition-colors hover:bg-surface-hover hover:text-foreground"
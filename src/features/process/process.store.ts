@@ -22,6 +22,7 @@ interface SelectedTarget {
 interface CollectionPage<T> {
 	items: T[];
 	total: number;
+	offset: number;
 	limit: number;
 	truncated: boolean;
 	query?: string | null;
@@ -94,6 +95,7 @@ async function refreshProcesses(
 			query: normalizeSearchQuery(query),
 			limit: DEFAULT_LIST_LIMIT,
 			forceRefresh: options?.forceRefresh ?? false,
+			options: { includeIcons: true },
 		});
 		if (!isCurrentRequest("processes", requestId)) {
 			return;
@@ -133,6 +135,7 @@ async function refreshApplications(
 			query: normalizeSearchQuery(query),
 			limit: DEFAULT_LIST_LIMIT,
 			forceRefresh: options?.forceRefresh ?? false,
+			includeIcons: true,
 		});
 		if (!isCurrentRequest("applications", requestId)) {
 			return;
@@ -215,6 +218,29 @@ async function attachToProcess(
 	}
 }
 
+async function attachToFrontmost(deviceId: string): Promise<void> {
+	setState({ loading: true, error: null });
+	try {
+		const app = await invoke<AppInfo | null>("get_frontmost_application", {
+			deviceId,
+		});
+		if (!app?.pid) {
+			setState({ loading: false, error: "No application is on screen" });
+			return;
+		}
+		await attachToProcess(deviceId, {
+			type: "app",
+			pid: app.pid,
+			identifier: app.identifier,
+		});
+	} catch (err) {
+		setState({
+			loading: false,
+			error: err instanceof Error ? err.message : String(err),
+		});
+	}
+}
+
 async function spawnAndAttach(
 	deviceId: string,
 	identifier: string,
@@ -263,5 +289,6 @@ export {
 	setShowMode,
 	killProcess,
 	attachToProcess,
+	attachToFrontmost,
 	spawnAndAttach,
 };
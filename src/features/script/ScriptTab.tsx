@@ -1,6 +1,7 @@
 import { For, Show, createMemo } from "solid-js";
 import { activeSession } from "~/features/session/session.store";
 import { cn } from "~/lib/cn";
+import { parseEventLog } from "~/lib/event-replay";
 import { pickTextFile } from "~/lib/file-picker";
 import { toastError } from "~/features/toast/toast.store";
 import {
@@ -8,6 +9,7 @@ import {
 	loadScript,
 	loadTemplate,
 	reloadScript,
+	replayRecordedEvents,
 	scriptState,
 	setCode,
 	setScriptPath,
@@ -28,6 +30,19 @@ async function handleOpenFile() {
 	}
 }
 
+async function handleReplayFile() {
+	try {
+		const selected = await pickTextFile(
+			".json,.ndjson,.jsonl,application/json",
+		);
+		if (selected) {
+			replayRecordedEvents(parseEventLog(selected.content));
+		}
+	} catch (e) {
+		toastError("Failed to replay event log", e);
+	}
+}
+
 function ScriptTab() {
 	async function handleLoadOrHotReload() {
 		const session = activeSession();
@@ -70,6 +85,22 @@ function ScriptTab() {
 					>
 						Open...
 					</button>
+					<button
+						type="button"
+						class="rounded px-2 py-0.5 text-xs text-muted-foreground hover:bg-surface-hover hover:text-foreground"
+						title="Run this script against the recorded console and hook events"
+						onClick={() => replayRecordedEvents()}
+					>
+						Replay
+					</button>
+					<button
+						type="button"
+						class="rounded px-2 py-0.5 text-xs text-muted-foreground hover:bg-surface-hover hover:text-foreground"
+						title="Run this script against an exported event log (JSON array or NDJSON)"
+						onClick={handleReplayFile}
+					>
+						Replay Log...
+					</button>
 					<button
 						type="button"
 						class={cn(
@@ -2,6 +2,9 @@ import { createSignal } from "solid-js";
 import { createStore } from "solid-js/store";
 import { restoreStore, snapshotStore } from "~/lib/store-snapshot";
 import { invoke } from "~/lib/tauri";
+import { buildEventLog, replayEvents } from "~/lib/event-replay";
+import type { RecordedEvent, ReplayReport } from "~/lib/types";
+import { addMessage, consoleState } from "~/features/console/console.store";
 import { toastError } from "~/features/toast/toast.store";
 
 interface ScriptTemplate {
@@ -123,13 +126,17 @@ function loadTemplate(index: number): void {
 
 // ─── RPC Functions ───
 
-async function loadScript(sessionId: string, code: string): Promise<void> {
+async function loadScript(
+	sessionId: string,
+	code: string,
+	parameters?: Record<string, unknown>,
+): Promise<void> {
 	setLoading(true);
 	try {
 		await invoke("rpc_call", {
 			sessionId,
 			method: "loadScript",
-			params: { code },
+			params: { code, parameters },
 		});
 		setLoaded(true);
 	} catch (e) {
@@ -158,6 +165,44 @@ async function reloadScript(sessionId: string): Promise<void> {
 	await loadScript(sessionId, state.code);
 }
 
+// ─── Replay ───
+
+/**
+ * Runs the editor contents as an automation script against `events`, or the
+ * events the console has recorded when none are given, so assertions can be
+ * checked without a live target.
+ */
+function replayRecordedEvents(events?: RecordedEvent[]): ReplayReport {
+	const report = replayEvents(
+		events ??
+			buildEventLog(
+				consoleState.hookEvents,
+				consoleState.messages,
+				consoleState.markers,
+			),
+		state.code,
+	);
+
+	for (const line of report.logs) {
+		addMessage("log", "system", `[replay] ${line}`);
+	}
+	for (const assertion of report.assertions) {
+		if (!assertion.passed) {
+			const where =
+				assertion.eventIndex === null
+					? ""
+					: ` (event #${assertion.eventIndex} ${assertion.event})`;
+			addMessage("error", "system", `[replay] ${assertion.message}${where}`);
+		}
+	}
+	addMessage(
+		report.failed > 0 ? "warn" : "info",
+		"system",
+		`[replay] ${report.eventsHandled}/${report.eventsReplayed} events handled, ${report.passed} passed, ${report.failed} failed`,
+	);
+	return report;
+}
+
 function resetScriptState(): void {
 	setState(restoreStore(DEFAULT_STATE));
 	setEditorDirty(false);
@@ -203,6 +248,7 @@ export {
 	loadScript,
 	unloadScript,
 	reloadScript,
+	replayRecordedEvents,
 	resetScriptState,
 	snapshotScriptState,
 	restoreScriptState,
@@ -18,6 +18,7 @@ import {
 	Wind,
 } from "lucide-solid";
 import { type Component, For } from "solid-js";
+import { kioskMode } from "~/features/settings/launch.store";
 import { cn } from "~/lib/cn";
 import type { TabId } from "~/lib/types";
 import { preloadTabComponent } from "./SessionMainContent";
@@ -26,26 +27,28 @@ interface NavItem {
 	id: TabId;
 	label: string;
 	icon: Component<{ size?: number; class?: string }>;
+	/** Hidden in kiosk mode. */
+	advanced?: boolean;
 }
 
 const NAV_ITEMS: NavItem[] = [
 	{ id: "console", label: "Console", icon: Terminal },
-	{ id: "modules", label: "Modules", icon: Package },
-	{ id: "threads", label: "Threads", icon: Cpu },
+	{ id: "modules", label: "Modules", icon: Package, advanced: true },
+	{ id: "threads", label: "Threads", icon: Cpu, advanced: true },
 	{ id: "memory", label: "Memory", icon: MemoryStick },
-	{ id: "java", label: "Java", icon: Coffee },
-	{ id: "objc", label: "ObjC", icon: Apple },
-	{ id: "native", label: "Native", icon: Binary },
-	{ id: "script", label: "Script", icon: FileCode2 },
+	{ id: "java", label: "Java", icon: Coffee, advanced: true },
+	{ id: "objc", label: "ObjC", icon: Apple, advanced: true },
+	{ id: "native", label: "Native", icon: Binary, advanced: true },
+	{ id: "script", label: "Script", icon: FileCode2, advanced: true },
 	{ id: "hooks", label: "Hooks", icon: Anchor },
 	{ id: "pinboard", label: "Pinboard", icon: Pin },
-	{ id: "callgraph", label: "Call Graph", icon: GitFork },
-	{ id: "network", label: "Network", icon: Globe },
-	{ id: "files", label: "Files", icon: FolderOpen },
-	{ id: "swift", label: "Swift", icon: Wind },
-	{ id: "il2cpp", label: "IL2CPP", icon: Gamepad2 },
-	{ id: "antidetect", label: "AntiDetect", icon: ShieldOff },
-	{ id: "ai", label: "AI Agent", icon: Sparkles },
+	{ id: "callgraph", label: "Call Graph", icon: GitFork, advanced: true },
+	{ id: "network", label: "Network", icon: Globe, advanced: true },
+	{ id: "files", label: "Files", icon: FolderOpen, advanced: true },
+	{ id: "swift", label: "Swift", icon: Wind, advanced: true },
+	{ id: "il2cpp", label: "IL2CPP", icon: Gamepad2, advanced: true },
+	{ id: "antidetect", label: "AntiDetect", icon: ShieldOff, advanced: true },
+	{ id: "ai", label: "AI Agent", icon: Sparkles, advanced: true },
 ];
 
 interface SessionNavBarProps {
@@ -56,7 +59,7 @@ interface SessionNavBarProps {
 export function SessionNavBar(props: SessionNavBarProps) {
 	return (
 		<nav class="flex w-12 flex-col items-center border-r bg-surface py-2">
-			<For each={NAV_ITEMS}>
+			<For each={NAV_ITEMS.filter((item) => !kioskMode() || !item.advanced)}>
 				{(item) => {
 					const isActive = () => props.activeTab === item.id;
 					const Icon = item.icon;
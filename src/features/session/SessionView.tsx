@@ -3,8 +3,10 @@ import { CommandPalette } from "~/components/CommandPalette";
 import { ConsolePanel } from "~/features/console/ConsolePanel";
 import {
 	addHookEvent as addConsoleHookEvent,
+	addMarker,
 	addMessage,
 	clearMessages,
+	placeMarker,
 } from "~/features/console/console.store";
 import { recordHookEvent } from "~/features/hooks/hooks.store";
 import { fetchHooks, hooksState } from "~/features/hooks/hooks.store";
@@ -32,6 +34,7 @@ import type {
 	HookEvent,
 	ProcessCrashedEvent,
 	SessionDetachedEvent,
+	SessionMarker,
 } from "~/lib/types";
 import { InspectorPanel } from "./InspectorPanel";
 import { SessionMainContent } from "./SessionMainContent";
@@ -112,11 +115,21 @@ export function SessionView() {
 			},
 		);
 
+		const unlistenMarker = listen<SessionMarker>(
+			"carf://session/marker",
+			(payload) => {
+				if (payload.sessionId === sessionId) {
+					addMarker(payload);
+				}
+			},
+		);
+
 		onCleanup(() => {
 			unlistenMessage();
 			unlistenHook();
 			unlistenDetached();
 			unlistenCrashed();
+			unlistenMarker();
 		});
 	});
 
@@ -134,6 +147,14 @@ export function SessionView() {
 			setActiveTab("pinboard");
 			return;
 		}
+		if (meta && e.shiftKey && e.key.toLowerCase() === "m") {
+			e.preventDefault();
+			const session = activeSession();
+			if (session) {
+				placeMarker(session.id).catch(() => {});
+			}
+			return;
+		}
 		if (meta && e.key === "i") {
 			e.preventDefault();
 			toggleInspector();
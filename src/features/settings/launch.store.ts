@@ -0,0 +1,64 @@
+import { createSignal } from "solid-js";
+import { invoke, isTauri, listen } from "~/lib/tauri";
+import type { DeepLinkRequest, LaunchOptions, Workspace } from "~/lib/types";
+import { toastError, toastWarning } from "~/features/toast/toast.store";
+
+const [launchOptions, setLaunchOptions] = createSignal<LaunchOptions | null>(null);
+
+/** Kiosk mode hides advanced tabs for prepared trainer workspaces. */
+const kioskMode = () => launchOptions()?.kiosk ?? false;
+
+async function loadLaunchOptions(): Promise<void> {
+  // Launch options come from the desktop process's own command line.
+  if (!isTauri()) return;
+  listen<DeepLinkRequest>("carf://launch/deep-link", (request) => {
+    void confirmDeepLink(request);
+  });
+  try {
+    const options = await invoke<LaunchOptions>("get_launch_options");
+    setLaunchOptions(options);
+    if (options.deepLink) {
+      void confirmDeepLink({
+        url: options.deepLink,
+        workspace: options.workspace,
+        error: options.error,
+      });
+    } else if (options.error) {
+      toastWarning("Workspace could not be loaded", options.error);
+    }
+  } catch {
+    // Older backends without launch options behave as a normal launch.
+  }
+}
+
+/** Any web page can open a `carf://` link, so ask before attaching. */
+async function confirmDeepLink(request: DeepLinkRequest): Promise<void> {
+  if (request.error || !request.workspace) {
+    toastWarning("Link could not be opened", request.error ?? request.url);
+    return;
+  }
+  const { ask } = await import("@tauri-apps/plugin-dialog");
+  const confirmed = await ask(describeLink(request.workspace), {
+    title: "Open CARF link?",
+    kind: "warning",
+    okLabel: "Attach",
+    cancelLabel: "Ignore",
+  });
+  if (!confirmed) return;
+  try {
+    await invoke("run_deep_link", { url: request.url });
+  } catch (error) {
+    toastError("Link could not be opened", error);
+  }
+}
+
+function describeLink(workspace: Workspace): string {
+  const device = workspace.deviceId ?? "local";
+  const lines = workspace.targets.map((target) => {
+    const script = target.bundledScript ? ` and load ${target.bundledScript}` : "";
+    return `Attach to ${String(target.target)} on ${device}${script}.`;
+  });
+  return `${lines.join("\n")}\n\nOnly continue if you trust where this link came from.`;
+}
+
+export { launchOptions, kioskMode, loadLaunchOptions };
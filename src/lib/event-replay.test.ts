@@ -0,0 +1,98 @@
+import { describe, expect, it } from "vitest";
+import {
+	buildEventLog,
+	parseEventLog,
+	replayEvents,
+} from "~/lib/event-replay";
+
+const EVENTS = parseEventLog(
+	[
+		'{"event":"hook/event","timestamp":1,"payload":{"args":["0x2a"]}}',
+		'{"event":"carf://console/message","timestamp":2,"payload":{"content":"hi"}}',
+		'{"event":"hook/event","timestamp":3,"payload":{"args":["0x07"]}}',
+	].join("\n"),
+);
+
+describe("parseEventLog", () => {
+	it("normalizes short event names from NDJSON", () => {
+		expect(EVENTS.map((event) => event.event)).toEqual([
+			"carf://hook/event",
+			"carf://console/message",
+			"carf://hook/event",
+		]);
+	});
+});
+
+describe("buildEventLog", () => {
+	it("interleaves markers with captured events by timestamp", () => {
+		const log = buildEventLog(
+			[],
+			[
+				{
+					id: "m1",
+					timestamp: 10,
+					level: "log",
+					source: "agent",
+					content: "before",
+				},
+				{
+					id: "m2",
+					timestamp: 30,
+					level: "log",
+					source: "agent",
+					content: "after",
+				},
+			],
+			[
+				{
+					id: "marker-1",
+					sessionId: "s1",
+					label: "died here",
+					timestamp: 20,
+					tracePosition: null,
+				},
+			],
+		);
+
+		expect(log.map((event) => event.event)).toEqual([
+			"carf://console/message",
+			"carf://session/marker",
+			"carf://console/message",
+		]);
+	});
+});
+
+describe("replayEvents", () => {
+	it("runs handlers in order and records assertion results", () => {
+		const report = replayEvents(
+			EVENTS,
+			`
+			const seen = [];
+			on("hook/event", (payload) => {
+				seen.push(parseInt(payload.args[0], 16));
+				assert(seen.at(-1) > 10, "value is large");
+			});
+			`,
+		);
+
+		expect(report.eventsHandled).toBe(2);
+		expect(report.passed).toBe(1);
+		expect(report.failed).toBe(1);
+		expect(report.assertions[1]).toMatchObject({
+			passed: false,
+			eventIndex: 2,
+			event: "carf://hook/event",
+		});
+	});
+
+	it("reports a throwing handler without stopping the replay", () => {
+		const report = replayEvents(
+			EVENTS,
+			`on("*", (payload) => { if (payload.content) throw new Error("boom"); });`,
+		);
+
+		expect(report.eventsHandled).toBe(3);
+		expect(report.failed).toBe(1);
+		expect(report.assertions[0].message).toContain("boom");
+	});
+});
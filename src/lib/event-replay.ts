@@ -0,0 +1,176 @@
+import type {
+	ConsoleMessage,
+	HookEvent,
+	RecordedEvent,
+	ReplayAssertion,
+	ReplayReport,
+	SessionMarker,
+} from "~/lib/types";
+
+// Replays a recorded event log through an automation script without a live
+// target. The script registers handlers with `on(event, fn)` and checks
+// payloads with `assert` / `assertEqual`; every handler runs in log order and
+// a throwing handler is recorded as a failed assertion instead of stopping
+// the replay.
+
+type ReplayHandler = (payload: unknown, event: RecordedEvent) => void;
+
+const EVENT_PREFIX = "carf://";
+
+function normalizeEventName(name: string): string {
+	return name === "*" || name.startsWith(EVENT_PREFIX)
+		? name
+		: `${EVENT_PREFIX}${name}`;
+}
+
+function formatValue(value: unknown): string {
+	try {
+		return JSON.stringify(value) ?? String(value);
+	} catch {
+		return String(value);
+	}
+}
+
+/**
+ * Builds a replayable log from what the console has captured. Markers are
+ * included as `carf://session/marker` so scripts can align on them.
+ */
+export function buildEventLog(
+	hookEvents: HookEvent[],
+	messages: ConsoleMessage[],
+	markers: SessionMarker[] = [],
+): RecordedEvent[] {
+	const events: RecordedEvent[] = [
+		...hookEvents.map((event) => ({
+			event: "carf://hook/event",
+			timestamp: event.timestamp,
+			payload: event,
+		})),
+		...messages
+			.filter((message) => message.source !== "user")
+			.map((message) => ({
+				event: "carf://console/message",
+				timestamp: message.timestamp,
+				payload: message,
+			})),
+		...markers.map((marker) => ({
+			event: "carf://session/marker",
+			timestamp: marker.timestamp,
+			payload: marker,
+		})),
+	];
+	return events.sort((a, b) => a.timestamp - b.timestamp);
+}
+
+/**
+ * Accepts a JSON array, or NDJSON with one `{ event, timestamp, payload }`
+ * object per line.
+ */
+export function parseEventLog(text: string): RecordedEvent[] {
+	const trimmed = text.trim();
+	if (!trimmed) return [];
+	const entries: unknown[] = trimmed.startsWith("[")
+		? JSON.parse(trimmed)
+		: trimmed
+				.split("\n")
+				.filter((line) => line.trim())
+				.map((line) => JSON.parse(line));
+
+	return entries.map((entry, index) => {
+		const record = entry as Record<string, unknown>;
+		if (!record || typeof record.event !== "string") {
+			throw new Error(`Event ${index} has no event name`);
+		}
+		return {
+			event: normalizeEventName(record.event),
+			timestamp: typeof record.timestamp === "number" ? record.timestamp : 0,
+			payload: record.payload ?? record.data ?? null,
+		};
+	});
+}
+
+export function replayEvents(
+	events: RecordedEvent[],
+	source: string,
+): ReplayReport {
+	const handlers: { event: string; handler: ReplayHandler }[] = [];
+	const assertions: ReplayAssertion[] = [];
+	const logs: string[] = [];
+	let current: { index: number; event: string } | null = null;
+
+	const record = (passed: boolean, message: string) => {
+		assertions.push({
+			passed,
+			message,
+			eventIndex: current?.index ?? null,
+			event: current?.event ?? null,
+		});
+	};
+
+	const api = {
+		on(event: string, handler: ReplayHandler) {
+			handlers.push({ event: normalizeEventName(event), handler });
+		},
+		assert(condition: unknown, message = "assertion") {
+			record(Boolean(condition), message);
+		},
+		assertEqual(actual: unknown, expected: unknown, message = "values equal") {
+			const a = formatValue(actual);
+			const b = formatValue(expected);
+			record(
+				a === b,
+				a === b ? message : `${message}: expected ${b}, got ${a}`,
+			);
+		},
+		log(...args: unknown[]) {
+			logs.push(
+				args
+					.map((arg) => (typeof arg === "string" ? arg : formatValue(arg)))
+					.join(" "),
+			);
+		},
+	};
+
+	try {
+		const setup = new Function(
+			"on",
+			"assert",
+			"assertEqual",
+			"log",
+			`"use strict";\n${source}`,
+		);
+		setup(api.on, api.assert, api.assertEqual, api.log);
+	} catch (e) {
+		const message = e instanceof Error ? e.message : String(e);
+		record(false, `Script failed to load: ${message}`);
+	}
+
+	let eventsHandled = 0;
+	for (const [index, event] of events.entries()) {
+		const matching = handlers.filter(
+			(entry) => entry.event === "*" || entry.event === event.event,
+		);
+		if (matching.length === 0) continue;
+		eventsHandled += 1;
+		current = { index, event: event.event };
+		for (const { handler } of matching) {
+			try {
+				handler(event.payload, event);
+			} catch (e) {
+				const message = e instanceof Error ? e.message : String(e);
+				record(false, `Handler threw: ${message}`);
+			}
+		}
+		current = null;
+	}
+
+	const failed = assertions.filter((assertion) => !assertion.passed).length;
+	return {
+		eventsReplayed: events.length,
+		eventsHandled,
+		assertions,
+		passed: assertions.length - failed,
+		failed,
+		logs,
+	};
+}
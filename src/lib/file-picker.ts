@@ -62,7 +62,9 @@ function buildFilters(
 	accept: string,
 ): Array<{ name: string; extensions: string[] }> {
 	if (accept.includes("json")) {
-		return [{ name: "JSON", extensions: ["json"] }];
+		return accept.includes(".ndjson")
+			? [{ name: "JSON", extensions: ["json", "ndjson", "jsonl"] }]
+			: [{ name: "JSON", extensions: ["json"] }];
 	}
 
 	if (accept.includes(".js") || accept.includes(".ts")) {
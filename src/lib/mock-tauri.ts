@@ -14,6 +14,7 @@ import type {
 	NetworkRequest,
 	ObjCMethodInfo,
 	ProcessInfo,
+	ProcessListOptions,
 	ScanResult,
 	SessionDetachedEvent,
 	SessionInfo,
@@ -362,6 +363,7 @@ const listeners = new Map<string, Set<EventHandler>>();
 const runtimeState = {
 	devices: createDevices(),
 	nextHookId: 1,
+	nextMarkerId: 1,
 	nextNetworkId: 1,
 	nextSessionId: 1,
 	processes: createProcesses(),
@@ -382,6 +384,7 @@ export function resetMockRuntime(): void {
 	listeners.clear();
 	runtimeState.devices = createDevices();
 	runtimeState.nextHookId = 1;
+	runtimeState.nextMarkerId = 1;
 	runtimeState.nextNetworkId = 1;
 	runtimeState.nextSessionId = 1;
 	runtimeState.processes = createProcesses();
@@ -426,6 +429,7 @@ function createDevices(): DeviceInfo[] {
 			os: { platform: "android", version: "15" },
 			arch: "arm64",
 			status: "connected",
+			pairing: null,
 		},
 	];
 }
@@ -498,19 +502,23 @@ function buildCollectionPage<T>(
 	items: T[],
 	limit: number,
 	query?: string,
+	offset = 0,
 ): {
 	items: T[];
 	total: number;
+	offset: number;
 	limit: number;
 	truncated: boolean;
 	query: string | null;
 } {
 	const total = items.length;
+	const page = items.slice(offset, offset + limit);
 	return {
-		items: items.slice(0, limit),
+		items: page,
 		total,
+		offset,
 		limit,
-		truncated: total > limit,
+		truncated: total > offset + page.length,
 		query: query ?? null,
 	};
 }
@@ -868,6 +876,7 @@ export async function mockInvoke<T>(
 				os: { platform: "android", version: "15" },
 				arch: "arm64",
 				status: "connected",
+				pairing: null,
 			};
 
 			if (!runtimeState.devices.some((item) => item.id === device.id)) {
@@ -898,7 +907,19 @@ export async function mockInvoke<T>(
 			const deviceId = String(args?.deviceId ?? DEFAULT_DEVICE_ID);
 			const query = normalizeListQuery(args?.query);
 			const limit = normalizeListLimit(args?.limit);
+			const options = (args?.options ?? {}) as ProcessListOptions;
+			const regex = options.regex ? new RegExp(options.regex, "i") : null;
 			const items = (runtimeState.processes[deviceId] ?? []).filter((item) => {
+				if (options.userAppsOnly && !item.identifier) {
+					return false;
+				}
+				if (
+					regex &&
+					!regex.test(item.name) &&
+					!(item.identifier && regex.test(item.identifier))
+				) {
+					return false;
+				}
 				if (!query) {
 					return true;
 				}
@@ -909,7 +930,15 @@ export async function mockInvoke<T>(
 					(item.identifier?.toLowerCase().includes(query) ?? false)
 				);
 			});
-			return clone(buildCollectionPage(items, limit, query)) as T;
+			if (options.sortBy === "pid") {
+				items.sort((left, right) => left.pid - right.pid);
+			}
+			if (options.descending) {
+				items.reverse();
+			}
+			return clone(
+				buildCollectionPage(items, limit, query, options.offset ?? 0),
+			) as T;
 		}
 		case "list_applications": {
 			const deviceId = String(args?.deviceId ?? DEFAULT_DEVICE_ID);
@@ -928,6 +957,13 @@ export async function mockInvoke<T>(
 			});
 			return clone(buildCollectionPage(items, limit, query)) as T;
 		}
+		case "get_frontmost_application": {
+			const deviceId = String(args?.deviceId ?? DEFAULT_DEVICE_ID);
+			const app = (runtimeState.applications[deviceId] ?? []).find(
+				(item) => item.pid != null,
+			);
+			return clone(app ?? null) as T;
+		}
 		case "kill_process": {
 			const deviceId = String(args?.deviceId ?? DEFAULT_DEVICE_ID);
 			const pid = Number(args?.pid);
@@ -974,6 +1010,19 @@ export async function mockInvoke<T>(
 		}
 		case "resume":
 			return undefined as T;
+		case "add_session_marker": {
+			const sessionId = String(args?.sessionId ?? "");
+			getSessionState(sessionId);
+			const marker = {
+				id: `marker-${runtimeState.nextMarkerId++}`,
+				sessionId,
+				label: String(args?.label ?? ""),
+				timestamp: Date.now(),
+				tracePosition: null,
+			};
+			emit("carf://session/marker", marker);
+			return clone(marker) as T;
+		}
 		case "rpc_call": {
 			const sessionId = String(args?.sessionId ?? "");
 			const method = String(args?.method ?? "");
@@ -1041,7 +1090,17 @@ async function handleMockRpc<T>(
 						{ address: "0x7100021080", size: 24 },
 					]
 				: [];
-			return clone(results) as T;
+			return clone({
+				results,
+				metadata: {
+					policy: "skip",
+					rangesTotal: MEMORY_RANGES.length,
+					rangesScanned: MEMORY_RANGES.length,
+					skippedRanges: [],
+					skippedBytes: 0,
+					aborted: false,
+				},
+			}) as T;
 		}
 		case "evaluate": {
 			const code = String(params.code ?? "");
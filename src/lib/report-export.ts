@@ -40,6 +40,14 @@ interface ReportData {
     errors: number;
     warnings: number;
   };
+  markers: {
+    total: number;
+    items: Array<{
+      label: string;
+      timestamp: string;
+      tracePosition: number | null;
+    }>;
+  };
 }
 
 function buildReportData(): ReportData {
@@ -83,6 +91,14 @@ function buildReportData(): ReportData {
       errors: consoleState.messages.filter((m) => m.level === "error").length,
       warnings: consoleState.messages.filter((m) => m.level === "warn").length,
     },
+    markers: {
+      total: consoleState.markers.length,
+      items: consoleState.markers.map((m) => ({
+        label: m.label,
+        timestamp: new Date(m.timestamp).toISOString(),
+        tracePosition: m.tracePosition,
+      })),
+    },
   };
 }
 
@@ -141,6 +157,17 @@ function buildHTMLReport(data: ReportData): string {
     )
     .join("\n");
 
+  const markerRows = data.markers.items
+    .map(
+      (m) =>
+        `<tr>
+          <td class="mono">${esc(m.timestamp)}</td>
+          <td>${esc(m.label)}</td>
+          <td class="mono">${m.tracePosition ?? "-"}</td>
+        </tr>`,
+    )
+    .join("\n");
+
   return `<!DOCTYPE html>
 <html lang="en">
 <head>
@@ -193,6 +220,12 @@ function buildHTMLReport(data: ReportData): string {
   <tbody>${pinRows || "<tr><td colspan='5'>No pins</td></tr>"}</tbody>
 </table>
 
+<h2>Markers (${data.markers.total})</h2>
+<table>
+  <thead><tr><th>Time</th><th>Label</th><th>Trace Position</th></tr></thead>
+  <tbody>${markerRows || "<tr><td colspan='3'>No markers</td></tr>"}</tbody>
+</table>
+
 <div class="meta" style="margin-top:32px">CARF v2 &mdash; Frida Dynamic Analysis Tool</div>
 </body>
 </html>`;
@@ -13,6 +13,34 @@ const DEFAULT_BRIDGE_URL =
 		: undefined;
 const BRIDGE_BASE_URL = EXPLICIT_BRIDGE_URL || DEFAULT_BRIDGE_URL;
 const RPC_CHUNK_EVENT = "carf://rpc/chunk";
+/** Several script events coalesced by the backend into one emit. */
+const BATCH_EVENT = "carf://events/batch";
+
+interface BatchedEvent {
+	name: string;
+	payload: unknown;
+}
+
+/** Mirrors the backend's `carf://events/batch` payload. */
+export interface EventBatch {
+	events: BatchedEvent[];
+	/** Events dropped over the rate limit since the previous batch, by name. */
+	dropped: Record<string, number>;
+	droppedTotal: number;
+}
+
+/** Calls `handler` for each event in `batch` named `event`. */
+function unpackBatch<T>(
+	batch: EventBatch,
+	event: string,
+	handler: (payload: T) => void,
+): void {
+	for (const entry of batch.events) {
+		if (entry.name === event) {
+			handler(entry.payload as T);
+		}
+	}
+}
 
 interface RpcChunkEvent {
 	requestId: string;
@@ -182,8 +210,17 @@ export function listen<T>(
 				) as T;
 				handler(payload);
 			};
+			const batchListener = (eventPayload: Event) => {
+				const batch = JSON.parse(
+					(eventPayload as MessageEvent<string>).data,
+				) as EventBatch;
+				unpackBatch(batch, event, handler);
+			};
 
 			eventSource.addEventListener(event, listener);
+			if (event !== BATCH_EVENT) {
+				eventSource.addEventListener(BATCH_EVENT, batchListener);
+			}
 			eventSource.onerror = () => {
 				if (disposed || fallbackCleanup) {
 					return;
@@ -197,6 +234,7 @@ export function listen<T>(
 			return () => {
 				disposed = true;
 				eventSource.removeEventListener(event, listener);
+				eventSource.removeEventListener(BATCH_EVENT, batchListener);
 				eventSource.close();
 				fallbackCleanup?.();
 			};
@@ -210,21 +248,40 @@ export function listen<T>(
 
 		return mockListen(event, handler);
 	}
-	let unlisten: (() => void) | undefined;
+	const unlisteners: (() => void)[] = [];
 	let disposed = false;
-	tauriListen<T>(event, (e) => handler(e.payload)).then((fn) => {
+	const track = (fn: () => void) => {
 		if (disposed) {
 			fn();
 		} else {
-			unlisten = fn;
+			unlisteners.push(fn);
 		}
-	});
+	};
+	tauriListen<T>(event, (e) => handler(e.payload)).then(track);
+	if (event !== BATCH_EVENT) {
+		tauriListen<EventBatch>(BATCH_EVENT, (e) =>
+			unpackBatch(e.payload, event, handler),
+		).then(track);
+	}
 	return () => {
 		disposed = true;
-		unlisten?.();
+		for (const fn of unlisteners.splice(0)) {
+			fn();
+		}
 	};
 }
 
+/** Reports script events the backend dropped over its rate limit. */
+export function listenEventDrops(
+	handler: (dropped: Record<string, number>, total: number) => void,
+): () => void {
+	return listen<EventBatch>(BATCH_EVENT, (batch) => {
+		if (Object.keys(batch.dropped).length > 0) {
+			handler(batch.dropped, batch.droppedTotal);
+		}
+	});
+}
+
 export function isTauri(): boolean {
 	return IS_TAURI;
 }
@@ -58,11 +58,65 @@ export interface NavigateOptions {
 export interface DeviceInfo {
 	id: string;
 	name: string;
-	type: "local" | "usb" | "remote";
+	type: "local" | "usb" | "remote" | "portal";
 	icon: string | null;
 	os: OsInfo | null;
 	arch: string | null;
 	status: "connected" | "disconnected" | "pairing";
+	/** Lockdown pairing with this host; only set for iOS devices over USB. */
+	pairing: DevicePairing | null;
+}
+
+/** Credentials for a frida-server started with `--token` / `--certificate`. */
+export interface RemoteDeviceOptions {
+	token?: string;
+	/** Path to the server's PEM certificate; connects over TLS when set. */
+	certificate?: string;
+}
+
+/**
+ * A remote device reconnected at startup; the token itself is never sent.
+ * Mirrors Rust `RemoteEndpoint`.
+ */
+export interface RemoteEndpoint {
+	/** `host:port` */
+	address: string;
+	nickname: string | null;
+	hasToken: boolean;
+	certificate: string | null;
+}
+
+/**
+ * `carf://device/version_mismatch`: a device's frida-server has a different
+ * major version than CARF's Frida. Mirrors Rust `FridaVersionMismatch`.
+ */
+export interface FridaVersionMismatch {
+	deviceId: string;
+	localVersion: string;
+	remoteVersion: string;
+	message: string;
+}
+
+/** Loopback listener tunneled to a TCP port on a device. Mirrors Rust `PortForward`. */
+export interface PortForward {
+	id: string;
+	deviceId: string;
+	remotePort: number;
+	/** Port on 127.0.0.1 that clients connect to. */
+	localPort: number;
+	createdAt: number;
+}
+
+export type PairingState =
+	| "paired"
+	| "unpaired"
+	| "awaiting_trust"
+	| "denied"
+	| "locked";
+
+export interface DevicePairing {
+	state: PairingState;
+	message: string | null;
 }
 
 export interface OsInfo {
@@ -70,6 +124,19 @@ export interface OsInfo {
 	version: string;
 }
 
+/** Device-reported system parameters; see `query_device`. */
+export interface DeviceSystemInfo {
+	deviceId: string;
+	platform: OsInfo["platform"] | null;
+	osName: string | null;
+	osVersion: string | null;
+	arch: string | null;
+	pointerSize: number | null;
+	apiLevel: number | null;
+	access: string | null;
+	jailbroken: boolean;
+}
+
 // ─── Process ───
 
 export interface ProcessInfo {
@@ -86,6 +153,122 @@ export interface AppInfo {
 	icon: string | null;
 }
 
+/** Server-side filtering and paging for `list_processes`. */
+export interface ProcessListOptions {
+	/** Case-insensitive; matched against name and identifier. */
+	regex?: string;
+	userAppsOnly?: boolean;
+	sortBy?: "name" | "pid";
+	descending?: boolean;
+	offset?: number;
+	/** Attach PNG `data:` URL icons to the returned page. */
+	includeIcons?: boolean;
+}
+
+/** `carf://process/changed`, emitted by a `watch_processes` refresher. */
+export interface ProcessChangedEvent {
+	deviceId: string;
+	added: ProcessInfo[];
+	removed: number[];
+	total: number;
+}
+
+/**
+ * `carf://device/unhealthy`: a remote device or one with sessions stopped
+ * answering. Mirrors Rust `DeviceUnhealthy`.
+ */
+export interface DeviceUnhealthyEvent {
+	deviceId: string;
+	/** Set for remote devices, which are reconnected automatically. */
+	address: string | null;
+	reason: string;
+	attempts: number;
+	/** `null` when no further reconnect will be tried. */
+	nextRetryMs: number | null;
+	timestamp: number;
+}
+
+/** `carf://device/reconnected`. Mirrors Rust `DeviceReconnected`. */
+export interface DeviceReconnectedEvent {
+	deviceId: string;
+	address: string | null;
+	/** `0` when the device came back on its own. */
+	attempts: number;
+	downMs: number;
+	timestamp: number;
+}
+
+/** Piped stdio of a spawned process; also emitted as `carf://process/output`. */
+export interface ProcessOutputChunk {
+	cursor: number;
+	pid: number;
+	/** 1 = stdout, 2 = stderr */
+	fd: number;
+	data: string;
+	timestamp: number;
+}
+
+export interface ProcessOutputPage {
+	pid: number;
+	chunks: ProcessOutputChunk[];
+	nextCursor: number;
+	/** Output after the requested cursor was dropped from the ring buffer. */
+	truncated: boolean;
+}
+
+/** Mirrors Rust `SpawnGatingOptions`. */
+export interface SpawnGatingOptions {
+	/** Attach to gated spawns, load `scriptPath` and resume them. */
+	autoAttach?: boolean;
+	/** Limits `autoAttach` to these identifiers; empty means all. */
+	identifiers?: string[];
+	scriptPath?: string;
+	scriptParameters?: unknown;
+	runtime?: string;
+}
+
+/**
+ * A process suspended by spawn gating; also emitted as `carf://spawn/added`.
+ * Mirrors Rust `PendingSpawn`.
+ */
+export interface PendingSpawn {
+	deviceId: string;
+	pid: number;
+	identifier: string | null;
+	timestamp: number;
+}
+
+/** Payload of `carf://spawn/attached` for auto-attached spawns. */
+export interface SpawnAttachedEvent {
+	deviceId: string;
+	pid: number;
+	identifier: string | null;
+	session: SessionInfo | null;
+	error: string | null;
+}
+
+/**
+ * One resource sample taken by the agent; also emitted (with `sessionId`) as
+ * `carf://process/metrics`. Null fields are not available on the platform.
+ * Mirrors Rust `ProcessMetrics`.
+ */
+export interface ProcessMetrics {
+	timestamp: number;
+	/** Above 100 when several cores are busy. */
+	cpuPercent: number | null;
+	rssBytes: number | null;
+	threadCount: number;
+	/** Open handles on Windows, open file descriptors elsewhere. */
+	handleCount: number | null;
+}
+
+/** Mirrors Rust `ProcessMetricsStatus`. */
+export interface ProcessMetricsStatus {
+	running: boolean;
+	intervalMs: number;
+	metrics: ProcessMetrics;
+}
+
 // ─── Session ───
 
 export interface SessionInfo {
@@ -98,6 +281,64 @@ export interface SessionInfo {
 	mode: "spawn" | "attach";
 	arch: string | null;
 	createdAt: number;
+	expiresAt?: number | null;
+	scriptCount?: number;
+}
+
+export interface WorkspaceTarget {
+	target: string | number;
+	wait?: boolean;
+	timeoutMs?: number | null;
+	scriptPath?: string | null;
+	scriptParameters?: unknown;
+	/** Id of a bundled script loaded after attaching. */
+	bundledScript?: string | null;
+}
+
+export interface Workspace {
+	name: string | null;
+	deviceId: string | null;
+	targets: WorkspaceTarget[];
+	rules: unknown[];
+	kiosk: boolean;
+}
+
+export interface LaunchOptions {
+	workspacePath: string | null;
+	workspace: Workspace | null;
+	kiosk: boolean;
+	error: string | null;
+	/** `carf://` URL CARF was opened with; attach only after `run_deep_link`. */
+	deepLink: string | null;
+}
+
+/** `carf://launch/deep-link`: a link opened while CARF is running. */
+export interface DeepLinkRequest {
+	url: string;
+	workspace: Workspace | null;
+	error: string | null;
+}
+
+export interface AttachManyResult {
+	pid: number;
+	session: SessionInfo | null;
+	error: string | null;
+}
+
+export interface SessionRestoreReport {
+	restored: SessionInfo[];
+	skipped: {
+		deviceId: string;
+		pid: number;
+		processName: string;
+		reason: string;
+	}[];
+	/** Restored sessions whose user script could not be reloaded. */
+	scriptErrors: {
+		sessionId: string;
+		processName: string;
+		reason: string;
+	}[];
 }
 
 export interface SpawnOptions {
@@ -112,6 +353,51 @@ export interface SpawnOptions {
 	runtime?: "qjs" | "v8";
 	enableChildGating?: boolean;
 	scriptPath?: string;
+	scriptParameters?: Record<string, unknown>;
+	maxDurationMs?: number;
+	detachAt?: number;
+}
+
+/** A saved setup for `profile_launch`. Mirrors Rust `TargetProfile`. */
+export interface TargetProfile {
+	/** Empty on a new profile; assigned by `profile_save`. */
+	id: string;
+	name: string;
+	/** Device id, or `usb` for the first USB device. Unset means local. */
+	device?: string | null;
+	target: ProfileTarget;
+	/** Bundled script ids, loaded together as the session's user script. */
+	scripts: string[];
+	hooks: ProfileHook[];
+	/** RPC actions without a `sessionId` go to the launched session. */
+	hotkeys: HotkeyBinding[];
+	updatedAt: number;
+}
+
+/** Mirrors Rust `ProfileTarget`. */
+export type ProfileTarget =
+	| { mode: "attach"; target: string | number; wait?: boolean; timeoutMs?: number | null }
+	| ({ mode: "spawn" } & SpawnOptions);
+
+/** Mirrors Rust `ProfileHook`. */
+export interface ProfileHook {
+	module?: string | null;
+	symbolOrOffset: string;
+	options: HookConfig["options"];
+}
+
+/** Result of `profile_launch`. Mirrors Rust `ProfileLaunchReport`. */
+export interface ProfileLaunchReport {
+	profileId: string;
+	session: SessionInfo;
+	steps: ProfileLaunchStep[];
+}
+
+/** Mirrors Rust `ProfileLaunchStep`. */
+export interface ProfileLaunchStep {
+	/** `scripts`, `hook <target>`, `hotkey <action>` or `resume`. */
+	name: string;
+	error: string | null;
 }
 
 export interface AttachOptions {
@@ -121,6 +407,41 @@ export interface AttachOptions {
 	runtime?: "qjs" | "v8";
 	enableChildGating?: boolean;
 	scriptPath?: string;
+	scriptParameters?: Record<string, unknown>;
+	maxDurationMs?: number;
+	detachAt?: number;
+	/** Lets `cancel_operation` abort the attach while it is in progress. */
+	operationId?: string;
+}
+
+/** Per-class operation timeouts in milliseconds; `0` waits indefinitely. */
+export interface TimeoutPolicy {
+	attachMs: number;
+	scriptLoadMs: number;
+	rpcMs: number;
+	scanMs: number;
+}
+
+export interface PerfSelftestOptions {
+	/** Synthetic events fed through each store (default 100 000). */
+	events?: number;
+}
+
+export interface PerfMeasurement {
+	name:
+		| "trace_store"
+		| "event_batching"
+		| "message_history"
+		| "message_query"
+		| "value_format";
+	unit: "events" | "queries" | "values";
+	items: number;
+	elapsedMs: number;
+	perSecond: number;
+}
+
+export interface PerfReport {
+	measurements: PerfMeasurement[];
 }
 
 // ─── Module ───
@@ -186,6 +507,333 @@ export interface ScanResult {
 	value?: string | null;
 }
 
+export type ScanErrorPolicy = "skip" | "retry" | "abort";
+
+export interface ScanMetadata {
+	policy: ScanErrorPolicy;
+	rangesTotal: number;
+	rangesScanned: number;
+	skippedRanges: {
+		base: string;
+		size: number;
+		protection: string | null;
+		attempts: number;
+		reason: string;
+	}[];
+	skippedBytes: number;
+	aborted: boolean;
+	/** Stopped early through `cancel_operation`. */
+	cancelled?: boolean;
+}
+
+export interface ScanResponse {
+	results: ScanResult[];
+	metadata: ScanMetadata;
+	/** Set for scans started with `startMemoryScan`. */
+	operationId?: string;
+}
+
+/** An address to record in a value snapshot. Mirrors Rust `SnapshotEntry`. */
+export interface SnapshotEntry {
+	address: string;
+	type: Exclude<WriteValueType, "utf8" | "utf16">;
+	/** Required for `bytes`. */
+	size?: number;
+}
+
+/** Mirrors Rust `ValueSnapshotInfo`. */
+export interface ValueSnapshotInfo {
+	name: string;
+	createdAt: number;
+	count: number;
+	/** Entries that could not be read when the snapshot was taken. */
+	unreadable: number;
+}
+
+/** Mirrors Rust `SnapshotChange`. */
+export interface SnapshotChange {
+	address: string;
+	type: string;
+	before: string | null;
+	after: string | null;
+	/** `after - before` for numeric types. */
+	delta: number | null;
+}
+
+/** Result of `scan_snapshot_compare`. Mirrors Rust `SnapshotComparison`. */
+export interface SnapshotComparison {
+	before: string;
+	after: string;
+	changed: SnapshotChange[];
+	unchanged: SnapshotChange[];
+	/** Addresses only in `before`. */
+	removed: string[];
+	/** Addresses only in `after`. */
+	added: string[];
+}
+
+export type WriteValueType =
+	| "u8"
+	| "i8"
+	| "u16"
+	| "i16"
+	| "u32"
+	| "i32"
+	| "u64"
+	| "i64"
+	| "f32"
+	| "f64"
+	| "pointer"
+	| "bytes"
+	| "utf8"
+	| "utf16";
+
+export interface ValueWrite {
+	address: string;
+	type: WriteValueType;
+	/** 64-bit integers and pointers may be strings to keep precision. */
+	value: number | string;
+}
+
+export interface BatchWriteReport {
+	written: number;
+	failed: { index: number; address: string; error: string }[];
+	rolledBack: boolean;
+}
+
+/** Mirrors Rust `TypedValueType`; the same names as batch writes. */
+export type TypedValueType = WriteValueType;
+
+/** Mirrors Rust `Endianness`. */
+export type Endianness = "little" | "big";
+
+/** Mirrors Rust `TypedReadOptions`. */
+export interface TypedReadOptions {
+	/** Defaults to the target's byte order. */
+	endianness?: Endianness;
+	/** Required for `bytes`; the most bytes a string read takes (256 by default). */
+	length?: number;
+}
+
+/** A typed value read or written; see `memory_read_typed` / `memory_write_typed`. */
+export interface TypedValue {
+	address: string;
+	type: TypedValueType;
+	endianness: Endianness;
+	size: number;
+	/** 64-bit integers are decimal strings, pointers `0x` strings, bytes hex. */
+	value: number | string | null;
+	/** Hex of the raw bytes. */
+	raw: string;
+}
+
+/** A value the agent writes back whenever the target changes it. */
+export interface FrozenValue {
+	id: string;
+	address: string;
+	type: WriteValueType;
+	value: number | string;
+	/** Times the target changed the value and it was restored. */
+	corrections: number;
+	lastError: string | null;
+}
+
+export interface FreezeState {
+	intervalMs: number;
+	values: FrozenValue[];
+}
+
+/** Named scan parameters for a kind of value. Mirrors Rust `ScanPreset`. */
+export interface ScanPreset {
+	name: string;
+	description: string;
+	valueType: "u8" | "u16" | "u32" | "u64" | "i8" | "i16" | "i32" | "i64" | "f32" | "f64";
+	/** Consecutive values per match, e.g. 3 for xyz coordinates. */
+	components: number;
+	alignment: number | null;
+	protection: string;
+	/** Skip file-backed mappings. */
+	privateOnly: boolean;
+	builtin: boolean;
+}
+
+/**
+ * A saved address, stored as `module!offset` (hex offset) so it can be
+ * rebased on every attach; a bare `0x` address is kept as is. Mirrors Rust
+ * `Bookmark`.
+ */
+export interface Bookmark {
+	name: string;
+	location: string;
+	note?: string | null;
+}
+
+/** Mirrors Rust `ResolvedLocation`. */
+export interface ResolvedLocation {
+	location: string;
+	address: string | null;
+	error: string | null;
+}
+
+/** Mirrors Rust `ResolvedBookmark`. */
+export interface ResolvedBookmark extends ResolvedLocation {
+	name: string;
+}
+
+/** `carf://bookmarks/resolved`, emitted after attaching to a target with bookmarks. */
+export interface BookmarksResolvedEvent {
+	sessionId: string;
+	profile: string;
+	bookmarks: ResolvedBookmark[];
+}
+
+/** Mirrors Rust `PointerScanOptions`. */
+export interface PointerScanOptions {
+	target: string;
+	/** Dereferences per chain; 1–8, default 4. */
+	maxDepth?: number;
+	/** Largest offset after a dereference; default 0x1000. */
+	maxOffset?: number;
+	maxResults?: number;
+	/** Take a new pointer map instead of reusing the last one. */
+	refresh?: boolean;
+}
+
+/**
+ * `[[module + moduleOffset] + offsets[0]] + ...`; the last offset is added
+ * without a read. Mirrors Rust `PointerChain`.
+ */
+export interface PointerChain {
+	module: string;
+	moduleOffset: number;
+	offsets: number[];
+}
+
+/** Mirrors Rust `PointerMapInfo`. */
+export interface PointerMapInfo {
+	createdAt: number;
+	entries: number;
+	truncated: boolean;
+}
+
+/** Mirrors Rust `PointerScanResult`. */
+export interface PointerScanResult {
+	chains: PointerChain[];
+	truncated: boolean;
+	snapshot: PointerMapInfo;
+}
+
+/** A chain saved per profile. Mirrors Rust `SavedPointerChain`. */
+export interface SavedPointerChain extends PointerChain {
+	name: string;
+	note?: string | null;
+}
+
+/** Mirrors Rust `ResolvedPointerChain`. */
+export interface ResolvedPointerChain {
+	name: string;
+	address: string | null;
+	error: string | null;
+}
+
+/** A file mapping read from memory and from disk; see `read_mapped_file`. */
+export interface MappedFileSlice {
+	path: string;
+	address: string;
+	rangeBase: string;
+	fileOffset: number;
+	size: number;
+	diskSize: number;
+	/** Hex-encoded bytes in memory. */
+	memory: string;
+	/** Hex-encoded bytes on disk, zero-filled past `diskSize`. */
+	disk: string;
+	differences: { offset: number; length: number }[];
+}
+
+/** Mirrors Rust `HexdumpOptions`. */
+export interface HexdumpOptions {
+	/** Bytes per row, 16 by default. */
+	width?: number;
+	/** Defaults to true. */
+	annotatePointers?: boolean;
+}
+
+/** Memory laid out as hexdump rows; see `memory_hexdump`. */
+export interface Hexdump {
+	address: string;
+	size: number;
+	pointerSize: number;
+	rows: HexdumpRow[];
+}
+
+export interface HexdumpRow {
+	offset: number;
+	address: string;
+	hex: string;
+	ascii: string;
+	pointers: PointerAnnotation[];
+}
+
+/** An aligned value pointing into a loaded module. */
+export interface PointerAnnotation {
+	/** From the dump's address. */
+	offset: number;
+	value: string;
+	/** `module!0x...` */
+	location: string;
+}
+
+/**
+ * `carf://memory-dump/progress`, per chunk and once more with `done` set.
+ * Mirrors Rust `MemoryDumpProgress`.
+ */
+export interface MemoryDumpProgress {
+	/** Pass to `cancel_operation` to stop the dump. */
+	dumpId: string;
+	address: string;
+	path: string;
+	bytesWritten: number;
+	totalBytes: number;
+	done: boolean;
+}
+
+/** Result of `memory_dump_range` / `memory_dump_module`. Mirrors Rust `MemoryDump`. */
+export interface MemoryDump {
+	dumpId: string;
+	address: string;
+	size: number;
+	path: string;
+	module: string | null;
+	/** Bytes on unreadable pages, written as zeros. */
+	unreadableBytes: number;
+	durationMs: number;
+}
+
+/** Ranked guesses at an unknown value; see `guess_type`. */
+export interface TypeGuess {
+	address: string;
+	samples: number;
+	volatile: boolean;
+	candidates: TypeCandidate[];
+}
+
+export interface TypeCandidate {
+	valueType: string;
+	confidence: number;
+	value: string;
+	reason: string;
+}
+
+/** Display rules for a saved value; see `format_values`. */
+export interface ValueFormat {
+	radix?: "dec" | "hex";
+	scale?: number | null;
+	decimals?: number | null;
+	unit?: string | null;
+	enumNames?: Record<string, string>;
+}
+
 export interface MemoryAccessEvent {
 	address: string;
 	size: number;
@@ -244,6 +892,43 @@ export interface ConsoleMessage {
 	data?: unknown;
 }
 
+/** Result of `repl_eval`. Mirrors Rust `EvalResult`. */
+export interface EvalResult {
+	/** Unset when the snippet threw; `value` and `stack` then hold the error. */
+	ok: boolean;
+	value: string;
+	/** `typeof`, or the class name for objects (`NativePointer`, `Array`, ...). */
+	typeName: string;
+	stack: string | null;
+	durationMs: number;
+}
+
+// ─── Event replay ───
+
+/** One backend event as it was delivered to the UI. */
+export interface RecordedEvent {
+	event: string;
+	timestamp: number;
+	payload: unknown;
+}
+
+export interface ReplayAssertion {
+	passed: boolean;
+	message: string;
+	/** Index into the replayed log; `null` for checks made outside a handler. */
+	eventIndex: number | null;
+	event: string | null;
+}
+
+export interface ReplayReport {
+	eventsReplayed: number;
+	eventsHandled: number;
+	assertions: ReplayAssertion[];
+	passed: number;
+	failed: number;
+	logs: string[];
+}
+
 // ─── Java / ObjC ───
 
 export interface JavaClassInfo {
@@ -345,6 +1030,31 @@ export interface FileEntry {
 	modified: number | null;
 }
 
+/**
+ * `carf://file-transfer/progress`, per chunk and once more with `done` set.
+ * Mirrors Rust `FileTransferProgress`.
+ */
+export interface FileTransferProgress {
+	/** Pass to `cancel_operation` to stop an agent transfer. */
+	transferId: string;
+	direction: "push" | "pull";
+	source: string;
+	destination: string;
+	bytesTransferred: number;
+	totalBytes: number;
+	done: boolean;
+}
+
+/** Mirrors Rust `FileTransfer`. */
+export interface FileTransfer {
+	transferId: string;
+	direction: "push" | "pull";
+	source: string;
+	destination: string;
+	bytes: number;
+	durationMs: number;
+}
+
 // ─── Pinboard ───
 
 export interface PinItem {
@@ -375,6 +1085,85 @@ export interface SessionDetachedEvent {
 		| "device_lost";
 }
 
+export interface TamperEvidence {
+	kind: "hook_reverted" | "agent_unresponsive" | "early_detach";
+	detail: Record<string, unknown>;
+	timestamp: number;
+}
+
+/** `carf://session/diagnostic`, also returned by `get_session_diagnostic`. */
+export interface SessionDiagnostic {
+	sessionId: string;
+	code: "TARGET_ANTI_TAMPER_SUSPECTED";
+	message: string;
+	evidence: TamperEvidence[];
+}
+
+/** One thing `cleanup_session` undid. Mirrors Rust `CleanupStep`. */
+export interface CleanupStep {
+	/** `nativeHooks`, `patches`, `userScript`, `detach`, ... */
+	name: string;
+	removed: number;
+	error: string | null;
+}
+
+/** Returned by `cleanup_session`. Mirrors Rust `SessionCleanupReport`. */
+export interface SessionCleanupReport {
+	sessionId: string;
+	steps: CleanupStep[];
+	detached: boolean;
+}
+
+/** Saved under `crashes/` in the data dir. Mirrors Rust `CrashReport`. */
+export interface CrashReport {
+	sessionId: string;
+	deviceId: string;
+	pid: number;
+	processName: string;
+	timestamp: number;
+	summary: string;
+	/** The OS crash log, when the device's crash reporter produced one. */
+	report: string;
+	parameters: Record<string, string>;
+}
+
+/** `carf://target/crashed` */
+export interface TargetCrashedEvent {
+	sessionId: string;
+	pid: number;
+	processName: string;
+	summary: string;
+	/** The saved report, or null when it could not be written. */
+	path: string | null;
+}
+
+/** A user-placed alignment point in the session's event log. */
+export interface SessionMarker {
+	id: string;
+	sessionId: string;
+	label: string;
+	timestamp: number;
+	/** Stalker trace entries recorded before the marker, counting dropped ones. */
+	tracePosition: number | null;
+}
+
+export interface VariableProfileSnapshot {
+	profile: string;
+	variables: Record<string, unknown>;
+	/** Pass back as `expectedRevision` so stale writes fail with STORAGE_CONFLICT. */
+	revision: string;
+	modifiedAt: number | null;
+}
+
+export type VariableMergeStrategy = "ours" | "theirs" | "union";
+
+export interface VariableMergeResult {
+	variables: Record<string, unknown>;
+	revision: string;
+	/** Keys changed on both sides, resolved by the strategy. */
+	conflicts: string[];
+}
+
 export interface ProcessCrashedEvent {
 	sessionId: string;
 	crashReport: {
@@ -384,6 +1173,36 @@ export interface ProcessCrashedEvent {
 	};
 }
 
+export interface ScriptErrorEvent {
+	sessionId: string;
+	script: "agent" | "user";
+	message: string;
+	stack: string | null;
+	fileName: string | null;
+	lineNumber: number | null;
+	columnNumber: number | null;
+}
+
+/** `carf://script/binary`: a `send(payload, data)` that carried a buffer. */
+export interface ScriptBinaryEvent {
+	sessionId: string;
+	script: "agent" | "user";
+	payload: unknown;
+	/** The buffer, base64-encoded. */
+	data: string;
+	size: number;
+}
+
+export interface MemoryLayoutChangedEvent {
+	sessionId: string;
+	reason: "modules" | "mappings";
+	modulesAdded: { name: string; path: string; base: string; size: number }[];
+	modulesRemoved: { name: string; path: string; base: string; size: number }[];
+	modulesMoved: { name: string; path: string; oldBase: string; newBase: string }[];
+	mappedBytesBefore: number;
+	mappedBytesAfter: number;
+}
+
 // ─── ADB ───
 
 export interface AdbDevice {
@@ -406,6 +1225,283 @@ export interface DeviceProps {
 	selinuxStatus: string;
 }
 
+export interface FridaServerStatus {
+	serial: string;
+	running: boolean;
+	pids: number[];
+	listening: boolean;
+	port: number;
+}
+
+/** Payload of `carf://adb/frida-server/progress`. */
+export interface FridaServerProgress {
+	serial: string;
+	stage: "resolve" | "push" | "start" | "verify";
+	bytesSent: number;
+	totalBytes: number;
+	message: string;
+}
+
+// ─── frida-server binaries ───
+
+/** Mirrors Rust `ServerBinary`. */
+export interface ServerBinary {
+	version: string;
+	/** Frida's OS name: `android`, `linux`, `macos`, ... */
+	os: string;
+	/** Frida's arch name: `arm64`, `arm`, `x86_64`, `x86`, ... */
+	arch: string;
+	path: string;
+	/** SHA-256 of the downloaded `.xz` archive. */
+	sha256: string;
+	/**
+	 * Whether `sha256` matched a published or caller-supplied checksum. Only
+	 * `false` when the download was made with `allowUnverified`.
+	 */
+	verified: boolean;
+	size: number;
+	downloadedAt: number;
+}
+
+/** Mirrors Rust `ServerDownloadProgress` (`carf://frida-server/download`). */
+export interface ServerDownloadProgress {
+	version: string;
+	os: string;
+	arch: string;
+	stage: "resolve" | "download" | "verify" | "extract";
+	bytesReceived: number;
+	totalBytes: number;
+}
+
+// ─── Frida Gadget ───
+
+export interface GadgetConfigOptions {
+	mode?: "listen" | "script";
+	/** Listen mode: interface to bind, `127.0.0.1` by default. */
+	address?: string;
+	port?: number;
+	/** Listen mode: run the app immediately instead of waiting for a client. */
+	resumeOnLoad?: boolean;
+	/** Script mode: path of the script on the device. */
+	scriptPath?: string;
+	reloadOnChange?: boolean;
+}
+
+export interface ApkPatchOptions {
+	apkPath: string;
+	/** e.g. `arm64-v8a` */
+	abi: string;
+	gadgetPath?: string;
+	outputPath?: string;
+	config?: GadgetConfigOptions;
+}
+
+export interface PatchedApk {
+	outputPath: string;
+	package: string;
+	injectedClass: string;
+	abi: string;
+	config: Record<string, unknown>;
+}
+
+export interface GadgetProgress {
+	stage: "decode" | "inject" | "build" | "align" | "sign";
+	message: string;
+}
+
+// ─── Bundled scripts ───
+
+export interface BundledScriptInfo {
+	id: string;
+	name: string;
+	description: string;
+	platforms: string[];
+	size: number;
+}
+
+/** Mirrors Rust `BackupRetention`. */
+export interface BackupRetention {
+	/** Backups kept per file; at least one is always kept. */
+	maxCount: number;
+	/** 0 keeps backups regardless of age. */
+	maxAgeDays: number;
+}
+
+/** A timestamped copy under `backups/`. Mirrors Rust `BackupInfo`. */
+export interface BackupInfo {
+	name: string;
+	/** Data file the backup is of, e.g. `settings.json`. */
+	source: string;
+	createdAt: number;
+	size: number;
+}
+
+/** Mirrors Rust `ScannerDefaults`. */
+export interface ScannerDefaults {
+	protection: string;
+	presets: ScanPreset[];
+}
+
+/** A backend plugin compiled into this build. Mirrors Rust `PluginInfo`. */
+export interface PluginInfo {
+	name: string;
+	/** Commands accepted by `plugin_invoke`. */
+	commands: string[];
+}
+
+/** A state-changing action CARF performed. Mirrors Rust `AuditEntry`. */
+export interface AuditEntry {
+	timestamp: number;
+	/** `attach`, `spawn`, `kill`, or the agent RPC method called. */
+	action: string;
+	sessionId: string | null;
+	/** Arguments, with long strings and arrays replaced by their size. */
+	args: unknown;
+	ok: boolean;
+	error: string | null;
+}
+
+/** Mirrors Rust `AuditFilter`. */
+export interface AuditFilter {
+	action?: string;
+	sessionId?: string;
+	/** Only entries at or after this time, in ms. */
+	since?: number;
+	/** Newest entries kept. Defaults to 200. */
+	limit?: number;
+}
+
+/** A backend log line, from `log_get_recent`. Mirrors Rust `LogEntry`. */
+export interface LogEntry {
+	timestamp: number;
+	level: "ERROR" | "WARN" | "INFO" | "DEBUG" | "TRACE";
+	/** Rust module the entry came from. */
+	target: string;
+	message: string;
+}
+
+/** Mirrors Rust `HotkeyBinding`. */
+export interface HotkeyBinding {
+	action: string;
+	/** Normalized chord, e.g. `Ctrl+Shift+F`. */
+	chord: string;
+	/** Set when the action calls the agent instead of the frontend. */
+	rpc: HotkeyRpcAction | null;
+}
+
+/** Mirrors Rust `HotkeyRpcAction`. */
+export interface HotkeyRpcAction {
+	/** Omitted means the only attached session. */
+	sessionId?: string | null;
+	method: string;
+	params?: unknown;
+}
+
+/** `carf://hotkey/rpc-result`, emitted by `hotkeys_trigger`. */
+export interface HotkeyRpcResult {
+	action: string;
+	sessionId: string | null;
+	method: string;
+	ok: boolean;
+	result?: unknown;
+	error?: string;
+}
+
+/** `settings.json`, as returned by `settings_get`. Mirrors Rust `Settings`. */
+export interface Settings {
+	/** Action name → key chord, e.g. `toggleFreeze: "Ctrl+Shift+F"`. */
+	hotkeys: Record<string, string>;
+	/** Action name → agent RPC call run when its hotkey fires. */
+	hotkeyRpc: Record<string, HotkeyRpcAction>;
+	scanner: ScannerDefaults;
+	/** Device id → nickname. */
+	deviceAliases: Record<string, string>;
+	rules: unknown[];
+	timeouts: TimeoutPolicy;
+	reloadExternalEdits: boolean;
+	backups: BackupRetention;
+	/** Runtime for attaches that do not pick one; null leaves it to Frida. */
+	defaultRuntime: "qjs" | "v8" | null;
+	/** Backend log level; null means `info`. Ignored while `RUST_LOG` is set. */
+	logLevel: "off" | "error" | "warn" | "info" | "debug" | "trace" | null;
+	/** Remote device addresses connected at startup. */
+	autoConnect: string[];
+}
+
+/** `carf://settings/changed-externally`. Mirrors Rust `SettingsFileChange`. */
+export interface SettingsFileChange {
+	path: string;
+	/** False when saves are blocked until `settings_reload`. */
+	reloaded: boolean;
+}
+
+/** Mirrors Rust `MessageLogOptions`. */
+export interface MessageLogOptions {
+	/** The current file is rotated past this size. Defaults to 64. */
+	maxFileMb?: number;
+	/** Rotated files kept besides the current one. Defaults to 5. */
+	maxFiles?: number;
+}
+
+/** Mirrors Rust `MessageLogStatus`. */
+export interface MessageLogStatus {
+	recording: boolean;
+	directory: string;
+	path: string | null;
+	startedAt: number | null;
+	messages: number;
+	bytes: number;
+}
+
+/** A script event kept in the backend's history. Mirrors Rust `HistoryMessage`. */
+export interface HistoryMessage {
+	cursor: number;
+	timestamp: number;
+	sessionId: string | null;
+	/** Event name, e.g. `carf://console/message`. */
+	event: string;
+	payload: unknown;
+}
+
+/** Mirrors Rust `MessageFilter`. */
+export interface MessageFilter {
+	sessionId?: string;
+	/** Event names to keep; a trailing `*` matches by prefix. */
+	events?: string[];
+	/** Case-insensitive substring searched in the payload. */
+	text?: string;
+}
+
+/** Mirrors Rust `MessagePage`. */
+export interface MessagePage {
+	messages: HistoryMessage[];
+	/** Pass back as `since` to continue after this page. */
+	nextCursor: number;
+	/** Messages after `since` were dropped from the history. */
+	truncated: boolean;
+	hasMore: boolean;
+}
+
+// ─── Local frida-server ───
+
+export interface LocalServerOptions {
+	binaryPath?: string;
+	listen?: string;
+	elevated?: boolean;
+}
+
+export interface LocalServerStatus {
+	running: boolean;
+	reachable: boolean;
+	pid: number | null;
+	binaryPath: string | null;
+	listen: string;
+	elevated: boolean;
+	startedAt: number | null;
+	lastExit: string | null;
+	logPath: string | null;
+}
+
 // ─── Swift ───
 
 export interface SwiftTypeInfo {
@@ -491,6 +1587,33 @@ export interface MemoryMonitorEvent {
 	timestamp: number;
 }
 
+/** Mirrors Rust `MemoryWatch`. */
+export interface MemoryWatch {
+	id: string;
+	address: string;
+	size: number;
+	/** `read` also reports writes. */
+	on: "read" | "write" | "execute";
+	/** `hardware` uses debug registers; `page` is the MemoryAccessMonitor fallback. */
+	mode: "hardware" | "page";
+	hits: number;
+}
+
+/** `carf://memory/watch-hit`, one per access to a watched range. */
+export interface MemoryWatchHit {
+	sessionId: string;
+	watchId: string;
+	operation: "read" | "write" | "execute";
+	/** Accessed address, or the watch's own when it could not be worked out. */
+	address: string;
+	/** Address of the accessing instruction. */
+	from: string;
+	instruction: string | null;
+	threadId: number;
+	backtrace: BacktraceFrame[];
+	timestamp: number;
+}
+
 // ─── Anti-Detection ───
 
 export interface CloakStatus {